@@ -0,0 +1,246 @@
+//! 音声パイプラインのスループットのベンチマーク
+//!
+//! 1チャンネルあたり `AUDIO_SECS` 秒の固定テスト音声（音声区間と無音の繰り返し）を
+//! 100ms のチャンクに分け、VAD判定・FLACエンコード・リトライ用バッファ・WAV書き込みを
+//! チャンネル数とサンプルレートごとに計測する。実デバイスやAWSには接続せず、
+//! 入力は固定シードの擬似乱数から作るため毎回同じになる。
+//!
+//! ```sh
+//! cargo bench --bench pipeline
+//!
+//! # 基準値を保存し、変更後に比較する（回帰は "Performance has regressed" と表示される）
+//! cargo bench --bench pipeline -- --save-baseline main
+//! cargo bench --bench pipeline -- --baseline main
+//! ```
+
+use criterion::measurement::WallTime;
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkGroup, BenchmarkId, Criterion,
+    Throughput,
+};
+use dcr_transcribe::buffer::AudioBuffer;
+use dcr_transcribe::config::{BufferConfig, VadConfig};
+use dcr_transcribe::flac_encoder::FlacEncoder;
+use dcr_transcribe::types::BufferedChunk;
+use dcr_transcribe::vad::VoiceActivityDetector;
+use dcr_transcribe::wav_writer::WavWriter;
+use std::path::PathBuf;
+
+/// 計測するチャンネル数
+const CHANNEL_COUNTS: [usize; 3] = [1, 4, 8];
+
+/// 計測するサンプルレート (Hz)
+const SAMPLE_RATES: [u32; 2] = [16000, 48000];
+
+/// 1チャンネルあたりのテスト音声の長さ（秒）
+const AUDIO_SECS: usize = 1;
+
+/// 1チャンクの長さ（ミリ秒、オーディオ入力から届く単位を想定）
+const CHUNK_MS: usize = 100;
+
+/// 擬似乱数のシード
+const SEED: u64 = 0x5eed_dc12;
+
+/// 1チャンネル分のテスト音声（`CHUNK_MS` ごとのチャンク）
+///
+/// 300ms の音声区間（正弦波 + 雑音）と 200ms の無音（弱い雑音）を繰り返す。
+/// チャンネルごとにシードと周波数をずらし、同じ入力にならないようにする。
+fn test_audio(channel: usize, sample_rate: u32) -> Vec<Vec<i16>> {
+    let mut state = SEED ^ (channel as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let mut noise = move || {
+        // 線形合同法（決定論的で十分）
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((state >> 48) as i16) >> 6
+    };
+
+    let frequency = 440.0 + channel as f32 * 110.0;
+    let period = sample_rate as usize / 2;
+    let voiced = period * 3 / 5;
+    let samples: Vec<i16> = (0..sample_rate as usize * AUDIO_SECS)
+        .map(|i| {
+            if i % period < voiced {
+                let t = i as f32 / sample_rate as f32;
+                ((t * frequency * std::f32::consts::TAU).sin() * 8000.0) as i16 + noise()
+            } else {
+                noise() >> 4
+            }
+        })
+        .collect();
+
+    let chunk_len = sample_rate as usize * CHUNK_MS / 1000;
+    samples.chunks(chunk_len).map(|c| c.to_vec()).collect()
+}
+
+/// 全チャンネル分のテスト音声
+fn test_channels(channels: usize, sample_rate: u32) -> Vec<Vec<Vec<i16>>> {
+    (0..channels).map(|channel| test_audio(channel, sample_rate)).collect()
+}
+
+/// パラメータの組み合わせごとに `f` で計測する
+///
+/// スループットは全チャンネル合計のサンプル数で表す。
+fn for_each_param(
+    group: &mut BenchmarkGroup<WallTime>,
+    mut f: impl FnMut(&mut BenchmarkGroup<WallTime>, BenchmarkId, u32, &[Vec<Vec<i16>>]),
+) {
+    for sample_rate in SAMPLE_RATES {
+        for channels in CHANNEL_COUNTS {
+            let audio = test_channels(channels, sample_rate);
+            let total_samples = channels * sample_rate as usize * AUDIO_SECS;
+            group.throughput(Throughput::Elements(total_samples as u64));
+            let id = BenchmarkId::from_parameter(format!("{}ch/{}Hz", channels, sample_rate));
+            f(group, id, sample_rate, &audio);
+        }
+    }
+}
+
+fn bench_vad(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipeline/vad");
+    for_each_param(&mut group, |group, id, sample_rate, audio| {
+        group.bench_function(id, |b| {
+            let mut detectors: Vec<VoiceActivityDetector> = audio
+                .iter()
+                .map(|_| VoiceActivityDetector::new(&VadConfig::default(), sample_rate))
+                .collect();
+            b.iter(|| {
+                for (detector, chunks) in detectors.iter_mut().zip(audio) {
+                    for chunk in chunks {
+                        black_box(detector.process(black_box(chunk)));
+                    }
+                }
+            })
+        });
+    });
+    group.finish();
+}
+
+fn bench_flac_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipeline/flac_encode");
+    group.sample_size(20);
+    for_each_param(&mut group, |group, id, sample_rate, audio| {
+        group.bench_function(id, |b| {
+            let mut encoders: Vec<FlacEncoder> =
+                audio.iter().map(|_| FlacEncoder::new(sample_rate, 5)).collect();
+            b.iter(|| {
+                for (encoder, chunks) in encoders.iter_mut().zip(audio) {
+                    for chunk in chunks {
+                        black_box(encoder.encode(black_box(chunk)).unwrap());
+                    }
+                }
+            })
+        });
+    });
+    group.finish();
+}
+
+fn bench_buffer(c: &mut Criterion) {
+    let config = BufferConfig {
+        capacity_seconds: 10,
+        ..BufferConfig::default()
+    };
+
+    // 容量に達した状態での push（ドロップを含む定常状態）
+    let mut group = c.benchmark_group("pipeline/buffer_push");
+    for_each_param(&mut group, |group, id, sample_rate, audio| {
+        group.bench_function(id, |b| {
+            let mut buffers: Vec<AudioBuffer> =
+                audio.iter().map(|_| AudioBuffer::new(&config, sample_rate)).collect();
+            let mut timestamp_ns = 0u128;
+            b.iter(|| {
+                for (buffer, chunks) in buffers.iter_mut().zip(audio) {
+                    for chunk in chunks {
+                        buffer.push(BufferedChunk {
+                            samples: chunk.clone(),
+                            timestamp_ns,
+                            is_voice: true,
+                        });
+                        timestamp_ns += CHUNK_MS as u128 * 1_000_000;
+                    }
+                }
+            })
+        });
+    });
+    group.finish();
+
+    // 満杯のバッファから再接続時の送信分（直近 AUDIO_SECS 秒）を取り出す
+    let mut group = c.benchmark_group("pipeline/buffer_get_range");
+    for_each_param(&mut group, |group, id, sample_rate, audio| {
+        let chunk_ns = CHUNK_MS as u128 * 1_000_000;
+        let mut buffers: Vec<AudioBuffer> =
+            audio.iter().map(|_| AudioBuffer::new(&config, sample_rate)).collect();
+        let mut timestamp_ns = 0u128;
+        for _ in 0..config.capacity_seconds as usize / AUDIO_SECS {
+            for (buffer, chunks) in buffers.iter_mut().zip(audio) {
+                for (i, chunk) in chunks.iter().enumerate() {
+                    buffer.push(BufferedChunk {
+                        samples: chunk.clone(),
+                        timestamp_ns: timestamp_ns + i as u128 * chunk_ns,
+                        is_voice: true,
+                    });
+                }
+            }
+            timestamp_ns += audio[0].len() as u128 * chunk_ns;
+        }
+        let from_ns = timestamp_ns - AUDIO_SECS as u128 * 1_000_000_000;
+
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                for buffer in &buffers {
+                    black_box(buffer.get_range(black_box(from_ns), timestamp_ns));
+                }
+            })
+        });
+    });
+    group.finish();
+}
+
+/// 書き終えたWAVファイルを計測の外で削除する
+struct RemoveOnDrop(Vec<PathBuf>);
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn bench_wav_write(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+
+    // ファイルの作成は準備に含め、サンプルの書き込みとヘッダの確定（finalize）を計測する
+    let mut group = c.benchmark_group("pipeline/wav_write");
+    group.sample_size(20);
+    for_each_param(&mut group, |group, id, sample_rate, audio| {
+        group.bench_function(id, |b| {
+            b.iter_batched(
+                || {
+                    audio
+                        .iter()
+                        .enumerate()
+                        .map(|(channel, _)| {
+                            let mut writer = WavWriter::new(channel, dir.path(), sample_rate).unwrap();
+                            writer.start().unwrap();
+                            writer
+                        })
+                        .collect::<Vec<_>>()
+                },
+                |mut writers| {
+                    let mut paths = Vec::new();
+                    for (writer, chunks) in writers.iter_mut().zip(audio) {
+                        for chunk in chunks {
+                            writer.write_samples(chunk).unwrap();
+                        }
+                        paths.extend(writer.finalize().unwrap());
+                    }
+                    RemoveOnDrop(paths)
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_vad, bench_flac_encode, bench_buffer, bench_wav_write);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+//! VADのRMS計算のベンチマーク
+//!
+//! 整数積算版 (`dcr_transcribe::vad::calculate_rms`) と、
+//! 書き換え前の浮動小数点版を同じ入力で比較する。
+//!
+//! ```sh
+//! cargo bench --bench vad_rms
+//! ```
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use dcr_transcribe::vad::calculate_rms;
+
+/// 書き換え前の実装（1サンプルずつ f64 に正規化して二乗和）
+fn calculate_rms_f64(samples: &[i16]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum_of_squares: f64 = samples
+        .iter()
+        .map(|&s| {
+            let normalized = s as f64 / i16::MAX as f64;
+            normalized * normalized
+        })
+        .sum();
+
+    let mean_square = sum_of_squares / samples.len() as f64;
+    mean_square.sqrt() as f32
+}
+
+fn bench_rms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vad_rms");
+
+    // 100ms分のチャンクを想定
+    for sample_rate in [16000usize, 48000] {
+        let len = sample_rate / 10;
+        let samples: Vec<i16> = (0..len)
+            .map(|i| ((i as f32 * 0.1).sin() * 10000.0) as i16)
+            .collect();
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("integer", sample_rate), &samples, |b, s| {
+            b.iter(|| calculate_rms(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("f64", sample_rate), &samples, |b, s| {
+            b.iter(|| calculate_rms_f64(black_box(s)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_rms);
+criterion_main!(benches);
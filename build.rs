@@ -0,0 +1,25 @@
+//! gRPC サービスのスタブを生成する
+//!
+//! protoc に依存しないよう、proto/transcript.proto と同じ定義を
+//! `tonic_build::manual` で記述している。
+
+fn main() {
+    let subscribe = tonic_build::manual::Method::builder()
+        .name("subscribe")
+        .route_name("Subscribe")
+        .input_type("crate::grpc::proto::SubscribeRequest")
+        .output_type("crate::grpc::proto::TranscriptEvent")
+        .codec_path("tonic::codec::ProstCodec")
+        .server_streaming()
+        .build();
+
+    let service = tonic_build::manual::Service::builder()
+        .name("TranscriptStream")
+        .package("dcr_transcribe.v1")
+        .method(subscribe)
+        .build();
+
+    tonic_build::manual::Builder::new().compile(&[service]);
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
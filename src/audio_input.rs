@@ -1,39 +1,276 @@
-use crate::config::AudioConfig;
-use crate::types::{AudioChunk, AudioFormat};
+use crate::config::{AudioConfig, InternalFormat};
+use crate::input_recovery::InputActivity;
+use crate::types::{AudioChunk, AudioFormat, DeviceSample, quantize_to_i16};
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Sample, SizedSample};
 use regex_lite::Regex;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 
+/// ストリーム時刻から求めた時刻とシステム時刻のずれの許容量（ナノ秒）
+///
+/// 超えた場合はクロックのドリフトやシステム時刻の補正とみなし、対応付けをやり直す。
+const MAX_CLOCK_DEVIATION_NS: i128 = 500_000_000;
+
+/// キャプチャからコールバック呼び出しまでの遅延として妥当な上限（ナノ秒）
+const MAX_CAPTURE_LATENCY_NS: i128 = 2_000_000_000;
+
+/// cpal のストリーム時刻（キャプチャ時刻）からチャンクの録音時刻を求める
+///
+/// `StreamInstant` の基準はホストによって異なる（起動時刻・ストリーム作成時など）ため、
+/// 最初のコールバックの呼び出し時刻とその時点の `SystemTime` を対応付け、
+/// 以降はストリーム時刻の差分で絶対時刻（UNIX_EPOCH からのナノ秒）に変換する。
+/// ストリーム時刻が進まない、キャプチャ時刻が妥当でないなど取得できない場合は
+/// 従来どおりコールバック時点の `SystemTime` を使う。
+#[derive(Debug, Default)]
+struct CaptureClock {
+    /// ストリーム時刻の原点（最初のコールバックの呼び出し時刻）
+    origin: Option<cpal::StreamInstant>,
+    /// 対応付け（原点からのストリーム時刻, その時点のシステム時刻）
+    anchor: Option<(i128, u128)>,
+    /// 直前のキャプチャ時刻（原点から）
+    last_capture_ns: Option<i128>,
+    /// `SystemTime` にフォールバックした回数
+    fallbacks: u64,
+}
+
+impl CaptureClock {
+    /// コールバック情報からチャンク先頭の録音時刻を求める
+    fn timestamp_from_info(&mut self, info: &cpal::InputCallbackInfo, now_ns: u128) -> u128 {
+        let timestamp = info.timestamp();
+        let origin = *self.origin.get_or_insert(timestamp.callback);
+        self.timestamp_ns(
+            Self::offset_ns(&timestamp.capture, &origin),
+            Self::offset_ns(&timestamp.callback, &origin),
+            now_ns,
+        )
+    }
+
+    /// `instant` の `origin` からの差（ナノ秒、前なら負）
+    fn offset_ns(instant: &cpal::StreamInstant, origin: &cpal::StreamInstant) -> i128 {
+        match instant.duration_since(origin) {
+            Some(after) => after.as_nanos() as i128,
+            None => -(origin.duration_since(instant).unwrap_or_default().as_nanos() as i128),
+        }
+    }
+
+    /// 原点からのキャプチャ時刻・呼び出し時刻と現在のシステム時刻から録音時刻を求める
+    fn timestamp_ns(&mut self, capture_ns: i128, callback_ns: i128, now_ns: u128) -> u128 {
+        let advancing = self.last_capture_ns.is_none_or(|last| capture_ns > last);
+        let latency = callback_ns - capture_ns;
+        if !advancing || !(0..=MAX_CAPTURE_LATENCY_NS).contains(&latency) {
+            if self.fallbacks == 0 {
+                log::debug!("入力のキャプチャ時刻を取得できないため、受信時刻をタイムスタンプに使います");
+            }
+            self.fallbacks += 1;
+            return now_ns;
+        }
+        self.last_capture_ns = Some(capture_ns);
+
+        let (anchor_stream_ns, anchor_system_ns) = *self.anchor.get_or_insert((callback_ns, now_ns));
+        let to_system = |stream_ns: i128| anchor_system_ns as i128 + (stream_ns - anchor_stream_ns);
+        if (to_system(callback_ns) - now_ns as i128).abs() > MAX_CLOCK_DEVIATION_NS {
+            log::debug!("入力のストリーム時刻がシステム時刻からずれたため、対応付けをやり直します");
+            self.anchor = Some((callback_ns, now_ns));
+            return (now_ns as i128 - latency).max(0) as u128;
+        }
+        to_system(capture_ns).max(0) as u128
+    }
+}
+
+/// 入力変換の統計をログ出力する間隔
+const CONVERSION_STATS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 送信キューの使用率がこの割合を超えたら、後段が追いついていないとみなして警告する
+const QUEUE_BACKLOG_WARN_RATIO: f64 = 0.5;
+
+/// 入力コールバックでの変換（デインターリーブ・i16変換）の計測値（`[debug] log_resampler_stats`）
+///
+/// コールバック内で加算するだけで、ロックや割り当てはしない。
+/// `CONVERSION_STATS_INTERVAL` ごとに `take_report` で集計してリセットする。
+#[derive(Debug)]
+struct ConversionStats {
+    window_start: Instant,
+    callbacks: u64,
+    /// 変換前のフレーム数（1フレーム = 全チャンネル分の1サンプル）
+    input_frames: u64,
+    /// 変換後の1チャンネルあたりのサンプル数
+    output_samples: u64,
+    process_ns_total: u64,
+    process_ns_max: u64,
+    /// キャプチャからコールバック呼び出しまでの遅延の合計
+    capture_latency_ns_total: u64,
+    /// 送信キューにたまっていたチャンクの最大数（全チャンネル中）
+    max_queued_chunks: usize,
+}
+
+/// 一定期間の入力変換の集計結果
+#[derive(Debug, Clone, PartialEq)]
+struct ConversionReport {
+    callbacks: u64,
+    input_frames: u64,
+    output_samples: u64,
+    avg_process_us: f64,
+    max_process_us: f64,
+    /// 推定レイテンシ（キャプチャ遅延 + 変換時間 + キューの待ち時間、ミリ秒）
+    estimated_latency_ms: f64,
+    max_queued_chunks: usize,
+    queue_capacity: usize,
+}
+
+impl ConversionStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            callbacks: 0,
+            input_frames: 0,
+            output_samples: 0,
+            process_ns_total: 0,
+            process_ns_max: 0,
+            capture_latency_ns_total: 0,
+            max_queued_chunks: 0,
+        }
+    }
+
+    /// 1回のコールバックの計測値を加算
+    fn record(
+        &mut self,
+        input_frames: usize,
+        output_samples: usize,
+        process_ns: u64,
+        capture_latency_ns: u64,
+        queued_chunks: usize,
+    ) {
+        self.callbacks += 1;
+        self.input_frames += input_frames as u64;
+        self.output_samples += output_samples as u64;
+        self.process_ns_total += process_ns;
+        self.process_ns_max = self.process_ns_max.max(process_ns);
+        self.capture_latency_ns_total += capture_latency_ns;
+        self.max_queued_chunks = self.max_queued_chunks.max(queued_chunks);
+    }
+
+    /// 集計間隔が経過していれば結果を返してリセットする
+    fn take_report(&mut self, now: Instant, sample_rate: u32, queue_capacity: usize) -> Option<ConversionReport> {
+        if now.duration_since(self.window_start) < CONVERSION_STATS_INTERVAL {
+            return None;
+        }
+        let stats = std::mem::replace(self, Self::new(now));
+        if stats.callbacks == 0 {
+            return None;
+        }
+
+        let callbacks = stats.callbacks as f64;
+        let avg_process_ns = stats.process_ns_total as f64 / callbacks;
+        let avg_capture_latency_ns = stats.capture_latency_ns_total as f64 / callbacks;
+        // キューのチャンクは1回のコールバック分（平均フレーム数）の音声
+        let chunk_ns = stats.input_frames as f64 / callbacks / sample_rate.max(1) as f64 * 1e9;
+        let queue_ns = stats.max_queued_chunks as f64 * chunk_ns;
+        Some(ConversionReport {
+            callbacks: stats.callbacks,
+            input_frames: stats.input_frames,
+            output_samples: stats.output_samples,
+            avg_process_us: avg_process_ns / 1e3,
+            max_process_us: stats.process_ns_max as f64 / 1e3,
+            estimated_latency_ms: (avg_capture_latency_ns + avg_process_ns + queue_ns) / 1e6,
+            max_queued_chunks: stats.max_queued_chunks,
+            queue_capacity,
+        })
+    }
+}
+
+impl ConversionReport {
+    /// 送信キューが溜まっている（後段の処理が入力に追いついていない）か
+    fn is_backlogged(&self) -> bool {
+        self.max_queued_chunks as f64 > self.queue_capacity as f64 * QUEUE_BACKLOG_WARN_RATIO
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "入力変換: コールバック {} 回, 入力 {} フレーム → 出力 {} サンプル/ch（比 {:.4}）, \
+             処理時間 平均 {:.1}us / 最大 {:.1}us, 推定レイテンシ {:.1}ms, 送信キュー 最大 {}/{}",
+            self.callbacks,
+            self.input_frames,
+            self.output_samples,
+            self.output_samples as f64 / self.input_frames.max(1) as f64,
+            self.avg_process_us,
+            self.max_process_us,
+            self.estimated_latency_ms,
+            self.max_queued_chunks,
+            self.queue_capacity
+        )
+    }
+}
+
+/// 設定の `[audio] channels` とデバイスの実チャンネル数の照合結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelCountCheck {
+    /// 設定のチャンネル数（論理チャンネル数）
+    pub configured: u16,
+    /// デバイスの実チャンネル数
+    pub device: u16,
+}
+
+impl ChannelCountCheck {
+    /// 設定のチャンネル数がデバイスの実チャンネル数以内か
+    pub fn is_ok(&self) -> bool {
+        self.configured <= self.device
+    }
+
+    /// 不一致の場合の説明（無音になる論理チャンネルと対処方法）
+    pub fn warning(&self) -> Option<String> {
+        if self.is_ok() {
+            return None;
+        }
+        let silent = if self.configured - self.device == 1 {
+            format!("入力位置 {}", self.device)
+        } else {
+            format!("入力位置 {}～{}", self.device, self.configured - 1)
+        };
+        Some(format!(
+            "[audio] channels = {} ですが、入力デバイスの実チャンネル数は {} です。\
+             {} の論理チャンネル（有効な [[channels]] の{}番目以降）には音声が入らず、常に無音になります。\
+             入力は有効な [[channels]] に定義順で割り当てられ、channel_map のような割り当て替えはできないため、\
+             [audio] channels を {} 以下にし、[[channels]] の並びをデバイスの入力に合わせてください\
+             （--detect-channels で信号のある入力を確認できます）",
+            self.configured,
+            self.device,
+            silent,
+            self.device + 1,
+            self.device
+        ))
+    }
+}
+
 /// オーディオデバイスからのマルチチャンネル音声入力
 pub struct AudioInput {
+    /// ストリームの再構築時にデバイスを取得し直すためのホストAPI名とデバイスID
+    host_name: Option<String>,
+    device_id: String,
     device: cpal::Device,
     config: cpal::StreamConfig,
     stream: Option<cpal::Stream>,
     num_channels: u16,
+    /// チャンネル数の不一致をエラーにするか（`[audio] strict_channels`）
+    strict_channels: bool,
+    /// 入力変換の統計を定期的にログ出力するか（`[debug] log_resampler_stats`）
+    log_conversion_stats: bool,
+    /// チャンネル処理に渡すサンプル形式（`[audio] internal_format`）
+    internal_format: InternalFormat,
+    /// ストリームの再構築に使う各チャンネル用の送信チャンネル（`start` で設定）
+    channel_senders: Vec<mpsc::Sender<AudioChunk>>,
+    /// 入力コールバックの到着と信号の有無（停止・無信号の監視用）
+    activity: Arc<InputActivity>,
 }
 
 impl AudioInput {
     /// 新しいAudioInputを作成
     pub fn new(config: &AudioConfig) -> Result<Self> {
-        let host = cpal::default_host();
-
         log::info!("設定: {:?}", config);
 
-        // デバイスを取得
-        let device = if config.device_id == "default" {
-            host.default_input_device()
-                .context("デフォルト入力デバイスが見つかりません")?
-        } else {
-            // デバイスIDが指定されている場合は、デバイス一覧から検索
-            Self::input_devices()?
-                .into_iter()
-                .find(|d| d.name().ok().as_deref() == Some(&config.device_id))
-                .with_context(|| format!("デバイスが見つかりません: {}", config.device_id))?
-        };
+        let device = Self::find_device(config.host.as_deref(), &config.device_id)?;
 
         log::info!("入力デバイス: {:?}", device.name());
 
@@ -57,10 +294,61 @@ impl AudioInput {
         };
 
         Ok(Self {
+            host_name: config.host.clone(),
+            device_id: config.device_id.clone(),
             device,
             config: stream_config,
             stream: None,
             num_channels: config.channels,
+            strict_channels: config.strict_channels,
+            log_conversion_stats: false,
+            internal_format: config.internal_format,
+            channel_senders: Vec::new(),
+            activity: Arc::new(InputActivity::default()),
+        })
+    }
+
+    /// 入力デバイスを取得
+    fn find_device(host: Option<&str>, device_id: &str) -> Result<cpal::Device> {
+        let host = Self::select_host(host);
+        if device_id == "default" {
+            host.default_input_device()
+                .context("デフォルト入力デバイスが見つかりません")
+        } else {
+            // デバイスIDが指定されている場合は、デバイス一覧から検索
+            Self::input_devices(&host)?
+                .into_iter()
+                .find(|d| d.name().ok().as_deref() == Some(device_id))
+                .with_context(|| format!("デバイスが見つかりません: {}", device_id))
+        }
+    }
+
+    /// 入力コールバックの到着と信号の有無（`InputWatchdog` に渡す）
+    pub fn activity(&self) -> &InputActivity {
+        &self.activity
+    }
+
+    /// 入力変換の統計（サンプル数・処理時間・推定レイテンシ・送信キューの残量）を定期的にログ出力する
+    ///
+    /// `start` より前に呼ぶ。
+    pub fn set_log_conversion_stats(&mut self, enabled: bool) {
+        self.log_conversion_stats = enabled;
+    }
+
+    /// デバイスの物理入力チャンネル数（デフォルト入力設定のチャンネル数）
+    pub fn device_channels(&self) -> Result<u16> {
+        let default_config = self
+            .device
+            .default_input_config()
+            .context("デフォルト入力設定が取得できません")?;
+        Ok(default_config.channels())
+    }
+
+    /// 設定のチャンネル数をデバイスの実チャンネル数と照合
+    pub fn check_channel_count(&self) -> Result<ChannelCountCheck> {
+        Ok(ChannelCountCheck {
+            configured: self.num_channels,
+            device: self.device_channels()?,
         })
     }
 
@@ -72,6 +360,45 @@ impl AudioInput {
     /// # Returns
     /// Result<()>
     pub fn start(&mut self, channel_senders: Vec<mpsc::Sender<AudioChunk>>) -> Result<()> {
+        // 実チャンネル数を超える論理チャンネルはデインターリーブで無音になるため、起動時に知らせる
+        if let Some(warning) = self.check_channel_count()?.warning() {
+            if self.strict_channels {
+                anyhow::bail!("{}", warning);
+            }
+            log::warn!("{}", warning);
+            eprintln!("警告: {}", warning);
+        }
+
+        self.channel_senders = channel_senders;
+        self.play()?;
+
+        log::info!("音声入力ストリームを開始しました");
+
+        Ok(())
+    }
+
+    /// ストリームを破棄し、デバイスを取得し直して再構築する（スリープ復帰後などの復帰処理）
+    ///
+    /// スリープからの復帰やデバイスの再接続でデバイスが列挙し直されることがあるため、
+    /// 既存のデバイスハンドルは使わずに取得し直す。`start` より前に呼ぶとエラー。
+    pub fn restart(&mut self) -> Result<()> {
+        if self.channel_senders.is_empty() {
+            anyhow::bail!("音声入力ストリームが開始されていません");
+        }
+        if let Some(stream) = self.stream.take() {
+            drop(stream);
+        }
+        self.device = Self::find_device(self.host_name.as_deref(), &self.device_id)?;
+        self.play()?;
+
+        log::info!("音声入力ストリームを再構築しました: {:?}", self.device.name());
+
+        Ok(())
+    }
+
+    /// 保持している送信チャンネルでストリームを構築して再生を開始
+    fn play(&mut self) -> Result<()> {
+        let channel_senders = self.channel_senders.clone();
         let num_channels = self.num_channels;
         let sample_rate = self.config.sample_rate.0;
 
@@ -97,8 +424,6 @@ impl AudioInput {
         stream.play().context("ストリームの再生開始に失敗")?;
         self.stream = Some(stream);
 
-        log::info!("音声入力ストリームを開始しました");
-
         Ok(())
     }
 
@@ -110,20 +435,31 @@ impl AudioInput {
         sample_rate: u32,
     ) -> Result<cpal::Stream>
     where
-        T: SizedSample + Sample + Send + 'static,
-        <T as Sample>::Float: Into<f32>,
+        T: SizedSample + Sample + DeviceSample + Send + 'static,
     {
         let channel_senders = Arc::new(channel_senders);
-
-        let data_callback = move |data: &[T], _info: &cpal::InputCallbackInfo| {
+        let internal_format = self.internal_format;
+        let activity = self.activity.clone();
+        let mut clock = CaptureClock::default();
+        let mut stats = self.log_conversion_stats.then(|| {
+            log::info!("入力変換の統計を {} 秒ごとに出力します", CONVERSION_STATS_INTERVAL.as_secs());
+            ConversionStats::new(Instant::now())
+        });
+
+        let data_callback = move |data: &[T], info: &cpal::InputCallbackInfo| {
+            let started = stats.as_ref().map(|_| Instant::now());
             // タイムスタンプを取得（全チャンネルで共有）
-            let timestamp_ns = SystemTime::now()
+            // コールバックの処理遅延に左右されないよう、デバイスのキャプチャ時刻を使う
+            let now_ns = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_nanos();
+            let timestamp_ns = clock.timestamp_from_info(info, now_ns);
+            activity.record(data.iter().any(|sample| *sample != T::EQUILIBRIUM));
 
             // インターリーブされたデータを各チャンネルに分離
             let samples_per_channel = data.len() / num_channels as usize;
+            let mut output_samples = 0;
 
             // 各チャンネルを順次処理
             for ch in 0..num_channels as usize {
@@ -131,27 +467,29 @@ impl AudioInput {
                     break;
                 }
 
-                // このチャンネルのサンプルを抽出
-                let mut channel_samples = Vec::with_capacity(samples_per_channel);
-                for frame in 0..samples_per_channel {
-                    let idx = frame * num_channels as usize + ch;
-                    if idx < data.len() {
-                        let sample = data[idx];
-                        let f = sample.to_float_sample().into();
-                        let clamped = f.clamp(-1.0, 1.0);
-                        let i16_sample = (clamped * i16::MAX as f32) as i16;
-                        channel_samples.push(i16_sample);
-                    }
+                // このチャンネルのサンプルを抽出（f32 モードでは量子化せずに渡す）
+                let channel_frames = (0..samples_per_channel)
+                    .map(|frame| frame * num_channels as usize + ch)
+                    .filter(|&idx| idx < data.len())
+                    .map(|idx| data[idx].to_f32());
+                let (samples, samples_f32) = match internal_format {
+                    InternalFormat::I16 => (channel_frames.map(quantize_to_i16).collect(), None),
+                    InternalFormat::F32 => (Vec::new(), Some(channel_frames.collect::<Vec<_>>())),
+                };
+
+                if ch == 0 {
+                    output_samples = samples_f32.as_ref().map_or(samples.len(), Vec::len);
                 }
 
                 // チャンクを作成
                 let chunk = AudioChunk {
-                    samples: channel_samples,
+                    samples,
                     format: AudioFormat {
                         sample_rate,
                         channels: 1, // モノラル
                     },
                     timestamp_ns,
+                    samples_f32,
                 };
 
                 // 非同期送信（ブロッキングしない）
@@ -169,6 +507,32 @@ impl AudioInput {
                     }
                 }
             }
+
+            if let (Some(stats), Some(started)) = (stats.as_mut(), started) {
+                let now = Instant::now();
+                let timestamp = info.timestamp();
+                let capture_latency = timestamp.callback.duration_since(&timestamp.capture).unwrap_or_default();
+                let queued_chunks = channel_senders
+                    .iter()
+                    .map(|sender| sender.max_capacity() - sender.capacity())
+                    .max()
+                    .unwrap_or_default();
+                stats.record(
+                    samples_per_channel,
+                    output_samples,
+                    now.duration_since(started).as_nanos() as u64,
+                    capture_latency.as_nanos() as u64,
+                    queued_chunks,
+                );
+                let queue_capacity = channel_senders.first().map_or(0, |sender| sender.max_capacity());
+                if let Some(report) = stats.take_report(now, sample_rate, queue_capacity) {
+                    if report.is_backlogged() {
+                        log::warn!("{}（送信キューが溜まっています: 後段の処理が追いついていません）", report.message());
+                    } else {
+                        log::info!("{}", report.message());
+                    }
+                }
+            }
         };
 
         let error_callback = move |err| {
@@ -185,19 +549,65 @@ impl AudioInput {
 
     /// ストリームを停止
     pub fn stop(&mut self) {
+        self.channel_senders.clear();
         if let Some(stream) = self.stream.take() {
             drop(stream);
             log::info!("音声入力ストリームを停止しました");
         }
     }
 
+    /// 入力に使うホストAPIを選択
+    ///
+    /// 未指定または "default" ならシステムのデフォルトホストを使う。
+    /// 名前は大文字小文字を区別しない。このビルドで利用できないホスト
+    /// （ASIO は cpal の `asio` フィーチャを有効にしたビルドのみ）は警告してデフォルトにフォールバックする。
+    pub fn select_host(name: Option<&str>) -> cpal::Host {
+        let Some(name) = name.filter(|name| !name.eq_ignore_ascii_case("default")) else {
+            return cpal::default_host();
+        };
+
+        let available = cpal::available_hosts();
+        match Self::find_host_id(name, &available) {
+            Some(id) => match cpal::host_from_id(id) {
+                Ok(host) => {
+                    log::info!("入力ホストAPI: {}", id.name());
+                    return host;
+                }
+                Err(e) => {
+                    log::warn!("ホストAPI '{}' を初期化できません: {}。デフォルトのホストを使用します", name, e);
+                }
+            },
+            None => {
+                let names: Vec<&str> = available.iter().map(|id| id.name()).collect();
+                log::warn!(
+                    "ホストAPI '{}' はこの環境では利用できません（利用可能: {}）。デフォルトのホストを使用します",
+                    name,
+                    names.join(", ")
+                );
+            }
+        }
+        cpal::default_host()
+    }
+
+    /// 利用可能なホストから名前（大文字小文字を区別しない）で検索
+    fn find_host_id(name: &str, available: &[cpal::HostId]) -> Option<cpal::HostId> {
+        available
+            .iter()
+            .copied()
+            .find(|id| id.name().eq_ignore_ascii_case(name))
+    }
+
     /// デバイス一覧を表示
-    pub fn list_devices() -> Result<()> {
-        let host = cpal::default_host();
+    ///
+    /// `host` で指定したホストAPIのデバイスを列挙する（`[audio] host` と同じ解釈）。
+    pub fn list_devices(host: Option<&str>) -> Result<()> {
+        let host = Self::select_host(host);
+        let available: Vec<&str> = cpal::available_hosts().iter().map(|id| id.name()).collect();
+        println!("ホストAPI: {} (利用可能: {})", host.id().name(), available.join(", "));
         println!("利用可能な入力デバイス:");
         println!();
 
-        for (idx, device) in Self::input_devices()?.into_iter().enumerate() {
+        for (idx, device) in Self::input_devices(&host)?.into_iter().enumerate() {
             let name = device.name()?;
             println!("  [{}] {}", idx, name);
 
@@ -217,18 +627,14 @@ impl AudioInput {
     }
 
     /// MacBook Air 本体・WebCam など、通常入力デバイスとして利用してはいけないデバイスを除外したデバイス一覧を取得
-    fn input_devices() -> Result<Vec<cpal::Device>> {
-        let host = cpal::default_host();
+    fn input_devices(host: &cpal::Host) -> Result<Vec<cpal::Device>> {
         let devices = host
             .input_devices()?
             .filter(|device| {
                 if let Ok(name) = device.name() {
                     // 除外するデバイス名のリスト
                     let excluded_names_regex = Regex::new("MacBook (Air|Pro)|AirPods|iPhone|Webcam|Background|Microsoft Teams|ZoomAudioDevice").unwrap();
-                    if excluded_names_regex.is_match(&name) {
-                        return false;
-                    }
-                    return true;
+                    !excluded_names_regex.is_match(&name)
                 } else {
                     true
                 }
@@ -243,3 +649,133 @@ impl Drop for AudioInput {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_host_id() {
+        let available = cpal::available_hosts();
+
+        // 利用可能なホストは大文字小文字を区別せずに見つかる
+        for id in &available {
+            assert_eq!(AudioInput::find_host_id(&id.name().to_lowercase(), &available), Some(*id));
+            assert_eq!(AudioInput::find_host_id(&id.name().to_uppercase(), &available), Some(*id));
+        }
+        assert_eq!(AudioInput::find_host_id("no-such-host", &available), None);
+        assert_eq!(AudioInput::find_host_id("alsa", &[]), None);
+    }
+
+    #[test]
+    fn test_channel_count_check() {
+        let matched = ChannelCountCheck { configured: 2, device: 2 };
+        assert!(matched.is_ok());
+        assert_eq!(matched.warning(), None);
+        // デバイスより少ないチャンネルだけ使うのは問題ない
+        assert_eq!(ChannelCountCheck { configured: 1, device: 8 }.warning(), None);
+
+        let stereo = ChannelCountCheck { configured: 4, device: 2 };
+        assert!(!stereo.is_ok());
+        let warning = stereo.warning().unwrap();
+        assert!(warning.contains("入力位置 2～3"), "{}", warning);
+        assert!(warning.contains("[[channels]] の3番目以降"), "{}", warning);
+
+        let one_over = ChannelCountCheck { configured: 3, device: 2 }.warning().unwrap();
+        assert!(one_over.contains("入力位置 2 の"), "{}", one_over);
+    }
+
+    #[test]
+    fn test_conversion_stats_report() {
+        let start = Instant::now();
+        let mut stats = ConversionStats::new(start);
+
+        // 48kHz・1回 4800 フレーム（100ms）のコールバックを100回
+        for i in 0..100 {
+            let queued = if i == 50 { 3 } else { 0 };
+            stats.record(4800, 4800, 20_000 + i * 100, 2_000_000, queued);
+        }
+        assert_eq!(stats.take_report(start + Duration::from_secs(1), 48000, 128), None);
+
+        let report = stats
+            .take_report(start + CONVERSION_STATS_INTERVAL, 48000, 128)
+            .unwrap();
+        assert_eq!(report.callbacks, 100);
+        assert_eq!(report.input_frames, 480_000);
+        assert_eq!(report.output_samples, 480_000);
+        assert!((report.avg_process_us - 24.95).abs() < 1e-9, "{:?}", report);
+        assert!((report.max_process_us - 29.9).abs() < 1e-9, "{:?}", report);
+        // キャプチャ遅延 2ms + 変換 約0.025ms + キュー 3チャンク × 100ms
+        assert!((report.estimated_latency_ms - 302.02495).abs() < 1e-6, "{:?}", report);
+        assert!(!report.is_backlogged());
+        assert!(report.message().contains("比 1.0000"), "{}", report.message());
+
+        // 集計後はリセットされ、キューが容量の半分を超えると溜まっていると判定する
+        let next = start + CONVERSION_STATS_INTERVAL;
+        assert_eq!(stats.take_report(next + CONVERSION_STATS_INTERVAL, 48000, 128), None);
+        stats.record(4800, 4800, 20_000, 2_000_000, 65);
+        let report = stats
+            .take_report(next + CONVERSION_STATS_INTERVAL * 2, 48000, 128)
+            .unwrap();
+        assert_eq!(report.callbacks, 1);
+        assert!(report.is_backlogged());
+    }
+
+    /// システム時刻の基準（UNIX_EPOCH からのナノ秒）
+    const EPOCH_NS: u128 = 1_700_000_000_000_000_000;
+    const MS: i128 = 1_000_000;
+
+    #[test]
+    fn test_capture_clock_removes_callback_jitter() {
+        let mut clock = CaptureClock::default();
+
+        // 10ms ごとにキャプチャされ、コールバックは 5～25ms 遅れてばらばらに呼ばれる
+        let delays_ms = [5, 25, 8, 17, 5, 22, 11, 6];
+        let mut capture_errors = Vec::new();
+        let mut system_errors = Vec::new();
+        for (i, delay) in delays_ms.iter().enumerate() {
+            let capture_ns = i as i128 * 10 * MS;
+            let callback_ns = capture_ns + delay * MS;
+            let truth = EPOCH_NS as i128 + capture_ns;
+            let now_ns = (EPOCH_NS as i128 + callback_ns) as u128;
+
+            let timestamp = clock.timestamp_ns(capture_ns, callback_ns, now_ns);
+            capture_errors.push((timestamp as i128 - truth).abs());
+            system_errors.push((now_ns as i128 - truth).abs());
+        }
+
+        // 受信時刻は呼び出しの遅延分ずれてばらつくが、キャプチャ時刻はずれない
+        assert!(capture_errors.iter().all(|&e| e == 0), "{:?}", capture_errors);
+        assert_eq!(system_errors.iter().min(), Some(&(5 * MS)));
+        assert_eq!(system_errors.iter().max(), Some(&(25 * MS)));
+        assert_eq!(clock.fallbacks, 0);
+    }
+
+    #[test]
+    fn test_capture_clock_falls_back_to_system_time() {
+        let mut clock = CaptureClock::default();
+        let now = |ms: i128| (EPOCH_NS as i128 + ms * MS) as u128;
+
+        assert_eq!(clock.timestamp_ns(0, 5 * MS, now(5)), now(0));
+        // キャプチャ時刻が進まない（常に0を返すホストなど）
+        assert_eq!(clock.timestamp_ns(0, 15 * MS, now(15)), now(15));
+        // キャプチャ時刻がコールバックより後・遅延が大きすぎる
+        assert_eq!(clock.timestamp_ns(30 * MS, 25 * MS, now(25)), now(25));
+        assert_eq!(clock.timestamp_ns(40 * MS, 5_000 * MS, now(5_000)), now(5_000));
+        assert_eq!(clock.fallbacks, 3);
+
+        // 正常な値に戻れば再びキャプチャ時刻を使う
+        assert_eq!(clock.timestamp_ns(5_000 * MS, 5_010 * MS, now(5_010)), now(5_000));
+    }
+
+    #[test]
+    fn test_capture_clock_reanchors_on_drift() {
+        let mut clock = CaptureClock::default();
+        let now = |ms: i128| (EPOCH_NS as i128 + ms * MS) as u128;
+        assert_eq!(clock.timestamp_ns(0, 10 * MS, now(10)), now(0));
+
+        // システム時刻が1秒進められた（NTP補正など）: 以降はずれた時刻に合わせ直す
+        assert_eq!(clock.timestamp_ns(100 * MS, 110 * MS, now(1_110)), now(1_100));
+        assert_eq!(clock.timestamp_ns(200 * MS, 230 * MS, now(1_230)), now(1_200));
+    }
+}
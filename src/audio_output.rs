@@ -1,15 +1,279 @@
+use crate::types::DeviceSample;
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, FromSample, Sample, SampleFormat, SizedSample, Stream, StreamConfig};
+use cpal::{Device, Sample, SampleFormat, SizedSample, Stream, StreamConfig};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+/// 出力音量の上限（+6dB 相当、これ以上はほぼ確実にクリップする）
+pub const MAX_OUTPUT_VOLUME: f32 = 2.0;
+
+/// 出力音量の係数を 0.0～`MAX_OUTPUT_VOLUME` に丸める
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::audio_output::{clamp_volume, MAX_OUTPUT_VOLUME};
+/// assert_eq!(clamp_volume(0.5), 0.5);
+/// assert_eq!(clamp_volume(-1.0), 0.0);
+/// assert_eq!(clamp_volume(10.0), MAX_OUTPUT_VOLUME);
+/// ```
+pub fn clamp_volume(gain: f32) -> f32 {
+    if gain.is_nan() {
+        return 1.0;
+    }
+    gain.clamp(0.0, MAX_OUTPUT_VOLUME)
+}
+
+/// サンプルに音量係数を掛ける（範囲外は飽和させる）
+pub fn apply_volume(sample: i16, gain: f32) -> i16 {
+    (sample as f32 * gain).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+/// 定位（-1.0 = 左のみ、0.0 = 中央、1.0 = 右のみ）から左右の係数を求める
+///
+/// 中央では左右とも原音のまま出力し（モノラル出力と同じ音量）、片側に寄せるほど反対側を絞る。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::audio_output::pan_gains;
+/// assert_eq!(pan_gains(0.0), (1.0, 1.0));
+/// assert_eq!(pan_gains(-1.0), (1.0, 0.0));
+/// assert_eq!(pan_gains(0.5), (0.5, 1.0));
+/// ```
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = if pan.is_nan() { 0.0 } else { pan.clamp(-1.0, 1.0) };
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+}
+
+/// `buffer` の先頭から1フレーム1サンプルずつ取り出して `data` を埋める（足りない分は無音）
+///
+/// `channel_gains` は出力チャンネルごとの係数（モノラルなら1要素、定位ありなら左右の2要素）。
+fn fill_output<T>(data: &mut [T], buffer: &mut Vec<i16>, gain: f32, channel_gains: &[f32])
+where
+    T: Sample + DeviceSample,
+{
+    let frames = data.len() / channel_gains.len();
+    let available = buffer.len().min(frames);
+    for (frame, out) in data.chunks_mut(channel_gains.len()).enumerate() {
+        let sample = (frame < available).then(|| buffer[frame]);
+        for (value, channel_gain) in out.iter_mut().zip(channel_gains) {
+            *value = match sample {
+                Some(sample) => T::from_i16(apply_volume(sample, gain * channel_gain)),
+                None => Sample::EQUILIBRIUM,
+            };
+        }
+    }
+    buffer.drain(..available);
+}
+
+/// 出力音量の共有ハンドル
+///
+/// 出力ストリームのコールバックと、音量を変更する側（TUIの監視タスクなど）で共有する。
+#[derive(Clone)]
+pub struct OutputVolume(Arc<AtomicU32>);
+
+impl OutputVolume {
+    pub fn new(gain: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(clamp_volume(gain).to_bits())))
+    }
+
+    /// 音量係数を設定し、丸めた後の値を返す
+    pub fn set(&self, gain: f32) -> f32 {
+        let gain = clamp_volume(gain);
+        self.0.store(gain.to_bits(), Ordering::Relaxed);
+        gain
+    }
+
+    /// 現在の音量係数
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// 自動フォローで別のチャンネルに切り替えるまでに、そのチャンネルが最優先であり続ける必要がある時間
+pub const FOLLOW_HOLD: Duration = Duration::from_millis(300);
+
+/// 自動フォローで切り替えた後、次の切り替えを抑える時間
+pub const FOLLOW_MIN_DWELL: Duration = Duration::from_millis(1500);
+
+/// 同時発話中に、出力中のチャンネルより何 dB 大きければ切り替えるか
+pub const FOLLOW_LOUDER_MARGIN_DB: f32 = 6.0;
+
+/// 話しているチャンネルへのモニタ出力の自動フォロー（`[audio] auto_follow_active`）
+///
+/// VADが音声と判定したチャンネルのうち最も音量が大きいものを出力先の候補とし、
+/// 候補が `FOLLOW_HOLD` 続いたら切り替える。出力中のチャンネルも話している間は
+/// `FOLLOW_LOUDER_MARGIN_DB` 以上大きい場合だけ切り替え、切り替え直後の `FOLLOW_MIN_DWELL` は
+/// 切り替えない（短い相槌などで出力が行き来しない）。どのチャンネルも話していなければ直前の出力を維持する。
+#[derive(Debug, Default)]
+pub struct ActiveChannelFollower {
+    current: Option<usize>,
+    switched_at: Option<Instant>,
+    /// 切り替え候補と、候補になった時刻
+    candidate: Option<(usize, Instant)>,
+}
+
+impl ActiveChannelFollower {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 自動フォローで出力中のチャンネル
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// 出力中のチャンネルを `channel_id` にして追従をやり直す（手動選択を解除したときなど）
+    pub fn reset_to(&mut self, channel_id: Option<usize>) {
+        self.current = channel_id;
+        self.switched_at = None;
+        self.candidate = None;
+    }
+
+    /// 各チャンネルの `(ID, 音声中か, 音量 dB)` から出力先を更新して返す
+    pub fn update(&mut self, activity: &[(usize, bool, f32)], now: Instant) -> Option<usize> {
+        let loudest = activity
+            .iter()
+            .filter(|(_, is_voice, _)| *is_voice)
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        let Some(&(loudest_id, _, loudest_db)) = loudest else {
+            // 誰も話していなければ直前のチャンネルを維持
+            self.candidate = None;
+            return self.current;
+        };
+        if self.current == Some(loudest_id) {
+            self.candidate = None;
+            return self.current;
+        }
+
+        // 出力中のチャンネルも話していれば、十分に大きい場合だけ候補にする
+        let current_db = activity
+            .iter()
+            .find(|(id, is_voice, _)| Some(*id) == self.current && *is_voice)
+            .map(|&(_, _, db)| db);
+        if current_db.is_some_and(|db| loudest_db < db + FOLLOW_LOUDER_MARGIN_DB) {
+            self.candidate = None;
+            return self.current;
+        }
+
+        let since = match self.candidate {
+            Some((id, since)) if id == loudest_id => since,
+            _ => {
+                self.candidate = Some((loudest_id, now));
+                now
+            }
+        };
+        let held = self.current.is_none() || now.duration_since(since) >= FOLLOW_HOLD;
+        let dwelled = self
+            .switched_at
+            .is_none_or(|at| now.duration_since(at) >= FOLLOW_MIN_DWELL);
+        if held && dwelled {
+            self.current = Some(loudest_id);
+            self.switched_at = Some(now);
+            self.candidate = None;
+        }
+        self.current
+    }
+}
+
+/// 出力チャンネルからサンプルを受信して再生バッファに積む処理の実行先
+pub enum ReceiverRuntime {
+    /// 指定した tokio ランタイムのタスクとして実行
+    Tokio(tokio::runtime::Handle),
+    /// 専用のOSスレッドで実行（tokio ランタイム不要）
+    Thread,
+}
+
+impl ReceiverRuntime {
+    /// 呼び出し元の tokio ランタイムを使う（ランタイム外なら None）
+    pub fn current() -> Option<Self> {
+        tokio::runtime::Handle::try_current().ok().map(Self::Tokio)
+    }
+}
+
+/// `audio_rx` のサンプルを `buffer` に積み続ける処理を起動する
+///
+/// 送信側がすべてドロップされると終了する。
+fn spawn_receiver(
+    runtime: ReceiverRuntime,
+    mut audio_rx: mpsc::Receiver<Vec<i16>>,
+    buffer: Arc<Mutex<Vec<i16>>>,
+) -> Result<()> {
+    match runtime {
+        ReceiverRuntime::Tokio(handle) => {
+            handle.spawn(async move {
+                while let Some(samples) = audio_rx.recv().await {
+                    buffer.lock().unwrap().extend_from_slice(&samples);
+                }
+            });
+        }
+        ReceiverRuntime::Thread => {
+            std::thread::Builder::new()
+                .name("audio-output-rx".to_string())
+                .spawn(move || {
+                    while let Some(samples) = audio_rx.blocking_recv() {
+                        buffer.lock().unwrap().extend_from_slice(&samples);
+                    }
+                })
+                .context("出力サンプル受信スレッドの起動に失敗")?;
+        }
+    }
+    Ok(())
+}
+
+/// モニタ出力1系統のチャンネルの割り当て（`[[audio.monitors]]` の1要素）
+///
+/// 担当チャンネルが固定されていればそのチャンネルを、なければTUIで選択中（または自動フォロー中）の
+/// チャンネルを出力する。
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorRoute {
+    fixed_channel: Option<usize>,
+    current: Option<usize>,
+}
+
+impl MonitorRoute {
+    pub fn new(fixed_channel: Option<usize>) -> Self {
+        Self {
+            fixed_channel,
+            current: None,
+        }
+    }
+
+    /// TUIの選択に従って出力チャンネルを切り替えるか
+    pub fn follows_selection(&self) -> bool {
+        self.fixed_channel.is_none()
+    }
+
+    /// 出力中のチャンネル
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// TUIの出力チャンネル `selected` から出力先を決め、変わった場合は `(変更前, 変更後)` を返す
+    pub fn update(&mut self, selected: Option<usize>) -> Option<(Option<usize>, Option<usize>)> {
+        let next = self.fixed_channel.or(selected);
+        if next == self.current {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.current, next);
+        Some((previous, next))
+    }
+}
+
 /// 音声出力デバイスマネージャ
 pub struct AudioOutput {
     device: Device,
     sample_rate: u32,
     stream: Option<Stream>,
     audio_tx: Option<mpsc::Sender<Vec<i16>>>,
+    /// 出力音量（モニタするチャンネルによらず出力全体に掛かる）
+    volume: OutputVolume,
+    /// 左右の定位（`None` ならモノラルで出力）
+    pan: Option<f32>,
 }
 
 impl AudioOutput {
@@ -36,9 +300,35 @@ impl AudioOutput {
             sample_rate,
             stream: None,
             audio_tx: None,
+            volume: OutputVolume::new(1.0),
+            pan: None,
         })
     }
 
+    /// 左右の定位を設定（-1.0 = 左のみ、1.0 = 右のみ、`None` = モノラル）
+    ///
+    /// 出力ストリームのチャンネル数が変わるため、`start` の前に設定すること。
+    pub fn set_pan(&mut self, pan: Option<f32>) {
+        self.pan = pan;
+    }
+
+    /// 出力音量の係数を設定（1.0 = 原音、0.0～`MAX_OUTPUT_VOLUME` にクランプ）
+    ///
+    /// 再生中でも次の出力バッファから反映される。丸めた後の値を返す。
+    pub fn set_volume(&self, gain: f32) -> f32 {
+        self.volume.set(gain)
+    }
+
+    /// 現在の出力音量の係数
+    pub fn volume(&self) -> f32 {
+        self.volume.get()
+    }
+
+    /// 出力音量の共有ハンドルを取得
+    pub fn volume_control(&self) -> OutputVolume {
+        self.volume.clone()
+    }
+
     /// デバイス一覧を表示
     pub fn list_devices() -> Result<()> {
         let host = cpal::default_host();
@@ -69,7 +359,22 @@ impl AudioOutput {
     }
 
     /// 音声ストリームを開始
+    ///
+    /// サンプルの受信は呼び出し元の tokio ランタイムのタスクで行う。
+    /// ランタイム外から呼んだ場合はパニックせずエラーを返す（`start_with` を使うこと）。
     pub fn start(&mut self) -> Result<mpsc::Sender<Vec<i16>>> {
+        let runtime = ReceiverRuntime::current().ok_or_else(|| {
+            anyhow::anyhow!(
+                "tokio ランタイム外では出力ストリームを開始できません（AudioOutput::start_with で実行先を指定してください）"
+            )
+        })?;
+        self.start_with(runtime)
+    }
+
+    /// サンプルを受信する処理の実行先を指定して音声ストリームを開始
+    ///
+    /// `ReceiverRuntime::Thread` なら tokio ランタイムなしで使える。
+    pub fn start_with(&mut self, runtime: ReceiverRuntime) -> Result<mpsc::Sender<Vec<i16>>> {
         // デバイスのデフォルト設定を取得してサンプルフォーマットを確認
         let default_config = self
             .device
@@ -84,7 +389,7 @@ impl AudioOutput {
         );
 
         let config = StreamConfig {
-            channels: 1,
+            channels: if self.pan.is_some() { 2 } else { 1 },
             sample_rate: cpal::SampleRate(self.sample_rate),
             buffer_size: cpal::BufferSize::Default,
         };
@@ -98,17 +403,23 @@ impl AudioOutput {
         // チャンネルを作成（大きめのバッファ）
         let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(1024);
 
+        // サンプルバッファを共有
+        let sample_buffer: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
+
         // デバイスのサンプルフォーマットに応じてストリームを構築
         let stream = match default_config.sample_format() {
-            SampleFormat::F32 => self.build_stream::<f32>(config, audio_rx)?,
-            SampleFormat::I16 => self.build_stream::<i16>(config, audio_rx)?,
-            SampleFormat::U16 => self.build_stream::<u16>(config, audio_rx)?,
+            SampleFormat::F32 => self.build_stream::<f32>(config, sample_buffer.clone())?,
+            SampleFormat::I16 => self.build_stream::<i16>(config, sample_buffer.clone())?,
+            SampleFormat::U16 => self.build_stream::<u16>(config, sample_buffer.clone())?,
             _ => anyhow::bail!(
                 "サポートされていないサンプルフォーマット: {:?}",
                 default_config.sample_format()
             ),
         };
 
+        // 音声データを受信してバッファに追加
+        spawn_receiver(runtime, audio_rx, sample_buffer)?;
+
         // ストリームを再生開始
         stream.play().context("ストリームの再生開始に失敗")?;
 
@@ -118,26 +429,19 @@ impl AudioOutput {
         Ok(audio_tx)
     }
 
-    /// 指定されたサンプルフォーマットで出力ストリームを構築
-    fn build_stream<T>(
-        &self,
-        config: StreamConfig,
-        mut audio_rx: mpsc::Receiver<Vec<i16>>,
-    ) -> Result<Stream>
+    /// 指定されたサンプルフォーマットで、`sample_buffer` から再生する出力ストリームを構築
+    fn build_stream<T>(&self, config: StreamConfig, sample_buffer: Arc<Mutex<Vec<i16>>>) -> Result<Stream>
     where
-        T: SizedSample + Sample + FromSample<f32> + Send + 'static,
+        T: SizedSample + Sample + DeviceSample + Send + 'static,
     {
-        // サンプルバッファを共有
-        let sample_buffer: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
-        let sample_buffer_clone = sample_buffer.clone();
-
-        // バックグラウンドタスクで音声データを受信してバッファに追加
-        tokio::spawn(async move {
-            while let Some(samples) = audio_rx.recv().await {
-                let mut buffer = sample_buffer_clone.lock().unwrap();
-                buffer.extend_from_slice(&samples);
+        let volume = self.volume.clone();
+        let channel_gains = match self.pan {
+            Some(pan) => {
+                let (left, right) = pan_gains(pan);
+                vec![left, right]
             }
-        });
+            None => vec![1.0],
+        };
 
         // 出力ストリームを構築
         let stream = self
@@ -145,28 +449,9 @@ impl AudioOutput {
             .build_output_stream(
                 &config,
                 move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                    // バッファから必要なサンプル数を取り出し、不足している分は無音
                     let mut buffer = sample_buffer.lock().unwrap();
-
-                    if buffer.len() >= data.len() {
-                        // バッファから必要なサンプル数を取り出し
-                        for (i, sample) in data.iter_mut().enumerate() {
-                            *sample = Self::convert_sample::<T>(buffer[i]);
-                        }
-                        buffer.drain(..data.len());
-                    } else {
-                        // バッファが不足している場合、利用可能な分だけコピーして残りは無音
-                        let available = buffer.len();
-                        for i in 0..data.len() {
-                            if i < available {
-                                data[i] = Self::convert_sample::<T>(buffer[i]);
-                            } else {
-                                data[i] = Sample::EQUILIBRIUM;
-                            }
-                        }
-                        if available > 0 {
-                            buffer.clear();
-                        }
-                    }
+                    fill_output(data, &mut buffer, volume.get(), &channel_gains);
                 },
                 move |err| {
                     log::error!("出力ストリームエラー: {}", err);
@@ -178,13 +463,6 @@ impl AudioOutput {
         Ok(stream)
     }
 
-    /// i16サンプルを指定されたフォーマットに変換
-    fn convert_sample<T: Sample + FromSample<f32>>(sample: i16) -> T {
-        // i16を-1.0~1.0の範囲に正規化してから対象フォーマットに変換
-        let normalized = sample as f32 / i16::MAX as f32;
-        T::from_sample(normalized)
-    }
-
     /// 音声ストリームを停止
     pub fn stop(&mut self) {
         if let Some(stream) = self.stream.take() {
@@ -200,3 +478,149 @@ impl Drop for AudioOutput {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receive(runtime: ReceiverRuntime) -> Vec<i16> {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let (tx, rx) = mpsc::channel(4);
+        spawn_receiver(runtime, rx, buffer.clone()).unwrap();
+        tx.blocking_send(vec![1, 2]).unwrap();
+        tx.blocking_send(vec![3]).unwrap();
+        drop(tx);
+        // 受信処理の終了（バッファへの参照の解放）を待つ
+        while Arc::strong_count(&buffer) > 1 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        Arc::try_unwrap(buffer).unwrap().into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_receiver_without_runtime() {
+        assert!(ReceiverRuntime::current().is_none());
+        assert_eq!(receive(ReceiverRuntime::Thread), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_receiver_on_runtime_handle() {
+        let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(1).build().unwrap();
+        assert_eq!(receive(ReceiverRuntime::Tokio(runtime.handle().clone())), vec![1, 2, 3]);
+        assert!(runtime.block_on(async { ReceiverRuntime::current() }).is_some());
+    }
+
+    #[test]
+    fn test_apply_volume() {
+        assert_eq!(apply_volume(1000, 1.0), 1000);
+        assert_eq!(apply_volume(1000, 0.5), 500);
+        assert_eq!(apply_volume(-1000, 0.0), 0);
+        // 増幅しても飽和するだけで折り返さない
+        assert_eq!(apply_volume(30000, 2.0), i16::MAX);
+        assert_eq!(apply_volume(-30000, 2.0), i16::MIN);
+    }
+
+    #[test]
+    fn test_output_volume_clamped_and_shared() {
+        let volume = OutputVolume::new(5.0);
+        assert_eq!(volume.get(), MAX_OUTPUT_VOLUME);
+
+        let handle = volume.clone();
+        assert_eq!(handle.set(0.8), 0.8);
+        assert_eq!(volume.get(), 0.8);
+        assert_eq!(handle.set(-0.1), 0.0);
+        assert_eq!(handle.set(f32::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_fill_output_mono_and_panned() {
+        // モノラル: 足りない分は無音にして、使ったサンプルだけ取り除く
+        let mut buffer = vec![100, 200, 300];
+        let mut data = [1i16; 2];
+        fill_output(&mut data, &mut buffer, 1.0, &[1.0]);
+        assert_eq!(data, [100, 200]);
+        assert_eq!(buffer, vec![300]);
+        let mut data = [1i16; 3];
+        fill_output(&mut data, &mut buffer, 2.0, &[1.0]);
+        assert_eq!(data, [600, 0, 0]);
+        assert!(buffer.is_empty());
+
+        // 定位あり: 1サンプルを左右の2チャンネルに振り分ける
+        let (left, right) = pan_gains(-0.5);
+        let mut buffer = vec![1000, -1000, 500];
+        let mut data = [1i16; 4];
+        fill_output(&mut data, &mut buffer, 1.0, &[left, right]);
+        assert_eq!(data, [1000, 500, -1000, -500]);
+        assert_eq!(buffer, vec![500]);
+    }
+
+    #[test]
+    fn test_monitor_route() {
+        // TUIの選択に従うモニタ
+        let mut route = MonitorRoute::new(None);
+        assert!(route.follows_selection());
+        assert_eq!(route.update(None), None);
+        assert_eq!(route.update(Some(2)), Some((None, Some(2))));
+        assert_eq!(route.update(Some(2)), None);
+        assert_eq!(route.update(None), Some((Some(2), None)));
+
+        // 担当チャンネルが固定されたモニタは選択によらず同じチャンネル
+        let mut route = MonitorRoute::new(Some(1));
+        assert_eq!(route.update(None), Some((None, Some(1))));
+        assert_eq!(route.update(Some(3)), None);
+        assert_eq!(route.current(), Some(1));
+    }
+
+    #[test]
+    fn test_follower_switches_to_speaking_channel() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut follower = ActiveChannelFollower::new();
+
+        // 出力先がなければすぐに話しているチャンネルへ
+        assert_eq!(follower.update(&[(0, false, -60.0), (1, true, -20.0)], at(0)), Some(1));
+
+        // 無音になっても直前のチャンネルを維持
+        assert_eq!(follower.update(&[(0, false, -60.0), (1, false, -60.0)], at(100)), Some(1));
+
+        // 別のチャンネルが話し始めても FOLLOW_HOLD 続くまでは切り替えない
+        let ch0 = [(0, true, -25.0), (1, false, -60.0)];
+        assert_eq!(follower.update(&ch0, at(2000)), Some(1));
+        assert_eq!(follower.update(&ch0, at(2200)), Some(1));
+        assert_eq!(follower.update(&ch0, at(2300)), Some(0));
+
+        // 切り替え直後は FOLLOW_MIN_DWELL の間、他のチャンネルに移らない
+        let ch1 = [(0, false, -60.0), (1, true, -20.0)];
+        assert_eq!(follower.update(&ch1, at(2400)), Some(0));
+        assert_eq!(follower.update(&ch1, at(3000)), Some(0));
+        assert_eq!(follower.update(&ch1, at(3800)), Some(1));
+    }
+
+    #[test]
+    fn test_follower_simultaneous_speech_prefers_louder() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut follower = ActiveChannelFollower::new();
+        follower.reset_to(Some(0));
+
+        // 出力中のチャンネルも話している間は、差が FOLLOW_LOUDER_MARGIN_DB 未満なら維持
+        let close = [(0, true, -24.0), (1, true, -20.0)];
+        assert_eq!(follower.update(&close, at(0)), Some(0));
+        assert_eq!(follower.update(&close, at(1000)), Some(0));
+
+        // 十分に大きければ FOLLOW_HOLD 後に切り替える
+        let louder = [(0, true, -30.0), (1, true, -20.0)];
+        assert_eq!(follower.update(&louder, at(1100)), Some(0));
+        assert_eq!(follower.update(&louder, at(1400)), Some(1));
+
+        // 候補が途切れたら待ち時間をやり直す
+        let mut follower = ActiveChannelFollower::new();
+        follower.reset_to(Some(0));
+        let ch1 = [(0, false, -60.0), (1, true, -20.0)];
+        let silent = [(0, false, -60.0), (1, false, -60.0)];
+        assert_eq!(follower.update(&ch1, at(0)), Some(0));
+        assert_eq!(follower.update(&silent, at(200)), Some(0));
+        assert_eq!(follower.update(&ch1, at(300)), Some(0));
+        assert_eq!(follower.update(&ch1, at(600)), Some(1));
+    }
+}
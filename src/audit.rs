@@ -0,0 +1,244 @@
+use crate::config::AuditConfig;
+use crate::timezone::OutputTimeZone;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// 監査ログのイベントの種類
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// VADが音声開始と判定した
+    VoiceStart,
+    /// VADが音声終了と判定した
+    VoiceEnd,
+    /// 文字起こしバックエンドに接続した
+    Connect,
+    /// 文字起こしバックエンドへの接続に失敗した
+    ConnectFailed,
+    /// 文字起こしバックエンドから切断した
+    Disconnect,
+    /// 送信チャンネルが閉じていたため切断状態に移行した
+    StreamClosed,
+    /// 切断中にためた音声を再接続時に送信した
+    BufferSent,
+    /// 再接続時バッファ送信が無効のため、切断中の音声を破棄した
+    BufferDiscarded,
+}
+
+/// 監査ログの1イベント
+///
+/// `ChannelProcessor` の処理中に作り、整形とファイル書き込みは `AuditSink` のワーカーで行う。
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    pub kind: AuditEventKind,
+    pub channel_id: usize,
+    /// イベントの時刻 (UNIX_EPOCHからのナノ秒)
+    pub timestamp_ns: u128,
+    /// 音量 (dB)
+    pub volume_db: Option<f32>,
+    /// 送信・破棄したバッファのチャンク数
+    pub buffered_chunks: Option<usize>,
+    /// 送信・破棄したバッファの長さ（ミリ秒）
+    pub buffered_ms: Option<u32>,
+    /// 切断までの無音の長さ（ミリ秒）
+    pub silence_ms: Option<u32>,
+    /// エラーの内容など
+    pub detail: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(kind: AuditEventKind, channel_id: usize, timestamp_ns: u128) -> Self {
+        Self {
+            kind,
+            channel_id,
+            timestamp_ns,
+            volume_db: None,
+            buffered_chunks: None,
+            buffered_ms: None,
+            silence_ms: None,
+            detail: None,
+        }
+    }
+
+    pub fn with_volume_db(mut self, volume_db: f32) -> Self {
+        self.volume_db = Some(volume_db);
+        self
+    }
+
+    pub fn with_buffered(mut self, chunks: usize, ms: u32) -> Self {
+        self.buffered_chunks = Some(chunks);
+        self.buffered_ms = Some(ms);
+        self
+    }
+
+    pub fn with_silence_ms(mut self, silence_ms: u32) -> Self {
+        self.silence_ms = Some(silence_ms);
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// 監査ログの1行（JSON）
+#[derive(Debug, Serialize)]
+struct AuditRecord<'a> {
+    time: String,
+    event: AuditEventKind,
+    channel: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume_db: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buffered_chunks: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buffered_ms: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    silence_ms: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<&'a str>,
+}
+
+impl AuditEvent {
+    /// 監査ログの1行に整形する（音量は小数第1位に丸める）
+    fn to_json_line(&self, timezone: &OutputTimeZone) -> Result<String> {
+        let record = AuditRecord {
+            time: timezone.format_timestamp_ns(self.timestamp_ns),
+            event: self.kind,
+            channel: self.channel_id,
+            volume_db: self.volume_db.map(|db| (db * 10.0).round() / 10.0),
+            buffered_chunks: self.buffered_chunks,
+            buffered_ms: self.buffered_ms,
+            silence_ms: self.silence_ms,
+            detail: self.detail.as_deref(),
+        };
+        Ok(serde_json::to_string(&record)?)
+    }
+}
+
+/// 監査ログ（`[audit] file`）をバックグラウンドで書き込むキュー
+///
+/// `record` はキューに積むだけで、音声処理のループをファイルI/Oで止めない。
+/// ワーカーはキューが空になるたびにフラッシュする。
+/// すべての `AuditSink` がドロップされると、残りを書き終えてからワーカーが終了する。
+#[derive(Clone)]
+pub struct AuditSink {
+    tx: mpsc::UnboundedSender<AuditEvent>,
+}
+
+impl AuditSink {
+    /// 監査ログを追記モードで開き、書き込みワーカーを起動
+    ///
+    /// 戻り値の `JoinHandle` を待つとキューが空になるまで待機できる。
+    pub fn spawn(
+        config: &AuditConfig,
+        timezone: OutputTimeZone,
+    ) -> Result<(Self, tokio::task::JoinHandle<()>)> {
+        let path = PathBuf::from(&config.file);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("監査ログのディレクトリ作成に失敗: {:?}", parent))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("監査ログを開けませんでした: {:?}", path))?;
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<AuditEvent>();
+        let handle = tokio::spawn(async move {
+            let mut writer = BufWriter::new(file);
+            while let Some(event) = rx.recv().await {
+                write_event(&mut writer, &event, &timezone, &path);
+                while let Ok(event) = rx.try_recv() {
+                    write_event(&mut writer, &event, &timezone, &path);
+                }
+                if let Err(e) = writer.flush() {
+                    log::warn!("監査ログのフラッシュに失敗: {:?}: {}", path, e);
+                }
+            }
+        });
+
+        Ok((Self { tx }, handle))
+    }
+
+    /// イベントをキューに追加
+    pub fn record(&self, event: AuditEvent) {
+        if self.tx.send(event).is_err() {
+            log::warn!("監査ログのワーカーが停止しているため記録できません");
+        }
+    }
+}
+
+fn write_event(writer: &mut impl Write, event: &AuditEvent, timezone: &OutputTimeZone, path: &PathBuf) {
+    let result = event
+        .to_json_line(timezone)
+        .and_then(|line| Ok(writeln!(writer, "{}", line)?));
+    if let Err(e) = result {
+        log::warn!("監査ログの書き込みに失敗: {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_to_json_line() {
+        let event = AuditEvent::new(AuditEventKind::VoiceStart, 2, 1_735_961_696_123_456_789)
+            .with_volume_db(-23.456);
+        let json: serde_json::Value =
+            serde_json::from_str(&event.to_json_line(&OutputTimeZone::Utc).unwrap()).unwrap();
+        assert_eq!(json["time"], "2025-01-04T03:34:56.123+00:00");
+        assert_eq!(json["event"], "voice_start");
+        assert_eq!(json["channel"], 2);
+        assert_eq!(json["volume_db"], -23.5);
+        // 付随データがなければ出力しない
+        assert!(json.get("buffered_ms").is_none());
+        assert!(json.get("detail").is_none());
+
+        let event = AuditEvent::new(AuditEventKind::BufferSent, 0, 0).with_buffered(5, 500);
+        let json: serde_json::Value =
+            serde_json::from_str(&event.to_json_line(&OutputTimeZone::Utc).unwrap()).unwrap();
+        assert_eq!(json["event"], "buffer_sent");
+        assert_eq!(json["buffered_chunks"], 5);
+        assert_eq!(json["buffered_ms"], 500);
+    }
+
+    #[tokio::test]
+    async fn test_sink_writes_one_line_per_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("audit/audit.jsonl");
+        let config = AuditConfig {
+            file: path.to_string_lossy().into_owned(),
+        };
+
+        let (sink, handle) = AuditSink::spawn(&config, OutputTimeZone::Utc).unwrap();
+        sink.record(AuditEvent::new(AuditEventKind::Connect, 1, 0));
+        sink.record(AuditEvent::new(AuditEventKind::Disconnect, 1, 1_000_000).with_silence_ms(3000));
+        drop(sink);
+        handle.await.unwrap();
+
+        // 2回目の起動では追記する
+        let (sink, handle) = AuditSink::spawn(&config, OutputTimeZone::Utc).unwrap();
+        sink.record(AuditEvent::new(AuditEventKind::ConnectFailed, 1, 2_000_000).with_detail("timeout"));
+        drop(sink);
+        handle.await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let events: Vec<serde_json::Value> =
+            content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["event"], "connect");
+        assert_eq!(events[1]["event"], "disconnect");
+        assert_eq!(events[1]["silence_ms"], 3000);
+        assert_eq!(events[2]["event"], "connect_failed");
+        assert_eq!(events[2]["detail"], "timeout");
+    }
+}
@@ -1,16 +1,88 @@
-use crate::config::TranscribeConfig;
-use crate::transcribe_backend::TranscribeBackend;
+use crate::config::{FlacConfig, TranscribeConfig};
+use crate::flac_encoder::{FlacEncodeStats, FlacEncoder};
+use crate::transcribe_backend::{MonoNormalizer, TranscribeAudio, TranscribeBackend};
 use crate::types::{Stability, TranscriptResult};
 use anyhow::Result;
 use async_trait::async_trait;
 use aws_config;
 use aws_sdk_transcribestreaming::Client as AwsTranscribeClient;
-use aws_sdk_transcribestreaming::types::{AudioEvent, AudioStream, LanguageCode, MediaEncoding};
+use aws_sdk_transcribestreaming::config::Region;
+use aws_sdk_transcribestreaming::error::SdkError;
+use aws_sdk_transcribestreaming::types::{AudioEvent, AudioStream, Item, LanguageCode, MediaEncoding};
 use aws_smithy_types::Blob;
-use std::time::SystemTime;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Instant, SystemTime};
 use tokio::sync::mpsc;
 use async_stream::stream;
 
+/// 予防的再接続時に新ストリームへ重複送信する直前の音声の長さ（ミリ秒）
+///
+/// 切り替え境界をまたぐ単語を新ストリーム側で認識できるようにする。
+const PROACTIVE_RECONNECT_OVERLAP_MS: u64 = 2000;
+
+/// Amazon Transcribe の同時ストリーム数クォータの既定値（`[transcribe] stream_quota` 未指定時に想定する値）
+///
+/// 実際の値はリージョンやアカウントごとに Service Quotas で確認できる。
+pub const DEFAULT_STREAM_QUOTA: usize = 25;
+
+/// 有効チャンネル数・`[transcribe] max_concurrent_streams` と同時ストリーム数クォータの照合結果
+///
+/// 同じアカウントの他のプロセスが使うストリームは数えない。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamQuotaCheck {
+    /// 有効なチャンネル数（全チャンネルが同時に話すと、この数のストリームを開く）
+    pub channels: usize,
+    /// 設定された同時ストリーム数の上限
+    pub max_concurrent_streams: Option<usize>,
+    /// 照合するクォータ
+    pub quota: usize,
+    /// `quota` が `[transcribe] stream_quota` で指定された値か（false は既定値の想定）
+    pub quota_configured: bool,
+}
+
+impl StreamQuotaCheck {
+    pub fn new(channels: usize, config: &TranscribeConfig) -> Self {
+        Self {
+            channels,
+            max_concurrent_streams: config.max_concurrent_streams,
+            quota: config.stream_quota.unwrap_or(DEFAULT_STREAM_QUOTA),
+            quota_configured: config.stream_quota.is_some(),
+        }
+    }
+
+    /// 同時に開きうるストリーム数の最大
+    pub fn peak_streams(&self) -> usize {
+        self.max_concurrent_streams.map_or(self.channels, |max| max.min(self.channels))
+    }
+
+    /// クォータを超えうる場合の説明（`max_concurrent_streams` の設定を促す）
+    pub fn warning(&self) -> Option<String> {
+        if self.peak_streams() <= self.quota {
+            return None;
+        }
+        let quota = if self.quota_configured {
+            format!("{}（[transcribe] stream_quota）", self.quota)
+        } else {
+            format!("{}（既定値を想定）", self.quota)
+        };
+        Some(match self.max_concurrent_streams {
+            Some(max) => format!(
+                "[transcribe] max_concurrent_streams = {} が Amazon Transcribe の同時ストリーム数クォータ {} を超えています。\
+                 一斉に接続すると一部のチャンネルが接続に失敗するため、max_concurrent_streams を {} 以下にしてください",
+                max, quota, self.quota
+            ),
+            None => format!(
+                "有効なチャンネル数 {} が Amazon Transcribe の同時ストリーム数クォータ {} を超えています。\
+                 一斉に接続すると一部のチャンネルが接続に失敗するため、[transcribe] max_concurrent_streams = {} を設定してください\
+                 （クォータを引き上げ済みの場合は [transcribe] stream_quota にその値を指定してください）",
+                self.channels, quota, self.quota
+            ),
+        })
+    }
+}
+
 /// AWS Transcribe Streaming API クライアント
 pub struct AwsTranscribeBackend {
     config: TranscribeConfig,
@@ -20,56 +92,311 @@ pub struct AwsTranscribeBackend {
     reconnection_count: u32,
     /// 現在実行中のタスクハンドル（リソースリーク防止用）
     task_handle: Option<tokio::task::JoinHandle<()>>,
+    /// 結果の通し番号（再接続をまたいで継続）
+    sequence: Arc<AtomicU64>,
+    /// FLACエンコードの成否の集計（再接続をまたいで継続）
+    flac_stats: Arc<FlacEncodeStats>,
+    /// 送信する音声のエンコード（`[flac]`）
+    flac: FlacConfig,
+    /// SDKクライアント（再接続をまたいで再利用）
+    client: ClientCache,
+    /// 次のストリームで使う言語（自動言語切り替え時は優先言語）
+    language: String,
+}
+
+/// 再接続をまたいで再利用する SDK クライアント
+///
+/// `load_sdk_config` による設定・認証プロバイダの解決は最初の接続時に一度だけ行う。
+/// クライアントは認証情報のキャッシュと HTTP 接続プールを持つため、再接続時は新しいストリームを張るだけで済む。
+/// ストリームがクライアント起因のエラー（リクエストの構築失敗・送信失敗）で終わった場合は
+/// `invalidated` が立ち、次の接続で設定の読み込みからやり直す。
+#[derive(Default)]
+struct ClientCache {
+    client: Option<AwsTranscribeClient>,
+    /// 次の接続でクライアントを作り直すか（ストリームのタスクから立てる）
+    invalidated: Arc<AtomicBool>,
+    /// クライアントを作成した回数
+    builds: u32,
+}
+
+impl ClientCache {
+    /// キャッシュ済みのクライアント（無効化されていれば破棄して `None`）
+    fn cached(&mut self) -> Option<AwsTranscribeClient> {
+        if self.invalidated.swap(false, Ordering::SeqCst) {
+            self.client = None;
+        }
+        self.client.clone()
+    }
+
+    fn store(&mut self, client: AwsTranscribeClient) {
+        self.builds += 1;
+        self.client = Some(client);
+    }
+}
+
+/// `[transcribe]` の `region` を指定して SDK の共通設定を読み込む
+///
+/// 認証情報などは環境変数・プロファイルから解決するが、リージョンは環境変数や
+/// プロファイルの設定より `region` を優先する。
+pub(crate) async fn load_sdk_config(config: &TranscribeConfig) -> aws_config::SdkConfig {
+    aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(Region::new(config.region.clone()))
+        .load()
+        .await
+}
+
+/// SDK の設定に `[transcribe]` の `region` と `endpoint_url` を反映したクライアント設定
+///
+/// `endpoint_url` を指定した場合もリクエストの署名には `region` を使う。
+fn client_config(
+    builder: aws_sdk_transcribestreaming::config::Builder,
+    config: &TranscribeConfig,
+) -> aws_sdk_transcribestreaming::Config {
+    let builder = builder.region(Region::new(config.region.clone()));
+    match &config.endpoint_url {
+        Some(endpoint_url) => builder.endpoint_url(endpoint_url).build(),
+        None => builder.build(),
+    }
+}
+
+/// ストリームの開始エラーがクライアント側の問題で、作り直すべきか
+///
+/// 認証情報の解決失敗などのリクエスト構築エラーと、接続できなかった送信エラーが対象。
+/// サービスからのエラー応答やタイムアウトはクライアントを作り直しても解決しないため対象外。
+fn requires_client_rebuild<E, R>(error: &SdkError<E, R>) -> bool {
+    matches!(error, SdkError::ConstructionFailure(_) | SdkError::DispatchFailure(_))
+}
+
+/// 1本のストリームを処理するために必要な共有情報
+#[derive(Clone)]
+struct StreamContext {
+    client: AwsTranscribeClient,
+    /// 言語（自動言語切り替え時は優先言語）
+    language_code: LanguageCode,
+    /// 自動言語切り替えの候補（カンマ区切り、無効なら None）
+    language_options: Option<String>,
+    sample_rate: u32,
+    channel_id: usize,
+    start_time: SystemTime,
+    result_tx: mpsc::Sender<TranscriptResult>,
+    sequence: Arc<AtomicU64>,
+    flac_stats: Arc<FlacEncodeStats>,
+    flac: FlacConfig,
+    chunk_sizing: ChunkSizing,
+    /// クライアント起因のエラーで失敗したら立てる（[`ClientCache::invalidated`]）
+    client_invalidated: Arc<AtomicBool>,
+}
+
+/// Amazon Transcribe に送る音声チャンクのエンコード（`[flac] enabled`）
+enum ChunkEncoder {
+    /// FLAC（`MediaEncoding::Flac`）
+    Flac(FlacEncoder),
+    /// 無圧縮（`MediaEncoding::Pcm`、リトルエンディアンの16ビット）
+    ///
+    /// エンコードのCPU負荷がなく、FLACの失敗を切り分ける場合にも使う。送信量は増える。
+    Pcm,
+}
+
+impl ChunkEncoder {
+    fn new(sample_rate: u32, flac: &FlacConfig) -> Self {
+        if flac.enabled {
+            Self::Flac(FlacEncoder::new(sample_rate, flac.compression_level))
+        } else {
+            Self::Pcm
+        }
+    }
+
+    fn media_encoding(&self) -> MediaEncoding {
+        match self {
+            Self::Flac(_) => MediaEncoding::Flac,
+            Self::Pcm => MediaEncoding::Pcm,
+        }
+    }
+
+    /// チャンクをエンコード（FLACのエンコードに失敗したチャンクは `None` で読み飛ばす）
+    fn encode(&mut self, channel_id: usize, samples: &[i16], stats: &FlacEncodeStats) -> Option<Vec<u8>> {
+        match self {
+            Self::Flac(encoder) => encoder.encode_or_skip(channel_id, samples, stats),
+            Self::Pcm => Some(samples.iter().flat_map(|sample| sample.to_le_bytes()).collect()),
+        }
+    }
+}
+
+/// 送信チャンクのサイズ（サンプル数）
+///
+/// 接続直後の `fast_start_chunks` 個は小さいチャンクで素早く送り、
+/// それ以降は通常サイズで送る。設定はミリ秒で持ち、サンプルレートから換算する。
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ChunkSizing {
+    initial_samples: usize,
+    chunk_samples: usize,
+    fast_start_chunks: u32,
+}
+
+impl ChunkSizing {
+    fn new(config: &TranscribeConfig, sample_rate: u32) -> Self {
+        let to_samples = |ms: u32| ((sample_rate as u64 * ms as u64 / 1000) as usize).max(1);
+        Self {
+            initial_samples: to_samples(config.initial_chunk_ms),
+            chunk_samples: to_samples(config.chunk_ms),
+            fast_start_chunks: config.fast_start_chunks,
+        }
+    }
+
+    /// 送信済みチャンク数に応じた次のチャンクのサンプル数
+    fn samples_for(&self, sent_chunks: u32) -> usize {
+        if sent_chunks < self.fast_start_chunks {
+            self.initial_samples
+        } else {
+            self.chunk_samples
+        }
+    }
+}
+
+/// 単語ごとの確信度の平均（確信度を持つ単語がなければ `None`）
+///
+/// 句読点などの確信度を持たない要素は平均に含めない。
+fn average_confidence(items: &[Item]) -> Option<f32> {
+    let confidences: Vec<f64> = items.iter().filter_map(|item| item.confidence).collect();
+    if confidences.is_empty() {
+        return None;
+    }
+    Some((confidences.iter().sum::<f64>() / confidences.len() as f64) as f32)
+}
+
+/// 予防的に切り替えたストリームの時間情報
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct StreamTiming {
+    /// このストリームの音声の先頭が、基準時刻（`start_time`）から何秒後か
+    offset_secs: f64,
+    /// 先頭のオーバーラップ区間（秒）。この区間内で始まる結果は旧ストリームと重複するため破棄する
+    overlap_secs: f64,
+}
+
+impl StreamTiming {
+    /// 基準時刻から `offset_secs` 後に始まる最初のストリーム（オーバーラップなし）
+    fn starting_at(offset_secs: f64) -> Self {
+        Self {
+            offset_secs,
+            overlap_secs: 0.0,
+        }
+    }
+
+    /// 旧ストリームに送った音声の長さとオーバーラップ長から次のストリームの時間情報を求める
+    ///
+    /// 音声は発話中のみ送られ、再送分はまとめて届くため、経過時間（壁時計）ではなく送った音声の長さで数える。
+    fn next(&self, sent_secs: f64, overlap_secs: f64) -> Self {
+        Self {
+            offset_secs: self.offset_secs + sent_secs - overlap_secs,
+            overlap_secs,
+        }
+    }
+
+    /// 結果がオーバーラップ区間内で始まるか
+    ///
+    /// 切り替え時点より前に始まる発話は旧ストリームが（切り替え時点までの音声で）出力するため、
+    /// 切り替え時点をまたぐ結果も新ストリームでは破棄する。
+    fn is_in_overlap(&self, audio_start_secs: f64) -> bool {
+        self.overlap_secs > 0.0 && audio_start_secs < self.overlap_secs
+    }
 }
 
 impl AwsTranscribeBackend {
-    pub async fn new(config: TranscribeConfig, channel_id: usize, start_time: SystemTime) -> Result<Self> {
+    /// 現在時刻をタイムスタンプの基準時刻として作成
+    pub async fn new(config: TranscribeConfig, channel_id: usize) -> Result<Self> {
+        Self::new_with_start_time(config, channel_id, SystemTime::now()).await
+    }
+
+    /// タイムスタンプの基準時刻を指定して作成（複数チャンネルで時刻をそろえる場合）
+    pub async fn new_with_start_time(
+        config: TranscribeConfig,
+        channel_id: usize,
+        start_time: SystemTime,
+    ) -> Result<Self> {
         let start_time_debug = start_time.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
         log::info!(
             "チャンネル {}: start_time = {} (UNIX timestamp)",
             channel_id,
             start_time_debug
         );
+        let language = config.language_code.clone();
         Ok(Self {
             config,
             channel_id,
             start_time,
             reconnection_count: 0,
             task_handle: None,
+            sequence: Arc::new(AtomicU64::new(0)),
+            flac_stats: Arc::new(FlacEncodeStats::default()),
+            flac: FlacConfig::default(),
+            client: ClientCache::default(),
+            language,
         })
     }
+
+    /// 送信する音声のエンコードを設定（`[flac]`、未設定ならFLAC）
+    pub fn with_flac(mut self, flac: FlacConfig) -> Self {
+        self.flac = flac;
+        self
+    }
+
+    /// SDKクライアントを取得する（未作成または無効化されていれば作成）
+    ///
+    /// 戻り値の `bool` はこの呼び出しで新しく作成したか。
+    async fn client(&mut self) -> (AwsTranscribeClient, bool) {
+        if let Some(client) = self.client.cached() {
+            return (client, false);
+        }
+        if self.client.builds > 0 {
+            log::warn!(
+                "チャンネル {}: 前回のストリームがクライアント起因のエラーで終了したため、AWS Transcribe クライアントを再作成",
+                self.channel_id
+            );
+        }
+
+        let sdk_config = load_sdk_config(&self.config).await;
+        if let Some(endpoint_url) = &self.config.endpoint_url {
+            log::info!(
+                "チャンネル {}: AWS Transcribe のエンドポイントを上書き: {} (署名リージョン {})",
+                self.channel_id,
+                endpoint_url,
+                self.config.region
+            );
+        }
+        let client = AwsTranscribeClient::from_conf(client_config((&sdk_config).into(), &self.config));
+        self.client.store(client.clone());
+        (client, true)
+    }
 }
 
 #[async_trait]
 impl TranscribeBackend for AwsTranscribeBackend {
     async fn start_stream(
         &mut self,
-    ) -> Result<(mpsc::Sender<Vec<i16>>, mpsc::Receiver<TranscriptResult>)> {
-        use std::sync::Arc;
-        use tokio::sync::Mutex;
-        use crate::flac_encoder::FlacEncoder;
-
-        let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(4096);
-        let audio_rx = Arc::new(Mutex::new(audio_rx));
+    ) -> Result<(mpsc::Sender<TranscribeAudio>, mpsc::Receiver<TranscriptResult>)> {
+        let (audio_tx, audio_rx) = mpsc::channel::<TranscribeAudio>(4096);
         let (result_tx, result_rx) = mpsc::channel::<TranscriptResult>(32);
 
-        // AWS SDKクライアント初期化（チャンネルごとに独立した設定で作成）
-        let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-        let client = AwsTranscribeClient::new(&sdk_config);
-
+        // AWS SDKクライアントはチャンネルごとに一度だけ作成し、再接続では再利用する
+        let setup_started = Instant::now();
+        let (client, created) = self.client().await;
         log::info!(
-            "チャンネル {}: AWS Transcribe クライアントを作成",
-            self.channel_id
+            "チャンネル {}: AWS Transcribe クライアントを{} (セットアップ {:.1}ms)",
+            self.channel_id,
+            if created { "作成" } else { "再利用" },
+            setup_started.elapsed().as_secs_f64() * 1000.0
         );
 
-        let language_code = match self.config.language_code.as_str() {
+        let language_code = match self.language.as_str() {
             "ja-JP" => LanguageCode::JaJp,
             "en-US" => LanguageCode::EnUs,
             other => LanguageCode::from(other),
         };
-        let sample_rate = self.config.sample_rate;
+        let language_options = self
+            .config
+            .auto_language()
+            .then(|| self.config.allowed_languages.join(","));
         let channel_id = self.channel_id;
-        let start_time = self.start_time;
 
         // 古いタスクがあれば破棄（チャンネルクローズにより自動終了）
         if let Some(old_handle) = self.task_handle.take() {
@@ -78,295 +405,487 @@ impl TranscribeBackend for AwsTranscribeBackend {
             drop(old_handle);
         }
 
-        let handle = tokio::spawn({
-            let language_code = language_code.clone();
-            let sample_rate = sample_rate;
-            let channel_id = channel_id;
-            let start_time = start_time;
-            let audio_rx = Arc::clone(&audio_rx);
-            let client = client.clone();
-            let result_tx = result_tx.clone();
-            async move {
-                use tokio::time::{Duration, timeout};
-                'outer: loop {
-                    let audio_rx_for_stream = Arc::clone(&audio_rx);
-
-                    // FLACエンコーダーを作成（圧縮レベル8 = 最高圧縮）
-                    let mut flac_encoder = FlacEncoder::new(sample_rate, 8);
-
-                    let input_stream = stream! {
-                        let mut pcm_buffer: Vec<i16> = Vec::new();
-                        // サンプルレートに応じた適切なバッファサイズを計算
-                        let max_samples = (sample_rate as f64 * 0.2) as usize; // 0.2秒分
-                        let initial_min_samples = (sample_rate as f64 * 0.15) as usize; // 0.15秒分（再接続直後）
-                        let mut chunk_count = 0; // 送信チャンク数をカウント
-
-                        log::info!("チャンネル {}: バッファサイズ設定 - 初期: {}サンプル({:.2}秒), 通常: {}サンプル({:.2}秒) @ {}Hz",
-                                   channel_id, initial_min_samples, initial_min_samples as f64 / sample_rate as f64,
-                                   max_samples, max_samples as f64 / sample_rate as f64, sample_rate);
-
-                        loop {
-                            let mut rx = audio_rx_for_stream.lock().await;
-
-                            // データを待機（最大100ms）- AWS Transcribeへの迅速なデータ送信を優先
-                            match timeout(Duration::from_millis(100), rx.recv()).await {
-                                Ok(Some(samples)) => {
-                                    pcm_buffer.extend_from_slice(&samples);
-
-                                    // 適応的バッファリング戦略
-                                    // - 最初の5チャンク: より小さいバッファで高速送信（AWS 20秒タイムアウト対策）
-                                    // - それ以降: 通常バッファサイズで安定送信
-                                    let min_samples = if chunk_count < 5 {
-                                        initial_min_samples
-                                    } else {
-                                        max_samples
-                                    };
+        let ctx = StreamContext {
+            client,
+            language_code,
+            language_options,
+            sample_rate: self.config.sample_rate,
+            channel_id,
+            start_time: self.start_time,
+            result_tx,
+            sequence: Arc::clone(&self.sequence),
+            flac_stats: Arc::clone(&self.flac_stats),
+            flac: self.flac.clone(),
+            chunk_sizing: ChunkSizing::new(&self.config, self.config.sample_rate),
+            client_invalidated: Arc::clone(&self.client.invalidated),
+        };
 
-                                    // バッファが一定サイズに達したらFLACエンコードして送信
-                                    if pcm_buffer.len() >= min_samples {
-                                        let to_encode: Vec<i16> = pcm_buffer.drain(..min_samples.min(pcm_buffer.len())).collect();
-                                        chunk_count += 1;
-
-                                        match flac_encoder.encode(&to_encode) {
-                                            Ok(flac_data) => {
-                                                let blob = Blob::new(flac_data);
-                                                if chunk_count % 10 == 0 {
-                                                    log::info!(
-                                                        "チャンネル {}: AWS送信 チャンク#{} - {}サンプル → {}バイト",
-                                                        channel_id,
-                                                        chunk_count,
-                                                        to_encode.len(),
-                                                        blob.as_ref().len()
-                                                    );
-                                                }
-                                                yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
-                                            }
-                                            Err(e) => {
-                                                log::error!("FLACエンコードエラー: {:?}", e);
-                                            }
-                                        }
-                                    }
-                                }
-                                Ok(None) => {
-                                    log::debug!("AwsTranscribeBackend: チャンネルクローズ");
-                                    // チャンネルがクローズされた場合、残りのバッファを送信
-                                    if !pcm_buffer.is_empty() {
-                                        match flac_encoder.encode(&pcm_buffer) {
-                                            Ok(flac_data) => {
-                                                let blob = Blob::new(flac_data);
-                                                log::debug!("Amazon Transcribe 最終送信: {} サンプル → {} バイト", pcm_buffer.len(), blob.as_ref().len());
-                                                yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
-                                            }
-                                            Err(e) => {
-                                                log::error!("FLACエンコードエラー: {:?}", e);
-                                            }
-                                        }
-                                    }
-                                    break;
-                                }
-                                Err(_) => {
-                                    log::debug!("AwsTranscribeBackend: タイムアウト（データなし）");
-                                    // タイムアウトした場合、バッファに残っているデータを送信
-                                    if !pcm_buffer.is_empty() {
-                                        let to_encode = pcm_buffer.split_off(0);
-                                        match flac_encoder.encode(&to_encode) {
-                                            Ok(flac_data) => {
-                                                let blob = Blob::new(flac_data);
-                                                log::debug!("Amazon Transcribe タイムアウト送信: {} サンプル → {} バイト", to_encode.len(), blob.as_ref().len());
-                                                yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
-                                            }
-                                            Err(e) => {
-                                                log::error!("FLACエンコードエラー: {:?}", e);
-                                            }
-                                        }
-                                    }
-                                }
+        // 結果の timestamp_seconds を他のバックエンドと同じく基準時刻からの経過秒数にする
+        let timing = StreamTiming::starting_at(
+            SystemTime::now()
+                .duration_since(self.start_time)
+                .unwrap_or_default()
+                .as_secs_f64(),
+        );
+        let handle = if self.config.proactive_reconnect_secs > 0 {
+            tokio::spawn(run_with_proactive_reconnect(
+                ctx,
+                audio_rx,
+                timing,
+                std::time::Duration::from_secs(self.config.proactive_reconnect_secs),
+            ))
+        } else {
+            tokio::spawn(run_stream(ctx, audio_rx, timing))
+        };
+
+        // タスクハンドルを保存（リソースリーク防止）
+        self.task_handle = Some(handle);
+
+        Ok((audio_tx, result_rx))
+    }
+
+    fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    fn reset_start_time(&mut self) {
+        self.start_time = SystemTime::now();
+        self.reconnection_count += 1;
+        log::info!(
+            "チャンネル {}: タイムスタンプの基準時刻をリセット (再接続 {}回目)",
+            self.channel_id,
+            self.reconnection_count
+        );
+    }
+
+    fn channel_id(&self) -> usize {
+        self.channel_id
+    }
+}
+
+/// 経過時間を監視し、`reconnect_after` ごとに新しいストリームへ切り替えながら音声を中継する
+///
+/// 切り替え時は直前の音声を新ストリームにも送信し、旧ストリームは入力を閉じて
+/// 残りの結果を出し切らせる。呼び出し側の送信チャンネルはそのまま使い続けられる。
+async fn run_with_proactive_reconnect(
+    ctx: StreamContext,
+    mut audio_rx: mpsc::Receiver<TranscribeAudio>,
+    mut timing: StreamTiming,
+    reconnect_after: std::time::Duration,
+) {
+    let channel_id = ctx.channel_id;
+    let overlap_capacity =
+        (ctx.sample_rate as u64 * PROACTIVE_RECONNECT_OVERLAP_MS / 1000) as usize;
+    let mut overlap: VecDeque<i16> = VecDeque::with_capacity(overlap_capacity);
+
+    let (mut stream_tx, stream_rx) = mpsc::channel::<TranscribeAudio>(4096);
+    tokio::spawn(run_stream(ctx.clone(), stream_rx, timing));
+    let mut opened_at = std::time::Instant::now();
+    // 現在のストリームに送った音声のフレーム数（オーバーラップ分を含む）
+    let mut sent_frames = 0u64;
+    let mut generation = 0u32;
+
+    log::info!(
+        "チャンネル {}: 予防的再接続を有効化（{}秒ごと, オーバーラップ{}ms）",
+        channel_id,
+        reconnect_after.as_secs(),
+        PROACTIVE_RECONNECT_OVERLAP_MS
+    );
+
+    // モノラル化は各ストリーム（`run_stream`）で行うため、ここでは受け取った音声をそのまま中継する
+    let mut overlap_channels = 1u16;
+    while let Some(audio) = audio_rx.recv().await {
+        if opened_at.elapsed() >= reconnect_after {
+            generation += 1;
+            let overlap_samples: Vec<i16> = overlap.iter().copied().collect();
+            let overlap_secs =
+                overlap_samples.len() as f64 / overlap_channels.max(1) as f64 / ctx.sample_rate as f64;
+            timing = timing.next(sent_frames as f64 / ctx.sample_rate as f64, overlap_secs);
+            sent_frames = 0;
+
+            log::info!(
+                "チャンネル {}: ストリーム最大継続時間の前に予防的再接続 (世代{}, オーバーラップ{:.2}秒)",
+                channel_id,
+                generation,
+                overlap_secs
+            );
+
+            let (new_tx, new_rx) = mpsc::channel::<TranscribeAudio>(4096);
+            if !overlap_samples.is_empty() {
+                let frames = (overlap_samples.len() / overlap_channels.max(1) as usize) as u64;
+                if new_tx.try_send(TranscribeAudio::new(overlap_samples, overlap_channels)).is_ok() {
+                    sent_frames = frames;
+                }
+            }
+            tokio::spawn(run_stream(ctx.clone(), new_rx, timing));
+
+            // 旧ストリームの送信側をドロップすると、残りのバッファを送信して結果を出し切ってから終了する
+            stream_tx = new_tx;
+            opened_at = std::time::Instant::now();
+        }
+
+        // 直近の音声をオーバーラップ用に保持（チャンネル数が変わったら保持分を捨てる）
+        if audio.channels != overlap_channels {
+            overlap.clear();
+            overlap_channels = audio.channels;
+        }
+        overlap.extend(audio.samples.iter().copied());
+        let capacity = overlap_capacity * overlap_channels.max(1) as usize;
+        if overlap.len() > capacity {
+            let excess = overlap.len() - capacity;
+            overlap.drain(..excess);
+        }
+
+        let frames = (audio.samples.len() / audio.channels.max(1) as usize) as u64;
+        if stream_tx.send(audio).await.is_err() {
+            log::warn!(
+                "チャンネル {}: Amazon Transcribeストリームが終了したため中継を停止",
+                channel_id
+            );
+            break;
+        }
+        sent_frames += frames;
+    }
+}
+
+/// 1本の Amazon Transcribe ストリームを開始し、入力が閉じられるかストリームが終了するまで処理する
+async fn run_stream(ctx: StreamContext, mut audio_rx: mpsc::Receiver<TranscribeAudio>, timing: StreamTiming) {
+    use tokio::time::{Duration, timeout};
+
+    let StreamContext {
+        client,
+        language_code,
+        language_options,
+        sample_rate,
+        channel_id,
+        start_time,
+        result_tx,
+        sequence,
+        flac_stats,
+        flac,
+        chunk_sizing,
+        client_invalidated,
+    } = ctx;
+
+    let mut encoder = ChunkEncoder::new(sample_rate, &flac);
+    let media_encoding = encoder.media_encoding();
+    let stream_flac_stats = Arc::clone(&flac_stats);
+
+    let input_stream = stream! {
+        let mut pcm_buffer: Vec<i16> = Vec::new();
+        let mut chunk_count = 0; // 送信チャンク数をカウント
+        let mut normalizer = MonoNormalizer::new(channel_id);
+
+        log::info!("チャンネル {}: バッファサイズ設定 - 初期: {}サンプル({:.2}秒)×{}, 通常: {}サンプル({:.2}秒) @ {}Hz",
+                   channel_id, chunk_sizing.initial_samples, chunk_sizing.initial_samples as f64 / sample_rate as f64,
+                   chunk_sizing.fast_start_chunks,
+                   chunk_sizing.chunk_samples, chunk_sizing.chunk_samples as f64 / sample_rate as f64, sample_rate);
+
+        loop {
+            // データを待機（最大100ms）- AWS Transcribeへの迅速なデータ送信を優先
+            match timeout(Duration::from_millis(100), audio_rx.recv()).await {
+                Ok(Some(audio)) => {
+                    pcm_buffer.extend_from_slice(&normalizer.normalize(audio));
+
+                    // 適応的バッファリング戦略
+                    // - 最初の fast_start_chunks 個: より小さいバッファで高速送信（AWS 20秒タイムアウト対策）
+                    // - それ以降: 通常バッファサイズで安定送信
+                    let min_samples = chunk_sizing.samples_for(chunk_count);
+
+                    // バッファが一定サイズに達したらエンコードして送信
+                    if pcm_buffer.len() >= min_samples {
+                        let to_encode: Vec<i16> = pcm_buffer.drain(..min_samples.min(pcm_buffer.len())).collect();
+                        chunk_count += 1;
+
+                        if let Some(encoded) = encoder.encode(channel_id, &to_encode, &stream_flac_stats) {
+                            let blob = Blob::new(encoded);
+                            if chunk_count % 10 == 0 {
+                                log::info!(
+                                    "チャンネル {}: AWS送信 チャンク#{} - {}サンプル → {}バイト",
+                                    channel_id,
+                                    chunk_count,
+                                    to_encode.len(),
+                                    blob.as_ref().len()
+                                );
                             }
+                            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
                         }
-                    };
-
-                    log::info!("チャンネル {}: Amazon Transcribe ストリーム開始...", channel_id);
-                    let mut resp = match client
-                        .start_stream_transcription()
-                        .language_code(language_code.clone())
-                        .media_sample_rate_hertz(sample_rate as i32)
-                        .media_encoding(MediaEncoding::Flac)
-                        .audio_stream(input_stream.into())
-                        .send()
-                        .await
-                    {
-                        Ok(r) => {
-                            log::info!(
-                                "チャンネル {}: Amazon Transcribe ストリーム開始成功 [PID={}, netstatで接続を確認してください]",
-                                channel_id,
-                                std::process::id()
-                            );
-                            r
+                    }
+                }
+                Ok(None) => {
+                    log::debug!("AwsTranscribeBackend: チャンネルクローズ");
+                    // チャンネルがクローズされた場合、残りのバッファを送信
+                    if !pcm_buffer.is_empty() {
+                        if let Some(encoded) = encoder.encode(channel_id, &pcm_buffer, &stream_flac_stats) {
+                            let blob = Blob::new(encoded);
+                            log::debug!("Amazon Transcribe 最終送信: {} サンプル → {} バイト", pcm_buffer.len(), blob.as_ref().len());
+                            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
                         }
-                        Err(e) => {
-                            log::error!("チャンネル {}: Amazon Transcribe API開始失敗: {:?}", channel_id, e);
-                            // エラーの詳細情報をログ出力
-                            if let Some(service_err) = e.as_service_error() {
-                                log::error!("チャンネル {}: サービスエラー詳細: {:?}", channel_id, service_err);
-                            }
-                            return;
+                    }
+                    break;
+                }
+                Err(_) => {
+                    log::debug!("AwsTranscribeBackend: タイムアウト（データなし）");
+                    // タイムアウトした場合、バッファに残っているデータを送信
+                    if !pcm_buffer.is_empty() {
+                        let to_encode = pcm_buffer.split_off(0);
+                        if let Some(encoded) = encoder.encode(channel_id, &to_encode, &stream_flac_stats) {
+                            let blob = Blob::new(encoded);
+                            log::debug!("Amazon Transcribe タイムアウト送信: {} サンプル → {} バイト", to_encode.len(), blob.as_ref().len());
+                            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
                         }
-                    };
-
-                    let mut last_recv_time = SystemTime::now();
-
-                    loop {
-                        // 【切り分けポイント1】recv()呼び出し直前のタイムスタンプ
-                        let before_recv = SystemTime::now();
-                        let before_recv_elapsed = before_recv.duration_since(start_time).unwrap().as_secs_f64();
-                        let interval = before_recv.duration_since(last_recv_time).unwrap().as_secs_f64();
-
-                        match resp.transcript_result_stream.recv().await {
-                            Ok(Some(event)) => {
-                                // 【切り分けポイント2】recv()完了直後のタイムスタンプ
-                                let after_recv = SystemTime::now();
-                                let after_recv_elapsed = after_recv.duration_since(start_time).unwrap().as_secs_f64();
-                                let recv_block_time = after_recv_elapsed - before_recv_elapsed;
-
-                                match event {
-                                aws_sdk_transcribestreaming::types::TranscriptResultStream::TranscriptEvent(transcript_event) => {
-                                if let Some(transcript) = transcript_event.transcript {
-                                    for result in transcript.results.unwrap_or_default() {
-                                        for alt in result.alternatives.unwrap_or_default() {
-                                            let text = alt.transcript.unwrap_or_default();
-                                            let is_partial = result.is_partial;
-
-                                            // stabilityを計算（stableフラグから推測）
-                                            let stability = if is_partial {
-                                                alt.items.as_ref().map(|items| {
-                                                    let total = items.len();
-                                                    if total == 0 {
-                                                        return Stability::Low;
-                                                    }
-
-                                                    // stableなitemの割合を計算
-                                                    let stable_count = items.iter()
-                                                        .filter(|item| item.stable.unwrap_or(false))
-                                                        .count();
-                                                    let stable_ratio = stable_count as f64 / total as f64;
-
-                                                    // 安定性を判定
-                                                    if stable_ratio >= 0.8 {
-                                                        Stability::High
-                                                    } else if stable_ratio >= 0.4 {
-                                                        Stability::Medium
-                                                    } else {
-                                                        Stability::Low
-                                                    }
-                                                })
-                                            } else {
-                                                None
-                                            };
-
-                                            // 【切り分けポイント2】AWS Transcribeの音声タイムスタンプを取得
-                                            let audio_start_time = alt.items.as_ref()
-                                                .and_then(|items| items.first())
-                                                .map(|item| item.start_time);
-                                            let audio_end_time = alt.items.as_ref()
-                                                .and_then(|items| items.last())
-                                                .map(|item| item.end_time);
-
-                                            let transcript = if let Some(start_secs) = audio_start_time {
-                                                // AWS Transcribe の実際の音声タイムスタンプを使用
-                                                if !is_partial && !text.is_empty() {
-                                                    // 【切り分けポイント3】AWS応答遅延を計算
-                                                    let aws_latency = if let Some(end_secs) = audio_end_time {
-                                                        after_recv_elapsed - end_secs
-                                                    } else {
-                                                        after_recv_elapsed - start_secs
-                                                    };
-
-                                                    // 【切り分けポイント4】recv()ループの間隔をログ出力
-                                                    if interval >= 1.0 {
-                                                        log::warn!(
-                                                            "チャンネル {}: recv()インターバルが長い！ interval={:.2}秒",
-                                                            channel_id,
-                                                            interval
-                                                        );
-                                                    }
-
-                                                    log::info!(
-                                                        "チャンネル {}: AWS応答受信 - interval={:.2}秒, before_recv={:.2}秒, after_recv={:.2}秒, recv_block={:.2}秒, audio_start={:.2}秒, audio_end={:.2}秒, AWS遅延={:.2}秒, text='{}'",
-                                                        channel_id,
-                                                        interval,
-                                                        before_recv_elapsed,
-                                                        after_recv_elapsed,
-                                                        recv_block_time,
-                                                        start_secs,
-                                                        audio_end_time.unwrap_or(start_secs),
-                                                        aws_latency,
-                                                        text.chars().take(30).collect::<String>()
-                                                    );
-                                                }
-                                                TranscriptResult::new_with_audio_time(
-                                                    channel_id, text, is_partial, stability, start_secs,
-                                                )
-                                            } else {
-                                                // start_time が取得できない場合は従来の方法
-                                                if !is_partial && !text.is_empty() {
-                                                    log::info!(
-                                                        "チャンネル {}: AWS応答受信 - before_recv={:.2}秒, after_recv={:.2}秒, recv_block={:.2}秒 (fallback), text='{}'",
-                                                        channel_id,
-                                                        before_recv_elapsed,
-                                                        after_recv_elapsed,
-                                                        recv_block_time,
-                                                        text.chars().take(30).collect::<String>()
-                                                    );
-                                                }
-                                                TranscriptResult::new(
-                                                    channel_id, text, is_partial, stability, start_time,
-                                                )
-                                            };
-                                            if let Err(e) = result_tx.try_send(transcript) {
-                                                log::warn!("Amazon Transcribe 結果送信失敗: {}", e);
-                                            }
-                                        }
+                    }
+                }
+            }
+        }
+    };
+
+    log::info!("チャンネル {}: Amazon Transcribe ストリーム開始...", channel_id);
+    let request = client
+        .start_stream_transcription()
+        .media_sample_rate_hertz(sample_rate as i32)
+        .media_encoding(media_encoding)
+        .audio_stream(input_stream.into());
+    // 自動言語切り替え時は候補の中から発話ごとに言語を識別させ、直前の発話の言語を優先言語にする
+    let request = match language_options {
+        Some(language_options) => request
+            .identify_multiple_languages(true)
+            .language_options(language_options)
+            .preferred_language(language_code),
+        None => request.language_code(language_code),
+    };
+    let mut resp = match request.send().await {
+        Ok(r) => {
+            log::info!(
+                "チャンネル {}: Amazon Transcribe ストリーム開始成功 [PID={}, netstatで接続を確認してください]",
+                channel_id,
+                std::process::id()
+            );
+            r
+        }
+        Err(e) => {
+            log::error!("チャンネル {}: Amazon Transcribe API開始失敗: {:?}", channel_id, e);
+            // エラーの詳細情報をログ出力
+            if let Some(service_err) = e.as_service_error() {
+                log::error!("チャンネル {}: サービスエラー詳細: {:?}", channel_id, service_err);
+            }
+            if requires_client_rebuild(&e) {
+                client_invalidated.store(true, Ordering::SeqCst);
+            }
+            return;
+        }
+    };
+
+    let mut last_recv_time = SystemTime::now();
+
+    loop {
+        // 【切り分けポイント1】recv()呼び出し直前のタイムスタンプ
+        let before_recv = SystemTime::now();
+        let before_recv_elapsed = before_recv.duration_since(start_time).unwrap().as_secs_f64();
+        let interval = before_recv.duration_since(last_recv_time).unwrap().as_secs_f64();
+
+        match resp.transcript_result_stream.recv().await {
+            Ok(Some(event)) => {
+                // 【切り分けポイント2】recv()完了直後のタイムスタンプ
+                let after_recv = SystemTime::now();
+                let after_recv_elapsed = after_recv.duration_since(start_time).unwrap().as_secs_f64();
+                let recv_block_time = after_recv_elapsed - before_recv_elapsed;
+
+                match event {
+                aws_sdk_transcribestreaming::types::TranscriptResultStream::TranscriptEvent(transcript_event) => {
+                if let Some(transcript) = transcript_event.transcript {
+                    for result in transcript.results.unwrap_or_default() {
+                        let detected_language = result.language_code.as_ref().map(|code| code.as_str().to_string());
+                        for alt in result.alternatives.unwrap_or_default() {
+                            let text = alt.transcript.unwrap_or_default();
+                            let is_partial = result.is_partial;
+
+                            // stabilityを計算（stableフラグから推測）
+                            let stability = if is_partial {
+                                alt.items.as_ref().map(|items| {
+                                    let total = items.len();
+                                    if total == 0 {
+                                        return Stability::Low;
                                     }
-                                }
-                                },
-                                other => {
-                                    log::warn!("チャンネル {}: Amazon Transcribe 未処理イベント: {:?}", channel_id, other);
+
+                                    // stableなitemの割合を計算
+                                    let stable_count = items.iter()
+                                        .filter(|item| item.stable.unwrap_or(false))
+                                        .count();
+                                    let stable_ratio = stable_count as f64 / total as f64;
+
+                                    // 安定性を判定
+                                    if stable_ratio >= 0.8 {
+                                        Stability::High
+                                    } else if stable_ratio >= 0.4 {
+                                        Stability::Medium
+                                    } else {
+                                        Stability::Low
+                                    }
+                                })
+                            } else {
+                                None
+                            };
+
+                            // 確信度は確定結果にのみ付く
+                            let confidence = if is_partial {
+                                None
+                            } else {
+                                alt.items.as_deref().and_then(average_confidence)
+                            };
+
+                            // 【切り分けポイント2】AWS Transcribeの音声タイムスタンプを取得
+                            let audio_start_time = alt.items.as_ref()
+                                .and_then(|items| items.first())
+                                .map(|item| item.start_time);
+                            let audio_end_time = alt.items.as_ref()
+                                .and_then(|items| items.last())
+                                .map(|item| item.end_time);
+
+                            // 予防的再接続のオーバーラップ区間で始まる結果は旧ストリームと重複するため破棄
+                            if let Some(start_secs) = audio_start_time {
+                                if timing.is_in_overlap(start_secs) {
+                                    log::debug!(
+                                        "チャンネル {}: オーバーラップ区間の結果を破棄 (start={:.2}秒): '{}'",
+                                        channel_id,
+                                        start_secs,
+                                        text.chars().take(30).collect::<String>()
+                                    );
+                                    continue;
                                 }
                             }
-                                // recv()完了後、次のループのためにタイムスタンプを更新
-                                last_recv_time = after_recv;
-                            },
-                            Ok(None) => {
-                                log::warn!("チャンネル {}: Amazon Transcribeストリームが予期せず終了（Ok(None)）", channel_id);
-                                break 'outer;
-                            },
-                            Err(e) => {
-                                log::error!("チャンネル {}: Amazon Transcribeストリーム受信エラー: {:?}", channel_id, e);
-                                // エラーの詳細をログ出力
-                                log::error!("チャンネル {}: エラー種別: {}", channel_id, std::any::type_name_of_val(&e));
-                                break 'outer;
+
+                            let transcript = if let Some(start_secs) = audio_start_time {
+                                // AWS Transcribe の実際の音声タイムスタンプを使用
+                                if !is_partial && !text.is_empty() {
+                                    // 【切り分けポイント3】AWS応答遅延を計算
+                                    let aws_latency = if let Some(end_secs) = audio_end_time {
+                                        after_recv_elapsed - end_secs
+                                    } else {
+                                        after_recv_elapsed - start_secs
+                                    };
+
+                                    // 【切り分けポイント4】recv()ループの間隔をログ出力
+                                    if interval >= 1.0 {
+                                        log::warn!(
+                                            "チャンネル {}: recv()インターバルが長い！ interval={:.2}秒",
+                                            channel_id,
+                                            interval
+                                        );
+                                    }
+
+                                    log::info!(
+                                        "チャンネル {}: AWS応答受信 - interval={:.2}秒, before_recv={:.2}秒, after_recv={:.2}秒, recv_block={:.2}秒, audio_start={:.2}秒, audio_end={:.2}秒, AWS遅延={:.2}秒, text='{}'",
+                                        channel_id,
+                                        interval,
+                                        before_recv_elapsed,
+                                        after_recv_elapsed,
+                                        recv_block_time,
+                                        start_secs,
+                                        audio_end_time.unwrap_or(start_secs),
+                                        aws_latency,
+                                        text.chars().take(30).collect::<String>()
+                                    );
+                                }
+                                TranscriptResult::new_with_audio_time(
+                                    channel_id,
+                                    text,
+                                    is_partial,
+                                    stability,
+                                    timing.offset_secs + start_secs,
+                                    audio_end_time.map(|end_secs| timing.offset_secs + end_secs),
+                                )
+                            } else {
+                                // start_time が取得できない場合は従来の方法
+                                if !is_partial && !text.is_empty() {
+                                    log::info!(
+                                        "チャンネル {}: AWS応答受信 - before_recv={:.2}秒, after_recv={:.2}秒, recv_block={:.2}秒 (fallback), text='{}'",
+                                        channel_id,
+                                        before_recv_elapsed,
+                                        after_recv_elapsed,
+                                        recv_block_time,
+                                        text.chars().take(30).collect::<String>()
+                                    );
+                                }
+                                TranscriptResult::new(
+                                    channel_id, text, is_partial, stability, start_time,
+                                )
+                            };
+                            let transcript = transcript
+                                .with_sequence(sequence.fetch_add(1, Ordering::SeqCst))
+                                .with_confidence(confidence)
+                                .with_detected_language(detected_language.clone());
+                            if let Err(e) = result_tx.try_send(transcript) {
+                                log::warn!("Amazon Transcribe 結果送信失敗: {}", e);
                             }
                         }
                     }
                 }
+                },
+                other => {
+                    log::warn!("チャンネル {}: Amazon Transcribe 未処理イベント: {:?}", channel_id, other);
+                }
             }
-        });
-
-        // タスクハンドルを保存（リソースリーク防止）
-        self.task_handle = Some(handle);
-
-        Ok((audio_tx, result_rx))
+                // recv()完了後、次のループのためにタイムスタンプを更新
+                last_recv_time = after_recv;
+            },
+            Ok(None) => {
+                log::warn!("チャンネル {}: Amazon Transcribeストリームが予期せず終了（Ok(None)）", channel_id);
+                break;
+            },
+            Err(e) => {
+                log::error!("チャンネル {}: Amazon Transcribeストリーム受信エラー: {:?}", channel_id, e);
+                // エラーの詳細をログ出力
+                log::error!("チャンネル {}: エラー種別: {}", channel_id, std::any::type_name_of_val(&e));
+                break;
+            }
+        }
     }
 
-    fn channel_id(&self) -> usize {
-        self.channel_id
+    // 恒常的なエンコード失敗に気付けるよう、失敗があればストリームごとに累計を出す
+    if flac_stats.failed_chunks() > 0 {
+        log::warn!(
+            "チャンネル {}: FLACエンコード失敗 累計{}チャンク ({:.1}秒分をスキップ, 失敗率 {:.1}%, エンコーダ再作成 {}回)",
+            channel_id,
+            flac_stats.failed_chunks(),
+            flac_stats.skipped_samples() as f64 / sample_rate.max(1) as f64,
+            flac_stats.failure_rate() * 100.0,
+            flac_stats.rebuilds()
+        );
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::TranscribeBackendType;
+    use crate::config::{DisconnectBufferOverflow, TranscribeBackendType};
+
+    #[test]
+    fn test_stream_quota_check() {
+        let mut config = TranscribeConfig::default();
+        let check = StreamQuotaCheck::new(DEFAULT_STREAM_QUOTA, &config);
+        assert_eq!(check.peak_streams(), DEFAULT_STREAM_QUOTA);
+        assert_eq!(check.warning(), None);
+
+        // 既定のクォータを超えるチャンネル数
+        let check = StreamQuotaCheck::new(30, &config);
+        assert!(check.warning().unwrap().contains("max_concurrent_streams = 25"));
+
+        // 上限を設定すればクォータ内に収まる
+        config.max_concurrent_streams = Some(20);
+        assert_eq!(StreamQuotaCheck::new(30, &config).peak_streams(), 20);
+        assert_eq!(StreamQuotaCheck::new(30, &config).warning(), None);
+        // チャンネル数より大きい上限はチャンネル数で頭打ち
+        assert_eq!(StreamQuotaCheck::new(4, &config).peak_streams(), 4);
+
+        // 上限自体がクォータを超えている
+        config.max_concurrent_streams = Some(40);
+        assert!(StreamQuotaCheck::new(50, &config).warning().unwrap().contains("25 以下"));
+
+        // 引き上げ済みのクォータ
+        config.stream_quota = Some(100);
+        assert_eq!(StreamQuotaCheck::new(50, &config).warning(), None);
+    }
 
     #[tokio::test]
     async fn test_aws_transcribe_backend_creation() {
@@ -379,10 +898,331 @@ mod tests {
             timeout_seconds: 10,
             connect_on_startup: false,
             send_buffered_on_reconnect: true,
+            proactive_reconnect_secs: 0,
+            partial_finalize_timeout_ms: 0,
+            initial_chunk_ms: 150,
+            chunk_ms: 200,
+            fast_start_chunks: 5,
+            predictive_connect: false,
+            endpoint_url: None,
+            allowed_languages: Vec::new(),
+            max_concurrent_streams: None,
+            stream_quota: None,
+            max_disconnect_buffer_secs: 30,
+            disconnect_buffer_overflow: DisconnectBufferOverflow::DropOldest,
+        };
+
+        let backend = AwsTranscribeBackend::new(config, 0).await.unwrap();
+        assert_eq!(backend.channel_id(), 0);
+        assert!(backend.start_time.elapsed().unwrap() < std::time::Duration::from_secs(1));
+
+        // 基準時刻を指定した場合はそのまま使う
+        let start_time = SystemTime::now() - std::time::Duration::from_secs(30);
+        let backend = AwsTranscribeBackend::new_with_start_time(TranscribeConfig::default(), 1, start_time)
+            .await
+            .unwrap();
+        assert_eq!(backend.start_time, start_time);
+    }
+
+    /// 設定の読み込み（ネットワークアクセス）なしで作るテスト用クライアント
+    fn test_client() -> AwsTranscribeClient {
+        let config = aws_sdk_transcribestreaming::Config::builder()
+            .behavior_version(aws_config::BehaviorVersion::latest())
+            .region(aws_sdk_transcribestreaming::config::Region::new("ap-northeast-1"))
+            .build();
+        AwsTranscribeClient::from_conf(config)
+    }
+
+    #[test]
+    fn test_client_cache_reuses_until_invalidated() {
+        let mut cache = ClientCache::default();
+        assert!(cache.cached().is_none());
+
+        cache.store(test_client());
+        assert!(cache.cached().is_some());
+        assert!(cache.cached().is_some());
+        assert_eq!(cache.builds, 1);
+
+        // ストリームのタスクから無効化されたら次の接続で作り直す
+        let invalidated = Arc::clone(&cache.invalidated);
+        invalidated.store(true, Ordering::SeqCst);
+        assert!(cache.cached().is_none());
+        assert!(!invalidated.load(Ordering::SeqCst));
+
+        cache.store(test_client());
+        assert!(cache.cached().is_some());
+        assert_eq!(cache.builds, 2);
+    }
+
+    #[test]
+    fn test_chunk_encoder_modes() {
+        let samples: Vec<i16> = (0..1600).map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16).collect();
+        let stats = FlacEncodeStats::default();
+
+        // FLAC: デコードすると元のPCMに戻る
+        let mut flac = ChunkEncoder::new(16000, &FlacConfig::default());
+        assert_eq!(flac.media_encoding(), MediaEncoding::Flac);
+        let encoded = flac.encode(0, &samples, &stats).unwrap();
+        assert!(encoded.starts_with(b"fLaC"));
+        let mut reader = claxon::FlacReader::new(std::io::Cursor::new(encoded)).unwrap();
+        let decoded: Vec<i16> = reader.samples().map(|s| s.unwrap() as i16).collect();
+        assert_eq!(decoded, samples);
+        assert_eq!(stats.encoded_chunks(), 1);
+
+        // PCM: リトルエンディアンの16ビットをそのまま送る
+        let disabled = FlacConfig {
+            enabled: false,
+            ..FlacConfig::default()
+        };
+        let mut pcm = ChunkEncoder::new(16000, &disabled);
+        assert_eq!(pcm.media_encoding(), MediaEncoding::Pcm);
+        let encoded = pcm.encode(0, &samples, &stats).unwrap();
+        assert_eq!(encoded.len(), samples.len() * 2);
+        let decoded: Vec<i16> = encoded
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        assert_eq!(decoded, samples);
+        // FLACの集計には含めない
+        assert_eq!(stats.encoded_chunks(), 1);
+    }
+
+    #[test]
+    fn test_chunk_encoder_uses_compression_level() {
+        // 音声に近い（正弦波の重ね合わせと小さな揺らぎ）0.2秒のチャンク
+        let samples: Vec<i16> = (0..3200)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                let tone = (t * 440.0 * std::f32::consts::TAU).sin() * 6000.0
+                    + (t * 1210.0 * std::f32::consts::TAU).sin() * 2500.0;
+                (tone + ((i * 7919) % 61) as f32 - 30.0) as i16
+            })
+            .collect();
+        let stats = FlacEncodeStats::default();
+        let encoded_len = |compression_level: u32| {
+            let flac = FlacConfig {
+                compression_level,
+                enabled: true,
+            };
+            let mut encoder = ChunkEncoder::new(16000, &flac);
+            match &encoder {
+                ChunkEncoder::Flac(flac_encoder) => assert_eq!(flac_encoder.compression_level(), compression_level),
+                ChunkEncoder::Pcm => panic!("FLACで送る設定"),
+            }
+            encoder.encode(0, &samples, &stats).unwrap().len()
+        };
+
+        let fastest = encoded_len(0);
+        let best = encoded_len(8);
+        assert!(best < fastest, "level 8: {} bytes, level 0: {} bytes", best, fastest);
+        assert!(fastest < samples.len() * 2);
+    }
+
+    #[test]
+    fn test_requires_client_rebuild() {
+        let construction: SdkError<(), ()> = SdkError::construction_failure("認証情報が見つかりません");
+        assert!(requires_client_rebuild(&construction));
+
+        let timeout: SdkError<(), ()> = SdkError::timeout_error("タイムアウト");
+        assert!(!requires_client_rebuild(&timeout));
+    }
+
+    #[test]
+    fn test_client_config_region() {
+        let config = TranscribeConfig {
+            region: "us-west-2".to_string(),
+            ..TranscribeConfig::default()
+        };
+        let client_config = client_config(aws_sdk_transcribestreaming::Config::builder(), &config);
+        assert_eq!(client_config.region().map(|r| r.as_ref()), Some("us-west-2"));
+    }
+
+    #[tokio::test]
+    #[ignore] // AWS の設定読み込み（環境によってはメタデータサービスへの問い合わせ）を伴うため、通常はスキップ
+    async fn test_load_sdk_config_uses_configured_region() {
+        let config = TranscribeConfig {
+            region: "eu-west-1".to_string(),
+            ..TranscribeConfig::default()
         };
+        let sdk_config = load_sdk_config(&config).await;
+        assert_eq!(sdk_config.region().map(|r| r.as_ref()), Some("eu-west-1"));
+
+        // SDK の共通設定から作るクライアントにも反映される
+        let client = AwsTranscribeClient::new(&sdk_config);
+        assert_eq!(client.config().region().map(|r| r.as_ref()), Some("eu-west-1"));
+        let client_config = client_config((&sdk_config).into(), &config);
+        assert_eq!(client_config.region().map(|r| r.as_ref()), Some("eu-west-1"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_uses_endpoint_override() {
+        use aws_sdk_transcribestreaming::config::Credentials;
+        use tokio::time::{Duration, timeout};
+
+        // モックサーバ: 接続を受け付けたら何も返さずに閉じる
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = TranscribeConfig {
+            endpoint_url: Some(format!("http://{}", listener.local_addr().unwrap())),
+            ..TranscribeConfig::default()
+        };
+        let (accepted_tx, mut accepted_rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let _ = accepted_tx.send(()).await;
+                drop(socket);
+            }
+        });
+
+        let builder = aws_sdk_transcribestreaming::Config::builder()
+            .behavior_version(aws_config::BehaviorVersion::latest())
+            .credentials_provider(Credentials::new("AKIDTEST", "SECRET", None, None, "test"));
+        let (result_tx, _result_rx) = mpsc::channel(8);
+        let client_invalidated = Arc::new(AtomicBool::new(false));
+        let ctx = StreamContext {
+            client: AwsTranscribeClient::from_conf(client_config(builder, &config)),
+            language_code: LanguageCode::JaJp,
+            language_options: None,
+            sample_rate: config.sample_rate,
+            channel_id: 0,
+            start_time: SystemTime::now(),
+            result_tx,
+            sequence: Arc::new(AtomicU64::new(0)),
+            flac_stats: Arc::new(FlacEncodeStats::default()),
+            flac: FlacConfig::default(),
+            chunk_sizing: ChunkSizing::new(&config, config.sample_rate),
+            client_invalidated: Arc::clone(&client_invalidated),
+        };
+        let (audio_tx, audio_rx) = mpsc::channel(8);
+        let stream = tokio::spawn(run_stream(ctx, audio_rx, StreamTiming::default()));
+
+        // 通常の AWS エンドポイントではなくモックサーバに接続する
+        timeout(Duration::from_secs(5), accepted_rx.recv())
+            .await
+            .expect("エンドポイントに接続されない")
+            .unwrap();
+        drop(audio_tx);
+        timeout(Duration::from_secs(10), stream).await.unwrap().unwrap();
+
+        // 接続が切られた（送信エラー）ため、次の接続ではクライアントを作り直す
+        assert!(client_invalidated.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    #[ignore] // AWS の設定読み込み（環境によってはメタデータサービスへの問い合わせ）を伴うため、通常はスキップ
+    async fn test_client_reuse_shortens_setup() {
+        let mut backend = AwsTranscribeBackend::new(TranscribeConfig::default(), 0).await.unwrap();
+
+        let started = Instant::now();
+        let (_, created) = backend.client().await;
+        let first = started.elapsed();
+        assert!(created);
+
+        let started = Instant::now();
+        let (_, created) = backend.client().await;
+        let second = started.elapsed();
+        assert!(!created);
+
+        assert!(second < first, "クライアント作成: {:?}, 再利用: {:?}", first, second);
+    }
+
+    #[tokio::test]
+    async fn test_reset_start_time() {
+        let started = SystemTime::now() - std::time::Duration::from_secs(60);
+        let mut backend = AwsTranscribeBackend::new_with_start_time(TranscribeConfig::default(), 0, started)
+            .await
+            .unwrap();
+        let before = TranscriptResult::new(0, "a".to_string(), false, None, backend.start_time);
+        assert!(before.timestamp_seconds >= 60.0);
+
+        // 再接続後は0付近から数え直す
+        backend.reset_start_time();
+        let after = TranscriptResult::new(0, "a".to_string(), false, None, backend.start_time);
+        assert!(after.timestamp_seconds < 1.0);
+        assert_eq!(backend.reconnection_count, 1);
+
+        backend.reset_start_time();
+        assert_eq!(backend.reconnection_count, 2);
+    }
+
+    #[test]
+    fn test_chunk_sizing_defaults_match_previous_behavior() {
+        let config = TranscribeConfig::default();
+        let sizing = ChunkSizing::new(&config, 16000);
+
+        // 0.15秒 × 5チャンク、以降 0.2秒
+        assert_eq!(sizing.samples_for(0), 2400);
+        assert_eq!(sizing.samples_for(4), 2400);
+        assert_eq!(sizing.samples_for(5), 3200);
+        assert_eq!(sizing.samples_for(100), 3200);
+    }
+
+    #[test]
+    fn test_chunk_sizing_is_time_based() {
+        let config = TranscribeConfig {
+            initial_chunk_ms: 50,
+            chunk_ms: 100,
+            fast_start_chunks: 2,
+            ..TranscribeConfig::default()
+        };
+
+        // サンプルレートが変わっても同じ時間長になる
+        for rate in [8000u32, 16000, 44100, 48000] {
+            let sizing = ChunkSizing::new(&config, rate);
+            assert_eq!(sizing.samples_for(0) as f64 / rate as f64, 0.05, "{}Hz", rate);
+            assert_eq!(sizing.samples_for(2) as f64 / rate as f64, 0.1, "{}Hz", rate);
+        }
+
+        // fast_start_chunks = 0 なら最初から通常サイズ
+        let config = TranscribeConfig {
+            fast_start_chunks: 0,
+            ..config
+        };
+        assert_eq!(ChunkSizing::new(&config, 16000).samples_for(0), 1600);
+    }
+
+    #[test]
+    fn test_stream_timing_continuity() {
+        // 最初のストリームはオフセット・オーバーラップなし
+        let first = StreamTiming::default();
+        assert!(!first.is_in_overlap(0.0));
+        assert!(!first.is_in_overlap(0.5));
+
+        // 100秒経過時点で2秒オーバーラップして切り替え → 新ストリームの0秒は全体の98秒
+        let second = first.next(100.0, 2.0);
+        assert_eq!(second.offset_secs, 98.0);
+        assert!(second.is_in_overlap(1.5));
+        assert!(!second.is_in_overlap(2.0));
+        assert!(!second.is_in_overlap(2.5));
+
+        // さらに50秒分の音声を送った後に切り替え
+        let third = second.next(50.0, 2.0);
+        assert_eq!(third.offset_secs, 146.0);
+
+        // 基準時刻の10秒後に始まったストリームは、切り替え後もその分ずれる
+        let started_later = StreamTiming::starting_at(10.0);
+        assert!(!started_later.is_in_overlap(0.5));
+        assert_eq!(started_later.next(100.0, 2.0).offset_secs, 108.0);
+    }
+
+    #[test]
+    fn test_stream_timing_drops_result_straddling_switch() {
+        let second = StreamTiming::default().next(100.0, 2.0);
+        // オーバーラップ区間（1.2秒）で始まり切り替え時点（2秒）の後（3.4秒）に終わる結果は、旧ストリームも出力するため破棄
+        assert!(second.is_in_overlap(1.2));
+        // 切り替え時点以降に始まる結果は新ストリームのもの（全体の時刻は送った音声の長さから求める）
+        assert!(!second.is_in_overlap(2.1));
+        assert_eq!(second.offset_secs + 2.1, 100.1);
+    }
+
+    #[test]
+    fn test_average_confidence() {
+        let word = |confidence: f64| Item::builder().confidence(confidence).build();
+        let punctuation = Item::builder().build();
 
-        let start_time = SystemTime::now();
-        let result = AwsTranscribeBackend::new(config, 0, start_time).await;
-        assert!(result.is_ok());
+        assert_eq!(average_confidence(&[]), None);
+        assert_eq!(average_confidence(std::slice::from_ref(&punctuation)), None);
+        // 句読点は平均に含めない
+        let items = [word(0.9), punctuation, word(0.5)];
+        assert!((average_confidence(&items).unwrap() - 0.7).abs() < 1e-6);
     }
 }
@@ -0,0 +1,324 @@
+use crate::types::TranscriptResult;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::broadcast;
+
+/// 購読者ごとの受信待ちの上限（これを超えて遅れた購読者は古い結果を取りこぼす）
+const DEFAULT_CAPACITY: usize = 256;
+
+/// 文字起こし結果を複数の外部配信先へファンアウトする内部ブロードキャスト層
+///
+/// gRPC などの配信機能はこの層を購読し、プロトコル固有の変換だけを行う。
+/// 購読者がいない間の `publish` は何もしない。
+#[derive(Clone)]
+pub struct TranscriptBroadcaster {
+    tx: broadcast::Sender<TranscriptResult>,
+}
+
+impl TranscriptBroadcaster {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(DEFAULT_CAPACITY);
+        Self { tx }
+    }
+
+    /// 文字起こし結果を全購読者へ配信
+    pub fn publish(&self, result: &TranscriptResult) {
+        // 購読者がいない場合のエラーは無視する
+        let _ = self.tx.send(result.clone());
+    }
+
+    /// 購読を開始
+    ///
+    /// `channel_ids` が空なら全チャンネルの結果を受け取る。
+    pub fn subscribe(&self, channel_ids: impl IntoIterator<Item = usize>) -> TranscriptSubscription {
+        let channel_ids: HashSet<usize> = channel_ids.into_iter().collect();
+        TranscriptSubscription {
+            rx: self.tx.subscribe(),
+            channel_ids,
+            lagged: false,
+        }
+    }
+
+    /// 現在の購読者数
+    pub fn subscriber_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+}
+
+impl Default for TranscriptBroadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// チャンネルIDでフィルタした購読
+///
+/// ドロップすると購読を解除する。
+pub struct TranscriptSubscription {
+    rx: broadcast::Receiver<TranscriptResult>,
+    channel_ids: HashSet<usize>,
+    /// 前回の `take_lagged` 以降に取りこぼしがあったか
+    lagged: bool,
+}
+
+impl TranscriptSubscription {
+    /// 次の結果を受信
+    ///
+    /// 配信元がすべてドロップされると `None` を返す。
+    /// 受信が遅れて取りこぼした場合は警告を出して続行する。
+    pub async fn recv(&mut self) -> Option<TranscriptResult> {
+        loop {
+            match self.rx.recv().await {
+                Ok(result) if self.accepts(result.channel) => return Some(result),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("配信先の受信が遅れたため {} 件の結果を破棄しました", skipped);
+                    self.lagged = true;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// 前回の呼び出し以降に取りこぼした結果があったかを返し、リセットする
+    ///
+    /// 差分配信では、取りこぼし後は前回のテキストが購読者と食い違うため全文送信からやり直す。
+    pub fn take_lagged(&mut self) -> bool {
+        std::mem::take(&mut self.lagged)
+    }
+
+    fn accepts(&self, channel: usize) -> bool {
+        self.channel_ids.is_empty() || self.channel_ids.contains(&channel)
+    }
+}
+
+/// 部分結果の差分（前回送った同じチャンネルの部分結果からの変更）
+///
+/// 受信側は前回のテキストの先頭 `base_len` 文字（Unicode のコードポイント数）に
+/// `appended` を連結して今回のテキストを再構成する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialDelta {
+    /// 前回のテキストのうち残す先頭の文字数（変更開始位置）
+    pub base_len: usize,
+    /// `base_len` 以降に続くテキスト
+    pub appended: String,
+}
+
+impl PartialDelta {
+    /// 前回のテキスト `base` に差分を適用する
+    ///
+    /// `base_len` が `base` の文字数を超える場合は `None`（前回のテキストが食い違っている）。
+    pub fn apply(&self, base: &str) -> Option<String> {
+        let prefix_bytes = match base.char_indices().nth(self.base_len) {
+            Some((index, _)) => index,
+            None if base.chars().count() == self.base_len => base.len(),
+            None => return None,
+        };
+        Some(format!("{}{}", &base[..prefix_bytes], self.appended))
+    }
+}
+
+/// 購読者ごとに、部分結果を前回送った部分結果との差分に変換する
+///
+/// 部分結果は発話が進むたびに全文が届くため、前回と共通の接頭辞を除いた分だけを送れば帯域を抑えられる。
+/// 確定結果は常に全文を送り、そのチャンネルの前回テキストを破棄する（次の部分結果は全文から始まる）。
+#[derive(Debug, Default)]
+pub struct PartialDeltaEncoder {
+    /// チャンネルごとの前回送った部分結果のテキスト
+    last_partial: HashMap<usize, String>,
+}
+
+impl PartialDeltaEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 送信する結果を差分に変換する（全文を送るべき場合は `None`）
+    pub fn encode(&mut self, result: &TranscriptResult) -> Option<PartialDelta> {
+        if !result.is_partial {
+            self.last_partial.remove(&result.channel);
+            return None;
+        }
+        let previous = self.last_partial.insert(result.channel, result.text.clone())?;
+        let base_len = previous
+            .chars()
+            .zip(result.text.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        Some(PartialDelta {
+            base_len,
+            appended: result.text.chars().skip(base_len).collect(),
+        })
+    }
+
+    /// 前回のテキストをすべて破棄する（取りこぼし後など、次の部分結果を全文で送る）
+    pub fn reset(&mut self) {
+        self.last_partial.clear();
+    }
+}
+
+/// 受信側で差分から部分結果のテキストを再構成する（`PartialDeltaEncoder` の対）
+#[derive(Debug, Default)]
+pub struct PartialDeltaDecoder {
+    last_partial: HashMap<usize, String>,
+}
+
+impl PartialDeltaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 全文で受信した結果を取り込む
+    pub fn full(&mut self, channel: usize, text: &str, is_partial: bool) -> String {
+        if is_partial {
+            self.last_partial.insert(channel, text.to_string());
+        } else {
+            self.last_partial.remove(&channel);
+        }
+        text.to_string()
+    }
+
+    /// 差分で受信した部分結果からテキストを再構成する
+    ///
+    /// 前回の部分結果がない、または食い違っている場合は `None`。
+    pub fn delta(&mut self, channel: usize, delta: &PartialDelta) -> Option<String> {
+        let text = delta.apply(self.last_partial.get(&channel)?)?;
+        self.last_partial.insert(channel, text.clone());
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(channel: usize, text: &str) -> TranscriptResult {
+        TranscriptResult::new_with_audio_time(channel, text.to_string(), false, None, 0.0, None)
+    }
+
+    #[tokio::test]
+    async fn test_fanout_with_filter() {
+        let broadcaster = TranscriptBroadcaster::new();
+        let mut all = broadcaster.subscribe([]);
+        let mut ch1 = broadcaster.subscribe([1]);
+        assert_eq!(broadcaster.subscriber_count(), 2);
+
+        broadcaster.publish(&result(0, "ゼロ"));
+        broadcaster.publish(&result(1, "イチ"));
+
+        assert_eq!(all.recv().await.unwrap().text, "ゼロ");
+        assert_eq!(all.recv().await.unwrap().text, "イチ");
+        assert_eq!(ch1.recv().await.unwrap().text, "イチ");
+    }
+
+    fn partial(channel: usize, text: &str) -> TranscriptResult {
+        TranscriptResult::new_with_audio_time(channel, text.to_string(), true, None, 0.0, None)
+    }
+
+    #[test]
+    fn test_partial_delta_encoding() {
+        let mut encoder = PartialDeltaEncoder::new();
+
+        // 最初の部分結果は全文、以降は追加分だけ
+        assert_eq!(encoder.encode(&partial(0, "現在")), None);
+        assert_eq!(
+            encoder.encode(&partial(0, "現在地を")),
+            Some(PartialDelta { base_len: 2, appended: "地を".to_string() })
+        );
+        // 途中から変わった場合は変更開始位置から
+        assert_eq!(
+            encoder.encode(&partial(0, "現在位置を")),
+            Some(PartialDelta { base_len: 2, appended: "位置を".to_string() })
+        );
+        // 短くなった場合・変化がない場合
+        assert_eq!(
+            encoder.encode(&partial(0, "現在")),
+            Some(PartialDelta { base_len: 2, appended: String::new() })
+        );
+        // チャンネルごとに独立
+        assert_eq!(encoder.encode(&partial(1, "了解")), None);
+
+        // 確定結果は全文で、次の部分結果も全文から
+        assert_eq!(encoder.encode(&result(0, "現在位置を送ります")), None);
+        assert_eq!(encoder.encode(&partial(0, "次")), None);
+
+        encoder.reset();
+        assert_eq!(encoder.encode(&partial(1, "了解です")), None);
+    }
+
+    #[test]
+    fn test_partial_delta_roundtrip() {
+        // 追加・書き換え・削除・絵文字や結合文字を含む部分結果の列
+        let updates: &[(usize, &str, bool)] = &[
+            (0, "こ", true),
+            (0, "こんに", true),
+            (1, "Roger", true),
+            (0, "こんにちは", true),
+            (0, "今日は", true),
+            (1, "Roger that", true),
+            (0, "今日は📻で", true),
+            (0, "今日は📻で", true),
+            (0, "今日", true),
+            (0, "今日は無線で連絡します", false),
+            (0, "次の", true),
+            (1, "Roger that.", false),
+            (0, "次のe\u{301}", true),
+            (0, "次のé", true),
+            (0, "", true),
+            (0, "次へ", true),
+        ];
+
+        let mut encoder = PartialDeltaEncoder::new();
+        let mut decoder = PartialDeltaDecoder::new();
+        let mut full_bytes = 0;
+        let mut sent_bytes = 0;
+        for &(channel, text, is_partial) in updates {
+            let result = if is_partial { partial(channel, text) } else { result(channel, text) };
+            let received = match encoder.encode(&result) {
+                Some(delta) => {
+                    sent_bytes += delta.appended.len();
+                    decoder.delta(channel, &delta).unwrap()
+                }
+                None => {
+                    sent_bytes += text.len();
+                    decoder.full(channel, text, is_partial)
+                }
+            };
+            full_bytes += text.len();
+            assert_eq!(received, text);
+        }
+        assert!(sent_bytes < full_bytes, "{} >= {}", sent_bytes, full_bytes);
+
+        // 前回のテキストが食い違っている場合は再構成できない
+        let delta = PartialDelta { base_len: 5, appended: "x".to_string() };
+        assert_eq!(delta.apply("abc"), None);
+        assert_eq!(PartialDeltaDecoder::new().delta(0, &delta), None);
+    }
+
+    #[tokio::test]
+    async fn test_lagged_subscription() {
+        let broadcaster = TranscriptBroadcaster::new();
+        let mut sub = broadcaster.subscribe([]);
+        for i in 0..DEFAULT_CAPACITY + 1 {
+            broadcaster.publish(&partial(0, &"あ".repeat(i + 1)));
+        }
+        assert!(!sub.take_lagged());
+        sub.recv().await.unwrap();
+        assert!(sub.take_lagged());
+        assert!(!sub.take_lagged());
+    }
+
+    #[tokio::test]
+    async fn test_close_and_unsubscribe() {
+        let broadcaster = TranscriptBroadcaster::new();
+        let sub = broadcaster.subscribe([]);
+        drop(sub);
+        assert_eq!(broadcaster.subscriber_count(), 0);
+
+        // 購読者がいなくても publish は失敗しない
+        broadcaster.publish(&result(0, "誰も聞いていない"));
+
+        let mut sub = broadcaster.subscribe([]);
+        drop(broadcaster);
+        assert!(sub.recv().await.is_none());
+    }
+}
@@ -1,6 +1,34 @@
 use crate::config::BufferConfig;
 use crate::types::{BufferedChunk, DropPolicy, SampleI16};
 use std::collections::VecDeque;
+use std::fmt;
+
+/// 1サンプルあたりのバイト数
+const BYTES_PER_SAMPLE: usize = std::mem::size_of::<SampleI16>();
+
+/// `DropPolicy::Block` のバッファが容量を超えるため、`AudioBuffer::try_push` でチャンクを追加できなかった
+#[derive(Debug)]
+pub struct BufferFullError {
+    /// 追加できなかったチャンク（呼び出し側で保持し、空きができてから再試行できる）
+    pub chunk: BufferedChunk,
+    /// 追加しようとした時点のバッファ内のサンプル数
+    pub buffered_samples: usize,
+    pub capacity_samples: usize,
+}
+
+impl fmt::Display for BufferFullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "音声バッファが満杯のため追加できません（{} + {} > 上限 {} サンプル）",
+            self.buffered_samples,
+            self.chunk.samples.len(),
+            self.capacity_samples
+        )
+    }
+}
+
+impl std::error::Error for BufferFullError {}
 
 /// リトライ用の音声データバッファ
 ///
@@ -16,7 +44,10 @@ pub struct AudioBuffer {
 
 impl AudioBuffer {
     pub fn new(config: &BufferConfig, sample_rate: u32) -> Self {
-        let capacity_samples = (config.capacity_seconds * sample_rate) as usize;
+        let mut capacity_samples = (config.capacity_seconds * sample_rate) as usize;
+        if let Some(capacity_bytes) = config.capacity_bytes {
+            capacity_samples = capacity_samples.min(capacity_bytes / BYTES_PER_SAMPLE);
+        }
         Self {
             capacity_samples,
             drop_policy: config.drop_policy,
@@ -27,6 +58,9 @@ impl AudioBuffer {
     }
 
     /// チャンクを追加
+    ///
+    /// 容量を超えた分はドロップポリシーに従って破棄する。`push` は待てないため、
+    /// `DropPolicy::Block` も DropOldest として扱う（バックプレッシャーを掛ける場合は `try_push` を使う）。
     pub fn push(&mut self, chunk: BufferedChunk) {
         let chunk_len = chunk.samples.len();
         self.total_samples += chunk_len;
@@ -45,9 +79,14 @@ impl AudioBuffer {
                         self.total_samples -= dropped.samples.len();
                     }
                 }
+                DropPolicy::KeepVoice => {
+                    let index = self.chunks.iter().position(|c| !c.is_voice).unwrap_or(0);
+                    if let Some(dropped) = self.chunks.remove(index) {
+                        self.total_samples -= dropped.samples.len();
+                    }
+                }
                 DropPolicy::Block => {
-                    // Block ポリシーは実装しない（アーキテクチャで「使わない」と記載）
-                    log::warn!("Block ポリシーは未実装: DropOldest として処理");
+                    log::warn!("Block ポリシーのバッファに push で容量を超えて追加: DropOldest として処理");
                     if let Some(dropped) = self.chunks.pop_front() {
                         self.total_samples -= dropped.samples.len();
                     }
@@ -56,61 +95,78 @@ impl AudioBuffer {
         }
     }
 
+    /// チャンクを追加（`DropPolicy::Block` で容量を超える場合は追加せずにエラーを返す）
+    ///
+    /// エラーには追加できなかったチャンクが入っているため、呼び出し側はそれを保持して入力を止め、
+    /// `clear_before` などで空きができてから再試行する。容量より長いチャンクは空のバッファにも追加できない。
+    /// Block 以外のポリシーでは `push` と同じく、容量を超えた分をポリシーに従って破棄する。
+    pub fn try_push(&mut self, chunk: BufferedChunk) -> Result<(), BufferFullError> {
+        if self.drop_policy == DropPolicy::Block
+            && self.total_samples + chunk.samples.len() > self.capacity_samples
+        {
+            return Err(BufferFullError {
+                chunk,
+                buffered_samples: self.total_samples,
+                capacity_samples: self.capacity_samples,
+            });
+        }
+        self.push(chunk);
+        Ok(())
+    }
+
     /// 指定期間のサンプルを取得
     ///
     /// # Arguments
-    /// * `from_ns` - 開始タイムスタンプ (ナノ秒)
-    /// * `to_ns` - 終了タイムスタンプ (ナノ秒)
+    /// * `from_ns` - 開始タイムスタンプ (ナノ秒、この時刻を含む)
+    /// * `to_ns` - 終了タイムスタンプ (ナノ秒、この時刻を含まない)
     ///
     /// # Returns
-    /// 指定期間内のサンプル配列
+    /// 指定期間内のサンプル配列（`from_ns >= to_ns` や、バッファと重ならない範囲なら空）
     pub fn get_range(&self, from_ns: u128, to_ns: u128) -> Vec<SampleI16> {
         let mut result = Vec::new();
+        if from_ns >= to_ns {
+            return result;
+        }
 
         for chunk in &self.chunks {
-            // チャンクの終了タイムスタンプを計算
-            let chunk_duration_ns =
-                (chunk.samples.len() as f64 / self.sample_rate as f64 * 1_000_000_000.0) as u128;
-            let chunk_end_ns = chunk.timestamp_ns + chunk_duration_ns;
+            let chunk_end_ns = self.chunk_end_ns(chunk);
 
             // 範囲と重なるチャンクのみ処理
-            if chunk_end_ns >= from_ns && chunk.timestamp_ns <= to_ns {
-                // チャンク内の開始・終了インデックスを計算
-                let start_offset = if chunk.timestamp_ns < from_ns {
-                    let offset_ns = from_ns - chunk.timestamp_ns;
-                    ((offset_ns as f64 / 1_000_000_000.0) * self.sample_rate as f64) as usize
-                } else {
-                    0
-                };
-
-                let end_offset = if chunk_end_ns > to_ns {
-                    let offset_ns = to_ns - chunk.timestamp_ns;
-                    ((offset_ns as f64 / 1_000_000_000.0) * self.sample_rate as f64) as usize
-                } else {
-                    chunk.samples.len()
-                };
+            if chunk_end_ns <= from_ns || chunk.timestamp_ns >= to_ns {
+                continue;
+            }
 
-                if start_offset < chunk.samples.len() {
-                    let end = end_offset.min(chunk.samples.len());
-                    result.extend_from_slice(&chunk.samples[start_offset..end]);
-                }
+            // チャンク内の開始・終了インデックス（ここでは chunk.timestamp_ns < to_ns）
+            let len = chunk.samples.len();
+            let start = self.ns_to_samples(from_ns.saturating_sub(chunk.timestamp_ns)).min(len);
+            let end = self.ns_to_samples(to_ns - chunk.timestamp_ns).min(len);
+            if start < end {
+                result.extend_from_slice(&chunk.samples[start..end]);
             }
         }
 
         result
     }
 
+    /// チャンクの終了タイムスタンプ（ナノ秒、u128 の上限で頭打ち）
+    fn chunk_end_ns(&self, chunk: &BufferedChunk) -> u128 {
+        // サンプルレートが 0 なら長さは無限大として u128::MAX に丸まる
+        let duration_ns = (chunk.samples.len() as f64 / self.sample_rate as f64 * 1_000_000_000.0) as u128;
+        chunk.timestamp_ns.saturating_add(duration_ns)
+    }
+
+    /// 時間（ナノ秒）に相当するサンプル数（usize の上限で頭打ち）
+    fn ns_to_samples(&self, ns: u128) -> usize {
+        (ns as f64 / 1_000_000_000.0 * self.sample_rate as f64) as usize
+    }
+
     /// 指定タイムスタンプより前のデータを削除
     ///
     /// # Arguments
     /// * `timestamp_ns` - このタイムスタンプより前のデータを削除
     pub fn clear_before(&mut self, timestamp_ns: u128) {
         while let Some(chunk) = self.chunks.front() {
-            let chunk_duration_ns =
-                (chunk.samples.len() as f64 / self.sample_rate as f64 * 1_000_000_000.0) as u128;
-            let chunk_end_ns = chunk.timestamp_ns + chunk_duration_ns;
-
-            if chunk_end_ns < timestamp_ns {
+            if self.chunk_end_ns(chunk) < timestamp_ns {
                 if let Some(removed) = self.chunks.pop_front() {
                     self.total_samples -= removed.samples.len();
                 }
@@ -160,6 +216,16 @@ impl AudioBuffer {
         self.total_samples == 0
     }
 
+    /// バッファ内のサンプルが使用しているバイト数
+    pub fn used_bytes(&self) -> usize {
+        self.total_samples * BYTES_PER_SAMPLE
+    }
+
+    /// 上限サンプル数（秒数とバイト数の上限のうち小さい方）
+    pub fn capacity_samples(&self) -> usize {
+        self.capacity_samples
+    }
+
     /// バッファ内のデータ時間（秒）
     pub fn duration_seconds(&self) -> f64 {
         self.total_samples as f64 / self.sample_rate as f64
@@ -181,6 +247,7 @@ mod tests {
         let config = BufferConfig {
             capacity_seconds: 1,
             drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: None,
         };
         let mut buffer = AudioBuffer::new(&config, 16000);
 
@@ -188,6 +255,7 @@ mod tests {
         let chunk1 = BufferedChunk {
             samples: vec![1i16; 8000],
             timestamp_ns: 0,
+            is_voice: false,
         };
         buffer.push(chunk1);
         assert_eq!(buffer.len(), 8000);
@@ -196,6 +264,7 @@ mod tests {
         let chunk2 = BufferedChunk {
             samples: vec![2i16; 8000],
             timestamp_ns: 500_000_000,
+            is_voice: false,
         };
         buffer.push(chunk2);
         assert_eq!(buffer.len(), 16000);
@@ -204,16 +273,169 @@ mod tests {
         let chunk3 = BufferedChunk {
             samples: vec![3i16; 8000],
             timestamp_ns: 1_000_000_000,
+            is_voice: false,
         };
         buffer.push(chunk3);
         assert!(buffer.len() <= 16000);
     }
 
+    #[test]
+    fn test_try_push_block_returns_error_when_full() {
+        let config = BufferConfig {
+            capacity_seconds: 1,
+            drop_policy: DropPolicy::Block,
+            capacity_bytes: None,
+        };
+        let mut buffer = AudioBuffer::new(&config, 16000);
+        let chunk = |value: i16, timestamp_ns: u128| BufferedChunk {
+            samples: vec![value; 8000],
+            timestamp_ns,
+            is_voice: false,
+        };
+        buffer.try_push(chunk(1, 0)).unwrap();
+        buffer.try_push(chunk(2, 500_000_000)).unwrap();
+
+        // 満杯なら何も破棄せず、追加できなかったチャンクを返す
+        let error = buffer.try_push(chunk(3, 1_000_000_000)).unwrap_err();
+        assert_eq!(error.chunk.samples[0], 3);
+        assert_eq!((error.buffered_samples, error.capacity_samples), (16000, 16000));
+        assert!(error.to_string().contains("満杯"), "{}", error);
+        assert_eq!(buffer.len(), 16000);
+        assert_eq!(buffer.get_latest(0.5)[0], 2);
+
+        // 空きができたら再試行できる
+        buffer.clear_before(600_000_000);
+        buffer.try_push(error.chunk).unwrap();
+        let values: Vec<i16> = buffer.chunks.iter().map(|c| c.samples[0]).collect();
+        assert_eq!(values, vec![2, 3]);
+
+        // 容量より長いチャンクは空でも追加できない
+        buffer.clear();
+        assert!(buffer.try_push(BufferedChunk {
+            samples: vec![4; 16001],
+            timestamp_ns: 0,
+            is_voice: false,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_try_push_other_policies_match_push() {
+        for drop_policy in [DropPolicy::DropOldest, DropPolicy::DropNewest, DropPolicy::KeepVoice] {
+            let config = BufferConfig {
+                capacity_seconds: 1,
+                drop_policy,
+                capacity_bytes: None,
+            };
+            let mut pushed = AudioBuffer::new(&config, 16000);
+            let mut try_pushed = AudioBuffer::new(&config, 16000);
+            for i in 0..6 {
+                let chunk = BufferedChunk {
+                    samples: vec![i as i16; 4000],
+                    timestamp_ns: i as u128 * 250_000_000,
+                    is_voice: i % 2 == 0,
+                };
+                pushed.push(chunk.clone());
+                try_pushed.try_push(chunk).unwrap();
+            }
+            let values = |buffer: &AudioBuffer| buffer.chunks.iter().map(|c| c.samples[0]).collect::<Vec<_>>();
+            assert_eq!(values(&try_pushed), values(&pushed), "{:?}", drop_policy);
+            assert_eq!(try_pushed.len(), 16000);
+        }
+    }
+
+    #[test]
+    fn test_keep_voice_drops_silence_first() {
+        let config = BufferConfig {
+            capacity_seconds: 1,
+            drop_policy: DropPolicy::KeepVoice,
+            capacity_bytes: None,
+        };
+        let mut buffer = AudioBuffer::new(&config, 16000);
+
+        // 0.25秒ずつ: 音声, 無音, 音声, 無音 で満杯
+        for (i, is_voice) in [true, false, true, false].into_iter().enumerate() {
+            buffer.push(BufferedChunk {
+                samples: vec![i as i16; 4000],
+                timestamp_ns: i as u128 * 250_000_000,
+                is_voice,
+            });
+        }
+        assert_eq!(buffer.len(), 16000);
+
+        // 容量オーバー: 最古の音声ではなく、最古の無音が破棄される
+        buffer.push(BufferedChunk {
+            samples: vec![4i16; 4000],
+            timestamp_ns: 1_000_000_000,
+            is_voice: true,
+        });
+        assert_eq!(buffer.len(), 16000);
+        let values: Vec<i16> = buffer.chunks.iter().map(|c| c.samples[0]).collect();
+        assert_eq!(values, vec![0, 2, 3, 4]);
+
+        // 無音が尽きるまでは音声を残す
+        buffer.push(BufferedChunk {
+            samples: vec![5i16; 4000],
+            timestamp_ns: 1_250_000_000,
+            is_voice: true,
+        });
+        let values: Vec<i16> = buffer.chunks.iter().map(|c| c.samples[0]).collect();
+        assert_eq!(values, vec![0, 2, 4, 5]);
+
+        // 全て音声なら DropOldest と同じ
+        buffer.push(BufferedChunk {
+            samples: vec![6i16; 4000],
+            timestamp_ns: 1_500_000_000,
+            is_voice: true,
+        });
+        let values: Vec<i16> = buffer.chunks.iter().map(|c| c.samples[0]).collect();
+        assert_eq!(values, vec![2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_capacity_bytes_limit() {
+        // 1秒 = 16000サンプル = 32000バイトだが、バイト上限 20000 の方が小さい
+        let config = BufferConfig {
+            capacity_seconds: 1,
+            drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: Some(20000),
+        };
+        let mut buffer = AudioBuffer::new(&config, 16000);
+        assert_eq!(buffer.capacity_samples(), 10000);
+
+        for i in 0..4 {
+            buffer.push(BufferedChunk {
+                samples: vec![i as i16; 4000],
+                timestamp_ns: i as u128 * 250_000_000,
+                is_voice: false,
+            });
+        }
+
+        // 古いチャンクがドロップされ、バイト上限内に収まる
+        assert_eq!(buffer.len(), 8000);
+        assert_eq!(buffer.used_bytes(), 16000);
+        assert!(buffer.used_bytes() <= 20000);
+    }
+
+    #[test]
+    fn test_capacity_seconds_smaller_than_bytes() {
+        // バイト上限の方が大きい場合は秒数の上限を使う
+        let config = BufferConfig {
+            capacity_seconds: 1,
+            drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: Some(1_000_000),
+        };
+        let buffer = AudioBuffer::new(&config, 16000);
+        assert_eq!(buffer.capacity_samples(), 16000);
+        assert_eq!(buffer.used_bytes(), 0);
+    }
+
     #[test]
     fn test_get_latest() {
         let config = BufferConfig {
             capacity_seconds: 10,
             drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: None,
         };
         let mut buffer = AudioBuffer::new(&config, 16000);
 
@@ -221,14 +443,17 @@ mod tests {
         buffer.push(BufferedChunk {
             samples: vec![1i16; 16000],
             timestamp_ns: 0,
+            is_voice: false,
         });
         buffer.push(BufferedChunk {
             samples: vec![2i16; 16000],
             timestamp_ns: 1_000_000_000,
+            is_voice: false,
         });
         buffer.push(BufferedChunk {
             samples: vec![3i16; 16000],
             timestamp_ns: 2_000_000_000,
+            is_voice: false,
         });
 
         // 最新1秒分を取得
@@ -237,25 +462,96 @@ mod tests {
         assert_eq!(latest[0], 3i16); // 最新チャンクのデータ
     }
 
+    #[test]
+    fn test_get_range_bounds() {
+        let config = BufferConfig {
+            capacity_seconds: 10,
+            drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: None,
+        };
+        let mut buffer = AudioBuffer::new(&config, 16000);
+
+        // 空のバッファ
+        assert!(buffer.get_range(0, u128::MAX).is_empty());
+
+        // 1秒〜3秒に2チャンク
+        buffer.push(BufferedChunk {
+            samples: vec![1i16; 16000],
+            timestamp_ns: 1_000_000_000,
+            is_voice: false,
+        });
+        buffer.push(BufferedChunk {
+            samples: vec![2i16; 16000],
+            timestamp_ns: 2_000_000_000,
+            is_voice: false,
+        });
+
+        // 全体
+        assert_eq!(buffer.get_range(0, u128::MAX).len(), 32000);
+        assert_eq!(buffer.get_range(1_000_000_000, 3_000_000_000).len(), 32000);
+
+        // バッファより前・後
+        assert!(buffer.get_range(0, 1_000_000_000).is_empty());
+        assert!(buffer.get_range(0, 500_000_000).is_empty());
+        assert!(buffer.get_range(3_000_000_000, 4_000_000_000).is_empty());
+        assert!(buffer.get_range(u128::MAX - 1, u128::MAX).is_empty());
+
+        // 逆転・空の範囲（チャンクの途中でもパニックしない）
+        assert!(buffer.get_range(1_800_000_000, 1_200_000_000).is_empty());
+        assert!(buffer.get_range(u128::MAX, 0).is_empty());
+        assert!(buffer.get_range(1_500_000_000, 1_500_000_000).is_empty());
+
+        // 部分的な重なり
+        assert_eq!(buffer.get_range(0, 1_500_000_000), vec![1i16; 8000]);
+        assert_eq!(buffer.get_range(2_500_000_000, 10_000_000_000), vec![2i16; 8000]);
+        let across = buffer.get_range(1_750_000_000, 2_250_000_000);
+        assert_eq!(across.len(), 8000);
+        assert_eq!(&across[..4000], &[1i16; 4000][..]);
+        assert_eq!(&across[4000..], &[2i16; 4000][..]);
+        assert_eq!(buffer.get_range(1_250_000_000, 1_500_000_000).len(), 4000);
+    }
+
+    #[test]
+    fn test_get_range_zero_sample_rate() {
+        let config = BufferConfig {
+            capacity_seconds: 10,
+            drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: Some(1_000_000),
+        };
+        let mut buffer = AudioBuffer::new(&config, 0);
+        buffer.push(BufferedChunk {
+            samples: vec![1i16; 100],
+            timestamp_ns: 0,
+            is_voice: false,
+        });
+        // サンプルレートが不正でもパニックしない
+        assert!(buffer.get_range(0, u128::MAX).is_empty());
+        buffer.clear_before(u128::MAX);
+    }
+
     #[test]
     fn test_clear_before() {
         let config = BufferConfig {
             capacity_seconds: 10,
             drop_policy: DropPolicy::DropOldest,
+            capacity_bytes: None,
         };
         let mut buffer = AudioBuffer::new(&config, 16000);
 
         buffer.push(BufferedChunk {
             samples: vec![1i16; 16000],
             timestamp_ns: 0,
+            is_voice: false,
         });
         buffer.push(BufferedChunk {
             samples: vec![2i16; 16000],
             timestamp_ns: 1_000_000_000,
+            is_voice: false,
         });
         buffer.push(BufferedChunk {
             samples: vec![3i16; 16000],
             timestamp_ns: 2_000_000_000,
+            is_voice: false,
         });
 
         // 1.5秒より前を削除
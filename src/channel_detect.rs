@@ -0,0 +1,350 @@
+use crate::audio_input::AudioInput;
+use crate::config::{AudioConfig, DetectConfig, InternalFormat};
+use crate::types::AudioChunk;
+use crate::vad::{calculate_rms, rms_to_db};
+use anyhow::Result;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// 音量を計算するウィンドウ長（ミリ秒）
+const WINDOW_MS: u32 = 100;
+
+/// 検出中に受信チャンネルを確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// チャンネルごとの音量の集計（ウィンドウ単位）
+#[derive(Debug, Clone)]
+struct LevelAccumulator {
+    /// ウィンドウに満たない端数のサンプル
+    pending: Vec<i16>,
+    peak_db: f32,
+    db_sum: f64,
+    windows: usize,
+    active_windows: usize,
+}
+
+impl LevelAccumulator {
+    fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            peak_db: rms_to_db(0.0),
+            db_sum: 0.0,
+            windows: 0,
+            active_windows: 0,
+        }
+    }
+}
+
+/// 1チャンネル分の検出結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelLevel {
+    /// 物理入力チャンネル番号（0始まり）
+    pub channel: usize,
+    /// ウィンドウ音量の最大値 (dBFS)
+    pub peak_db: f32,
+    /// ウィンドウ音量の平均値 (dBFS)
+    pub mean_db: f32,
+    /// 計測したウィンドウ数
+    pub windows: usize,
+    /// 閾値を超えたウィンドウ数
+    pub active_windows: usize,
+}
+
+impl ChannelLevel {
+    /// 閾値を超えた区間があったか
+    pub fn has_signal(&self) -> bool {
+        self.active_windows > 0
+    }
+
+    /// 閾値を超えたウィンドウの割合 (0.0-1.0)
+    pub fn active_ratio(&self) -> f32 {
+        if self.windows == 0 {
+            return 0.0;
+        }
+        self.active_windows as f32 / self.windows as f32
+    }
+}
+
+/// 物理入力チャンネルごとの信号有無の判定
+///
+/// VADと同じRMS/dB計算で `WINDOW_MS` ごとの音量を求め、閾値を超えたウィンドウを数える。
+pub struct ChannelDetector {
+    window_samples: usize,
+    threshold_db: f32,
+    channels: Vec<LevelAccumulator>,
+}
+
+impl ChannelDetector {
+    pub fn new(num_channels: usize, sample_rate: u32, threshold_db: f32) -> Self {
+        Self {
+            window_samples: (sample_rate * WINDOW_MS / 1000).max(1) as usize,
+            threshold_db,
+            channels: vec![LevelAccumulator::new(); num_channels],
+        }
+    }
+
+    /// `channel` のサンプルを集計する（範囲外のチャンネルは無視）
+    pub fn process(&mut self, channel: usize, samples: &[i16]) {
+        let window_samples = self.window_samples;
+        let threshold_db = self.threshold_db;
+        let Some(acc) = self.channels.get_mut(channel) else {
+            return;
+        };
+
+        acc.pending.extend_from_slice(samples);
+        let full = acc.pending.len() / window_samples * window_samples;
+        for window in acc.pending[..full].chunks(window_samples) {
+            let db = rms_to_db(calculate_rms(window));
+            acc.peak_db = acc.peak_db.max(db);
+            acc.db_sum += db as f64;
+            acc.windows += 1;
+            if db > threshold_db {
+                acc.active_windows += 1;
+            }
+        }
+        acc.pending.drain(..full);
+    }
+
+    /// 現時点の集計結果
+    pub fn report(&self) -> DetectionReport {
+        let channels = self
+            .channels
+            .iter()
+            .enumerate()
+            .map(|(channel, acc)| ChannelLevel {
+                channel,
+                peak_db: acc.peak_db,
+                mean_db: if acc.windows == 0 {
+                    rms_to_db(0.0)
+                } else {
+                    (acc.db_sum / acc.windows as f64) as f32
+                },
+                windows: acc.windows,
+                active_windows: acc.active_windows,
+            })
+            .collect();
+
+        DetectionReport {
+            threshold_db: self.threshold_db,
+            channels,
+        }
+    }
+}
+
+/// `--detect-channels` の結果
+#[derive(Debug, Clone)]
+pub struct DetectionReport {
+    /// 判定に使った閾値 (dBFS)
+    pub threshold_db: f32,
+    /// 物理入力チャンネルごとの結果（チャンネル番号順）
+    pub channels: Vec<ChannelLevel>,
+}
+
+impl DetectionReport {
+    /// 信号のあった物理チャンネル番号
+    pub fn signal_channels(&self) -> Vec<usize> {
+        self.channels
+            .iter()
+            .filter(|c| c.has_signal())
+            .map(|c| c.channel)
+            .collect()
+    }
+
+    /// 「物理8ch中、ch0/2/4/6に信号あり」形式の要約
+    pub fn summary(&self) -> String {
+        let signal = self.signal_channels();
+        if signal.is_empty() {
+            return format!("物理{}ch中、信号のあるチャンネルはありません", self.channels.len());
+        }
+        let list = signal.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("/");
+        format!("物理{}ch中、ch{}に信号あり", self.channels.len(), list)
+    }
+
+    /// 推奨する `[audio] channels` と `[[channels]]` の設定（TOML）
+    ///
+    /// 物理入力は有効な `[[channels]]` に定義順で割り当てられる（チャンネルの対応表は設定できない）。
+    /// そのため信号のあるチャンネルが先頭から連続していればそれだけを、
+    /// 飛び飛びなら最後の信号チャンネルまでの全チャンネルを有効にする設定を返す。
+    /// 信号のあるチャンネルがなければ `None`。
+    pub fn suggested_config(&self) -> Option<String> {
+        let signal = self.signal_channels();
+        let last = *signal.last()?;
+        let contiguous = signal.len() == last + 1;
+
+        let mut out = String::new();
+        writeln!(out, "[audio]").unwrap();
+        writeln!(out, "channels = {}", last + 1).unwrap();
+        if !contiguous {
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "# 物理入力は有効な [[channels]] に定義順で割り当てられるため、"
+            )
+            .unwrap();
+            writeln!(out, "# 信号のないチャンネルも入力位置を揃えるために有効のままにしています").unwrap();
+        }
+        for channel in 0..=last {
+            writeln!(out).unwrap();
+            writeln!(out, "[[channels]]").unwrap();
+            writeln!(out, "id = {}", channel).unwrap();
+            writeln!(out, "name = \"Channel {}\"", channel + 1).unwrap();
+            if signal.contains(&channel) {
+                writeln!(out, "enabled = true").unwrap();
+            } else {
+                writeln!(out, "enabled = true # 信号なし").unwrap();
+            }
+        }
+        Some(out)
+    }
+
+    /// 結果を標準出力に表示
+    pub fn print(&self) {
+        for level in &self.channels {
+            println!(
+                "ch{}: {} (ピーク {:.1} dB, 平均 {:.1} dB, 閾値超え {:.0}%)",
+                level.channel,
+                if level.has_signal() { "信号あり" } else { "信号なし" },
+                level.peak_db,
+                level.mean_db,
+                level.active_ratio() * 100.0
+            );
+        }
+        println!();
+        println!("{} (閾値 {:.1} dB)", self.summary(), self.threshold_db);
+
+        if let Some(config) = self.suggested_config() {
+            println!();
+            println!("=== 推奨設定 ===");
+            print!("{}", config);
+        }
+    }
+}
+
+/// 入力デバイスを `detect.duration_secs` 秒だけ開き、物理チャンネルごとの信号有無を調べる
+///
+/// 録音・文字起こしは行わない。`audio.channels` によらずデバイスの全入力チャンネルを開く。
+pub async fn detect(audio: &AudioConfig, detect: &DetectConfig) -> Result<DetectionReport> {
+    let physical_channels = AudioInput::new(audio)?.device_channels()?;
+    // 判定は16ビット整数のサンプルで行う
+    let audio = AudioConfig {
+        channels: physical_channels,
+        internal_format: InternalFormat::I16,
+        ..audio.clone()
+    };
+
+    let mut input = AudioInput::new(&audio)?;
+    let mut senders = Vec::new();
+    let mut receivers: Vec<mpsc::Receiver<AudioChunk>> = Vec::new();
+    for _ in 0..physical_channels {
+        let (tx, rx) = mpsc::channel(128);
+        senders.push(tx);
+        receivers.push(rx);
+    }
+
+    let mut detector = ChannelDetector::new(
+        physical_channels as usize,
+        audio.sample_rate,
+        detect.threshold_db,
+    );
+    let drain = |detector: &mut ChannelDetector, receivers: &mut [mpsc::Receiver<AudioChunk>]| {
+        for (channel, rx) in receivers.iter_mut().enumerate() {
+            while let Ok(chunk) = rx.try_recv() {
+                detector.process(channel, &chunk.samples);
+            }
+        }
+    };
+
+    input.start(senders)?;
+    let deadline = Instant::now() + Duration::from_secs(detect.duration_secs as u64);
+    while Instant::now() < deadline {
+        drain(&mut detector, &mut receivers);
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    input.stop();
+    drain(&mut detector, &mut receivers);
+
+    Ok(detector.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16kHz で 100ms のウィンドウ
+    const WINDOW: usize = 1600;
+
+    fn report_for(signal: &[bool]) -> DetectionReport {
+        let mut detector = ChannelDetector::new(signal.len(), 16000, -60.0);
+        for (channel, &has_signal) in signal.iter().enumerate() {
+            let level = if has_signal { 3000 } else { 0 };
+            detector.process(channel, &vec![level; WINDOW]);
+        }
+        detector.report()
+    }
+
+    #[test]
+    fn test_detector_counts_windows_over_threshold() {
+        let mut detector = ChannelDetector::new(2, 16000, -60.0);
+
+        // 約 -21 dB のウィンドウ1つと、約 -70 dB (振幅10) のウィンドウ1つ
+        detector.process(0, &[3000; WINDOW]);
+        detector.process(0, &[10; WINDOW]);
+        // 端数はウィンドウになるまで集計しない
+        detector.process(1, &[3000; WINDOW / 2]);
+        // 範囲外のチャンネルは無視
+        detector.process(5, &[3000; WINDOW]);
+
+        let report = detector.report();
+        let ch0 = &report.channels[0];
+        assert_eq!(ch0.windows, 2);
+        assert_eq!(ch0.active_windows, 1);
+        assert!((ch0.peak_db + 20.8).abs() < 0.1, "peak_db = {}", ch0.peak_db);
+        assert!(ch0.mean_db < ch0.peak_db);
+        assert_eq!(ch0.active_ratio(), 0.5);
+
+        let ch1 = &report.channels[1];
+        assert_eq!(ch1.windows, 0);
+        assert!(!ch1.has_signal());
+
+        detector.process(1, &[3000; WINDOW / 2]);
+        assert!(detector.report().channels[1].has_signal());
+    }
+
+    #[test]
+    fn test_summary() {
+        let report = report_for(&[true, false, true, false, true, false, true, false]);
+        assert_eq!(report.signal_channels(), vec![0, 2, 4, 6]);
+        assert_eq!(report.summary(), "物理8ch中、ch0/2/4/6に信号あり");
+
+        let silent = report_for(&[false, false]);
+        assert_eq!(silent.summary(), "物理2ch中、信号のあるチャンネルはありません");
+        assert_eq!(silent.suggested_config(), None);
+    }
+
+    #[test]
+    fn test_suggested_config_contiguous() {
+        let report = report_for(&[true, true, false, false]);
+        let suggested = report.suggested_config().unwrap();
+        let config: toml::Table = toml::from_str(&suggested).unwrap();
+
+        assert_eq!(config["audio"]["channels"].as_integer(), Some(2));
+        let channels = config["channels"].as_array().unwrap();
+        assert_eq!(channels.len(), 2);
+        assert!(!suggested.contains("信号なし"));
+    }
+
+    #[test]
+    fn test_suggested_config_keeps_gaps_enabled() {
+        let report = report_for(&[true, false, true, false]);
+        let suggested = report.suggested_config().unwrap();
+        let config: toml::Table = toml::from_str(&suggested).unwrap();
+
+        // 物理 ch2 を3番目の有効チャンネルに届けるため、ch1 も有効のまま残す
+        assert_eq!(config["audio"]["channels"].as_integer(), Some(3));
+        let channels = config["channels"].as_array().unwrap();
+        assert_eq!(channels.len(), 3);
+        assert!(channels.iter().all(|c| c["enabled"].as_bool() == Some(true)));
+        assert_eq!(suggested.matches("# 信号なし").count(), 1);
+    }
+}
@@ -1,15 +1,69 @@
+use crate::audit::{AuditEvent, AuditEventKind, AuditSink};
 use crate::aws_transcribe::AwsTranscribeBackend;
 use crate::buffer::AudioBuffer;
-use crate::config::{BufferConfig, ChannelConfig, OutputConfig, TranscribeBackendType, TranscribeConfig, VadConfig, WhisperConfig};
+use crate::clip::{ClipDetector, ClipEvent};
+use crate::cost::{CostMeter, CostSummary};
+use crate::config::{BufferConfig, ChannelConfig, ClipConfig, CostConfig, DisconnectBufferOverflow, DspConfig, FlacConfig, InternalFormat, OutputConfig, PauseInput, PostprocessConfig, RecordMode, TranscribeBackendType, TranscribeConfig, VadConfig, VadRecordFiles, WhisperConfig};
+use crate::encryption::RecordingKey;
+use crate::language::LanguageSwitcher;
+use crate::latency::{FirstResultLatency, FirstResultStats, LatencyCompensator};
+use crate::limiter::PeakLimiter;
+use crate::memory::MemoryUsage;
+use crate::segment_clip::SegmentClipWriter;
+use crate::sidecar::{RecordingMetadata, SpeechCounter};
+use crate::timeline::Timeline;
+use crate::timezone::OutputTimeZone;
 use crate::transcribe::TranscribeClient;
-use crate::transcribe_backend::TranscribeBackend;
-use crate::tui_state::{TranscribeStatus, TuiState};
-use crate::types::{AudioChunk, BufferedChunk, TranscriptResult, VadState};
+use crate::transcribe_backend::{StreamLimiter, TranscribeAudio, TranscribeBackend};
+use crate::tui_state::{BackendSwitchStatus, TranscribeStatus, TranscriptEntry, TuiState};
+use crate::types::{
+    AudioChunk, BufferedChunk, PostprocessStage, PostprocessStep, SampleF32, SampleI16, TranscriptResult, VadEvent,
+    VadEventKind, VadState, quantize_to_i16,
+};
+use crate::session_report::{ChannelSessionStats, RecordedFileStat};
+use crate::upload::UploadSink;
 use crate::vad::VoiceActivityDetector;
-use crate::wav_writer::WavWriter;
+use crate::vad_record::{self, RecordedSegment, VadRecordGate, VadRecordStep};
+use crate::wav_writer::{FilenameTemplate, WavWriter};
 use crate::whisper_api::WhisperBackend;
 use anyhow::{Context, Result};
-use tokio::sync::mpsc;
+use log::LevelFilter;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{OwnedSemaphorePermit, mpsc};
+
+/// チャンネル個別のログレベル（`log_level`）で判定してからログを出力する
+///
+/// グローバルなロガーは `ChannelProcessor` のログを全レベル通すよう設定し、
+/// 出力するかどうかはここでチャンネルごとに決める。
+macro_rules! channel_log {
+    ($filter:expr, $level:ident, $($arg:tt)+) => {
+        if log::Level::$level <= $filter {
+            log::log!(log::Level::$level, $($arg)+);
+        }
+    };
+}
+
+macro_rules! channel_error {
+    ($filter:expr, $($arg:tt)+) => { channel_log!($filter, Error, $($arg)+) };
+}
+
+macro_rules! channel_warn {
+    ($filter:expr, $($arg:tt)+) => { channel_log!($filter, Warn, $($arg)+) };
+}
+
+macro_rules! channel_info {
+    ($filter:expr, $($arg:tt)+) => { channel_log!($filter, Info, $($arg)+) };
+}
+
+macro_rules! channel_debug {
+    ($filter:expr, $($arg:tt)+) => { channel_log!($filter, Debug, $($arg)+) };
+}
+
+macro_rules! channel_trace {
+    ($filter:expr, $($arg:tt)+) => { channel_log!($filter, Trace, $($arg)+) };
+}
 
 /// Transcribe API接続状態
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -20,6 +74,488 @@ enum TranscribeConnectionState {
     Connected,
 }
 
+/// 録音（WAVへの書き込み）を中断する理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingSuspendReason {
+    /// 入力ストリームの復帰処理中
+    InputRecovery,
+    /// `wav_output_dir` の空き容量が `[output] min_free_bytes` を下回った
+    LowDiskSpace,
+}
+
+impl RecordingSuspendReason {
+    fn describe(self) -> &'static str {
+        match self {
+            Self::InputRecovery => "入力の復帰処理",
+            Self::LowDiskSpace => "空き容量の不足",
+        }
+    }
+}
+
+/// 確定結果が届かないまま残った部分結果を確定扱いに昇格させる
+///
+/// 部分結果を受信してから `timeout` の間、確定結果も新しい部分結果も来なければ、
+/// 最後の部分結果を `promoted_from_partial: true` の確定結果として出力する。
+/// 昇格後に同じ発話の結果（開始時刻が近い、または昇格したテキストで始まる）が
+/// 届いた場合は、重複を避けるため破棄する。
+struct PartialFinalizer {
+    timeout: Duration,
+    log_level: LevelFilter,
+    /// 最後に受信した部分結果と受信時刻
+    pending: Option<(TranscriptResult, Instant)>,
+    /// 直近に昇格させた結果
+    promoted: Option<TranscriptResult>,
+}
+
+impl PartialFinalizer {
+    /// 同じ発話とみなす開始時刻の差（秒）
+    const SAME_UTTERANCE_TOLERANCE_SECS: f64 = 0.5;
+
+    fn new(timeout: Duration, log_level: LevelFilter) -> Self {
+        Self {
+            timeout,
+            log_level,
+            pending: None,
+            promoted: None,
+        }
+    }
+
+    /// 受信した結果を通過させ、必要なら昇格した結果を末尾に追加して返す
+    fn process(&mut self, results: Vec<TranscriptResult>, now: Instant) -> Vec<TranscriptResult> {
+        let mut output = Vec::with_capacity(results.len() + 1);
+
+        for result in results {
+            if let Some(promoted) = &self.promoted {
+                if Self::is_same_utterance(promoted, &result) {
+                    if !result.is_partial {
+                        channel_info!(
+                            self.log_level,
+                            "チャンネル {}: 昇格済みの発話に対する確定結果を破棄: '{}'",
+                            result.channel,
+                            result.text
+                        );
+                        self.promoted = None;
+                    }
+                    continue;
+                }
+                // 別の発話が始まったので昇格済みの記録は不要
+                self.promoted = None;
+            }
+
+            if result.is_partial && !result.text.trim().is_empty() {
+                self.pending = Some((result.clone(), now));
+            } else if !result.is_partial {
+                self.pending = None;
+            }
+            output.push(result);
+        }
+
+        let timed_out = self
+            .pending
+            .as_ref()
+            .is_some_and(|(_, received_at)| now.duration_since(*received_at) >= self.timeout);
+        if timed_out {
+            if let Some((mut promoted, _)) = self.pending.take() {
+                channel_info!(
+                    self.log_level,
+                    "チャンネル {}: {}ms 確定結果が来ないため部分結果を確定扱いに昇格: '{}'",
+                    promoted.channel,
+                    self.timeout.as_millis(),
+                    promoted.text
+                );
+                promoted.is_partial = false;
+                promoted.stability = None;
+                promoted.promoted_from_partial = true;
+                self.promoted = Some(promoted.clone());
+                output.push(promoted);
+            }
+        }
+
+        output
+    }
+
+    fn is_same_utterance(promoted: &TranscriptResult, result: &TranscriptResult) -> bool {
+        (promoted.timestamp_seconds - result.timestamp_seconds).abs()
+            < Self::SAME_UTTERANCE_TOLERANCE_SECS
+            || result.text.starts_with(&promoted.text)
+    }
+}
+
+/// 発話区間の長さによる送信判定の結果
+#[derive(Debug, PartialEq, Eq)]
+enum UtteranceAction {
+    /// 通常どおり処理する（保留していた発話冒頭を含む場合がある）
+    Pass(Vec<i16>),
+    /// 最小長に達するまで送信を保留
+    Hold,
+    /// 最大長を超えた（`first` は超過した最初のチャンク）
+    Exceeded { first: bool },
+}
+
+/// VADの音声区間長が範囲外の発話をTranscribeへ送らないためのフィルタ
+///
+/// 最小長に達するまでは発話冒頭を保留し、達した時点でまとめて送信する。
+/// 最小長に達しないまま無音に戻った発話は破棄する。
+/// 最大長を超えた発話は、無音に戻るまで以降の音声を送信しない。
+struct UtteranceFilter {
+    channel_id: usize,
+    log_level: LevelFilter,
+    min_ms: u32,
+    max_ms: Option<u32>,
+    /// 現在の音声区間の長さ（ミリ秒）
+    voice_ms: u32,
+    /// 最小長に達するまで保留している音声
+    pending: Vec<i16>,
+}
+
+impl UtteranceFilter {
+    fn new(channel_id: usize, min_ms: Option<u32>, max_ms: Option<u32>, log_level: LevelFilter) -> Self {
+        Self {
+            channel_id,
+            log_level,
+            min_ms: min_ms.unwrap_or(0),
+            max_ms,
+            voice_ms: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// チャンクごとのVAD判定と送信予定の音声から、送信可否を判定
+    fn process(&mut self, is_voice: bool, samples: Vec<i16>, chunk_ms: u32) -> UtteranceAction {
+        if !is_voice {
+            self.end_utterance();
+            return UtteranceAction::Pass(samples);
+        }
+
+        let previous_ms = self.voice_ms;
+        self.voice_ms += chunk_ms;
+
+        if let Some(max_ms) = self.max_ms {
+            if self.voice_ms > max_ms {
+                let first = previous_ms <= max_ms;
+                if first {
+                    channel_warn!(
+                        self.log_level,
+                        "チャンネル {}: 音声区間が最大長 {}ms を超えたため送信を停止（ゲートが開いたままの可能性、無音に戻るまでスキップ）",
+                        self.channel_id,
+                        max_ms
+                    );
+                    self.pending.clear();
+                }
+                return UtteranceAction::Exceeded { first };
+            }
+        }
+
+        if self.voice_ms < self.min_ms {
+            self.pending.extend_from_slice(&samples);
+            return UtteranceAction::Hold;
+        }
+
+        if self.pending.is_empty() {
+            UtteranceAction::Pass(samples)
+        } else {
+            let mut released = std::mem::take(&mut self.pending);
+            released.extend_from_slice(&samples);
+            UtteranceAction::Pass(released)
+        }
+    }
+
+    /// 音声区間の終了処理（範囲外でスキップした理由をログに残す）
+    fn end_utterance(&mut self) {
+        if self.voice_ms == 0 {
+            return;
+        }
+
+        if self.voice_ms < self.min_ms {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 音声区間 {}ms が最小長 {}ms 未満のため送信をスキップ",
+                self.channel_id,
+                self.voice_ms,
+                self.min_ms
+            );
+        } else if let Some(max_ms) = self.max_ms.filter(|&max_ms| self.voice_ms > max_ms) {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 最大長 {}ms を超えた音声区間が終了 (全体 {}ms)、送信を再開",
+                self.channel_id,
+                max_ms,
+                self.voice_ms
+            );
+        }
+
+        self.voice_ms = 0;
+        self.pending.clear();
+    }
+}
+
+/// 音声確定前の立ち上がりで先行接続する予測接続の状態
+///
+/// 音量がVAD閾値の `MARGIN_DB` 手前まで上がったら投機的に接続し、接続待ちによる語頭の欠けを防ぐ。
+/// 先行接続中は立ち上がり部分の音声もそのまま送信する。
+/// `TIMEOUT_MS` 以内に音声と判定されなければ誤接続として切断し、課金を抑える。
+struct PredictiveConnector {
+    /// 先行接続してから音声が確定していない時間（ミリ秒、先行接続中でなければ None）
+    pending_ms: Option<u32>,
+}
+
+impl PredictiveConnector {
+    /// 閾値からこの値（dB）手前の音量を「立ち上がり中」とみなす
+    const MARGIN_DB: f32 = 6.0;
+    /// 先行接続後、この時間（ミリ秒）以内に音声と判定されなければ切断する
+    const TIMEOUT_MS: u32 = 1500;
+
+    fn new() -> Self {
+        Self { pending_ms: None }
+    }
+
+    /// 立ち上がり中の音量なら先行接続すべき
+    fn should_start(&self, volume_db: f32, threshold_db: f32) -> bool {
+        self.pending_ms.is_none() && volume_db < threshold_db && volume_db >= threshold_db - Self::MARGIN_DB
+    }
+
+    fn start(&mut self) {
+        self.pending_ms = Some(0);
+    }
+
+    fn is_pending(&self) -> bool {
+        self.pending_ms.is_some()
+    }
+
+    /// 先行接続中の状態を解除（音声と確定した場合や切断時）
+    fn reset(&mut self) {
+        self.pending_ms = None;
+    }
+
+    /// 先行接続中の無音チャンク。タイムアウトしたら `true` を返し、状態をリセットする
+    fn on_silence(&mut self, chunk_ms: u32) -> bool {
+        let Some(pending_ms) = self.pending_ms.as_mut() else {
+            return false;
+        };
+        *pending_ms += chunk_ms;
+        if *pending_ms >= Self::TIMEOUT_MS {
+            self.pending_ms = None;
+            return true;
+        }
+        false
+    }
+}
+
+/// デバイス間の遅延補正（`[audio] internal_format` の形式のサンプルを補正する）
+enum ChunkLatencyCompensator {
+    I16(LatencyCompensator<SampleI16>),
+    F32(LatencyCompensator<SampleF32>),
+}
+
+impl ChunkLatencyCompensator {
+    fn new(offset_ms: i32, sample_rate: u32, format: InternalFormat) -> Self {
+        match format {
+            InternalFormat::I16 => Self::I16(LatencyCompensator::new(offset_ms, sample_rate)),
+            InternalFormat::F32 => Self::F32(LatencyCompensator::new(offset_ms, sample_rate)),
+        }
+    }
+
+    /// 補正量（フレーム数、正なら遅延）
+    fn offset_frames(&self) -> i64 {
+        match self {
+            Self::I16(compensator) => compensator.offset_frames(),
+            Self::F32(compensator) => compensator.offset_frames(),
+        }
+    }
+
+    /// チャンクのサンプルを補正し、タイムスタンプを補正量だけずらす
+    fn process(&mut self, chunk: AudioChunk, sample_rate: u32) -> AudioChunk {
+        let channels = chunk.format.channels;
+        match self {
+            Self::I16(compensator) => AudioChunk {
+                samples: compensator.process(&chunk.samples, channels),
+                timestamp_ns: compensator.adjust_timestamp_ns(chunk.timestamp_ns, sample_rate),
+                ..chunk
+            },
+            Self::F32(compensator) => AudioChunk {
+                samples_f32: chunk.samples_f32.map(|samples_f32| compensator.process(&samples_f32, channels)),
+                timestamp_ns: compensator.adjust_timestamp_ns(chunk.timestamp_ns, sample_rate),
+                ..chunk
+            },
+        }
+    }
+}
+
+/// 切断中に再接続時の送信用としてためる音声
+///
+/// チャンク単位で保持し、合計サンプル数は追加・破棄のたびに更新する（チャンクごとに数え直さない）。
+#[derive(Debug, Default)]
+struct DisconnectBuffer {
+    chunks: VecDeque<Vec<i16>>,
+    samples: usize,
+}
+
+impl DisconnectBuffer {
+    fn push(&mut self, samples: Vec<i16>) {
+        self.samples += samples.len();
+        self.chunks.push_back(samples);
+    }
+
+    /// 合計サンプル数
+    fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// チャンク数
+    fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    fn clear(&mut self) {
+        self.chunks.clear();
+        self.samples = 0;
+    }
+
+    /// 先頭（最古）から `excess` サンプルを捨て、捨てたサンプル数を返す
+    fn drop_oldest(&mut self, excess: usize) -> usize {
+        let mut dropped = 0;
+        while dropped < excess {
+            let Some(front) = self.chunks.front_mut() else {
+                break;
+            };
+            let remaining = excess - dropped;
+            if front.len() <= remaining {
+                dropped += front.len();
+                self.chunks.pop_front();
+            } else {
+                front.drain(..remaining);
+                dropped += remaining;
+            }
+        }
+        self.samples -= dropped;
+        dropped
+    }
+
+    /// 再接続時に送信する分を取り出す
+    ///
+    /// `send` が true の場合は蓄積分をすべて返し、false の場合は破棄して空を返す。
+    /// いずれの場合も呼び出し後のバッファは空になる。
+    fn take(&mut self, send: bool) -> VecDeque<Vec<i16>> {
+        self.samples = 0;
+        let backlog = std::mem::take(&mut self.chunks);
+        if send {
+            backlog
+        } else {
+            VecDeque::new()
+        }
+    }
+}
+
+/// 録音ファイルへの書き込み失敗が続いた場合に録音を止めるサーキットブレーカ
+///
+/// ディスク満杯などで書き込みが `FAILURE_THRESHOLD` 回続けて失敗したらオープンし、
+/// `RETRY_INTERVAL` の間は書き込みを試みない（エラーログとI/Oの空回りを防ぐ）。
+/// 間隔が過ぎたら1回だけ書き込みを試し、成功すればクローズ、失敗すれば再びオープンする。
+#[derive(Debug, Default)]
+struct RecordingBreaker {
+    /// 連続した書き込み失敗の回数
+    consecutive_failures: u32,
+    /// オープン中なら、次に書き込みを試す時刻
+    retry_at: Option<Instant>,
+}
+
+impl RecordingBreaker {
+    /// 連続してこの回数失敗したらオープンする
+    const FAILURE_THRESHOLD: u32 = 3;
+    /// オープン後、書き込みを再び試すまでの時間
+    const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// 書き込みを試してよいか（クローズ中、または再試行の時刻を過ぎた）
+    fn allow(&self, now: Instant) -> bool {
+        self.retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    fn is_open(&self) -> bool {
+        self.retry_at.is_some()
+    }
+
+    /// 書き込み成功。オープン中からの回復なら `true` を返す
+    fn record_success(&mut self) -> bool {
+        self.consecutive_failures = 0;
+        self.retry_at.take().is_some()
+    }
+
+    /// 書き込み失敗。新たにオープンした場合は `true` を返す（再試行の失敗は `false`）
+    fn record_failure(&mut self, now: Instant) -> bool {
+        self.consecutive_failures += 1;
+        let was_open = self.is_open();
+        if was_open || self.consecutive_failures >= Self::FAILURE_THRESHOLD {
+            self.retry_at = Some(now + Self::RETRY_INTERVAL);
+        }
+        !was_open && self.is_open()
+    }
+}
+
+/// 録音開始をずらしている間の音声（`[output] recording_start_stagger_ms`）
+///
+/// 他のチャンネルと録音ファイルを開くタイミングをずらす間、入力をメモリに保持し、
+/// ファイルを開いたときに先頭へ書き込む。
+struct PendingWav {
+    /// 保持している音声の先頭の時刻（録音ファイルの開始時刻にする）
+    started_at: SystemTime,
+    samples: Vec<i16>,
+    /// ファイルを開くまでの残りサンプル数
+    remaining_samples: usize,
+}
+
+/// 現在時刻 (UNIX_EPOCHからのナノ秒、`AudioChunk::timestamp_ns` と同じ基準)
+fn unix_now_ns() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// 設定に応じた文字起こしバックエンドを作成
+///
+/// 起動時と、設定リロードでバックエンドの種類が変わった場合（`ChannelProcessor::switch_backend`）に使う。
+pub async fn create_transcribe_backend(
+    channel_id: usize,
+    transcribe_config: &TranscribeConfig,
+    whisper_config: Option<&WhisperConfig>,
+    flac_config: &FlacConfig,
+    start_time: SystemTime,
+) -> Result<Box<dyn TranscribeBackend>> {
+    match transcribe_config.backend {
+        TranscribeBackendType::Aws => Ok(Box::new(
+            AwsTranscribeBackend::new_with_start_time(transcribe_config.clone(), channel_id, start_time)
+                .await
+                .context("Amazon Transcribe バックエンド作成失敗")?
+                .with_flac(flac_config.clone()),
+        )),
+        TranscribeBackendType::Whisper => {
+            let whisper_cfg = whisper_config
+                .ok_or_else(|| anyhow::anyhow!("Whisper設定が見つかりません"))?;
+
+            // WhisperConfig を作成
+            let whisper_backend_config = crate::whisper_api::WhisperConfig {
+                api_key: whisper_cfg.api_key.clone(),
+                model: whisper_cfg.model.clone(),
+                language: whisper_cfg.language.clone(),
+                sample_rate: whisper_cfg.sample_rate,
+                chunk_duration_secs: whisper_cfg.chunk_duration_secs,
+                vad_segmentation: whisper_cfg.vad_segmentation,
+                max_segment_secs: whisper_cfg.max_segment_secs,
+                allowed_languages: transcribe_config.allowed_languages.clone(),
+            };
+
+            Ok(Box::new(
+                WhisperBackend::new_with_start_time(whisper_backend_config, channel_id, start_time)
+                    .await
+                    .context("Whisper API バックエンド作成失敗")?,
+            ))
+        }
+    }
+}
+
+/// VADイベントのフック
+pub type VadHook = Box<dyn Fn(VadEvent) + Send>;
+
 /// 1つのチャンネルの完全な処理パイプライン
 ///
 /// VAD、バッファリング、WAV書き出し、Transcribe送信を統合
@@ -29,81 +565,226 @@ pub struct ChannelProcessor {
     vad: VoiceActivityDetector,
     vad_threshold_db: f32,
     buffer: AudioBuffer,
-    wav_writer: WavWriter,
-    transcribe_tx: Option<mpsc::Sender<Vec<i16>>>,
+    /// 録音WAVの書き出し（`[output] save_wav` が無効なら None）
+    wav_writer: Option<WavWriter>,
+    /// 録音ファイルと発話区間のクリップの暗号鍵（`[output.encryption]` が無効なら None）
+    encryption_key: Option<RecordingKey>,
+    transcribe_tx: Option<mpsc::Sender<TranscribeAudio>>,
     transcribe_rx: Option<mpsc::Receiver<TranscriptResult>>,
     transcribe_backend: Option<Box<dyn TranscribeBackend>>,
+    /// 使用中のバックエンドの名前（ログ・TUI表示用）
+    backend_label: String,
+    /// 切り替え待ちのバックエンドと名前（接続中の発話が終わって切断されたら切り替える）
+    pending_backend: Option<(String, Box<dyn TranscribeBackend>)>,
+    /// 切り替え前のバックエンドと名前（切り替え後の最初の接続に失敗したら戻す）
+    previous_backend: Option<(String, Box<dyn TranscribeBackend>)>,
+    /// 切り替え前・再接続前のストリームの受信側（閉じるまで確定結果を取り出し続ける）
+    draining_rx: Vec<mpsc::Receiver<TranscriptResult>>,
+    /// 接続から最初の文字起こし結果までの時間（初回応答遅延）
+    first_result_latency: FirstResultLatency,
     // 後方互換性のため残す（削除予定）
     #[allow(dead_code)]
     transcribe_client: Option<TranscribeClient>,
     sample_rate: u32,
     tui_state: Option<TuiState>,
-    /// 音声出力用Sender (オプション)
-    audio_output_tx: Option<mpsc::Sender<Vec<i16>>>,
+    /// 音声出力用Sender（このチャンネルを出力中のモニタの番号と組）
+    audio_outputs: Vec<(usize, mpsc::Sender<Vec<i16>>)>,
     /// Transcribe接続状態
     connection_state: TranscribeConnectionState,
+    /// 全チャンネルで共有する同時ストリーム数の上限（`max_concurrent_streams` 未指定なら None）
+    stream_limiter: Option<StreamLimiter>,
+    /// 接続中に保持する同時ストリームの許可（切断時に返す）
+    stream_permit: Option<OwnedSemaphorePermit>,
+    /// 上限に空きがなく接続を待っているか（待ち始めに1回だけ警告する）
+    waiting_for_stream: bool,
     /// 無音継続時間（ミリ秒）
     silence_duration_ms: u32,
     /// 接続切断の無音閾値（ミリ秒）
     silence_threshold_ms: u32,
     /// 切断中に蓄積された音声サンプル
-    buffered_samples_during_disconnect: Vec<Vec<i16>>,
+    buffered_samples_during_disconnect: DisconnectBuffer,
+    /// 切断中バッファの上限（サンプル数、`max_disconnect_buffer_secs`）
+    max_disconnect_buffer_samples: usize,
+    /// 切断中バッファが上限を超えた場合の扱い
+    disconnect_buffer_overflow: DisconnectBufferOverflow,
+    /// 今回の切断中に上限超過で捨てたサンプル数（再接続時にログ出力してリセット）
+    disconnect_dropped_samples: usize,
+    /// 上限超過で捨てたサンプル数の累計
+    total_disconnect_dropped_samples: u64,
+    /// `discard_all` で今回の切断中の再送を諦めたか（再接続までためない）
+    disconnect_buffer_abandoned: bool,
+    /// 直近に受信したチャンクのサイズ（バイト、キューのメモリ使用量の見積もり用）
+    last_chunk_bytes: usize,
     /// 起動時に接続するか
     connect_on_startup: bool,
-    /// 再接続時にバッファを送信するか
+    /// 再接続時にバッファを送信するか（チャンネル個別値で上書き済み）
     send_buffered_on_reconnect: bool,
+    /// 受信したチャンクのチャンネル数（文字起こしの送信時に伝え、モノラル化はバックエンドの送信段で行う）
+    input_channels: u16,
+    /// finalize した録音ファイルのアップロード先 (オプション)
+    upload_sink: Option<UploadSink>,
+    /// 監査ログ（`[audit]` 未設定なら None）
+    audit_sink: Option<AuditSink>,
+    /// JSONL出力のタイムライン（`[output] timeline_events` が無効なら None）
+    timeline: Option<Timeline>,
+    /// 部分結果の確定扱いへの昇格（`partial_finalize_timeout_ms` が 0 の場合は None）
+    partial_finalizer: Option<PartialFinalizer>,
+    /// 起動時に録音ファイルを開くまでの遅れ（サンプル数）
+    recording_start_delay_samples: usize,
+    /// 録音開始をずらしている間に保持している音声（ずらしていなければ None）
+    pending_wav: Option<PendingWav>,
+    /// 録音ファイルへの書き込み失敗のサーキットブレーカ
+    recording_breaker: RecordingBreaker,
+    /// 録音ファイルに書き込んだサンプル数の累計（TUIの録音時間表示用）
+    recorded_samples: u64,
+    /// ウォームアップ期間の残りサンプル数
+    warmup_remaining_samples: usize,
+    /// ウォームアップ期間中に観測した最大振幅
+    warmup_peak: u16,
+    /// 文字起こし結果のタイムスタンプを表すタイムゾーン
+    timezone: OutputTimeZone,
+    /// 所属するチャンネルグループ名
+    group_name: Option<String>,
+    /// 全結果に添付するセッションのメタデータ（未設定なら None）
+    session_metadata: Option<HashMap<String, String>>,
+    /// 入力クリップの監視 (無効時は None)
+    clip_detector: Option<ClipDetector>,
+    /// 発話区間の長さによる送信フィルタ（最小・最大とも未指定なら None）
+    utterance_filter: Option<UtteranceFilter>,
+    /// デバイス間の遅延補正（`latency_offset_ms` が 0 なら None）
+    latency_compensator: Option<ChunkLatencyCompensator>,
+    /// `[channels] latency_offset_ms`（`set_internal_format` で遅延補正を作り直すために保持）
+    latency_offset_ms: i32,
+    /// 予測接続（`predictive_connect` が無効なら None）
+    predictive_connector: Option<PredictiveConnector>,
+    /// 送信音声のピークリミッタ（`[dsp] limiter_threshold_db` が未設定なら None）
+    limiter: Option<PeakLimiter>,
+    /// リミッタの作動回数のうちログに出力済みの回数
+    logged_limiter_activations: u64,
+    /// 発話区間ごとのクリップ書き出し（`[output] save_clips` が無効なら None）
+    segment_clips: Option<SegmentClipWriter>,
+    /// 文字起こし結果の `timestamp_seconds` の基準時刻（`new` では全チャンネル共通の開始時刻）
+    start_time: SystemTime,
+    /// 音声区間だけを録音する場合の切り出し（`record_mode = "continuous"` なら None）
+    vad_record: Option<VadRecordGate>,
+    /// `record_mode = "vad_triggered"` の録音ファイルの分け方
+    vad_record_files: VadRecordFiles,
+    /// 書き込み中のファイルに詰めて書いた録音セグメント（`vad_record_files = "single"` のみ）
+    recorded_segments: Vec<RecordedSegment>,
+    /// 次に開く録音ファイルの開始時刻（音声区間の開始時に、プリロールの先頭の時刻を設定する）
+    next_file_started_at: Option<SystemTime>,
+    /// TUIの波形プレビュー1点あたりのサンプル数（`[ui] show_waveform` が無効なら None）
+    waveform_bucket_len: Option<usize>,
+    /// 発話ごとの自動言語切り替え（`[transcribe] allowed_languages` が未指定なら None）
+    language_switcher: Option<LanguageSwitcher>,
+    /// 録音中のファイルの発話統計（`[output] sidecar_metadata` が無効なら None）
+    recording_speech: Option<SpeechCounter>,
+    /// 送信音声時間と概算コストの積算（`[cost]` が無効なら None）
+    cost_meter: Option<CostMeter>,
+    /// finalize した録音ファイルの実績（セッションレポート用）
+    recorded_files: Vec<RecordedFileStat>,
+    /// VADが音声区間を検出した回数（セッションレポート用）
+    utterances: u64,
+    /// このチャンネルの確定結果の後処理（チャンネル個別の上書きを解決済み）
+    postprocess: PostprocessConfig,
+    /// VADイベントのフック（登録順に呼び出す）
+    vad_hooks: Vec<VadHook>,
+    /// 直前のチャンクのVAD判定（フックの状態変化検出用）
+    last_is_voice: bool,
+    /// 一時停止中か（録音・Transcribe送信を止める）
+    paused: bool,
+    /// 一時停止中の入力の扱い
+    pause_input: PauseInput,
+    /// 録音を中断している理由（いずれかがある間はWAVへの書き込みを止める）
+    recording_suspended: Vec<RecordingSuspendReason>,
+    /// このチャンネルのログレベル（チャンネル個別値、なければ `[output]` の値）
+    log_level: LevelFilter,
 }
 
 impl ChannelProcessor {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         channel_config: &ChannelConfig,
         vad_config: &VadConfig,
         buffer_config: &BufferConfig,
         transcribe_config: &TranscribeConfig,
         whisper_config: Option<&WhisperConfig>,
+        flac_config: &FlacConfig,
         output_config: &OutputConfig,
         sample_rate: u32,
         start_time: std::time::SystemTime,
     ) -> Result<Self> {
-        let vad = VoiceActivityDetector::new(vad_config, sample_rate);
-        let buffer = AudioBuffer::new(buffer_config, sample_rate);
-        let wav_writer = WavWriter::new(
+        let log_level = channel_config.resolve_log_level(output_config);
+
+        channel_info!(
+            log_level,
+            "チャンネル {}: {} バックエンドを使用",
             channel_config.id,
-            &output_config.wav_output_dir,
+            transcribe_config.backend.label()
+        );
+        let transcribe_backend =
+            create_transcribe_backend(channel_config.id, transcribe_config, whisper_config, flac_config, start_time)
+                .await?;
+
+        let mut processor = Self::new_with_backend(
+            channel_config,
+            vad_config,
+            buffer_config,
+            transcribe_config,
+            output_config,
             sample_rate,
+            transcribe_backend,
         )?;
+        processor.start_time = start_time;
+        Ok(processor)
+    }
 
-        // バックエンドを選択して作成
-        let transcribe_backend: Box<dyn TranscribeBackend> = match transcribe_config.backend {
-            TranscribeBackendType::Aws => {
-                log::info!("チャンネル {}: Amazon Transcribe バックエンドを使用", channel_config.id);
-                Box::new(
-                    AwsTranscribeBackend::new(transcribe_config.clone(), channel_config.id, start_time)
-                        .await
-                        .context("Amazon Transcribe バックエンド作成失敗")?,
-                )
+    /// 作成済みの文字起こしバックエンドを使ってプロセッサを作成
+    ///
+    /// `transcribe_config.backend` は参照せず、渡されたバックエンドをそのまま使う
+    /// （テスト用のモックや独自バックエンドの差し込みに使う）。
+    pub fn new_with_backend(
+        channel_config: &ChannelConfig,
+        vad_config: &VadConfig,
+        buffer_config: &BufferConfig,
+        transcribe_config: &TranscribeConfig,
+        output_config: &OutputConfig,
+        sample_rate: u32,
+        transcribe_backend: Box<dyn TranscribeBackend>,
+    ) -> Result<Self> {
+        let timezone = OutputTimeZone::parse(&output_config.timezone)?;
+        let log_level = channel_config.resolve_log_level(output_config);
+        let vad = VoiceActivityDetector::new(vad_config, sample_rate);
+        let buffer = AudioBuffer::new(buffer_config, sample_rate);
+        let mut wav_writer = output_config
+            .save_wav
+            .then(|| WavWriter::new(channel_config.id, &output_config.wav_output_dir, sample_rate))
+            .transpose()?;
+        if let (Some(wav_writer), Some(secs)) = (&mut wav_writer, output_config.wav_rotation_secs) {
+            if channel_config.resolve_record_mode(output_config) == RecordMode::VadTriggered
+                && output_config.vad_record_files == VadRecordFiles::Single
+            {
+                // 音声区間の一覧（セグメントのオフセット）がファイルをまたげないため分割しない
+                channel_warn!(
+                    log_level,
+                    "チャンネル {}: vad_record_files = \"single\" では wav_rotation_secs を無視します",
+                    channel_config.id
+                );
+            } else {
+                wav_writer.set_rotation_interval_secs(Some(secs));
             }
-            TranscribeBackendType::Whisper => {
-                log::info!("チャンネル {}: OpenAI Whisper API バックエンドを使用", channel_config.id);
-                let whisper_cfg = whisper_config
-                    .ok_or_else(|| anyhow::anyhow!("Whisper設定が見つかりません"))?;
-
-                // WhisperConfig を作成
-                let whisper_backend_config = crate::whisper_api::WhisperConfig {
-                    api_key: whisper_cfg.api_key.clone(),
-                    model: whisper_cfg.model.clone(),
-                    language: whisper_cfg.language.clone(),
-                    sample_rate: whisper_cfg.sample_rate,
-                    chunk_duration_secs: whisper_cfg.chunk_duration_secs,
-                };
+        }
 
-                Box::new(
-                    WhisperBackend::new(whisper_backend_config, channel_config.id, start_time)
-                        .await
-                        .context("Whisper API バックエンド作成失敗")?,
-                )
-            }
-        };
+        let send_buffered_on_reconnect =
+            channel_config.resolve_send_buffered_on_reconnect(transcribe_config);
+        if channel_config.send_buffered_on_reconnect.is_some() {
+            channel_info!(
+                log_level,
+                "チャンネル {}: 再接続時バッファ送信をチャンネル個別設定で上書き ({})",
+                channel_config.id,
+                send_buffered_on_reconnect
+            );
+        }
 
         Ok(Self {
             channel_id: channel_config.id,
@@ -112,19 +793,98 @@ impl ChannelProcessor {
             vad_threshold_db: vad_config.threshold_db,
             buffer,
             wav_writer,
+            encryption_key: None,
             transcribe_tx: None,
             transcribe_rx: None,
             transcribe_backend: Some(transcribe_backend),
+            backend_label: transcribe_config.backend.label().to_string(),
+            pending_backend: None,
+            previous_backend: None,
+            draining_rx: Vec::new(),
+            first_result_latency: FirstResultLatency::default(),
             transcribe_client: None,
             sample_rate,
             tui_state: None,
-            audio_output_tx: None,
+            audio_outputs: Vec::new(),
             connection_state: TranscribeConnectionState::Disconnected,
+            stream_limiter: None,
+            stream_permit: None,
+            waiting_for_stream: false,
             silence_duration_ms: 0,
             silence_threshold_ms: vad_config.silence_disconnect_threshold_ms,
-            buffered_samples_during_disconnect: Vec::new(),
+            buffered_samples_during_disconnect: DisconnectBuffer::default(),
+            max_disconnect_buffer_samples: (sample_rate as u64 * transcribe_config.max_disconnect_buffer_secs as u64)
+                as usize,
+            disconnect_buffer_overflow: transcribe_config.disconnect_buffer_overflow,
+            disconnect_dropped_samples: 0,
+            total_disconnect_dropped_samples: 0,
+            disconnect_buffer_abandoned: false,
+            last_chunk_bytes: 0,
             connect_on_startup: transcribe_config.connect_on_startup,
-            send_buffered_on_reconnect: transcribe_config.send_buffered_on_reconnect,
+            send_buffered_on_reconnect,
+            input_channels: 1,
+            upload_sink: None,
+            audit_sink: None,
+            timeline: None,
+            partial_finalizer: (transcribe_config.partial_finalize_timeout_ms > 0).then(|| {
+                PartialFinalizer::new(
+                    Duration::from_millis(transcribe_config.partial_finalize_timeout_ms),
+                    log_level,
+                )
+            }),
+            warmup_remaining_samples: 0,
+            warmup_peak: 0,
+            timezone,
+            group_name: None,
+            session_metadata: None,
+            clip_detector: None,
+            utterance_filter: (channel_config.min_utterance_ms.is_some()
+                || channel_config.max_utterance_ms.is_some())
+            .then(|| {
+                UtteranceFilter::new(
+                    channel_config.id,
+                    channel_config.min_utterance_ms,
+                    channel_config.max_utterance_ms,
+                    log_level,
+                )
+            }),
+            latency_compensator: (channel_config.latency_offset_ms != 0).then(|| {
+                ChunkLatencyCompensator::new(channel_config.latency_offset_ms, sample_rate, InternalFormat::I16)
+            }),
+            latency_offset_ms: channel_config.latency_offset_ms,
+            predictive_connector: transcribe_config.predictive_connect.then(PredictiveConnector::new),
+            limiter: None,
+            logged_limiter_activations: 0,
+            segment_clips: None,
+            start_time: SystemTime::now(),
+            vad_record: (output_config.save_wav
+                && channel_config.resolve_record_mode(output_config) == RecordMode::VadTriggered)
+                .then(|| VadRecordGate::new(output_config.vad_preroll_ms, sample_rate)),
+            vad_record_files: output_config.vad_record_files,
+            recorded_segments: Vec::new(),
+            next_file_started_at: None,
+            waveform_bucket_len: None,
+            language_switcher: transcribe_config.auto_language().then(|| {
+                LanguageSwitcher::new(
+                    transcribe_config.allowed_languages.clone(),
+                    &transcribe_config.language_code,
+                )
+            }),
+            recording_speech: None,
+            recorded_files: Vec::new(),
+            utterances: 0,
+            cost_meter: None,
+            postprocess: PostprocessConfig::default(),
+            recording_start_delay_samples: 0,
+            pending_wav: None,
+            recording_breaker: RecordingBreaker::default(),
+            recorded_samples: 0,
+            vad_hooks: Vec::new(),
+            last_is_voice: false,
+            paused: false,
+            pause_input: output_config.pause_input,
+            recording_suspended: Vec::new(),
+            log_level,
         })
     }
 
@@ -137,125 +897,719 @@ impl ChannelProcessor {
         self.tui_state = Some(tui_state);
     }
 
-    /// 音声出力用Senderを設定
-    pub fn set_audio_output(&mut self, tx: mpsc::Sender<Vec<i16>>) {
-        self.audio_output_tx = Some(tx);
+    /// 所属するチャンネルグループを設定
+    pub fn set_group(&mut self, group_name: String) {
+        self.group_name = Some(group_name);
     }
 
-    /// 音声出力用Senderをクリア
-    pub fn clear_audio_output(&mut self) {
-        self.audio_output_tx = None;
+    /// 全結果に添付するセッションのメタデータを設定（空なら添付しない）
+    pub fn set_session_metadata(&mut self, metadata: &HashMap<String, String>) {
+        self.session_metadata = (!metadata.is_empty()).then(|| metadata.clone());
     }
 
-    /// 処理を開始
-    pub async fn start(&mut self) -> Result<()> {
-        log::info!(
-            "チャンネル {} ({}) の処理を開始",
+    /// 録音ファイル名のテンプレートを設定（次に開始する録音ファイルから適用）
+    pub fn set_filename_template(&mut self, template: FilenameTemplate) {
+        if let Some(wav_writer) = &mut self.wav_writer {
+            wav_writer.set_filename_template(template, &self.channel_name);
+        }
+    }
+
+    /// 録音ファイルと発話区間のクリップを暗号化して保存する（次に開始するファイルから適用）
+    pub fn set_encryption_key(&mut self, key: RecordingKey) {
+        if let Some(clips) = &mut self.segment_clips {
+            clips.set_encryption_key(key.clone());
+        }
+        if let Some(wav_writer) = &mut self.wav_writer {
+            wav_writer.set_encryption_key(key.clone());
+        }
+        self.encryption_key = Some(key);
+    }
+
+    /// 文字起こしバックエンドを切り替える（設定リロードでバックエンドの種類が変わった場合）
+    ///
+    /// 録音は止めない。接続中なら発話が終わって無音で切断されるのを待ち、未接続ならすぐに切り替える。
+    /// 新しいバックエンドは次の音声検出から使い、切り替え前のストリームの確定結果は
+    /// `poll_transcripts` で取り切る。切り替え後の最初の接続に失敗した場合は元のバックエンドに戻す。
+    pub fn switch_backend(&mut self, label: &str, backend: Box<dyn TranscribeBackend>) {
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: 文字起こしバックエンドを {} から {} に切り替えます",
             self.channel_id,
-            self.channel_name
+            self.backend_label,
+            label
         );
+        self.pending_backend = Some((label.to_string(), backend));
+        self.set_backend_switch_status(BackendSwitchStatus::Pending(label.to_string()));
 
-        // WAVファイル書き込みを開始
-        self.wav_writer.start()?;
-
-        // connect_on_startupがtrueの場合のみ起動時に接続
-        if self.connect_on_startup {
-            log::info!(
-                "チャンネル {}: 起動時にTranscribe接続を開始",
-                self.channel_id
-            );
-            self.reconnect_transcribe().await?;
+        if self.connection_state == TranscribeConnectionState::Disconnected {
+            self.apply_pending_backend();
         } else {
-            log::info!(
-                "チャンネル {}: 音声検出まで接続を待機",
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 接続中の発話が終わってから切り替えます",
                 self.channel_id
             );
-            // TUI状態を未接続に設定
-            if let Some(tui_state) = &self.tui_state {
-                tui_state.update_channel(self.channel_id, |channel| {
-                    channel.update_transcribe_status(TranscribeStatus::Disconnected);
-                });
-            }
         }
-
-        Ok(())
     }
 
-    /// 音声チャンクを処理
-    pub async fn process_chunk(&mut self, chunk: AudioChunk) -> Result<()> {
-        use std::time::Instant;
-        let start_instant = Instant::now();
-
-        let samples = &chunk.samples;
-
-        // 1. WAVファイルに書き込み（無音含む全データ）
-        self.wav_writer.write_samples(samples)?;
+    /// 切り替え待ちのバックエンドに切り替える（未接続の間に呼ぶ）
+    fn apply_pending_backend(&mut self) {
+        let Some((label, mut backend)) = self.pending_backend.take() else {
+            return;
+        };
+        if let Some(switcher) = &self.language_switcher {
+            backend.set_language(switcher.current());
+        }
 
-        // 2. バッファに追加
-        self.buffer.push(BufferedChunk {
-            samples: samples.clone(),
-            timestamp_ns: chunk.timestamp_ns,
-        });
+        let previous_label = std::mem::replace(&mut self.backend_label, label.clone());
+        if let Some(previous) = self.transcribe_backend.replace(backend) {
+            self.previous_backend = Some((previous_label, previous));
+        }
+        // 切り替え前のストリームがまだ返していない確定結果を受け取れるよう残す
+        if let Some(rx) = self.transcribe_rx.take() {
+            self.draining_rx.push(rx);
+        }
 
-        // 3. VADで音声区間を判定
-        let is_voice = self.vad.process(samples);
-        let volume_db = self.vad.get_last_volume_db();
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: 文字起こしバックエンドを {} に切り替えました（次の音声検出から使用）",
+            self.channel_id,
+            label
+        );
+        self.set_backend_switch_status(BackendSwitchStatus::Switched(label));
+    }
 
-        // 4. TUI状態を更新
+    fn set_backend_switch_status(&self, status: BackendSwitchStatus) {
         if let Some(tui_state) = &self.tui_state {
-            let volume_db = self.vad.get_last_volume_db();
-            let vad_state = self.vad.get_state();
             tui_state.update_channel(self.channel_id, |channel| {
-                channel.update_volume(volume_db);
-                channel.update_vad_state(vad_state);
+                channel.backend_switch = Some(status);
             });
         }
+    }
 
-        // 5. チャンク時間を計算（ミリ秒）
-        let chunk_duration_ms = (samples.len() as f64 / self.sample_rate as f64 * 1000.0) as u32;
+    /// 録音ファイルのアップロード先を設定
+    pub fn set_upload_sink(&mut self, upload_sink: UploadSink) {
+        self.upload_sink = Some(upload_sink);
+    }
 
-        // 6. 接続状態に応じた処理
-        match (is_voice, &self.connection_state) {
-            // 音声検出 + 未接続 → 再接続 + バッファ送信
-            (true, TranscribeConnectionState::Disconnected) => {
-                // バッファサイズを計算（メトリクス収集）
-                let total_buffered_samples: usize = self.buffered_samples_during_disconnect
-                    .iter()
-                    .map(|chunk| chunk.len())
-                    .sum();
-                let buffered_duration_ms = (total_buffered_samples as f64 / self.sample_rate as f64 * 1000.0) as u32;
+    /// 全チャンネルで共有する同時ストリーム数の上限を設定（`init` より前に呼ぶ）
+    pub fn set_stream_limiter(&mut self, limiter: StreamLimiter) {
+        self.stream_limiter = Some(limiter);
+    }
 
-                log::info!(
-                    "チャンネル {}: ★音声検出★ Transcribe再接続を開始 (音量: {:.2} dB, バッファ: {}チャンク, {}ms相当)",
+    /// VAD・接続状態の遷移を記録する監査ログを設定
+    pub fn set_audit_sink(&mut self, audit_sink: AuditSink) {
+        self.audit_sink = Some(audit_sink);
+    }
+
+    /// 録音ファイルの開始・終了と接続・切断をJSONL出力のタイムラインに書き込む（`[output] timeline_events`）
+    pub fn set_timeline(&mut self, timeline: Timeline) {
+        if let Some(wav_writer) = &mut self.wav_writer {
+            wav_writer.set_timeline(timeline.clone());
+        }
+        self.timeline = Some(timeline);
+    }
+
+    /// 監査ログとタイムラインにイベントを記録（どちらも無効ならイベントを作らない）
+    fn audit(&self, event: impl FnOnce() -> AuditEvent) {
+        if self.audit_sink.is_none() && self.timeline.is_none() {
+            return;
+        }
+        let event = event();
+        if let Some(timeline) = &self.timeline {
+            timeline.record_audit(&event);
+        }
+        if let Some(audit_sink) = &self.audit_sink {
+            audit_sink.record(event);
+        }
+    }
+
+    /// 入力開始直後のウォームアップ期間を設定
+    ///
+    /// 期間中はVAD判定・Transcribe送信・音声出力を行わず、WAVには無音を書き込む。
+    /// 期間はチャンク単位で判定するため、最大1チャンク分長くなる。
+    pub fn set_warmup_ms(&mut self, warmup_ms: u32) {
+        self.warmup_remaining_samples =
+            (self.sample_rate as u64 * warmup_ms as u64 / 1000) as usize;
+        self.warmup_peak = 0;
+    }
+
+    /// 起動時に録音ファイルを開くのを `delay_ms` だけ遅らせる（`start` より前に呼ぶ）
+    ///
+    /// 遅らせている間の入力は保持し、ファイルを開いたときに先頭へ書き込む。
+    pub fn set_recording_start_delay_ms(&mut self, delay_ms: u32) {
+        self.recording_start_delay_samples = (self.sample_rate as u64 * delay_ms as u64 / 1000) as usize;
+    }
+
+    /// VADの観測用閾値を設定する（`[debug] vad_observe_thresholds`）
+    ///
+    /// 判定には影響せず、各閾値での音声区間率を音声1分ごとと停止時にログ出力する。
+    pub fn set_vad_observe_thresholds(&mut self, thresholds_db: &[f32]) {
+        self.vad.set_observe_thresholds(thresholds_db);
+    }
+
+    /// VADの観測用閾値の集計がたまっていればログ出力する
+    fn log_vad_observations(&mut self, force: bool) {
+        let Some((observations, total_ms)) = self.vad.take_observations(force) else {
+            return;
+        };
+        let summary = observations
+            .iter()
+            .map(|observation| {
+                format!(
+                    "{:.1}dB{} 音声率 {:.1}% (区間 {})",
+                    observation.threshold_db,
+                    if observation.is_active { "(本番)" } else { "" },
+                    observation.voice_ratio(total_ms) * 100.0,
+                    observation.segments
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: VAD観測 {:.1}秒: {}",
+            self.channel_id,
+            total_ms as f64 / 1000.0,
+            summary
+        );
+    }
+
+    /// VADの音声開始・終了時に呼ばれるフックを登録
+    ///
+    /// フックは登録順に、チャンク処理のスレッドから同期的に呼ばれる。
+    /// TUI更新やTranscribe制御とは独立しており、時間のかかる処理は
+    /// フック内で別タスクに渡すこと。
+    pub fn add_vad_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(VadEvent) + Send + 'static,
+    {
+        self.vad_hooks.push(Box::new(hook));
+    }
+
+    /// VAD判定の変化をフックに通知
+    fn notify_vad_hooks(&mut self, is_voice: bool, timestamp_ns: u128, volume_db: f32) {
+        if is_voice == self.last_is_voice {
+            return;
+        }
+        self.last_is_voice = is_voice;
+        if is_voice {
+            self.utterances += 1;
+        }
+
+        let event = VadEvent {
+            channel_id: self.channel_id,
+            kind: if is_voice {
+                VadEventKind::VoiceStart
+            } else {
+                VadEventKind::VoiceEnd
+            },
+            timestamp_ns,
+            volume_db,
+        };
+        for hook in &self.vad_hooks {
+            hook(event);
+        }
+        self.audit(|| {
+            let kind = match event.kind {
+                VadEventKind::VoiceStart => AuditEventKind::VoiceStart,
+                VadEventKind::VoiceEnd => AuditEventKind::VoiceEnd,
+            };
+            AuditEvent::new(kind, event.channel_id, event.timestamp_ns).with_volume_db(event.volume_db)
+        });
+    }
+
+    /// 発話区間ごとのクリップを `output_dir` に保存する
+    ///
+    /// 確定結果は音声の時刻から対応する区間のクリップに紐づけ、`clip_path` に設定する。
+    pub fn enable_segment_clips<P: AsRef<Path>>(&mut self, output_dir: P) -> Result<()> {
+        let mut clips = SegmentClipWriter::new(self.channel_id, output_dir, self.sample_rate)?;
+        if let Some(key) = &self.encryption_key {
+            clips.set_encryption_key(key.clone());
+        }
+        self.segment_clips = Some(clips);
+        Ok(())
+    }
+
+    /// 録音WAVの finalize 時に人手の注釈用のサイドカーYAMLを生成する
+    pub fn enable_sidecar_metadata(&mut self) {
+        self.recording_speech = Some(SpeechCounter::default());
+    }
+
+    /// 文字起こしバックエンドへの送信音声時間を積算し、概算コストをTUIとログに出す
+    pub fn enable_cost_tracking(&mut self, config: &CostConfig) {
+        self.cost_meter = Some(CostMeter::new(config, self.sample_rate));
+    }
+
+    /// 入力チャンクのサンプル形式（`[audio] internal_format`）を設定する（既定は i16）
+    ///
+    /// 遅延補正はこの形式のサンプルだけに行う。`start` の前に呼ぶ。
+    pub fn set_internal_format(&mut self, format: InternalFormat) {
+        if self.latency_compensator.is_some() {
+            self.latency_compensator = Some(ChunkLatencyCompensator::new(self.latency_offset_ms, self.sample_rate, format));
+        }
+    }
+
+    /// 送信音声にピークリミッタをかける（`[dsp] limiter_threshold_db` 設定時、WAV録音は無加工のまま）
+    pub fn enable_limiter(&mut self, config: &DspConfig) {
+        let Some(threshold_db) = config.limiter_threshold_db else {
+            return;
+        };
+        let limiter = PeakLimiter::new(
+            threshold_db,
+            config.limiter_release_ms,
+            config.limiter_lookahead_ms,
+            self.sample_rate,
+        );
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: 送信音声のリミッタを有効化 (閾値 {:.1} dBFS, リリース {}ms, 先読みによる遅延 {}サンプル)",
+            self.channel_id,
+            threshold_db,
+            config.limiter_release_ms,
+            limiter.latency_samples()
+        );
+        self.limiter = Some(limiter);
+    }
+
+    /// 送信音声にリミッタをかけ、新たに作動した場合はログに出す
+    fn apply_limiter(&mut self, samples: Vec<i16>) -> Vec<i16> {
+        let Some(limiter) = &mut self.limiter else {
+            return samples;
+        };
+        let limited = limiter.process(&samples);
+        let activations = limiter.activations();
+        if activations > self.logged_limiter_activations {
+            self.logged_limiter_activations = activations;
+            channel_debug!(
+                self.log_level,
+                "チャンネル {}: リミッタ作動 (累計 {}回)",
+                self.channel_id,
+                activations
+            );
+        }
+        limited
+    }
+
+    /// 文字起こしバックエンドへ送る音声（受信したチャンネル数のまま送り、バックエンドがモノラルにする）
+    fn transcribe_audio(&self, samples: Vec<i16>) -> TranscribeAudio {
+        TranscribeAudio::new(samples, self.input_channels)
+    }
+
+    /// セッション中の録音実績（finalize したファイル・発話回数・概算コスト）
+    pub fn session_stats(&self) -> ChannelSessionStats {
+        ChannelSessionStats {
+            channel: self.channel_id,
+            channel_name: self.channel_name.clone(),
+            utterances: self.utterances,
+            estimated_cost: self.cost_summary().map(|summary| summary.cost),
+            files: self.recorded_files.clone(),
+        }
+    }
+
+    /// finalize したファイルの時間と実ファイルのサイズを記録
+    fn record_file_stat(&mut self, path: &Path, duration_secs: f64) {
+        let bytes = match std::fs::metadata(path) {
+            Ok(metadata) => metadata.len(),
+            Err(e) => {
+                channel_warn!(self.log_level, "チャンネル {}: 録音ファイルのサイズを取得できません: {:?}: {}", self.channel_id, path, e);
+                0
+            }
+        };
+        self.recorded_files.push(RecordedFileStat {
+            path: path.display().to_string(),
+            duration_secs,
+            bytes,
+        });
+    }
+
+    /// 送信音声時間と概算コスト（`[cost]` が無効なら None）
+    pub fn cost_summary(&self) -> Option<CostSummary> {
+        self.cost_meter.as_ref().map(CostMeter::summary)
+    }
+
+    /// 送信音声時間と概算コストのバックエンドごとの内訳（`[cost]` が無効なら None）
+    pub fn cost_breakdown(&self) -> Option<String> {
+        self.cost_meter.as_ref().map(CostMeter::describe)
+    }
+
+    /// 文字起こしバックエンドへ送信できたサンプル数を積算
+    fn record_sent(&mut self, samples: usize) {
+        let Some(meter) = &mut self.cost_meter else {
+            return;
+        };
+        // 多チャンネルの場合はインターリーブされたサンプル数のため、フレーム数（モノラル化後の長さ）で数える
+        let samples = samples / self.input_channels.max(1) as usize;
+        if samples == 0 {
+            return;
+        }
+        meter.add(&self.backend_label, samples);
+        if let Some(tui_state) = &self.tui_state {
+            let summary = meter.summary();
+            tui_state.update_channel(self.channel_id, |channel| {
+                channel.cost = Some(summary);
+            });
+        }
+    }
+
+    /// TUIに波形プレビュー用のサンプルを送る
+    ///
+    /// 描画負荷を抑えるため、10ms ごとの最小値・最大値に間引いて送る。
+    pub fn enable_waveform_preview(&mut self) {
+        self.waveform_bucket_len = Some((self.sample_rate as usize / 100).max(1));
+    }
+
+    /// 入力クリップの監視を設定
+    pub fn set_clip_detection(&mut self, config: &ClipConfig) {
+        self.clip_detector = config
+            .enabled
+            .then(|| ClipDetector::new(config, self.sample_rate));
+    }
+
+    /// 入力クリップを監視し、状態が変化したらログとTUIに反映
+    fn check_clipping(&mut self, samples: &[i16]) {
+        let Some(event) = self.clip_detector.as_mut().and_then(|d| d.process(samples)) else {
+            return;
+        };
+
+        let clip_reduction_db = match event {
+            ClipEvent::Warn {
+                ratio,
+                suggested_reduction_db,
+            } => {
+                channel_warn!(
+                    self.log_level,
+                    "チャンネル {} ({}): 入力がクリップしています (クリップ率 {:.2}%)。入力ゲインを下げてください（推奨 -{:.0} dB）",
+                    self.channel_id,
+                    self.channel_name,
+                    ratio * 100.0,
+                    suggested_reduction_db
+                );
+                Some(suggested_reduction_db)
+            }
+            ClipEvent::Cleared => {
+                channel_info!(
+                    self.log_level,
+                    "チャンネル {} ({}): 入力のクリップが解消しました",
+                    self.channel_id,
+                    self.channel_name
+                );
+                None
+            }
+        };
+
+        if let Some(tui_state) = &self.tui_state {
+            tui_state.update_channel(self.channel_id, |channel| {
+                channel.clip_reduction_db = clip_reduction_db;
+            });
+        }
+    }
+
+    /// `monitor` 番目のモニタ出力の音声出力用Senderを設定（同じモニタの既存のSenderは置き換える）
+    pub fn set_audio_output(&mut self, monitor: usize, tx: mpsc::Sender<Vec<i16>>) {
+        self.clear_audio_output(monitor);
+        self.audio_outputs.push((monitor, tx));
+    }
+
+    /// `monitor` 番目のモニタ出力の音声出力用Senderをクリア
+    pub fn clear_audio_output(&mut self, monitor: usize) {
+        self.audio_outputs.retain(|(index, _)| *index != monitor);
+    }
+
+    /// 処理を開始
+    pub async fn start(&mut self) -> Result<()> {
+        channel_info!(
+            self.log_level,
+            "チャンネル {} ({}) の処理を開始",
+            self.channel_id,
+            self.channel_name
+        );
+
+        // WAVファイル書き込みを開始（ずらす場合はそれまでの入力を保持する）
+        if self.wav_writer.is_none() {
+            channel_info!(self.log_level, "チャンネル {}: WAV保存は無効です（文字起こしのみ）", self.channel_id);
+        } else if self.vad_record.is_some() {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 音声区間だけを録音します（録音ファイルは音声の検出時に開く）",
+                self.channel_id
+            );
+        } else if self.recording_start_delay_samples > 0 {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 録音ファイルを {}ms 遅れて開きます（それまでの音声は保持）",
+                self.channel_id,
+                self.recording_start_delay_samples as u64 * 1000 / self.sample_rate as u64
+            );
+            self.pending_wav = Some(PendingWav {
+                started_at: SystemTime::now(),
+                samples: Vec::with_capacity(self.recording_start_delay_samples),
+                remaining_samples: self.recording_start_delay_samples,
+            });
+        } else if let Some(wav_writer) = &mut self.wav_writer {
+            wav_writer.start()?;
+        }
+
+        if self.warmup_remaining_samples > 0 {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: ウォームアップ期間 {}ms はVAD判定・Transcribe送信を抑制",
+                self.channel_id,
+                self.warmup_remaining_samples as u64 * 1000 / self.sample_rate as u64
+            );
+        }
+
+        if let Some(compensator) = &self.latency_compensator {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 遅延補正 {}フレーム ({})",
+                self.channel_id,
+                compensator.offset_frames(),
+                if compensator.offset_frames() > 0 { "遅延追加" } else { "先行" }
+            );
+        }
+
+        // connect_on_startupがtrueの場合のみ起動時に接続（同時ストリーム数の上限に空きがなければ音声検出まで待つ）
+        if self.connect_on_startup && self.acquire_stream_permit() {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 起動時にTranscribe接続を開始",
+                self.channel_id
+            );
+            self.reconnect_transcribe().await?;
+        } else {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 音声検出まで接続を待機",
+                self.channel_id
+            );
+            // TUI状態を未接続に設定
+            if let Some(tui_state) = &self.tui_state {
+                tui_state.update_channel(self.channel_id, |channel| {
+                    channel.update_transcribe_status(TranscribeStatus::Disconnected);
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 音声チャンクを処理
+    pub async fn process_chunk(&mut self, chunk: AudioChunk) -> Result<()> {
+        use std::time::Instant;
+        let start_instant = Instant::now();
+        self.last_chunk_bytes = chunk.samples.len() * std::mem::size_of::<SampleI16>()
+            + chunk.samples_f32.as_ref().map_or(0, |s| s.len() * std::mem::size_of::<SampleF32>());
+
+        // デバイス間の遅延差を補正（WAVを含む以降の処理すべてに反映）
+        let mut chunk = match &mut self.latency_compensator {
+            Some(compensator) => compensator.process(chunk, self.sample_rate),
+            None => chunk,
+        };
+        // f32 モードでは遅延補正・VADを量子化前のサンプルで行い、リミッタ・WAV書き込み・Transcribe送信用にここで量子化する
+        let samples_f32 = chunk.samples_f32.take();
+        if let Some(samples_f32) = &samples_f32 {
+            chunk.samples = samples_f32.iter().copied().map(quantize_to_i16).collect();
+        }
+        if chunk.samples.is_empty() {
+            // 先行させる分の読み捨て中
+            return Ok(());
+        }
+
+        if self.paused {
+            if self.pause_input == PauseInput::Buffer {
+                // 再開時に新しいWAVの先頭に書き込むため保持（容量を超えた分はドロップポリシーに従う）
+                // 一時停止中はVADを通さないため、無音として扱う
+                self.buffer.push(BufferedChunk {
+                    samples: chunk.samples,
+                    timestamp_ns: chunk.timestamp_ns,
+                    is_voice: false,
+                });
+            }
+            return Ok(());
+        }
+
+        let samples = &chunk.samples;
+
+        self.input_channels = chunk.format.channels;
+        // 起動直後のポップノイズ・DC過渡でVADが誤発火しないよう、ウォームアップ中は無音として扱う
+        if self.warmup_remaining_samples > 0 {
+            return self.process_warmup_chunk(samples);
+        }
+
+        let send_samples = self.apply_limiter(samples.clone());
+
+        // 1. WAVファイルに書き込み（無音含む全データ、音声区間だけを録音する場合はVAD判定後）
+        if self.vad_record.is_none() {
+            self.write_wav(samples);
+        }
+
+        // 2. VADで音声区間を判定
+        let is_voice = match &samples_f32 {
+            Some(samples_f32) => self.vad.process_f32(samples_f32),
+            None => self.vad.process(samples),
+        };
+        self.log_vad_observations(false);
+        self.write_wav_vad_triggered(is_voice, samples, chunk.timestamp_ns);
+
+        // 3. バッファに追加（ドロップポリシーが KeepVoice なら判定結果で破棄する順が変わる）
+        self.buffer.push(BufferedChunk {
+            samples: samples.clone(),
+            timestamp_ns: chunk.timestamp_ns,
+            is_voice,
+        });
+        if let Some(speech) = &mut self.recording_speech {
+            speech.process(is_voice, samples.len());
+        }
+        self.check_clipping(samples);
+        // 録音の中断中はクリップも書かない（中断時に書き込み中のクリップは閉じている）
+        if self.recording_suspended.is_empty() {
+            if let Some(clips) = &mut self.segment_clips {
+                if let Err(e) = clips.process(is_voice, samples, chunk.timestamp_ns) {
+                    // クリップは補助的な出力のため、失敗しても録音・文字起こしは継続する
+                    channel_warn!(self.log_level, "チャンネル {}: 発話クリップの書き込みに失敗: {}", self.channel_id, e);
+                }
+            }
+        }
+        let volume_db = self.vad.get_last_volume_db();
+        self.notify_vad_hooks(is_voice, chunk.timestamp_ns, volume_db);
+
+        // 4. TUI状態を更新
+        if let Some(tui_state) = &self.tui_state {
+            let volume_db = self.vad.get_last_volume_db();
+            let vad_state = self.vad.get_state();
+            let waveform_bucket_len = self.waveform_bucket_len;
+            let recorded_secs = self.recorded_samples as f64 / self.sample_rate as f64;
+            tui_state.update_channel(self.channel_id, |channel| {
+                channel.recorded_secs = recorded_secs;
+                channel.update_volume(volume_db);
+                channel.update_vad_state(vad_state);
+                if let Some(bucket_len) = waveform_bucket_len {
+                    channel.push_waveform(samples, bucket_len);
+                }
+            });
+        }
+
+        // 5. チャンク時間を計算（ミリ秒）
+        let chunk_duration_ms = (samples.len() as f64 / self.sample_rate as f64 * 1000.0) as u32;
+
+        // 発話区間の長さが範囲外なら送信しない（WAVとバッファには書き込み済み）
+        let send_samples = match self.utterance_filter.as_mut() {
+            Some(filter) => match filter.process(is_voice, send_samples, chunk_duration_ms) {
+                UtteranceAction::Pass(samples) => samples,
+                UtteranceAction::Hold => {
+                    self.forward_to_audio_output(samples);
+                    return Ok(());
+                }
+                UtteranceAction::Exceeded { first } => {
+                    if first && self.connection_state == TranscribeConnectionState::Connected {
+                        self.disconnect_transcribe().await?;
+                    }
+                    self.forward_to_audio_output(samples);
+                    return Ok(());
+                }
+            },
+            None => send_samples,
+        };
+
+        // 音声と判定される前の立ち上がりで先行して接続する（予測接続）
+        let start_predictive = !is_voice
+            && self.connection_state == TranscribeConnectionState::Disconnected
+            && self
+                .predictive_connector
+                .as_ref()
+                .is_some_and(|p| p.should_start(volume_db, self.vad_threshold_db));
+        if start_predictive && self.acquire_stream_permit() {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 音量の立ち上がりを検出 ({:.2} dB)、予測接続を開始",
+                self.channel_id,
+                volume_db
+            );
+            match self.reconnect_transcribe().await {
+                Ok(()) => {
+                    if let Some(predictive) = &mut self.predictive_connector {
+                        predictive.start();
+                    }
+                }
+                Err(e) => {
+                    channel_warn!(self.log_level, "チャンネル {}: 予測接続に失敗: {}", self.channel_id, e);
+                }
+            }
+        }
+
+        // 同時ストリーム数の上限に空きがなければ接続せず、音声は空くまで切断中のバッファに保持する
+        if is_voice
+            && self.connection_state == TranscribeConnectionState::Disconnected
+            && !self.acquire_stream_permit()
+        {
+            if self.send_buffered_on_reconnect {
+                self.buffer_during_disconnect(send_samples);
+            }
+            self.forward_to_audio_output(samples);
+            return Ok(());
+        }
+
+        // 6. 接続状態に応じた処理
+        let mut sent_samples = 0;
+        match (is_voice, &self.connection_state) {
+            // 音声検出 + 未接続 → 再接続 + バッファ送信
+            (true, TranscribeConnectionState::Disconnected) => {
+                // バッファサイズを計算（メトリクス収集）
+                let total_buffered_samples = self.buffered_samples_during_disconnect.samples();
+                let buffered_duration_ms = (total_buffered_samples as f64 / self.sample_rate as f64 * 1000.0) as u32;
+                let buffered_chunks = self.buffered_samples_during_disconnect.len();
+
+                channel_info!(
+                    self.log_level,
+                    "チャンネル {}: ★音声検出★ Transcribe再接続を開始 (音量: {:.2} dB, バッファ: {}チャンク, {}ms相当)",
                     self.channel_id,
                     volume_db,
                     self.buffered_samples_during_disconnect.len(),
                     buffered_duration_ms
                 );
-                self.reconnect_transcribe().await?;
+                if let Err(e) = self.reconnect_transcribe().await {
+                    // 再接続に失敗した音声は次回の再接続時に送れるよう保持する
+                    if self.send_buffered_on_reconnect {
+                        self.buffer_during_disconnect(send_samples);
+                    }
+                    return Err(e);
+                }
 
                 // 再接続時にバッファ送信が有効な場合
-                if self.send_buffered_on_reconnect && !self.buffered_samples_during_disconnect.is_empty() {
-                    log::info!(
-                        "チャンネル {}: 切断中の音声バッファを送信（{}チャンク, {}ms相当）",
+                let backlog = self.buffered_samples_during_disconnect.take(self.send_buffered_on_reconnect);
+                self.report_disconnect_dropped();
+                if !backlog.is_empty() {
+                    // 再送分はリアルタイム音声より先に処理されるため、その分だけ認識結果が遅れ、
+                    // 課金対象の音声時間も増える
+                    channel_info!(
+                        self.log_level,
+                        "チャンネル {}: 切断中の音声バッファを送信（{}チャンク, {}ms相当 - 認識遅延と課金時間がその分だけ増加）",
                         self.channel_id,
-                        self.buffered_samples_during_disconnect.len(),
+                        backlog.len(),
                         buffered_duration_ms
                     );
+                    self.audit(|| {
+                        AuditEvent::new(AuditEventKind::BufferSent, self.channel_id, unix_now_ns())
+                            .with_buffered(backlog.len(), buffered_duration_ms)
+                    });
 
                     // バッファを送信（非ブロッキング）
                     if let Some(tx) = &self.transcribe_tx {
-                        for buffered in &self.buffered_samples_during_disconnect {
-                            match tx.try_send(buffered.clone()) {
-                                Ok(_) => {}
+                        for buffered in backlog {
+                            let len = buffered.len();
+                            match tx.try_send(self.transcribe_audio(buffered)) {
+                                Ok(_) => sent_samples += len,
                                 Err(mpsc::error::TrySendError::Full(_)) => {
-                                    log::warn!(
+                                    channel_warn!(
+                                        self.log_level,
                                         "チャンネル {}: バッファ送信失敗（AWS Transcribe送信バッファ満杯） - データドロップ",
                                         self.channel_id
                                     );
                                 }
                                 Err(mpsc::error::TrySendError::Closed(_)) => {
-                                    log::error!(
+                                    channel_error!(
+                                        self.log_level,
                                         "チャンネル {}: バッファ送信失敗（チャンネルクローズ）",
                                         self.channel_id
                                     );
@@ -264,35 +1618,37 @@ impl ChannelProcessor {
                             }
                         }
                     }
+                } else if total_buffered_samples > 0 {
+                    channel_info!(
+                        self.log_level,
+                        "チャンネル {}: 再接続時バッファ送信は無効のため切断中の音声を破棄（{}ms相当 - 遅延・課金なし、この区間は文字起こしされない）",
+                        self.channel_id,
+                        buffered_duration_ms
+                    );
+                    self.audit(|| {
+                        AuditEvent::new(AuditEventKind::BufferDiscarded, self.channel_id, unix_now_ns())
+                            .with_buffered(buffered_chunks, buffered_duration_ms)
+                    });
                 }
 
-                // バッファをクリア
-                self.buffered_samples_during_disconnect.clear();
-
                 // 現在のチャンクを送信（非ブロッキング）
                 if let Some(tx) = &self.transcribe_tx {
-                    match tx.try_send(samples.clone()) {
-                        Ok(_) => {}
+                    match tx.try_send(self.transcribe_audio(send_samples.clone())) {
+                        Ok(_) => sent_samples += send_samples.len(),
                         Err(mpsc::error::TrySendError::Full(_)) => {
-                            log::warn!(
+                            channel_warn!(
+                                self.log_level,
                                 "チャンネル {}: AWS Transcribe送信バッファ満杯 - データドロップ",
                                 self.channel_id
                             );
                         }
                         Err(mpsc::error::TrySendError::Closed(_)) => {
-                            log::error!(
+                            channel_error!(
+                                self.log_level,
                                 "チャンネル {}: Transcribeへの送信に失敗: チャンネルクローズ - 切断して次回再接続します",
                                 self.channel_id
                             );
-                            // チャンネルが閉じられた場合は切断状態に移行
-                            self.transcribe_tx = None;
-                            self.connection_state = TranscribeConnectionState::Disconnected;
-
-                            if let Some(tui_state) = &self.tui_state {
-                                tui_state.update_channel(self.channel_id, |channel| {
-                                    channel.update_transcribe_status(TranscribeStatus::Disconnected);
-                                });
-                            }
+                            self.mark_stream_closed();
                         }
                     }
                 }
@@ -303,10 +1659,14 @@ impl ChannelProcessor {
             // 音声検出 + 接続中 → 通常送信
             (true, TranscribeConnectionState::Connected) => {
                 self.silence_duration_ms = 0;
+                if let Some(predictive) = &mut self.predictive_connector {
+                    predictive.reset();
+                }
 
                 if let Some(tx) = &self.transcribe_tx {
-                    match tx.try_send(samples.clone()) {
+                    match tx.try_send(self.transcribe_audio(send_samples.clone())) {
                         Ok(_) => {
+                            sent_samples += send_samples.len();
                             // 正常送信時はTUI状態を更新
                             if let Some(tui_state) = &self.tui_state {
                                 tui_state.update_channel(self.channel_id, |channel| {
@@ -315,37 +1675,50 @@ impl ChannelProcessor {
                             }
                         }
                         Err(mpsc::error::TrySendError::Full(_)) => {
-                            log::warn!(
+                            channel_warn!(
+                                self.log_level,
                                 "チャンネル {}: AWS Transcribe送信バッファ満杯 - データドロップ",
                                 self.channel_id
                             );
                         }
                         Err(mpsc::error::TrySendError::Closed(_)) => {
-                            log::error!(
+                            channel_error!(
+                                self.log_level,
                                 "チャンネル {}: Transcribeへの送信に失敗: チャンネルクローズ - 切断して次回再接続します",
                                 self.channel_id
                             );
-                            // チャンネルが閉じられた場合は切断状態に移行
-                            self.transcribe_tx = None;
-                            self.connection_state = TranscribeConnectionState::Disconnected;
-
-                            // エラー時はTUI状態を切断に更新
-                            if let Some(tui_state) = &self.tui_state {
-                                tui_state.update_channel(self.channel_id, |channel| {
-                                    channel.update_transcribe_status(TranscribeStatus::Disconnected);
-                                });
-                            }
+                            self.mark_stream_closed();
                         }
                     }
                 }
             }
 
-            // 無音 + 接続中 → カウント増加、閾値超過で切断
-            (false, TranscribeConnectionState::Connected) => {
-                self.silence_duration_ms += chunk_duration_ms;
+            // 無音 + 予測接続中 → 立ち上がり部分を送信、音声と確定しなければ短時間で切断
+            (false, TranscribeConnectionState::Connected)
+                if self.predictive_connector.as_ref().is_some_and(PredictiveConnector::is_pending) =>
+            {
+                self.process_predictive_silence(send_samples, chunk_duration_ms).await?;
+            }
+
+            // 無音 + 接続中（発話区間ごとに送るバックエンド） → 発話終了で切断し、発話をまとめて文字起こしさせる
+            (false, TranscribeConnectionState::Connected)
+                if self.transcribe_backend.as_ref().is_some_and(|backend| backend.segments_by_vad()) =>
+            {
+                channel_info!(
+                    self.log_level,
+                    "チャンネル {}: 発話終了、発話区間を文字起こしに送信",
+                    self.channel_id
+                );
+                self.disconnect_transcribe().await?;
+            }
+
+            // 無音 + 接続中 → カウント増加、閾値超過で切断
+            (false, TranscribeConnectionState::Connected) => {
+                self.silence_duration_ms += chunk_duration_ms;
 
                 if self.silence_duration_ms >= self.silence_threshold_ms {
-                    log::info!(
+                    channel_info!(
+                        self.log_level,
                         "チャンネル {}: 無音が{}ms継続、Transcribe接続を停止 (閾値: {}ms)",
                         self.channel_id,
                         self.silence_duration_ms,
@@ -355,29 +1728,24 @@ impl ChannelProcessor {
                 } else {
                     // 閾値未満の場合はゼロサンプル送信（既存の挙動）
                     if let Some(tx) = &self.transcribe_tx {
-                        let zero_samples = vec![0i16; samples.len()];
-                        match tx.try_send(zero_samples) {
-                            Ok(_) => {}
+                        let zero_samples = vec![0i16; send_samples.len()];
+                        match tx.try_send(self.transcribe_audio(zero_samples)) {
+                            // 無音でも送信した分は課金対象
+                            Ok(_) => sent_samples += send_samples.len(),
                             Err(mpsc::error::TrySendError::Full(_)) => {
-                                log::warn!(
+                                channel_warn!(
+                                    self.log_level,
                                     "チャンネル {}: ゼロサンプル送信失敗（バッファ満杯） - データドロップ",
                                     self.channel_id
                                 );
                             }
                             Err(mpsc::error::TrySendError::Closed(_)) => {
-                                log::error!(
+                                channel_error!(
+                                    self.log_level,
                                     "チャンネル {}: ゼロサンプル送信に失敗: チャンネルクローズ - 切断して次回再接続します",
                                     self.channel_id
                                 );
-                                // チャンネルが閉じられた場合は切断状態に移行
-                                self.transcribe_tx = None;
-                                self.connection_state = TranscribeConnectionState::Disconnected;
-
-                                if let Some(tui_state) = &self.tui_state {
-                                    tui_state.update_channel(self.channel_id, |channel| {
-                                        channel.update_transcribe_status(TranscribeStatus::Disconnected);
-                                    });
-                                }
+                                self.mark_stream_closed();
                             }
                         }
                     }
@@ -388,35 +1756,133 @@ impl ChannelProcessor {
             (false, TranscribeConnectionState::Disconnected) => {
                 // 切断中の無音はバッファに蓄積しない
                 // これにより、再接続時の遅延を防ぐ
+                self.waiting_for_stream = false;
             }
         }
 
+        self.record_sent(sent_samples);
+
         // 7. 音声出力デバイスに送信（設定されている場合）
-        if let Some(tx) = &self.audio_output_tx {
-            match tx.try_send(samples.clone()) {
+        self.forward_to_audio_output(samples);
+
+        // 処理時間をログに記録（10ms以上かかった場合のみ）
+        let elapsed = start_instant.elapsed();
+        if elapsed.as_millis() >= 10 {
+            channel_warn!(
+                self.log_level,
+                "チャンネル {}: process_chunk処理時間が {}ms（閾値10ms超過）",
+                self.channel_id,
+                elapsed.as_millis()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// 予測接続中（音声未確定）の無音チャンクを処理
+    async fn process_predictive_silence(&mut self, send_samples: Vec<i16>, chunk_duration_ms: u32) -> Result<()> {
+        let timed_out = self
+            .predictive_connector
+            .as_mut()
+            .is_some_and(|p| p.on_silence(chunk_duration_ms));
+        if timed_out {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 予測接続後 {}ms 以内に音声と判定されなかったため切断",
+                self.channel_id,
+                PredictiveConnector::TIMEOUT_MS
+            );
+            return self.disconnect_transcribe().await;
+        }
+
+        // 立ち上がり部分は語頭の可能性があるため、ゼロサンプルではなく実際の音声を送る
+        if let Some(tx) = &self.transcribe_tx {
+            let len = send_samples.len();
+            match tx.try_send(self.transcribe_audio(send_samples)) {
+                Ok(_) => self.record_sent(len),
+                Err(e) => {
+                    channel_warn!(
+                        self.log_level,
+                        "チャンネル {}: 予測接続中の音声送信に失敗: {}",
+                        self.channel_id,
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 音声出力デバイスに送信（このチャンネルを出力中のモニタすべて）
+    fn forward_to_audio_output(&self, samples: &[i16]) {
+        for (monitor, tx) in &self.audio_outputs {
+            match tx.try_send(samples.to_vec()) {
                 Ok(_) => {}
                 Err(mpsc::error::TrySendError::Full(_)) => {
-                    log::warn!(
-                        "チャンネル {}: 音声出力バッファ満杯 - データドロップ",
-                        self.channel_id
+                    channel_warn!(
+                        self.log_level,
+                        "チャンネル {}: モニタ {} の音声出力バッファ満杯 - データドロップ",
+                        self.channel_id,
+                        monitor
                     );
                 }
                 Err(mpsc::error::TrySendError::Closed(_)) => {
-                    log::warn!(
-                        "チャンネル {}: 音声出力への送信失敗: チャンネルクローズ",
-                        self.channel_id
+                    channel_warn!(
+                        self.log_level,
+                        "チャンネル {}: モニタ {} の音声出力への送信失敗: チャンネルクローズ",
+                        self.channel_id,
+                        monitor
                     );
                 }
             }
         }
+    }
 
-        // 処理時間をログに記録（10ms以上かかった場合のみ）
-        let elapsed = start_instant.elapsed();
-        if elapsed.as_millis() >= 10 {
-            log::warn!(
-                "チャンネル {}: process_chunk処理時間が {}ms（閾値10ms超過）",
+    /// 送信先のストリームが閉じられていたため切断状態に移行する（次の音声で再接続する）
+    fn mark_stream_closed(&mut self) {
+        self.transcribe_tx = None;
+        self.connection_state = TranscribeConnectionState::Disconnected;
+        self.stream_permit = None;
+        self.audit(|| AuditEvent::new(AuditEventKind::StreamClosed, self.channel_id, unix_now_ns()));
+
+        if let Some(tui_state) = &self.tui_state {
+            tui_state.update_channel(self.channel_id, |channel| {
+                channel.update_transcribe_status(TranscribeStatus::Disconnected);
+            });
+        }
+    }
+
+    /// ウォームアップ期間中のチャンクを処理
+    ///
+    /// WAVのタイムラインを他チャンネルと揃えるため、同じ長さの無音を書き込む。
+    fn process_warmup_chunk(&mut self, samples: &[i16]) -> Result<()> {
+        let peak = samples.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+        self.warmup_peak = self.warmup_peak.max(peak);
+        channel_debug!(
+            self.log_level,
+            "チャンネル {}: ウォームアップ中 (残り{}サンプル, チャンク最大振幅 {})",
+            self.channel_id,
+            self.warmup_remaining_samples,
+            peak
+        );
+
+        // 音声区間だけを録音する場合は、無音として扱うウォームアップ期間を録音しない
+        if self.vad_record.is_none() {
+            self.write_wav(&vec![0i16; samples.len()]);
+        }
+
+        self.warmup_remaining_samples = self.warmup_remaining_samples.saturating_sub(samples.len());
+        if self.warmup_remaining_samples == 0 {
+            let peak_db = if self.warmup_peak == 0 {
+                -100.0
+            } else {
+                20.0 * (self.warmup_peak as f32 / i16::MAX as f32).log10()
+            };
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: ウォームアップ完了、通常動作へ移行 (期間中の最大レベル: {:.1} dBFS)",
                 self.channel_id,
-                elapsed.as_millis()
+                peak_db
             );
         }
 
@@ -430,16 +1896,24 @@ impl ChannelProcessor {
             return Ok(());
         }
 
-        log::info!("チャンネル {}: Transcribe再接続開始", self.channel_id);
+        self.apply_pending_backend();
+
+        channel_info!(self.log_level, "チャンネル {}: Transcribe再接続開始", self.channel_id);
 
         // バックエンドから新しいストリームを開始
         if let Some(mut backend) = self.transcribe_backend.take() {
+            self.first_result_latency.connect_started(Instant::now());
             match backend.start_stream().await {
                 Ok((tx, rx)) => {
                     self.transcribe_tx = Some(tx);
-                    self.transcribe_rx = Some(rx);
+                    // 前のストリームが切断後に返す確定結果（発話区間ごとの送信では直前の発話の結果）も受け取る
+                    if let Some(previous_rx) = self.transcribe_rx.replace(rx) {
+                        self.draining_rx.push(previous_rx);
+                    }
                     self.transcribe_backend = Some(backend);
                     self.connection_state = TranscribeConnectionState::Connected;
+                    // 切り替え後のバックエンドで接続できたので、元のバックエンドは不要
+                    self.previous_backend = None;
 
                     // TUI状態を接続中に更新
                     if let Some(tui_state) = &self.tui_state {
@@ -448,16 +1922,20 @@ impl ChannelProcessor {
                         });
                     }
 
-                    log::info!(
+                    channel_info!(
+                        self.log_level,
                         "チャンネル {}: Transcribe再接続成功 (無音閾値: {}ms)",
                         self.channel_id,
                         self.silence_threshold_ms
                     );
+                    self.audit(|| AuditEvent::new(AuditEventKind::Connect, self.channel_id, unix_now_ns()));
                     Ok(())
                 }
                 Err(e) => {
-                    // エラー時もバックエンドを戻す
+                    // エラー時もバックエンドを戻し、同時ストリームの枠を返す
+                    self.first_result_latency.connect_failed();
                     self.transcribe_backend = Some(backend);
+                    self.stream_permit = None;
 
                     // TUI状態をエラーに更新
                     if let Some(tui_state) = &self.tui_state {
@@ -466,7 +1944,12 @@ impl ChannelProcessor {
                         });
                     }
 
-                    log::error!("チャンネル {}: Transcribe再接続失敗: {}", self.channel_id, e);
+                    channel_error!(self.log_level, "チャンネル {}: Transcribe再接続失敗: {}", self.channel_id, e);
+                    self.revert_backend_switch();
+                    self.audit(|| {
+                        AuditEvent::new(AuditEventKind::ConnectFailed, self.channel_id, unix_now_ns())
+                            .with_detail(format!("{:#}", e))
+                    });
                     Err(e)
                 }
             }
@@ -475,308 +1958,2570 @@ impl ChannelProcessor {
         }
     }
 
-    /// Transcribe API接続を切断
-    async fn disconnect_transcribe(&mut self) -> Result<()> {
-        log::info!("チャンネル {}: Transcribe接続を停止", self.channel_id);
+    /// 同時ストリーム数の上限に空きがあれば許可を確保する（上限なし・確保済みなら true）
+    fn acquire_stream_permit(&mut self) -> bool {
+        let Some(limiter) = &self.stream_limiter else {
+            return true;
+        };
+        if self.stream_permit.is_some() {
+            return true;
+        }
+        match limiter.try_acquire() {
+            Some(permit) => {
+                self.stream_permit = Some(permit);
+                self.waiting_for_stream = false;
+                true
+            }
+            None => {
+                if !self.waiting_for_stream {
+                    channel_warn!(
+                        self.log_level,
+                        "チャンネル {}: 同時ストリーム数の上限（{}）に達しているため、他のチャンネルが切断するまで接続を待ちます",
+                        self.channel_id,
+                        limiter.limit()
+                    );
+                    self.waiting_for_stream = true;
+                }
+                false
+            }
+        }
+    }
+
+    /// 切り替え後の最初の接続に失敗した場合、元のバックエンドに戻す
+    fn revert_backend_switch(&mut self) {
+        let Some((label, previous)) = self.previous_backend.take() else {
+            return;
+        };
+        channel_error!(
+            self.log_level,
+            "チャンネル {}: {} で接続できないため、{} に戻します",
+            self.channel_id,
+            self.backend_label,
+            label
+        );
+        self.transcribe_backend = Some(previous);
+        self.backend_label = label.clone();
+        self.set_backend_switch_status(BackendSwitchStatus::Failed(label));
+    }
+
+    /// Transcribe API接続を切断
+    async fn disconnect_transcribe(&mut self) -> Result<()> {
+        channel_info!(self.log_level, "チャンネル {}: Transcribe接続を停止", self.channel_id);
+
+        if self.connection_state == TranscribeConnectionState::Connected {
+            self.audit(|| {
+                AuditEvent::new(AuditEventKind::Disconnect, self.channel_id, unix_now_ns())
+                    .with_silence_ms(self.silence_duration_ms)
+            });
+        }
+
+        // 送信チャンネルをドロップすることで接続終了
+        self.transcribe_tx = None;
+        self.connection_state = TranscribeConnectionState::Disconnected;
+        self.stream_permit = None;
+        self.silence_duration_ms = 0;
+        if let Some(predictive) = &mut self.predictive_connector {
+            predictive.reset();
+        }
+        if let Some(breakdown) = self.cost_breakdown() {
+            channel_info!(self.log_level, "チャンネル {}: 送信音声の累計（概算コスト）: {}", self.channel_id, breakdown);
+        }
+
+        // TUI状態を未接続に更新
+        if let Some(tui_state) = &self.tui_state {
+            tui_state.update_channel(self.channel_id, |channel| {
+                channel.update_transcribe_status(TranscribeStatus::Disconnected);
+            });
+        }
+
+        // 発話が終わったので、切り替え待ちのバックエンドがあれば切り替える
+        self.apply_pending_backend();
+
+        Ok(())
+    }
+
+    /// TUIで変更されたチャンネル名を取り込む
+    fn sync_channel_name(&mut self) {
+        let Some(tui_state) = &self.tui_state else {
+            return;
+        };
+        if let Some(name) = tui_state.get_channel_name(self.channel_id) {
+            if name != self.channel_name {
+                channel_info!(
+                    self.log_level,
+                    "チャンネル {}: 名前を変更 '{}' → '{}'",
+                    self.channel_id,
+                    self.channel_name,
+                    name
+                );
+                self.channel_name = name;
+            }
+        }
+    }
+
+    /// 文字起こし結果を取得（non-blocking）
+    pub async fn poll_transcripts(&mut self) -> Vec<TranscriptResult> {
+        self.sync_channel_name();
+
+        let mut results = Vec::new();
+
+        // バックエンド切り替え前・再接続前のストリームの残りの結果を先に取り出す
+        let (channel_id, log_level) = (self.channel_id, self.log_level);
+        self.draining_rx.retain_mut(|rx| {
+            let closed = loop {
+                match rx.try_recv() {
+                    Ok(result) => results.push(result),
+                    Err(mpsc::error::TryRecvError::Empty) => break false,
+                    Err(mpsc::error::TryRecvError::Disconnected) => break true,
+                }
+            };
+            if closed {
+                channel_debug!(log_level, "チャンネル {}: 以前のストリームの結果を取り切りました", channel_id);
+            }
+            !closed
+        });
+
+        if let Some(rx) = &mut self.transcribe_rx {
+            // 利用可能な全ての結果を取得
+            let previous_len = results.len();
+            while let Ok(result) = rx.try_recv() {
+                results.push(result);
+            }
+            if results.len() > previous_len {
+                self.record_first_result();
+            }
+        } else {
+            // transcribe_rxがNoneの場合（未接続または切断中）
+            if self.connection_state == TranscribeConnectionState::Disconnected {
+                channel_trace!(self.log_level, "チャンネル {}: Transcribe未接続のため結果なし", self.channel_id);
+            }
+        }
+
+        for result in &mut results {
+            channel_debug!(
+                self.log_level,
+                "チャンネル {}: 文字起こし結果受信 - テキスト: '{}', 部分結果: {}",
+                self.channel_id,
+                result.text,
+                result.is_partial
+            );
+            result.channel_name = Some(self.channel_name.clone());
+            result.group = self.group_name.clone();
+            result.metadata = self.session_metadata.clone();
+            if let Some(timestamp) = self.timezone.convert_rfc3339(&result.timestamp) {
+                result.timestamp = timestamp;
+            }
+        }
+
+        // 切断などで確定結果が届かない部分結果を昇格（未接続中も時間経過で判定する）
+        if let Some(finalizer) = &mut self.partial_finalizer {
+            results = finalizer.process(results, Instant::now());
+        }
+
+        // 確定結果の言語を判定し、次の発話（ストリーム）の言語を切り替える
+        for result in results.iter_mut().filter(|result| !result.is_partial) {
+            self.observe_language(result);
+        }
+
+        // 確定結果を音声の時刻から発話区間のクリップに紐づける
+        if let Some(clips) = &self.segment_clips {
+            for result in results.iter_mut().filter(|result| !result.is_partial) {
+                result.clip_path = Self::audio_span_ns(result, self.start_time)
+                    .and_then(|(start_ns, end_ns)| clips.clip_for(start_ns, end_ns))
+                    .map(|path| path.display().to_string());
+            }
+        }
+
+        results
+    }
+
+    /// 結果の音声の開始・終了時刻 (UNIX_EPOCHからのナノ秒)
+    ///
+    /// `start_time` に `timestamp_seconds` と発話の長さを足して求める。
+    /// 発話の長さが分からない結果（音声の時刻を持たない結果を含む）は `None`。
+    fn audio_span_ns(result: &TranscriptResult, start_time: SystemTime) -> Option<(u128, u128)> {
+        let duration_secs = result.audio_duration_seconds?;
+        let start = start_time + Duration::try_from_secs_f64(result.timestamp_seconds.max(0.0)).ok()?;
+        let end = start + Duration::try_from_secs_f64(duration_secs.max(0.0)).ok()?;
+        let unix_ns = |time: SystemTime| time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        Some((unix_ns(start), unix_ns(end)))
+    }
+
+    /// 接続後の最初の結果なら初回応答遅延を記録し、ログとTUIに反映する
+    fn record_first_result(&mut self) {
+        let Some(latency) = self.first_result_latency.result_received(Instant::now()) else {
+            return;
+        };
+        let Some(stats) = self.first_result_latency.stats() else {
+            return;
+        };
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: 初回応答遅延 {}ms ({})",
+            self.channel_id,
+            latency.as_millis(),
+            stats.describe()
+        );
+        if let Some(tui_state) = &self.tui_state {
+            tui_state.update_channel(self.channel_id, |channel| channel.first_result_latency = Some(stats));
+        }
+    }
+
+    /// 初回応答遅延の集計（まだ計測していなければ `None`）
+    pub fn first_result_stats(&self) -> Option<FirstResultStats> {
+        self.first_result_latency.stats()
+    }
+
+    /// 確定結果の言語を `detected_language` に反映し、変わっていればバックエンドの言語を切り替える
+    ///
+    /// 接続中のストリームには影響せず、次に開始するストリームから切り替わる。
+    fn observe_language(&mut self, result: &mut TranscriptResult) {
+        let Some(switcher) = &mut self.language_switcher else {
+            return;
+        };
+        let (detected, switched) = switcher.observe(result.detected_language.as_deref(), &result.text);
+        result.detected_language = detected;
+
+        if switched {
+            let language = switcher.current().to_string();
+            channel_info!(self.log_level, "チャンネル {}: 次の発話の言語を {} に切り替え", self.channel_id, language);
+            if let Some(backend) = &mut self.transcribe_backend {
+                backend.set_language(&language);
+            }
+        }
+    }
+
+    /// 処理を停止
+    pub async fn stop(&mut self) -> Result<()> {
+        channel_info!(
+            self.log_level,
+            "チャンネル {} ({}) の処理を停止",
+            self.channel_id,
+            self.channel_name
+        );
+
+        // Transcribeストリームをクローズ
+        self.transcribe_tx = None;
+        self.log_vad_observations(true);
+        if self.total_disconnect_dropped_samples > 0 {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 切断中バッファの上限超過で捨てた音声: 累計 {:.1}秒",
+                self.channel_id,
+                self.disconnect_dropped_secs()
+            );
+        }
+        if let Some(limiter) = &self.limiter {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 送信音声のリミッタ作動: 累計 {}回",
+                self.channel_id,
+                limiter.activations()
+            );
+        }
+
+        let result = self.finalize_wav();
+        // 停止後にアップロード・監査ログのキューが閉じられるよう、送信側を手放す
+        self.upload_sink = None;
+        self.audit_sink = None;
+        result
+    }
+
+    /// 録音・Transcribe送信を一時停止
+    ///
+    /// Transcribe接続を切断し、WAVファイルを終了する（再開時は新しいファイルを開く）。
+    /// 既に一時停止中なら何もしない。
+    pub async fn pause(&mut self) -> Result<()> {
+        if self.paused {
+            return Ok(());
+        }
+        self.paused = true;
+        channel_info!(self.log_level, "チャンネル {}: 一時停止", self.channel_id);
+
+        self.disconnect_transcribe().await?;
+        // 一時停止前の音声を再開後に送らないよう、再送待ちの音声も破棄する
+        self.buffered_samples_during_disconnect.clear();
+        self.report_disconnect_dropped();
+        self.buffer.clear();
+        if let Some(gate) = &mut self.vad_record {
+            gate.reset();
+        }
+        self.finalize_wav()
+    }
+
+    /// 一時停止から再開し、新しいWAVファイルを開く
+    ///
+    /// `pause_input` が `Buffer` の場合は、一時停止中に保持した音声を新しいファイルの先頭に書き込む。
+    /// 一時停止中でなければ何もしない。
+    pub fn resume(&mut self) -> Result<()> {
+        if !self.paused {
+            return Ok(());
+        }
+        self.paused = false;
+        // 一時停止前の音声区間・ハングオーバーを引き継がない
+        self.vad.reset();
+
+        let held = self.buffer.get_range(0, u128::MAX);
+        self.buffer.clear();
+        let vad_triggered = self.vad_record.is_some();
+        let Some(wav_writer) = self.wav_writer.as_mut().filter(|_| !vad_triggered) else {
+            // 音声区間だけを録音する場合は、次の音声区間で新しいファイルを開く（保持した無音は書き込まない）
+            // WAVを保存しない場合は保持した音声を捨てるだけ
+            channel_info!(self.log_level, "チャンネル {}: 再開", self.channel_id);
+            return Ok(());
+        };
+        if held.is_empty() {
+            wav_writer.start()?;
+        } else {
+            wav_writer.write_samples(&held)?;
+            self.finish_rotated_files();
+        }
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: 再開 (一時停止中の保持音声 {:.1}秒をWAVに書き込み)",
+            self.channel_id,
+            held.len() as f64 / self.sample_rate as f64
+        );
+        Ok(())
+    }
+
+    /// 一時停止中か
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// `reason` が解消するまでWAV・発話クリップへの書き込みを止める
+    ///
+    /// 現在のファイルと書き込み中のクリップは終了し、`resume_recording` の後の最初の書き込みで新しいファイルを開く。
+    /// 停止・無信号の間の無音や、スリープで途切れた前後の音声が1つのファイルに入らないようにする。
+    /// VAD・文字起こしは止めない。
+    pub fn suspend_recording(&mut self, reason: RecordingSuspendReason) -> Result<()> {
+        if self.recording_suspended.contains(&reason) {
+            return Ok(());
+        }
+        self.recording_suspended.push(reason);
+        if self.recording_suspended.len() > 1 {
+            // 別の理由で中断済み
+            return Ok(());
+        }
+        channel_info!(self.log_level, "チャンネル {}: {}のため録音を中断", self.channel_id, reason.describe());
+        if let Some(gate) = &mut self.vad_record {
+            gate.reset();
+        }
+        self.finalize_wav()
+    }
+
+    /// `reason` が解消したら、ほかに中断の理由がなければ次の書き込みから新しいファイルで録音を再開する
+    ///
+    /// 入力の復旧では前後の音声が途切れているため、VADの状態もリセットする。
+    /// 空き容量不足の間も音声は途切れずVADは動き続けているので、その解消ではリセットしない。
+    pub fn resume_recording(&mut self, reason: RecordingSuspendReason) {
+        let Some(index) = self.recording_suspended.iter().position(|&r| r == reason) else {
+            return;
+        };
+        self.recording_suspended.remove(index);
+        if reason == RecordingSuspendReason::InputRecovery {
+            self.vad.reset();
+        }
+        if let Some(remaining) = self.recording_suspended.first() {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: {}は解消しましたが、{}のため録音の中断を続けます",
+                self.channel_id,
+                reason.describe(),
+                remaining.describe()
+            );
+            return;
+        }
+        channel_info!(
+            self.log_level,
+            "チャンネル {}: {}が解消したため、新しいファイルで録音を再開",
+            self.channel_id,
+            reason.describe()
+        );
+    }
+
+    /// 録音WAVに書き込む（録音開始をずらしている間は保持し、時間が来たらファイルを開いて書き込む）
+    ///
+    /// 書き込みに失敗してもVAD・文字起こしは続ける。失敗したファイルは閉じ、失敗が続いた場合は
+    /// `RecordingBreaker` で一定時間録音を止める（回復後は新しいファイルに録音する）。
+    fn write_wav(&mut self, samples: &[i16]) {
+        if !self.recording_suspended.is_empty() || self.wav_writer.is_none() {
+            return;
+        }
+        let now = Instant::now();
+        if !self.recording_breaker.allow(now) {
+            return;
+        }
+        let Err(e) = self.write_wav_samples(samples) else {
+            self.recorded_samples += samples.len() as u64;
+            if self.recording_breaker.record_success() {
+                channel_info!(
+                    self.log_level,
+                    "チャンネル {}: 録音ファイルへの書き込みが回復しました。新しいファイルで録音を再開します",
+                    self.channel_id
+                );
+                self.set_recording_error(false);
+            }
+            return;
+        };
+
+        // 書き込めなくなったファイルは閉じ、次の書き込みで新しいファイルを開く
+        if let Some(Err(finalize_error)) = self.wav_writer.as_mut().map(WavWriter::finalize) {
+            channel_debug!(
+                self.log_level,
+                "チャンネル {}: 書き込みに失敗したファイルを閉じられませんでした: {:#}",
+                self.channel_id,
+                finalize_error
+            );
+        }
+        let was_open = self.recording_breaker.is_open();
+        if self.recording_breaker.record_failure(now) {
+            channel_error!(
+                self.log_level,
+                "チャンネル {}: 録音ファイルへの書き込みが{}回続けて失敗したため、録音を停止します（{}秒ごとに再試行、文字起こしは継続）: {:#}",
+                self.channel_id,
+                RecordingBreaker::FAILURE_THRESHOLD,
+                RecordingBreaker::RETRY_INTERVAL.as_secs(),
+                e
+            );
+            self.set_recording_error(true);
+        } else if was_open {
+            channel_debug!(self.log_level, "チャンネル {}: 録音の再試行に失敗: {:#}", self.channel_id, e);
+        } else {
+            channel_warn!(self.log_level, "チャンネル {}: 録音ファイルへの書き込みに失敗: {:#}", self.channel_id, e);
+        }
+    }
+
+    /// `record_mode = "vad_triggered"` の録音（音声区間の開始時はプリロールから書き込む）
+    ///
+    /// `vad_record_files = "per_segment"` では音声区間の終了ごとにファイルを閉じ、
+    /// `"single"` では同じファイルに詰めて書き、区間の位置を `recorded_segments` に記録する。
+    fn write_wav_vad_triggered(&mut self, is_voice: bool, samples: &[i16], timestamp_ns: u128) {
+        if !self.recording_suspended.is_empty() {
+            return;
+        }
+        let Some(gate) = &mut self.vad_record else {
+            return;
+        };
+        match gate.process(is_voice, samples, timestamp_ns) {
+            VadRecordStep::Skip => {}
+            VadRecordStep::Start { preroll, start_ns } => {
+                if self.wav_writer.as_ref().and_then(WavWriter::started_at).is_none() {
+                    self.next_file_started_at = Some(UNIX_EPOCH + Duration::from_nanos(start_ns as u64));
+                }
+                if self.vad_record_files == VadRecordFiles::Single {
+                    self.recorded_segments.push(RecordedSegment {
+                        offset_samples: self.wav_samples_written(),
+                        samples: 0,
+                        start_ns,
+                    });
+                }
+                if !preroll.is_empty() {
+                    self.write_wav(&preroll);
+                }
+                self.write_wav(samples);
+            }
+            VadRecordStep::Write => self.write_wav(samples),
+            VadRecordStep::End => {
+                self.close_recorded_segment();
+                if self.vad_record_files == VadRecordFiles::PerSegment {
+                    if let Err(e) = self.finalize_wav() {
+                        channel_warn!(self.log_level, "チャンネル {}: 録音ファイルの終了に失敗: {:#}", self.channel_id, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 書き込み中の録音セグメントの長さを確定する
+    fn close_recorded_segment(&mut self) {
+        let written = self.wav_samples_written();
+        if let Some(segment) = self.recorded_segments.last_mut() {
+            segment.samples = written.saturating_sub(segment.offset_samples);
+        }
+    }
+
+    /// 録音の停止状態（I/Oエラー）をTUIに反映
+    fn set_recording_error(&self, recording_error: bool) {
+        if let Some(tui_state) = &self.tui_state {
+            tui_state.update_channel(self.channel_id, |channel| {
+                channel.recording_error = recording_error;
+            });
+        }
+    }
+
+    /// 録音ファイルに書き込む（録音開始をずらしている間は保持する）
+    fn write_wav_samples(&mut self, samples: &[i16]) -> Result<()> {
+        let Some(pending) = &mut self.pending_wav else {
+            let Some(wav_writer) = &mut self.wav_writer else {
+                return Ok(());
+            };
+            if let Some(started_at) = self.next_file_started_at.take() {
+                wav_writer.start_at(started_at)?;
+            }
+            wav_writer.write_samples(samples)?;
+            self.finish_rotated_files();
+            return Ok(());
+        };
+        pending.samples.extend_from_slice(samples);
+        pending.remaining_samples = pending.remaining_samples.saturating_sub(samples.len());
+        if pending.remaining_samples == 0 {
+            self.flush_pending_wav()?;
+        }
+        Ok(())
+    }
+
+    /// 録音開始をずらしている間に保持した音声でWAVファイルを開く
+    fn flush_pending_wav(&mut self) -> Result<()> {
+        let (Some(pending), Some(wav_writer)) = (self.pending_wav.take(), &mut self.wav_writer) else {
+            return Ok(());
+        };
+        wav_writer.start_at(pending.started_at)?;
+        wav_writer.write_samples(&pending.samples)?;
+        self.finish_rotated_files();
+        channel_debug!(
+            self.log_level,
+            "チャンネル {}: 録音ファイルを開き、保持していた音声 {:.2}秒を書き込み",
+            self.channel_id,
+            pending.samples.len() as f64 / self.sample_rate as f64
+        );
+        Ok(())
+    }
+
+    /// WAVファイルと発話クリップを終了し、アップロードキューへ渡す
+    fn finalize_wav(&mut self) -> Result<()> {
+        // 録音開始前に止めた場合も、保持していた音声は書き出す
+        self.flush_pending_wav()?;
+        self.close_recorded_segment();
+        let recorded_segments = std::mem::take(&mut self.recorded_segments);
+        let (started_at, duration_secs, finalized) = match &mut self.wav_writer {
+            Some(wav_writer) => (wav_writer.started_at(), wav_writer.duration_seconds(), wav_writer.finalize()?),
+            None => (None, 0.0, None),
+        };
+        if let Some(path) = &finalized {
+            self.write_recorded_segments(path, &recorded_segments);
+            self.record_file_stat(path, duration_secs);
+        }
+        if let Some(clips) = &mut self.segment_clips {
+            clips.finish(unix_now_ns())?;
+        }
+        if let (Some(path), Some(started_at)) = (&finalized, started_at) {
+            self.write_sidecar(path, started_at, duration_secs);
+        }
+
+        // finalize 済みのファイルをアップロードキューへ
+        if let (Some(path), Some(upload_sink)) = (finalized, &self.upload_sink) {
+            upload_sink.enqueue(path);
+        }
+
+        Ok(())
+    }
+
+    /// ローテーションで閉じた録音ファイルを、停止時に finalize したファイルと同様に扱う
+    ///
+    /// セッションの実績に加え、サイドカーYAMLを書き出してアップロードキューへ渡す。
+    fn finish_rotated_files(&mut self) {
+        let rotated = self.wav_writer.as_mut().map(WavWriter::take_rotated).unwrap_or_default();
+        for file in rotated {
+            channel_info!(
+                self.log_level,
+                "チャンネル {}: 録音ファイルをローテーション ({:.0}秒): {:?}",
+                self.channel_id,
+                file.duration_secs,
+                file.path
+            );
+            self.record_file_stat(&file.path, file.duration_secs);
+            self.write_sidecar(&file.path, file.started_at, file.duration_secs);
+            if let Some(upload_sink) = &self.upload_sink {
+                upload_sink.enqueue(file.path);
+            }
+        }
+    }
+
+    /// 音声区間を詰めて書いた録音ファイルのセグメント一覧を書き出す（`vad_record_files = "single"` 時のみ）
+    ///
+    /// 補助的な出力のため、失敗しても警告のみで録音は継続する。
+    fn write_recorded_segments(&self, wav_path: &Path, segments: &[RecordedSegment]) {
+        if segments.is_empty() {
+            return;
+        }
+        let result = vad_record::write_segments(wav_path, segments, self.sample_rate, |time| {
+            self.timezone.format_system_time(time)
+        });
+        match result {
+            Ok(path) => channel_debug!(self.log_level, "チャンネル {}: 録音セグメント一覧を作成: {:?}", self.channel_id, path),
+            Err(e) => channel_warn!(self.log_level, "チャンネル {}: {:#}", self.channel_id, e),
+        }
+    }
+
+    /// 録音ファイルのサイドカーYAMLを書き出す（`enable_sidecar_metadata` 時のみ）
+    ///
+    /// 補助的な出力のため、失敗しても警告のみで録音は継続する。
+    fn write_sidecar(&mut self, wav_path: &Path, started_at: SystemTime, duration_secs: f64) {
+        let Some(speech) = &mut self.recording_speech else {
+            return;
+        };
+        let metadata = RecordingMetadata {
+            recording: wav_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            channel_id: self.channel_id,
+            channel_name: self.channel_name.clone(),
+            started_at: self.timezone.format_system_time(started_at),
+            sample_rate: self.sample_rate,
+            duration_secs,
+            speech: Some(speech.take(self.sample_rate)),
+            metadata: self
+                .session_metadata
+                .iter()
+                .flatten()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            summary: String::new(),
+            tags: Vec::new(),
+            notes: String::new(),
+        };
+
+        match metadata.write(wav_path) {
+            Ok(Some(path)) => channel_debug!(self.log_level, "チャンネル {}: 録音メタデータを作成: {:?}", self.channel_id, path),
+            Ok(None) => channel_warn!(
+                self.log_level,
+                "チャンネル {}: 録音メタデータが既にあるため上書きしません: {:?}",
+                self.channel_id,
+                RecordingMetadata::sidecar_path(wav_path)
+            ),
+            Err(e) => channel_warn!(self.log_level, "チャンネル {}: {:#}", self.channel_id, e),
+        }
+    }
+
+    /// チャンネルIDを取得
+    pub fn channel_id(&self) -> usize {
+        self.channel_id
+    }
+
+    /// チャンネル名を取得
+    pub fn channel_name(&self) -> &str {
+        &self.channel_name
+    }
+
+    /// WAV書き込み時間を取得（WAVを保存しない場合は 0）
+    pub fn wav_duration_seconds(&self) -> f64 {
+        self.wav_writer.as_ref().map_or(0.0, WavWriter::duration_seconds)
+    }
+
+    /// 書き込み中の録音ファイルに書き込んだサンプル数（WAVを保存しない場合は 0）
+    fn wav_samples_written(&self) -> usize {
+        self.wav_writer.as_ref().map_or(0, WavWriter::samples_written)
+    }
+
+    /// バッファサイズを取得
+    pub fn buffer_duration_seconds(&self) -> f64 {
+        self.buffer.duration_seconds()
+    }
+
+    /// バッファの使用メモリ量（バイト）を取得
+    pub fn buffer_used_bytes(&self) -> usize {
+        self.buffer.used_bytes()
+    }
+
+    /// 切断中にためている送信待ちの音声の量（バイト）
+    pub fn disconnect_buffer_bytes(&self) -> usize {
+        self.disconnect_buffer_samples() * std::mem::size_of::<SampleI16>()
+    }
+
+    /// 切断中にためている送信待ちの音声の長さ（秒）
+    pub fn disconnect_buffer_secs(&self) -> f64 {
+        self.disconnect_buffer_samples() as f64 / self.sample_rate as f64
+    }
+
+    fn disconnect_buffer_samples(&self) -> usize {
+        self.buffered_samples_during_disconnect.samples()
+    }
+
+    /// 切断中バッファの上限超過で捨てた音声の累計時間（秒）
+    pub fn disconnect_dropped_secs(&self) -> f64 {
+        self.total_disconnect_dropped_samples as f64 / self.sample_rate as f64
+    }
+
+    /// 切断中の音声を再接続時に送るためにためる
+    ///
+    /// `max_disconnect_buffer_secs` を超えた分は `disconnect_buffer_overflow` に従って捨てる。
+    /// 捨て始めた時に1回だけ警告し、捨てた量は再接続時にまとめてログ出力する。
+    fn buffer_during_disconnect(&mut self, samples: Vec<i16>) {
+        if self.disconnect_buffer_abandoned {
+            self.count_disconnect_dropped(samples.len());
+            return;
+        }
+        self.buffered_samples_during_disconnect.push(samples);
+
+        let total = self.disconnect_buffer_samples();
+        if total <= self.max_disconnect_buffer_samples {
+            return;
+        }
+        let first_overflow = self.disconnect_dropped_samples == 0;
+        let dropped = match self.disconnect_buffer_overflow {
+            DisconnectBufferOverflow::DropOldest => {
+                self.buffered_samples_during_disconnect.drop_oldest(total - self.max_disconnect_buffer_samples)
+            }
+            DisconnectBufferOverflow::DiscardAll => {
+                self.disconnect_buffer_abandoned = true;
+                self.buffered_samples_during_disconnect.clear();
+                total
+            }
+        };
+        self.count_disconnect_dropped(dropped);
+
+        if first_overflow {
+            let limit_secs = self.max_disconnect_buffer_samples as f64 / self.sample_rate as f64;
+            match self.disconnect_buffer_overflow {
+                DisconnectBufferOverflow::DropOldest => channel_warn!(
+                    self.log_level,
+                    "チャンネル {}: 切断中バッファが上限 {:.0}秒 を超えたため、古い音声から捨てます",
+                    self.channel_id,
+                    limit_secs
+                ),
+                DisconnectBufferOverflow::DiscardAll => channel_warn!(
+                    self.log_level,
+                    "チャンネル {}: 切断中バッファが上限 {:.0}秒 を超えたため、再接続まで切断中の音声の再送を諦めます",
+                    self.channel_id,
+                    limit_secs
+                ),
+            }
+        }
+    }
+
+    fn count_disconnect_dropped(&mut self, samples: usize) {
+        self.disconnect_dropped_samples += samples;
+        self.total_disconnect_dropped_samples += samples as u64;
+    }
+
+    /// 今回の切断中に上限超過で捨てた量をログ・監査ログに出力し、切断中バッファの状態を戻す
+    fn report_disconnect_dropped(&mut self) {
+        self.disconnect_buffer_abandoned = false;
+        let dropped = std::mem::take(&mut self.disconnect_dropped_samples);
+        if dropped == 0 {
+            return;
+        }
+        let dropped_ms = (dropped as u64 * 1000 / self.sample_rate as u64) as u32;
+        channel_warn!(
+            self.log_level,
+            "チャンネル {}: 切断中バッファの上限超過で {}ms 相当の音声を捨てました（この区間は文字起こしされない、累計 {:.1}秒）",
+            self.channel_id,
+            dropped_ms,
+            self.disconnect_dropped_secs()
+        );
+        self.audit(|| {
+            AuditEvent::new(AuditEventKind::BufferDiscarded, self.channel_id, unix_now_ns())
+                .with_buffered(0, dropped_ms)
+                .with_detail("切断中バッファの上限超過")
+        });
+    }
+
+    /// このチャンネルの概算メモリ使用量（TUI履歴を除く）
+    ///
+    /// `input_queued_chunks` は入力からこのチャンネルへのキューに残っているチャンク数。
+    /// キューは直近に受信したチャンクと同じサイズのチャンクがたまっているものとして見積もる。
+    pub fn memory_usage(&self, input_queued_chunks: usize) -> MemoryUsage {
+        let transcribe_queued = self
+            .transcribe_tx
+            .as_ref()
+            .map_or(0, |tx| tx.max_capacity() - tx.capacity());
+        MemoryUsage {
+            audio_buffers: self.buffer.used_bytes() as u64,
+            disconnect_buffers: self.disconnect_buffer_bytes() as u64,
+            queues: ((input_queued_chunks + transcribe_queued) * self.last_chunk_bytes) as u64,
+            tui_history: 0,
+        }
+    }
+
+    /// VAD状態を取得
+    pub fn vad_state(&self) -> VadState {
+        self.vad.get_state()
+    }
+
+    /// 最新のボリューム（dB）を取得
+    pub fn current_volume_db(&self) -> f32 {
+        self.vad.get_last_volume_db()
+    }
+
+    /// 確定結果の後処理の設定（`ChannelConfig::resolve_postprocess` で解決したもの）
+    pub fn set_postprocess(&mut self, postprocess: PostprocessConfig) {
+        self.postprocess = postprocess;
+    }
+
+    /// このチャンネルの後処理の設定
+    pub fn postprocess(&self) -> &PostprocessConfig {
+        &self.postprocess
+    }
+
+    /// 確定結果のテキストにこのチャンネルの後処理を適用
+    ///
+    /// 全角英数字の正規化 → フィラーワード削除 → 置換ルール の順に、有効なものだけ適用する。
+    pub fn postprocess_text(&self, text: &str) -> String {
+        self.postprocess_steps(text)
+            .pop()
+            .map_or_else(|| text.trim().to_string(), |step| step.text)
+    }
+
+    /// 後処理を段ごとに適用し、テキストが変わった段の結果（前後の空白を除く）を適用順に返す
+    pub fn postprocess_steps(&self, text: &str) -> Vec<PostprocessStep> {
+        let mut steps = Vec::new();
+        let mut current = text.to_string();
+        if self.postprocess.normalize_width {
+            let next = Self::normalize_width(&current);
+            Self::push_postprocess_step(&mut steps, &mut current, PostprocessStage::NormalizeWidth, next);
+        }
+        if self.postprocess.remove_fillers {
+            let next = Self::remove_filler_words(&current);
+            Self::push_postprocess_step(&mut steps, &mut current, PostprocessStage::RemoveFillers, next);
+        }
+        if !self.postprocess.replacements.is_empty() {
+            let next = self
+                .postprocess
+                .replacements
+                .iter()
+                .fold(current.clone(), |text, rule| text.replace(&rule.from, &rule.to));
+            Self::push_postprocess_step(&mut steps, &mut current, PostprocessStage::Replacements, next);
+        }
+        steps
+    }
+
+    fn push_postprocess_step(steps: &mut Vec<PostprocessStep>, current: &mut String, stage: PostprocessStage, next: String) {
+        if next.trim() != current.trim() {
+            steps.push(PostprocessStep {
+                stage,
+                text: next.trim().to_string(),
+            });
+        }
+        *current = next;
+    }
+
+    /// 確定結果に後処理を適用する
+    ///
+    /// `text` を表示用（後処理済み）にし、原文を `raw_text`、各段の結果を `postprocess` に残す。
+    pub fn postprocess_result(&self, result: &mut TranscriptResult) {
+        let steps = self.postprocess_steps(&result.text);
+        let text = steps
+            .last()
+            .map_or_else(|| result.text.trim().to_string(), |step| step.text.clone());
+        result.raw_text = Some(std::mem::replace(&mut result.text, text));
+        result.postprocess = steps;
+    }
+
+    /// 全角英数字と全角スペースを半角にする（全角の記号・カナはそのまま）
+    pub fn normalize_width(text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                '\u{3000}' => ' ',
+                _ => c,
+            })
+            .collect()
+    }
+
+    /// フィラーワード（言い淀み）を削除
+    pub fn remove_filler_words(text: &str) -> String {
+        // 削除対象のフィラーワードリスト
+        let filler_words = [
+            "えっと",
+            "あの",
+            "ええと",
+            "ええ",
+            "えー",
+            "えーと",
+            "あのー",
+            "っと",
+            "っとー",
+        ];
+
+        let mut result = text.to_string();
+
+        // 各フィラーワードを削除
+        for filler in &filler_words {
+            // 完全一致する単語を削除（前後に空白がある場合）
+            result = result.replace(&format!("{} ", filler), "");
+            result = result.replace(&format!(" {}", filler), "");
+            // 文頭・文末の場合
+            if result.starts_with(filler) {
+                result = result[filler.len()..].to_string();
+            }
+            if result.ends_with(filler) {
+                result = result[..result.len() - filler.len()].to_string();
+            }
+        }
+
+        // 連続する空白を1つにまとめる
+        while result.contains("  ") {
+            result = result.replace("  ", " ");
+        }
+
+        // 前後の空白を削除
+        result.trim().to_string()
+    }
+
+    /// 句読点のみの行かどうかをチェック
+    pub fn is_punctuation_only(text: &str) -> bool {
+        let trimmed = text.trim();
+
+        // 空文字列の場合はtrue
+        if trimmed.is_empty() {
+            return true;
+        }
+
+        // 句読点のみで構成されているかチェック
+        // 「、」「。」「と。」のような組み合わせ
+        let allowed_chars = ['、', '。', 'と'];
+
+        // すべての文字が許可された文字かチェック
+        let all_punctuation = trimmed.chars().all(|c| allowed_chars.contains(&c));
+
+        all_punctuation
+    }
+
+    /// TUI状態にTranscribe結果を追加
+    pub fn add_transcript_to_tui(&self, result: &TranscriptResult) {
+        if let Some(tui_state) = &self.tui_state {
+            let text_to_display = if result.is_partial {
+                // 部分結果はフィラーワード削除しない（リアルタイム性を優先）
+                result.text.clone()
+            } else {
+                // 確定結果のみ後処理（フィラーワード削除など）を適用
+                let cleaned_text = self.postprocess_text(&result.text);
+
+                // 空文字列または句読点のみの場合は追加しない
+                if cleaned_text.is_empty() || Self::is_punctuation_only(&cleaned_text) {
+                    return;
+                }
+
+                cleaned_text
+            };
+
+            let entry = TranscriptEntry {
+                text: text_to_display,
+                time: result.timestamp.clone(),
+                seconds: result.timestamp_seconds,
+                is_partial: result.is_partial,
+                stability: result.stability,
+                confidence: result.confidence,
+                clip_path: result.clip_path.clone(),
+                flagged: false,
+            };
+
+            if !result.is_partial && self.group_name.is_some() {
+                // グループの統合トランスクリプトにはチャンネル名を話者ラベルとして追加
+                tui_state.add_group_transcript(self.channel_id, self.channel_name.clone(), entry.clone());
+            }
+
+            tui_state.update_channel(self.channel_id, |channel| channel.add_transcript(entry));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SessionReportFormat;
+    use crate::mock_backend::MockBackend;
+    use crate::wav_writer::DEFAULT_FILENAME_TEMPLATE;
+
+    #[tokio::test]
+    #[ignore] // AWS認証情報が必要なため、通常はスキップ
+    async fn test_channel_processor_creation() {
+        let channel_config = ChannelConfig {
+            id: 0,
+            name: "テストチャンネル".to_string(),
+            enabled: true,
+            send_buffered_on_reconnect: None,
+            min_utterance_ms: None,
+            max_utterance_ms: None,
+            latency_offset_ms: 0,
+            log_level: None,
+            display_order: None,
+            symbol: None,
+            postprocess: None,
+            record_mode: None,
+        };
+
+        let vad_config = VadConfig {
+            threshold_db: -40.0,
+            hangover_duration_ms: 500,
+            silence_disconnect_threshold_ms: 10000,
+            smoothing_window_ms: 0,
+        };
+
+        let buffer_config = BufferConfig {
+            capacity_seconds: 30,
+            drop_policy: crate::types::DropPolicy::DropOldest,
+            capacity_bytes: None,
+        };
+
+        let transcribe_config = TranscribeConfig {
+            backend: TranscribeBackendType::Aws,
+            region: "ap-northeast-1".to_string(),
+            language_code: "ja-JP".to_string(),
+            sample_rate: 16000,
+            max_retries: 3,
+            timeout_seconds: 10,
+            connect_on_startup: false,
+            send_buffered_on_reconnect: true,
+            proactive_reconnect_secs: 0,
+            partial_finalize_timeout_ms: 0,
+            initial_chunk_ms: 150,
+            chunk_ms: 200,
+            fast_start_chunks: 5,
+            predictive_connect: false,
+            endpoint_url: None,
+            allowed_languages: Vec::new(),
+            max_concurrent_streams: None,
+            stream_quota: None,
+            max_disconnect_buffer_secs: 30,
+            disconnect_buffer_overflow: DisconnectBufferOverflow::DropOldest,
+        };
+
+        let output_config = OutputConfig {
+            wav_output_dir: "/tmp/test_recordings".to_string(),
+            log_level: "info".to_string(),
+            min_free_bytes: 0,
+            expected_recording_hours: 8.0,
+            timezone: "local".to_string(),
+            save_clips: false,
+            pause_input: PauseInput::Discard,
+            sidecar_metadata: false,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            encryption: None,
+            recording_start_stagger_ms: 0,
+            record_mode: RecordMode::Continuous,
+            vad_record_files: VadRecordFiles::PerSegment,
+            vad_preroll_ms: 500,
+            session_report: false,
+            session_report_dir: None,
+            session_report_format: SessionReportFormat::Markdown,
+            feedback_file: None,
+            timeline_events: false,
+            wav_rotation_secs: None,
+            save_wav: true,
+        };
+
+        let result = ChannelProcessor::new(
+            &channel_config,
+            &vad_config,
+            &buffer_config,
+            &transcribe_config,
+            None, // whisper_config
+            &FlacConfig::default(),
+            &output_config,
+            16000,
+            std::time::SystemTime::now(),
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    /// AWSに接続しない（connect_on_startup = false）テスト用プロセッサを作成
+    async fn offline_processor(output_dir: &std::path::Path) -> ChannelProcessor {
+        let channel_config = ChannelConfig {
+            id: 0,
+            name: "テスト".to_string(),
+            enabled: true,
+            send_buffered_on_reconnect: None,
+            min_utterance_ms: None,
+            max_utterance_ms: None,
+            latency_offset_ms: 0,
+            log_level: None,
+            display_order: None,
+            symbol: None,
+            postprocess: None,
+            record_mode: None,
+        };
+        let output_config = OutputConfig {
+            wav_output_dir: output_dir.to_string_lossy().into_owned(),
+            ..OutputConfig::default()
+        };
+
+        ChannelProcessor::new(
+            &channel_config,
+            &VadConfig::default(),
+            &BufferConfig::default(),
+            &TranscribeConfig::default(),
+            None,
+            &FlacConfig::default(),
+            &output_config,
+            16000,
+            std::time::SystemTime::now(),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// モックバックエンドを使うテスト用プロセッサを作成
+    ///
+    /// VADはハングオーバー100ms（100msチャンク1つで無音に戻る）、無音300msで切断。
+    fn mock_processor(
+        output_dir: &std::path::Path,
+        transcribe_config: TranscribeConfig,
+    ) -> (ChannelProcessor, MockBackend) {
+        let channel_config = ChannelConfig {
+            id: 0,
+            name: "モック".to_string(),
+            enabled: true,
+            send_buffered_on_reconnect: None,
+            min_utterance_ms: None,
+            max_utterance_ms: None,
+            latency_offset_ms: 0,
+            log_level: None,
+            display_order: None,
+            symbol: None,
+            postprocess: None,
+            record_mode: None,
+        };
+        let vad_config = VadConfig {
+            threshold_db: -40.0,
+            hangover_duration_ms: 100,
+            silence_disconnect_threshold_ms: 300,
+            smoothing_window_ms: 0,
+        };
+        let output_config = OutputConfig {
+            wav_output_dir: output_dir.to_string_lossy().into_owned(),
+            ..OutputConfig::default()
+        };
+
+        let backend = MockBackend::new(0);
+        let processor = ChannelProcessor::new_with_backend(
+            &channel_config,
+            &vad_config,
+            &BufferConfig::default(),
+            &transcribe_config,
+            &output_config,
+            16000,
+            Box::new(backend.clone()),
+        )
+        .unwrap();
+        (processor, backend)
+    }
+
+    /// 100ms 分の音声（約 -12 dB）
+    fn voice(level: i16) -> Vec<i16> {
+        vec![level; 1600]
+    }
+
+    /// 100ms 分の無音
+    fn silence() -> Vec<i16> {
+        vec![0; 1600]
+    }
+
+    fn chunk(samples: Vec<i16>) -> AudioChunk {
+        AudioChunk {
+            samples,
+            format: crate::types::AudioFormat {
+                sample_rate: 16000,
+                channels: 1,
+            },
+            timestamp_ns: 0,
+            samples_f32: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_voice_silence_voice_transitions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+
+        // 無音のみでは接続しない
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert_eq!(backend.stream_count(), 0);
+
+        // 音声検出で接続し、そのチャンクを送信
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Connected);
+        assert_eq!(backend.stream_count(), 1);
+
+        // 無音が閾値未満の間はゼロサンプルを送り続ける
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Connected);
+
+        // 無音 300ms で切断（このチャンクは送らない）
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert!(backend.is_closed(0));
+        assert_eq!(backend.received(0), vec![voice(8000), silence(), silence()]);
+
+        // 再び音声が来たら新しいストリームで再接続
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Connected);
+        assert_eq!(backend.stream_count(), 2);
+        assert_eq!(backend.received(1), vec![voice(9000)]);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_first_result_latency() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        let tui_state = TuiState::new();
+        tui_state.add_channel(0, "モック".to_string());
+        processor.set_tui_state(tui_state.clone());
+        processor.start().await.unwrap();
+        assert_eq!(processor.first_result_stats(), None);
+
+        // 接続ごとに最初の結果だけを数える
+        for text in ["一つ目", "二つ目"] {
+            processor.process_chunk(chunk(voice(8000))).await.unwrap();
+            backend.push_result(TranscriptResult::new(0, text.to_string(), true, None, SystemTime::now()));
+            backend.push_result(TranscriptResult::new(0, text.to_string(), false, None, SystemTime::now()));
+            assert_eq!(processor.poll_transcripts().await.len(), 2);
+            for _ in 0..3 {
+                processor.process_chunk(chunk(silence())).await.unwrap();
+            }
+            assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        }
+
+        let stats = processor.first_result_stats().unwrap();
+        assert_eq!(stats.connections, 2);
+        assert!(stats.max >= stats.average);
+        let channel = tui_state.get_all_channels().remove(0);
+        assert_eq!(channel.first_result_latency, Some(stats));
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stereo_input_sent_as_mono() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+
+        // L/R インターリーブのステレオ入力は、L/R の平均のモノラル1系列として送る
+        let stereo: Vec<i16> = voice(8000).into_iter().flat_map(|s| [s, s / 2]).collect();
+        let expected: Vec<i16> = voice(8000).into_iter().map(|s| ((s as i32 + (s / 2) as i32) / 2) as i16).collect();
+        let mut stereo_chunk = chunk(stereo);
+        stereo_chunk.format.channels = 2;
+        processor.process_chunk(stereo_chunk).await.unwrap();
+
+        assert_eq!(backend.received(0), vec![expected]);
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_limiter_applies_to_sent_audio_only() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.enable_limiter(&DspConfig {
+            limiter_threshold_db: Some(-6.0),
+            limiter_release_ms: 100,
+            limiter_lookahead_ms: 1,
+        });
+        processor.start().await.unwrap();
+
+        let loud: Vec<i16> = (0..1600).map(|i| if i % 40 < 20 { 30000 } else { -30000 }).collect();
+        processor.process_chunk(chunk(loud.clone())).await.unwrap();
+        processor.process_chunk(chunk(loud.clone())).await.unwrap();
+        processor.stop().await.unwrap();
+
+        // 送信音声は閾値（-6 dBFS ≒ 16422）以下に抑え、先読みの16サンプル分だけ遅れる
+        let sent = backend.received(0).concat();
+        assert_eq!(sent.len(), 3200);
+        assert!(sent[..16].iter().all(|&s| s == 0));
+        assert!(sent.iter().all(|&s| s.unsigned_abs() <= 16423));
+        assert!(sent[16..].iter().all(|&s| s.unsigned_abs() > 10000));
+        assert_eq!(processor.limiter.as_ref().unwrap().activations(), 1);
+        // WAV録音は無加工
+        assert_eq!(read_recordings(temp_dir.path()), vec![[loud.clone(), loud].concat()]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_segments_by_vad() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        backend.set_segments_by_vad(true);
+        processor.start().await.unwrap();
+
+        // 発話終了（ハングオーバー後の最初の無音）ですぐにストリームを閉じ、ゼロサンプルは送らない
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert!(backend.is_closed(0));
+        assert_eq!(backend.received(0), vec![voice(8000), voice(8000)]);
+
+        // 次の発話は新しいストリーム（1発話 = 1ストリーム）
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        assert_eq!(backend.stream_count(), 2);
+        assert_eq!(backend.received(1), vec![voice(9000)]);
+
+        // 前の発話の文字起こしが次の発話の開始後に返っても受け取る
+        backend.push_result_to(0, TranscriptResult::new(0, "一つ目".to_string(), false, None, SystemTime::now()));
+        backend.push_result(TranscriptResult::new(0, "二つ目".to_string(), false, None, SystemTime::now()));
+        let texts: Vec<String> = processor.poll_transcripts().await.into_iter().map(|r| r.text).collect();
+        assert_eq!(texts, vec!["一つ目", "二つ目"]);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_stream_limit_waits_for_other_channel() {
+        let (dir_a, dir_b) = (tempfile::TempDir::new().unwrap(), tempfile::TempDir::new().unwrap());
+        let (mut first, first_backend) = mock_processor(dir_a.path(), TranscribeConfig::default());
+        let (mut second, second_backend) = mock_processor(dir_b.path(), TranscribeConfig::default());
+        let limiter = StreamLimiter::new(1);
+        first.set_stream_limiter(limiter.clone());
+        second.set_stream_limiter(limiter);
+        first.start().await.unwrap();
+        second.start().await.unwrap();
+
+        // 上限に達している間は接続せず、音声は切断中のバッファに保持する
+        first.process_chunk(chunk(voice(8000))).await.unwrap();
+        second.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(first.connection_state, TranscribeConnectionState::Connected);
+        assert_eq!(second.connection_state, TranscribeConnectionState::Disconnected);
+        assert_eq!(second_backend.stream_count(), 0);
+
+        // 1つ目のチャンネルが切断すると枠が空き、保持していた音声から送る
+        for _ in 0..5 {
+            first.process_chunk(chunk(silence())).await.unwrap();
+        }
+        assert_eq!(first.connection_state, TranscribeConnectionState::Disconnected);
+        second.process_chunk(chunk(voice(9000))).await.unwrap();
+        assert_eq!(second.connection_state, TranscribeConnectionState::Connected);
+        assert_eq!(second_backend.received(0), vec![voice(8000), voice(9000)]);
+
+        // 2つ目が接続中なので、1つ目は次の発話で待つ
+        first.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(first.connection_state, TranscribeConnectionState::Disconnected);
+        assert_eq!(first_backend.stream_count(), 1);
+
+        first.stop().await.unwrap();
+        second.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_buffered_audio_resent_after_failed_reconnect() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+
+        // 再接続に失敗した音声は保持され、次の再接続時に先に送られる
+        backend.fail_next_starts(1);
+        assert!(processor.process_chunk(chunk(voice(8000))).await.is_err());
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert_eq!(backend.stream_count(), 0);
+        // 保持中の音声はメモリ使用量に切断中バッファとして現れる（1600サンプル = 0.1秒）
+        assert_eq!(processor.disconnect_buffer_bytes(), 3200);
+        assert!((processor.disconnect_buffer_secs() - 0.1).abs() < 1e-9);
+        let usage = processor.memory_usage(2);
+        assert_eq!(usage.disconnect_buffers, 3200);
+        assert_eq!(usage.queues, 2 * 3200);
+
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        assert_eq!(backend.stream_count(), 1);
+        assert_eq!(backend.received(0), vec![voice(8000), voice(9000)]);
+        assert_eq!(processor.disconnect_buffer_bytes(), 0);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_disconnect_buffer_limit() {
+        for overflow in [DisconnectBufferOverflow::DropOldest, DisconnectBufferOverflow::DiscardAll] {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let config = TranscribeConfig {
+                max_disconnect_buffer_secs: 1,
+                disconnect_buffer_overflow: overflow,
+                ..TranscribeConfig::default()
+            };
+            let (mut processor, backend) = mock_processor(temp_dir.path(), config);
+            let limiter = StreamLimiter::new(1);
+            processor.set_stream_limiter(limiter.clone());
+            processor.start().await.unwrap();
+
+            // 他のチャンネルが枠を使っている間に 1.2秒分の音声が続く（上限は1秒）
+            let other = limiter.try_acquire().unwrap();
+            for i in 0..12 {
+                processor.process_chunk(chunk(voice(8000 + i))).await.unwrap();
+            }
+            // drop_oldest は超えた 0.2秒分、discard_all は上限超過時にためていた分と以降の分をすべて捨てる
+            let expected_dropped_secs = match overflow {
+                DisconnectBufferOverflow::DropOldest => 0.2,
+                DisconnectBufferOverflow::DiscardAll => 1.2,
+            };
+            assert!((processor.disconnect_dropped_secs() - expected_dropped_secs).abs() < 1e-9);
+
+            drop(other);
+            processor.process_chunk(chunk(voice(9000))).await.unwrap();
+            let received = backend.received(0);
+            match overflow {
+                // 最古の 0.2秒分を捨て、直近の1秒分を送る
+                DisconnectBufferOverflow::DropOldest => {
+                    let expected: Vec<_> = (2..12).map(|i| voice(8000 + i)).chain([voice(9000)]).collect();
+                    assert_eq!(received, expected);
+                }
+                // 上限を超えた時点でためた分を捨て、以降も再接続まではためない
+                DisconnectBufferOverflow::DiscardAll => assert_eq!(received, vec![voice(9000)]),
+            }
+            assert_eq!(processor.disconnect_buffer_bytes(), 0);
+            processor.stop().await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_drop_oldest_splits_chunk() {
+        let mut buffered = DisconnectBuffer::default();
+        for value in 1..=3 {
+            buffered.push(vec![value; 4]);
+        }
+        assert_eq!(buffered.drop_oldest(6), 6);
+        assert_eq!(buffered.chunks, vec![vec![2; 2], vec![3; 4]]);
+        assert_eq!(buffered.samples(), 6);
+        assert_eq!(buffered.drop_oldest(100), 6);
+        assert_eq!(buffered.len(), 0);
+        assert_eq!(buffered.samples(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cost_counts_resent_and_silence() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        let tui_state = TuiState::new();
+        tui_state.add_channel(0, "モック".to_string());
+        processor.set_tui_state(tui_state.clone());
+        processor.enable_cost_tracking(&CostConfig::default());
+        processor.start().await.unwrap();
+
+        // 再接続時に再送した音声と、接続中に送ったゼロサンプルも数える（切断するチャンクは送らない）
+        backend.fail_next_starts(1);
+        assert!(processor.process_chunk(chunk(voice(8000))).await.is_err());
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        for _ in 0..3 {
+            processor.process_chunk(chunk(silence())).await.unwrap();
+        }
+        assert_eq!(backend.received(0).len(), 4);
+
+        let summary = processor.cost_summary().unwrap();
+        assert!((summary.sent_secs - 0.4).abs() < 1e-9, "{:?}", summary);
+        assert!((summary.cost - 0.4 / 60.0 * 0.024).abs() < 1e-12, "{:?}", summary);
+        assert_eq!(tui_state.get_channel(0).unwrap().cost, Some(summary));
+        assert_eq!(processor.cost_breakdown().unwrap(), "Amazon Transcribe 0.4秒 = 0.0002 USD");
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_buffered_audio_dropped_when_disabled() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let transcribe_config = TranscribeConfig {
+            send_buffered_on_reconnect: false,
+            ..TranscribeConfig::default()
+        };
+        let (mut processor, backend) = mock_processor(temp_dir.path(), transcribe_config);
+        processor.start().await.unwrap();
+
+        backend.fail_next_starts(1);
+        assert!(processor.process_chunk(chunk(voice(8000))).await.is_err());
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+
+        // 失敗時の音声は再送しない
+        assert_eq!(backend.received(0), vec![voice(9000)]);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_events() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let audit_path = temp_dir.path().join("audit.jsonl");
+        let audit_config = crate::config::AuditConfig {
+            file: audit_path.to_string_lossy().into_owned(),
+        };
+        let (audit_sink, audit_task) = AuditSink::spawn(&audit_config, OutputTimeZone::Utc).unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.set_audit_sink(audit_sink);
+        processor.start().await.unwrap();
+
+        backend.fail_next_starts(1);
+        assert!(processor.process_chunk(chunk(voice(8000))).await.is_err());
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        for _ in 0..4 {
+            processor.process_chunk(chunk(silence())).await.unwrap();
+        }
+        processor.stop().await.unwrap();
+        // stop で送信側を手放すため、チャンネルが残っていても監査ログの書き込みは終わる
+        audit_task.await.unwrap();
+
+        let events: Vec<serde_json::Value> = std::fs::read_to_string(&audit_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let kinds: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+        assert_eq!(
+            kinds,
+            vec!["voice_start", "connect_failed", "connect", "buffer_sent", "voice_end", "disconnect"]
+        );
+        assert!(events.iter().all(|e| e["channel"] == 0));
+        assert!(events[0]["volume_db"].as_f64().unwrap() > -40.0);
+        assert_eq!(events[3]["buffered_chunks"], 1);
+        assert_eq!(events[3]["buffered_ms"], 100);
+        assert_eq!(events[5]["silence_ms"], 300);
+    }
+
+    #[tokio::test]
+    async fn test_switch_backend_after_utterance() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, old_backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        let tui_state = TuiState::new();
+        tui_state.add_channel(0, "モック".to_string());
+        processor.set_tui_state(tui_state.clone());
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+
+        // 接続中は発話が終わるまで切り替えない
+        let new_backend = MockBackend::new(0);
+        processor.switch_backend("新", Box::new(new_backend.clone()));
+        assert_eq!(
+            tui_state.get_channel(0).unwrap().backend_switch,
+            Some(BackendSwitchStatus::Pending("新".to_string()))
+        );
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(old_backend.received(0), vec![voice(8000), voice(8000)]);
+
+        // 無音で切断したら切り替える（録音は続く）
+        for _ in 0..4 {
+            processor.process_chunk(chunk(silence())).await.unwrap();
+        }
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert_eq!(
+            tui_state.get_channel(0).unwrap().backend_switch,
+            Some(BackendSwitchStatus::Switched("新".to_string()))
+        );
+        assert_eq!(processor.wav_samples_written(), 1600 * 6);
+
+        // 次の音声検出から新しいバックエンドを使う
+        old_backend.push_result(TranscriptResult::new(0, "旧".to_string(), false, None, SystemTime::now()));
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        assert_eq!(old_backend.stream_count(), 1);
+        assert_eq!(new_backend.stream_count(), 1);
+        assert_eq!(new_backend.received(0), vec![voice(9000)]);
+
+        // 切り替え前のストリームの確定結果も取り切る
+        new_backend.push_result(TranscriptResult::new(0, "新".to_string(), false, None, SystemTime::now()));
+        let texts: Vec<String> = processor.poll_transcripts().await.into_iter().map(|r| r.text).collect();
+        assert_eq!(texts, vec!["旧", "新"]);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_switch_backend_reverts_on_failure() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, old_backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        let tui_state = TuiState::new();
+        tui_state.add_channel(0, "モック".to_string());
+        processor.set_tui_state(tui_state.clone());
+        processor.start().await.unwrap();
+
+        // 未接続ならすぐに切り替わる
+        let new_backend = MockBackend::new(0);
+        new_backend.fail_next_starts(1);
+        processor.switch_backend("新", Box::new(new_backend.clone()));
+        assert_eq!(
+            tui_state.get_channel(0).unwrap().backend_switch,
+            Some(BackendSwitchStatus::Switched("新".to_string()))
+        );
+
+        // 切り替え後の最初の接続に失敗したら元のバックエンドに戻す
+        assert!(processor.process_chunk(chunk(voice(8000))).await.is_err());
+        assert_eq!(
+            tui_state.get_channel(0).unwrap().backend_switch,
+            Some(BackendSwitchStatus::Failed("Amazon Transcribe".to_string()))
+        );
+        processor.process_chunk(chunk(voice(9000))).await.unwrap();
+        assert_eq!(new_backend.stream_count(), 0);
+        assert_eq!(old_backend.stream_count(), 1);
+        assert_eq!(old_backend.received(0), vec![voice(8000), voice(9000)]);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_poll_transcripts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+
+        backend.push_result(final_result("了解しました", 0.5));
+        let results = processor.poll_transcripts().await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "了解しました");
+        assert_eq!(results[0].channel_name.as_deref(), Some("モック"));
+        assert!(processor.poll_transcripts().await.is_empty());
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_auto_language_switch() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let transcribe_config = TranscribeConfig {
+            allowed_languages: vec!["ja-JP".to_string(), "en-US".to_string()],
+            ..TranscribeConfig::default()
+        };
+        let (mut processor, backend) = mock_processor(temp_dir.path(), transcribe_config);
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+
+        // バックエンドの検出がなければ文字種から判定し、英語に切り替える
+        backend.push_result(final_result("Copy that, heading out", 0.5));
+        let results = processor.poll_transcripts().await;
+        assert_eq!(results[0].detected_language.as_deref(), Some("en-US"));
+        assert_eq!(backend.languages(), vec!["en-US"]);
+
+        // 同じ言語が続く間は切り替えない。候補外の検出は無視する
+        backend.push_result(final_result("Roger", 1.0).with_detected_language(Some("en-US".to_string())));
+        backend.push_result(final_result("알겠습니다", 1.5).with_detected_language(Some("ko-KR".to_string())));
+        let results = processor.poll_transcripts().await;
+        assert_eq!(results[0].detected_language.as_deref(), Some("en-US"));
+        assert_eq!(results[1].detected_language, None);
+        assert_eq!(backend.languages(), vec!["en-US"]);
+
+        backend.push_result(final_result("了解しました", 2.0));
+        processor.poll_transcripts().await;
+        assert_eq!(backend.languages(), vec!["en-US", "ja-JP"]);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+
+        // 一時停止で切断し、WAVを閉じる。一時停止中の入力は録音も送信もしない
+        processor.pause().await.unwrap();
+        assert!(processor.is_paused());
+        assert!(backend.is_closed(0));
+        assert_eq!(processor.wav_samples_written(), 0);
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(backend.stream_count(), 1);
+        assert_eq!(processor.wav_samples_written(), 0);
+
+        // 再開後は新しいWAVに書き込み、音声検出で再接続する
+        processor.resume().unwrap();
+        assert!(!processor.is_paused());
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 1600);
+        assert_eq!(backend.stream_count(), 2);
+
+        processor.stop().await.unwrap();
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_suspend_recording_during_input_recovery() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+
+        // 復帰処理中はWAVを閉じて書き込まないが、文字起こしは続ける
+        processor.suspend_recording(RecordingSuspendReason::InputRecovery).unwrap();
+        assert_eq!(processor.wav_samples_written(), 0);
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 0);
+        assert!(!backend.is_closed(0));
+
+        // 復帰後は新しいファイルに書き込む
+        processor.resume_recording(RecordingSuspendReason::InputRecovery);
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 1600);
+
+        processor.stop().await.unwrap();
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_suspend_recording_reasons_are_independent() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, _backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+
+        processor.suspend_recording(RecordingSuspendReason::LowDiskSpace).unwrap();
+        processor.suspend_recording(RecordingSuspendReason::InputRecovery).unwrap();
+
+        // 入力が復帰しても、空き容量が足りない間は録音しない
+        processor.resume_recording(RecordingSuspendReason::InputRecovery);
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 0);
+
+        processor.resume_recording(RecordingSuspendReason::LowDiskSpace);
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 1600);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clip_path_matches_result_audio_time() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.enable_segment_clips(temp_dir.path().join("clips")).unwrap();
+        processor.start_time = UNIX_EPOCH + Duration::from_secs(1_735_689_600);
+        processor.start().await.unwrap();
+
+        // 開始から10秒後に発話
+        let base_ns = processor.start_time.duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        for i in 0..3 {
+            let mut voice_chunk = chunk(voice(8000));
+            voice_chunk.timestamp_ns = base_ns + 10_000_000_000 + i * 100_000_000;
+            processor.process_chunk(voice_chunk).await.unwrap();
+        }
+
+        // 音声の時刻を持つ結果は区間のクリップに紐づけ、持たない結果には付けない
+        backend.push_result(TranscriptResult::new_with_audio_time(
+            0,
+            "発話".to_string(),
+            false,
+            None,
+            10.05,
+            Some(10.25),
+        ));
+        backend.push_result(TranscriptResult::new(0, "時刻なし".to_string(), false, None, SystemTime::now()));
+        backend.push_result(TranscriptResult::new_with_audio_time(
+            0,
+            "区間外".to_string(),
+            false,
+            None,
+            5.0,
+            Some(6.0),
+        ));
+        let results = processor.poll_transcripts().await;
+        assert_eq!(results.len(), 3);
+        assert!(results[0].clip_path.as_deref().unwrap().contains("clips"));
+        assert_eq!(results[1].clip_path, None);
+        assert_eq!(results[2].clip_path, None);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_low_disk_space_suspends_segment_clips() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let clip_dir = temp_dir.path().join("clips");
+        let (mut processor, _backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.enable_segment_clips(&clip_dir).unwrap();
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert!(processor.vad.is_voice());
+
+        // 中断時に書き込み中のクリップを閉じ、中断中は新しいクリップを作らない
+        processor.suspend_recording(RecordingSuspendReason::LowDiskSpace).unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(std::fs::read_dir(&clip_dir).unwrap().count(), 1);
+
+        // 空き容量の回復ではVADをリセットしない
+        assert!(processor.vad.is_voice());
+        processor.resume_recording(RecordingSuspendReason::LowDiskSpace);
+        assert!(processor.vad.is_voice());
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_stats_match_recorded_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, _) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+
+        // 一時停止をまたいで2つのファイルに分かれる
+        processor.pause().await.unwrap();
+        processor.resume().unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.stop().await.unwrap();
+
+        let stats = processor.session_stats();
+        assert_eq!(stats.utterances, 2);
+        assert_eq!(stats.file_count(), 2);
+        assert!((stats.duration_secs() - 0.4).abs() < 1e-9);
+        let actual_bytes: u64 = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().metadata().unwrap().len())
+            .sum();
+        assert_eq!(stats.bytes(), actual_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_pause_with_buffered_input() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.pause_input = PauseInput::Buffer;
+        processor.start().await.unwrap();
+
+        processor.pause().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+
+        // 保持した入力は新しいWAVの先頭に書き込むが、文字起こしには送らない
+        processor.resume().unwrap();
+        assert_eq!(processor.wav_samples_written(), 3200);
+        assert_eq!(backend.stream_count(), 0);
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sidecar_metadata_on_finalize() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, _backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.enable_sidecar_metadata();
+        processor.start().await.unwrap();
+
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        for _ in 0..3 {
+            processor.process_chunk(chunk(silence())).await.unwrap();
+        }
+        processor.stop().await.unwrap();
+
+        let yaml: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "yaml"))
+            .collect();
+        assert_eq!(yaml.len(), 1);
+
+        let metadata = RecordingMetadata::load(&yaml[0]).unwrap();
+        assert_eq!(metadata.recording, yaml[0].with_extension("wav").file_name().unwrap().to_string_lossy());
+        assert_eq!(metadata.channel_name, "モック");
+        assert_eq!(metadata.sample_rate, 16000);
+        assert_eq!(metadata.duration_secs, 0.5);
+        let speech = metadata.speech.unwrap();
+        assert_eq!(speech.segments, 1);
+        assert!(speech.voiced_secs >= 0.1);
+        assert!(metadata.notes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_predictive_connect() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let transcribe_config = TranscribeConfig {
+            predictive_connect: true,
+            ..TranscribeConfig::default()
+        };
+        let (mut processor, backend) = mock_processor(temp_dir.path(), transcribe_config);
+        processor.start().await.unwrap();
+
+        // 閾値 -40dB の手前（約 -43dB）の立ち上がりで先行接続し、その音声も送る
+        processor.process_chunk(chunk(voice(232))).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Connected);
+        assert_eq!(backend.received(0), vec![voice(232)]);
+
+        // 音声と確定すれば通常どおり送信を続ける
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(backend.received(0).len(), 2);
+
+        // 無音で切断された後の立ち上がりは再び先行接続する
+        for _ in 0..3 {
+            processor.process_chunk(chunk(silence())).await.unwrap();
+        }
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        processor.process_chunk(chunk(voice(232))).await.unwrap();
+        assert_eq!(backend.stream_count(), 2);
+
+        // 音声と確定しないまま 1.5 秒経過したら切断（先行接続したチャンクも含めて数える）
+        for _ in 0..13 {
+            processor.process_chunk(chunk(silence())).await.unwrap();
+        }
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Connected);
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert!(backend.is_closed(1));
+
+        processor.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_staggered_recording_start() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wav_files = || {
+            std::fs::read_dir(temp_dir.path())
+                .unwrap()
+                .filter(|entry| entry.as_ref().unwrap().path().extension().is_some_and(|e| e == "wav"))
+                .count()
+        };
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.set_recording_start_delay_ms(250);
+        processor.start().await.unwrap();
+        assert_eq!(wav_files(), 0);
 
-        // 送信チャンネルをドロップすることで接続終了
-        self.transcribe_tx = None;
-        self.connection_state = TranscribeConnectionState::Disconnected;
-        self.silence_duration_ms = 0;
+        // ずらしている間もVAD・文字起こしは通常どおり
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(wav_files(), 0);
+        assert_eq!(backend.received(0).len(), 2);
 
-        // TUI状態を未接続に更新
-        if let Some(tui_state) = &self.tui_state {
-            tui_state.update_channel(self.channel_id, |channel| {
-                channel.update_transcribe_status(TranscribeStatus::Disconnected);
-            });
+        // 250ms を超えたらファイルを開き、保持していた音声を先頭に書き込む
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(wav_files(), 1);
+        assert_eq!(processor.wav_samples_written(), 1600 * 3);
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 1600 * 4);
+        processor.stop().await.unwrap();
+
+        // 開始前に停止しても保持していた音声は書き出す
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, _) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.set_recording_start_delay_ms(1000);
+        processor.start().await.unwrap();
+        processor.process_chunk(chunk(silence())).await.unwrap();
+        processor.stop().await.unwrap();
+        let path = std::fs::read_dir(temp_dir.path()).unwrap().next().unwrap().unwrap().path();
+        assert_eq!(hound::WavReader::open(path).unwrap().len(), 1600);
+    }
+
+    /// 1kHz の矩形波のビープ（`(開始サンプル, 長さ)`）を含む無音の信号
+    fn beep_signal(len: usize, beeps: &[(usize, usize)]) -> Vec<i16> {
+        let mut signal = vec![0i16; len];
+        for &(onset, duration) in beeps {
+            for (i, sample) in signal[onset..onset + duration].iter_mut().enumerate() {
+                *sample = if (i / 8) % 2 == 0 { 8000 } else { -8000 };
+            }
         }
+        signal
+    }
 
-        Ok(())
+    /// 無音（10ms 以上のゼロ）の後に始まるビープの開始位置
+    fn beep_onsets(samples: &[i16]) -> Vec<usize> {
+        (0..samples.len())
+            .filter(|&i| samples[i] != 0 && samples[i.saturating_sub(160)..i].iter().all(|&s| s == 0))
+            .collect()
     }
 
-    /// 文字起こし結果を取得（non-blocking）
-    pub async fn poll_transcripts(&mut self) -> Vec<TranscriptResult> {
-        let mut results = Vec::new();
+    #[tokio::test]
+    async fn test_recording_and_stream_timing_aligned() {
+        const BASE_NS: u64 = 1_000_000_000;
+        const CHUNK_NS: u64 = 100_000_000;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_events = events.clone();
+        processor.add_vad_hook(move |event| hook_events.lock().unwrap().push((event.kind, event.timestamp_ns)));
+        // 録音開始の遅延（保持した音声をファイル先頭に書く）も含めて検証する
+        processor.set_recording_start_delay_ms(250);
+        processor.start().await.unwrap();
 
-        if let Some(rx) = &mut self.transcribe_rx {
-            // 利用可能な全ての結果を取得
-            while let Ok(result) = rx.try_recv() {
-                log::debug!(
-                    "チャンネル {}: 文字起こし結果受信 - テキスト: '{}', 部分結果: {}",
-                    self.channel_id,
-                    result.text,
-                    result.is_partial
-                );
-                results.push(result);
-            }
-        } else {
-            // transcribe_rxがNoneの場合（未接続または切断中）
-            if self.connection_state == TranscribeConnectionState::Disconnected {
-                log::trace!("チャンネル {}: Transcribe未接続のため結果なし", self.channel_id);
+        // 0.55秒から200ms、2.23秒から150ms のビープ（チャンク境界に揃えない）
+        let beeps = [(8_800, 3_200), (35_680, 2_400)];
+        let signal = beep_signal(16000 * 3, &beeps);
+        for (i, samples) in signal.chunks(1600).enumerate() {
+            let mut chunk = chunk(samples.to_vec());
+            chunk.timestamp_ns = (BASE_NS + i as u64 * CHUNK_NS) as u128;
+            // 2回目の発話は最初の接続に失敗させ、再接続時に切断中のバッファ（プリロール）から送らせる
+            if i == 22 {
+                backend.fail_next_starts(1);
+                assert!(processor.process_chunk(chunk).await.is_err());
+            } else {
+                processor.process_chunk(chunk).await.unwrap();
             }
         }
+        processor.stop().await.unwrap();
 
-        results
+        // WAV 上のビープ位置は入力と一致する
+        let path = std::fs::read_dir(temp_dir.path()).unwrap().next().unwrap().unwrap().path();
+        let recorded: Vec<i16> = hound::WavReader::open(path).unwrap().samples::<i16>().map(Result::unwrap).collect();
+        assert_eq!(recorded.len(), signal.len());
+        let onsets = beep_onsets(&recorded);
+        assert_eq!(onsets, vec![8_800, 35_680]);
+
+        // VAD の開始はビープを含むチャンクの先頭、終了はビープ終了 + ハングオーバー 100ms 以内のチャンク境界
+        let ns_at = |sample: usize| BASE_NS as u128 + sample as u128 * 1_000_000_000 / 16000;
+        let events = events.lock().unwrap().clone();
+        assert_eq!(events.len(), 4);
+        for (k, &(onset, duration)) in beeps.iter().enumerate() {
+            let (start_kind, start_ns) = events[k * 2];
+            let (end_kind, end_ns) = events[k * 2 + 1];
+            assert_eq!(start_kind, VadEventKind::VoiceStart);
+            assert_eq!(end_kind, VadEventKind::VoiceEnd);
+            assert!(start_ns <= ns_at(onset) && ns_at(onset) - start_ns < CHUNK_NS as u128);
+            let end_limit = ns_at(onset + duration) + 100_000_000 + CHUNK_NS as u128;
+            assert!(end_ns >= ns_at(onset + duration) && end_ns <= end_limit, "voice end {} > {}", end_ns, end_limit);
+
+            // ストリームの音声は VAD の開始チャンクから始まる（ストリーム内の時刻 + 開始時刻 = 録音上の時刻）
+            let sent: Vec<i16> = backend.received(k).concat();
+            let stream_onset = beep_onsets(&sent)[0];
+            assert_eq!(start_ns + (stream_onset as u128 * 1_000_000_000 / 16000), ns_at(onsets[k]));
+        }
+        assert_eq!(backend.stream_count(), 2);
     }
 
-    /// 処理を停止
-    pub async fn stop(&mut self) -> Result<()> {
-        log::info!(
-            "チャンネル {} ({}) の処理を停止",
-            self.channel_id,
-            self.channel_name
-        );
+    #[tokio::test]
+    async fn test_postprocess_per_channel() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let common = PostprocessConfig {
+            normalize_width: true,
+            replacements: vec![crate::config::ReplacementRule {
+                from: "本分".to_string(),
+                to: "本部".to_string(),
+            }],
+            ..PostprocessConfig::default()
+        };
+        let english = common.with_override(Some(&crate::config::PostprocessOverride {
+            remove_fillers: Some(false),
+            replacements: Some(Vec::new()),
+            ..Default::default()
+        }));
 
-        // Transcribeストリームをクローズ
-        self.transcribe_tx = None;
+        // 日本語チャンネル: 共通設定（正規化・フィラー削除・置換）
+        let (mut ja, _) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        ja.set_postprocess(common.clone());
+        assert_eq!(ja.postprocess_text("えっと 本分、ＡＢ１２号車どうぞ"), "本部、AB12号車どうぞ");
 
-        // WAVファイルを終了
-        self.wav_writer.finalize()?;
+        // 英語チャンネル: フィラー削除と置換を無効化（"あの" を含む固有名詞も残す）
+        let (mut en, _) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        en.set_postprocess(english);
+        assert_eq!(en.postprocess_text("Copy that, あの\u{3000}Ｕｎｉｔ ２ 本分"), "Copy that, あの Unit 2 本分");
 
-        Ok(())
+        // 後処理を全部無効にすれば前後の空白を除くだけ
+        en.set_postprocess(PostprocessConfig {
+            remove_fillers: false,
+            ..PostprocessConfig::default()
+        });
+        assert_eq!(en.postprocess_text(" えっと ＡＢ "), "えっと ＡＢ");
+        assert!(!en.postprocess().remove_fillers);
     }
 
-    /// チャンネルIDを取得
-    pub fn channel_id(&self) -> usize {
-        self.channel_id
+    #[tokio::test]
+    async fn test_postprocess_result_keeps_raw_text_and_steps() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (mut processor, _) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        processor.set_postprocess(PostprocessConfig {
+            normalize_width: true,
+            replacements: vec![crate::config::ReplacementRule {
+                from: "本分".to_string(),
+                to: "本部".to_string(),
+            }],
+            ..PostprocessConfig::default()
+        });
+
+        let mut result = TranscriptResult::new(0, "えっと 本分、１号車".to_string(), false, None, SystemTime::now());
+        processor.postprocess_result(&mut result);
+        assert_eq!(result.text, "本部、1号車");
+        assert_eq!(result.raw_text.as_deref(), Some("えっと 本分、１号車"));
+        let steps: Vec<(PostprocessStage, &str)> =
+            result.postprocess.iter().map(|step| (step.stage, step.text.as_str())).collect();
+        assert_eq!(
+            steps,
+            vec![
+                (PostprocessStage::NormalizeWidth, "えっと 本分、1号車"),
+                (PostprocessStage::RemoveFillers, "本分、1号車"),
+                (PostprocessStage::Replacements, "本部、1号車"),
+            ]
+        );
+
+        // テキストが変わらない段は記録しない
+        let mut unchanged = TranscriptResult::new(0, "了解".to_string(), false, None, SystemTime::now());
+        processor.postprocess_result(&mut unchanged);
+        assert_eq!(unchanged.text, "了解");
+        assert_eq!(unchanged.raw_text.as_deref(), Some("了解"));
+        assert!(unchanged.postprocess.is_empty());
     }
 
-    /// チャンネル名を取得
-    pub fn channel_name(&self) -> &str {
-        &self.channel_name
+    #[tokio::test]
+    async fn test_warmup_suppresses_vad() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut processor = offline_processor(temp_dir.path()).await;
+        processor.set_warmup_ms(200);
+        processor.start().await.unwrap();
+
+        // 起動直後のポップノイズ（フルスケール）100ms × 2
+        let pop = vec![i16::MAX; 1600];
+        processor.process_chunk(chunk(pop.clone())).await.unwrap();
+        processor.process_chunk(chunk(pop)).await.unwrap();
+
+        // VADは発火せず、接続もしない。WAVには同じ長さの無音を書き込む
+        assert_eq!(processor.vad_state(), VadState::Silence);
+        assert_eq!(processor.connection_state, TranscribeConnectionState::Disconnected);
+        assert_eq!(processor.wav_samples_written(), 3200);
+        assert_eq!(processor.warmup_remaining_samples, 0);
+
+        // ウォームアップ後は通常どおりVAD判定する（無音なので接続しない）
+        processor.process_chunk(chunk(vec![0i16; 1600])).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 4800);
+        assert!(processor.current_volume_db() <= -100.0);
+
+        processor.stop().await.unwrap();
     }
 
-    /// WAV書き込み時間を取得
-    pub fn wav_duration_seconds(&self) -> f64 {
-        self.wav_writer.duration_seconds()
+    #[tokio::test]
+    async fn test_latency_compensation_applies_to_wav() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut processor = offline_processor(temp_dir.path()).await;
+        processor.latency_compensator = Some(ChunkLatencyCompensator::new(-50, 16000, InternalFormat::I16));
+        processor.start().await.unwrap();
+
+        // 50ms (800サンプル) 分は読み捨てられ、WAVにも書き込まれない
+        processor.process_chunk(chunk(vec![0i16; 480])).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 0);
+        processor.process_chunk(chunk(vec![0i16; 480])).await.unwrap();
+        assert_eq!(processor.wav_samples_written(), 160);
+
+        processor.stop().await.unwrap();
     }
 
-    /// バッファサイズを取得
-    pub fn buffer_duration_seconds(&self) -> f64 {
-        self.buffer.duration_seconds()
+    /// 音声区間だけを録音するプロセッサに、小さな雑音・発話・無音・雑音・発話の順で入力する
+    async fn record_vad_triggered(output_dir: &std::path::Path, files: VadRecordFiles) {
+        let (mut processor, _backend) = mock_processor(output_dir, TranscribeConfig::default());
+        processor.vad_record = Some(VadRecordGate::new(100, 16000));
+        processor.vad_record_files = files;
+        processor.start().await.unwrap();
+        for samples in [
+            voice(10),
+            voice(10),
+            voice(8000),
+            voice(8000),
+            silence(),
+            silence(),
+            silence(),
+            silence(),
+            voice(10),
+            voice(9000),
+            silence(),
+            silence(),
+        ] {
+            processor.process_chunk(chunk(samples)).await.unwrap();
+        }
+        processor.stop().await.unwrap();
     }
 
-    /// VAD状態を取得
-    pub fn vad_state(&self) -> VadState {
-        self.vad.get_state()
+    fn read_recordings(output_dir: &std::path::Path) -> Vec<Vec<i16>> {
+        let mut paths: Vec<_> = std::fs::read_dir(output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+            .collect();
+        paths.sort();
+        paths
+            .iter()
+            .map(|path| hound::WavReader::open(path).unwrap().samples::<i16>().map(Result::unwrap).collect())
+            .collect()
     }
 
-    /// 最新のボリューム（dB）を取得
-    pub fn current_volume_db(&self) -> f32 {
-        self.vad.get_last_volume_db()
+    #[tokio::test]
+    async fn test_vad_triggered_recording_per_segment() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        record_vad_triggered(temp_dir.path(), VadRecordFiles::PerSegment).await;
+
+        let recordings = read_recordings(temp_dir.path());
+        assert_eq!(recordings.len(), 2);
+        // 直前の100ms（プリロール）から始まり、語頭を欠かさない
+        assert_eq!(&recordings[0][..1600], &voice(10)[..]);
+        assert_eq!(&recordings[0][1600..4800], &[voice(8000), voice(8000)].concat()[..]);
+        // 語尾はハングオーバー分まで含み、その後の無音は書き込まない
+        assert!(recordings[0].len() < 1600 * 6);
+        assert_eq!(&recordings[1][..1600], &voice(10)[..]);
+        assert_eq!(&recordings[1][1600..3200], &voice(9000)[..]);
     }
 
-    /// フィラーワード（言い淀み）を削除
-    pub fn remove_filler_words(text: &str) -> String {
-        // 削除対象のフィラーワードリスト
-        let filler_words = [
-            "えっと",
-            "あの",
-            "ええと",
-            "ええ",
-            "えー",
-            "えーと",
-            "あのー",
-            "っと",
-            "っとー",
-        ];
+    #[tokio::test]
+    async fn test_vad_triggered_recording_single_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        record_vad_triggered(temp_dir.path(), VadRecordFiles::Single).await;
 
-        let mut result = text.to_string();
+        let recordings = read_recordings(temp_dir.path());
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(&recordings[0][..1600], &voice(10)[..]);
 
-        // 各フィラーワードを削除
-        for filler in &filler_words {
-            // 完全一致する単語を削除（前後に空白がある場合）
-            result = result.replace(&format!("{} ", filler), "");
-            result = result.replace(&format!(" {}", filler), "");
-            // 文頭・文末の場合
-            if result.starts_with(filler) {
-                result = result[filler.len()..].to_string();
+        // 2つの区間の位置と実時刻をセグメント一覧に記録する
+        let csv_path = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().ends_with(".segments.csv"))
+            .unwrap();
+        let csv = std::fs::read_to_string(csv_path).unwrap();
+        let rows: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("0.000,"));
+        let second_offset: f64 = rows[1].split(',').next().unwrap().parse().unwrap();
+        let second_start = (second_offset * 16000.0).round() as usize;
+        assert_eq!(&recordings[0][second_start..second_start + 3200], &[voice(10), voice(9000)].concat()[..]);
+    }
+
+    #[tokio::test]
+    async fn test_save_wav_disabled_creates_no_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("recordings");
+        let (_, backend) = mock_processor(temp_dir.path(), TranscribeConfig::default());
+        let output_config = OutputConfig {
+            wav_output_dir: output_dir.to_string_lossy().into_owned(),
+            save_wav: false,
+            wav_rotation_secs: Some(1),
+            sidecar_metadata: true,
+            ..OutputConfig::default()
+        };
+        let mut processor = ChannelProcessor::new_with_backend(
+            &ChannelConfig {
+                id: 0,
+                name: "文字起こしのみ".to_string(),
+                enabled: true,
+                send_buffered_on_reconnect: None,
+                min_utterance_ms: None,
+                max_utterance_ms: None,
+                latency_offset_ms: 0,
+                log_level: None,
+                display_order: None,
+                symbol: None,
+                postprocess: None,
+                record_mode: None,
+            },
+            &VadConfig {
+                threshold_db: -40.0,
+                hangover_duration_ms: 100,
+                silence_disconnect_threshold_ms: 300,
+                smoothing_window_ms: 0,
+            },
+            &BufferConfig::default(),
+            &TranscribeConfig::default(),
+            &output_config,
+            16000,
+            Box::new(backend.clone()),
+        )
+        .unwrap();
+        processor.enable_sidecar_metadata();
+
+        processor.start().await.unwrap();
+        for samples in [voice(8000), voice(8000), silence(), silence()] {
+            processor.process_chunk(chunk(samples)).await.unwrap();
+        }
+        processor.pause().await.unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        processor.resume().unwrap();
+        processor.process_chunk(chunk(voice(8000))).await.unwrap();
+        assert_eq!(processor.wav_duration_seconds(), 0.0);
+        processor.stop().await.unwrap();
+
+        // 文字起こしは行うが、WAVもその保存先ディレクトリも作らない
+        assert!(backend.stream_count() >= 1);
+        assert!(!output_dir.exists());
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_f32_input_quantized_before_wav() {
+        let input: Vec<f32> = (0..3200)
+            .map(|i| if i < 1600 { (i as f32 * 0.1).sin() * 0.3 } else { 0.0 })
+            .collect();
+
+        // f32 のチャンクと、同じ信号を量子化した i16 のチャンクで、遅延補正込みの WAV と VAD 状態が一致する
+        let mut recorded = Vec::new();
+        for use_f32 in [true, false] {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let mut processor = offline_processor(temp_dir.path()).await;
+            processor.latency_offset_ms = 10;
+            processor.latency_compensator = Some(ChunkLatencyCompensator::new(10, 16000, InternalFormat::I16));
+            if use_f32 {
+                processor.set_internal_format(InternalFormat::F32);
             }
-            if result.ends_with(filler) {
-                result = result[..result.len() - filler.len()].to_string();
+            processor.start().await.unwrap();
+            for part in input.chunks(1600) {
+                let chunk = if use_f32 {
+                    AudioChunk {
+                        samples_f32: Some(part.to_vec()),
+                        ..chunk(Vec::new())
+                    }
+                } else {
+                    chunk(part.iter().copied().map(quantize_to_i16).collect())
+                };
+                processor.process_chunk(chunk).await.unwrap();
+                assert_eq!(processor.wav_samples_written() % 1600, 0);
             }
-        }
+            let vad_state = processor.vad_state();
+            processor.stop().await.unwrap();
 
-        // 連続する空白を1つにまとめる
-        while result.contains("  ") {
-            result = result.replace("  ", " ");
+            let path = std::fs::read_dir(temp_dir.path()).unwrap().next().unwrap().unwrap().path();
+            let samples: Vec<i16> =
+                hound::WavReader::open(path).unwrap().samples::<i16>().map(Result::unwrap).collect();
+            recorded.push((samples, vad_state));
         }
-
-        // 前後の空白を削除
-        result.trim().to_string()
+        assert_eq!(recorded[0].0.len(), 3200);
+        assert_eq!(&recorded[0].0[..160], &[0; 160]);
+        assert_eq!(recorded[0], recorded[1]);
     }
 
-    /// 句読点のみの行かどうかをチェック
-    pub fn is_punctuation_only(text: &str) -> bool {
-        let trimmed = text.trim();
+    #[tokio::test]
+    async fn test_vad_hooks_called_in_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut processor = offline_processor(temp_dir.path()).await;
 
-        // 空文字列の場合はtrue
-        if trimmed.is_empty() {
-            return true;
+        let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        for name in ["first", "second"] {
+            let calls = calls.clone();
+            processor.add_vad_hook(move |event| {
+                calls.lock().unwrap().push((name, event.kind, event.timestamp_ns));
+            });
         }
 
-        // 句読点のみで構成されているかチェック
-        // 「、」「。」「と。」のような組み合わせ
-        let allowed_chars = ['、', '。', 'と'];
+        // 状態が変化した時だけ呼ばれる
+        processor.notify_vad_hooks(false, 0, -100.0);
+        processor.notify_vad_hooks(true, 100, -20.0);
+        processor.notify_vad_hooks(true, 200, -20.0);
+        processor.notify_vad_hooks(false, 300, -100.0);
 
-        // すべての文字が許可された文字かチェック
-        let all_punctuation = trimmed.chars().all(|c| allowed_chars.contains(&c));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                ("first", VadEventKind::VoiceStart, 100),
+                ("second", VadEventKind::VoiceStart, 100),
+                ("first", VadEventKind::VoiceEnd, 300),
+                ("second", VadEventKind::VoiceEnd, 300),
+            ]
+        );
+    }
 
-        all_punctuation
+    #[tokio::test]
+    async fn test_clip_warning_reaches_tui() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut processor = offline_processor(temp_dir.path()).await;
+        let tui_state = TuiState::new();
+        tui_state.add_channel(0, "test".to_string());
+        processor.set_tui_state(tui_state.clone());
+        processor.set_clip_detection(&ClipConfig::default());
+
+        // 1秒のうち5%が飽和した音声が3秒続く
+        let mut second = vec![1000i16; 16000];
+        second[..800].fill(i16::MAX);
+        for _ in 0..3 {
+            processor.check_clipping(&second);
+        }
+        assert_eq!(tui_state.get_channel(0).unwrap().clip_reduction_db, Some(9.0));
+
+        // 無効化していれば監視しない
+        processor.set_clip_detection(&ClipConfig {
+            enabled: false,
+            ..ClipConfig::default()
+        });
+        assert!(processor.clip_detector.is_none());
     }
 
-    /// サンプルのRMS（二乗平均平方根）を計算
-    ///
-    /// RMSは音声の平均的な大きさを表す指標
-    fn calculate_rms(samples: &[i16]) -> f32 {
-        if samples.is_empty() {
-            return 0.0;
+    /// 100ms のチャンクで発話区間を流し、送信された音声のサンプル数を返す
+    fn run_utterance(filter: &mut UtteranceFilter, voice_chunks: usize) -> usize {
+        let mut sent = 0;
+        for _ in 0..voice_chunks {
+            if let UtteranceAction::Pass(samples) = filter.process(true, vec![1; 1600], 100) {
+                sent += samples.len();
+            }
         }
+        // 無音に戻る
+        assert_eq!(
+            filter.process(false, vec![0; 1600], 100),
+            UtteranceAction::Pass(vec![0; 1600])
+        );
+        sent
+    }
 
-        let sum_of_squares: f64 = samples
-            .iter()
-            .map(|&s| {
-                let normalized = s as f64 / i16::MAX as f64;
-                normalized * normalized
-            })
-            .sum();
+    #[test]
+    fn test_recording_breaker_opens_and_retries() {
+        let start = Instant::now();
+        let mut breaker = RecordingBreaker::default();
+        assert!(breaker.allow(start));
+
+        // 閾値未満の失敗はオープンしない。成功で回数をリセット
+        assert!(!breaker.record_failure(start));
+        assert!(!breaker.record_failure(start));
+        assert!(!breaker.record_success());
+        assert!(!breaker.record_failure(start));
+        assert!(!breaker.record_failure(start));
+        assert!(breaker.allow(start));
 
-        let mean_square = sum_of_squares / samples.len() as f64;
-        mean_square.sqrt() as f32
+        // 連続して閾値に達したらオープンし、再試行の時刻まで書き込まない
+        assert!(breaker.record_failure(start));
+        assert!(breaker.is_open());
+        assert!(!breaker.allow(start + Duration::from_secs(9)));
+        let retry = start + RecordingBreaker::RETRY_INTERVAL;
+        assert!(breaker.allow(retry));
+
+        // 再試行の失敗は再びオープン（新たなオープンとしては数えない）
+        assert!(!breaker.record_failure(retry));
+        assert!(!breaker.allow(retry + Duration::from_secs(1)));
+        assert!(breaker.allow(retry + RecordingBreaker::RETRY_INTERVAL));
+
+        // 成功すれば回復
+        assert!(breaker.record_success());
+        assert!(!breaker.is_open());
+        assert!(breaker.allow(retry));
     }
 
-    /// 音声サンプルを正規化
-    ///
-    /// 目標RMSレベルに調整することで、チャンネル間の音量を統一
-    /// クリッピングを避けるため、ピーク値も考慮
-    fn normalize_samples(samples: &mut [i16]) {
-        if samples.is_empty() {
-            return;
-        }
+    #[test]
+    fn test_predictive_connector_trigger_range() {
+        let predictive = PredictiveConnector::new();
 
-        // 目標RMS（0.0 ~ 1.0）
-        // 0.1 = 約 -20dB（会話音声として適度なレベル）
-        const TARGET_RMS: f32 = 0.1;
+        // 閾値の 6dB 手前から閾値未満までを立ち上がりとみなす
+        assert!(!predictive.should_start(-50.0, -40.0));
+        assert!(predictive.should_start(-46.0, -40.0));
+        assert!(predictive.should_start(-41.0, -40.0));
+        // 閾値以上は通常の音声検出で接続する
+        assert!(!predictive.should_start(-40.0, -40.0));
+    }
 
-        // 現在のRMSを計算
-        let current_rms = Self::calculate_rms(samples);
+    #[test]
+    fn test_predictive_connector_timeout() {
+        let mut predictive = PredictiveConnector::new();
+        predictive.start();
+        assert!(predictive.is_pending());
+        // 先行接続中は重ねて開始しない
+        assert!(!predictive.should_start(-45.0, -40.0));
 
-        // RMSがほぼゼロ（無音）の場合は正規化しない
-        if current_rms < 0.001 {
-            return;
+        // 音声が確定しないまま TIMEOUT_MS 経過で切断
+        for _ in 0..14 {
+            assert!(!predictive.on_silence(100));
         }
+        assert!(predictive.on_silence(100));
+        assert!(!predictive.is_pending());
+
+        // 音声が確定すればタイムアウトしない
+        predictive.start();
+        predictive.on_silence(1000);
+        predictive.reset();
+        assert!(!predictive.on_silence(1000));
+    }
 
-        // 必要なゲインを計算
-        let gain = TARGET_RMS / current_rms;
+    #[test]
+    fn test_utterance_filter_min_boundary() {
+        let mut filter = UtteranceFilter::new(0, Some(300), None, LevelFilter::Info);
 
-        // クリッピング防止: ピーク値を確認
-        let peak = samples
-            .iter()
-            .map(|&s| s.abs())
-            .max()
-            .unwrap_or(0) as f32
-            / i16::MAX as f32;
-
-        // ゲイン適用後にクリッピングする場合は、ゲインを制限
-        let safe_gain = if peak * gain > 0.95 {
-            0.95 / peak
-        } else {
-            gain
-        };
+        // 200ms は最小長未満なので破棄
+        assert_eq!(run_utterance(&mut filter, 2), 0);
+        // ちょうど 300ms は保留していた冒頭を含めて送信
+        assert_eq!(run_utterance(&mut filter, 3), 4800);
+        // 最小長に達した後は通常どおり送信
+        assert_eq!(run_utterance(&mut filter, 5), 8000);
+    }
+
+    #[test]
+    fn test_utterance_filter_max_boundary() {
+        let mut filter = UtteranceFilter::new(0, None, Some(500), LevelFilter::Info);
 
-        // ゲインを適用（1.5倍以上のゲインは避ける = 音質劣化防止）
-        let final_gain = safe_gain.min(1.5);
+        // ちょうど 500ms は全て送信
+        assert_eq!(run_utterance(&mut filter, 5), 8000);
 
-        // サンプルに適用
-        for sample in samples.iter_mut() {
-            let normalized = (*sample as f32 * final_gain).clamp(i16::MIN as f32, i16::MAX as f32);
-            *sample = normalized as i16;
+        // 超過した最初のチャンクで Exceeded { first: true }、以降は first: false
+        for _ in 0..5 {
+            filter.process(true, vec![1; 1600], 100);
         }
+        assert_eq!(
+            filter.process(true, vec![1; 1600], 100),
+            UtteranceAction::Exceeded { first: true }
+        );
+        assert_eq!(
+            filter.process(true, vec![1; 1600], 100),
+            UtteranceAction::Exceeded { first: false }
+        );
+
+        // 無音に戻れば次の発話は再び送信される
+        filter.process(false, vec![0; 1600], 100);
+        assert_eq!(run_utterance(&mut filter, 1), 1600);
     }
 
-    /// TUI状態にTranscribe結果を追加
-    pub fn add_transcript_to_tui(&self, result: &TranscriptResult) {
-        if let Some(tui_state) = &self.tui_state {
-            let text_to_display = if result.is_partial {
-                // 部分結果はフィラーワード削除しない（リアルタイム性を優先）
-                result.text.clone()
-            } else {
-                // 確定結果のみフィラーワードを削除
-                let cleaned_text = Self::remove_filler_words(&result.text);
+    fn partial(text: &str, seconds: f64) -> TranscriptResult {
+        TranscriptResult::new_with_audio_time(0, text.to_string(), true, None, seconds, None)
+    }
 
-                // 空文字列または句読点のみの場合は追加しない
-                if cleaned_text.is_empty() || Self::is_punctuation_only(&cleaned_text) {
-                    return;
-                }
+    fn final_result(text: &str, seconds: f64) -> TranscriptResult {
+        TranscriptResult::new_with_audio_time(0, text.to_string(), false, None, seconds, None)
+    }
 
-                cleaned_text
-            };
+    #[test]
+    fn test_partial_finalizer_promotes_after_timeout() {
+        let mut finalizer = PartialFinalizer::new(Duration::from_millis(1000), LevelFilter::Info);
+        let t0 = Instant::now();
 
-            tui_state.update_channel(self.channel_id, |channel| {
-                channel.add_transcript(
-                    text_to_display,
-                    result.timestamp.clone(),
-                    result.timestamp_seconds,
-                    result.is_partial,
-                    result.stability,
-                );
-            });
-        }
+        let out = finalizer.process(vec![partial("こちら本部", 1.0)], t0);
+        assert_eq!(out.len(), 1);
+        assert!(out[0].is_partial);
+
+        // タイムアウト前は昇格しない
+        assert!(finalizer.process(vec![], t0 + Duration::from_millis(500)).is_empty());
+
+        let out = finalizer.process(vec![], t0 + Duration::from_millis(1000));
+        assert_eq!(out.len(), 1);
+        assert!(!out[0].is_partial);
+        assert!(out[0].promoted_from_partial);
+        assert_eq!(out[0].text, "こちら本部");
+
+        // 一度昇格したら再度は昇格しない
+        assert!(finalizer.process(vec![], t0 + Duration::from_millis(3000)).is_empty());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::AudioFormat;
+    #[test]
+    fn test_partial_finalizer_final_cancels_promotion() {
+        let mut finalizer = PartialFinalizer::new(Duration::from_millis(1000), LevelFilter::Info);
+        let t0 = Instant::now();
 
-    #[tokio::test]
-    #[ignore] // AWS認証情報が必要なため、通常はスキップ
-    async fn test_channel_processor_creation() {
-        let channel_config = ChannelConfig {
-            id: 0,
-            name: "テストチャンネル".to_string(),
-            enabled: true,
-        };
+        finalizer.process(vec![partial("こちら", 1.0)], t0);
+        let out = finalizer.process(
+            vec![final_result("こちら本部", 1.0)],
+            t0 + Duration::from_millis(200),
+        );
+        assert_eq!(out.len(), 1);
+        assert!(!out[0].promoted_from_partial);
 
-        let vad_config = VadConfig {
-            threshold_db: -40.0,
-            hangover_duration_ms: 500,
-            silence_disconnect_threshold_ms: 10000,
-        };
+        assert!(finalizer.process(vec![], t0 + Duration::from_millis(2000)).is_empty());
+    }
 
-        let buffer_config = BufferConfig {
-            capacity_seconds: 30,
-            drop_policy: crate::types::DropPolicy::DropOldest,
-        };
+    #[test]
+    fn test_partial_finalizer_drops_late_final_of_promoted_utterance() {
+        let mut finalizer = PartialFinalizer::new(Duration::from_millis(1000), LevelFilter::Info);
+        let t0 = Instant::now();
 
-        let transcribe_config = TranscribeConfig {
-            backend: TranscribeBackendType::Aws,
-            region: "ap-northeast-1".to_string(),
-            language_code: "ja-JP".to_string(),
-            sample_rate: 16000,
-            max_retries: 3,
-            timeout_seconds: 10,
-            connect_on_startup: false,
-            send_buffered_on_reconnect: true,
-        };
+        finalizer.process(vec![partial("こちら本部", 1.0)], t0);
+        finalizer.process(vec![], t0 + Duration::from_millis(1000));
 
-        let output_config = OutputConfig {
-            wav_output_dir: "/tmp/test_recordings".to_string(),
-            log_level: "info".to_string(),
-        };
+        // 昇格後に同じ発話の確定結果が届いても重複させない
+        let out = finalizer.process(
+            vec![final_result("こちら本部、応答願います", 1.0)],
+            t0 + Duration::from_millis(1500),
+        );
+        assert!(out.is_empty());
 
-        let result = ChannelProcessor::new(
-            &channel_config,
-            &vad_config,
-            &buffer_config,
-            &transcribe_config,
-            None, // whisper_config
-            &output_config,
-            16000,
-        )
-        .await;
+        // 別の発話は通常どおり通過する
+        let out = finalizer.process(
+            vec![final_result("了解", 5.0)],
+            t0 + Duration::from_millis(2000),
+        );
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].text, "了解");
+    }
 
-        assert!(result.is_ok());
+    #[test]
+    fn test_reconnect_backlog_sent() {
+        let mut buffered = DisconnectBuffer::default();
+        buffered.push(vec![1i16; 160]);
+        buffered.push(vec![2i16; 160]);
+        let backlog = buffered.take(true);
+        assert_eq!(backlog, vec![vec![1i16; 160], vec![2i16; 160]]);
+        assert_eq!(buffered.len(), 0);
+        assert_eq!(buffered.samples(), 0);
+    }
+
+    #[test]
+    fn test_reconnect_backlog_discarded() {
+        let mut buffered = DisconnectBuffer::default();
+        buffered.push(vec![1i16; 160]);
+        buffered.push(vec![2i16; 160]);
+        let backlog = buffered.take(false);
+        assert!(backlog.is_empty());
+        assert_eq!(buffered.len(), 0);
+        assert_eq!(buffered.samples(), 0);
     }
 }
@@ -0,0 +1,225 @@
+use crate::config::ClipConfig;
+
+/// 飽和とみなすサンプルの絶対値（フルスケールから1LSB以内）
+const CLIP_LEVEL: u16 = i16::MAX as u16 - 1;
+
+/// 判定の単位となるウィンドウ長（ミリ秒）
+const WINDOW_MS: u32 = 1000;
+
+/// クリップ監視の状態変化
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipEvent {
+    /// クリップが継続しているため入力ゲインを下げるべき
+    Warn {
+        /// 直近ウィンドウのクリップ率（0.0～1.0）
+        ratio: f64,
+        /// 推奨するゲインの下げ幅（dB、正の値）
+        suggested_reduction_db: f32,
+    },
+    /// クリップが収まった
+    Cleared,
+}
+
+/// 入力音声のクリップ（飽和）を監視する
+///
+/// 飽和サンプルが `min_run` 個以上連続した区間をクリップとして数え、
+/// 1秒ごとのクリップ率が `ratio_threshold` を超えたウィンドウが
+/// `trigger_windows` 回連続したら警告、`release_windows` 回連続で下回ったら解除する。
+/// 短時間の一過性のクリップでは警告しないためのヒステリシス。
+///
+/// 入力ゲインを下げる必要があるのはADCの手前（オーディオインターフェースや無線機の音量）のため、
+/// ソフトウェアで振幅を下げても飽和した波形は戻らない。ここでは提案のみ行う。
+pub struct ClipDetector {
+    window_samples: usize,
+    ratio_threshold: f64,
+    min_run: usize,
+    trigger_windows: u32,
+    release_windows: u32,
+    /// 現在のウィンドウのサンプル数
+    window_total: usize,
+    /// 現在のウィンドウのクリップサンプル数
+    window_clipped: usize,
+    /// 連続している飽和サンプル数（チャンクをまたいで継続）
+    run_len: usize,
+    /// 閾値を超えたウィンドウの連続数
+    over_windows: u32,
+    /// 閾値を下回ったウィンドウの連続数
+    under_windows: u32,
+    /// 警告中か
+    warning: bool,
+}
+
+impl ClipDetector {
+    pub fn new(config: &ClipConfig, sample_rate: u32) -> Self {
+        Self {
+            window_samples: (sample_rate as u64 * WINDOW_MS as u64 / 1000).max(1) as usize,
+            ratio_threshold: config.ratio_threshold,
+            min_run: config.min_run.max(1),
+            trigger_windows: config.trigger_secs.max(1),
+            release_windows: config.release_secs.max(1),
+            window_total: 0,
+            window_clipped: 0,
+            run_len: 0,
+            over_windows: 0,
+            under_windows: 0,
+            warning: false,
+        }
+    }
+
+    /// サンプルを処理し、警告状態が変化した場合にイベントを返す
+    pub fn process(&mut self, samples: &[i16]) -> Option<ClipEvent> {
+        let mut event = None;
+
+        for &sample in samples {
+            if sample.unsigned_abs() >= CLIP_LEVEL {
+                self.run_len += 1;
+                if self.run_len == self.min_run {
+                    // 連続数に達した時点で、それまでの飽和サンプルもまとめて数える
+                    self.window_clipped += self.min_run;
+                } else if self.run_len > self.min_run {
+                    self.window_clipped += 1;
+                }
+            } else {
+                self.run_len = 0;
+            }
+
+            self.window_total += 1;
+            if self.window_total >= self.window_samples {
+                if let Some(e) = self.close_window() {
+                    event = Some(e);
+                }
+            }
+        }
+
+        event
+    }
+
+    /// 警告中か
+    pub fn is_warning(&self) -> bool {
+        self.warning
+    }
+
+    /// ウィンドウを締めてヒステリシス判定を行う
+    fn close_window(&mut self) -> Option<ClipEvent> {
+        let ratio = self.window_clipped as f64 / self.window_total as f64;
+        self.window_total = 0;
+        self.window_clipped = 0;
+
+        if ratio > self.ratio_threshold {
+            self.over_windows += 1;
+            self.under_windows = 0;
+            if !self.warning && self.over_windows >= self.trigger_windows {
+                self.warning = true;
+                return Some(ClipEvent::Warn {
+                    ratio,
+                    suggested_reduction_db: suggest_reduction_db(ratio),
+                });
+            }
+        } else {
+            self.under_windows += 1;
+            self.over_windows = 0;
+            if self.warning && self.under_windows >= self.release_windows {
+                self.warning = false;
+                return Some(ClipEvent::Cleared);
+            }
+        }
+
+        None
+    }
+}
+
+/// クリップ率から推奨するゲインの下げ幅（dB）を求める
+///
+/// 飽和した区間の本来の振幅は分からないため、クリップ率が高いほど
+/// 大きく超過しているとみなして段階的に提案する。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::clip::suggest_reduction_db;
+/// assert_eq!(suggest_reduction_db(0.002), 3.0);
+/// assert_eq!(suggest_reduction_db(0.2), 12.0);
+/// ```
+pub fn suggest_reduction_db(ratio: f64) -> f32 {
+    if ratio < 0.01 {
+        3.0
+    } else if ratio < 0.05 {
+        6.0
+    } else if ratio < 0.1 {
+        9.0
+    } else {
+        12.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> ClipDetector {
+        // 1秒 = 1000サンプルで判定
+        ClipDetector::new(&ClipConfig::default(), 1000)
+    }
+
+    /// 1秒分のサンプル（先頭 `clipped` 個を飽和させる）
+    fn second(clipped: usize) -> Vec<i16> {
+        let mut samples = vec![1000i16; 1000];
+        for s in samples.iter_mut().take(clipped) {
+            *s = i16::MAX;
+        }
+        samples
+    }
+
+    #[test]
+    fn test_transient_clip_ignored() {
+        let mut detector = detector();
+
+        // 1秒だけ大きくクリップしても警告しない
+        assert_eq!(detector.process(&second(200)), None);
+        assert_eq!(detector.process(&second(0)), None);
+        assert!(!detector.is_warning());
+    }
+
+    #[test]
+    fn test_isolated_peaks_not_counted() {
+        let mut detector = detector();
+
+        // 連続しないフルスケールのサンプルはクリップとみなさない
+        let mut samples = vec![0i16; 1000];
+        for s in samples.iter_mut().step_by(2) {
+            *s = i16::MIN;
+        }
+        for _ in 0..5 {
+            assert_eq!(detector.process(&samples), None);
+        }
+    }
+
+    #[test]
+    fn test_sustained_clip_warns_and_clears() {
+        let mut detector = detector();
+        let config = ClipConfig::default();
+
+        let mut warned = None;
+        for _ in 0..config.trigger_secs {
+            warned = detector.process(&second(30));
+        }
+        match warned {
+            Some(ClipEvent::Warn {
+                ratio,
+                suggested_reduction_db,
+            }) => {
+                assert!((ratio - 0.03).abs() < 1e-9);
+                assert_eq!(suggested_reduction_db, 6.0);
+            }
+            other => panic!("警告が出ていない: {:?}", other),
+        }
+        assert!(detector.is_warning());
+
+        // 解除に必要な秒数に満たないうちは警告を維持
+        for _ in 1..config.release_secs {
+            assert_eq!(detector.process(&second(0)), None);
+        }
+        assert_eq!(detector.process(&second(0)), Some(ClipEvent::Cleared));
+        assert!(!detector.is_warning());
+    }
+}
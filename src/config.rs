@@ -1,8 +1,14 @@
+use crate::limiter::MAX_LIMITER_LOOKAHEAD_MS;
+use crate::timezone::OutputTimeZone;
+use crate::tui_state::DEFAULT_TRANSCRIPT_HISTORY;
 use crate::types::DropPolicy;
-use anyhow::{Context, Result};
+use crate::wav_writer::{DEFAULT_FILENAME_TEMPLATE, FilenameTemplate};
+use anyhow::{Context, Result, bail};
+use log::LevelFilter;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -20,7 +26,48 @@ pub struct Config {
     #[serde(default)]
     pub flac: FlacConfig,
     #[serde(default)]
+    pub clip: ClipConfig,
+    /// 文字起こしバックエンドへ送る音声の信号処理
+    #[serde(default)]
+    pub dsp: DspConfig,
+    pub upload: Option<UploadConfig>,
+    pub grpc: Option<GrpcConfig>,
+    /// VAD・接続状態の遷移を記録する監査ログ（未設定なら記録しない）
+    pub audit: Option<AuditConfig>,
+    /// 確定結果を保存する SQLite データベース（未設定なら保存しない）
+    pub db: Option<DbConfig>,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub postprocess: PostprocessConfig,
+    /// 全文字起こし結果に添付するセッションのメタデータ（`site_id`、`event_id` など任意のキー）
+    ///
+    /// 起動時に固定され、実行中は変わらない。
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub session: HashMap<String, String>,
+    #[serde(default)]
     pub channels: Vec<ChannelConfig>,
+    /// チャンネルグループ（未定義なら全チャンネルを独立して扱う）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupConfig>,
+    /// `--detect-channels` の判定設定
+    #[serde(default)]
+    pub detect: DetectConfig,
+    /// 確定結果の要約
+    #[serde(default)]
+    pub summary: SummaryConfig,
+    /// 分断された確定結果の結合
+    #[serde(default)]
+    pub merge: MergeConfig,
+    /// 文字起こしの概算コスト
+    #[serde(default)]
+    pub cost: CostConfig,
+    /// 概算メモリ使用量の集計
+    #[serde(default)]
+    pub memory: MemoryConfig,
+    /// 運用検証用の計測ログ
+    #[serde(default)]
+    pub debug: DebugConfig,
 }
 
 /// オーディオ入力設定
@@ -33,6 +80,18 @@ pub struct Config {
 /// - `sample_rate`: 16000 Hz (16kHz - AWS Transcribeの推奨値)
 /// - `channels`: 4 (4チャンネル入力)
 /// - `output_device_id`: "default" (システムのデフォルト出力デバイス)
+/// - `warmup_ms`: 500 ms (起動直後のポップノイズ対策)
+/// - `output_volume`: 1.0 (モニタ出力を原音の音量で再生)
+/// - `host`: なし (システムのデフォルトのホストAPI)
+/// - `strict_channels`: false (`channels` がデバイスの実チャンネル数を超えても警告のみ)
+/// - `auto_follow_active`: false (モニタ出力はTUIで選択したチャンネルのみ)
+/// - `internal_format`: "i16" (入力時点で16ビット整数に量子化)
+/// - `stall_timeout_secs`: 10 秒 (入力が届かなくなったらストリームを再構築)
+/// - `silent_input_timeout_secs`: 0 (全サンプルが0の入力が続いても再構築しない)
+/// - `recovery_interval_secs`: 5 秒 (再構築の再試行間隔)
+/// - `recovery_max_attempts`: 10 回
+/// - `preset`: なし (`[vad]` の値をそのまま使う)
+/// - `monitors`: なし (`output_device_id` / `output_volume` の単一モニタ)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AudioConfig {
     #[serde(default = "default_device_id")]
@@ -44,6 +103,163 @@ pub struct AudioConfig {
     /// 音声出力デバイスID（TUIでチャンネル選択時に使用）
     #[serde(default = "default_device_id")]
     pub output_device_id: String,
+    /// 入力開始直後にVAD判定・Transcribe送信を抑制する時間（ミリ秒、0=無効）
+    ///
+    /// この期間の音声はWAVに無音として書き込む。
+    #[serde(default = "default_warmup_ms")]
+    pub warmup_ms: u32,
+    /// モニタ出力の音量係数の初期値（1.0 = 原音、0.0～2.0 にクランプ、TUIの `+`/`-` で変更可能）
+    #[serde(default = "default_output_volume")]
+    pub output_volume: f32,
+    /// 入力に使うホストAPI（"default" / "wasapi" / "asio" / "coreaudio" / "alsa" / "jack" など）
+    ///
+    /// このビルド・プラットフォームで利用できないホストは警告してデフォルトにフォールバックする。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// `channels` がデバイスの実チャンネル数を超える場合に起動をエラーにするか（false なら警告のみ）
+    #[serde(default)]
+    pub strict_channels: bool,
+    /// TUIでチャンネルを選択していない間、モニタ出力を発話中のチャンネルに自動で切り替えるか
+    ///
+    /// 同時に発話している場合は音量の大きいチャンネルを優先し、無音の間は直前のチャンネルを維持する。
+    /// TUIでチャンネルを選択すると、選択を解除するまで自動切替を止める。
+    #[serde(default)]
+    pub auto_follow_active: bool,
+    /// チャンネル処理（遅延補正・VAD）で扱うサンプル形式
+    #[serde(default)]
+    pub internal_format: InternalFormat,
+    /// 入力コールバックがこの秒数届かなければ、ストリームが止まったとみなして再構築する（0=監視しない）
+    ///
+    /// 監視中はシステムのスリープからの復帰（システム時刻だけが大きく進んだこと）も検知し、
+    /// 直ちにストリームを再構築する。復帰処理の間はWAVへの書き込みを止め、復帰後は新しいファイルに録音する。
+    #[serde(default = "default_stall_timeout_secs")]
+    pub stall_timeout_secs: u32,
+    /// 全チャンネルの全サンプルが0（デジタル無音）の入力がこの秒数続いたら、ストリームが無信号のまま
+    /// 止まったとみなして再構築する（0=無効）
+    ///
+    /// 無線機のスケルチなどで正常時にも完全な無音が続く構成では有効にしない。
+    #[serde(default)]
+    pub silent_input_timeout_secs: u32,
+    /// 再構築しても入力が戻らない場合に再試行する間隔（秒）
+    #[serde(default = "default_recovery_interval_secs")]
+    pub recovery_interval_secs: u32,
+    /// 再構築を試みる最大回数（0=無制限）
+    #[serde(default = "default_recovery_max_attempts")]
+    pub recovery_max_attempts: u32,
+    /// 用途別のVAD初期値のプリセット（`[vad]` に書いた値はプリセットより優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<AudioPreset>,
+    /// 複数の出力デバイスへの同時モニタ（`[[audio.monitors]]`）
+    ///
+    /// 1つでも定義すると `output_device_id` / `output_volume` は使わない。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitors: Vec<MonitorConfig>,
+}
+
+impl AudioConfig {
+    /// 実際に開くモニタ出力の一覧
+    ///
+    /// `monitors` が空なら、`output_device_id` / `output_volume` からTUIの選択に従う単一モニタを作る（従来の構成）。
+    pub fn effective_monitors(&self) -> Vec<MonitorConfig> {
+        if !self.monitors.is_empty() {
+            return self.monitors.clone();
+        }
+        vec![MonitorConfig {
+            device_id: self.output_device_id.clone(),
+            channel: None,
+            volume: self.output_volume,
+            pan: None,
+        }]
+    }
+}
+
+/// モニタ出力1系統の設定（`[[audio.monitors]]`）
+///
+/// 出力デバイスごとに、出力するチャンネル・音量・左右の定位を個別に持つ。
+/// 同じデバイスを複数のモニタに指定することはできない（1つのデバイスには1つのチャンネルだけを出力する）。
+///
+/// ```toml
+/// [[audio.monitors]]
+/// device_id = "USB Headset"
+/// channel = 1
+/// volume = 0.8
+/// pan = -1.0
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct MonitorConfig {
+    /// 出力デバイス名（"default" でシステムのデフォルト出力デバイス）
+    #[serde(default = "default_device_id")]
+    pub device_id: String,
+    /// 常に出力するチャンネルID（省略時はTUIの選択と `auto_follow_active` に従う）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<usize>,
+    /// 音量係数（1.0 = 原音、0.0～2.0 にクランプ）
+    ///
+    /// TUIの `+`/`-` は、TUIの選択に従うモニタの音量だけを変更する。
+    #[serde(default = "default_output_volume")]
+    pub volume: f32,
+    /// 左右の定位（-1.0 = 左のみ、0.0 = 中央、1.0 = 右のみ）。省略時はモノラルで出力する
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pan: Option<f32>,
+}
+
+impl MonitorConfig {
+    /// TUIの選択（と自動フォロー）に従って出力チャンネルを切り替えるモニタか
+    pub fn follows_selection(&self) -> bool {
+        self.channel.is_none()
+    }
+}
+
+/// 用途別のVAD初期値のプリセット（`[audio] preset`）
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioPreset {
+    /// 無線: スケルチの雑音を拾わないよう閾値を高めにし、送信の間の短い途切れでは区間を切らない。
+    /// 交信の合間は長く空くため、早めに切断して課金を抑える
+    Radio,
+    /// 放送: レベルが高く安定した連続音声。閾値を高くしてハングオーバーを短くし、接続は維持する
+    Broadcast,
+    /// 会議: 離れた話者の小さな声も拾うよう閾値を低くし、話者交代の間で区間を切らない
+    Meeting,
+}
+
+impl AudioPreset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Radio => "radio",
+            Self::Broadcast => "broadcast",
+            Self::Meeting => "meeting",
+        }
+    }
+
+    /// プリセットが設定する `[vad]` の値
+    pub fn vad(&self) -> VadConfig {
+        let (threshold_db, hangover_duration_ms, silence_disconnect_threshold_ms) = match self {
+            Self::Radio => (-35.0, 800, 5000),
+            Self::Broadcast => (-30.0, 300, 30000),
+            Self::Meeting => (-45.0, 1000, 15000),
+        };
+        VadConfig {
+            threshold_db,
+            hangover_duration_ms,
+            silence_disconnect_threshold_ms,
+            smoothing_window_ms: 0,
+        }
+    }
+}
+
+/// チャンネル処理の内部サンプル形式
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InternalFormat {
+    /// 入力時点で16ビット整数に量子化する（従来の動作）
+    #[default]
+    I16,
+    /// 32ビット浮動小数点のまま遅延補正・VADを行い、遅延補正の直後に量子化する
+    /// （リミッタ・WAV書き込み・バッファ・Transcribe送信は量子化後のサンプルで行う）
+    ///
+    /// 入力からチャンネル処理までのキューのメモリ使用量は i16 の2倍になる。
+    F32,
 }
 
 /// VAD (Voice Activity Detection) 設定
@@ -55,6 +271,7 @@ pub struct AudioConfig {
 /// - `threshold_db`: -40.0 dB
 /// - `hangover_duration_ms`: 500 ms
 /// - `silence_disconnect_threshold_ms`: 10000 ms (10秒)
+/// - `smoothing_window_ms`: 0 (チャンクごとの音量でそのまま判定)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VadConfig {
     #[serde(default = "default_threshold_db")]
@@ -64,6 +281,12 @@ pub struct VadConfig {
     /// 無音が何ミリ秒継続したらTranscribe APIへの接続を切断するか
     #[serde(default = "default_silence_disconnect_threshold_ms")]
     pub silence_disconnect_threshold_ms: u32,
+    /// 直近この時間（ミリ秒）の複数チャンクにまたがる平均の音量で閾値判定する（0=平滑化しない）
+    ///
+    /// 1チャンクが短いとRMSがばらつき、瞬間的なブレで音声開始・終了を誤判定しやすいため、
+    /// チャンクの長さに合わせてチャンク数十個分程度を指定する。
+    #[serde(default)]
+    pub smoothing_window_ms: u32,
 }
 
 /// オーディオバッファ設定
@@ -74,12 +297,18 @@ pub struct VadConfig {
 ///
 /// - `capacity_seconds`: 300 秒
 /// - `drop_policy`: DropOldest
+/// - `capacity_bytes`: なし (秒数のみで上限を決める)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BufferConfig {
     #[serde(default = "default_capacity_seconds")]
     pub capacity_seconds: u32,
     #[serde(default = "default_drop_policy")]
     pub drop_policy: DropPolicy,
+    /// 1チャンネルあたりのメモリ上限（バイト）
+    ///
+    /// 指定時は `capacity_seconds` から求めたサンプル数と比べて小さい方を上限にする。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity_bytes: Option<usize>,
 }
 
 /// 文字起こしバックエンドの種類
@@ -92,6 +321,16 @@ pub enum TranscribeBackendType {
     Whisper,
 }
 
+impl TranscribeBackendType {
+    /// ログ・TUI表示用の名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Aws => "Amazon Transcribe",
+            Self::Whisper => "OpenAI Whisper API",
+        }
+    }
+}
+
 /// AWS Transcribe 設定
 ///
 /// AWS Transcribe Streaming APIに関する設定。
@@ -106,6 +345,18 @@ pub enum TranscribeBackendType {
 /// - `timeout_seconds`: 10 秒
 /// - `connect_on_startup`: false (音声検出まで接続しない)
 /// - `send_buffered_on_reconnect`: true (再接続時にバッファを送信)
+/// - `proactive_reconnect_secs`: 0 (予防的再接続なし)
+/// - `partial_finalize_timeout_ms`: 0 (部分結果の昇格なし)
+/// - `initial_chunk_ms`: 150 ms (接続直後の送信チャンク長)
+/// - `chunk_ms`: 200 ms (通常の送信チャンク長)
+/// - `fast_start_chunks`: 5 (接続直後に `initial_chunk_ms` で送るチャンク数)
+/// - `predictive_connect`: false (音声確定まで接続しない)
+/// - `endpoint_url`: なし (リージョンの通常の AWS エンドポイント)
+/// - `allowed_languages`: 空 (言語は `language_code` に固定)
+/// - `max_concurrent_streams`: なし (同時ストリーム数を制限しない)
+/// - `stream_quota`: なし (AWS の既定クォータ `DEFAULT_STREAM_QUOTA` を想定)
+/// - `max_disconnect_buffer_secs`: 30 秒 (切断中にためる音声の上限)
+/// - `disconnect_buffer_overflow`: "drop_oldest" (上限を超えたら古い音声から捨てる)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TranscribeConfig {
     #[serde(default = "default_backend")]
@@ -126,6 +377,82 @@ pub struct TranscribeConfig {
     /// 再接続時に切断中に蓄積されたバッファの音声を送信するか
     #[serde(default = "default_send_buffered_on_reconnect")]
     pub send_buffered_on_reconnect: bool,
+    /// ストリーム接続からこの秒数が経過したら新しいストリームへ切り替える（0=無効）
+    ///
+    /// Amazon Transcribe のストリーム最大継続時間（4時間）より短く設定する。
+    #[serde(default)]
+    pub proactive_reconnect_secs: u64,
+    /// 部分結果の後にこの時間（ミリ秒）確定結果が来なければ、最後の部分結果を確定扱いにする（0=無効）
+    #[serde(default)]
+    pub partial_finalize_timeout_ms: u64,
+    /// 接続直後に送信する音声チャンクの長さ（ミリ秒）
+    ///
+    /// 小さいほど最初の音声が早く届く（ストリーム開始直後のタイムアウト対策）。
+    #[serde(default = "default_initial_chunk_ms")]
+    pub initial_chunk_ms: u32,
+    /// 通常時に送信する音声チャンクの長さ（ミリ秒）
+    ///
+    /// 小さくすると低レイテンシ、大きくすると送信回数が減り安定する。
+    #[serde(default = "default_chunk_ms")]
+    pub chunk_ms: u32,
+    /// 接続直後に `initial_chunk_ms` で送信するチャンク数
+    #[serde(default = "default_fast_start_chunks")]
+    pub fast_start_chunks: u32,
+    /// 音量がVAD閾値の手前まで立ち上がった時点で投機的に接続する（語頭の取りこぼし対策）
+    ///
+    /// 音声と確定しないまま短時間経過した場合は切断する。
+    #[serde(default)]
+    pub predictive_connect: bool,
+    /// 接続先エンドポイントの上書き（LocalStack・社内プロキシ・モックサーバ向け）
+    ///
+    /// `http://` は TLS なし、`https://` は通常どおり証明書を検証する（検証の無効化はできない）。
+    /// 署名には `region` を使うため、AWS のエンドポイントを指定する場合はリージョンを一致させる。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint_url: Option<String>,
+    /// 発話ごとの自動言語切り替えの候補（2つ以上指定すると有効、`language_code` を含める）
+    ///
+    /// 検出した言語がこの中になければ切り替えない（誤切り替え対策）。
+    /// `language_code` は最初の発話の言語になる。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_languages: Vec<String>,
+    /// 全チャンネルで同時に開くストリーム数の上限（未指定は制限なし）
+    ///
+    /// 上限に達している間に音声を検出したチャンネルは、他のチャンネルが切断するまで接続を待つ
+    /// （待っている間の音声は再接続時のバッファとして保持する）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_streams: Option<usize>,
+    /// AWS アカウントの同時ストリーム数クォータ（起動時の事前チェック用、未指定は既定値を想定）
+    ///
+    /// Service Quotas でクォータを引き上げた場合に、その値を指定する。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_quota: Option<usize>,
+    /// 再接続時に送るために切断中にためる音声の上限（秒）
+    ///
+    /// 接続の失敗や同時ストリーム数の上限で切断が続く間、音声が断続的に続くとためた分が増え続けるため、
+    /// この長さを超えた分は `disconnect_buffer_overflow` に従って捨てる。
+    #[serde(default = "default_max_disconnect_buffer_secs")]
+    pub max_disconnect_buffer_secs: u32,
+    /// 切断中にためた音声が上限を超えた場合の扱い
+    #[serde(default)]
+    pub disconnect_buffer_overflow: DisconnectBufferOverflow,
+}
+
+/// 切断中にためた音声が `max_disconnect_buffer_secs` を超えた場合の扱い
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectBufferOverflow {
+    /// 古い音声から捨て、直近の上限分だけを再接続時に送る
+    #[default]
+    DropOldest,
+    /// ためた音声をすべて捨て、再接続まで切断中の音声の再送を諦める
+    DiscardAll,
+}
+
+impl TranscribeConfig {
+    /// 発話ごとの自動言語切り替えが有効か
+    pub fn auto_language(&self) -> bool {
+        self.allowed_languages.len() >= 2
+    }
 }
 
 /// OpenAI Whisper API 設定
@@ -141,9 +468,15 @@ pub struct WhisperConfig {
     /// サンプルレート
     #[serde(default = "default_transcribe_sample_rate")]
     pub sample_rate: u32,
-    /// 音声チャンクをためる時間（秒）
+    /// 音声チャンクをためる時間（秒、`vad_segmentation` が有効な場合は使わない）
     #[serde(default = "default_chunk_duration_secs")]
     pub chunk_duration_secs: u64,
+    /// 固定秒数ではなく、VADの発話区間（無音で区切られた1発話）ごとに1リクエストで送るか
+    #[serde(default)]
+    pub vad_segmentation: bool,
+    /// `vad_segmentation` で1リクエストに含める最大の長さ（秒、超える発話は強制的に分割する）
+    #[serde(default = "default_max_segment_secs")]
+    pub max_segment_secs: u64,
 }
 
 /// 出力設定
@@ -154,12 +487,212 @@ pub struct WhisperConfig {
 ///
 /// - `wav_output_dir`: "./recordings"
 /// - `log_level`: "info"
+/// - `min_free_bytes`: 0 (空き容量監視なし)
+/// - `expected_recording_hours`: 8.0 時間
+/// - `timezone`: "local" (実行環境のローカルタイムゾーン)
+/// - `save_clips`: false (発話区間ごとのクリップを保存しない)
+/// - `pause_input`: Discard (一時停止中の入力は読み捨てる)
+/// - `sidecar_metadata`: false (録音メタデータのサイドカーYAMLを生成しない)
+/// - `encryption`: なし (録音ファイルを暗号化しない)
+/// - `recording_start_stagger_ms`: 0 (全チャンネル同時に録音ファイルを開く)
+/// - `record_mode`: Continuous (無音区間も含めて常時録音)
+/// - `vad_record_files`: PerSegment (`vad_triggered` 時は音声区間ごとに別ファイル)
+/// - `vad_preroll_ms`: 500 ms (`vad_triggered` 時に音声区間の前に含める長さ)
+/// - `session_report`: false (終了時のセッションレポートを生成しない)
+/// - `session_report_dir`: なし (`wav_output_dir` に保存)
+/// - `session_report_format`: Markdown
+/// - `feedback_file`: なし (`wav_output_dir/feedback.jsonl` に追記)
+/// - `timeline_events`: false (JSONL出力は文字起こし結果のみ)
+/// - `wav_rotation_secs`: なし (録音ファイルを時間で分割しない)
+/// - `save_wav`: true (録音WAVを保存する)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OutputConfig {
     #[serde(default = "default_wav_output_dir")]
     pub wav_output_dir: String,
     #[serde(default = "default_log_level")]
     pub log_level: String,
+    /// `wav_output_dir` のボリュームの空き容量がこの値（バイト）を下回ったら録音を中断する（0=監視しない）
+    ///
+    /// 文字起こしは続け、空き容量がこの値の2倍まで回復したら新しいファイルで録音を再開する。
+    #[serde(default)]
+    pub min_free_bytes: u64,
+    /// 起動時の必要容量見積もりに使う想定録音時間（時間）
+    #[serde(default = "default_expected_recording_hours")]
+    pub expected_recording_hours: f64,
+    /// 出力タイムスタンプのタイムゾーン（"local" / "UTC" / "Asia/Tokyo" などのIANA名）
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// 発話区間ごとのWAVクリップを `wav_output_dir/clips` に保存し、確定結果の `clip_path` に紐づける
+    #[serde(default)]
+    pub save_clips: bool,
+    /// 一時停止中（TUIの `p` キー）の入力の扱い
+    #[serde(default)]
+    pub pause_input: PauseInput,
+    /// 録音WAVごとに人手の注釈用のサイドカー `<録音ファイル名>.yaml` を finalize 時に生成する
+    #[serde(default)]
+    pub sidecar_metadata: bool,
+    /// 録音WAVのファイル名テンプレート（`wav_output_dir` からの相対パス、`FilenameTemplate` を参照）
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    /// 録音ファイル（発話区間ごとのクリップを含む）を暗号化して `*.wav.enc` として保存する
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionConfig>,
+    /// 起動時に録音ファイルを開くタイミングをチャンネルごとにこの時間（ミリ秒）ずつずらす
+    ///
+    /// 全チャンネルのファイルを同時に開くことによるディスクI/Oの集中を避ける。
+    /// ずらしている間の音声はメモリに保持してファイルの先頭に書き込むため欠落しない。
+    /// 最後のチャンネルが開始するまでの時間は `MAX_RECORDING_START_STAGGER_TOTAL_MS` までに抑える。
+    #[serde(default)]
+    pub recording_start_stagger_ms: u32,
+    /// 録音する区間（チャンネルごとに `[[channels]]` の `record_mode` で上書きできる）
+    #[serde(default)]
+    pub record_mode: RecordMode,
+    /// `record_mode = "vad_triggered"` の録音ファイルの分け方
+    #[serde(default)]
+    pub vad_record_files: VadRecordFiles,
+    /// `record_mode = "vad_triggered"` で音声区間の直前から録音に含める長さ（ミリ秒、語頭の欠け対策）
+    #[serde(default = "default_vad_preroll_ms")]
+    pub vad_preroll_ms: u32,
+    /// 終了時に、チャンネルごとの録音ファイル数・総時間・総バイト・発話回数・推定コストのレポートを保存する
+    #[serde(default)]
+    pub session_report: bool,
+    /// セッションレポートの保存先ディレクトリ（省略時は `wav_output_dir`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_report_dir: Option<String>,
+    /// セッションレポートの形式
+    #[serde(default)]
+    pub session_report_format: SessionReportFormat,
+    /// TUIで誤認識のフラグを立てた確定結果を追記するファイル（省略時は `wav_output_dir/feedback.jsonl`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback_file: Option<String>,
+    /// 録音の開始・終了や接続・切断などのシステムイベントを、文字起こし結果と同じJSONL出力に書き込む
+    ///
+    /// 有効にすると各行に種別 `"type"` を付ける（文字起こし結果は "transcript"、システムイベントは "event"）。
+    #[serde(default)]
+    pub timeline_events: bool,
+    /// 録音ファイルの長さがこの秒数に達したら閉じ、続きを新しいファイルに書き込む（長時間録音の分割）
+    ///
+    /// `record_mode = "vad_triggered"` で `vad_record_files = "single"` のチャンネルには適用しない。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wav_rotation_secs: Option<u32>,
+    /// 録音WAVを保存する（false なら文字起こしのみ行い、録音ファイルもその保存先ディレクトリも作らない）
+    #[serde(default = "default_save_wav")]
+    pub save_wav: bool,
+}
+
+/// セッションレポートの形式
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionReportFormat {
+    /// Markdown（`.md`）
+    #[default]
+    Markdown,
+    /// JSON（`.json`）
+    Json,
+    /// Markdown と JSON の両方
+    Both,
+}
+
+/// Amazon Transcribe Streaming が受け付けるサンプルレート（Hz）
+pub const AWS_SAMPLE_RATES: std::ops::RangeInclusive<u32> = 8000..=48000;
+
+/// 録音開始をずらす場合に、最後のチャンネルが録音ファイルを開くまでの上限（ミリ秒）
+pub const MAX_RECORDING_START_STAGGER_TOTAL_MS: u32 = 2000;
+
+impl OutputConfig {
+    /// `channel_count` チャンネル中 `index` 番目（0始まり）のチャンネルの録音開始の遅れ（ミリ秒）
+    ///
+    /// チャンネル数が多く上限を超える場合は、間隔を詰めて上限内に収める。
+    pub fn recording_start_delay_ms(&self, index: usize, channel_count: usize) -> u32 {
+        if channel_count <= 1 {
+            return 0;
+        }
+        let max_step = MAX_RECORDING_START_STAGGER_TOTAL_MS / (channel_count as u32 - 1);
+        self.recording_start_stagger_ms.min(max_step) * index as u32
+    }
+
+    /// TUIで誤認識のフラグを立てた確定結果を追記するファイルのパス
+    pub fn feedback_path(&self) -> PathBuf {
+        match &self.feedback_file {
+            Some(path) => PathBuf::from(path),
+            None => Path::new(&self.wav_output_dir).join("feedback.jsonl"),
+        }
+    }
+}
+
+/// 録音ファイルの暗号化設定（`[output.encryption]`）
+///
+/// 鍵は AES-256 の32バイトを16進数64文字で表したもの（`openssl rand -hex 32` などで生成）。
+/// `key_file` と `key_env` のどちらか一方を指定する。復号は `--decrypt` で行う。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EncryptionConfig {
+    /// 鍵を書いたファイルのパス
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<String>,
+    /// 鍵を設定した環境変数の名前
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_env: Option<String>,
+}
+
+/// 録音する区間
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordMode {
+    /// 無音区間も含めて常時録音する
+    #[default]
+    Continuous,
+    /// VADの音声区間（`vad_preroll_ms` のプリロールとハングオーバーを含む）だけを録音する
+    VadTriggered,
+}
+
+/// `record_mode = "vad_triggered"` の録音ファイルの分け方
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VadRecordFiles {
+    /// 音声区間ごとに別のファイルにする（ファイル名の日時は区間の開始時刻）
+    #[default]
+    PerSegment,
+    /// 1つのファイルに音声区間を詰めて書き、各区間の位置と実時刻を `<録音ファイル名>.segments.csv` に記録する
+    Single,
+}
+
+/// 一時停止中の入力の扱い
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseInput {
+    /// 読み捨てる
+    #[default]
+    Discard,
+    /// `[buffer]` の容量分だけメモリに保持し、再開時に新しいWAVファイルの先頭に書き込む
+    ///
+    /// 文字起こしには送らない。再開の操作が遅れた場合でも直前の音声を録音に残せる。
+    Buffer,
+}
+
+/// 入力クリップ監視設定
+///
+/// 入力音声の飽和（クリップ）を監視し、継続している場合に
+/// ゲインを下げるようTUI・ログで提案する。
+///
+/// # デフォルト値
+///
+/// - `enabled`: true
+/// - `ratio_threshold`: 0.001 (1秒あたり0.1%のサンプルがクリップ)
+/// - `min_run`: 3 (飽和サンプルが3個以上連続した区間をクリップとみなす)
+/// - `trigger_secs`: 3 秒 (閾値超過がこの秒数続いたら警告)
+/// - `release_secs`: 10 秒 (閾値未満がこの秒数続いたら解除)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClipConfig {
+    #[serde(default = "default_clip_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_clip_ratio_threshold")]
+    pub ratio_threshold: f64,
+    #[serde(default = "default_clip_min_run")]
+    pub min_run: usize,
+    #[serde(default = "default_clip_trigger_secs")]
+    pub trigger_secs: u32,
+    #[serde(default = "default_clip_release_secs")]
+    pub release_secs: u32,
 }
 
 /// FLAC圧縮設定
@@ -184,103 +717,742 @@ pub struct FlacConfig {
     pub enabled: bool,
 }
 
-/// チャンネル個別設定
+/// アップロード先の種類
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadBackendType {
+    /// Amazon S3（または S3 互換ストレージ）
+    S3,
+}
+
+/// 録音ファイルのアップロード設定
 ///
-/// 各チャンネルの名前と有効/無効を設定。
+/// 設定されている場合、finalize 済みの録音ファイルをバックグラウンドでアップロードする。
+///
+/// # デフォルト値
+///
+/// - `prefix`: "" (バケット直下)
+/// - `region`: なし (AWS SDK の既定リージョン)
+/// - `delete_after_upload`: false (ローカルに残す)
+/// - `max_retries`: 5 回
+/// - `retry_interval_secs`: 10 秒 (失敗ごとに倍増)
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ChannelConfig {
-    pub id: usize,
-    pub name: String,
-    #[serde(default = "default_enabled")]
-    pub enabled: bool,
+pub struct UploadConfig {
+    pub backend: UploadBackendType,
+    /// アップロード先バケット名
+    pub bucket: String,
+    /// オブジェクトキーの接頭辞（例: "recordings/"）
+    #[serde(default)]
+    pub prefix: String,
+    /// バケットのリージョン（省略時は AWS SDK の既定値）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// アップロード成功後にローカルファイルを削除するか
+    #[serde(default)]
+    pub delete_after_upload: bool,
+    /// 1ファイルあたりの最大リトライ回数
+    #[serde(default = "default_upload_max_retries")]
+    pub max_retries: u32,
+    /// 最初のリトライまでの待ち時間（秒）
+    #[serde(default = "default_upload_retry_interval_secs")]
+    pub retry_interval_secs: u64,
 }
 
-// Default functions
-fn default_device_id() -> String {
-    "default".to_string()
+/// gRPC 配信設定
+///
+/// 設定されている場合、文字起こし結果（確定・部分結果）を
+/// ストリーミング配信する gRPC サーバを起動する（proto/transcript.proto）。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GrpcConfig {
+    /// 待ち受けアドレス（例: "127.0.0.1:50051"）
+    pub bind: String,
 }
 
-fn default_sample_rate() -> u32 {
-    16000 // 16kHz - AWS Transcribeの推奨値
+/// 監査ログ設定
+///
+/// 設定されている場合、VADの音声開始・終了、文字起こしバックエンドへの接続・切断、
+/// 切断中バッファの送信・破棄を1行1イベントのJSONで `file` に追記する。
+/// 通常のログ（`dcr-transcribe.log`）とは別のファイルに書き込む。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuditConfig {
+    /// 監査ログの出力先（例: "./audit.jsonl"）
+    pub file: String,
 }
 
-fn default_channels() -> u16 {
-    4
+/// データベース保存設定
+///
+/// 設定されている場合、確定した文字起こし結果（JSONLログと同じもの）を SQLite に保存する。
+/// 時刻範囲・チャンネルでの検索と、本文の全文検索（FTS5）ができる。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DbConfig {
+    /// データベースファイルのパス（例: "./transcripts.db"、存在しなければ作成する）
+    pub sqlite_path: String,
 }
 
-fn default_threshold_db() -> f32 {
-    -40.0
+/// TUI表示設定
+///
+/// # デフォルト値
+///
+/// - `low_confidence_threshold`: なし (確信度による色分けをしない)
+/// - `show_waveform`: false (波形プレビューを表示しない)
+/// - `max_transcript_history`: 100 件
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UiConfig {
+    /// 確信度がこの値未満の確定結果を暗い色で表示する（0.0～1.0）
+    #[serde(default)]
+    pub low_confidence_threshold: Option<f32>,
+    /// 各チャンネルに直近の入力波形を表示する（VAD閾値の調整用）
+    #[serde(default)]
+    pub show_waveform: bool,
+    /// チャンネル・グループごとにTUIで保持する確定結果の件数（超えた分は古いものから捨てる）
+    #[serde(default = "default_max_transcript_history")]
+    pub max_transcript_history: usize,
 }
 
-fn default_hangover_duration_ms() -> u32 {
-    500
-}
+/// TUIの確定結果1件あたりのメモリ使用量の目安（バイト）
+///
+/// 日本語60文字程度のテキスト（UTF-8で約180バイト）と時刻文字列、構造体本体の合計。
+/// TUIは描画のたびに状態を複製するため、実際にはこの約2倍を一時的に使う。
+pub const TRANSCRIPT_ENTRY_ESTIMATED_BYTES: usize = 512;
 
-fn default_silence_disconnect_threshold_ms() -> u32 {
-    10000 // 10秒
-}
+/// `max_transcript_history` がこの件数を超えたら起動時に警告する
+pub const TRANSCRIPT_HISTORY_WARN_THRESHOLD: usize = 10_000;
 
-fn default_capacity_seconds() -> u32 {
-    300
+impl UiConfig {
+    /// 確定結果の保持に使うメモリの目安（バイト）
+    ///
+    /// `views` はチャンネル数とグループ数の合計。
+    pub fn estimated_transcript_history_bytes(&self, views: usize) -> usize {
+        views
+            .saturating_mul(self.max_transcript_history)
+            .saturating_mul(TRANSCRIPT_ENTRY_ESTIMATED_BYTES)
+    }
 }
 
-fn default_drop_policy() -> DropPolicy {
-    DropPolicy::DropOldest
+/// 入力チャンネルの信号検出（`--detect-channels`）の設定
+///
+/// # デフォルト値
+///
+/// - `duration_secs`: 5 秒
+/// - `threshold_db`: -60.0 dB (これを超える区間があれば信号ありとみなす)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DetectConfig {
+    /// デバイスを開いて入力を調べる時間（秒）
+    #[serde(default = "default_detect_duration_secs")]
+    pub duration_secs: u32,
+    /// 信号ありとみなす音量（dBFS）。接続されていない入力のノイズフロアより高く、
+    /// 無線機の待受時のノイズより低い値にする
+    #[serde(default = "default_detect_threshold_db")]
+    pub threshold_db: f32,
 }
 
-fn default_region() -> String {
-    "ap-northeast-1".to_string()
+/// 確定結果の要約の設定
+///
+/// 一定時間または一定発話数ごとに、蓄積した確定結果を LLM API（OpenAI の Chat Completions）へ送って要約する。
+/// APIキーは `api_key`、未指定なら `[whisper]` の `api_key` を使う。
+///
+/// # デフォルト値
+///
+/// - `enabled`: false
+/// - `interval_secs`: 300 秒 (0 なら時間では区切らない)
+/// - `max_utterances`: 50 件 (0 なら発話数では区切らない)
+/// - `model`: "gpt-4o-mini"
+/// - `scope`: channel
+/// - `api_key`: なし (`[whisper]` の `api_key` を使う)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SummaryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 前回の要約からこの秒数が経ったら、蓄積分を要約する
+    #[serde(default = "default_summary_interval_secs")]
+    pub interval_secs: u64,
+    /// 蓄積した確定結果がこの件数に達したら、時間を待たずに要約する
+    #[serde(default = "default_summary_max_utterances")]
+    pub max_utterances: usize,
+    /// 要約に使うモデル名
+    #[serde(default = "default_summary_model")]
+    pub model: String,
+    /// 要約の単位（チャンネル別 / グループ別）
+    #[serde(default)]
+    pub scope: SummaryScope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
 }
 
-fn default_language_code() -> String {
-    "ja-JP".to_string()
-}
+impl SummaryConfig {
+    /// 使用するAPIキー（`api_key`、なければ `[whisper]` の `api_key`）
+    pub fn resolve_api_key<'a>(&'a self, whisper: Option<&'a WhisperConfig>) -> Option<&'a str> {
+        self.api_key
+            .as_deref()
+            .or(whisper.map(|w| w.api_key.as_str()))
+            .filter(|key| !key.trim().is_empty())
+    }
 
-fn default_transcribe_sample_rate() -> u32 {
-    16000
+    /// 時間間隔による要約の1時間あたりの API 呼び出し回数（`targets` は要約の単位の数）
+    ///
+    /// 発話数による要約は含まない。`interval_secs` が 0 なら 0 を返す。
+    pub fn requests_per_hour(&self, targets: usize) -> u64 {
+        if self.interval_secs == 0 {
+            return 0;
+        }
+        targets as u64 * 3600u64.div_ceil(self.interval_secs)
+    }
 }
 
-fn default_max_retries() -> u32 {
-    5
+/// 分断された確定結果の結合設定
+///
+/// 有効にすると、同じチャンネルで音声時刻が連続する確定結果（ストリームの予防的な切り替えや
+/// セッション分割で途切れた発話）を1つの発話に結合してから JSONL・データベース・配信・要約に出力する。
+/// 結合したかどうかは結果の `merged` で分かる。
+/// 次の結果を待つため、確定結果の出力が最大 `hold_ms` 遅れる。
+/// 過剰な結合を避けるため、音声時刻（`audio_duration_seconds`）が分からない結果や
+/// 検出言語が異なる結果は結合しない。
+///
+/// # デフォルト値
+///
+/// - `enabled`: false
+/// - `max_gap_ms`: 300 ms (前の結果の終了から次の結果の開始までの最大の間隔)
+/// - `hold_ms`: 1500 ms (次の結果を待つ時間)
+/// - `max_merged_secs`: 20 秒 (結合後の発話の最大の長さ)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MergeConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 結合する音声の間隔の上限（ミリ秒）
+    #[serde(default = "default_merge_max_gap_ms")]
+    pub max_gap_ms: u64,
+    /// 確定結果を出力せずに次の結果を待つ時間（ミリ秒）
+    #[serde(default = "default_merge_hold_ms")]
+    pub hold_ms: u64,
+    /// 結合後の発話の長さの上限（秒）
+    #[serde(default = "default_merge_max_merged_secs")]
+    pub max_merged_secs: f64,
 }
 
-fn default_timeout_seconds() -> u64 {
-    10
+/// 要約の単位
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryScope {
+    /// 有効なチャンネルごと
+    #[default]
+    Channel,
+    /// `[[groups]]` のグループごと（グループに属さないチャンネルは要約しない）
+    Group,
 }
 
-fn default_wav_output_dir() -> String {
-    "./recordings".to_string()
+/// 文字起こし結果の後処理設定
+///
+/// 確定結果に 全角英数字の正規化 → フィラーワード削除 → 置換ルール の順に適用する。
+/// `[[channels]]` の `postprocess` で項目ごとにチャンネル個別に上書きできる（`PostprocessOverride`）。
+///
+/// # デフォルト値
+///
+/// - `min_confidence`: なし (確信度による除外をしない)
+/// - `remove_fillers`: true (日本語のフィラーワードを削除)
+/// - `normalize_width`: false
+/// - `replacements`: [] (置換しない)
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PostprocessConfig {
+    /// 確信度がこの値未満の確定結果をJSONL出力から除外する（0.0～1.0）
+    ///
+    /// 確信度を返さないバックエンドの結果は除外しない。
+    #[serde(default)]
+    pub min_confidence: Option<f32>,
+    /// 日本語のフィラーワード（「えっと」「あのー」など）を削除する
+    #[serde(default = "default_remove_fillers")]
+    pub remove_fillers: bool,
+    /// 全角英数字と全角スペースを半角にする
+    #[serde(default)]
+    pub normalize_width: bool,
+    /// 置換ルール（記載順に、すべての出現箇所を置換する）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replacements: Vec<ReplacementRule>,
 }
 
-fn default_log_level() -> String {
-    "info".to_string()
+/// 確定結果のテキストの置換ルール（部分一致、大文字小文字を区別する）
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReplacementRule {
+    pub from: String,
+    pub to: String,
 }
 
-fn default_enabled() -> bool {
-    true
+/// チャンネル個別の後処理設定（`[[channels]]` の `postprocess`）
+///
+/// 指定した項目だけ `[postprocess]` の共通設定を上書きし、未指定の項目は共通設定を引き継ぐ。
+/// `replacements` を指定した場合は共通のルールに追加せず、指定したルールだけを使う
+/// （空の配列で共通のルールを無効にできる）。
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct PostprocessOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_fillers: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_width: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacements: Option<Vec<ReplacementRule>>,
 }
 
-fn default_flac_compression_level() -> u32 {
-    5 // バランス型（推奨）
+impl PostprocessConfig {
+    /// チャンネル個別の上書きを適用した設定
+    pub fn with_override(&self, channel: Option<&PostprocessOverride>) -> PostprocessConfig {
+        let Some(channel) = channel else {
+            return self.clone();
+        };
+        PostprocessConfig {
+            min_confidence: channel.min_confidence.or(self.min_confidence),
+            remove_fillers: channel.remove_fillers.unwrap_or(self.remove_fillers),
+            normalize_width: channel.normalize_width.unwrap_or(self.normalize_width),
+            replacements: channel.replacements.clone().unwrap_or_else(|| self.replacements.clone()),
+        }
+    }
 }
 
-fn default_flac_enabled() -> bool {
-    true // デフォルトでFLAC圧縮を使用
+/// 文字起こしの概算コストの設定
+///
+/// 有効にすると、チャンネルごとに文字起こしバックエンドへ実際に送信した音声時間
+/// （再接続時に送った切断中のバッファ・接続中の無音を含む）を積算し、
+/// バックエンドごとの1分あたりの単価から概算コストを求めてTUIとログに表示する。
+/// 単価は契約や料金改定に合わせて設定する。
+///
+/// # デフォルト値
+///
+/// - `enabled`: false
+/// - `aws_per_minute`: 0.024 (Amazon Transcribe ストリーミングの標準料金、USD/分)
+/// - `whisper_per_minute`: 0.006 (OpenAI Whisper API の料金、USD/分)
+/// - `currency`: "USD"
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CostConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Amazon Transcribe の1分あたりの単価
+    #[serde(default = "default_aws_per_minute")]
+    pub aws_per_minute: f64,
+    /// OpenAI Whisper API の1分あたりの単価
+    #[serde(default = "default_whisper_per_minute")]
+    pub whisper_per_minute: f64,
+    /// 表示する通貨単位
+    #[serde(default = "default_currency")]
+    pub currency: String,
 }
 
-fn default_backend() -> TranscribeBackendType {
-    TranscribeBackendType::Aws
+/// 文字起こしバックエンドへ送る音声の信号処理設定
+///
+/// 送信する音声にのみ適用し、WAV録音・発話クリップ・モニタ出力は無加工のまま。
+///
+/// # デフォルト値
+///
+/// - `limiter_threshold_db`: なし (ピークリミッタを使わず、送信音声をそのまま送る)
+/// - `limiter_release_ms`: 100 ms
+/// - `limiter_lookahead_ms`: 2 ms
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DspConfig {
+    /// ピークリミッタの閾値（dBFS、0 以下）。設定した場合のみ、閾値を超えるピークを抑えてから送信する
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limiter_threshold_db: Option<f32>,
+    /// ピークが過ぎた後にゲインを戻す時間（ミリ秒）
+    #[serde(default = "default_limiter_release_ms")]
+    pub limiter_release_ms: u32,
+    /// ピークの手前からゲインを下げ始めるための先読み（ミリ秒、送信がこの分遅れる）
+    #[serde(default = "default_limiter_lookahead_ms")]
+    pub limiter_lookahead_ms: u32,
 }
 
-fn default_whisper_model() -> String {
-    "whisper-1".to_string()
+/// 概算メモリ使用量の集計設定
+///
+/// `interval_secs` ごとにリトライ用バッファ・切断中バッファ・キュー・TUI履歴の概算メモリ使用量を集計し、
+/// TUIのステータスバーとログに表示する。前回の集計から `surge_warn_mb` 以上増えた場合は警告する
+/// （長時間の切断で切断中バッファが膨らむ場合など）。
+///
+/// # デフォルト値
+///
+/// - `interval_secs`: 60 秒 (0 なら集計しない)
+/// - `surge_warn_mb`: 64 MB (0 なら急増を警告しない)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MemoryConfig {
+    /// 集計の間隔（秒）
+    #[serde(default = "default_memory_interval_secs")]
+    pub interval_secs: u64,
+    /// 1回の集計の間にこの量（MB）以上増えたら警告する
+    #[serde(default = "default_memory_surge_warn_mb")]
+    pub surge_warn_mb: u64,
 }
 
-fn default_chunk_duration_secs() -> u64 {
-    5 // 5秒ごとにWhisper APIに送信
+impl MemoryConfig {
+    /// 急増とみなす増加量（バイト）
+    pub fn surge_warn_bytes(&self) -> u64 {
+        self.surge_warn_mb.saturating_mul(1_000_000)
+    }
 }
 
-fn default_connect_on_startup() -> bool {
+/// 運用検証用の計測ログ設定
+///
+/// # デフォルト値
+///
+/// - `log_resampler_stats`: false (計測しない)
+/// - `vad_observe_thresholds`: [] (観測しない)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DebugConfig {
+    /// 入力コールバックでの変換（デインターリーブ・i16変換）の統計を定期的にログ出力する
+    ///
+    /// 変換前後のサンプル数、処理時間、推定レイテンシ、送信キューの残量を10秒ごとに出力し、
+    /// キューが容量の半分を超えて溜まった場合は警告する。
+    #[serde(default)]
+    pub log_resampler_stats: bool,
+    /// VADの観測用閾値 (dB)
+    ///
+    /// 判定には使わず、各閾値で判定した場合の音声区間率を本番の閾値と並べて
+    /// 音声1分ごとにログ出力する（閾値調整の検証用）。
+    #[serde(default)]
+    pub vad_observe_thresholds: Vec<f32>,
+}
+
+/// チャンネルの `symbol` の最大表示幅（全角1文字・半角2文字相当）
+pub const MAX_SYMBOL_WIDTH: usize = 2;
+
+/// チャンネル個別設定
+///
+/// 各チャンネルの名前と有効/無効を設定。
+/// `send_buffered_on_reconnect` を指定すると `[transcribe]` の共通値を上書きする。
+/// `min_utterance_ms` / `max_utterance_ms` を指定すると、VADの音声区間長が
+/// 範囲外の発話をTranscribeに送信しない（WAV録音には影響しない）。
+/// `latency_offset_ms` はデバイス間の既知の遅延差を揃えるために使う
+/// （`latency::estimate_offset_ms` で同時刻信号から推定できる）。
+/// `display_order` でTUIでの表示順を指定できる（数字キーでの出力選択はIDのまま）。
+/// `symbol` を指定するとTUIの各発話の先頭に付け、色以外でもチャンネルを区別できる。
+/// `postprocess` で後処理（フィラー削除・正規化・置換ルール・確信度による除外）を項目ごとに上書きできる。
+/// `record_mode` で音声区間だけを録音するかを `[output]` の値から上書きできる。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChannelConfig {
+    pub id: usize,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 再接続時に切断中の音声を送信するか（未指定は `TranscribeConfig` の値）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send_buffered_on_reconnect: Option<bool>,
+    /// これより短い音声区間はノイズとして送信しない（ミリ秒、未指定は制限なし）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_utterance_ms: Option<u32>,
+    /// これより長く続く音声区間は超過分を送信せず切断する（ミリ秒、未指定は制限なし）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_utterance_ms: Option<u32>,
+    /// デバイス間の固定遅延の補正量（ミリ秒、正は遅延追加・負は先行）
+    #[serde(default)]
+    pub latency_offset_ms: i32,
+    /// このチャンネルの `ChannelProcessor` のログレベル（未指定は `[output]` の `log_level`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+    /// TUIでの表示順（小さいほど左、未指定のチャンネルはその後ろにID順）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_order: Option<u32>,
+    /// TUIで各発話の先頭に付ける記号（絵文字など、表示幅 `MAX_SYMBOL_WIDTH` 以内）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// 後処理の上書き（未指定の項目は `[postprocess]` の値）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postprocess: Option<PostprocessOverride>,
+    /// 録音する区間（未指定は `[output]` の `record_mode`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_mode: Option<RecordMode>,
+}
+
+impl ChannelConfig {
+    /// このチャンネルで使う再接続時バッファ送信ポリシーを解決
+    ///
+    /// チャンネル個別値があればそれを、なければ共通値を返す。
+    pub fn resolve_send_buffered_on_reconnect(&self, transcribe: &TranscribeConfig) -> bool {
+        self.send_buffered_on_reconnect
+            .unwrap_or(transcribe.send_buffered_on_reconnect)
+    }
+
+    /// このチャンネルで使う録音区間を解決
+    ///
+    /// チャンネル個別値があればそれを、なければ `[output]` の `record_mode` を返す。
+    pub fn resolve_record_mode(&self, output: &OutputConfig) -> RecordMode {
+        self.record_mode.unwrap_or(output.record_mode)
+    }
+
+    /// このチャンネルで使うログレベルを解決
+    ///
+    /// チャンネル個別値があればそれを、なければ `[output]` の `log_level` を使う。
+    /// 解釈できない値は `Config::validate` で弾かれる前提で、ここでは `Info` とみなす。
+    pub fn resolve_log_level(&self, output: &OutputConfig) -> LevelFilter {
+        self.log_level
+            .as_deref()
+            .unwrap_or(&output.log_level)
+            .parse()
+            .unwrap_or(LevelFilter::Info)
+    }
+
+    /// このチャンネルで使う後処理設定を解決
+    ///
+    /// チャンネル個別に指定した項目はその値を、それ以外は `[postprocess]` の値を使う。
+    pub fn resolve_postprocess(&self, postprocess: &PostprocessConfig) -> PostprocessConfig {
+        postprocess.with_override(self.postprocess.as_ref())
+    }
+}
+
+/// チャンネルグループ設定
+///
+/// 同じ話者グループ（例: 同一部隊の複数無線機）のチャンネルをまとめ、
+/// 文字起こし結果を時刻順にマージした統合トランスクリプトとして扱う。
+/// 1つのチャンネルは1つのグループにのみ所属できる。
+///
+/// ```toml
+/// [[groups]]
+/// name = "第1小隊"
+/// channels = [0, 1]
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GroupConfig {
+    pub name: String,
+    /// メンバーのチャンネルID
+    pub channels: Vec<usize>,
+}
+
+// Default functions
+fn default_device_id() -> String {
+    "default".to_string()
+}
+
+fn default_sample_rate() -> u32 {
+    16000 // 16kHz - AWS Transcribeの推奨値
+}
+
+fn default_warmup_ms() -> u32 {
+    500
+}
+
+fn default_output_volume() -> f32 {
+    1.0
+}
+
+fn default_stall_timeout_secs() -> u32 {
+    10
+}
+
+fn default_recovery_interval_secs() -> u32 {
+    5
+}
+
+fn default_recovery_max_attempts() -> u32 {
+    10
+}
+
+fn default_channels() -> u16 {
+    4
+}
+
+fn default_threshold_db() -> f32 {
+    -40.0
+}
+
+fn default_hangover_duration_ms() -> u32 {
+    500
+}
+
+fn default_silence_disconnect_threshold_ms() -> u32 {
+    10000 // 10秒
+}
+
+fn default_capacity_seconds() -> u32 {
+    300
+}
+
+fn default_drop_policy() -> DropPolicy {
+    DropPolicy::DropOldest
+}
+
+fn default_region() -> String {
+    "ap-northeast-1".to_string()
+}
+
+fn default_language_code() -> String {
+    "ja-JP".to_string()
+}
+
+fn default_transcribe_sample_rate() -> u32 {
+    16000
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_timeout_seconds() -> u64 {
+    10
+}
+
+fn default_initial_chunk_ms() -> u32 {
+    150
+}
+
+fn default_chunk_ms() -> u32 {
+    200
+}
+
+fn default_fast_start_chunks() -> u32 {
+    5
+}
+
+fn default_wav_output_dir() -> String {
+    "./recordings".to_string()
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_expected_recording_hours() -> f64 {
+    8.0
+}
+
+fn default_detect_duration_secs() -> u32 {
+    5
+}
+
+fn default_detect_threshold_db() -> f32 {
+    -60.0
+}
+
+fn default_summary_interval_secs() -> u64 {
+    300
+}
+
+fn default_summary_max_utterances() -> usize {
+    50
+}
+
+fn default_summary_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_remove_fillers() -> bool {
+    true
+}
+
+fn default_limiter_release_ms() -> u32 {
+    100
+}
+
+fn default_limiter_lookahead_ms() -> u32 {
+    2
+}
+
+fn default_aws_per_minute() -> f64 {
+    0.024
+}
+
+fn default_whisper_per_minute() -> f64 {
+    0.006
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+fn default_max_disconnect_buffer_secs() -> u32 {
+    30
+}
+
+fn default_memory_interval_secs() -> u64 {
+    60
+}
+
+fn default_memory_surge_warn_mb() -> u64 {
+    64
+}
+
+fn default_merge_max_gap_ms() -> u64 {
+    300
+}
+
+fn default_merge_hold_ms() -> u64 {
+    1500
+}
+
+fn default_merge_max_merged_secs() -> f64 {
+    20.0
+}
+
+fn default_max_transcript_history() -> usize {
+    DEFAULT_TRANSCRIPT_HISTORY
+}
+
+fn default_timezone() -> String {
+    "local".to_string()
+}
+
+fn default_filename_template() -> String {
+    DEFAULT_FILENAME_TEMPLATE.to_string()
+}
+
+fn default_upload_max_retries() -> u32 {
+    5
+}
+
+fn default_upload_retry_interval_secs() -> u64 {
+    10
+}
+
+fn default_vad_preroll_ms() -> u32 {
+    500
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_save_wav() -> bool {
+    true
+}
+
+fn default_flac_compression_level() -> u32 {
+    5 // バランス型（推奨）
+}
+
+fn default_flac_enabled() -> bool {
+    true // デフォルトでFLAC圧縮を使用
+}
+
+fn default_clip_enabled() -> bool {
+    true
+}
+
+fn default_clip_ratio_threshold() -> f64 {
+    0.001
+}
+
+fn default_clip_min_run() -> usize {
+    3
+}
+
+fn default_clip_trigger_secs() -> u32 {
+    3
+}
+
+fn default_clip_release_secs() -> u32 {
+    10
+}
+
+fn default_backend() -> TranscribeBackendType {
+    TranscribeBackendType::Aws
+}
+
+fn default_whisper_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_chunk_duration_secs() -> u64 {
+    5 // 5秒ごとにWhisper APIに送信
+}
+
+fn default_max_segment_secs() -> u64 {
+    30
+}
+
+fn default_connect_on_startup() -> bool {
     false // デフォルトでは音声検出まで接続しない
 }
 
@@ -298,18 +1470,52 @@ impl Default for Config {
             whisper: None, // デフォルトではWhisper設定なし
             output: OutputConfig::default(),
             flac: FlacConfig::default(),
+            clip: ClipConfig::default(),
+            dsp: DspConfig::default(),
+            upload: None, // デフォルトではアップロードしない
+            grpc: None,   // デフォルトでは gRPC 配信しない
+            audit: None,  // デフォルトでは監査ログを記録しない
+            db: None,     // デフォルトではデータベースに保存しない
+            ui: UiConfig::default(),
+            postprocess: PostprocessConfig::default(),
+            session: HashMap::new(),
             channels: vec![
                 ChannelConfig {
                     id: 0,
                     name: "無線機1".to_string(),
                     enabled: true,
+                    send_buffered_on_reconnect: None,
+                    min_utterance_ms: None,
+                    max_utterance_ms: None,
+                    latency_offset_ms: 0,
+                    log_level: None,
+                    display_order: None,
+                    symbol: None,
+                    postprocess: None,
+                    record_mode: None,
                 },
                 ChannelConfig {
                     id: 1,
                     name: "無線機2".to_string(),
                     enabled: true,
+                    send_buffered_on_reconnect: None,
+                    min_utterance_ms: None,
+                    max_utterance_ms: None,
+                    latency_offset_ms: 0,
+                    log_level: None,
+                    display_order: None,
+                    symbol: None,
+                    postprocess: None,
+                    record_mode: None,
                 },
             ],
+            groups: Vec::new(),
+            detect: DetectConfig::default(),
+            summary: SummaryConfig::default(),
+            merge: MergeConfig::default(),
+            cost: CostConfig::default(),
+            memory: MemoryConfig::default(),
+            debug: DebugConfig::default(),
         }
     }
 }
@@ -321,6 +1527,18 @@ impl Default for AudioConfig {
             sample_rate: default_sample_rate(),
             channels: default_channels(),
             output_device_id: default_device_id(),
+            warmup_ms: default_warmup_ms(),
+            output_volume: default_output_volume(),
+            host: None,
+            strict_channels: false,
+            auto_follow_active: false,
+            internal_format: InternalFormat::default(),
+            stall_timeout_secs: default_stall_timeout_secs(),
+            silent_input_timeout_secs: 0,
+            recovery_interval_secs: default_recovery_interval_secs(),
+            recovery_max_attempts: default_recovery_max_attempts(),
+            preset: None,
+            monitors: Vec::new(),
         }
     }
 }
@@ -331,6 +1549,7 @@ impl Default for VadConfig {
             threshold_db: default_threshold_db(),
             hangover_duration_ms: default_hangover_duration_ms(),
             silence_disconnect_threshold_ms: default_silence_disconnect_threshold_ms(),
+            smoothing_window_ms: 0,
         }
     }
 }
@@ -340,6 +1559,7 @@ impl Default for BufferConfig {
         Self {
             capacity_seconds: default_capacity_seconds(),
             drop_policy: default_drop_policy(),
+            capacity_bytes: None,
         }
     }
 }
@@ -355,6 +1575,102 @@ impl Default for TranscribeConfig {
             timeout_seconds: default_timeout_seconds(),
             connect_on_startup: default_connect_on_startup(),
             send_buffered_on_reconnect: default_send_buffered_on_reconnect(),
+            proactive_reconnect_secs: 0,
+            partial_finalize_timeout_ms: 0,
+            initial_chunk_ms: default_initial_chunk_ms(),
+            chunk_ms: default_chunk_ms(),
+            fast_start_chunks: default_fast_start_chunks(),
+            predictive_connect: false,
+            endpoint_url: None,
+            allowed_languages: Vec::new(),
+            max_concurrent_streams: None,
+            stream_quota: None,
+            max_disconnect_buffer_secs: default_max_disconnect_buffer_secs(),
+            disconnect_buffer_overflow: DisconnectBufferOverflow::DropOldest,
+        }
+    }
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_summary_interval_secs(),
+            max_utterances: default_summary_max_utterances(),
+            model: default_summary_model(),
+            scope: SummaryScope::default(),
+            api_key: None,
+        }
+    }
+}
+
+impl Default for PostprocessConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: None,
+            remove_fillers: default_remove_fillers(),
+            normalize_width: false,
+            replacements: Vec::new(),
+        }
+    }
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_gap_ms: default_merge_max_gap_ms(),
+            hold_ms: default_merge_hold_ms(),
+            max_merged_secs: default_merge_max_merged_secs(),
+        }
+    }
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_memory_interval_secs(),
+            surge_warn_mb: default_memory_surge_warn_mb(),
+        }
+    }
+}
+
+impl Default for DspConfig {
+    fn default() -> Self {
+        Self {
+            limiter_threshold_db: None,
+            limiter_release_ms: default_limiter_release_ms(),
+            limiter_lookahead_ms: default_limiter_lookahead_ms(),
+        }
+    }
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            aws_per_minute: default_aws_per_minute(),
+            whisper_per_minute: default_whisper_per_minute(),
+            currency: default_currency(),
+        }
+    }
+}
+
+impl Default for DetectConfig {
+    fn default() -> Self {
+        Self {
+            duration_secs: default_detect_duration_secs(),
+            threshold_db: default_detect_threshold_db(),
+        }
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            low_confidence_threshold: None,
+            show_waveform: false,
+            max_transcript_history: default_max_transcript_history(),
         }
     }
 }
@@ -364,44 +1680,537 @@ impl Default for OutputConfig {
         Self {
             wav_output_dir: default_wav_output_dir(),
             log_level: default_log_level(),
+            min_free_bytes: 0,
+            expected_recording_hours: default_expected_recording_hours(),
+            timezone: default_timezone(),
+            save_clips: false,
+            pause_input: PauseInput::default(),
+            sidecar_metadata: false,
+            filename_template: default_filename_template(),
+            encryption: None,
+            recording_start_stagger_ms: 0,
+            record_mode: RecordMode::default(),
+            vad_record_files: VadRecordFiles::default(),
+            vad_preroll_ms: default_vad_preroll_ms(),
+            session_report: false,
+            session_report_dir: None,
+            session_report_format: SessionReportFormat::default(),
+            feedback_file: None,
+            timeline_events: false,
+            wav_rotation_secs: None,
+            save_wav: default_save_wav(),
+        }
+    }
+}
+
+impl Default for FlacConfig {
+    fn default() -> Self {
+        Self {
+            compression_level: default_flac_compression_level(),
+            enabled: default_flac_enabled(),
+        }
+    }
+}
+
+impl Default for ClipConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_clip_enabled(),
+            ratio_threshold: default_clip_ratio_threshold(),
+            min_run: default_clip_min_run(),
+            trigger_secs: default_clip_trigger_secs(),
+            release_secs: default_clip_release_secs(),
+        }
+    }
+}
+
+/// 設定値の `${VAR}` を環境変数の値に展開する（APIキーなどの秘匿値を設定ファイルに平文で書かないため）
+///
+/// `$$` はリテラルの `$` になる。`${` 以外に続く単独の `$` はそのまま残す。
+/// 変数が未定義（または UTF-8 でない）・変数名が不正・`}` で閉じていない場合はエラー。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::config::expand_env;
+/// std::env::set_var("DCR_EXAMPLE_API_KEY", "sk-test");
+/// assert_eq!(expand_env("${DCR_EXAMPLE_API_KEY}").unwrap(), "sk-test");
+/// assert_eq!(expand_env("cost: $$5").unwrap(), "cost: $5");
+/// assert!(expand_env("${DCR_EXAMPLE_UNDEFINED}").is_err());
+/// ```
+pub fn expand_env(value: &str) -> Result<String> {
+    expand_with(value, |name| std::env::var(name).ok())
+}
+
+/// `expand_env` の本体（`lookup` で変数の値を引く）
+fn expand_with(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(after) = after.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+        } else if let Some(after) = after.strip_prefix('{') {
+            let Some(end) = after.find('}') else {
+                bail!("環境変数の参照が '}}' で閉じていません: ${{{}", after);
+            };
+            let name = &after[..end];
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("環境変数名が不正です: ${{{}}}", name);
+            }
+            let Some(var) = lookup(name) else {
+                bail!("環境変数 {} が定義されていません", name);
+            };
+            expanded.push_str(&var);
+            rest = &after[end + 1..];
+        } else {
+            expanded.push('$');
+            rest = after;
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+impl Config {
+    /// 設定ファイルから読み込み
+    ///
+    /// TOML形式の設定ファイルをパースしてConfig構造体を生成する。
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - 設定ファイルのパス
+    ///
+    /// # Errors
+    ///
+    /// ファイルの読み込みまたはパースに失敗した場合にエラーを返す。
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use dcr_transcribe::config::Config;
+    /// let config = Config::from_file("config.toml").unwrap();
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = fs::read_to_string(path.as_ref())
+            .with_context(|| format!("設定ファイルの読み込みに失敗: {:?}", path.as_ref()))?;
+        let raw: toml::Table = toml::from_str(&content).with_context(|| "設定ファイルのパースに失敗")?;
+        let mut config: Config =
+            toml::from_str(&content).with_context(|| "設定ファイルのパースに失敗")?;
+        config.apply_audio_preset(&raw);
+        config.expand_secrets()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// APIキーの `${VAR}` を環境変数から展開する（`expand_env`）
+    fn expand_secrets(&mut self) -> Result<()> {
+        if let Some(whisper) = self.whisper.as_mut() {
+            whisper.api_key = expand_env(&whisper.api_key).with_context(|| "[whisper] api_key の展開に失敗")?;
+        }
+        if let Some(api_key) = self.summary.api_key.as_mut() {
+            *api_key = expand_env(api_key).with_context(|| "[summary] api_key の展開に失敗")?;
+        }
+        Ok(())
+    }
+
+    /// `[audio] preset` の値を、設定ファイルの `[vad]` に書かれていない項目に適用する
+    ///
+    /// `raw` は設定ファイルに書かれていた内容。
+    fn apply_audio_preset(&mut self, raw: &toml::Table) {
+        let Some(preset) = self.audio.preset else {
+            return;
+        };
+        let written = |key: &str| {
+            raw.get("vad")
+                .and_then(|vad| vad.as_table())
+                .is_some_and(|vad| vad.contains_key(key))
+        };
+        let values = preset.vad();
+        if !written("threshold_db") {
+            self.vad.threshold_db = values.threshold_db;
+        }
+        if !written("hangover_duration_ms") {
+            self.vad.hangover_duration_ms = values.hangover_duration_ms;
+        }
+        if !written("silence_disconnect_threshold_ms") {
+            self.vad.silence_disconnect_threshold_ms = values.silence_disconnect_threshold_ms;
+        }
+    }
+
+    /// プリセット適用後の実効的なVAD設定の説明（起動ログ用、プリセット未指定なら `None`）
+    pub fn describe_audio_preset(&self) -> Option<String> {
+        let preset = self.audio.preset?;
+        Some(format!(
+            "音声プリセット \"{}\" 適用後のVAD設定: 閾値 {:.1} dB, ハングオーバー {}ms, 無音切断 {}ms",
+            preset.name(),
+            self.vad.threshold_db,
+            self.vad.hangover_duration_ms,
+            self.vad.silence_disconnect_threshold_ms
+        ))
+    }
+
+    /// 設定値の整合性を検証
+    ///
+    /// # Errors
+    ///
+    /// 以下の場合にエラーを返す。
+    ///
+    /// - チャンネルIDが重複している、有効なチャンネルがない、または `[audio] channels` の範囲外のIDがある
+    /// - `backend = "aws"` で `[transcribe] sample_rate` が Amazon Transcribe の受け付ける範囲外
+    /// - `backend = "whisper"` で `[whisper]` がない、または `api_key` が空
+    /// - `[flac] compression_level` が 8 を超える
+    /// - `[dsp] limiter_threshold_db` が 0 dBFS を超える、または `limiter_lookahead_ms` が上限を超える
+    /// - 不正なタイムゾーン名が指定されている
+    /// - 不正なログレベルが指定されている（`[output]` および各チャンネル）
+    /// - チャンネルの `symbol` が空、または表示幅が `MAX_SYMBOL_WIDTH` を超える
+    /// - グループ名が空または重複している
+    /// - グループのメンバーが空、存在しないチャンネルID、または重複（グループ間も含む）
+    /// - `[summary]` が有効で、APIキーがない・要約の区切りがない・グループ単位なのにグループがない
+    pub fn validate(&self) -> Result<()> {
+        self.validate_channels()?;
+        self.validate_backend()?;
+        self.validate_monitors()?;
+        if self.flac.compression_level > 8 {
+            bail!("[flac] compression_level は 0～8 で指定してください: {}", self.flac.compression_level);
+        }
+        OutputTimeZone::parse(&self.output.timezone)
+            .with_context(|| "[output] timezone の設定が不正です")?;
+        FilenameTemplate::parse(&self.output.filename_template, &self.session)
+            .with_context(|| "[output] filename_template の設定が不正です")?;
+        if let Some(encryption) = &self.output.encryption {
+            if encryption.key_file.is_some() == encryption.key_env.is_some() {
+                bail!("[output.encryption] には key_file と key_env のどちらか一方を指定してください");
+            }
+        }
+        self.validate_dsp()?;
+        if self.output.wav_rotation_secs == Some(0) {
+            bail!("[output] wav_rotation_secs は 1 以上で指定してください");
+        }
+        self.validate_log_levels()?;
+        self.validate_symbols()?;
+        self.validate_confidence_thresholds()?;
+        self.validate_replacements()?;
+        self.validate_endpoint_url()?;
+        self.validate_allowed_languages()?;
+        for (name, value) in [
+            ("max_concurrent_streams", self.transcribe.max_concurrent_streams),
+            ("stream_quota", self.transcribe.stream_quota),
+        ] {
+            if value == Some(0) {
+                bail!("[transcribe] {} は 1 以上で指定してください", name);
+            }
+        }
+        if self.transcribe.max_disconnect_buffer_secs == 0 {
+            bail!(
+                "[transcribe] max_disconnect_buffer_secs は 1 以上で指定してください（切断中の音声を送らない場合は send_buffered_on_reconnect = false）"
+            );
+        }
+        if self.audio.recovery_interval_secs == 0 {
+            bail!("[audio] recovery_interval_secs は 1 以上で指定してください");
+        }
+        if self.ui.max_transcript_history == 0 {
+            bail!("[ui] max_transcript_history は 1 以上で指定してください");
+        }
+        self.validate_groups()?;
+        self.validate_summary()?;
+        if let Some(whisper) = &self.whisper {
+            if whisper.chunk_duration_secs == 0 || whisper.max_segment_secs == 0 {
+                bail!("[whisper] chunk_duration_secs と max_segment_secs は 1 以上で指定してください");
+            }
+        }
+        if !self.merge.max_merged_secs.is_finite() || self.merge.max_merged_secs <= 0.0 {
+            bail!("[merge] max_merged_secs は 0 より大きい値で指定してください: {}", self.merge.max_merged_secs);
+        }
+        for (name, value) in [
+            ("aws_per_minute", self.cost.aws_per_minute),
+            ("whisper_per_minute", self.cost.whisper_per_minute),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                bail!("[cost] {} は 0 以上で指定してください: {}", name, value);
+            }
+        }
+        if let Some(threshold) = self.debug.vad_observe_thresholds.iter().find(|t| !t.is_finite()) {
+            bail!("[debug] vad_observe_thresholds に不正な値があります: {}", threshold);
+        }
+        Ok(())
+    }
+
+    /// チャンネルIDが重複せず `[audio] channels` の範囲内で、有効なチャンネルが1つ以上あるか検証
+    fn validate_channels(&self) -> Result<()> {
+        let mut ids = HashSet::new();
+        for channel in &self.channels {
+            if !ids.insert(channel.id) {
+                bail!("チャンネルID {} が重複しています（[[channels]] の id は一意にしてください）", channel.id);
+            }
+            if channel.id >= usize::from(self.audio.channels) {
+                bail!(
+                    "チャンネル {} ({}) が [audio] channels = {} の範囲外です（id は 0～{} で指定してください）",
+                    channel.id,
+                    channel.name,
+                    self.audio.channels,
+                    self.audio.channels.saturating_sub(1)
+                );
+            }
+        }
+        if !self.channels.iter().any(|channel| channel.enabled) {
+            bail!("有効なチャンネルがありません（[[channels]] に enabled = true のチャンネルを1つ以上定義してください）");
+        }
+        Ok(())
+    }
+
+    /// `[dsp]` のリミッタの閾値と先読みの長さが有効か検証
+    fn validate_dsp(&self) -> Result<()> {
+        if let Some(threshold_db) = self.dsp.limiter_threshold_db {
+            if !threshold_db.is_finite() || threshold_db > 0.0 {
+                bail!("[dsp] limiter_threshold_db は 0 以下（dBFS）で指定してください: {}", threshold_db);
+            }
+        }
+        if self.dsp.limiter_lookahead_ms > MAX_LIMITER_LOOKAHEAD_MS {
+            bail!(
+                "[dsp] limiter_lookahead_ms は {} 以下で指定してください: {}",
+                MAX_LIMITER_LOOKAHEAD_MS,
+                self.dsp.limiter_lookahead_ms
+            );
+        }
+        Ok(())
+    }
+
+    /// `[[audio.monitors]]` の出力デバイスが重複せず、チャンネル・音量・定位が有効か検証
+    fn validate_monitors(&self) -> Result<()> {
+        let mut devices = HashSet::new();
+        for (index, monitor) in self.audio.monitors.iter().enumerate() {
+            if !devices.insert(monitor.device_id.trim()) {
+                bail!(
+                    "[[audio.monitors]] の出力デバイス '{}' が重複しています（1つのデバイスには1つのモニタだけを指定してください）",
+                    monitor.device_id
+                );
+            }
+            if let Some(id) = monitor.channel {
+                if !self.channels.iter().any(|channel| channel.id == id && channel.enabled) {
+                    bail!("[[audio.monitors]] {} 番目のチャンネルID {} は有効なチャンネルではありません", index + 1, id);
+                }
+            }
+            if !monitor.volume.is_finite() || monitor.volume < 0.0 {
+                bail!("[[audio.monitors]] {} 番目の volume は 0 以上で指定してください: {}", index + 1, monitor.volume);
+            }
+            if let Some(pan) = monitor.pan.filter(|pan| !(-1.0..=1.0).contains(pan)) {
+                bail!("[[audio.monitors]] {} 番目の pan は -1.0～1.0 で指定してください: {}", index + 1, pan);
+            }
+        }
+        Ok(())
+    }
+
+    /// 文字起こしバックエンドに必要な設定がそろっているか検証
+    fn validate_backend(&self) -> Result<()> {
+        match self.transcribe.backend {
+            TranscribeBackendType::Aws => {
+                if !AWS_SAMPLE_RATES.contains(&self.transcribe.sample_rate) {
+                    bail!(
+                        "[transcribe] sample_rate は Amazon Transcribe が受け付ける {}～{} Hz で指定してください（推奨 16000、電話音声は 8000）: {}",
+                        AWS_SAMPLE_RATES.start(),
+                        AWS_SAMPLE_RATES.end(),
+                        self.transcribe.sample_rate
+                    );
+                }
+            }
+            TranscribeBackendType::Whisper => match &self.whisper {
+                None => bail!("backend = \"whisper\" には [whisper] の設定（api_key など）が必要です"),
+                Some(whisper) if whisper.api_key.trim().is_empty() => {
+                    bail!("backend = \"whisper\" には [whisper] の api_key を設定してください")
+                }
+                Some(_) => {}
+            },
+        }
+        Ok(())
+    }
+
+    /// `log_level` が解釈できる値か検証
+    fn validate_log_levels(&self) -> Result<()> {
+        if self.output.log_level.parse::<LevelFilter>().is_err() {
+            bail!("[output] log_level の設定が不正です: {}", self.output.log_level);
+        }
+        for channel in &self.channels {
+            if let Some(level) = &channel.log_level {
+                if level.parse::<LevelFilter>().is_err() {
+                    bail!("チャンネル {} の log_level の設定が不正です: {}", channel.id, level);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// チャンネルの `symbol` が空でなく、表示幅が `MAX_SYMBOL_WIDTH` 以内か検証
+    fn validate_symbols(&self) -> Result<()> {
+        for channel in &self.channels {
+            if let Some(symbol) = &channel.symbol {
+                let width = crate::tui::display_width(symbol);
+                if width == 0 || width > MAX_SYMBOL_WIDTH || symbol.chars().any(char::is_control) {
+                    bail!(
+                        "チャンネル {} の symbol は1～2文字相当（表示幅{}以内）で指定してください: {:?}",
+                        channel.id,
+                        MAX_SYMBOL_WIDTH,
+                        symbol
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 確信度の閾値が 0.0～1.0 の範囲か検証
+    fn validate_confidence_thresholds(&self) -> Result<()> {
+        let thresholds = [
+            ("[ui] low_confidence_threshold", self.ui.low_confidence_threshold),
+            ("[postprocess] min_confidence", self.postprocess.min_confidence),
+        ];
+        let channel_thresholds = self.channels.iter().filter_map(|channel| {
+            let min_confidence = channel.postprocess.as_ref()?.min_confidence;
+            Some((format!("チャンネル {} の postprocess.min_confidence", channel.id), min_confidence))
+        });
+        let thresholds = thresholds
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .chain(channel_thresholds);
+        for (name, value) in thresholds {
+            if let Some(value) = value {
+                if !(0.0..=1.0).contains(&value) {
+                    bail!("{} は 0.0～1.0 の範囲で指定してください: {}", name, value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 後処理の置換ルール（共通・チャンネル個別）の `from` が空でないか検証
+    fn validate_replacements(&self) -> Result<()> {
+        let channel_rules = self
+            .channels
+            .iter()
+            .filter_map(|channel| channel.postprocess.as_ref()?.replacements.as_ref());
+        for rule in std::iter::once(&self.postprocess.replacements).chain(channel_rules).flatten() {
+            if rule.from.is_empty() {
+                bail!("postprocess の replacements の from は空にできません（to = {:?}）", rule.to);
+            }
+        }
+        Ok(())
+    }
+
+    /// `[transcribe] endpoint_url` が http(s) の URL で、AWS のエンドポイントなら `region` と一致するか検証
+    fn validate_endpoint_url(&self) -> Result<()> {
+        let Some(endpoint_url) = &self.transcribe.endpoint_url else {
+            return Ok(());
+        };
+        let url = reqwest::Url::parse(endpoint_url)
+            .with_context(|| format!("[transcribe] endpoint_url の設定が不正です: {}", endpoint_url))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("[transcribe] endpoint_url は http:// または https:// で指定してください: {}", endpoint_url);
+        }
+        let Some(host) = url.host_str() else {
+            bail!("[transcribe] endpoint_url にホストがありません: {}", endpoint_url);
+        };
+
+        // transcribestreaming.<リージョン>.amazonaws.com なら署名に使うリージョンと一致させる
+        if let Some(region) = host
+            .strip_prefix("transcribestreaming.")
+            .and_then(|rest| rest.strip_suffix(".amazonaws.com"))
+        {
+            if region != self.transcribe.region {
+                bail!(
+                    "[transcribe] endpoint_url のリージョン ({}) が region ({}) と一致しません",
+                    region,
+                    self.transcribe.region
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// `[transcribe] allowed_languages` が2つ以上で `language_code` を含むか検証
+    fn validate_allowed_languages(&self) -> Result<()> {
+        let transcribe = &self.transcribe;
+        if transcribe.allowed_languages.is_empty() {
+            return Ok(());
+        }
+        if !transcribe.auto_language() {
+            bail!("[transcribe] allowed_languages は2つ以上指定してください（言語を固定する場合は language_code のみ指定）");
+        }
+        if transcribe.allowed_languages.iter().any(|code| code.trim().is_empty()) {
+            bail!("[transcribe] allowed_languages に空の言語コードがあります");
+        }
+        if !transcribe
+            .allowed_languages
+            .iter()
+            .any(|code| code.eq_ignore_ascii_case(&transcribe.language_code))
+        {
+            bail!(
+                "[transcribe] allowed_languages に language_code ({}) を含めてください",
+                transcribe.language_code
+            );
         }
+        Ok(())
     }
-}
 
-impl Default for FlacConfig {
-    fn default() -> Self {
-        Self {
-            compression_level: default_flac_compression_level(),
-            enabled: default_flac_enabled(),
+    /// `[[groups]]` の整合性を検証
+    fn validate_groups(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        let mut assigned: HashMap<usize, &str> = HashMap::new();
+
+        for group in &self.groups {
+            if group.name.trim().is_empty() {
+                bail!("グループ名が空です");
+            }
+            if !names.insert(group.name.as_str()) {
+                bail!("グループ名が重複しています: {}", group.name);
+            }
+            if group.channels.is_empty() {
+                bail!("グループ {} にメンバーがありません", group.name);
+            }
+
+            for &id in &group.channels {
+                if !self.channels.iter().any(|c| c.id == id) {
+                    bail!("グループ {} のチャンネルID {} は [[channels]] に存在しません", group.name, id);
+                }
+                if let Some(other) = assigned.insert(id, group.name.as_str()) {
+                    bail!(
+                        "チャンネルID {} がグループ {} と {} に重複して所属しています",
+                        id,
+                        other,
+                        group.name
+                    );
+                }
+            }
         }
+
+        Ok(())
     }
-}
 
-impl Config {
-    /// 設定ファイルから読み込み
-    ///
-    /// TOML形式の設定ファイルをパースしてConfig構造体を生成する。
-    ///
-    /// # Arguments
-    ///
-    /// * `path` - 設定ファイルのパス
-    ///
-    /// # Errors
-    ///
-    /// ファイルの読み込みまたはパースに失敗した場合にエラーを返す。
-    ///
-    /// # Examples
-    ///
-    /// ```no_run
-    /// # use dcr_transcribe::config::Config;
-    /// let config = Config::from_file("config.toml").unwrap();
-    /// ```
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = fs::read_to_string(path.as_ref())
-            .with_context(|| format!("設定ファイルの読み込みに失敗: {:?}", path.as_ref()))?;
-        let config: Config =
-            toml::from_str(&content).with_context(|| "設定ファイルのパースに失敗")?;
-        Ok(config)
+    /// `[summary]` 有効時に要約を実行できる設定か検証
+    fn validate_summary(&self) -> Result<()> {
+        let summary = &self.summary;
+        if !summary.enabled {
+            return Ok(());
+        }
+        if summary.resolve_api_key(self.whisper.as_ref()).is_none() {
+            bail!("[summary] を有効にするには [summary] または [whisper] の api_key を設定してください");
+        }
+        if summary.interval_secs == 0 && summary.max_utterances == 0 {
+            bail!("[summary] interval_secs と max_utterances の少なくとも一方を 1 以上にしてください");
+        }
+        if summary.model.trim().is_empty() {
+            bail!("[summary] model が空です");
+        }
+        if summary.scope == SummaryScope::Group && self.groups.is_empty() {
+            bail!("[summary] scope = \"group\" には [[groups]] の定義が必要です");
+        }
+        Ok(())
+    }
+
+    /// チャンネルが所属するグループを取得
+    pub fn group_of(&self, channel_id: usize) -> Option<&GroupConfig> {
+        self.groups.iter().find(|g| g.channels.contains(&channel_id))
     }
 
     /// デフォルト設定をファイルに書き出し
@@ -471,24 +2280,231 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    /// 検証を通すための最小限のチャンネル定義（設定ファイルの末尾に付ける）
+    const CHANNEL: &str = "\n[[channels]]\nid = 0\nname = \"無線機1\"\n";
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
         assert_eq!(config.audio.sample_rate, 16000);
         assert_eq!(config.audio.channels, 4);
+        assert_eq!(config.audio.warmup_ms, 500);
+        assert_eq!(config.audio.internal_format, InternalFormat::I16);
         assert_eq!(config.vad.threshold_db, -40.0);
         assert_eq!(config.vad.silence_disconnect_threshold_ms, 10000);
         assert_eq!(config.buffer.capacity_seconds, 300);
         assert_eq!(config.transcribe.language_code, "ja-JP");
         assert_eq!(config.transcribe.region, "ap-northeast-1");
-        assert_eq!(config.transcribe.connect_on_startup, false);
-        assert_eq!(config.transcribe.send_buffered_on_reconnect, true);
+        assert!(!config.transcribe.connect_on_startup);
+        assert!(config.transcribe.send_buffered_on_reconnect);
         assert_eq!(config.channels.len(), 2);
     }
 
     #[test]
-    fn test_write_and_read_config() {
+    fn test_whisper_vad_segmentation() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[whisper]\napi_key = \"sk\"\nvad_segmentation = true{}", CHANNEL).unwrap();
+        let whisper = Config::from_file(temp_file.path()).unwrap().whisper.unwrap();
+        assert!(whisper.vad_segmentation);
+        assert_eq!(whisper.max_segment_secs, 30);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[whisper]\napi_key = \"sk\"\nmax_segment_secs = 0{}", CHANNEL).unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_internal_format() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[audio]\ninternal_format = \"f32\"{}", CHANNEL).unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.audio.internal_format, InternalFormat::F32);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[audio]\ninternal_format = \"f64\"{}", CHANNEL).unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_input_recovery_config() {
+        let config = Config::default();
+        assert_eq!(config.audio.stall_timeout_secs, 10);
+        assert_eq!(config.audio.silent_input_timeout_secs, 0);
+        assert_eq!(config.audio.recovery_interval_secs, 5);
+        assert_eq!(config.audio.recovery_max_attempts, 10);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "[audio]\nstall_timeout_secs = 0\nsilent_input_timeout_secs = 60\nrecovery_max_attempts = 0{}",
+            CHANNEL
+        )
+        .unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.audio.stall_timeout_secs, 0);
+        assert_eq!(config.audio.silent_input_timeout_secs, 60);
+        assert_eq!(config.audio.recovery_max_attempts, 0);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[audio]\nrecovery_interval_secs = 0{}", CHANNEL).unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_expand_env() {
+        let lookup = |name: &str| (name == "OPENAI_API_KEY").then(|| "sk-abc".to_string());
+        assert_eq!(expand_with("${OPENAI_API_KEY}", lookup).unwrap(), "sk-abc");
+        assert_eq!(expand_with("Bearer ${OPENAI_API_KEY}!", lookup).unwrap(), "Bearer sk-abc!");
+        assert_eq!(expand_with("plain-key", lookup).unwrap(), "plain-key");
+        assert_eq!(expand_with("$${OPENAI_API_KEY}", lookup).unwrap(), "${OPENAI_API_KEY}");
+        assert_eq!(expand_with("a$b$", lookup).unwrap(), "a$b$");
+
+        let message = expand_with("${MISSING}", lookup).unwrap_err().to_string();
+        assert!(message.contains("環境変数 MISSING が定義されていません"), "{}", message);
+        assert!(expand_with("${OPENAI_API_KEY", lookup).is_err());
+        assert!(expand_with("${}", lookup).is_err());
+        assert!(expand_with("${OPENAI-KEY}", lookup).is_err());
+    }
+
+    #[test]
+    fn test_from_file_expands_api_keys() -> Result<()> {
+        std::env::set_var("DCR_TEST_WHISPER_API_KEY", "sk-from-env");
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            format!(
+                "[transcribe]\nbackend = \"whisper\"\n\n[whisper]\napi_key = \"${{DCR_TEST_WHISPER_API_KEY}}\"\n{}",
+                CHANNEL
+            ),
+        )?;
+        assert_eq!(Config::from_file(&path)?.whisper.unwrap().api_key, "sk-from-env");
+
+        fs::write(
+            &path,
+            format!("[whisper]\napi_key = \"${{DCR_TEST_UNDEFINED_API_KEY}}\"\n{}", CHANNEL),
+        )?;
+        let message = format!("{:#}", Config::from_file(&path).unwrap_err());
+        assert!(message.contains("[whisper] api_key"), "{}", message);
+        assert!(message.contains("DCR_TEST_UNDEFINED_API_KEY"), "{}", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_audio_preset() {
+        let load = |content: &str| {
+            let mut temp_file = NamedTempFile::new().unwrap();
+            writeln!(temp_file, "{}{}", content, CHANNEL).unwrap();
+            Config::from_file(temp_file.path()).unwrap()
+        };
+
+        // 未指定なら [vad] のデフォルト値のまま
+        let config = load("[audio]\nchannels = 2");
+        assert_eq!(config.audio.preset, None);
+        assert_eq!(config.vad.threshold_db, -40.0);
+        assert_eq!(config.describe_audio_preset(), None);
+
+        let config = load("[audio]\npreset = \"radio\"");
+        assert_eq!(config.audio.preset, Some(AudioPreset::Radio));
+        assert_eq!(config.vad.threshold_db, -35.0);
+        assert_eq!(config.vad.hangover_duration_ms, 800);
+        assert_eq!(config.vad.silence_disconnect_threshold_ms, 5000);
+        assert_eq!(
+            config.describe_audio_preset().unwrap(),
+            "音声プリセット \"radio\" 適用後のVAD設定: 閾値 -35.0 dB, ハングオーバー 800ms, 無音切断 5000ms"
+        );
+
+        // [vad] に書いた値はプリセットより優先（デフォルトと同じ値でも）
+        let config = load("[audio]\npreset = \"meeting\"\n\n[vad]\nthreshold_db = -40.0\nhangover_duration_ms = 200");
+        assert_eq!(config.vad.threshold_db, -40.0);
+        assert_eq!(config.vad.hangover_duration_ms, 200);
+        assert_eq!(config.vad.silence_disconnect_threshold_ms, 15000);
+
+        let config = load("[audio]\npreset = \"broadcast\"");
+        assert_eq!(config.vad.threshold_db, -30.0);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[audio]\npreset = \"studio\"{}", CHANNEL).unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_timezone_rejected() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[output]\ntimezone = \"Asia/Tokio\"{}", CHANNEL).unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[output]\ntimezone = \"Asia/Tokyo\"{}", CHANNEL).unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.output.timezone, "Asia/Tokyo");
+    }
+
+    fn config_with_groups(groups: &str) -> Result<Config> {
         let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            r#"
+[[channels]]
+id = 0
+name = "無線機1"
+
+[[channels]]
+id = 1
+name = "無線機2"
+
+[[channels]]
+id = 2
+name = "無線機3"
+{}"#,
+            groups
+        )
+        .unwrap();
+        Config::from_file(temp_file.path())
+    }
+
+    #[test]
+    fn test_groups() {
+        let config = config_with_groups(
+            r#"
+[[groups]]
+name = "第1小隊"
+channels = [0, 1]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.groups.len(), 1);
+        assert_eq!(config.group_of(1).unwrap().name, "第1小隊");
+        assert!(config.group_of(2).is_none());
+
+        // グループ未定義なら全チャンネル独立
+        let config = config_with_groups("").unwrap();
+        assert!(config.groups.is_empty());
+        assert!(config.group_of(0).is_none());
+    }
+
+    #[test]
+    fn test_invalid_groups_rejected() {
+        // 存在しないチャンネルID
+        assert!(config_with_groups("[[groups]]\nname = \"A\"\nchannels = [0, 5]\n").is_err());
+        // グループ内の重複
+        assert!(config_with_groups("[[groups]]\nname = \"A\"\nchannels = [0, 0]\n").is_err());
+        // グループ間の重複
+        assert!(config_with_groups(
+            "[[groups]]\nname = \"A\"\nchannels = [0, 1]\n[[groups]]\nname = \"B\"\nchannels = [1, 2]\n"
+        )
+        .is_err());
+        // メンバーなし・名前の重複
+        assert!(config_with_groups("[[groups]]\nname = \"A\"\nchannels = []\n").is_err());
+        assert!(config_with_groups(
+            "[[groups]]\nname = \"A\"\nchannels = [0]\n[[groups]]\nname = \"A\"\nchannels = [1]\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_write_and_read_config() {
+        let temp_file = NamedTempFile::new().unwrap();
         let path = temp_file.path();
 
         // デフォルト設定を書き込み
@@ -500,6 +2516,67 @@ mod tests {
         assert_eq!(config.transcribe.region, "ap-northeast-1");
     }
 
+    #[test]
+    fn test_session_report_config() {
+        let config = Config::default();
+        assert!(!config.output.session_report);
+        assert_eq!(config.output.session_report_format, SessionReportFormat::Markdown);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "[output]\nsession_report = true\nsession_report_dir = \"reports\"\nsession_report_format = \"both\"{}",
+            CHANNEL
+        )
+        .unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert!(config.output.session_report);
+        assert_eq!(config.output.session_report_dir.as_deref(), Some("reports"));
+        assert_eq!(config.output.session_report_format, SessionReportFormat::Both);
+    }
+
+    #[test]
+    fn test_transcribe_connection_flags() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "[transcribe]\nconnect_on_startup = true\nsend_buffered_on_reconnect = false{}",
+            CHANNEL
+        )
+        .unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert!(config.transcribe.connect_on_startup);
+        assert!(!config.transcribe.send_buffered_on_reconnect);
+
+        // 省略時はデフォルト値で、デフォルト設定の出力にも含まれる
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[transcribe]\nregion = \"us-east-1\"{}", CHANNEL).unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert!(!config.transcribe.connect_on_startup);
+        assert!(config.transcribe.send_buffered_on_reconnect);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        Config::write_default(temp_file.path()).unwrap();
+        let written = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(written.contains("connect_on_startup = false"));
+        assert!(written.contains("send_buffered_on_reconnect = true"));
+    }
+
+    #[test]
+    fn test_silence_disconnect_threshold_default() {
+        // 省略時はデフォルト値
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[vad]\nthreshold_db = -30.0{}", CHANNEL).unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.vad.silence_disconnect_threshold_ms, 10000);
+
+        // デフォルト設定の出力にも含まれる
+        let temp_file = NamedTempFile::new().unwrap();
+        Config::write_default(temp_file.path()).unwrap();
+        let written = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(written.contains("silence_disconnect_threshold_ms = 10000"));
+    }
+
     #[test]
     fn test_custom_config() {
         let toml_content = r#"
@@ -556,12 +2633,551 @@ enabled = false
         assert_eq!(config.transcribe.max_retries, 10);
         assert_eq!(config.output.wav_output_dir, "/tmp/test");
         assert_eq!(config.output.log_level, "debug");
+        assert_eq!(config.output.min_free_bytes, 0);
         assert_eq!(config.channels.len(), 2);
         assert_eq!(config.channels[0].name, "Channel 1");
         assert!(config.channels[0].enabled);
         assert!(!config.channels[1].enabled);
     }
 
+    #[test]
+    fn test_channel_send_buffered_on_reconnect_override() {
+        let toml_content = r#"
+[transcribe]
+send_buffered_on_reconnect = false
+
+[[channels]]
+id = 0
+name = "重要"
+send_buffered_on_reconnect = true
+
+[[channels]]
+id = 1
+name = "通常"
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.channels[0].send_buffered_on_reconnect, Some(true));
+        assert_eq!(config.channels[1].send_buffered_on_reconnect, None);
+        assert!(config.channels[0].resolve_send_buffered_on_reconnect(&config.transcribe));
+        assert!(!config.channels[1].resolve_send_buffered_on_reconnect(&config.transcribe));
+    }
+
+    #[test]
+    fn test_channel_record_mode_override() {
+        let toml_content = r#"
+[output]
+record_mode = "vad_triggered"
+vad_record_files = "single"
+
+[[channels]]
+id = 0
+name = "常時録音"
+record_mode = "continuous"
+
+[[channels]]
+id = 1
+name = "発話のみ"
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.output.vad_record_files, VadRecordFiles::Single);
+        assert_eq!(config.output.vad_preroll_ms, 500);
+        assert_eq!(config.channels[0].resolve_record_mode(&config.output), RecordMode::Continuous);
+        assert_eq!(config.channels[1].resolve_record_mode(&config.output), RecordMode::VadTriggered);
+
+        // 既定は常時録音
+        let output = OutputConfig::default();
+        assert_eq!(output.record_mode, RecordMode::Continuous);
+        assert_eq!(output.vad_record_files, VadRecordFiles::PerSegment);
+    }
+
+    #[test]
+    fn test_channel_postprocess_override() {
+        let toml_content = r#"
+[postprocess]
+min_confidence = 0.3
+normalize_width = true
+replacements = [{ from = "本分", to = "本部" }]
+
+[[channels]]
+id = 0
+name = "日本語"
+
+[[channels]]
+id = 1
+name = "English"
+postprocess = { remove_fillers = false, replacements = [] }
+
+[[channels]]
+id = 2
+name = "混在"
+postprocess = { min_confidence = 0.6, replacements = [{ from = "roger", to = "Roger" }] }
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert!(config.validate().is_ok());
+
+        // 未指定のチャンネルは共通設定のまま
+        let ja = config.channels[0].resolve_postprocess(&config.postprocess);
+        assert_eq!(ja, config.postprocess);
+        assert!(ja.remove_fillers);
+
+        // 指定した項目だけ上書きし、空の replacements で共通のルールを無効にする
+        let en = config.channels[1].resolve_postprocess(&config.postprocess);
+        assert!(!en.remove_fillers);
+        assert!(en.normalize_width);
+        assert_eq!(en.min_confidence, Some(0.3));
+        assert!(en.replacements.is_empty());
+
+        // replacements は共通のルールに追加せず置き換える
+        let mixed = config.channels[2].resolve_postprocess(&config.postprocess);
+        assert!(mixed.remove_fillers);
+        assert_eq!(mixed.min_confidence, Some(0.6));
+        assert_eq!(
+            mixed.replacements,
+            vec![ReplacementRule { from: "roger".to_string(), to: "Roger".to_string() }]
+        );
+
+        // 共通設定の既定値は従来通りフィラーワードを削除する
+        assert!(Config::default().postprocess.remove_fillers);
+
+        let invalid = |channel: &str| {
+            let config: Config = toml::from_str(&format!("[[channels]]\nid = 0\nname = \"A\"\n{}\n", channel)).unwrap();
+            config.validate().is_err()
+        };
+        assert!(invalid("postprocess = { min_confidence = 1.5 }"));
+        assert!(invalid("postprocess = { replacements = [{ from = \"\", to = \"x\" }] }"));
+        assert!(!invalid("postprocess = { remove_fillers = false }"));
+    }
+
+    #[test]
+    fn test_channel_log_level_override() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            r#"
+[output]
+log_level = "warn"
+
+[[channels]]
+id = 0
+name = "問題チャンネル"
+log_level = "trace"
+
+[[channels]]
+id = 1
+name = "通常"
+"#
+        )
+        .unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+
+        assert_eq!(config.channels[0].resolve_log_level(&config.output), LevelFilter::Trace);
+        assert_eq!(config.channels[1].resolve_log_level(&config.output), LevelFilter::Warn);
+
+        // 解釈できないレベルは読み込み時にエラー
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "[[channels]]\nid = 0\nname = \"A\"\nlog_level = \"verbose\"\n").unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_channel_symbol() {
+        let parse = |symbol: &str| {
+            let config: Config = toml::from_str(&format!(
+                "[[channels]]\nid = 0\nname = \"本部\"\nsymbol = \"{}\"\n",
+                symbol
+            ))
+            .unwrap();
+            config.validate().map(|_| config)
+        };
+
+        assert_eq!(Config::default().channels[0].symbol, None);
+        assert_eq!(parse("●").unwrap().channels[0].symbol.as_deref(), Some("●"));
+        assert!(parse("A").is_ok());
+        assert!(parse("📻").is_ok());
+        // 異体字セレクタは幅に数えない
+        assert!(parse("☎\u{FE0F}").is_ok());
+
+        assert!(parse("").is_err());
+        assert!(parse("ABC").is_err());
+        assert!(parse("📻📻").is_err());
+        assert!(parse("\\t").is_err());
+    }
+
+    #[test]
+    fn test_session_metadata() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.session.is_empty());
+
+        let config: Config =
+            toml::from_str("[session]\nsite_id = \"tokyo-01\"\nevent_id = \"drill-2025\"\n").unwrap();
+        assert_eq!(config.session.len(), 2);
+        assert_eq!(config.session["site_id"], "tokyo-01");
+        assert_eq!(config.session["event_id"], "drill-2025");
+    }
+
+    #[test]
+    fn test_confidence_thresholds() {
+        // 未指定なら色分け・除外ともに無効
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.ui.low_confidence_threshold, None);
+        assert_eq!(config.postprocess.min_confidence, None);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            "[ui]\nlow_confidence_threshold = 0.7\n\n[postprocess]\nmin_confidence = 0.3\n{}",
+            CHANNEL
+        )
+        .unwrap();
+        let config = Config::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.ui.low_confidence_threshold, Some(0.7));
+        assert_eq!(config.postprocess.min_confidence, Some(0.3));
+
+        // 範囲外の値は読み込み時にエラー
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "[postprocess]\nmin_confidence = 1.5\n{}", CHANNEL).unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_endpoint_url() {
+        let validate = |transcribe: &str| {
+            let config: Config = toml::from_str(&format!("[transcribe]\n{}\n{}", transcribe, CHANNEL)).unwrap();
+            config.validate()
+        };
+
+        assert_eq!(Config::default().transcribe.endpoint_url, None);
+        assert!(validate("endpoint_url = \"http://localhost:4566\"").is_ok());
+        assert!(validate("endpoint_url = \"https://proxy.example.internal:8443\"").is_ok());
+        assert!(validate(
+            "region = \"us-east-1\"\nendpoint_url = \"https://transcribestreaming.us-east-1.amazonaws.com\""
+        )
+        .is_ok());
+
+        // 不正な URL・スキーム
+        assert!(validate("endpoint_url = \"localhost:4566\"").is_err());
+        assert!(validate("endpoint_url = \"not a url\"").is_err());
+        assert!(validate("endpoint_url = \"ftp://localhost\"").is_err());
+        // 署名に使うリージョンと AWS エンドポイントのリージョンが食い違う
+        assert!(validate("endpoint_url = \"https://transcribestreaming.us-east-1.amazonaws.com\"").is_err());
+    }
+
+    #[test]
+    fn test_max_concurrent_streams() {
+        let validate = |transcribe: &str| {
+            let config: Config = toml::from_str(&format!("[transcribe]\n{}\n{}", transcribe, CHANNEL)).unwrap();
+            config.validate().map(|_| config.transcribe)
+        };
+
+        let transcribe = Config::default().transcribe;
+        assert_eq!(transcribe.max_concurrent_streams, None);
+        assert_eq!(transcribe.stream_quota, None);
+
+        let transcribe = validate("max_concurrent_streams = 20\nstream_quota = 50").unwrap();
+        assert_eq!(transcribe.max_concurrent_streams, Some(20));
+        assert_eq!(transcribe.stream_quota, Some(50));
+        assert!(validate("max_concurrent_streams = 0").is_err());
+        assert!(validate("stream_quota = 0").is_err());
+    }
+
+    #[test]
+    fn test_max_disconnect_buffer() {
+        let validate = |transcribe: &str| {
+            let config: Config = toml::from_str(&format!("[transcribe]\n{}\n{}", transcribe, CHANNEL)).unwrap();
+            config.validate().map(|_| config.transcribe)
+        };
+
+        let transcribe = Config::default().transcribe;
+        assert_eq!(transcribe.max_disconnect_buffer_secs, 30);
+        assert_eq!(transcribe.disconnect_buffer_overflow, DisconnectBufferOverflow::DropOldest);
+
+        let transcribe = validate("max_disconnect_buffer_secs = 120\ndisconnect_buffer_overflow = \"discard_all\"").unwrap();
+        assert_eq!(transcribe.max_disconnect_buffer_secs, 120);
+        assert_eq!(transcribe.disconnect_buffer_overflow, DisconnectBufferOverflow::DiscardAll);
+        assert!(validate("max_disconnect_buffer_secs = 0").is_err());
+    }
+
+    #[test]
+    fn test_allowed_languages() {
+        let parse = |transcribe: &str| {
+            let config: Config = toml::from_str(&format!("[transcribe]\n{}\n{}", transcribe, CHANNEL)).unwrap();
+            config.validate().map(|_| config)
+        };
+
+        assert!(!Config::default().transcribe.auto_language());
+        let config = parse("allowed_languages = [\"ja-JP\", \"en-US\"]").unwrap();
+        assert!(config.transcribe.auto_language());
+
+        // 候補が1つだけ・language_code を含まない・空のコードはエラー
+        assert!(parse("allowed_languages = [\"ja-JP\"]").is_err());
+        assert!(parse("allowed_languages = [\"en-US\", \"ko-KR\"]").is_err());
+        assert!(parse("allowed_languages = [\"ja-JP\", \" \"]").is_err());
+    }
+
+    #[test]
+    fn test_summary_config() {
+        let parse = |toml: &str| {
+            let config: Config = toml::from_str(&format!("{}{}", toml, CHANNEL)).unwrap();
+            config.validate().map(|_| config)
+        };
+
+        let summary = Config::default().summary;
+        assert!(!summary.enabled);
+        assert_eq!(summary.interval_secs, 300);
+        assert_eq!(summary.scope, SummaryScope::Channel);
+        assert_eq!(summary.requests_per_hour(2), 24);
+
+        // [whisper] の APIキーを流用できる
+        let config = parse(
+            "[whisper]\napi_key = \"sk-whisper\"\n[summary]\nenabled = true\nscope = \"channel\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.summary.resolve_api_key(config.whisper.as_ref()), Some("sk-whisper"));
+
+        // APIキーがない・区切りがない・グループがないのにグループ単位はエラー
+        assert!(parse("[summary]\nenabled = true\n").is_err());
+        assert!(parse("[summary]\nenabled = true\napi_key = \"sk\"\ninterval_secs = 0\nmax_utterances = 0\n").is_err());
+        assert!(parse("[summary]\nenabled = true\napi_key = \"sk\"\nscope = \"group\"\n").is_err());
+        // 無効なら検証しない
+        assert!(parse("[summary]\nscope = \"group\"\n").is_ok());
+    }
+
+    #[test]
+    fn test_memory_config() {
+        let memory = Config::default().memory;
+        assert_eq!(memory.interval_secs, 60);
+        assert_eq!(memory.surge_warn_bytes(), 64_000_000);
+
+        let config: Config = toml::from_str("[memory]\ninterval_secs = 10\n").unwrap();
+        assert_eq!(config.memory.interval_secs, 10);
+        assert_eq!(config.memory.surge_warn_mb, 64);
+    }
+
+    #[test]
+    fn test_merge_config() {
+        let parse = |toml: &str| {
+            let config: Config = toml::from_str(&format!("{}{}", toml, CHANNEL)).unwrap();
+            config.validate().map(|_| config)
+        };
+
+        let merge = Config::default().merge;
+        assert!(!merge.enabled);
+        assert_eq!(merge.max_gap_ms, 300);
+        assert_eq!(merge.hold_ms, 1500);
+
+        let config = parse("[merge]\nenabled = true\nmax_gap_ms = 500\n").unwrap();
+        assert!(config.merge.enabled);
+        assert_eq!(config.merge.max_gap_ms, 500);
+        assert_eq!(config.merge.max_merged_secs, 20.0);
+
+        assert!(parse("[merge]\nmax_merged_secs = 0.0\n").is_err());
+    }
+
+    #[test]
+    fn test_recording_start_delay() {
+        let output = OutputConfig::default();
+        assert_eq!(output.recording_start_delay_ms(3, 4), 0);
+
+        let output: OutputConfig = toml::from_str("recording_start_stagger_ms = 200").unwrap();
+        let delays: Vec<u32> = (0..4).map(|i| output.recording_start_delay_ms(i, 4)).collect();
+        assert_eq!(delays, vec![0, 200, 400, 600]);
+        assert_eq!(output.recording_start_delay_ms(0, 1), 0);
+
+        // 全チャンネルの開始までが上限を超える場合は間隔を詰める
+        let delays: Vec<u32> = (0..21).map(|i| output.recording_start_delay_ms(i, 21)).collect();
+        assert_eq!(delays[1], 100);
+        assert_eq!(delays[20], MAX_RECORDING_START_STAGGER_TOTAL_MS);
+    }
+
+    #[test]
+    fn test_cost_config() {
+        let cost = Config::default().cost;
+        assert!(!cost.enabled);
+        assert_eq!(cost.per_minute(TranscribeBackendType::Aws.label()), 0.024);
+        assert_eq!(cost.per_minute(TranscribeBackendType::Whisper.label()), 0.006);
+        assert_eq!(cost.format(0.5), "0.5000 USD");
+
+        let config: Config =
+            toml::from_str(&format!("[cost]\nenabled = true\naws_per_minute = 3.6\ncurrency = \"JPY\"\n{}", CHANNEL))
+                .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.cost.per_minute(TranscribeBackendType::Aws.label()), 3.6);
+        assert_eq!(config.cost.whisper_per_minute, 0.006);
+
+        let config: Config = toml::from_str(&format!("[cost]\nwhisper_per_minute = -1.0\n{}", CHANNEL)).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_vad_observe_thresholds() {
+        assert!(Config::default().debug.vad_observe_thresholds.is_empty());
+
+        let config: Config =
+            toml::from_str(&format!("[debug]\nvad_observe_thresholds = [-35, -40.5]\n{}", CHANNEL)).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.debug.vad_observe_thresholds, vec![-35.0, -40.5]);
+        assert!(!config.debug.log_resampler_stats);
+
+        let config: Config =
+            toml::from_str(&format!("[debug]\nvad_observe_thresholds = [-35, nan]\n{}", CHANNEL)).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_channels_and_backend() {
+        let validate = |toml: &str| toml::from_str::<Config>(toml).unwrap().validate();
+        assert!(Config::default().validate().is_ok());
+
+        // チャンネルがない・すべて無効
+        let message = validate("").unwrap_err().to_string();
+        assert!(message.contains("有効なチャンネルがありません"), "{}", message);
+        assert!(validate("[[channels]]\nid = 0\nname = \"a\"\nenabled = false").is_err());
+
+        // IDの重複
+        let message = validate("[[channels]]\nid = 1\nname = \"a\"\n\n[[channels]]\nid = 1\nname = \"b\"")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("チャンネルID 1 が重複"), "{}", message);
+
+        // [audio] channels の範囲外
+        let message = validate("[audio]\nchannels = 2\n\n[[channels]]\nid = 2\nname = \"a\"")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("[audio] channels = 2 の範囲外"), "{}", message);
+        assert!(validate("[audio]\nchannels = 2\n\n[[channels]]\nid = 1\nname = \"a\"").is_ok());
+
+        // Amazon Transcribe のサンプルレート
+        for sample_rate in [8000, 16000, 48000] {
+            assert!(validate(&format!("[transcribe]\nsample_rate = {}\n{}", sample_rate, CHANNEL)).is_ok());
+        }
+        for sample_rate in [0, 7999, 96000] {
+            assert!(validate(&format!("[transcribe]\nsample_rate = {}\n{}", sample_rate, CHANNEL)).is_err());
+        }
+
+        // Whisper バックエンドには [whisper] と api_key が必要
+        let message = validate(&format!("[transcribe]\nbackend = \"whisper\"\n{}", CHANNEL))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("[whisper] の設定"), "{}", message);
+        let whisper = |api_key: &str| {
+            validate(&format!(
+                "[transcribe]\nbackend = \"whisper\"\n\n[whisper]\napi_key = \"{}\"\n{}",
+                api_key, CHANNEL
+            ))
+        };
+        assert!(whisper(" ").is_err());
+        assert!(whisper("sk-test").is_ok());
+        // Whisper ではAWSのサンプルレートは検証しない
+        assert!(validate(&format!(
+            "[transcribe]\nbackend = \"whisper\"\nsample_rate = 96000\n\n[whisper]\napi_key = \"sk\"\n{}",
+            CHANNEL
+        ))
+        .is_ok());
+
+        // FLACの圧縮レベル
+        assert!(validate(&format!("[flac]\ncompression_level = 8\n{}", CHANNEL)).is_ok());
+        let message = validate(&format!("[flac]\ncompression_level = 9\n{}", CHANNEL)).unwrap_err().to_string();
+        assert!(message.contains("compression_level"), "{}", message);
+
+        // 送信音声のリミッタ
+        assert!(Config::default().dsp.limiter_threshold_db.is_none());
+        let dsp = |settings: &str| validate(&format!("[dsp]\n{}\n{}", settings, CHANNEL));
+        assert!(dsp("limiter_threshold_db = -1.0\nlimiter_lookahead_ms = 5").is_ok());
+        let message = dsp("limiter_threshold_db = 3.0").unwrap_err().to_string();
+        assert!(message.contains("limiter_threshold_db"), "{}", message);
+        let message = dsp("limiter_lookahead_ms = 50").unwrap_err().to_string();
+        assert!(message.contains("limiter_lookahead_ms"), "{}", message);
+
+        // 録音ファイルのローテーション間隔
+        assert!(validate(&format!("[output]\nwav_rotation_secs = 3600\n{}", CHANNEL)).is_ok());
+        let message = validate(&format!("[output]\nwav_rotation_secs = 0\n{}", CHANNEL)).unwrap_err().to_string();
+        assert!(message.contains("wav_rotation_secs"), "{}", message);
+    }
+
+    #[test]
+    fn test_audio_monitors() {
+        let load = |toml: &str| toml::from_str::<Config>(&format!("{}\n{}", toml, CHANNEL)).unwrap();
+
+        // 未定義なら output_device_id / output_volume の単一モニタ
+        let config = load("[audio]\noutput_device_id = \"Speakers\"\noutput_volume = 0.5");
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.audio.effective_monitors(),
+            vec![MonitorConfig {
+                device_id: "Speakers".to_string(),
+                channel: None,
+                volume: 0.5,
+                pan: None,
+            }]
+        );
+
+        let config = load(
+            "[[audio.monitors]]\n\n[[audio.monitors]]\ndevice_id = \"Headset\"\nchannel = 0\nvolume = 0.8\npan = -1.0",
+        );
+        assert!(config.validate().is_ok());
+        let monitors = config.audio.effective_monitors();
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors[0].device_id, "default");
+        assert!(monitors[0].follows_selection());
+        assert_eq!(monitors[1].channel, Some(0));
+        assert_eq!(monitors[1].pan, Some(-1.0));
+
+        // 同じデバイスの重複、存在しないチャンネル、範囲外の定位・音量
+        let message = load("[[audio.monitors]]\ndevice_id = \"A\"\n\n[[audio.monitors]]\ndevice_id = \"A\"\nchannel = 0")
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("'A' が重複"), "{}", message);
+        assert!(load("[[audio.monitors]]\nchannel = 1").validate().is_err());
+        assert!(load("[[audio.monitors]]\npan = 1.5").validate().is_err());
+        assert!(load("[[audio.monitors]]\nvolume = -0.1").validate().is_err());
+    }
+
+    #[test]
+    fn test_max_transcript_history() {
+        // 未指定なら従来通り100件
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.ui.max_transcript_history, 100);
+        assert_eq!(Config::default().ui.max_transcript_history, 100);
+        let config: Config = toml::from_str("[ui]\nshow_waveform = true\n").unwrap();
+        assert_eq!(config.ui.max_transcript_history, 100);
+
+        let config: Config = toml::from_str("[ui]\nmax_transcript_history = 1000\n").unwrap();
+        // 2チャンネル + 1グループ分
+        assert_eq!(config.ui.estimated_transcript_history_bytes(3), 3 * 1000 * TRANSCRIPT_ENTRY_ESTIMATED_BYTES);
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "[ui]\nmax_transcript_history = 0\n").unwrap();
+        assert!(Config::from_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_upload_config() {
+        let toml_content = r#"
+[upload]
+backend = "s3"
+bucket = "dcr-archive"
+prefix = "site-a/"
+delete_after_upload = true
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let upload = config.upload.unwrap();
+        assert_eq!(upload.backend, UploadBackendType::S3);
+        assert_eq!(upload.bucket, "dcr-archive");
+        assert_eq!(upload.prefix, "site-a/");
+        assert!(upload.region.is_none());
+        assert!(upload.delete_after_upload);
+        assert_eq!(upload.max_retries, 5);
+        assert_eq!(upload.retry_interval_secs, 10);
+
+        // 未設定の場合はアップロードしない
+        assert!(Config::default().upload.is_none());
+    }
+
     #[test]
     fn test_load_or_default_nonexistent() {
         let config = Config::load_or_default("nonexistent_file.toml").unwrap();
@@ -0,0 +1,158 @@
+use crate::config::{CostConfig, TranscribeBackendType};
+
+/// チャンネルの送信音声時間と概算コスト
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostSummary {
+    /// 文字起こしバックエンドに送信した音声の時間（秒）
+    pub sent_secs: f64,
+    /// 概算コスト（`[cost]` の通貨単位）
+    pub cost: f64,
+}
+
+impl CostSummary {
+    /// 複数チャンネルの合計
+    pub fn total<'a>(summaries: impl IntoIterator<Item = &'a CostSummary>) -> CostSummary {
+        summaries.into_iter().fold(CostSummary::default(), |total, summary| CostSummary {
+            sent_secs: total.sent_secs + summary.sent_secs,
+            cost: total.cost + summary.cost,
+        })
+    }
+}
+
+/// チャンネルごとに文字起こしバックエンドへ送信した音声時間を積算し、概算コストを求める
+///
+/// 従量課金は実際に送信した音声の長さに対してかかるため、再接続時に送った切断中のバッファや、
+/// 接続中に送るゼロサンプル（無音）も含めて数える。
+/// 設定リロードでバックエンドが切り替わった場合に備え、バックエンドごとに分けて積算する。
+#[derive(Debug, Clone)]
+pub struct CostMeter {
+    config: CostConfig,
+    sample_rate: u32,
+    /// バックエンドの表示名ごとの送信サンプル数（最初に送信した順）
+    sent_samples: Vec<(String, u64)>,
+}
+
+impl CostMeter {
+    pub fn new(config: &CostConfig, sample_rate: u32) -> Self {
+        Self {
+            config: config.clone(),
+            sample_rate: sample_rate.max(1),
+            sent_samples: Vec::new(),
+        }
+    }
+
+    /// `backend_label` のバックエンドへ送信したサンプル数を加算
+    pub fn add(&mut self, backend_label: &str, samples: usize) {
+        match self.sent_samples.iter_mut().find(|(label, _)| label == backend_label) {
+            Some((_, sent)) => *sent += samples as u64,
+            None => self.sent_samples.push((backend_label.to_string(), samples as u64)),
+        }
+    }
+
+    /// バックエンドごとの送信時間と概算コスト
+    pub fn breakdown(&self) -> Vec<(&str, CostSummary)> {
+        self.sent_samples
+            .iter()
+            .map(|(label, samples)| {
+                let sent_secs = *samples as f64 / self.sample_rate as f64;
+                let cost = sent_secs / 60.0 * self.config.per_minute(label);
+                (label.as_str(), CostSummary { sent_secs, cost })
+            })
+            .collect()
+    }
+
+    /// 全バックエンドの合計
+    pub fn summary(&self) -> CostSummary {
+        CostSummary::total(self.breakdown().iter().map(|(_, summary)| summary))
+    }
+
+    /// ログ出力用の内訳（例: "Amazon Transcribe 120.0秒 = 0.0480 USD"）
+    pub fn describe(&self) -> String {
+        if self.sent_samples.is_empty() {
+            return "送信なし".to_string();
+        }
+        self.breakdown()
+            .iter()
+            .map(|(label, summary)| {
+                format!("{} {:.1}秒 = {}", label, summary.sent_secs, self.config.format(summary.cost))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl CostConfig {
+    /// バックエンドの表示名（`TranscribeBackendType::label`）に対応する1分あたりの単価
+    pub fn per_minute(&self, backend_label: &str) -> f64 {
+        if backend_label == TranscribeBackendType::Aws.label() {
+            self.aws_per_minute
+        } else if backend_label == TranscribeBackendType::Whisper.label() {
+            self.whisper_per_minute
+        } else {
+            0.0
+        }
+    }
+
+    /// 金額を通貨単位付きで表示
+    pub fn format(&self, cost: f64) -> String {
+        format!("{:.4} {}", cost, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CostConfig {
+        CostConfig {
+            enabled: true,
+            aws_per_minute: 0.024,
+            whisper_per_minute: 0.006,
+            currency: "USD".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cost_per_backend() {
+        let mut meter = CostMeter::new(&config(), 16000);
+        assert_eq!(meter.summary(), CostSummary::default());
+        assert_eq!(meter.describe(), "送信なし");
+
+        let aws = TranscribeBackendType::Aws.label();
+        let whisper = TranscribeBackendType::Whisper.label();
+        // AWS に 90秒（音声60秒 + ゼロサンプル30秒）、切り替え後の Whisper に 120秒
+        meter.add(aws, 16000 * 60);
+        meter.add(aws, 16000 * 30);
+        meter.add(whisper, 16000 * 120);
+
+        let breakdown = meter.breakdown();
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].0, aws);
+        assert!((breakdown[0].1.sent_secs - 90.0).abs() < 1e-9);
+        assert!((breakdown[0].1.cost - 0.036).abs() < 1e-9);
+        assert!((breakdown[1].1.cost - 0.012).abs() < 1e-9);
+
+        let summary = meter.summary();
+        assert!((summary.sent_secs - 210.0).abs() < 1e-9);
+        assert!((summary.cost - 0.048).abs() < 1e-9);
+        assert_eq!(
+            meter.describe(),
+            "Amazon Transcribe 90.0秒 = 0.0360 USD, OpenAI Whisper API 120.0秒 = 0.0120 USD"
+        );
+
+        // 単価が分からないバックエンド（テスト用など）は0として数える
+        meter.add("mock", 16000 * 60);
+        assert!((meter.summary().cost - 0.048).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_total() {
+        let summaries = [
+            CostSummary { sent_secs: 30.0, cost: 0.012 },
+            CostSummary { sent_secs: 90.0, cost: 0.036 },
+        ];
+        let total = CostSummary::total(&summaries);
+        assert!((total.sent_secs - 120.0).abs() < 1e-9);
+        assert!((total.cost - 0.048).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::config::DbConfig;
+use crate::types::TranscriptResult;
+use anyhow::{Context, Result, bail};
+use chrono::DateTime;
+use rusqlite::{Connection, params};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// スキーマのマイグレーション（`PRAGMA user_version` が適用済みの件数を表す）
+///
+/// 既存のマイグレーションは書き換えず、変更は末尾に追加する。
+const MIGRATIONS: &[&str] = &[
+    // 1: 確定結果のテーブル、時刻・チャンネルの索引、本文の全文検索
+    //    （trigram トークナイザで、分かち書きのない日本語も3文字以上の部分一致で検索できる）
+    "CREATE TABLE transcripts (
+        id INTEGER PRIMARY KEY,
+        channel INTEGER NOT NULL,
+        channel_name TEXT,
+        group_name TEXT,
+        timestamp TEXT NOT NULL,
+        unix_ms INTEGER,
+        timestamp_seconds REAL NOT NULL,
+        text TEXT NOT NULL,
+        sequence INTEGER NOT NULL,
+        confidence REAL,
+        detected_language TEXT,
+        promoted_from_partial INTEGER NOT NULL DEFAULT 0,
+        clip_path TEXT,
+        metadata TEXT
+    );
+    CREATE INDEX idx_transcripts_time ON transcripts (unix_ms);
+    CREATE INDEX idx_transcripts_channel_time ON transcripts (channel, unix_ms);
+    CREATE VIRTUAL TABLE transcripts_fts USING fts5(
+        text, content = 'transcripts', content_rowid = 'id', tokenize = 'trigram'
+    );
+    CREATE TRIGGER transcripts_ai AFTER INSERT ON transcripts BEGIN
+        INSERT INTO transcripts_fts (rowid, text) VALUES (new.id, new.text);
+    END;
+    CREATE TRIGGER transcripts_ad AFTER DELETE ON transcripts BEGIN
+        INSERT INTO transcripts_fts (transcripts_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    END;",
+    // 2: 発話の長さ（秒、取得できないバックエンドでは NULL）
+    "ALTER TABLE transcripts ADD COLUMN audio_duration_seconds REAL;",
+    // 3: 分断された確定結果を結合したものか（`[merge]`）
+    "ALTER TABLE transcripts ADD COLUMN merged INTEGER NOT NULL DEFAULT 0;",
+    // 4: 後処理前の原文と、後処理の各段の結果（JSON配列）。`text` は後処理済みの表示用テキスト
+    "ALTER TABLE transcripts ADD COLUMN raw_text TEXT;
+    ALTER TABLE transcripts ADD COLUMN postprocess TEXT;",
+];
+
+/// 1回のトランザクションでまとめて書き込む最大件数
+const MAX_BATCH: usize = 256;
+
+/// 他のプロセス（検索・集計用の読み取り）がロック中の場合に待つ時間
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// データベースを開き、WALモードにしてスキーマを最新にする
+///
+/// WAL と `synchronous = NORMAL` により、コミット済みの結果はプロセスが異常終了しても失われない
+/// （OS・電源の異常時は直前のコミットが失われる場合がある）。
+pub fn open(path: &Path) -> Result<Connection> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("データベースのディレクトリ作成に失敗: {:?}", parent))?;
+    }
+    let mut conn =
+        Connection::open(path).with_context(|| format!("データベースを開けませんでした: {:?}", path))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    migrate(&mut conn).with_context(|| format!("データベースのマイグレーションに失敗: {:?}", path))?;
+    Ok(conn)
+}
+
+/// 未適用のマイグレーションを1つのトランザクションで適用する
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        bail!(
+            "データベースのスキーマ（バージョン {}）がこのバージョンの対応範囲（{}）より新しいです",
+            version,
+            MIGRATIONS.len()
+        );
+    }
+    if version == MIGRATIONS.len() {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    for migration in &MIGRATIONS[version..] {
+        tx.execute_batch(migration)?;
+    }
+    tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+    tx.commit()?;
+    log::info!("データベースのスキーマを更新しました: バージョン {} -> {}", version, MIGRATIONS.len());
+    Ok(())
+}
+
+/// 確定結果をまとめて1つのトランザクションで書き込む
+fn insert_batch(conn: &mut Connection, results: &[TranscriptResult]) -> Result<()> {
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO transcripts (
+                channel, channel_name, group_name, timestamp, unix_ms, timestamp_seconds, text,
+                sequence, confidence, detected_language, promoted_from_partial, clip_path, metadata,
+                audio_duration_seconds, merged, raw_text, postprocess
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        )?;
+        for result in results {
+            let unix_ms = DateTime::parse_from_rfc3339(&result.timestamp)
+                .ok()
+                .map(|time| time.timestamp_millis());
+            let metadata = result.metadata.as_ref().map(serde_json::to_string).transpose()?;
+            let postprocess = (!result.postprocess.is_empty())
+                .then(|| serde_json::to_string(&result.postprocess))
+                .transpose()?;
+            stmt.execute(params![
+                result.channel as i64,
+                result.channel_name,
+                result.group,
+                result.timestamp,
+                unix_ms,
+                result.timestamp_seconds,
+                result.text,
+                result.sequence as i64,
+                result.confidence,
+                result.detected_language,
+                result.promoted_from_partial,
+                result.clip_path,
+                metadata,
+                result.audio_duration_seconds,
+                result.merged,
+                result.raw_text,
+                postprocess,
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// 確定結果を SQLite（`[db] sqlite_path`）にバックグラウンドで保存するキュー
+///
+/// `record` はキューに積むだけで、結果の処理ループをディスクI/Oで止めない。
+/// ワーカーはキューにたまっている分（最大 `MAX_BATCH` 件）を1つのトランザクションで書き込み、
+/// すぐにコミットする（負荷が高いほど1回のコミットにまとまる）。
+/// すべての `SqliteSink` がドロップされると、残りを書き終えてからワーカーが終了する。
+#[derive(Clone)]
+pub struct SqliteSink {
+    tx: mpsc::UnboundedSender<TranscriptResult>,
+}
+
+impl SqliteSink {
+    /// データベースを開いてマイグレーションし、書き込みワーカーを起動
+    ///
+    /// 戻り値の `JoinHandle` を待つとキューが空になるまで待機できる。
+    pub fn spawn(config: &DbConfig) -> Result<(Self, tokio::task::JoinHandle<()>)> {
+        let path = PathBuf::from(&config.sqlite_path);
+        let mut conn = open(&path)?;
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<TranscriptResult>();
+        let handle = tokio::task::spawn_blocking(move || {
+            let mut batch = Vec::with_capacity(MAX_BATCH);
+            while let Some(result) = rx.blocking_recv() {
+                batch.push(result);
+                while batch.len() < MAX_BATCH {
+                    match rx.try_recv() {
+                        Ok(result) => batch.push(result),
+                        Err(_) => break,
+                    }
+                }
+                if let Err(e) = insert_batch(&mut conn, &batch) {
+                    log::warn!("データベースへの書き込みに失敗（{}件）: {:?}: {:#}", batch.len(), path, e);
+                }
+                batch.clear();
+            }
+        });
+
+        Ok((Self { tx }, handle))
+    }
+
+    /// 確定結果をキューに追加
+    pub fn record(&self, result: &TranscriptResult) {
+        if self.tx.send(result.clone()).is_err() {
+            log::warn!("データベースの書き込みワーカーが停止しているため保存できません");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PostprocessStage, PostprocessStep};
+    use std::collections::HashMap;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn result(channel: usize, timestamp: &str, text: &str) -> TranscriptResult {
+        let mut result = TranscriptResult::new(channel, text.to_string(), false, None, SystemTime::now());
+        result.timestamp = timestamp.to_string();
+        result
+    }
+
+    #[test]
+    fn test_insert_and_query() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut conn = open(&temp_dir.path().join("transcripts.db"))?;
+
+        let mut with_metadata = result(1, "2025-01-04T12:00:05+09:00", "了解、ゲートに向かいます");
+        with_metadata.confidence = Some(0.9);
+        with_metadata.audio_duration_seconds = Some(1.5);
+        with_metadata.merged = true;
+        with_metadata.raw_text = Some("えー了解、ゲートに向かいます".to_string());
+        with_metadata.postprocess = vec![PostprocessStep {
+            stage: PostprocessStage::RemoveFillers,
+            text: "了解、ゲートに向かいます".to_string(),
+        }];
+        with_metadata.metadata = Some(HashMap::from([("site_id".to_string(), "tokyo".to_string())]));
+        insert_batch(
+            &mut conn,
+            &[
+                result(0, "2025-01-04T03:00:00+00:00", "現在地を教えてください"),
+                with_metadata,
+                result(0, "2025-01-04T03:01:00+00:00", "現在地は正面入口です"),
+            ],
+        )?;
+
+        // 時刻範囲とチャンネル（タイムゾーンが違っても UNIX 時刻で比較する）
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM transcripts WHERE channel = 0 AND unix_ms >= ?1",
+            [1_735_959_600_000i64],
+            |row| row.get(0),
+        )?;
+        assert_eq!(count, 2);
+        let (channel, metadata, duration, merged): (i64, Option<String>, Option<f64>, bool) = conn.query_row(
+            "SELECT channel, metadata, audio_duration_seconds, merged FROM transcripts
+             WHERE unix_ms > ?1 ORDER BY unix_ms LIMIT 1",
+            [1_735_959_600_000i64],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        assert_eq!(channel, 1);
+        assert_eq!(metadata.as_deref(), Some(r#"{"site_id":"tokyo"}"#));
+        assert_eq!(duration, Some(1.5));
+        assert!(merged);
+        let (raw_text, postprocess): (Option<String>, Option<String>) = conn.query_row(
+            "SELECT raw_text, postprocess FROM transcripts WHERE channel = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(raw_text.as_deref(), Some("えー了解、ゲートに向かいます"));
+        assert_eq!(
+            postprocess.as_deref(),
+            Some(r#"[{"stage":"remove_fillers","text":"了解、ゲートに向かいます"}]"#)
+        );
+
+        // 全文検索（日本語の部分一致）
+        let mut stmt = conn.prepare(
+            "SELECT t.text FROM transcripts_fts JOIN transcripts t ON t.id = transcripts_fts.rowid
+             WHERE transcripts_fts MATCH ?1 ORDER BY t.unix_ms",
+        )?;
+        let texts: Vec<String> = stmt
+            .query_map(["現在地"], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        assert_eq!(texts, vec!["現在地を教えてください", "現在地は正面入口です"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_migration_is_versioned() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("transcripts.db");
+
+        let conn = open(&path)?;
+        let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        assert_eq!(version, MIGRATIONS.len());
+        let journal_mode: String = conn.pragma_query_value(None, "journal_mode", |row| row.get(0))?;
+        assert_eq!(journal_mode, "wal");
+        drop(conn);
+
+        // 開き直しても再適用しない
+        open(&path)?;
+
+        // 新しいバージョンのスキーマは開かない
+        Connection::open(&path)?.pragma_update(None, "user_version", MIGRATIONS.len() + 1)?;
+        assert!(open(&path).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sink_writes_on_drop() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = DbConfig {
+            sqlite_path: temp_dir.path().join("db/transcripts.db").to_string_lossy().into_owned(),
+        };
+
+        let (sink, handle) = SqliteSink::spawn(&config)?;
+        for i in 0..10 {
+            sink.record(&result(i % 2, "2025-01-04T03:00:00+00:00", "テスト"));
+        }
+        drop(sink);
+        handle.await?;
+
+        let conn = Connection::open(&config.sqlite_path)?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM transcripts", [], |row| row.get(0))?;
+        assert_eq!(count, 10);
+        Ok(())
+    }
+}
@@ -0,0 +1,487 @@
+use crate::config::EncryptionConfig;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::Aes256Gcm;
+use anyhow::{Context, Result, bail};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// 暗号化した録音ファイルに付ける拡張子（`<元のファイル名>.enc`）
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+/// 暗号化の単位となる平文のチャンク長（バイト）
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// ファイル先頭の識別子（形式のバージョンを含む）
+const MAGIC: &[u8; 8] = b"DCRENC01";
+
+/// ファイルごとに乱数で決めるノンスの前半（後半4バイトはチャンク番号）
+const NONCE_PREFIX_LEN: usize = 8;
+
+/// ファイルヘッダの長さ（識別子・チャンク長・ノンスの前半）
+const HEADER_LEN: usize = MAGIC.len() + 4 + NONCE_PREFIX_LEN;
+
+/// AES-GCM の認証タグの長さ
+const TAG_LEN: usize = 16;
+
+/// AES-256 の鍵の長さ
+const KEY_LEN: usize = 32;
+
+/// チャンク長の範囲（先頭チャンクにWAVヘッダが収まり、復号時に巨大な確保をしない）
+const MIN_CHUNK_SIZE: usize = 64;
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// 録音ファイルの暗号鍵（AES-256）
+#[derive(Clone)]
+pub struct RecordingKey([u8; KEY_LEN]);
+
+impl fmt::Debug for RecordingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecordingKey(..)")
+    }
+}
+
+impl RecordingKey {
+    /// 16進数64文字（前後の空白は無視）から鍵を作る
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dcr_transcribe::encryption::RecordingKey;
+    /// assert!(RecordingKey::from_hex(&"ab".repeat(32)).is_ok());
+    /// assert!(RecordingKey::from_hex("abcd").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+        if hex.len() != KEY_LEN * 2 || !hex.is_ascii() {
+            bail!("暗号鍵は16進数{}文字で指定してください（{}文字）", KEY_LEN * 2, hex.len());
+        }
+        let mut key = [0u8; KEY_LEN];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .with_context(|| "暗号鍵に16進数以外の文字が含まれています")?;
+        }
+        Ok(Self(key))
+    }
+
+    /// `[output.encryption]` の指定（鍵ファイルまたは環境変数）から鍵を読み込む
+    pub fn load(config: &EncryptionConfig) -> Result<Self> {
+        match (&config.key_file, &config.key_env) {
+            (Some(path), None) => {
+                let hex = fs::read_to_string(path)
+                    .with_context(|| format!("暗号鍵ファイルの読み込みに失敗: {}", path))?;
+                Self::from_hex(&hex).with_context(|| format!("暗号鍵ファイルが不正です: {}", path))
+            }
+            (None, Some(name)) => {
+                let hex = std::env::var(name)
+                    .with_context(|| format!("暗号鍵の環境変数 {} が設定されていません", name))?;
+                Self::from_hex(&hex).with_context(|| format!("環境変数 {} の暗号鍵が不正です", name))
+            }
+            _ => bail!("[output.encryption] には key_file と key_env のどちらか一方を指定してください"),
+        }
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.0.into())
+    }
+}
+
+/// チャンク番号 `index` のノンス
+fn chunk_nonce(prefix: &[u8], index: u32) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..].copy_from_slice(&index.to_be_bytes());
+    nonce
+}
+
+/// チャンクの追加認証データ（ファイルヘッダ・チャンク番号・最終チャンクか）
+///
+/// 最終チャンクかを含めることで、末尾のチャンクを削った切り詰めを検出できる。
+fn chunk_aad(header: &[u8], index: u32, last: bool) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + 5);
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(&index.to_le_bytes());
+    aad.push(last as u8);
+    aad
+}
+
+/// 書き込んだデータをチャンク単位で AES-256-GCM で暗号化するラッパー
+///
+/// ファイルはヘッダ（識別子・チャンク長・ノンスの前半）に続けて、
+/// チャンクごとに「暗号文の長さ (u32 LE) + 暗号文（認証タグ付き）」を並べる。
+/// ノンスはファイルごとの乱数とチャンク番号から作り、追加認証データに最終チャンクかを含める。
+///
+/// 先頭チャンクは `finish` まで平文のまま保持し、`rewrite_head` で書き換えられる
+/// （WAVヘッダのサイズ欄を終了時に確定するため）。2チャンク目以降を書き始めた時点で
+/// 先頭チャンクの位置を確保し、`finish` で書き込む。`finish` を呼ばずに終了したファイルは
+/// 先頭チャンクと最終チャンクが欠けるため、復号しても不完全になる。
+pub struct EncryptedWriter<W: Write + Seek> {
+    inner: W,
+    cipher: Aes256Gcm,
+    header: [u8; HEADER_LEN],
+    chunk_size: usize,
+    /// 先頭チャンクの平文
+    head: Vec<u8>,
+    /// 先頭チャンクの位置を確保したか（2チャンク目以降を書き始めたか）
+    head_reserved: bool,
+    /// 書き込み中のチャンク（2チャンク目以降）の平文
+    current: Vec<u8>,
+    /// `current` のチャンク番号
+    index: u32,
+}
+
+impl<W: Write + Seek> EncryptedWriter<W> {
+    /// ファイルヘッダを書き込んで暗号化を開始する
+    pub fn new(mut inner: W, key: &RecordingKey, chunk_size: usize) -> io::Result<Self> {
+        assert!(
+            (MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size),
+            "chunk_size は {}～{} バイト",
+            MIN_CHUNK_SIZE,
+            MAX_CHUNK_SIZE
+        );
+
+        let mut header = [0u8; HEADER_LEN];
+        header[..MAGIC.len()].copy_from_slice(MAGIC);
+        header[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(chunk_size as u32).to_le_bytes());
+        OsRng.fill_bytes(&mut header[MAGIC.len() + 4..]);
+        inner.write_all(&header)?;
+
+        Ok(Self {
+            inner,
+            cipher: key.cipher(),
+            header,
+            chunk_size,
+            head: Vec::with_capacity(chunk_size),
+            head_reserved: false,
+            current: Vec::with_capacity(chunk_size),
+            index: 1,
+        })
+    }
+
+    /// 先頭チャンクの `offset` からを `bytes` で書き換える（書き込み済みの範囲のみ）
+    pub fn rewrite_head(&mut self, offset: usize, bytes: &[u8]) -> io::Result<()> {
+        let end = offset + bytes.len();
+        if end > self.head.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "先頭チャンクの書き込み済みの範囲外は書き換えられません",
+            ));
+        }
+        self.head[offset..end].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// 残りのチャンクを暗号化して書き込み、内側の書き込み先を返す
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.head_reserved {
+            let last = self.seal(self.index, true, &self.current)?;
+            Self::write_record(&mut self.inner, &last)?;
+            let head = self.seal(0, false, &self.head)?;
+            let end = self.inner.stream_position()?;
+            self.inner.seek(SeekFrom::Start(HEADER_LEN as u64))?;
+            Self::write_record(&mut self.inner, &head)?;
+            self.inner.seek(SeekFrom::Start(end))?;
+        } else {
+            let head = self.seal(0, true, &self.head)?;
+            Self::write_record(&mut self.inner, &head)?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn seal(&self, index: u32, last: bool, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+        let nonce = chunk_nonce(&self.header[MAGIC.len() + 4..], index);
+        let aad = chunk_aad(&self.header, index, last);
+        self.cipher
+            .encrypt(&nonce.into(), Payload { msg: plaintext, aad: &aad })
+            .map_err(|_| io::Error::other("チャンクの暗号化に失敗"))
+    }
+
+    fn write_record(inner: &mut W, ciphertext: &[u8]) -> io::Result<()> {
+        inner.write_all(&(ciphertext.len() as u32).to_le_bytes())?;
+        inner.write_all(ciphertext)
+    }
+}
+
+impl<W: Write + Seek> Write for EncryptedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+
+        if !self.head_reserved {
+            let n = (self.chunk_size - self.head.len()).min(rest.len());
+            self.head.extend_from_slice(&rest[..n]);
+            rest = &rest[n..];
+            if rest.is_empty() {
+                return Ok(buf.len());
+            }
+            // 先頭チャンクの位置を確保する（長さ欄が0のままなら未書き込み）
+            self.inner.write_all(&vec![0u8; 4 + self.chunk_size + TAG_LEN])?;
+            self.head_reserved = true;
+        }
+
+        while !rest.is_empty() {
+            // 満杯のチャンクは続きが来た時点で暗号化する（最後のチャンクは finish で最終チャンクとして書く）
+            if self.current.len() == self.chunk_size {
+                let sealed = self.seal(self.index, false, &self.current)?;
+                Self::write_record(&mut self.inner, &sealed)?;
+                self.current.clear();
+                self.index += 1;
+            }
+            let n = (self.chunk_size - self.current.len()).min(rest.len());
+            self.current.extend_from_slice(&rest[..n]);
+            rest = &rest[n..];
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 復号の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecryptSummary {
+    /// 復号した平文のバイト数
+    pub bytes: u64,
+    /// 最終チャンクまで揃っていたか（`false` なら異常終了などで途中までのファイル）
+    pub complete: bool,
+}
+
+/// `buf` を埋めるまで読む（途中でEOFになった場合は読めたバイト数を返す）
+fn read_full<R: Read>(input: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// `EncryptedWriter` で暗号化したデータを復号して `output` に書き込む
+///
+/// 鍵が違う・改ざんされている場合はエラー。途中で終わっているファイル（異常終了時など）は
+/// 読めたチャンクまでを書き込み、`complete: false` を返す。先頭チャンクが未書き込みの場合は
+/// その範囲を0で埋める。
+pub fn decrypt<R: Read, W: Write>(mut input: R, mut output: W, key: &RecordingKey) -> Result<DecryptSummary> {
+    let mut header = [0u8; HEADER_LEN];
+    if read_full(&mut input, &mut header)? < HEADER_LEN || &header[..MAGIC.len()] != MAGIC {
+        bail!("暗号化された録音ファイルではありません");
+    }
+    let chunk_size = u32::from_le_bytes(header[MAGIC.len()..MAGIC.len() + 4].try_into().unwrap()) as usize;
+    if !(MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+        bail!("暗号化ファイルのチャンク長が不正です: {}", chunk_size);
+    }
+    let nonce_prefix = &header[MAGIC.len() + 4..];
+    let cipher = key.cipher();
+
+    let mut summary = DecryptSummary { bytes: 0, complete: false };
+    let mut ciphertext = vec![0u8; chunk_size + TAG_LEN];
+    for index in 0u32.. {
+        let mut len = [0u8; 4];
+        if read_full(&mut input, &mut len)? < len.len() {
+            return Ok(summary);
+        }
+        let len = u32::from_le_bytes(len) as usize;
+
+        if len == 0 && index == 0 {
+            // finish 前に終了したファイルの、確保したままの先頭チャンク
+            if read_full(&mut input, &mut ciphertext)? < ciphertext.len() {
+                return Ok(summary);
+            }
+            log::warn!("先頭チャンクが書き込まれていません（{}バイトを0で埋めます）", chunk_size);
+            output.write_all(&vec![0u8; chunk_size])?;
+            summary.bytes += chunk_size as u64;
+            continue;
+        }
+        if len < TAG_LEN || len > chunk_size + TAG_LEN {
+            bail!("チャンク {} の長さが不正です: {}", index, len);
+        }
+        if read_full(&mut input, &mut ciphertext[..len])? < len {
+            return Ok(summary);
+        }
+        let record = &ciphertext[..len];
+
+        let nonce = chunk_nonce(nonce_prefix, index);
+        let open = |last: bool| {
+            let aad = chunk_aad(&header, index, last);
+            cipher.decrypt(&nonce.into(), Payload { msg: record, aad: &aad })
+        };
+        let (plaintext, last) = match open(false) {
+            Ok(plaintext) => (plaintext, false),
+            Err(_) => match open(true) {
+                Ok(plaintext) => (plaintext, true),
+                Err(_) => bail!("チャンク {} の復号に失敗しました（鍵が違うか、ファイルが改ざん・破損しています）", index),
+            },
+        };
+        output.write_all(&plaintext)?;
+        summary.bytes += plaintext.len() as u64;
+
+        if last {
+            // 先頭チャンクは最後に書き込むため、2チャンク以上あるファイルでは最終チャンクの後に
+            // 先頭チャンクの位置へ戻ることはない（順番どおりに並んでいる）
+            if read_full(&mut input, &mut [0u8; 1])? != 0 {
+                bail!("最終チャンクの後に余分なデータがあります");
+            }
+            summary.complete = true;
+            return Ok(summary);
+        }
+    }
+    unreachable!("チャンク番号が上限に達しました")
+}
+
+/// 暗号化した録音ファイル `path`（`*.enc`）を、拡張子 `.enc` を除いたパスに復号する
+///
+/// 出力先が既に存在する場合は上書きしない。復号に失敗した場合は書きかけの出力を削除する。
+pub fn decrypt_file(path: &Path, key: &RecordingKey) -> Result<(PathBuf, DecryptSummary)> {
+    let output_path = path
+        .to_str()
+        .and_then(|p| p.strip_suffix(&format!(".{}", ENCRYPTED_EXTENSION)))
+        .map(PathBuf::from)
+        .with_context(|| format!("拡張子が .{} ではありません: {:?}", ENCRYPTED_EXTENSION, path))?;
+    if output_path.exists() {
+        bail!("復号先のファイルが既に存在します: {:?}", output_path);
+    }
+
+    let input = fs::File::open(path).with_context(|| format!("ファイルを開けません: {:?}", path))?;
+    let output = fs::File::create(&output_path)
+        .with_context(|| format!("復号先のファイルを作成できません: {:?}", output_path))?;
+    let mut output = BufWriter::new(output);
+    let result = decrypt(BufReader::new(input), &mut output, key)
+        .and_then(|summary| output.flush().map(|_| summary).map_err(Into::into));
+
+    match result {
+        Ok(summary) => Ok((output_path, summary)),
+        Err(e) => {
+            drop(output);
+            let _ = fs::remove_file(&output_path);
+            Err(e.context(format!("復号に失敗: {:?}", path)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn key(byte: u8) -> RecordingKey {
+        RecordingKey([byte; KEY_LEN])
+    }
+
+    fn encrypt(data: &[u8], chunk_size: usize, write_len: usize) -> Vec<u8> {
+        let mut writer = EncryptedWriter::new(Cursor::new(Vec::new()), &key(1), chunk_size).unwrap();
+        for part in data.chunks(write_len) {
+            writer.write_all(part).unwrap();
+        }
+        writer.rewrite_head(0, b"HEAD").unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn expected(data: &[u8]) -> Vec<u8> {
+        let mut expected = data.to_vec();
+        expected[..4].copy_from_slice(b"HEAD");
+        expected
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+
+        // 複数チャンク（書き込み単位がチャンク境界をまたぐ場合を含む）
+        for write_len in [1, 37, 64, 1000] {
+            let encrypted = encrypt(&data, 64, write_len);
+            let mut output = Vec::new();
+            let summary = decrypt(Cursor::new(&encrypted), &mut output, &key(1)).unwrap();
+            assert_eq!(summary, DecryptSummary { bytes: 1000, complete: true });
+            assert_eq!(output, expected(&data));
+        }
+
+        // 1チャンクに収まる場合
+        let encrypted = encrypt(&data[..50], 64, 50);
+        let mut output = Vec::new();
+        assert!(decrypt(Cursor::new(&encrypted), &mut output, &key(1)).unwrap().complete);
+        assert_eq!(output, expected(&data[..50]));
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering_rejected() {
+        let data = vec![7u8; 300];
+        let encrypted = encrypt(&data, 64, 300);
+
+        assert!(decrypt(Cursor::new(&encrypted), Vec::new(), &key(2)).is_err());
+
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(Cursor::new(&tampered), Vec::new(), &key(1)).is_err());
+
+        assert!(decrypt(Cursor::new(b"RIFF....WAVE".to_vec()), Vec::new(), &key(1)).is_err());
+    }
+
+    #[test]
+    fn test_truncated_file_is_incomplete() {
+        let data = vec![7u8; 300];
+        let encrypted = encrypt(&data, 64, 300);
+
+        // 最終チャンクを削る
+        let record = 4 + 64 + TAG_LEN;
+        let truncated = &encrypted[..encrypted.len() - (4 + 300 - 4 * 64 + TAG_LEN)];
+        let mut output = Vec::new();
+        let summary = decrypt(Cursor::new(truncated), &mut output, &key(1)).unwrap();
+        assert_eq!(summary, DecryptSummary { bytes: 256, complete: false });
+        assert_eq!(truncated.len(), HEADER_LEN + 4 * record);
+
+        // finish せずに終了した（先頭チャンクが未書き込み）
+        let mut writer = EncryptedWriter::new(Cursor::new(Vec::new()), &key(1), 64).unwrap();
+        writer.write_all(&data).unwrap();
+        let unfinished = writer.inner.into_inner();
+        let mut output = Vec::new();
+        let summary = decrypt(Cursor::new(&unfinished), &mut output, &key(1)).unwrap();
+        assert!(!summary.complete);
+        assert_eq!(&output[..64], &[0u8; 64]);
+        assert_eq!(&output[64..], &data[64..256]);
+    }
+
+    #[test]
+    fn test_decrypt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("channel_0.wav.enc");
+        fs::write(&path, encrypt(&[1u8; 100], 64, 100)).unwrap();
+
+        let (output_path, summary) = decrypt_file(&path, &key(1)).unwrap();
+        assert_eq!(output_path, dir.path().join("channel_0.wav"));
+        assert!(summary.complete);
+        assert_eq!(fs::read(&output_path).unwrap(), expected(&[1u8; 100]));
+
+        // 既存のファイルは上書きしない
+        assert!(decrypt_file(&path, &key(1)).is_err());
+
+        // 失敗したら書きかけの出力を残さない
+        fs::remove_file(&output_path).unwrap();
+        assert!(decrypt_file(&path, &key(2)).is_err());
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn test_load_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.key");
+        fs::write(&path, format!("{}\n", "0f".repeat(KEY_LEN))).unwrap();
+
+        let config = EncryptionConfig {
+            key_file: Some(path.to_string_lossy().into_owned()),
+            key_env: None,
+        };
+        assert_eq!(RecordingKey::load(&config).unwrap().0, [0x0f; KEY_LEN]);
+
+        let config = EncryptionConfig { key_file: None, key_env: None };
+        assert!(RecordingKey::load(&config).is_err());
+        assert!(RecordingKey::from_hex(&"zz".repeat(KEY_LEN)).is_err());
+    }
+}
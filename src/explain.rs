@@ -0,0 +1,318 @@
+use crate::buffer::AudioBuffer;
+use crate::config::{Config, TranscribeBackendType};
+use crate::storage;
+use crate::summary::SummaryTarget;
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// 値をマスクするキー名（部分一致）
+const SECRET_KEYS: [&str; 4] = ["api_key", "secret", "password", "token"];
+
+/// 未設定なら機能自体が無効になるセクション
+const OPTIONAL_SECTIONS: [&str; 5] = ["whisper", "upload", "grpc", "audit", "db"];
+
+/// 存在の有無だけを表示する環境変数
+const AWS_ENV_VARS: [&str; 4] = [
+    "AWS_ACCESS_KEY_ID",
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_REGION",
+    "AWS_PROFILE",
+];
+
+/// 設定ファイルを読み込み、最終的な設定を人間可読なツリー形式で返す
+///
+/// 実際の起動と同じくパース・バリデーションを行うが、録音やデバイスのオープンは行わない。
+/// ファイルが存在しない場合はデフォルト設定を説明する。
+pub fn explain_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+    let (config, raw, source) = if path.exists() {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("設定ファイルの読み込みに失敗: {:?}", path))?;
+        let raw: toml::Table =
+            toml::from_str(&content).with_context(|| "設定ファイルのパースに失敗")?;
+        let config = Config::from_file(path)?;
+        (config, raw, path.display().to_string())
+    } else {
+        (
+            Config::default(),
+            toml::Table::new(),
+            format!("{} (見つからないためデフォルト設定)", path.display()),
+        )
+    };
+
+    let mut out = explain(&config, &raw, &source)?;
+    out.push('\n');
+    out.push_str(&describe_environment(&config));
+    Ok(out)
+}
+
+/// 最終的な設定 `config` をツリー形式で説明する
+///
+/// `raw` は設定ファイルに書かれていた内容で、ここに存在しない値を
+/// デフォルト由来として表示する。秘匿値はマスクする。
+pub fn explain(config: &Config, raw: &toml::Table, source: &str) -> Result<String> {
+    let resolved = toml::Table::try_from(config).with_context(|| "設定のシリアライズに失敗")?;
+
+    let mut out = String::new();
+    writeln!(out, "設定ファイル: {}", source).unwrap();
+    writeln!(out, "(*) はデフォルト値").unwrap();
+    writeln!(out).unwrap();
+
+    for (key, value) in &resolved {
+        write_node(&mut out, key, value, raw.get(key), 0);
+    }
+    for section in OPTIONAL_SECTIONS {
+        if !resolved.contains_key(section) {
+            writeln!(out, "{} = (未設定: 無効){}", section, default_marker(raw.get(section))).unwrap();
+        }
+    }
+
+    writeln!(out).unwrap();
+    out.push_str(&summarize(config));
+    Ok(out)
+}
+
+/// ツリーの1ノードを書き出す
+///
+/// `raw` は同じ位置にある設定ファイル側の値（なければデフォルト由来）。
+fn write_node(out: &mut String, key: &str, value: &toml::Value, raw: Option<&toml::Value>, depth: usize) {
+    let indent = "  ".repeat(depth);
+
+    match value {
+        toml::Value::Table(table) => {
+            writeln!(out, "{}{}{}", indent, key, default_marker(raw)).unwrap();
+            for (child_key, child) in table {
+                let child_raw = raw.and_then(|r| r.get(child_key));
+                write_node(out, child_key, child, child_raw, depth + 1);
+            }
+        }
+        toml::Value::Array(items) if items.iter().any(|v| v.is_table()) => {
+            writeln!(out, "{}{}{}", indent, key, default_marker(raw)).unwrap();
+            for (index, item) in items.iter().enumerate() {
+                let item_raw = raw.and_then(|r| r.get(index));
+                write_node(out, &format!("[{}]", index), item, item_raw, depth + 1);
+            }
+        }
+        leaf => {
+            let shown = if is_secret(key) {
+                mask(leaf)
+            } else {
+                leaf.to_string()
+            };
+            writeln!(out, "{}{} = {}{}", indent, key, shown, default_marker(raw)).unwrap();
+        }
+    }
+}
+
+fn default_marker(raw: Option<&toml::Value>) -> &'static str {
+    if raw.is_some() { "" } else { " (*)" }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|s| key.contains(s))
+}
+
+fn mask(value: &toml::Value) -> String {
+    match value.as_str() {
+        Some("") => "\"\" (空)".to_string(),
+        _ => "******** (マスク)".to_string(),
+    }
+}
+
+/// 有効チャンネル数や推定リソース使用量のサマリ
+fn summarize(config: &Config) -> String {
+    let enabled: Vec<_> = config.channels.iter().filter(|c| c.enabled).collect();
+    let mut out = String::new();
+
+    writeln!(out, "=== サマリ ===").unwrap();
+    writeln!(out, "有効チャンネル: {} / {}", enabled.len(), config.channels.len()).unwrap();
+    for channel in &enabled {
+        let group = config
+            .group_of(channel.id)
+            .map(|g| format!(", グループ {}", g.name))
+            .unwrap_or_default();
+        writeln!(out, "  - {}: {}{}", channel.id, channel.name, group).unwrap();
+    }
+
+    match config.transcribe.backend {
+        TranscribeBackendType::Aws => writeln!(
+            out,
+            "バックエンド: Amazon Transcribe ({}, {})",
+            config.transcribe.region, config.transcribe.language_code
+        ),
+        TranscribeBackendType::Whisper => match &config.whisper {
+            Some(whisper) => writeln!(out, "バックエンド: Whisper API ({})", whisper.model),
+            None => writeln!(out, "バックエンド: Whisper API ([whisper] 未設定のため起動時にエラー)"),
+        },
+    }
+    .unwrap();
+    writeln!(out, "録音出力先: {}", config.output.wav_output_dir).unwrap();
+    if let Some(upload) = &config.upload {
+        writeln!(out, "アップロード先: {}/{}", upload.bucket, upload.prefix).unwrap();
+    }
+    if let Some(grpc) = &config.grpc {
+        writeln!(out, "gRPC 配信: {}", grpc.bind).unwrap();
+    }
+    if let Some(audit) = &config.audit {
+        writeln!(out, "監査ログ: {}", audit.file).unwrap();
+    }
+    if let Some(db) = &config.db {
+        writeln!(out, "データベース: {}", db.sqlite_path).unwrap();
+    }
+    if config.summary.enabled {
+        let targets = SummaryTarget::from_config(config).len();
+        writeln!(
+            out,
+            "要約: {} ({}件の単位, 時間間隔分だけで 最大 約{}回/時の API 呼び出し)",
+            config.summary.model,
+            targets,
+            config.summary.requests_per_hour(targets)
+        )
+        .unwrap();
+    }
+    if config.merge.enabled {
+        writeln!(
+            out,
+            "確定結果の結合: 間隔 {}ms 以内, 最大 {}秒 (出力が最大 {}ms 遅れる)",
+            config.merge.max_gap_ms, config.merge.max_merged_secs, config.merge.hold_ms
+        )
+        .unwrap();
+    }
+
+    let buffer_bytes =
+        AudioBuffer::new(&config.buffer, config.audio.sample_rate).capacity_samples() as u64 * 2;
+    let recording = storage::estimate_recording_size(
+        enabled.len(),
+        config.audio.sample_rate,
+        16,
+        config.output.expected_recording_hours,
+    );
+    writeln!(out, "推定リソース使用量:").unwrap();
+    writeln!(out, "  Transcribe 同時ストリーム: 最大 {}", enabled.len()).unwrap();
+    writeln!(
+        out,
+        "  リトライ用バッファ: 最大 {} ({}/チャンネル)",
+        storage::format_bytes(buffer_bytes * enabled.len() as u64),
+        storage::format_bytes(buffer_bytes)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "  録音容量: {}/時間, {:.1}時間で {}",
+        storage::format_bytes(recording.bytes_per_hour),
+        config.output.expected_recording_hours,
+        storage::format_bytes(recording.total_bytes)
+    )
+    .unwrap();
+
+    out
+}
+
+/// 設定に影響する環境変数の有無（値は表示しない）
+fn describe_environment(config: &Config) -> String {
+    let mut out = String::new();
+    if config.transcribe.backend != TranscribeBackendType::Aws && config.upload.is_none() {
+        return out;
+    }
+
+    writeln!(out, "=== 環境変数 ===").unwrap();
+    for name in AWS_ENV_VARS {
+        let state = if std::env::var_os(name).is_some() {
+            "設定済み"
+        } else {
+            "未設定"
+        };
+        writeln!(out, "{}: {}", name, state).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explain_str(content: &str) -> String {
+        let raw: toml::Table = toml::from_str(content).unwrap();
+        let config: Config = toml::from_str(content).unwrap();
+        explain(&config, &raw, "test.toml").unwrap()
+    }
+
+    #[test]
+    fn test_marks_default_values() {
+        let out = explain_str(
+            r#"
+[audio]
+sample_rate = 48000
+
+[[channels]]
+id = 0
+name = "無線機1"
+"#,
+        );
+
+        assert!(out.contains("audio\n"));
+        assert!(out.contains("  sample_rate = 48000\n"));
+        assert!(out.contains("  device_id = \"default\" (*)\n"));
+        assert!(out.contains("vad (*)\n"));
+        // 配列の要素内でも省略された値はデフォルト扱い
+        assert!(out.contains("    name = \"無線機1\"\n"));
+        assert!(out.contains("    enabled = true (*)\n"));
+        assert!(out.contains("grpc = (未設定: 無効) (*)\n"));
+        assert!(out.contains("audit = (未設定: 無効) (*)\n"));
+        assert!(out.contains("db = (未設定: 無効) (*)\n"));
+    }
+
+    #[test]
+    fn test_masks_secrets() {
+        let out = explain_str(
+            r#"
+[transcribe]
+backend = "whisper"
+
+[whisper]
+api_key = "sk-very-secret"
+"#,
+        );
+
+        assert!(!out.contains("sk-very-secret"));
+        assert!(out.contains("  api_key = ******** (マスク)\n"));
+        assert!(out.contains("  model = \"whisper-1\" (*)\n"));
+        assert!(out.contains("バックエンド: Whisper API (whisper-1)"));
+    }
+
+    #[test]
+    fn test_summary() {
+        let out = explain_str(
+            r#"
+[buffer]
+capacity_seconds = 10
+
+[[channels]]
+id = 0
+name = "A"
+
+[[channels]]
+id = 1
+name = "B"
+enabled = false
+"#,
+        );
+
+        assert!(out.contains("有効チャンネル: 1 / 2\n"));
+        assert!(out.contains("  - 0: A\n"));
+        assert!(!out.contains("  - 1: B"));
+        // 10秒 × 16kHz × 2バイト
+        assert!(out.contains("リトライ用バッファ: 最大 320.00 KB (320.00 KB/チャンネル)"));
+        assert!(out.contains("録音容量: 115.20 MB/時間"));
+    }
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let out = explain_file("/nonexistent/config.toml").unwrap();
+        assert!(out.contains("見つからないためデフォルト設定"));
+        assert!(out.contains("有効チャンネル: 2 / 2"));
+    }
+}
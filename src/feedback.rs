@@ -0,0 +1,116 @@
+use crate::tui_state::TranscriptEntry;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// TUIで誤認識のフラグを立てた確定結果（フィードバックファイルの1行）
+///
+/// 後でカスタムボキャブラリやプロンプトを見直す材料にするため、
+/// 表示していたテキストと、聞き直すための発話区間のクリップを記録する。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeedbackRecord {
+    pub channel: usize,
+    pub channel_name: String,
+    /// 発話の時刻（ISO 8601形式）
+    pub time: String,
+    /// 録音開始からの経過秒数
+    pub seconds: f64,
+    /// TUIに表示していたテキスト（後処理済み）
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// 発話区間のクリップ（`[output] save_clips` が無効なら `None`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clip_path: Option<String>,
+}
+
+impl FeedbackRecord {
+    pub fn new(channel: usize, channel_name: String, entry: &TranscriptEntry) -> Self {
+        Self {
+            channel,
+            channel_name,
+            time: entry.time.clone(),
+            seconds: entry.seconds,
+            text: entry.text.clone(),
+            confidence: entry.confidence,
+            clip_path: entry.clip_path.clone(),
+        }
+    }
+}
+
+/// フラグを立てた確定結果をフィードバックファイル（JSONL）に追記する
+#[derive(Debug, Clone)]
+pub struct FeedbackWriter {
+    path: PathBuf,
+}
+
+impl FeedbackWriter {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 1件追記する（ファイルと親ディレクトリがなければ作る）
+    pub fn append(&self, record: &FeedbackRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("フィードバックファイルの保存先の作成に失敗: {:?}", parent))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("フィードバックファイルを開けません: {:?}", self.path))?;
+        let line = serde_json::to_string(record)?;
+        writeln!(file, "{}", line)
+            .with_context(|| format!("フィードバックファイルへの書き込みに失敗: {:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_records() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let writer = FeedbackWriter::new(temp_dir.path().join("sub").join("feedback.jsonl"));
+        let entry = TranscriptEntry {
+            text: "こちら本部".to_string(),
+            time: "2024-01-01T12:00:00+09:00".to_string(),
+            seconds: 12.5,
+            is_partial: false,
+            stability: None,
+            confidence: Some(0.5),
+            clip_path: Some("clips/channel_0_0001.wav".to_string()),
+            flagged: true,
+        };
+        writer.append(&FeedbackRecord::new(0, "本部".to_string(), &entry))?;
+        writer.append(&FeedbackRecord::new(
+            1,
+            "警備".to_string(),
+            &TranscriptEntry {
+                clip_path: None,
+                confidence: None,
+                ..entry
+            },
+        ))?;
+
+        let content = fs::read_to_string(writer.path())?;
+        let records: Vec<FeedbackRecord> = content
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].clip_path.as_deref(), Some("clips/channel_0_0001.wav"));
+        assert_eq!(records[0].seconds, 12.5);
+        assert_eq!(records[1].channel_name, "警備");
+        assert!(!content.lines().nth(1).unwrap().contains("clip_path"));
+        Ok(())
+    }
+}
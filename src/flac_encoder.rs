@@ -1,9 +1,58 @@
 use crate::types::SampleI16;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use flacenc::bitsink::ByteSink;
-use flacenc::component::BitRepr;
-use flacenc::error::Verify;
-use flacenc::source::MemSource;
+use flacenc::component::{BitRepr, Stream, StreamInfo};
+use flacenc::error::{Verified, Verify};
+use flacenc::source::{Fill, FrameBuf, MemSource};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// エンコードがこの回数続けて失敗したら、エンコーダ設定を作り直して回復を試みる
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// FLACエンコードの成否の集計
+///
+/// チャンネルごとに1つ作り、再接続で作り直されるエンコーダ間で共有する。
+/// 失敗率が高い状態が続く場合は、入力フォーマットやサンプルレートの設定を疑う。
+#[derive(Debug, Default)]
+pub struct FlacEncodeStats {
+    encoded_chunks: AtomicU64,
+    failed_chunks: AtomicU64,
+    skipped_samples: AtomicU64,
+    rebuilds: AtomicU64,
+}
+
+impl FlacEncodeStats {
+    /// エンコードに成功したチャンク数
+    pub fn encoded_chunks(&self) -> u64 {
+        self.encoded_chunks.load(Ordering::Relaxed)
+    }
+
+    /// エンコードに失敗して送信をスキップしたチャンク数
+    pub fn failed_chunks(&self) -> u64 {
+        self.failed_chunks.load(Ordering::Relaxed)
+    }
+
+    /// スキップしたサンプル数の合計
+    pub fn skipped_samples(&self) -> u64 {
+        self.skipped_samples.load(Ordering::Relaxed)
+    }
+
+    /// 連続失敗によりエンコーダを作り直した回数
+    pub fn rebuilds(&self) -> u64 {
+        self.rebuilds.load(Ordering::Relaxed)
+    }
+
+    /// エンコード失敗率（0.0～1.0、まだエンコードしていなければ 0.0）
+    pub fn failure_rate(&self) -> f64 {
+        let failed = self.failed_chunks();
+        let total = self.encoded_chunks() + failed;
+        if total == 0 {
+            0.0
+        } else {
+            failed as f64 / total as f64
+        }
+    }
+}
 
 /// FLAC エンコーダー
 ///
@@ -24,9 +73,28 @@ use flacenc::source::MemSource;
 /// let pcm_samples = vec![0i16; 16000];
 /// let flac_data = encoder.encode(&pcm_samples).unwrap();
 /// ```
+///
+/// 接続の間ずっと1つのFLACストリームを送る場合は、`encode` の代わりに
+/// `encode_chunk` と `finish` を使う。
 pub struct FlacEncoder {
     sample_rate: u32,
     compression_level: u32,
+    /// 検証済みのエンコーダ設定（初回のエンコード時に作成し、作り直すまで使い回す）
+    verified_config: Option<Verified<flacenc::config::Encoder>>,
+    /// 連続したエンコード失敗の回数
+    consecutive_failures: u32,
+    /// `encode_chunk` で出力中のストリーム（`finish` で終わる）
+    stream: Option<Box<StreamState>>,
+}
+
+/// `encode_chunk` で出力中のFLACストリームの状態
+struct StreamState {
+    stream_info: StreamInfo,
+    frame_buf: FrameBuf,
+    /// まだフレームにしていないサンプル（1ブロックに満たない端数）
+    pending: Vec<i32>,
+    /// 次に出力するフレームの番号
+    frame_number: usize,
 }
 
 impl FlacEncoder {
@@ -50,6 +118,9 @@ impl FlacEncoder {
         Self {
             sample_rate,
             compression_level: compression_level.min(8),
+            verified_config: None,
+            consecutive_failures: 0,
+            stream: None,
         }
     }
 
@@ -65,7 +136,7 @@ impl FlacEncoder {
     ///
     /// # Errors
     ///
-    /// エンコードに失敗した場合にエラーを返す
+    /// 設定の検証やエンコードに失敗した場合に、サンプル数・サンプルレート・圧縮レベルを含むエラーを返す
     ///
     /// # Examples
     ///
@@ -81,6 +152,116 @@ impl FlacEncoder {
             return Ok(Vec::new());
         }
 
+        self.encode_samples(samples).with_context(|| {
+            format!(
+                "FLACエンコード失敗 (サンプル数 {}, サンプルレート {}Hz, 圧縮レベル {})",
+                samples.len(),
+                self.sample_rate,
+                self.compression_level
+            )
+        })
+    }
+
+    /// 連続したPCM音声データを、1つのFLACストリームの続きとしてエンコード
+    ///
+    /// ストリームの最初の呼び出しでは `fLaC` マーカーとSTREAMINFOを先頭に付ける。
+    /// 以降は1ブロック（エンコーダ設定のブロックサイズ）分のサンプルがたまるごとにフレームを出力し、
+    /// 端数は次の呼び出しに持ち越す。そのため返すデータが空のこともある。
+    /// 持ち越したサンプルは `finish` で最後のフレームとして出力する。
+    ///
+    /// STREAMINFOの総サンプル数とMD5は書かない（不明扱い）。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dcr_transcribe::flac_encoder::FlacEncoder;
+    /// let mut encoder = FlacEncoder::new(16000, 5);
+    /// let mut flac_data = Vec::new();
+    /// for _ in 0..5 {
+    ///     flac_data.extend(encoder.encode_chunk(&[0i16; 3200]).unwrap());
+    /// }
+    /// flac_data.extend(encoder.finish().unwrap());
+    /// assert!(flac_data.starts_with(b"fLaC"));
+    /// ```
+    pub fn encode_chunk(&mut self, samples: &[SampleI16]) -> Result<Vec<u8>> {
+        self.encode_stream(samples, false).with_context(|| {
+            format!(
+                "FLACストリームのエンコード失敗 (サンプル数 {}, サンプルレート {}Hz, 圧縮レベル {})",
+                samples.len(),
+                self.sample_rate,
+                self.compression_level
+            )
+        })
+    }
+
+    /// `encode_chunk` で出力中のストリームを終え、持ち越していたサンプルを最後のフレームとして返す
+    ///
+    /// ストリームを始めていなければ空を返す。次の `encode_chunk` からは新しいストリームになる。
+    pub fn finish(&mut self) -> Result<Vec<u8>> {
+        if self.stream.is_none() {
+            return Ok(Vec::new());
+        }
+        let result = self
+            .encode_stream(&[], true)
+            .with_context(|| format!("FLACストリームの終了に失敗 (サンプルレート {}Hz)", self.sample_rate));
+        self.stream = None;
+        result
+    }
+
+    /// PCM音声データをエンコードし、失敗した場合はそのチャンクをスキップする
+    ///
+    /// 失敗時は詳細をログに出して `None` を返し、`stats` に集計する。
+    /// `MAX_CONSECUTIVE_FAILURES` 回続けて失敗した場合はエンコーダ設定を作り直す。
+    ///
+    /// Amazon Transcribe のストリームはメディア形式を途中で変えられないため、
+    /// 失敗したチャンクをPCMで送り直すことはしない。
+    pub fn encode_or_skip(
+        &mut self,
+        channel_id: usize,
+        samples: &[SampleI16],
+        stats: &FlacEncodeStats,
+    ) -> Option<Vec<u8>> {
+        match self.encode(samples) {
+            Ok(flac_data) => {
+                self.consecutive_failures = 0;
+                stats.encoded_chunks.fetch_add(1, Ordering::Relaxed);
+                Some(flac_data)
+            }
+            Err(e) => {
+                self.consecutive_failures += 1;
+                stats.failed_chunks.fetch_add(1, Ordering::Relaxed);
+                stats.skipped_samples.fetch_add(samples.len() as u64, Ordering::Relaxed);
+                log::error!(
+                    "チャンネル {}: {:#} - このチャンク ({:.2}秒) をスキップ (連続{}回目, 累計失敗率 {:.1}%)",
+                    channel_id,
+                    e,
+                    samples.len() as f64 / self.sample_rate.max(1) as f64,
+                    self.consecutive_failures,
+                    stats.failure_rate() * 100.0
+                );
+
+                if self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                    log::warn!(
+                        "チャンネル {}: FLACエンコードが{}回連続で失敗したため、エンコーダを作り直します",
+                        channel_id,
+                        self.consecutive_failures
+                    );
+                    self.rebuild();
+                    stats.rebuilds.fetch_add(1, Ordering::Relaxed);
+                }
+                None
+            }
+        }
+    }
+
+    /// 検証済みの設定を破棄し、次回のエンコードで作り直す
+    pub fn rebuild(&mut self) {
+        self.verified_config = None;
+        self.consecutive_failures = 0;
+    }
+
+    fn encode_samples(&mut self, samples: &[SampleI16]) -> Result<Vec<u8>> {
+
         // i16からi32に変換（flacencの要求）
         let samples_i32: Vec<i32> = samples.iter().map(|&s| s as i32).collect();
 
@@ -92,17 +273,18 @@ impl FlacEncoder {
             self.sample_rate as usize,
         );
 
-        // エンコード設定
-        let config = flacenc::config::Encoder::default();
-
-        // 設定を検証
-        let verified_config = config
-            .into_verified()
-            .map_err(|e| anyhow::anyhow!("FLAC設定の検証に失敗: {:?}", e))?;
+        // エンコード設定（検証済みのものがなければ作成して検証）
+        let verified_config = match self.verified_config.take() {
+            Some(verified_config) => verified_config,
+            None => encoder_config(self.compression_level)
+                .into_verified()
+                .map_err(|e| anyhow::anyhow!("FLAC設定の検証に失敗: {:?}", e))?,
+        };
+        let verified_config = self.verified_config.insert(verified_config);
 
         // エンコード実行
         let flac_stream = flacenc::encode_with_fixed_block_size(
-            &verified_config,
+            verified_config,
             source,
             verified_config.block_size,
         )
@@ -118,6 +300,69 @@ impl FlacEncoder {
         Ok(flac_bytes)
     }
 
+    fn encode_stream(&mut self, samples: &[SampleI16], flush: bool) -> Result<Vec<u8>> {
+        let verified_config = match self.verified_config.take() {
+            Some(verified_config) => verified_config,
+            None => encoder_config(self.compression_level)
+                .into_verified()
+                .map_err(|e| anyhow::anyhow!("FLAC設定の検証に失敗: {:?}", e))?,
+        };
+        let verified_config = self.verified_config.insert(verified_config);
+        let block_size = verified_config.block_size;
+
+        let mut sink = ByteSink::new();
+        if self.stream.is_none() {
+            let mut stream_info = StreamInfo::new(self.sample_rate as usize, 1, 16)
+                .map_err(|e| anyhow::anyhow!("STREAMINFOの作成に失敗: {:?}", e))?;
+            stream_info
+                .set_block_sizes(block_size, block_size)
+                .map_err(|e| anyhow::anyhow!("STREAMINFOの作成に失敗: {:?}", e))?;
+            // フレームサイズは出力前には分からないので 0（不明）
+            stream_info
+                .set_frame_sizes(0, 0)
+                .map_err(|e| anyhow::anyhow!("STREAMINFOの作成に失敗: {:?}", e))?;
+            Stream::with_stream_info(stream_info.clone())
+                .write(&mut sink)
+                .map_err(|e| anyhow::anyhow!("FLACヘッダの書き込みに失敗: {:?}", e))?;
+            let frame_buf = FrameBuf::with_size(1, block_size)
+                .map_err(|e| anyhow::anyhow!("フレームバッファの作成に失敗: {:?}", e))?;
+            self.stream = Some(Box::new(StreamState {
+                stream_info,
+                frame_buf,
+                pending: Vec::new(),
+                frame_number: 0,
+            }));
+        }
+        let Some(state) = self.stream.as_mut() else {
+            unreachable!("ストリームは直前に作成済み");
+        };
+
+        state.pending.extend(samples.iter().map(|&s| s as i32));
+        let mut offset = 0;
+        while state.pending.len() - offset >= block_size || (flush && offset < state.pending.len()) {
+            let end = (offset + block_size).min(state.pending.len());
+            state
+                .frame_buf
+                .fill_interleaved(&state.pending[offset..end])
+                .map_err(|e| anyhow::anyhow!("フレームバッファへの書き込みに失敗: {:?}", e))?;
+            let frame = flacenc::encode_fixed_size_frame(
+                verified_config,
+                &state.frame_buf,
+                state.frame_number,
+                &state.stream_info,
+            )
+            .map_err(|e| anyhow::anyhow!("FLACフレームのエンコードに失敗: {:?}", e))?;
+            frame
+                .write(&mut sink)
+                .map_err(|e| anyhow::anyhow!("FLACフレームの書き込みに失敗: {:?}", e))?;
+            state.frame_number += 1;
+            offset = end;
+        }
+        state.pending.drain(..offset);
+
+        Ok(sink.into_inner())
+    }
+
     /// 圧縮レベルを設定
     ///
     /// # Arguments
@@ -125,6 +370,7 @@ impl FlacEncoder {
     /// * `level` - 圧縮レベル (0-8)
     pub fn set_compression_level(&mut self, level: u32) {
         self.compression_level = level.min(8);
+        self.verified_config = None;
     }
 
     /// 現在の圧縮レベルを取得
@@ -138,6 +384,31 @@ impl FlacEncoder {
     }
 }
 
+/// 圧縮レベル（0-8）に応じたエンコーダ設定
+///
+/// libFLAC のプリセットにならい、低いレベルでは線形予測（LPC）を使わず固定予測だけで済ませ、
+/// 高いレベルほど予測次数を上げる。8 では固定予測の次数を実際の符号量で選ぶ。
+fn encoder_config(compression_level: u32) -> flacenc::config::Encoder {
+    let mut config = flacenc::config::Encoder::default();
+    let coding = &mut config.subframe_coding;
+    match compression_level {
+        0 => {
+            coding.use_lpc = false;
+            coding.fixed.max_order = 2;
+        }
+        1 | 2 => coding.use_lpc = false,
+        3 => coding.qlpc.lpc_order = 6,
+        4 => coding.qlpc.lpc_order = 8,
+        5 | 6 => coding.qlpc.lpc_order = 10,
+        7 => coding.qlpc.lpc_order = 12,
+        _ => {
+            coding.qlpc.lpc_order = 12;
+            coding.fixed.order_sel = flacenc::config::OrderSel::BitCount;
+        }
+    }
+    config
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,6 +468,35 @@ mod tests {
         assert_eq!(encoder.compression_level(), 5);
     }
 
+    #[test]
+    fn test_encode_error_has_context() {
+        // flacenc が扱えないサンプルレート
+        let mut encoder = FlacEncoder::new(2_000_000, 5);
+        let message = format!("{:#}", encoder.encode(&[1i16; 100]).unwrap_err());
+        assert!(message.contains("サンプル数 100"), "{}", message);
+        assert!(message.contains("サンプルレート 2000000Hz"), "{}", message);
+        assert!(message.contains("圧縮レベル 5"), "{}", message);
+    }
+
+    #[test]
+    fn test_encode_or_skip_counts_failures_and_rebuilds() {
+        let stats = FlacEncodeStats::default();
+
+        let mut encoder = FlacEncoder::new(16000, 5);
+        assert!(encoder.encode_or_skip(0, &[1i16; 160], &stats).is_some());
+
+        let mut failing = FlacEncoder::new(2_000_000, 5);
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            assert!(failing.encode_or_skip(0, &[1i16; 160], &stats).is_none());
+        }
+
+        assert_eq!(stats.encoded_chunks(), 1);
+        assert_eq!(stats.failed_chunks(), MAX_CONSECUTIVE_FAILURES as u64);
+        assert_eq!(stats.skipped_samples(), 160 * MAX_CONSECUTIVE_FAILURES as u64);
+        assert_eq!(stats.rebuilds(), 1);
+        assert_eq!(stats.failure_rate(), 0.75);
+    }
+
     #[test]
     fn test_compression_level_bounds() {
         let encoder = FlacEncoder::new(16000, 10);
@@ -380,7 +680,7 @@ mod tests {
     fn test_roundtrip_complex_waveform() {
         // 複雑な波形を生成（複数の周波数を合成）
         let sample_rate = 16000;
-        let frequencies = vec![220.0, 440.0, 880.0]; // A3, A4, A5
+        let frequencies = [220.0, 440.0, 880.0]; // A3, A4, A5
 
         let original_samples: Vec<i16> = (0..sample_rate)
             .map(|i| {
@@ -437,9 +737,7 @@ mod tests {
             .map(|i| {
                 // 疑似ランダムな値を生成（再現性のため、シンプルな式を使用）
                 // オーバーフロー対策: wrapping_mul と wrapping_add を使用
-                let x =
-                    ((i as u32).wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff) as i16;
-                x
+                ((i as u32).wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff) as i16
             })
             .collect();
 
@@ -481,6 +779,56 @@ mod tests {
         println!("✓ ランダムデータのラウンドトリップテスト成功（可逆圧縮を確認）");
     }
 
+    #[test]
+    fn test_stream_roundtrip() {
+        // 0.2秒ごとのチャンク（ブロックサイズの倍数ではない）を1つのストリームにする
+        let original_samples: Vec<i16> = (0..16000 * 3 + 123)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                ((t * 440.0 * 2.0 * std::f32::consts::PI).sin() * 10000.0) as i16
+            })
+            .collect();
+
+        let mut encoder = FlacEncoder::new(16000, 5);
+        let mut flac_data = Vec::new();
+        for chunk in original_samples.chunks(3200) {
+            flac_data.extend(encoder.encode_chunk(chunk).unwrap());
+        }
+        flac_data.extend(encoder.finish().unwrap());
+
+        // ヘッダはストリームの先頭に1回だけ
+        assert!(flac_data.starts_with(b"fLaC"));
+        assert_eq!(flac_data.windows(4).filter(|window| window == b"fLaC").count(), 1);
+
+        let decoded_samples = decode_flac(&flac_data).unwrap();
+        assert_eq!(original_samples, decoded_samples);
+
+        // 一括エンコードより小さい（チャンクごとのヘッダがない）
+        let chunked_size: usize = original_samples
+            .chunks(3200)
+            .map(|chunk| FlacEncoder::new(16000, 5).encode(chunk).unwrap().len())
+            .sum();
+        assert!(flac_data.len() < chunked_size);
+    }
+
+    #[test]
+    fn test_stream_restarts_after_finish() {
+        let mut encoder = FlacEncoder::new(16000, 5);
+        assert!(encoder.finish().unwrap().is_empty());
+
+        // ブロックに満たないサンプルは finish まで持ち越す
+        let header = encoder.encode_chunk(&[100i16; 1000]).unwrap();
+        assert!(header.starts_with(b"fLaC"));
+        let mut first = header;
+        first.extend(encoder.finish().unwrap());
+        assert_eq!(decode_flac(&first).unwrap(), vec![100i16; 1000]);
+
+        let mut second = encoder.encode_chunk(&[-5i16; 10]).unwrap();
+        assert!(second.starts_with(b"fLaC"));
+        second.extend(encoder.finish().unwrap());
+        assert_eq!(decode_flac(&second).unwrap(), vec![-5i16; 10]);
+    }
+
     #[test]
     fn test_roundtrip_different_compression_levels() {
         // サイン波データを生成
@@ -492,7 +840,7 @@ mod tests {
             .collect();
 
         // 異なる圧縮レベルでテスト
-        for compression_level in [0, 5, 8] {
+        for compression_level in 0..=8 {
             let mut encoder = FlacEncoder::new(16000, compression_level);
             let flac_data = encoder.encode(&original_samples).unwrap();
 
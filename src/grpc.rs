@@ -0,0 +1,350 @@
+use crate::broadcast::{PartialDelta, PartialDeltaEncoder, TranscriptBroadcaster};
+use crate::types::{Stability, TranscriptResult};
+use anyhow::{Context, Result};
+use futures_util::Stream;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tonic::{Request, Response, Status};
+
+/// proto/transcript.proto に対応するメッセージとサービス
+pub mod proto {
+    /// 購読リクエスト
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeRequest {
+        /// 受信するチャンネルID（空なら全チャンネル）
+        #[prost(uint32, repeated, tag = "1")]
+        pub channel_ids: Vec<u32>,
+        /// 部分結果を前回の部分結果からの差分（`base_len` + `appended`）で受け取る
+        #[prost(bool, tag = "2")]
+        pub incremental_partials: bool,
+        /// `text` を後処理前の原文で受け取る（既定は後処理済みの表示用テキスト）
+        #[prost(bool, tag = "3")]
+        pub use_raw_text: bool,
+    }
+
+    /// 部分結果の安定性
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Stability {
+        Unspecified = 0,
+        Low = 1,
+        Medium = 2,
+        High = 3,
+    }
+
+    /// 文字起こし結果イベント
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TranscriptEvent {
+        #[prost(uint32, tag = "1")]
+        pub channel: u32,
+        #[prost(string, tag = "2")]
+        pub channel_name: String,
+        #[prost(string, tag = "3")]
+        pub group: String,
+        #[prost(string, tag = "4")]
+        pub timestamp: String,
+        #[prost(double, tag = "5")]
+        pub timestamp_seconds: f64,
+        #[prost(string, tag = "6")]
+        pub text: String,
+        #[prost(bool, tag = "7")]
+        pub is_partial: bool,
+        #[prost(enumeration = "Stability", tag = "8")]
+        pub stability: i32,
+        #[prost(uint64, tag = "9")]
+        pub sequence: u64,
+        #[prost(bool, tag = "10")]
+        pub promoted_from_partial: bool,
+        /// 差分で送った部分結果か（`text` は空で、`base_len` と `appended` から再構成する）
+        #[prost(bool, tag = "11")]
+        pub is_delta: bool,
+        #[prost(uint32, tag = "12")]
+        pub base_len: u32,
+        #[prost(string, tag = "13")]
+        pub appended: String,
+        /// 分断された複数の確定結果を結合したものか（`[merge]` 有効時）
+        #[prost(bool, tag = "14")]
+        pub merged: bool,
+        /// 後処理前の原文（後処理を適用した確定結果のみ、それ以外は空）
+        #[prost(string, tag = "15")]
+        pub raw_text: String,
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/dcr_transcribe.v1.TranscriptStream.rs"));
+}
+
+use proto::transcript_stream_server::{TranscriptStream, TranscriptStreamServer};
+
+impl From<&TranscriptResult> for proto::TranscriptEvent {
+    fn from(result: &TranscriptResult) -> Self {
+        let stability = match result.stability {
+            None => proto::Stability::Unspecified,
+            Some(Stability::Low) => proto::Stability::Low,
+            Some(Stability::Medium) => proto::Stability::Medium,
+            Some(Stability::High) => proto::Stability::High,
+        };
+
+        Self {
+            channel: result.channel as u32,
+            channel_name: result.channel_name.clone().unwrap_or_default(),
+            group: result.group.clone().unwrap_or_default(),
+            timestamp: result.timestamp.clone(),
+            timestamp_seconds: result.timestamp_seconds,
+            text: result.text.clone(),
+            is_partial: result.is_partial,
+            stability: stability as i32,
+            sequence: result.sequence,
+            promoted_from_partial: result.promoted_from_partial,
+            is_delta: false,
+            base_len: 0,
+            appended: String::new(),
+            merged: result.merged,
+            raw_text: result.raw_text.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl proto::TranscriptEvent {
+    /// 部分結果を差分で送るイベント（`text` の代わりに `base_len` と `appended` を設定する）
+    fn delta(result: &TranscriptResult, delta: PartialDelta) -> Self {
+        Self {
+            text: String::new(),
+            is_delta: true,
+            base_len: delta.base_len as u32,
+            appended: delta.appended,
+            ..Self::from(result)
+        }
+    }
+}
+
+/// `text` を後処理前の原文に置き換える（後処理していない結果はそのまま）
+///
+/// 部分結果の差分も置き換えた後の `text` から作るため、購読ごとにどちらのテキストでも差分配信できる。
+fn with_raw_text(mut result: TranscriptResult) -> TranscriptResult {
+    if let Some(raw_text) = &result.raw_text {
+        result.text = raw_text.clone();
+    }
+    result
+}
+
+/// `TranscriptStream` サービスの実装
+pub struct TranscriptStreamService {
+    broadcaster: TranscriptBroadcaster,
+}
+
+impl TranscriptStreamService {
+    pub fn new(broadcaster: TranscriptBroadcaster) -> Self {
+        Self { broadcaster }
+    }
+}
+
+#[tonic::async_trait]
+impl TranscriptStream for TranscriptStreamService {
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<proto::TranscriptEvent, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let peer = request.remote_addr();
+        let request = request.into_inner();
+        let channel_ids: Vec<usize> = request.channel_ids.into_iter().map(|id| id as usize).collect();
+        log::info!(
+            "gRPC: 購読開始 {:?} (チャンネル: {:?}, 部分結果の差分配信: {}, 原文: {})",
+            peer,
+            channel_ids,
+            request.incremental_partials,
+            request.use_raw_text
+        );
+
+        // クライアントが切断するとストリームごと購読がドロップされる
+        let mut subscription = self.broadcaster.subscribe(channel_ids);
+        let mut encoder = request.incremental_partials.then(PartialDeltaEncoder::new);
+        let use_raw_text = request.use_raw_text;
+        let stream = async_stream::stream! {
+            while let Some(result) = subscription.recv().await {
+                let result = if use_raw_text { with_raw_text(result) } else { result };
+                let Some(encoder) = &mut encoder else {
+                    yield Ok(proto::TranscriptEvent::from(&result));
+                    continue;
+                };
+                // 取りこぼした部分結果があるとクライアントの前回テキストと食い違うため、全文からやり直す
+                if subscription.take_lagged() {
+                    encoder.reset();
+                }
+                match encoder.encode(&result) {
+                    Some(delta) => yield Ok(proto::TranscriptEvent::delta(&result, delta)),
+                    None => yield Ok(proto::TranscriptEvent::from(&result)),
+                }
+            }
+            log::info!("gRPC: 配信元が停止したため購読を終了 {:?}", peer);
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// gRPC サーバを起動し、`running` が false になるまで配信する
+pub async fn serve(
+    bind: &str,
+    broadcaster: TranscriptBroadcaster,
+    running: Arc<AtomicBool>,
+) -> Result<()> {
+    let addr: SocketAddr = bind
+        .parse()
+        .with_context(|| format!("[grpc] bind のアドレスが不正です: {}", bind))?;
+
+    log::info!("gRPC サーバを起動: {}", addr);
+
+    tonic::transport::Server::builder()
+        .add_service(TranscriptStreamServer::new(TranscriptStreamService::new(broadcaster)))
+        .serve_with_shutdown(addr, async move {
+            while running.load(Ordering::SeqCst) {
+                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+            }
+        })
+        .await
+        .with_context(|| format!("gRPC サーバの実行に失敗: {}", addr))?;
+
+    log::info!("gRPC サーバを停止しました");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    #[test]
+    fn test_transcript_event_mapping() {
+        let mut result = TranscriptResult::new_with_audio_time(
+            2,
+            "了解".to_string(),
+            true,
+            Some(Stability::Medium),
+            1.5,
+            None,
+        )
+        .with_sequence(7);
+        result.channel_name = Some("無線機3".to_string());
+
+        let event = proto::TranscriptEvent::from(&result);
+        assert_eq!(event.channel, 2);
+        assert_eq!(event.channel_name, "無線機3");
+        assert_eq!(event.group, "");
+        assert_eq!(event.text, "了解");
+        assert!(event.is_partial);
+        assert_eq!(event.stability, proto::Stability::Medium as i32);
+        assert_eq!(event.sequence, 7);
+        assert_eq!(event.timestamp_seconds, 1.5);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_filters_and_disconnects() {
+        let broadcaster = TranscriptBroadcaster::new();
+        let service = TranscriptStreamService::new(broadcaster.clone());
+
+        let mut stream = service
+            .subscribe(Request::new(proto::SubscribeRequest {
+                channel_ids: vec![1],
+                incremental_partials: false,
+                use_raw_text: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(broadcaster.subscriber_count(), 1);
+
+        broadcaster.publish(&TranscriptResult::new_with_audio_time(
+            0,
+            "対象外".to_string(),
+            false,
+            None,
+            0.0,
+            None,
+        ));
+        broadcaster.publish(&TranscriptResult::new_with_audio_time(
+            1,
+            "対象".to_string(),
+            false,
+            None,
+            0.0,
+            None,
+        ));
+
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(event.channel, 1);
+        assert_eq!(event.text, "対象");
+
+        // クライアント切断（ストリームのドロップ）で購読が解除される
+        drop(stream);
+        assert_eq!(broadcaster.subscriber_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_incremental_partials() {
+        let broadcaster = TranscriptBroadcaster::new();
+        let service = TranscriptStreamService::new(broadcaster.clone());
+        let mut stream = service
+            .subscribe(Request::new(proto::SubscribeRequest {
+                channel_ids: vec![],
+                incremental_partials: true,
+                use_raw_text: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let publish = |text: &str, is_partial: bool| {
+            broadcaster.publish(&TranscriptResult::new_with_audio_time(0, text.to_string(), is_partial, None, 0.0, None));
+        };
+        publish("現在", true);
+        publish("現在地は", true);
+        publish("現在地は正門です", false);
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert!(!first.is_delta);
+        assert_eq!(first.text, "現在");
+
+        let second = stream.next().await.unwrap().unwrap();
+        assert!(second.is_delta);
+        assert!(second.is_partial);
+        assert_eq!(second.text, "");
+        assert_eq!((second.base_len, second.appended.as_str()), (2, "地は"));
+
+        // 確定結果は全文
+        let last = stream.next().await.unwrap().unwrap();
+        assert!(!last.is_delta);
+        assert_eq!(last.text, "現在地は正門です");
+    }
+
+    #[tokio::test]
+    async fn test_raw_text_selected_per_subscription() {
+        let broadcaster = TranscriptBroadcaster::new();
+        let service = TranscriptStreamService::new(broadcaster.clone());
+        let subscribe = |use_raw_text: bool| {
+            service.subscribe(Request::new(proto::SubscribeRequest {
+                channel_ids: vec![],
+                incremental_partials: false,
+                use_raw_text,
+            }))
+        };
+        let mut filtered = subscribe(false).await.unwrap().into_inner();
+        let mut raw = subscribe(true).await.unwrap().into_inner();
+
+        let mut result = TranscriptResult::new_with_audio_time(0, "了解".to_string(), false, None, 0.0, None);
+        result.raw_text = Some("えー了解".to_string());
+        broadcaster.publish(&result);
+        // 後処理していない結果はどちらも同じテキスト
+        broadcaster.publish(&TranscriptResult::new_with_audio_time(0, "現在".to_string(), true, None, 0.0, None));
+
+        let event = filtered.next().await.unwrap().unwrap();
+        assert_eq!((event.text.as_str(), event.raw_text.as_str()), ("了解", "えー了解"));
+        let event = raw.next().await.unwrap().unwrap();
+        assert_eq!((event.text.as_str(), event.raw_text.as_str()), ("えー了解", "えー了解"));
+        assert_eq!(filtered.next().await.unwrap().unwrap().text, "現在");
+        assert_eq!(raw.next().await.unwrap().unwrap().text, "現在");
+    }
+}
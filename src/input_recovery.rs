@@ -0,0 +1,380 @@
+use crate::config::AudioConfig;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+/// システム時刻がモノトニック時刻よりこれ以上進んだら、スリープから復帰したとみなす
+///
+/// モノトニック時刻（`Instant`）はスリープ中に進まないプラットフォームが多いため、
+/// 監視の間隔の間にシステム時刻だけが大きく進んだことで復帰を検知できる。
+const SLEEP_DETECT_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// 入力コールバックの到着と信号の有無（コールバックで更新し、監視側で読む）
+///
+/// 時刻は作成時点からの経過ミリ秒 + 1（0 は未到着）。ストリームを再構築しても同じものを使い続ける。
+#[derive(Debug)]
+pub struct InputActivity {
+    origin: Instant,
+    last_callback_ms: AtomicU64,
+    last_signal_ms: AtomicU64,
+}
+
+impl Default for InputActivity {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+            last_callback_ms: AtomicU64::new(0),
+            last_signal_ms: AtomicU64::new(0),
+        }
+    }
+}
+
+impl InputActivity {
+    /// コールバックの到着を記録する（`has_signal` は0以外のサンプルを含んでいたか）
+    pub fn record(&self, has_signal: bool) {
+        let ms = self.origin.elapsed().as_millis() as u64 + 1;
+        self.last_callback_ms.store(ms, Ordering::Relaxed);
+        if has_signal {
+            self.last_signal_ms.store(ms, Ordering::Relaxed);
+        }
+    }
+
+    /// 最後にコールバックが届いた時刻
+    pub fn last_callback(&self) -> Option<Instant> {
+        self.to_instant(self.last_callback_ms.load(Ordering::Relaxed))
+    }
+
+    /// 最後に0以外のサンプルが届いた時刻
+    pub fn last_signal(&self) -> Option<Instant> {
+        self.to_instant(self.last_signal_ms.load(Ordering::Relaxed))
+    }
+
+    fn to_instant(&self, ms: u64) -> Option<Instant> {
+        (ms > 0).then(|| self.origin + Duration::from_millis(ms - 1))
+    }
+}
+
+/// 検出した入力ストリームの異常
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputFault {
+    /// 入力コールバックが届かない
+    Stalled { secs: f64 },
+    /// 全サンプルが0の入力が続いている
+    Silent { secs: f64 },
+    /// システムのスリープから復帰した
+    Resumed { slept_secs: f64 },
+}
+
+impl InputFault {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Stalled { secs } => format!("入力が {:.0} 秒間届いていません", secs),
+            Self::Silent { secs } => format!("無信号（全サンプルが0）の入力が {:.0} 秒間続いています", secs),
+            Self::Resumed { slept_secs } => format!("システムのスリープからの復帰を検知しました（約 {:.0} 秒間停止）", slept_secs),
+        }
+    }
+}
+
+/// 監視の結果、呼び出し側が行う復帰処理
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecoveryAction {
+    /// 異常を検出した。録音を止めてストリームを再構築する
+    Lost(InputFault),
+    /// 入力が戻らないため、再構築を再試行する（通算の試行回数）
+    Retry(u32),
+    /// 最大回数まで再構築しても戻らないため、再試行をやめる（録音は止めたまま）
+    GiveUp(u32),
+    /// 入力が戻った。新しいファイルで録音を再開する
+    Recovered { attempts: u32, downtime_secs: f64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum WatchdogState {
+    /// 正常。`since` 以前の到着時刻は判定に使わない（開始・復帰の時刻）
+    Normal { since: Instant },
+    /// 復帰処理中
+    Recovering {
+        lost_at: Instant,
+        attempts: u32,
+        last_attempt: Instant,
+    },
+    /// 再試行をやめた（入力が戻れば復帰する）
+    GaveUp {
+        lost_at: Instant,
+        attempts: u32,
+        last_attempt: Instant,
+    },
+}
+
+/// 入力ストリームの停止・無信号・スリープ復帰を検出し、再構築の時期を決める（`[audio] stall_timeout_secs`）
+///
+/// 定期的に `poll` を呼び、返された `RecoveryAction` に従って録音の停止・ストリームの再構築・録音の再開を行う。
+/// 再構築後に入力コールバックが届き（無信号検出が有効なら0以外のサンプルも届き）次第、復帰とみなす。
+#[derive(Debug)]
+pub struct InputWatchdog {
+    stall_timeout: Option<Duration>,
+    silent_timeout: Option<Duration>,
+    interval: Duration,
+    max_attempts: u32,
+    /// 前回の `poll` のモノトニック時刻とシステム時刻（スリープ検知用）
+    last_poll: Option<(Instant, SystemTime)>,
+    state: WatchdogState,
+}
+
+impl InputWatchdog {
+    pub fn new(config: &AudioConfig, now: Instant) -> Self {
+        let timeout = |secs: u32| (secs > 0).then(|| Duration::from_secs(secs as u64));
+        Self {
+            stall_timeout: timeout(config.stall_timeout_secs),
+            silent_timeout: timeout(config.silent_input_timeout_secs),
+            interval: Duration::from_secs(config.recovery_interval_secs.max(1) as u64),
+            max_attempts: config.recovery_max_attempts,
+            last_poll: None,
+            state: WatchdogState::Normal { since: now },
+        }
+    }
+
+    /// 監視が有効か（`stall_timeout_secs` と `silent_input_timeout_secs` がともに0なら監視しない）
+    pub fn is_enabled(&self) -> bool {
+        self.stall_timeout.is_some() || self.silent_timeout.is_some()
+    }
+
+    /// 入力の到着状況を確認し、必要な復帰処理を返す
+    pub fn poll(&mut self, now: Instant, wall_now: SystemTime, activity: &InputActivity) -> Option<RecoveryAction> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let slept = self.detect_sleep(now, wall_now);
+        let last_callback = activity.last_callback();
+        let last_signal = activity.last_signal();
+
+        match self.state {
+            WatchdogState::Normal { since } => {
+                let fault = slept
+                    .map(|slept_secs| InputFault::Resumed { slept_secs })
+                    .or_else(|| self.detect_fault(now, since, last_callback, last_signal))?;
+                self.state = WatchdogState::Recovering {
+                    lost_at: now,
+                    attempts: 1,
+                    last_attempt: now,
+                };
+                Some(RecoveryAction::Lost(fault))
+            }
+            WatchdogState::Recovering {
+                lost_at,
+                attempts,
+                last_attempt,
+            } => {
+                if self.is_recovered(last_attempt, last_callback, last_signal) {
+                    return Some(self.recovered(now, lost_at, attempts));
+                }
+                if now.duration_since(last_attempt) < self.interval {
+                    return None;
+                }
+                if self.max_attempts > 0 && attempts >= self.max_attempts {
+                    self.state = WatchdogState::GaveUp {
+                        lost_at,
+                        attempts,
+                        last_attempt,
+                    };
+                    return Some(RecoveryAction::GiveUp(attempts));
+                }
+                self.state = WatchdogState::Recovering {
+                    lost_at,
+                    attempts: attempts + 1,
+                    last_attempt: now,
+                };
+                Some(RecoveryAction::Retry(attempts + 1))
+            }
+            WatchdogState::GaveUp {
+                lost_at,
+                attempts,
+                last_attempt,
+            } => self
+                .is_recovered(last_attempt, last_callback, last_signal)
+                .then(|| self.recovered(now, lost_at, attempts)),
+        }
+    }
+
+    /// 前回の `poll` からシステム時刻だけが大きく進んでいれば、止まっていた秒数を返す
+    fn detect_sleep(&mut self, now: Instant, wall_now: SystemTime) -> Option<f64> {
+        let (last_now, last_wall) = self.last_poll.replace((now, wall_now))?;
+        let wall_elapsed = wall_now.duration_since(last_wall).ok()?;
+        let slept = wall_elapsed.checked_sub(now.duration_since(last_now))?;
+        (slept >= SLEEP_DETECT_THRESHOLD).then_some(slept.as_secs_f64())
+    }
+
+    fn detect_fault(
+        &self,
+        now: Instant,
+        since: Instant,
+        last_callback: Option<Instant>,
+        last_signal: Option<Instant>,
+    ) -> Option<InputFault> {
+        // 開始・復帰より前の到着は数えない
+        let elapsed = |last: Option<Instant>| now.duration_since(last.map_or(since, |last| last.max(since)));
+        if let Some(timeout) = self.stall_timeout {
+            let secs = elapsed(last_callback);
+            if secs >= timeout {
+                return Some(InputFault::Stalled { secs: secs.as_secs_f64() });
+            }
+        }
+        if let Some(timeout) = self.silent_timeout {
+            let secs = elapsed(last_signal);
+            if secs >= timeout {
+                return Some(InputFault::Silent { secs: secs.as_secs_f64() });
+            }
+        }
+        None
+    }
+
+    /// 最後の再構築より後に入力（無信号検出が有効なら0以外のサンプル）が届いたか
+    fn is_recovered(&self, last_attempt: Instant, last_callback: Option<Instant>, last_signal: Option<Instant>) -> bool {
+        let arrived = |last: Option<Instant>| last.is_some_and(|last| last > last_attempt);
+        arrived(last_callback) && (self.silent_timeout.is_none() || arrived(last_signal))
+    }
+
+    fn recovered(&mut self, now: Instant, lost_at: Instant, attempts: u32) -> RecoveryAction {
+        self.state = WatchdogState::Normal { since: now };
+        RecoveryAction::Recovered {
+            attempts,
+            downtime_secs: now.duration_since(lost_at).as_secs_f64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(stall: u32, silent: u32, max_attempts: u32) -> AudioConfig {
+        AudioConfig {
+            stall_timeout_secs: stall,
+            silent_input_timeout_secs: silent,
+            recovery_interval_secs: 5,
+            recovery_max_attempts: max_attempts,
+            ..AudioConfig::default()
+        }
+    }
+
+    /// `activity` の作成時刻から `secs` 秒後
+    fn at(activity: &InputActivity, secs: u64) -> Instant {
+        activity.origin + Duration::from_secs(secs)
+    }
+
+    fn record_at(activity: &InputActivity, secs: u64, has_signal: bool) {
+        let ms = secs * 1000 + 1;
+        activity.last_callback_ms.store(ms, Ordering::Relaxed);
+        if has_signal {
+            activity.last_signal_ms.store(ms, Ordering::Relaxed);
+        }
+    }
+
+    /// システム時刻がモノトニック時刻と同じだけ進む場合の `poll`
+    fn poll(watchdog: &mut InputWatchdog, activity: &InputActivity, secs: u64) -> Option<RecoveryAction> {
+        watchdog.poll(at(activity, secs), SystemTime::UNIX_EPOCH + Duration::from_secs(secs), activity)
+    }
+
+    #[test]
+    fn test_stall_retry_and_recover() {
+        let activity = InputActivity::default();
+        let mut watchdog = InputWatchdog::new(&config(10, 0, 10), activity.origin);
+        record_at(&activity, 1, true);
+        assert_eq!(poll(&mut watchdog, &activity, 10), None);
+
+        // 最後の入力から10秒
+        assert_eq!(
+            poll(&mut watchdog, &activity, 11),
+            Some(RecoveryAction::Lost(InputFault::Stalled { secs: 10.0 }))
+        );
+        // 再試行は5秒ごと
+        assert_eq!(poll(&mut watchdog, &activity, 15), None);
+        assert_eq!(poll(&mut watchdog, &activity, 16), Some(RecoveryAction::Retry(2)));
+
+        // 再構築後に入力が届いた
+        record_at(&activity, 17, false);
+        assert_eq!(
+            poll(&mut watchdog, &activity, 18),
+            Some(RecoveryAction::Recovered {
+                attempts: 2,
+                downtime_secs: 7.0
+            })
+        );
+        assert_eq!(poll(&mut watchdog, &activity, 19), None);
+    }
+
+    #[test]
+    fn test_give_up_after_max_attempts() {
+        let activity = InputActivity::default();
+        let mut watchdog = InputWatchdog::new(&config(10, 0, 2), activity.origin);
+
+        // 一度も入力が届かない場合は開始時刻から数える
+        assert!(matches!(poll(&mut watchdog, &activity, 10), Some(RecoveryAction::Lost(_))));
+        assert_eq!(poll(&mut watchdog, &activity, 15), Some(RecoveryAction::Retry(2)));
+        assert_eq!(poll(&mut watchdog, &activity, 20), Some(RecoveryAction::GiveUp(2)));
+        assert_eq!(poll(&mut watchdog, &activity, 60), None);
+
+        // 諦めた後でも入力が戻れば復帰する
+        record_at(&activity, 61, true);
+        assert!(matches!(
+            poll(&mut watchdog, &activity, 62),
+            Some(RecoveryAction::Recovered { attempts: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_silent_input() {
+        let activity = InputActivity::default();
+        let mut watchdog = InputWatchdog::new(&config(10, 30, 0), activity.origin);
+        record_at(&activity, 5, true);
+        record_at(&activity, 34, false);
+        assert_eq!(poll(&mut watchdog, &activity, 34), None);
+        assert_eq!(
+            poll(&mut watchdog, &activity, 35),
+            Some(RecoveryAction::Lost(InputFault::Silent { secs: 30.0 }))
+        );
+
+        // 入力が届いても無音のままなら復帰とみなさない
+        record_at(&activity, 36, false);
+        assert_eq!(poll(&mut watchdog, &activity, 37), None);
+        record_at(&activity, 38, true);
+        assert!(matches!(poll(&mut watchdog, &activity, 39), Some(RecoveryAction::Recovered { .. })));
+    }
+
+    #[test]
+    fn test_detect_sleep() {
+        let activity = InputActivity::default();
+        let mut watchdog = InputWatchdog::new(&config(10, 0, 10), activity.origin);
+        record_at(&activity, 1, true);
+        assert_eq!(poll(&mut watchdog, &activity, 1), None);
+
+        // モノトニック時刻は1秒、システム時刻は1時間進んだ
+        let action = watchdog.poll(
+            at(&activity, 2),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(3601),
+            &activity,
+        );
+        assert_eq!(
+            action,
+            Some(RecoveryAction::Lost(InputFault::Resumed { slept_secs: 3599.0 }))
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let activity = InputActivity::default();
+        let mut watchdog = InputWatchdog::new(&config(0, 0, 10), activity.origin);
+        assert!(!watchdog.is_enabled());
+        assert_eq!(poll(&mut watchdog, &activity, 3600), None);
+    }
+
+    #[test]
+    fn test_activity_record() {
+        let activity = InputActivity::default();
+        assert!(activity.last_callback().is_none());
+        activity.record(false);
+        assert!(activity.last_callback().is_some());
+        assert!(activity.last_signal().is_none());
+        activity.record(true);
+        assert!(activity.last_signal().is_some());
+    }
+}
@@ -0,0 +1,182 @@
+/// Whisper の `verbose_json` が返す言語名と ISO 639-1 コードの対応（主なもののみ）
+const WHISPER_LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("japanese", "ja"),
+    ("english", "en"),
+    ("korean", "ko"),
+    ("chinese", "zh"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("spanish", "es"),
+    ("portuguese", "pt"),
+    ("russian", "ru"),
+    ("vietnamese", "vi"),
+    ("thai", "th"),
+    ("indonesian", "id"),
+];
+
+/// 文字種による判定で、その文字種とみなす割合
+const SCRIPT_DOMINANCE: f32 = 0.6;
+
+/// 文字種による判定でラテン文字を英語とみなす最小文字数（"OK" などの混入で切り替えない）
+const MIN_LATIN_LETTERS: usize = 4;
+
+/// 言語コードの主言語部分（"ja-JP" → "ja"）
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::language::primary_subtag;
+/// assert_eq!(primary_subtag("ja-JP"), "ja");
+/// assert_eq!(primary_subtag("en"), "en");
+/// ```
+pub fn primary_subtag(code: &str) -> &str {
+    code.split(['-', '_']).next().unwrap_or(code)
+}
+
+/// 検出された言語を `allowed` の言語コードに対応付ける
+///
+/// 完全一致（大文字小文字を区別しない）を優先し、なければ主言語が同じものを返す。
+/// Whisper の言語名（"japanese" など）も受け付ける。候補外なら `None`。
+pub fn match_allowed(detected: &str, allowed: &[String]) -> Option<String> {
+    let detected = detected.trim();
+    if let Some(exact) = allowed.iter().find(|code| code.eq_ignore_ascii_case(detected)) {
+        return Some(exact.clone());
+    }
+
+    let primary = WHISPER_LANGUAGE_NAMES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(detected))
+        .map(|(_, code)| *code)
+        .unwrap_or_else(|| primary_subtag(detected));
+    allowed
+        .iter()
+        .find(|code| primary_subtag(code).eq_ignore_ascii_case(primary))
+        .cloned()
+}
+
+/// 文字種の比率からテキストの言語を推定する（"ja" / "ko" / "en"）
+///
+/// ラテン文字が大半かつ `MIN_LATIN_LETTERS` 文字以上なら英語、それ以外でかなを含めば日本語、
+/// ハングルが大半なら韓国語とする。
+/// 漢字のみ（日本語か中国語か区別できない）など判断できない場合は `None`。
+pub fn guess_from_text(text: &str) -> Option<&'static str> {
+    let (mut kana, mut han, mut hangul, mut latin) = (0usize, 0usize, 0usize, 0usize);
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{4E00}'..='\u{9FFF}' => han += 1,
+            '\u{AC00}'..='\u{D7AF}' => hangul += 1,
+            c if c.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+
+    let total = (kana + han + hangul + latin) as f32;
+    if total == 0.0 {
+        return None;
+    }
+    if latin >= MIN_LATIN_LETTERS && latin as f32 / total >= SCRIPT_DOMINANCE {
+        Some("en")
+    } else if kana > 0 {
+        Some("ja")
+    } else if hangul as f32 / total >= SCRIPT_DOMINANCE {
+        Some("ko")
+    } else {
+        None
+    }
+}
+
+/// 発話ごとの言語判定と、次の発話に使う言語の切り替え
+///
+/// 確定結果ごとにバックエンドの言語検出（なければ文字種による推定）を `allowed` の候補に対応付け、
+/// 現在の言語と異なれば切り替える。候補外の言語は無視する。
+#[derive(Debug, Clone)]
+pub struct LanguageSwitcher {
+    allowed: Vec<String>,
+    current: String,
+}
+
+impl LanguageSwitcher {
+    /// `initial` は `allowed` に含まれている前提（設定の検証で保証する）
+    pub fn new(allowed: Vec<String>, initial: &str) -> Self {
+        let current = match_allowed(initial, &allowed).unwrap_or_else(|| initial.to_string());
+        Self { allowed, current }
+    }
+
+    /// 次の発話に使う言語
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    /// 確定結果の言語を判定する
+    ///
+    /// 戻り値は判定した言語（候補外・判定不能なら `None`）と、現在の言語を切り替えたか。
+    pub fn observe(&mut self, backend_detected: Option<&str>, text: &str) -> (Option<String>, bool) {
+        let detected = match backend_detected {
+            Some(detected) => match_allowed(detected, &self.allowed),
+            None => guess_from_text(text).and_then(|guess| match_allowed(guess, &self.allowed)),
+        };
+
+        let switched = match &detected {
+            Some(language) if *language != self.current => {
+                self.current = language.clone();
+                true
+            }
+            _ => false,
+        };
+        (detected, switched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed() -> Vec<String> {
+        vec!["ja-JP".to_string(), "en-US".to_string()]
+    }
+
+    #[test]
+    fn test_match_allowed() {
+        assert_eq!(match_allowed("ja-JP", &allowed()), Some("ja-JP".to_string()));
+        assert_eq!(match_allowed("EN-us", &allowed()), Some("en-US".to_string()));
+        assert_eq!(match_allowed("en", &allowed()), Some("en-US".to_string()));
+        assert_eq!(match_allowed("japanese", &allowed()), Some("ja-JP".to_string()));
+        // 候補外
+        assert_eq!(match_allowed("ko-KR", &allowed()), None);
+        assert_eq!(match_allowed("chinese", &allowed()), None);
+    }
+
+    #[test]
+    fn test_guess_from_text() {
+        assert_eq!(guess_from_text("了解しました、現在地に向かいます"), Some("ja"));
+        assert_eq!(guess_from_text("Copy that, heading to the gate"), Some("en"));
+        assert_eq!(guess_from_text("알겠습니다"), Some("ko"));
+        // 日本語の中の短い英単語では英語にしない
+        assert_eq!(guess_from_text("OK です"), Some("ja"));
+        assert_eq!(guess_from_text("ゲート A に到着"), Some("ja"));
+        assert_eq!(guess_from_text("OK"), None);
+        // 漢字のみは日本語・中国語を区別できない
+        assert_eq!(guess_from_text("了解"), None);
+        assert_eq!(guess_from_text("123 !?"), None);
+    }
+
+    #[test]
+    fn test_switcher() {
+        let mut switcher = LanguageSwitcher::new(allowed(), "ja-JP");
+        assert_eq!(switcher.current(), "ja-JP");
+
+        // バックエンドの検出結果を優先する
+        assert_eq!(switcher.observe(Some("en-US"), "はい"), (Some("en-US".to_string()), true));
+        assert_eq!(switcher.current(), "en-US");
+        assert_eq!(switcher.observe(Some("en-US"), "roger"), (Some("en-US".to_string()), false));
+
+        // 検出結果がなければ文字種から推定する
+        assert_eq!(switcher.observe(None, "了解しました"), (Some("ja-JP".to_string()), true));
+
+        // 候補外・判定不能なら切り替えない
+        assert_eq!(switcher.observe(Some("ko-KR"), "알겠습니다"), (None, false));
+        assert_eq!(switcher.observe(None, "了解"), (None, false));
+        assert_eq!(switcher.current(), "ja-JP");
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// チャンネルごとの固定遅延を補正する
+///
+/// デバイス間の既知の遅延差を揃えるため、サンプル単位で遅延を付与、または先行させる。
+///
+/// - 正のオフセット: 先頭に無音を挿入して遅延させる（出力チャンクの長さは入力と同じ）
+/// - 負のオフセット: 先頭のサンプルを読み捨てて先行させる（読み捨て中は出力が短くなる）
+///
+/// インターリーブされた多チャンネル入力でもフレーム単位で補正する。
+/// サンプルの型は `i16`（既定）と、`[audio] internal_format = "f32"` 用の `f32` に対応する。
+pub struct LatencyCompensator<T = i16> {
+    offset_frames: i64,
+    /// 遅延付与用のディレイライン（最初のチャンクで初期化）
+    delay_line: Option<VecDeque<T>>,
+    /// 先行用にまだ読み捨てるサンプル数（最初のチャンクで初期化）
+    skip_remaining: Option<usize>,
+}
+
+impl<T: Copy + Default> LatencyCompensator<T> {
+    pub fn new(offset_ms: i32, sample_rate: u32) -> Self {
+        Self {
+            offset_frames: offset_ms as i64 * sample_rate as i64 / 1000,
+            delay_line: None,
+            skip_remaining: None,
+        }
+    }
+
+    /// 補正量（フレーム数、正なら遅延）
+    pub fn offset_frames(&self) -> i64 {
+        self.offset_frames
+    }
+
+    /// チャンクを補正して返す
+    pub fn process(&mut self, samples: &[T], channels: u16) -> Vec<T> {
+        let channels = channels.max(1) as usize;
+
+        if self.offset_frames > 0 {
+            let delay_samples = self.offset_frames as usize * channels;
+            let line = self
+                .delay_line
+                .get_or_insert_with(|| VecDeque::from(vec![T::default(); delay_samples]));
+            line.extend(samples.iter().copied());
+            line.drain(..samples.len()).collect()
+        } else if self.offset_frames < 0 {
+            let skip = self
+                .skip_remaining
+                .get_or_insert(self.offset_frames.unsigned_abs() as usize * channels);
+            let skipped = (*skip).min(samples.len());
+            *skip -= skipped;
+            samples[skipped..].to_vec()
+        } else {
+            samples.to_vec()
+        }
+    }
+
+    /// タイムスタンプを補正（共通の時間軸上で、遅延させた分だけ後ろにずらす）
+    pub fn adjust_timestamp_ns(&self, timestamp_ns: u128, sample_rate: u32) -> u128 {
+        let offset_ns = self.offset_frames.unsigned_abs() as u128 * 1_000_000_000 / sample_rate as u128;
+        if self.offset_frames >= 0 {
+            timestamp_ns + offset_ns
+        } else {
+            timestamp_ns.saturating_sub(offset_ns)
+        }
+    }
+}
+
+/// 既知の同時刻信号（拍手・トーンなど）を録った2チャンネルから、
+/// `target` に設定すべき `latency_offset_ms` を推定する
+///
+/// `max_lag_ms` の範囲で相互相関が最大になるずれを探す。
+/// `target` が `reference` より遅れている場合は負の値（先行させる）を返す。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::latency::estimate_offset_ms;
+/// let reference: Vec<i16> = (0..1600).map(|i| if i == 400 { 20000 } else { 0 }).collect();
+/// // 5ms (80サンプル @16kHz) 遅れて届いたチャンネル
+/// let target: Vec<i16> = (0..1600).map(|i| if i == 480 { 20000 } else { 0 }).collect();
+/// assert_eq!(estimate_offset_ms(&reference, &target, 16000, 50), -5.0);
+/// ```
+pub fn estimate_offset_ms(reference: &[i16], target: &[i16], sample_rate: u32, max_lag_ms: u32) -> f64 {
+    let max_lag = (sample_rate as u64 * max_lag_ms as u64 / 1000) as i64;
+
+    let correlation = |lag: i64| -> i64 {
+        // target[n + lag] と reference[n] の積和
+        reference
+            .iter()
+            .enumerate()
+            .filter_map(|(n, &r)| {
+                let t = n as i64 + lag;
+                (t >= 0 && (t as usize) < target.len()).then(|| r as i64 * target[t as usize] as i64)
+            })
+            .sum()
+    };
+
+    let best_lag = (-max_lag..=max_lag)
+        .max_by_key(|&lag| (correlation(lag), -lag.abs()))
+        .unwrap_or(0);
+
+    -(best_lag as f64) * 1000.0 / sample_rate as f64
+}
+
+/// 初回応答遅延の集計値
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirstResultStats {
+    /// 計測した接続数
+    pub connections: u32,
+    pub last: Duration,
+    pub average: Duration,
+    pub max: Duration,
+}
+
+impl FirstResultStats {
+    /// TUI・ログ用の表示
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dcr_transcribe::latency::FirstResultStats;
+    /// # use std::time::Duration;
+    /// let stats = FirstResultStats {
+    ///     connections: 3,
+    ///     last: Duration::from_millis(800),
+    ///     average: Duration::from_millis(1250),
+    ///     max: Duration::from_millis(2100),
+    /// };
+    /// assert_eq!(stats.describe(), "初回応答 平均 1250ms / 最悪 2100ms (3接続)");
+    /// ```
+    pub fn describe(&self) -> String {
+        format!(
+            "初回応答 平均 {}ms / 最悪 {}ms ({}接続)",
+            self.average.as_millis(),
+            self.max.as_millis(),
+            self.connections
+        )
+    }
+}
+
+/// 接続の開始から、その接続で最初の文字起こし結果を受けるまでの時間（初回応答遅延）を計測する
+///
+/// 音声の検出で接続する場合、発話の開始から最初の結果が表示されるまでの体感の遅れのほとんどを占める。
+/// バッファサイズや `fast_start_chunks` の調整の効果を評価するために使う。
+#[derive(Debug, Clone, Default)]
+pub struct FirstResultLatency {
+    /// 結果を待っている接続の開始時刻
+    pending: Option<Instant>,
+    connections: u32,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+impl FirstResultLatency {
+    /// 接続を開始した（前の接続の計測が終わっていなければ破棄する）
+    pub fn connect_started(&mut self, at: Instant) {
+        self.pending = Some(at);
+    }
+
+    /// 接続に失敗した
+    pub fn connect_failed(&mut self) {
+        self.pending = None;
+    }
+
+    /// 結果を受けた（接続後の最初の結果なら遅延を記録して返す）
+    pub fn result_received(&mut self, at: Instant) -> Option<Duration> {
+        let latency = at.saturating_duration_since(self.pending.take()?);
+        self.connections += 1;
+        self.total += latency;
+        self.last = latency;
+        self.max = self.max.max(latency);
+        Some(latency)
+    }
+
+    /// 集計値（まだ計測していなければ `None`）
+    pub fn stats(&self) -> Option<FirstResultStats> {
+        (self.connections > 0).then(|| FirstResultStats {
+            connections: self.connections,
+            last: self.last,
+            average: self.total / self.connections,
+            max: self.max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_result_latency() {
+        let start = Instant::now();
+        let mut latency = FirstResultLatency::default();
+        assert_eq!(latency.stats(), None);
+        // 接続前の結果（以前のストリームの残り）は数えない
+        assert_eq!(latency.result_received(start), None);
+
+        latency.connect_started(start);
+        assert_eq!(latency.result_received(start + Duration::from_millis(900)), Some(Duration::from_millis(900)));
+        // 同じ接続の2件目以降は数えない
+        assert_eq!(latency.result_received(start + Duration::from_millis(1500)), None);
+
+        // 失敗した接続は数えない
+        latency.connect_started(start + Duration::from_secs(10));
+        latency.connect_failed();
+        assert_eq!(latency.result_received(start + Duration::from_secs(11)), None);
+
+        latency.connect_started(start + Duration::from_secs(20));
+        latency.result_received(start + Duration::from_millis(22_100));
+        assert_eq!(
+            latency.stats(),
+            Some(FirstResultStats {
+                connections: 2,
+                last: Duration::from_millis(2100),
+                average: Duration::from_millis(1500),
+                max: Duration::from_millis(2100),
+            })
+        );
+    }
+
+    #[test]
+    fn test_positive_offset_delays() {
+        // 1ms = 16サンプル遅延
+        let mut comp = LatencyCompensator::new(1, 16000);
+        let input: Vec<i16> = (1..=20).collect();
+
+        let out = comp.process(&input, 1);
+        assert_eq!(out.len(), 20);
+        assert_eq!(&out[..16], &[0; 16]);
+        assert_eq!(&out[16..], &[1, 2, 3, 4]);
+
+        // 次のチャンクに続きが出てくる
+        let out = comp.process(&[21, 22], 1);
+        assert_eq!(out, vec![5, 6]);
+    }
+
+    #[test]
+    fn test_negative_offset_advances() {
+        let mut comp = LatencyCompensator::new(-1, 16000);
+
+        // 最初の16サンプルを読み捨てる（チャンクをまたぐ）
+        assert!(comp.process(&[1; 10], 1).is_empty());
+        let out = comp.process(&(11..=20).collect::<Vec<i16>>(), 1);
+        assert_eq!(out, vec![17, 18, 19, 20]);
+        assert_eq!(comp.process(&[21], 1), vec![21]);
+    }
+
+    #[test]
+    fn test_interleaved_frames() {
+        // 2ch インターリーブでは 1フレーム = 2サンプル
+        let mut comp = LatencyCompensator::new(1, 1000);
+        assert_eq!(comp.process(&[1, 2, 3, 4], 2), vec![0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_f32_samples() {
+        // f32 でも同じフレーム数だけ遅延する
+        let mut comp = LatencyCompensator::<f32>::new(1, 1000);
+        assert_eq!(comp.process(&[0.5, -0.5, 0.25], 1), vec![0.0, 0.5, -0.5]);
+        assert_eq!(comp.process(&[1.0], 1), vec![0.25]);
+    }
+
+    #[test]
+    fn test_adjust_timestamp() {
+        let delay = LatencyCompensator::<i16>::new(10, 16000);
+        assert_eq!(delay.adjust_timestamp_ns(1_000_000_000, 16000), 1_010_000_000);
+
+        let advance = LatencyCompensator::<i16>::new(-10, 16000);
+        assert_eq!(advance.adjust_timestamp_ns(1_000_000_000, 16000), 990_000_000);
+        assert_eq!(advance.adjust_timestamp_ns(0, 16000), 0);
+    }
+
+    #[test]
+    fn test_estimate_offset_roundtrip() {
+        // 推定したオフセットで補正すると揃う
+        let pulse = |at: usize| -> Vec<i16> {
+            (0..4000)
+                .map(|i| if (at..at + 8).contains(&i) { 12000 } else { 0 })
+                .collect()
+        };
+        let reference = pulse(1000);
+        let early = pulse(920); // 5ms 早い
+
+        let offset = estimate_offset_ms(&reference, &early, 16000, 20);
+        assert_eq!(offset, 5.0);
+
+        let mut comp = LatencyCompensator::new(offset as i32, 16000);
+        assert_eq!(comp.process(&early, 1), reference);
+    }
+}
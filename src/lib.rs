@@ -41,15 +41,44 @@
 
 pub mod audio_input;
 pub mod audio_output;
+pub mod audit;
 pub mod aws_transcribe;
+pub mod broadcast;
 pub mod buffer;
+pub mod channel_detect;
 pub mod channel_processor;
+pub mod clip;
 pub mod config;
+pub mod cost;
+pub mod db;
+pub mod encryption;
+pub mod explain;
+pub mod feedback;
 pub mod flac_encoder;
+pub mod grpc;
+pub mod input_recovery;
+pub mod language;
+pub mod latency;
+pub mod limiter;
+pub mod memory;
+pub mod merge;
+#[cfg(test)]
+pub(crate) mod mock_backend;
+pub mod segment_clip;
+pub mod session_report;
+pub mod sidecar;
+pub mod storage;
+pub mod summary;
+pub mod timeline;
+pub mod timezone;
 pub mod transcribe;
 pub mod transcribe_backend;
+pub mod tui;
 pub mod tui_state;
 pub mod types;
+pub mod upload;
 pub mod vad;
+pub mod vad_record;
+pub mod verify;
 pub mod wav_writer;
 pub mod whisper_api;
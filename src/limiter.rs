@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+
+/// ルックアヘッドの上限（ミリ秒、送信の遅延になるため短く抑える）
+pub const MAX_LIMITER_LOOKAHEAD_MS: u32 = 20;
+
+/// リミッタが作動中とみなすゲイン（これを下回ったら作動、戻ったら解除）
+const ENGAGED_GAIN: f32 = 0.999;
+
+/// 送信音声のピークリミッタ（`[dsp] limiter_threshold_db`）
+///
+/// 入力をルックアヘッド分だけ遅らせ、これから出力するサンプルまでの区間のピークが閾値を超える場合に、
+/// ピークが出力されるまでにゲインを滑らかに下げる。ピークが過ぎたらリリース時間をかけてゲインを戻す。
+/// ゲインの追従が間に合わない分は閾値で頭打ちにするため、出力が閾値を超えることはない。
+///
+/// 出力はルックアヘッド分（`latency_samples`）だけ遅れる（最初のチャンクの先頭は無音）。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::limiter::PeakLimiter;
+/// let mut limiter = PeakLimiter::new(-6.0, 50, 1, 16000);
+/// let input: Vec<i16> = (0..1600).map(|i| if i % 40 < 20 { 30000 } else { -30000 }).collect();
+/// let output = limiter.process(&input);
+/// assert_eq!(output.len(), input.len());
+/// assert!(output.iter().all(|&s| s.unsigned_abs() <= 16423));
+/// assert_eq!(limiter.activations(), 1);
+/// ```
+#[derive(Debug)]
+pub struct PeakLimiter {
+    /// 閾値（i16のフルスケールに対する振幅）
+    threshold: f32,
+    lookahead: usize,
+    attack_coeff: f32,
+    release_coeff: f32,
+    /// ルックアヘッド分遅らせるディレイライン（長さは常に `lookahead`）
+    delay_line: VecDeque<i16>,
+    /// ルックアヘッド区間の振幅の最大値を求めるための単調減少キュー（サンプル番号, 振幅）
+    peaks: VecDeque<(u64, f32)>,
+    /// 次に入力するサンプルの番号
+    position: u64,
+    gain: f32,
+    engaged: bool,
+    activations: u64,
+}
+
+impl PeakLimiter {
+    pub fn new(threshold_db: f32, release_ms: u32, lookahead_ms: u32, sample_rate: u32) -> Self {
+        let lookahead = (lookahead_ms.min(MAX_LIMITER_LOOKAHEAD_MS) as u64 * sample_rate as u64 / 1000) as usize;
+        let release_samples = release_ms as f32 * sample_rate as f32 / 1000.0;
+        Self {
+            threshold: 10f32.powf(threshold_db.min(0.0) / 20.0) * i16::MAX as f32,
+            lookahead,
+            // ルックアヘッドの間にゲインをほぼ（e^-4 まで）目標に近づける
+            attack_coeff: if lookahead > 0 { (-4.0 / lookahead as f32).exp() } else { 0.0 },
+            release_coeff: if release_samples > 0.0 { (-1.0 / release_samples).exp() } else { 0.0 },
+            delay_line: VecDeque::from(vec![0; lookahead]),
+            peaks: VecDeque::new(),
+            position: 0,
+            gain: 1.0,
+            engaged: false,
+            activations: 0,
+        }
+    }
+
+    /// ルックアヘッドによる出力の遅延（サンプル数）
+    pub fn latency_samples(&self) -> usize {
+        self.lookahead
+    }
+
+    /// ゲインを下げ始めた回数（作動してからゲインが戻るまでを1回と数える）
+    pub fn activations(&self) -> u64 {
+        self.activations
+    }
+
+    /// チャンクにリミッタをかけて返す（出力の長さは入力と同じ）
+    pub fn process(&mut self, samples: &[i16]) -> Vec<i16> {
+        let mut output = Vec::with_capacity(samples.len());
+        for &sample in samples {
+            let amplitude = (sample as f32).abs();
+            while self.peaks.back().is_some_and(|&(_, peak)| peak <= amplitude) {
+                self.peaks.pop_back();
+            }
+            self.peaks.push_back((self.position, amplitude));
+            // 区間は、これから出力するサンプル（`lookahead` 個前）から今回の入力まで
+            while self
+                .peaks
+                .front()
+                .is_some_and(|&(index, _)| index + (self.lookahead as u64) < self.position)
+            {
+                self.peaks.pop_front();
+            }
+            self.position += 1;
+
+            let peak = self.peaks.front().map_or(0.0, |&(_, peak)| peak);
+            let target = if peak > self.threshold { self.threshold / peak } else { 1.0 };
+            let coeff = if target < self.gain { self.attack_coeff } else { self.release_coeff };
+            self.gain = target + (self.gain - target) * coeff;
+
+            let engaged = self.gain < ENGAGED_GAIN;
+            if engaged && !self.engaged {
+                self.activations += 1;
+            }
+            self.engaged = engaged;
+
+            self.delay_line.push_back(sample);
+            let delayed = self.delay_line.pop_front().unwrap_or_default();
+            let limited = (delayed as f32 * self.gain).clamp(-self.threshold, self.threshold);
+            output.push(limited.round() as i16);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| (amplitude * (i as f32 * 2.0 * std::f32::consts::PI * 440.0 / 16000.0).sin()) as i16)
+            .collect()
+    }
+
+    #[test]
+    fn test_below_threshold_only_delayed() {
+        let mut limiter = PeakLimiter::new(-1.0, 100, 2, 16000);
+        assert_eq!(limiter.latency_samples(), 32);
+        let input = sine(10000.0, 1600);
+        // チャンクの境界をまたいでも、ルックアヘッド分遅れるだけで波形は変えない
+        let mut output = limiter.process(&input[..1000]);
+        output.extend(limiter.process(&input[1000..]));
+        assert!(output[..32].iter().all(|&s| s == 0));
+        assert_eq!(&output[32..], &input[..1600 - 32]);
+        assert_eq!(limiter.activations(), 0);
+    }
+
+    #[test]
+    fn test_limits_sudden_peak_smoothly() {
+        let mut limiter = PeakLimiter::new(-6.0, 20, 2, 16000);
+        let threshold = (10f32.powf(-6.0 / 20.0) * i16::MAX as f32).round() as i16;
+        let mut input = sine(8000.0, 800);
+        input.extend(sine(32000.0, 400));
+        input.extend(sine(8000.0, 3200));
+        let output = limiter.process(&input);
+
+        assert!(output.iter().all(|&s| s.abs() <= threshold));
+        // 閾値で切り詰める（ハードクリップ）場合と違い、閾値に張り付くのは各周期の頂点付近のみ
+        let clamped = output.iter().filter(|&&s| s.abs() == threshold).count();
+        let hard_clipped = input.iter().filter(|&&s| s.abs() >= threshold).count();
+        assert!(clamped * 10 < hard_clipped, "{} / {}", clamped, hard_clipped);
+        // ピークの前の区間は変えない
+        assert_eq!(&output[32..700], &input[..700 - 32]);
+        // ピークが過ぎた後はリリース時間をかけて戻る（急に戻さない）
+        let after_peak = 1200 + 32;
+        let ratio = |i: usize| output[i] as f32 / input[i - 32] as f32;
+        let recovering = (after_peak..after_peak + 100).find(|&i| input[i - 32].abs() > 4000).unwrap();
+        assert!(ratio(recovering) < 0.95);
+        assert!((ratio(2400 - 10) - 1.0).abs() < 0.05);
+        assert_eq!(limiter.activations(), 1);
+
+        // 2度目のピークで2回目の作動
+        limiter.process(&sine(32000.0, 400));
+        assert_eq!(limiter.activations(), 2);
+    }
+}
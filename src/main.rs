@@ -1,34 +1,122 @@
-mod audio_input;
-mod audio_output;
-mod aws_transcribe;
-mod buffer;
-mod channel_processor;
-mod config;
-mod flac_encoder;
-mod transcribe;
-mod transcribe_backend;
-mod tui;
-mod tui_state;
-mod types;
-mod vad;
-mod wav_writer;
-mod whisper_api;
-
 use anyhow::{Context, Result};
-use audio_input::AudioInput;
-use audio_output::AudioOutput;
-use channel_processor::ChannelProcessor;
-use config::Config;
+use dcr_transcribe::audio_input::AudioInput;
+use dcr_transcribe::audio_output::{ActiveChannelFollower, AudioOutput, MonitorRoute};
+use dcr_transcribe::audit::AuditSink;
+use dcr_transcribe::aws_transcribe::StreamQuotaCheck;
+use dcr_transcribe::cost::CostSummary;
+use dcr_transcribe::db::SqliteSink;
+use dcr_transcribe::broadcast::TranscriptBroadcaster;
+use dcr_transcribe::channel_detect;
+use dcr_transcribe::channel_processor::{self, ChannelProcessor, RecordingSuspendReason};
+use dcr_transcribe::config::{Config, TRANSCRIPT_HISTORY_WARN_THRESHOLD, TranscribeBackendType};
+use dcr_transcribe::encryption::{self, RecordingKey};
+use dcr_transcribe::explain;
+use dcr_transcribe::feedback::FeedbackWriter;
+use dcr_transcribe::grpc;
+use dcr_transcribe::input_recovery::{InputWatchdog, RecoveryAction};
+use dcr_transcribe::memory::{MemorySurgeDetector, MemoryUsage};
+use dcr_transcribe::merge::TranscriptMerger;
+use dcr_transcribe::session_report::SessionReporter;
+use dcr_transcribe::storage;
+use dcr_transcribe::summary::{OpenAiSummaryModel, Summarizer, SummaryTarget};
+use dcr_transcribe::timeline::{self, Timeline, TimelineEventKind};
+use dcr_transcribe::timezone::OutputTimeZone;
+use dcr_transcribe::transcribe_backend::StreamLimiter;
+use dcr_transcribe::tui::TuiApp;
+use dcr_transcribe::tui_state::{BackendSwitchStatus, TuiState};
+use dcr_transcribe::types::{PauseGap, TranscriptResult};
+use dcr_transcribe::upload::{self, UploadSink};
+use dcr_transcribe::verify;
+use dcr_transcribe::wav_writer::FilenameTemplate;
 use env_logger::Env;
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::path::Path;
 use std::sync::{
     Arc, Mutex,
     atomic::{AtomicBool, Ordering},
 };
 use tokio::sync::mpsc;
-use tui::TuiApp;
-use tui_state::TuiState;
+
+/// 有効チャンネル数と Amazon Transcribe の同時ストリーム数クォータの照合（AWS バックエンド以外は None）
+fn stream_quota_check(config: &Config) -> Option<StreamQuotaCheck> {
+    (config.transcribe.backend == TranscribeBackendType::Aws).then(|| {
+        let channels = config.channels.iter().filter(|c| c.enabled).count();
+        StreamQuotaCheck::new(channels, &config.transcribe)
+    })
+}
+
+/// 確定結果を JSONL（ログ）・データベース・外部配信に出力
+///
+/// `timeline_events` が有効なら、JSONLの行に `"type":"transcript"` を付ける。
+fn output_final(
+    result: &TranscriptResult,
+    db_sink: Option<&SqliteSink>,
+    broadcaster: Option<&TranscriptBroadcaster>,
+    timeline_events: bool,
+) {
+    let json = if timeline_events {
+        timeline::typed_json_line("transcript", result)
+    } else {
+        serde_json::to_string(result)
+    };
+    if let Ok(json) = json {
+        log::info!("{}", json);
+    }
+    if let Some(db_sink) = db_sink {
+        db_sink.record(result);
+    }
+    if let Some(broadcaster) = broadcaster {
+        broadcaster.publish(result);
+    }
+}
+
+/// チャンネルID -> プロセッサ
+type ProcessorMap = tokio::sync::Mutex<std::collections::HashMap<usize, Arc<tokio::sync::Mutex<ChannelProcessor>>>>;
+
+/// 入力ストリームの復帰処理（`InputWatchdog` の結果に従う）
+///
+/// 異常を検出したら全チャンネルの録音を中断してストリームを再構築し、
+/// 入力が戻ったら新しいファイルで録音を再開する。
+async fn recover_input(action: RecoveryAction, audio_input: &mut AudioInput, processors_map: &ProcessorMap) {
+    let processors: Vec<_> = processors_map.lock().await.values().cloned().collect();
+    match action {
+        RecoveryAction::Lost(fault) => {
+            log::warn!("{}。録音を中断し、音声入力ストリームを再構築します", fault.describe());
+            for processor in &processors {
+                let mut proc = processor.lock().await;
+                if let Err(e) = proc.suspend_recording(RecordingSuspendReason::InputRecovery) {
+                    log::error!("チャンネル {}: 録音の中断エラー: {}", proc.channel_id(), e);
+                }
+            }
+        }
+        RecoveryAction::Retry(attempt) => {
+            log::warn!("音声入力が戻らないため、ストリームを再構築します（{}回目）", attempt);
+        }
+        RecoveryAction::GiveUp(attempts) => {
+            log::error!(
+                "音声入力ストリームを{}回再構築しても入力が戻りませんでした。再構築を中止します（入力が戻れば録音を再開します）",
+                attempts
+            );
+            return;
+        }
+        RecoveryAction::Recovered { attempts, downtime_secs } => {
+            log::info!(
+                "音声入力が復帰しました（再構築 {}回, 中断 {:.1}秒）。新しいファイルで録音を再開します",
+                attempts,
+                downtime_secs
+            );
+            for processor in &processors {
+                processor.lock().await.resume_recording(RecordingSuspendReason::InputRecovery);
+            }
+            return;
+        }
+    }
+
+    if let Err(e) = audio_input.restart() {
+        log::error!("音声入力ストリームの再構築に失敗: {:#}", e);
+    }
+}
 
 /// ログファイルに書き込むためのWriter
 struct LogWriter(Arc<Mutex<std::fs::File>>);
@@ -58,22 +146,84 @@ async fn main() -> Result<()> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info"))
         .format_timestamp_millis()
         .filter_module("flacenc", log::LevelFilter::Off)
+        // ChannelProcessor はチャンネルごとの log_level で自前で判定する
+        .filter_module("dcr_transcribe::channel_processor", log::LevelFilter::Trace)
         .target(env_logger::Target::Pipe(Box::new(log_writer)))
         .init();
 
     // コマンドライン引数をパース
     let args: Vec<String> = std::env::args().collect();
 
-    // デバイス一覧表示モード
+    // デバイス一覧表示モード（入力デバイスは設定ファイルの [audio] host のものを列挙）
     if args.len() > 1 && args[1] == "--show-interfaces" {
+        let config_path = if args.len() > 2 {
+            &args[2]
+        } else {
+            "config.toml"
+        };
+        let config = Config::load_or_default(config_path)?;
         println!("=== 入力デバイス ===");
-        AudioInput::list_devices()?;
+        AudioInput::list_devices(config.audio.host.as_deref())?;
         println!();
         println!("=== 出力デバイス ===");
         AudioOutput::list_devices()?;
         return Ok(());
     }
 
+    // 入力チャンネルの信号検出モード（録音・文字起こしは行わない）
+    if args.len() > 1 && args[1] == "--detect-channels" {
+        let config_path = if args.len() > 2 {
+            &args[2]
+        } else {
+            "config.toml"
+        };
+        let config = Config::load_or_default(config_path)?;
+        println!(
+            "入力を{}秒間調べています...",
+            config.detect.duration_secs
+        );
+        let report = channel_detect::detect(&config.audio, &config.detect).await?;
+        report.print();
+        return Ok(());
+    }
+
+    // 起動前チェックモード（設定の検証とデバイスの照合のみ。録音・文字起こしは行わない）
+    if args.len() > 1 && args[1] == "--check" {
+        let config_path = if args.len() > 2 {
+            &args[2]
+        } else {
+            "config.toml"
+        };
+        let config = Config::load_or_default(config_path)?;
+        println!("[OK] 設定ファイル: {}", config_path);
+
+        let check = AudioInput::new(&config.audio)?.check_channel_count()?;
+        match check.warning() {
+            None => println!(
+                "[OK] 入力チャンネル数: 設定 {}ch / デバイス {}ch",
+                check.configured, check.device
+            ),
+            Some(warning) => {
+                println!("[{}] {}", if config.audio.strict_channels { "NG" } else { "WARN" }, warning);
+                if config.audio.strict_channels {
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        if let Some(check) = stream_quota_check(&config) {
+            match check.warning() {
+                None => println!(
+                    "[OK] 同時ストリーム数: 最大 {} / クォータ {}",
+                    check.peak_streams(),
+                    check.quota
+                ),
+                Some(warning) => println!("[WARN] {}", warning),
+            }
+        }
+        return Ok(());
+    }
+
     // 設定ファイル生成モード
     if args.len() > 1 && args[1] == "--generate-config" {
         let config_path = if args.len() > 2 {
@@ -86,6 +236,52 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // 設定の説明モード（録音・デバイスのオープンは行わない）
+    if args.len() > 1 && args[1] == "--explain-config" {
+        let config_path = if args.len() > 2 {
+            &args[2]
+        } else {
+            "config.toml"
+        };
+        print!("{}", explain::explain_file(config_path)?);
+        return Ok(());
+    }
+
+    // 録音WAVの整合性チェックモード
+    if args.len() > 1 && args[1] == "--verify-recordings" {
+        let dir = if args.len() > 2 {
+            &args[2]
+        } else {
+            "./recordings"
+        };
+        let report = verify::verify_directory(dir)?;
+        report.print();
+        if report.corrupt_files().next().is_some() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // 暗号化した録音ファイルの復号モード（鍵は設定ファイルの [output.encryption] から読み込む）
+    if args.len() > 1 && args[1] == "--decrypt" {
+        let Some(path) = args.get(2) else {
+            anyhow::bail!("使い方: dcr-transcribe --decrypt <FILE.enc> [config.toml]");
+        };
+        let config_path = args.get(3).map(String::as_str).unwrap_or("config.toml");
+        let config = Config::load_or_default(config_path)?;
+        let Some(encryption) = &config.output.encryption else {
+            anyhow::bail!("{} に [output.encryption] が設定されていません", config_path);
+        };
+        let key = RecordingKey::load(encryption)?;
+        let (output_path, summary) = encryption::decrypt_file(Path::new(path), &key)?;
+        println!("復号しました: {} ({})", output_path.display(), storage::format_bytes(summary.bytes));
+        if !summary.complete {
+            println!("[WARN] ファイルが途中で終わっています（録音中に異常終了した可能性があります）");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // 設定ファイルのパス
     let config_path = if args.len() > 1 && !args[1].starts_with("--") {
         &args[1]
@@ -93,11 +289,27 @@ async fn main() -> Result<()> {
         "config.toml"
     };
 
-    // 設定を読み込み
-    let config = Config::load_or_default(config_path)?;
+    // 設定を読み込む（設定値の検証は `Config::from_file` で行う。デフォルト設定は常に検証を通る）
+    let config = match Config::load_or_default(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("設定エラー ({}): {:#}", config_path, e);
+            eprintln!("設定エラー ({}): {:#}", config_path, e);
+            eprintln!("設定を修正してから再度起動してください（--explain-config で実効設定を確認できます）");
+            std::process::exit(1);
+        }
+    };
 
     log::info!("dcr-transcribe を起動します");
     log::info!("設定: {:?}", config);
+    if let Some(preset) = config.describe_audio_preset() {
+        log::info!("{}", preset);
+    }
+
+    // 一斉接続で同時ストリーム数のクォータを超えそうなら警告する（起動は続ける）
+    if let Some(warning) = stream_quota_check(&config).and_then(|check| check.warning()) {
+        log::warn!("{}", warning);
+    }
 
     // Ctrl+C ハンドラを設定
     let running = Arc::new(AtomicBool::new(true));
@@ -108,14 +320,104 @@ async fn main() -> Result<()> {
     })?;
 
     // TUI状態を作成
-    let tui_state = TuiState::new();
+    let tui_state = TuiState::new().with_max_transcript_history(config.ui.max_transcript_history);
+    if config.cost.enabled {
+        tui_state.set_cost_currency(&config.cost.currency);
+    }
+    let history_views = config.channels.iter().filter(|c| c.enabled).count() + config.groups.len();
+    let history_bytes = config.ui.estimated_transcript_history_bytes(history_views);
+    if config.ui.max_transcript_history > TRANSCRIPT_HISTORY_WARN_THRESHOLD {
+        log::warn!(
+            "[ui] max_transcript_history = {} は大きすぎます。確定結果の保持に最大 約{} を使い、TUIの描画も重くなります",
+            config.ui.max_transcript_history,
+            storage::format_bytes(history_bytes as u64)
+        );
+    } else {
+        log::debug!(
+            "TUIの確定結果保持: {}件 × {} ビュー (最大 約{})",
+            config.ui.max_transcript_history,
+            history_views,
+            storage::format_bytes(history_bytes as u64)
+        );
+    }
 
     // 全チャンネル共通の start_time を作成
     let start_time = std::time::SystemTime::now();
 
+    // 録音ファイルのアップロードキューを起動（設定時のみ）
+    let upload = match &config.upload {
+        Some(upload_config) => {
+            let store = upload::create_object_store(upload_config)
+                .await
+                .context("アップロード先の初期化に失敗")?;
+            log::info!(
+                "録音ファイルのアップロードを有効化: bucket={}, prefix={}",
+                upload_config.bucket,
+                upload_config.prefix
+            );
+            Some(UploadSink::spawn(upload_config, store))
+        }
+        None => None,
+    };
+
+    // 監査ログの書き込みワーカーを起動（設定時のみ）
+    let audit = match &config.audit {
+        Some(audit_config) => {
+            let timezone = OutputTimeZone::parse(&config.output.timezone)?;
+            log::info!("監査ログを有効化: {}", audit_config.file);
+            Some(AuditSink::spawn(audit_config, timezone)?)
+        }
+        None => None,
+    };
+
+    // 録音の開始・終了や接続・切断を文字起こし結果と同じJSONL出力に書き込む（設定時のみ）
+    let timeline_events = config.output.timeline_events;
+    let timeline = if timeline_events {
+        let timeline = Timeline::new(start_time, OutputTimeZone::parse(&config.output.timezone)?);
+        timeline.emit(&timeline.event(TimelineEventKind::SessionStart, None, start_time));
+        Some(timeline)
+    } else {
+        None
+    };
+
+    // 確定結果のデータベース保存ワーカーを起動（設定時のみ）
+    let db = match &config.db {
+        Some(db_config) => {
+            log::info!("確定結果をデータベースに保存: {}", db_config.sqlite_path);
+            Some(SqliteSink::spawn(db_config)?)
+        }
+        None => None,
+    };
+
+    // 文字起こし結果の外部配信層（gRPC・要約の有効時のみ）
+    let broadcaster =
+        (config.grpc.is_some() || config.summary.enabled).then(TranscriptBroadcaster::new);
+
+    // チャンネルグループをTUI状態に追加（統合ビュー用）
+    for group in &config.groups {
+        log::info!("チャンネルグループ {}: メンバー {:?}", group.name, group.channels);
+        tui_state.add_group(group.name.clone(), group.channels.clone());
+    }
+
     // チャンネルプロセッサを作成
+    let filename_template = FilenameTemplate::parse(&config.output.filename_template, &config.session)?;
+    let encryption_key = config
+        .output
+        .encryption
+        .as_ref()
+        .map(RecordingKey::load)
+        .transpose()?;
+    if encryption_key.is_some() {
+        log::info!("録音ファイルを暗号化して保存します（*.wav.enc）");
+    }
+    let stream_limiter = config.transcribe.max_concurrent_streams.map(|limit| {
+        log::info!("同時ストリーム数の上限: {}", limit);
+        StreamLimiter::new(limit)
+    });
     let mut processors = Vec::new();
     let mut channel_senders = Vec::new();
+    // メモリ使用量の集計でキューの残量を見るための参照（キューを閉じさせないよう弱参照で持つ）
+    let mut input_queues = Vec::new();
 
     for channel_config in &config.channels {
         if !channel_config.enabled {
@@ -125,8 +427,11 @@ async fn main() -> Result<()> {
 
         // TUI状態にチャンネルを追加
         tui_state.add_channel(channel_config.id, channel_config.name.clone());
+        tui_state.set_display_order(channel_config.id, channel_config.display_order);
+        tui_state.set_symbol(channel_config.id, channel_config.symbol.clone());
 
         let (tx, rx) = mpsc::channel(128);
+        input_queues.push((channel_config.id, tx.downgrade()));
         channel_senders.push(tx);
 
         let mut processor = ChannelProcessor::new(
@@ -135,6 +440,7 @@ async fn main() -> Result<()> {
             &config.buffer,
             &config.transcribe,
             config.whisper.as_ref(),
+            &config.flac,
             &config.output,
             config.audio.sample_rate,
             start_time,
@@ -149,35 +455,140 @@ async fn main() -> Result<()> {
 
         // TUI状態を設定
         processor.set_tui_state(tui_state.clone());
+        processor.set_warmup_ms(config.audio.warmup_ms);
+        processor.set_clip_detection(&config.clip);
+        processor.set_session_metadata(&config.session);
+        processor.set_filename_template(filename_template.clone());
+        if let Some(key) = &encryption_key {
+            processor.set_encryption_key(key.clone());
+        }
+        if config.ui.show_waveform {
+            processor.enable_waveform_preview();
+        }
+        if config.output.sidecar_metadata {
+            processor.enable_sidecar_metadata();
+        }
+        if config.cost.enabled {
+            processor.enable_cost_tracking(&config.cost);
+        }
+        processor.set_internal_format(config.audio.internal_format);
+        processor.enable_limiter(&config.dsp);
+        processor.set_postprocess(channel_config.resolve_postprocess(&config.postprocess));
+        processor.set_vad_observe_thresholds(&config.debug.vad_observe_thresholds);
+        if config.output.save_clips {
+            processor.enable_segment_clips(Path::new(&config.output.wav_output_dir).join("clips"))?;
+        }
+
+        if let Some(group) = config.group_of(channel_config.id) {
+            processor.set_group(group.name.clone());
+        }
+
+        if let Some(limiter) = &stream_limiter {
+            processor.set_stream_limiter(limiter.clone());
+        }
+        if let Some((audit_sink, _)) = &audit {
+            processor.set_audit_sink(audit_sink.clone());
+        }
+        if let Some(timeline) = &timeline {
+            processor.set_timeline(timeline.clone());
+        }
+        if let Some((upload_sink, _)) = &upload {
+            processor.set_upload_sink(upload_sink.clone());
+        }
 
         processors.push((rx, processor));
     }
 
-    // 各チャンネルプロセッサを開始
-    for (_, processor) in &mut processors {
+    // 録音に必要なディスク容量を見積もる（WAVを保存しない場合は不要）
+    // 初回起動でも出力先のボリュームを調べられるよう、先に録音ディレクトリを作成しておく
+    if config.output.save_wav {
+        std::fs::create_dir_all(&config.output.wav_output_dir)
+            .with_context(|| format!("録音ディレクトリの作成に失敗: {}", config.output.wav_output_dir))?;
+        let estimate = storage::estimate_recording_size(
+            processors.len(),
+            config.audio.sample_rate,
+            16,
+            config.output.expected_recording_hours,
+        );
+        log::info!(
+            "録音容量の見積もり: {}/時間, {:.1}時間で {}",
+            storage::format_bytes(estimate.bytes_per_hour),
+            config.output.expected_recording_hours,
+            storage::format_bytes(estimate.total_bytes)
+        );
+        match storage::available_space(&config.output.wav_output_dir) {
+            Ok(free) => {
+                log::info!(
+                    "{} の空き容量: {}",
+                    config.output.wav_output_dir,
+                    storage::format_bytes(free)
+                );
+                if free < estimate.total_bytes {
+                    log::warn!(
+                        "空き容量が想定録音時間分に足りません（約 {:.1} 時間で枯渇）",
+                        free as f64 / estimate.bytes_per_hour.max(1) as f64
+                    );
+                }
+            }
+            Err(e) => log::warn!("{:#}", e),
+        }
+    } else {
+        log::info!("WAV保存は無効です（[output] save_wav = false）。文字起こしのみ行います");
+    }
+
+    // 各チャンネルプロセッサを開始（録音ファイルを開くタイミングはチャンネルごとにずらせる）
+    let channel_count = processors.len();
+    for (index, (_, processor)) in processors.iter_mut().enumerate() {
+        processor.set_recording_start_delay_ms(config.output.recording_start_delay_ms(index, channel_count));
         processor.start().await?;
     }
 
     // AudioInputを作成して開始
     let mut audio_input = AudioInput::new(&config.audio)?;
+    audio_input.set_log_conversion_stats(config.debug.log_resampler_stats);
     audio_input.start(channel_senders)?;
 
-    // AudioOutputを作成して開始
-    let output_device = if config.audio.output_device_id == "default" {
-        None
-    } else {
-        Some(config.audio.output_device_id.as_str())
-    };
-    let mut audio_output = AudioOutput::new(output_device, config.audio.sample_rate)?;
-    let audio_output_tx = audio_output.start()?;
+    // モニタ出力ごとにAudioOutputを作成して開始（[[audio.monitors]] がなければ output_device_id の1つ）
+    if !config.audio.monitors.is_empty() && config.audio.output_device_id != "default" {
+        log::warn!("[[audio.monitors]] が定義されているため [audio] output_device_id / output_volume は使いません");
+    }
+    let mut audio_outputs = Vec::new();
+    let mut monitors = Vec::new();
+    for (index, monitor) in config.audio.effective_monitors().iter().enumerate() {
+        let output_device = (monitor.device_id != "default").then_some(monitor.device_id.as_str());
+        let mut audio_output = AudioOutput::new(output_device, config.audio.sample_rate)?;
+        audio_output.set_pan(monitor.pan);
+        let audio_output_tx = audio_output.start()?;
+        let volume = audio_output.set_volume(monitor.volume);
+        log::info!(
+            "モニタ {}: 出力デバイス={}, チャンネル={}, 音量={:.0}%, 定位={}",
+            index,
+            monitor.device_id,
+            monitor.channel.map_or_else(|| "TUIの選択".to_string(), |id| id.to_string()),
+            volume * 100.0,
+            monitor.pan.map_or_else(|| "モノラル".to_string(), |pan| format!("{:+.1}", pan))
+        );
+        monitors.push((MonitorRoute::new(monitor.channel), audio_output_tx, audio_output.volume_control()));
+        audio_outputs.push(audio_output);
+    }
+    // TUIの音量はTUIの選択に従うモニタの音量（最初のモニタの値を初期値にする）
+    if let Some((_, _, volume)) = monitors.iter().find(|(route, _, _)| route.follows_selection()) {
+        tui_state.set_output_volume(volume.get());
+    }
 
     log::info!("録音を開始しました (Ctrl+C または 'q' で停止)");
 
     // TUIタスクを起動
     let tui_state_clone = tui_state.clone();
     let running_clone = running.clone();
+    let low_confidence_threshold = config.ui.low_confidence_threshold;
+    let show_waveform = config.ui.show_waveform;
+    let feedback = FeedbackWriter::new(config.output.feedback_path());
     let tui_task = tokio::spawn(async move {
-        let mut tui_app = TuiApp::new(tui_state_clone, running_clone);
+        let mut tui_app = TuiApp::new(tui_state_clone, running_clone)
+            .with_low_confidence_threshold(low_confidence_threshold)
+            .with_waveform(show_waveform)
+            .with_feedback(feedback);
         if let Err(e) = tui_app.run().await {
             log::error!("TUIエラー: {}", e);
         }
@@ -187,9 +598,7 @@ async fn main() -> Result<()> {
     let mut tasks = Vec::new();
 
     // プロセッサをマップに格納（channel_id -> processor）
-    let processors_map = Arc::new(tokio::sync::Mutex::new(
-        std::collections::HashMap::<usize, Arc<tokio::sync::Mutex<ChannelProcessor>>>::new(),
-    ));
+    let processors_map: Arc<ProcessorMap> = Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new()));
 
     for (mut rx, processor) in processors {
         let channel_id = processor.channel_id();
@@ -238,7 +647,11 @@ async fn main() -> Result<()> {
         // タスク2: 文字起こし結果取得スレッド
         let processor_clone = processor.clone();
         let running_clone = running.clone();
+        let broadcaster_clone = broadcaster.clone();
+        let db_sink = db.as_ref().map(|(sink, _)| sink.clone());
+        let mut merger = config.merge.enabled.then(|| TranscriptMerger::new(&config.merge));
         let transcript_task = tokio::spawn(async move {
+            use std::time::Instant;
             while running_clone.load(Ordering::SeqCst) {
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
@@ -253,22 +666,71 @@ async fn main() -> Result<()> {
                         // TUI状態に追加（フィラーワード削除は内部で実行）
                         proc.add_transcript_to_tui(&result);
 
-                        // 途中状態でなく、かつフィラーワード削除後に内容がある場合のみログ出力
+                        // 部分結果はそのまま外部へ配信
+                        if result.is_partial {
+                            if let Some(broadcaster) = &broadcaster_clone {
+                                broadcaster.publish(&result);
+                            }
+                            if let Some(merger) = merger.as_mut() {
+                                merger.observe_partial(&result, Instant::now());
+                            }
+                        }
+
+                        // 途中状態でなく、かつ後処理（フィラーワード削除など）後に内容がある場合のみログ出力
                         if !result.is_partial {
-                            let cleaned_text = ChannelProcessor::remove_filler_words(&result.text);
-                            if !cleaned_text.is_empty() && !ChannelProcessor::is_punctuation_only(&cleaned_text) {
-                                // クリーニング後のテキストでログ出力
-                                result.text = cleaned_text;
-                                if let Ok(json) = serde_json::to_string(&result) {
-                                    log::info!("{}", json);
+                            // 表示用テキスト（後処理済み）と原文の両方を出力する
+                            proc.postprocess_result(&mut result);
+                            if !result.text.is_empty() && !ChannelProcessor::is_punctuation_only(&result.text) {
+                                if !result.meets_min_confidence(proc.postprocess().min_confidence) {
+                                    // 低確信度の結果はJSONL出力から除外（TUIには表示済み）
+                                    log::debug!(
+                                        "チャンネル {}: 確信度 {:.2} が閾値未満のためJSONL出力から除外: '{}'",
+                                        channel_id,
+                                        result.confidence.unwrap_or_default(),
+                                        result.text
+                                    );
+                                    // 低確信度の結果は結合しない（保留中の結果を先に出力して順序を保つ）
+                                    if let Some(pending) = merger.as_mut().and_then(TranscriptMerger::flush) {
+                                        output_final(
+                                            &pending,
+                                            db_sink.as_ref(),
+                                            broadcaster_clone.as_ref(),
+                                            timeline_events,
+                                        );
+                                    }
+                                    if let Some(broadcaster) = &broadcaster_clone {
+                                        broadcaster.publish(&result);
+                                    }
+                                } else {
+                                    // 結合が有効なら、続きの結果を待ってから出力する
+                                    let ready = match merger.as_mut() {
+                                        Some(merger) => merger.push(result, Instant::now()),
+                                        None => Some(result),
+                                    };
+                                    if let Some(ready) = ready {
+                                        output_final(
+                                            &ready,
+                                            db_sink.as_ref(),
+                                            broadcaster_clone.as_ref(),
+                                            timeline_events,
+                                        );
+                                    }
                                 }
                             }
                         }
                     }
                 }
+
+                // 保留の期限が過ぎた結合待ちの結果を出力
+                if let Some(ready) = merger.as_mut().and_then(|merger| merger.flush_expired(Instant::now())) {
+                    output_final(&ready, db_sink.as_ref(), broadcaster_clone.as_ref(), timeline_events);
+                }
             }
 
-            // 停止処理
+            // 停止処理（結合待ちの結果を出力してから停止）
+            if let Some(pending) = merger.as_mut().and_then(TranscriptMerger::flush) {
+                output_final(&pending, db_sink.as_ref(), broadcaster_clone.as_ref(), timeline_events);
+            }
             let mut proc = processor_clone.lock().await;
             if let Err(e) = proc.stop().await {
                 log::error!("プロセッサ停止エラー: {}", e);
@@ -277,57 +739,388 @@ async fn main() -> Result<()> {
         tasks.push(transcript_task);
     }
 
-    // タスク3: 選択チャンネルを監視して音声出力を切り替え
+    // タスク3: 選択チャンネルと出力音量を監視して各モニタの音声出力に反映
+    // （auto_follow_active なら、選択がない間は発話中のチャンネルに自動で切り替える。
+    //   担当チャンネルが固定されたモニタは選択によらず同じチャンネルを出力する）
+    let auto_follow_active = config.audio.auto_follow_active;
     let processors_map_clone = processors_map.clone();
     let tui_state_clone = tui_state.clone();
     let running_clone = running.clone();
-    let audio_output_tx_clone = audio_output_tx.clone();
     let output_monitor_task = tokio::spawn(async move {
-        let mut last_selected: Option<usize> = None;
+        let mut monitors = monitors;
+        let mut last_manual: Option<usize> = None;
+        let mut follower = auto_follow_active.then(ActiveChannelFollower::new);
+        let mut last_volume = tui_state_clone.get_output_volume();
 
         while running_clone.load(Ordering::SeqCst) {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-            let current_selected = tui_state_clone.get_selected_channel_for_output();
+            // 手動選択中は自動切替を止め、選択が解除されたら出力なしの状態から追従し直す
+            let manual = tui_state_clone.get_selected_channel_for_output();
+            if let Some(follower) = follower.as_mut() {
+                let auto = if manual.is_none() {
+                    if last_manual.is_some() {
+                        follower.reset_to(None);
+                    }
+                    follower.update(&tui_state_clone.voice_activity(), std::time::Instant::now())
+                } else {
+                    None
+                };
+                tui_state_clone.set_auto_output_channel(auto);
+            }
+            last_manual = manual;
+
+            let current_selected = tui_state_clone.get_output_channel();
 
-            // 選択が変更された場合
-            if current_selected != last_selected {
-                log::info!("音声出力チャンネル変更: {:?} -> {:?}", last_selected, current_selected);
+            for (index, (route, audio_output_tx, _)) in monitors.iter_mut().enumerate() {
+                // 出力チャンネルが変更された場合
+                let Some((old, new)) = route.update(current_selected) else {
+                    continue;
+                };
+                log::info!("モニタ {} の音声出力チャンネル変更: {:?} -> {:?}", index, old, new);
 
                 let map = processors_map_clone.lock().await;
 
                 // 前のチャンネルから音声出力を解除
-                if let Some(old_id) = last_selected {
-                    if let Some(processor) = map.get(&old_id) {
-                        let mut proc = processor.lock().await;
-                        proc.clear_audio_output();
-                    }
+                if let Some(processor) = old.and_then(|id| map.get(&id)) {
+                    processor.lock().await.clear_audio_output(index);
                 }
 
                 // 新しいチャンネルに音声出力を設定
-                if let Some(new_id) = current_selected {
-                    if let Some(processor) = map.get(&new_id) {
-                        let mut proc = processor.lock().await;
-                        proc.set_audio_output(audio_output_tx_clone.clone());
-                    }
+                if let Some(processor) = new.and_then(|id| map.get(&id)) {
+                    processor.lock().await.set_audio_output(index, audio_output_tx.clone());
                 }
+            }
 
-                last_selected = current_selected;
+            // TUIで変更されたモニタ音量を、TUIの選択に従うモニタに反映
+            let current_volume = tui_state_clone.get_output_volume();
+            if current_volume != last_volume {
+                for (_, _, volume) in monitors.iter().filter(|(route, _, _)| route.follows_selection()) {
+                    volume.set(current_volume);
+                }
+                last_volume = current_volume;
+                log::info!("出力音量変更: {:.0}%", last_volume * 100.0);
             }
         }
     });
     tasks.push(output_monitor_task);
 
-    // メインループ: 停止を待つ
+    // タスク4: 一時停止/再開の切り替え（TUIの p キー、SIGUSR1）を全チャンネルに反映
+    #[cfg(unix)]
+    {
+        let tui_state_clone = tui_state.clone();
+        let mut sigusr1 = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined1())?;
+        tokio::spawn(async move {
+            while sigusr1.recv().await.is_some() {
+                tui_state_clone.toggle_paused();
+            }
+        });
+    }
+    // SIGHUP で設定ファイルを読み直し、文字起こしバックエンドの種類の変更を反映する
+    // （それ以外の設定の変更は再起動まで反映しない）
+    // 終了時にチャンネルを解放できるよう、チャンネルの一覧は弱参照で持つ
+    #[cfg(unix)]
+    {
+        let config_path = config_path.to_string();
+        let processors_map_weak = Arc::downgrade(&processors_map);
+        let tui_state_clone = tui_state.clone();
+        let mut current_backend = config.transcribe.backend.clone();
+        let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while sighup.recv().await.is_some() {
+                let Some(processors_map) = processors_map_weak.upgrade() else {
+                    // 終了処理中
+                    break;
+                };
+                log::info!("SIGHUP を受信: 設定ファイルを再読み込みします: {}", config_path);
+                let new_config = match Config::from_file(&config_path) {
+                    Ok(new_config) => new_config,
+                    Err(e) => {
+                        log::error!("設定ファイルの再読み込みに失敗（現在の設定を継続）: {:#}", e);
+                        continue;
+                    }
+                };
+                if new_config.transcribe.backend == current_backend {
+                    log::info!("文字起こしバックエンドの変更はありません（その他の設定の変更は再起動後に反映されます）");
+                    continue;
+                }
+
+                let label = new_config.transcribe.backend.label();
+                log::info!(
+                    "文字起こしバックエンドを {} から {} に切り替えます",
+                    current_backend.label(),
+                    label
+                );
+                let processors: Vec<_> = processors_map
+                    .lock()
+                    .await
+                    .iter()
+                    .map(|(id, processor)| (*id, processor.clone()))
+                    .collect();
+                drop(processors_map);
+                let mut all_switched = true;
+                for (channel_id, processor) in processors {
+                    // バックエンドの作成は音声処理を止めないようロックの外で行う
+                    match channel_processor::create_transcribe_backend(
+                        channel_id,
+                        &new_config.transcribe,
+                        new_config.whisper.as_ref(),
+                        &new_config.flac,
+                        start_time,
+                    )
+                    .await
+                    {
+                        Ok(backend) => processor.lock().await.switch_backend(label, backend),
+                        Err(e) => {
+                            all_switched = false;
+                            log::error!(
+                                "チャンネル {}: {} の作成に失敗したため {} を継続します: {:#}",
+                                channel_id,
+                                label,
+                                current_backend.label(),
+                                e
+                            );
+                            let previous = current_backend.label().to_string();
+                            tui_state_clone.update_channel(channel_id, |channel| {
+                                channel.backend_switch = Some(BackendSwitchStatus::Failed(previous));
+                            });
+                        }
+                    }
+                }
+                // 失敗したチャンネルがあれば、次の SIGHUP で再試行する
+                if all_switched {
+                    current_backend = new_config.transcribe.backend;
+                }
+            }
+        });
+    }
+
+    let processors_map_clone = processors_map.clone();
+    let tui_state_clone = tui_state.clone();
+    let running_clone = running.clone();
+    let timezone = OutputTimeZone::parse(&config.output.timezone)?;
+    let pause_task = tokio::spawn(async move {
+        let mut paused_at: Option<std::time::SystemTime> = None;
+
+        while running_clone.load(Ordering::SeqCst) {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+            let paused = tui_state_clone.is_paused();
+            if paused == paused_at.is_some() {
+                continue;
+            }
+
+            let processors: Vec<_> = processors_map_clone.lock().await.values().cloned().collect();
+            if paused {
+                log::info!("全チャンネルの録音・文字起こしを一時停止");
+                paused_at = Some(std::time::SystemTime::now());
+                for processor in &processors {
+                    let mut proc = processor.lock().await;
+                    if let Err(e) = proc.pause().await {
+                        log::error!("チャンネル {}: 一時停止エラー: {}", proc.channel_id(), e);
+                    }
+                }
+            } else {
+                for processor in &processors {
+                    let mut proc = processor.lock().await;
+                    if let Err(e) = proc.resume() {
+                        log::error!("チャンネル {}: 再開エラー: {}", proc.channel_id(), e);
+                    }
+                }
+                // 結果の時刻の空白を説明できるよう、一時停止区間をJSONL出力に記録する
+                if let Some(paused_at) = paused_at.take() {
+                    let gap = PauseGap::new(start_time, paused_at, std::time::SystemTime::now(), &timezone);
+                    log::info!("全チャンネルの録音・文字起こしを再開 ({:.1}秒間停止)", gap.duration_secs);
+                    let json = if timeline_events {
+                        timeline::typed_json_line("event", &gap)
+                    } else {
+                        serde_json::to_string(&gap)
+                    };
+                    if let Ok(json) = json {
+                        log::info!("{}", json);
+                    }
+                }
+            }
+        }
+    });
+    tasks.push(pause_task);
+
+    // タスク5: 空き容量をTUIのステータスバーに表示し、閾値（min_free_bytes > 0 の場合）を下回ったら録音を中断
+    // 各チャンネルは WAV を finalize して書き込みを止める（文字起こしは継続）。閾値の2倍まで回復したら新しいファイルで再開する
+    if config.output.save_wav {
+        let processors_map_clone = processors_map.clone();
+        let running_clone = running.clone();
+        let tui_state_clone = tui_state.clone();
+        let wav_output_dir = config.output.wav_output_dir.clone();
+        let min_free_bytes = config.output.min_free_bytes;
+        let disk_monitor_task = tokio::spawn(async move {
+            let mut warned = false;
+            let mut suspended = false;
+            while running_clone.load(Ordering::SeqCst) {
+                let available = storage::available_space(&wav_output_dir);
+                tui_state_clone.set_disk_free_bytes(available.as_ref().ok().copied());
+                match available {
+                    Ok(_) if min_free_bytes == 0 => {}
+                    Ok(free) if free < min_free_bytes => {
+                        if !suspended {
+                            log::error!(
+                                "空き容量が閾値を下回ったため録音を中断します（文字起こしは継続）: {} < {}",
+                                storage::format_bytes(free),
+                                storage::format_bytes(min_free_bytes)
+                            );
+                            let processors: Vec<_> = processors_map_clone.lock().await.values().cloned().collect();
+                            for processor in &processors {
+                                let mut proc = processor.lock().await;
+                                if let Err(e) = proc.suspend_recording(RecordingSuspendReason::LowDiskSpace) {
+                                    log::error!("チャンネル {}: 録音の中断エラー: {}", proc.channel_id(), e);
+                                }
+                            }
+                            suspended = true;
+                        }
+                    }
+                    Ok(free) if free < min_free_bytes.saturating_mul(2) => {
+                        if !warned && !suspended {
+                            log::warn!(
+                                "空き容量が残りわずかです: {} (停止閾値 {})",
+                                storage::format_bytes(free),
+                                storage::format_bytes(min_free_bytes)
+                            );
+                            warned = true;
+                        }
+                    }
+                    Ok(free) => {
+                        warned = false;
+                        if suspended {
+                            log::info!(
+                                "空き容量が回復したため録音を再開します: {}",
+                                storage::format_bytes(free)
+                            );
+                            let processors: Vec<_> = processors_map_clone.lock().await.values().cloned().collect();
+                            for processor in &processors {
+                                processor.lock().await.resume_recording(RecordingSuspendReason::LowDiskSpace);
+                            }
+                            suspended = false;
+                        }
+                    }
+                    Err(e) if min_free_bytes > 0 && !warned => {
+                        log::warn!("{:#}", e);
+                        warned = true;
+                    }
+                    Err(_) => {}
+                }
+
+                // ステータスバーの表示を1秒ごとに更新し、停止要求にも素早く反応できるよう短い間隔で running を確認する
+                for _ in 0..10 {
+                    if !running_clone.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                }
+            }
+        });
+        tasks.push(disk_monitor_task);
+    }
+
+    // タスク5b: 各コンポーネントの概算メモリ使用量を定期的に集計してTUIとログに表示し、急増を警告
+    if config.memory.interval_secs > 0 {
+        let interval = tokio::time::Duration::from_secs(config.memory.interval_secs);
+        let mut detector = MemorySurgeDetector::new(config.memory.surge_warn_bytes());
+        let processors_map_clone = processors_map.clone();
+        let tui_state_clone = tui_state.clone();
+        let running_clone = running.clone();
+        let memory_task = tokio::spawn(async move {
+            let mut next = tokio::time::Instant::now();
+            while running_clone.load(Ordering::SeqCst) {
+                if tokio::time::Instant::now() < next {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                    continue;
+                }
+                next += interval;
+
+                let processors: Vec<_> = processors_map_clone.lock().await.values().cloned().collect();
+                let mut usage = MemoryUsage {
+                    tui_history: tui_state_clone.history_bytes() as u64,
+                    ..MemoryUsage::default()
+                };
+                for processor in processors {
+                    let proc = processor.lock().await;
+                    let channel_id = proc.channel_id();
+                    let queued = input_queues
+                        .iter()
+                        .find(|(id, _)| *id == channel_id)
+                        .and_then(|(_, queue)| queue.upgrade())
+                        .map_or(0, |queue| queue.max_capacity() - queue.capacity());
+                    usage += proc.memory_usage(queued);
+                    let disconnect_buffer = (proc.disconnect_buffer_bytes() as u64, proc.disconnect_buffer_secs());
+                    drop(proc);
+                    tui_state_clone.update_channel(channel_id, |channel| {
+                        channel.disconnect_buffer = disconnect_buffer;
+                    });
+                }
+
+                tui_state_clone.set_memory_usage(usage);
+                log::info!("概算メモリ使用量: {}", usage.describe());
+                if let Some(warning) = detector.check(&usage) {
+                    log::warn!("{}", warning);
+                }
+            }
+        });
+        tasks.push(memory_task);
+    }
+
+    // タスク6: gRPC で文字起こし結果を配信
+    if let (Some(grpc_config), Some(broadcaster)) = (&config.grpc, &broadcaster) {
+        let bind = grpc_config.bind.clone();
+        let broadcaster = broadcaster.clone();
+        let running_clone = running.clone();
+        let grpc_task = tokio::spawn(async move {
+            // 配信に失敗しても録音・文字起こしは継続する
+            if let Err(e) = grpc::serve(&bind, broadcaster, running_clone).await {
+                log::error!("{:#}", e);
+            }
+        });
+        tasks.push(grpc_task);
+    }
+
+    // タスク7: 確定結果を一定時間・一定発話数ごとに要約
+    if let (true, Some(broadcaster)) = (config.summary.enabled, &broadcaster) {
+        let targets = SummaryTarget::from_config(&config);
+        log::warn!(
+            "要約を有効化: {} 件の単位ごとに LLM API（{}）を呼び出すため利用料金が発生します（時間間隔分だけで 最大 約{}回/時、{}件ごとの要約は別途）",
+            targets.len(),
+            config.summary.model,
+            config.summary.requests_per_hour(targets.len()),
+            config.summary.max_utterances
+        );
+        let api_key = config.summary.resolve_api_key(config.whisper.as_ref()).unwrap_or_default();
+        let model = OpenAiSummaryModel::new(api_key, &config.summary.model)?;
+        let channel_ids: Vec<usize> = targets.iter().flat_map(|t| t.channels.clone()).collect();
+        let summarizer = Summarizer::new(&config.summary, targets, Arc::new(model));
+        tasks.push(summarizer.spawn(
+            broadcaster.subscribe(channel_ids),
+            Some(tui_state.clone()),
+            running.clone(),
+        ));
+    }
+
+    // メインループ: 停止を待ちながら、入力ストリームの停止・無信号・スリープ復帰を監視して再構築する
+    let mut input_watchdog = InputWatchdog::new(&config.audio, std::time::Instant::now());
     while running.load(Ordering::SeqCst) {
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let action = input_watchdog.poll(std::time::Instant::now(), std::time::SystemTime::now(), audio_input.activity());
+        if let Some(action) = action {
+            recover_input(action, &mut audio_input, &processors_map).await;
+        }
     }
 
     // クリーンアップ
     log::info!("停止処理を開始します...");
 
     audio_input.stop();
-    audio_output.stop();
+    for audio_output in &mut audio_outputs {
+        audio_output.stop();
+    }
 
     // TUIタスクの完了を待つ
     let _ = tui_task.await;
@@ -336,6 +1129,71 @@ async fn main() -> Result<()> {
     for task in tasks {
         let _ = task.await;
     }
+    if let Some(timeline) = &timeline {
+        timeline.emit(&timeline.event(TimelineEventKind::SessionEnd, None, std::time::SystemTime::now()));
+    }
+
+    // 送信音声時間と概算コストのサマリ
+    if config.cost.enabled {
+        let mut processors: Vec<_> = processors_map
+            .lock()
+            .await
+            .iter()
+            .map(|(channel_id, processor)| (*channel_id, processor.clone()))
+            .collect();
+        processors.sort_by_key(|(channel_id, _)| *channel_id);
+        let mut summaries = Vec::new();
+        for (_, processor) in processors {
+            let proc = processor.lock().await;
+            if let (Some(summary), Some(breakdown)) = (proc.cost_summary(), proc.cost_breakdown()) {
+                log::info!("チャンネル {} ({}): 送信音声 {}", proc.channel_id(), proc.channel_name(), breakdown);
+                summaries.push(summary);
+            }
+        }
+        let total = CostSummary::total(&summaries);
+        log::info!(
+            "送信音声の合計: {:.1}分, 概算コスト {}",
+            total.sent_secs / 60.0,
+            config.cost.format(total.cost)
+        );
+    }
+
+    // チャンネルごとの録音実績をセッションレポートに保存
+    if config.output.session_report {
+        let mut channels = Vec::new();
+        for processor in processors_map.lock().await.values() {
+            channels.push(processor.lock().await.session_stats());
+        }
+        let reporter = SessionReporter::new(&config.output, OutputTimeZone::parse(&config.output.timezone)?, start_time);
+        match reporter.write(channels, std::time::SystemTime::now(), |cost| config.cost.format(cost)) {
+            Ok(paths) => {
+                for path in paths {
+                    log::info!("セッションレポートを保存しました: {:?}", path);
+                }
+            }
+            Err(e) => log::error!("セッションレポートの保存に失敗: {:#}", e),
+        }
+    }
+
+    // 停止時に finalize したファイルのアップロード完了を待つ
+    drop(processors_map);
+    if let Some((upload_sink, upload_task)) = upload {
+        drop(upload_sink);
+        log::info!("録音ファイルのアップロード完了を待機しています...");
+        let _ = upload_task.await;
+    }
+
+    // 監査ログの残りを書き終えるのを待つ
+    if let Some((audit_sink, audit_task)) = audit {
+        drop(audit_sink);
+        let _ = audit_task.await;
+    }
+
+    // データベースへの書き込み（コミット）が終わるのを待つ
+    if let Some((db_sink, db_task)) = db {
+        drop(db_sink);
+        let _ = db_task.await;
+    }
 
     log::info!("dcr-transcribe を終了しました");
 
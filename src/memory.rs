@@ -0,0 +1,161 @@
+use crate::storage::format_bytes;
+use std::ops::AddAssign;
+
+/// 音声パイプラインの各コンポーネントが使用している概算メモリ量（バイト）
+///
+/// サンプルやテキストの実データ量から見積もるため、アロケータの管理領域や
+/// 確保済みで未使用の容量は含まない。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// リトライ用バッファ（`[buffer]`）
+    pub audio_buffers: u64,
+    /// 切断中にためている送信待ちの音声（`send_buffered_on_reconnect`）
+    pub disconnect_buffers: u64,
+    /// 入力からチャンネル処理へのキューと、文字起こしバックエンドへの送信キュー
+    pub queues: u64,
+    /// TUIの確定結果の履歴
+    pub tui_history: u64,
+}
+
+impl MemoryUsage {
+    /// 合計
+    pub fn total(&self) -> u64 {
+        self.audio_buffers + self.disconnect_buffers + self.queues + self.tui_history
+    }
+
+    /// ログ出力用の内訳
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dcr_transcribe::memory::MemoryUsage;
+    /// let usage = MemoryUsage {
+    ///     audio_buffers: 9_600_000,
+    ///     disconnect_buffers: 0,
+    ///     queues: 6_400,
+    ///     tui_history: 120_000,
+    /// };
+    /// assert_eq!(
+    ///     usage.describe(),
+    ///     "合計 9.73 MB (リトライ用バッファ 9.60 MB, 切断中バッファ 0 B, キュー 6.40 KB, TUI履歴 120.00 KB)"
+    /// );
+    /// ```
+    pub fn describe(&self) -> String {
+        format!(
+            "合計 {} (リトライ用バッファ {}, 切断中バッファ {}, キュー {}, TUI履歴 {})",
+            format_bytes(self.total()),
+            format_bytes(self.audio_buffers),
+            format_bytes(self.disconnect_buffers),
+            format_bytes(self.queues),
+            format_bytes(self.tui_history)
+        )
+    }
+}
+
+impl AddAssign for MemoryUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.audio_buffers += other.audio_buffers;
+        self.disconnect_buffers += other.disconnect_buffers;
+        self.queues += other.queues;
+        self.tui_history += other.tui_history;
+    }
+}
+
+/// 定期的な集計の間のメモリ使用量の急増を検出する
+///
+/// 前回の集計から `surge_bytes` 以上増えた場合に警告文を返す。
+/// 切断中バッファは長時間の切断で際限なく増えるため、増加分の大半を占める場合は明示する。
+#[derive(Debug, Clone)]
+pub struct MemorySurgeDetector {
+    surge_bytes: u64,
+    previous: Option<MemoryUsage>,
+}
+
+impl MemorySurgeDetector {
+    pub fn new(surge_bytes: u64) -> Self {
+        Self {
+            surge_bytes,
+            previous: None,
+        }
+    }
+
+    /// 今回の集計結果を記録し、急増していれば警告文を返す
+    pub fn check(&mut self, usage: &MemoryUsage) -> Option<String> {
+        let previous = self.previous.replace(*usage)?;
+        let increase = usage.total().saturating_sub(previous.total());
+        if self.surge_bytes == 0 || increase < self.surge_bytes {
+            return None;
+        }
+
+        let disconnect_increase = usage.disconnect_buffers.saturating_sub(previous.disconnect_buffers);
+        let cause = if disconnect_increase * 2 > increase {
+            "（主に切断中バッファ。文字起こしバックエンドへの接続が長時間失敗していないか確認してください）"
+        } else {
+            ""
+        };
+        Some(format!(
+            "メモリ使用量が急増しました: {} -> {} (+{}){}",
+            format_bytes(previous.total()),
+            format_bytes(usage.total()),
+            format_bytes(increase),
+            cause
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_and_sum() {
+        let mut usage = MemoryUsage {
+            audio_buffers: 100,
+            disconnect_buffers: 20,
+            queues: 3,
+            tui_history: 4,
+        };
+        assert_eq!(usage.total(), 127);
+
+        usage += MemoryUsage {
+            audio_buffers: 1,
+            ..MemoryUsage::default()
+        };
+        assert_eq!(usage.audio_buffers, 101);
+        assert_eq!(usage.total(), 128);
+    }
+
+    #[test]
+    fn test_surge_detector() {
+        let mut detector = MemorySurgeDetector::new(1_000_000);
+        let base = MemoryUsage {
+            audio_buffers: 9_600_000,
+            ..MemoryUsage::default()
+        };
+
+        // 初回は比較対象がない
+        assert!(detector.check(&base).is_none());
+        let small = MemoryUsage {
+            tui_history: 500_000,
+            ..base
+        };
+        assert!(detector.check(&small).is_none());
+
+        // 切断中バッファが膨らんだ
+        let surge = MemoryUsage {
+            disconnect_buffers: 2_000_000,
+            ..small
+        };
+        let warning = detector.check(&surge).unwrap();
+        assert!(warning.contains("+2.00 MB"));
+        assert!(warning.contains("切断中バッファ"));
+
+        // 減少は警告しない
+        assert!(detector.check(&base).is_none());
+
+        // 0 なら検出しない
+        let mut disabled = MemorySurgeDetector::new(0);
+        disabled.check(&base);
+        assert!(disabled.check(&surge).is_none());
+    }
+}
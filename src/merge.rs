@@ -0,0 +1,260 @@
+use crate::config::MergeConfig;
+use crate::types::TranscriptResult;
+use std::time::{Duration, Instant};
+
+/// 音声時刻の比較で許容する誤差（秒）
+const GAP_EPSILON_SECS: f64 = 0.001;
+
+/// 分断された確定結果を1つの発話に結合する（`[merge]`）
+///
+/// ストリームの予防的な切り替えやセッション分割で途切れた発話は、音声時刻（`timestamp_seconds`）が
+/// セッションをまたいで連続するため、前の結果の終了と次の結果の開始の間隔で判定できる。
+/// 確定結果を1件保留し、`hold_ms` の間に同じチャンネルの連続する結果が届けば結合する。
+/// 連続する部分結果が届いている間は、続きの確定結果を待つため保留を延長する。
+/// チャンネルごとに1つ作成する。
+#[derive(Debug)]
+pub struct TranscriptMerger {
+    config: MergeConfig,
+    /// 保留中の確定結果と、出力する期限
+    pending: Option<(TranscriptResult, Instant)>,
+}
+
+impl TranscriptMerger {
+    pub fn new(config: &MergeConfig) -> Self {
+        Self {
+            config: config.clone(),
+            pending: None,
+        }
+    }
+
+    /// 確定結果を追加し、出力が決まった結果を返す
+    ///
+    /// 保留中の結果と結合できれば結合して保留を続け、できなければ保留中の結果を返して入れ替える。
+    pub fn push(&mut self, result: TranscriptResult, now: Instant) -> Option<TranscriptResult> {
+        let deadline = now + self.hold();
+        match self.pending.take() {
+            Some((pending, _)) if self.can_merge(&pending, &result) => {
+                self.pending = Some((merge(pending, result), deadline));
+                None
+            }
+            previous => {
+                self.pending = Some((result, deadline));
+                previous.map(|(pending, _)| pending)
+            }
+        }
+    }
+
+    /// 部分結果を観測し、保留中の結果の続きであれば保留を延長する
+    pub fn observe_partial(&mut self, partial: &TranscriptResult, now: Instant) {
+        let hold = self.hold();
+        let max_gap_secs = self.max_gap_secs();
+        if let Some((pending, deadline)) = &mut self.pending {
+            let contiguous = pending.channel == partial.channel
+                && end_seconds(pending).is_some_and(|end| {
+                    let gap = partial.timestamp_seconds - end;
+                    (-GAP_EPSILON_SECS..=max_gap_secs + GAP_EPSILON_SECS).contains(&gap)
+                });
+            if contiguous {
+                *deadline = (*deadline).max(now + hold);
+            }
+        }
+    }
+
+    /// 保留の期限が過ぎた結果を返す
+    pub fn flush_expired(&mut self, now: Instant) -> Option<TranscriptResult> {
+        match &self.pending {
+            Some((_, deadline)) if *deadline <= now => self.flush(),
+            _ => None,
+        }
+    }
+
+    /// 保留中の結果を期限を待たずに返す（終了時や、結合しない結果を出力する前）
+    pub fn flush(&mut self) -> Option<TranscriptResult> {
+        self.pending.take().map(|(pending, _)| pending)
+    }
+
+    fn hold(&self) -> Duration {
+        Duration::from_millis(self.config.hold_ms)
+    }
+
+    fn max_gap_secs(&self) -> f64 {
+        self.config.max_gap_ms as f64 / 1000.0
+    }
+
+    /// 同じチャンネル・同じ検出言語で、音声時刻が連続し、結合後も長すぎない場合のみ結合する
+    fn can_merge(&self, previous: &TranscriptResult, next: &TranscriptResult) -> bool {
+        let (Some(previous_end), Some(next_end)) = (end_seconds(previous), end_seconds(next)) else {
+            return false;
+        };
+        let gap = next.timestamp_seconds - previous_end;
+        previous.channel == next.channel
+            && previous.detected_language == next.detected_language
+            && (-GAP_EPSILON_SECS..=self.max_gap_secs() + GAP_EPSILON_SECS).contains(&gap)
+            && next_end - previous.timestamp_seconds <= self.config.max_merged_secs + GAP_EPSILON_SECS
+    }
+}
+
+/// 結果の音声の終了時刻（秒、発話の長さが分からなければ `None`）
+fn end_seconds(result: &TranscriptResult) -> Option<f64> {
+    result
+        .audio_duration_seconds
+        .map(|duration| result.timestamp_seconds + duration)
+}
+
+/// 2つの確定結果を1つの発話にする
+///
+/// 開始時刻は前の結果、出力時刻・通し番号・チャンネル名などは後の結果のものを使う。
+/// 確信度は発話の長さで重み付けした平均（どちらかが不明なら `None`）。
+/// 音声クリップは発話区間との対応が曖昧になるため付けない。
+fn merge(previous: TranscriptResult, next: TranscriptResult) -> TranscriptResult {
+    let previous_duration = previous.audio_duration_seconds.unwrap_or_default();
+    let next_duration = next.audio_duration_seconds.unwrap_or_default();
+    let end = next.timestamp_seconds + next_duration;
+    let confidence = match (previous.confidence, next.confidence) {
+        (Some(a), Some(b)) if previous_duration + next_duration > 0.0 => Some(
+            ((a as f64 * previous_duration + b as f64 * next_duration) / (previous_duration + next_duration)) as f32,
+        ),
+        (Some(a), Some(b)) => Some((a + b) / 2.0),
+        _ => None,
+    };
+
+    TranscriptResult {
+        text: join_text(&previous.text, &next.text),
+        timestamp_seconds: previous.timestamp_seconds,
+        audio_duration_seconds: Some((end - previous.timestamp_seconds).max(0.0)),
+        promoted_from_partial: previous.promoted_from_partial || next.promoted_from_partial,
+        raw_text: raw_text(&previous, &next),
+        postprocess: Vec::new(),
+        merged: true,
+        confidence,
+        clip_path: None,
+        ..next
+    }
+}
+
+/// 後処理前の原文を連結する（どちらも後処理していなければ `None`）
+fn raw_text(previous: &TranscriptResult, next: &TranscriptResult) -> Option<String> {
+    if previous.raw_text.is_none() && next.raw_text.is_none() {
+        return None;
+    }
+    let raw = |result: &TranscriptResult| result.raw_text.clone().unwrap_or_else(|| result.text.clone());
+    Some(join_text(&raw(previous), &raw(next)))
+}
+
+/// テキストを連結する（英数字どうしの境目だけ空白で区切る）
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::merge::join_text;
+/// assert_eq!(join_text("現在地は", "正面入口です"), "現在地は正面入口です");
+/// assert_eq!(join_text("gate", "A"), "gate A");
+/// ```
+pub fn join_text(previous: &str, next: &str) -> String {
+    let needs_space = previous.chars().last().is_some_and(|c| c.is_ascii_alphanumeric())
+        && next.chars().next().is_some_and(|c| c.is_ascii_alphanumeric());
+    if needs_space {
+        format!("{} {}", previous, next)
+    } else {
+        format!("{}{}", previous, next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MergeConfig {
+        MergeConfig {
+            enabled: true,
+            ..MergeConfig::default()
+        }
+    }
+
+    fn final_result(channel: usize, text: &str, start: f64, end: f64) -> TranscriptResult {
+        TranscriptResult::new_with_audio_time(channel, text.to_string(), false, None, start, Some(end))
+    }
+
+    #[test]
+    fn test_merge_contiguous_results() {
+        let mut merger = TranscriptMerger::new(&config());
+        let now = Instant::now();
+
+        let mut first = final_result(0, "現在地は", 10.0, 11.0);
+        first.confidence = Some(0.9);
+        first.raw_text = Some("えー現在地は".to_string());
+        let mut second = final_result(0, "正面入口です", 11.0, 14.0);
+        second.confidence = Some(0.5);
+        second.sequence = 2;
+        assert!(merger.push(first, now).is_none());
+        assert!(merger.push(second, now).is_none());
+
+        // 保留中は出力しない
+        assert!(merger.flush_expired(now + Duration::from_millis(1000)).is_none());
+        let merged = merger.flush_expired(now + Duration::from_millis(1500)).unwrap();
+        assert_eq!(merged.text, "現在地は正面入口です");
+        assert_eq!(merged.raw_text.as_deref(), Some("えー現在地は正面入口です"));
+        assert!(merged.merged);
+        assert_eq!(merged.timestamp_seconds, 10.0);
+        assert_eq!(merged.audio_duration_seconds, Some(4.0));
+        assert_eq!(merged.sequence, 2);
+        assert!((merged.confidence.unwrap() - 0.6).abs() < 1e-6);
+        assert!(merger.flush().is_none());
+    }
+
+    #[test]
+    fn test_does_not_merge_beyond_conditions() {
+        let mut merger = TranscriptMerger::new(&config());
+        let now = Instant::now();
+
+        // 間隔が max_gap_ms を超える
+        merger.push(final_result(0, "了解", 10.0, 11.0), now);
+        let out = merger.push(final_result(0, "次の件です", 11.5, 12.0), now).unwrap();
+        assert_eq!(out.text, "了解");
+        assert!(!out.merged);
+
+        // 別のチャンネル
+        let out = merger.push(final_result(1, "どうぞ", 12.1, 13.0), now).unwrap();
+        assert_eq!(out.text, "次の件です");
+
+        // 発話の長さが分からない
+        let unknown = TranscriptResult::new_with_audio_time(1, "以上".to_string(), false, None, 13.0, None);
+        assert_eq!(merger.push(unknown, now).unwrap().text, "どうぞ");
+        assert_eq!(merger.push(final_result(1, "です", 13.1, 14.0), now).unwrap().text, "以上");
+
+        // 結合後の長さが max_merged_secs を超える
+        merger.push(final_result(2, "長い発話", 0.0, 19.0), now);
+        assert_eq!(merger.push(final_result(2, "続き", 19.1, 21.0), now).unwrap().text, "長い発話");
+
+        // 検出言語が異なる
+        let mut english = final_result(2, "copy that", 21.1, 22.0);
+        english.detected_language = Some("en-US".to_string());
+        assert_eq!(merger.push(english, now).unwrap().text, "続き");
+        assert_eq!(merger.flush().unwrap().text, "copy that");
+    }
+
+    #[test]
+    fn test_partial_extends_hold() {
+        let mut merger = TranscriptMerger::new(&config());
+        let now = Instant::now();
+        merger.push(final_result(0, "現在地は", 10.0, 11.0), now);
+
+        // 続きの部分結果が届いている間は期限を延長する
+        let later = now + Duration::from_millis(1000);
+        let partial = TranscriptResult::new_with_audio_time(0, "正面".to_string(), true, None, 11.1, None);
+        merger.observe_partial(&partial, later);
+        assert!(merger.flush_expired(now + Duration::from_millis(2000)).is_none());
+
+        // 離れた部分結果では延長しない
+        let distant = TranscriptResult::new_with_audio_time(0, "別件".to_string(), true, None, 15.0, None);
+        merger.observe_partial(&distant, now + Duration::from_millis(2400));
+        assert!(merger.flush_expired(now + Duration::from_millis(2500)).is_some());
+    }
+
+    #[test]
+    fn test_join_text() {
+        assert_eq!(join_text("ゲート", "Aへ"), "ゲートAへ");
+        assert_eq!(join_text("gate", "A"), "gate A");
+        assert_eq!(join_text("", "了解"), "了解");
+    }
+}
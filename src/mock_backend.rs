@@ -0,0 +1,150 @@
+use crate::transcribe_backend::{MonoNormalizer, TranscribeAudio, TranscribeBackend};
+use crate::types::TranscriptResult;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// 1ストリームあたりの送受信チャンネルの容量
+const STREAM_CAPACITY: usize = 1024;
+
+/// 開始したストリーム1本分の記録
+struct MockStream {
+    audio_rx: mpsc::Receiver<TranscribeAudio>,
+    /// 実際のバックエンドと同じく、受信した音声をモノラルにする
+    normalizer: MonoNormalizer,
+    /// 受信済みのPCMチャンク（モノラルに正規化後、送信順）
+    received: Vec<Vec<i16>>,
+    result_tx: mpsc::Sender<TranscriptResult>,
+}
+
+#[derive(Default)]
+struct MockState {
+    streams: Vec<MockStream>,
+    /// 次の `start_stream` を失敗させる回数
+    fail_starts: u32,
+    /// `set_language` で設定された言語（設定順）
+    languages: Vec<String>,
+    /// `segments_by_vad` の戻り値
+    segments_by_vad: bool,
+}
+
+/// テスト用のインメモリ文字起こしバックエンド
+///
+/// ストリームごとに送られたPCMを記録し、`push_result` で任意の結果を返せる。
+/// クローンは状態を共有するため、`ChannelProcessor` に渡した後もテスト側から検査できる。
+#[derive(Clone)]
+pub struct MockBackend {
+    channel_id: usize,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockBackend {
+    pub fn new(channel_id: usize) -> Self {
+        Self {
+            channel_id,
+            state: Arc::new(Mutex::new(MockState::default())),
+        }
+    }
+
+    /// 次の `count` 回の `start_stream` を失敗させる（接続エラーの再現用）
+    pub fn fail_next_starts(&self, count: u32) {
+        self.state.lock().unwrap().fail_starts = count;
+    }
+
+    /// 発話区間ごとにストリームを区切るバックエンドとして振る舞う
+    pub fn set_segments_by_vad(&self, enabled: bool) {
+        self.state.lock().unwrap().segments_by_vad = enabled;
+    }
+
+    /// 開始されたストリーム数
+    pub fn stream_count(&self) -> usize {
+        self.state.lock().unwrap().streams.len()
+    }
+
+    /// `stream` 番目のストリームが受信したPCMチャンク（送信順）
+    pub fn received(&self, stream: usize) -> Vec<Vec<i16>> {
+        let mut state = self.state.lock().unwrap();
+        let stream = &mut state.streams[stream];
+        while let Ok(audio) = stream.audio_rx.try_recv() {
+            let samples = stream.normalizer.normalize(audio);
+            stream.received.push(samples);
+        }
+        stream.received.clone()
+    }
+
+    /// `stream` 番目のストリームの送信側が閉じられたか（切断されたか）
+    pub fn is_closed(&self, stream: usize) -> bool {
+        let state = self.state.lock().unwrap();
+        state.streams[stream].audio_rx.is_closed()
+    }
+
+    /// `set_language` で設定された言語（設定順）
+    pub fn languages(&self) -> Vec<String> {
+        self.state.lock().unwrap().languages.clone()
+    }
+
+    /// 最新のストリームから結果を返す
+    pub fn push_result(&self, result: TranscriptResult) {
+        let state = self.state.lock().unwrap();
+        let stream = state.streams.last().expect("ストリームが開始されていません");
+        stream.result_tx.try_send(result).expect("結果の送信に失敗");
+    }
+
+    /// `stream` 番目のストリームから結果を返す（切断後に届く結果の再現用）
+    pub fn push_result_to(&self, stream: usize, result: TranscriptResult) {
+        let state = self.state.lock().unwrap();
+        state.streams[stream].result_tx.try_send(result).expect("結果の送信に失敗");
+    }
+}
+
+#[async_trait]
+impl TranscribeBackend for MockBackend {
+    async fn start_stream(
+        &mut self,
+    ) -> Result<(mpsc::Sender<TranscribeAudio>, mpsc::Receiver<TranscriptResult>)> {
+        let mut state = self.state.lock().unwrap();
+        if state.fail_starts > 0 {
+            state.fail_starts -= 1;
+            bail!("MockBackend: 接続失敗（テスト用）");
+        }
+
+        let (audio_tx, audio_rx) = mpsc::channel(STREAM_CAPACITY);
+        let (result_tx, result_rx) = mpsc::channel(STREAM_CAPACITY);
+        state.streams.push(MockStream {
+            audio_rx,
+            normalizer: MonoNormalizer::new(self.channel_id),
+            received: Vec::new(),
+            result_tx,
+        });
+        Ok((audio_tx, result_rx))
+    }
+
+    fn set_language(&mut self, language: &str) {
+        self.state.lock().unwrap().languages.push(language.to_string());
+    }
+
+    fn segments_by_vad(&self) -> bool {
+        self.state.lock().unwrap().segments_by_vad
+    }
+
+    fn channel_id(&self) -> usize {
+        self.channel_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stereo_received_as_mono() {
+        let mut backend = MockBackend::new(0);
+        let (tx, _rx) = backend.start_stream().await.unwrap();
+
+        // 想定外のステレオ音声も、実際のバックエンドと同じ正規化でモノラルとして受け取る
+        tx.try_send(TranscribeAudio::new(vec![1000, 3000, -1000, -3000], 2)).unwrap();
+        tx.try_send(TranscribeAudio::mono(vec![7, 8])).unwrap();
+        assert_eq!(backend.received(0), vec![vec![2000, -2000], vec![7, 8]]);
+    }
+}
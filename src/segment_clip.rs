@@ -0,0 +1,197 @@
+use crate::encryption::RecordingKey;
+use crate::types::SampleI16;
+use crate::wav_writer::{WavFile, wav_extension};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 確定結果との対応付けのために保持する区間数
+const MAX_SEGMENTS: usize = 32;
+
+/// 書き出した発話区間
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    /// 区間の開始タイムスタンプ (UNIX_EPOCHからのナノ秒)
+    start_ns: u128,
+    /// 区間の終了タイムスタンプ（発話中なら `None`）
+    end_ns: Option<u128>,
+    /// クリップファイルのパス
+    path: PathBuf,
+}
+
+/// 発話区間（VADの音声区間）ごとのWAVクリップ書き出し
+///
+/// 書き出した区間の時刻を保持し、確定結果を音声の時刻から元の区間のクリップに対応付ける。
+pub struct SegmentClipWriter {
+    channel_id: usize,
+    output_dir: PathBuf,
+    spec: hound::WavSpec,
+    current: Option<WavFile>,
+    segments: VecDeque<Segment>,
+    /// クリップの暗号鍵（`[output.encryption]` が無効なら None）
+    encryption_key: Option<RecordingKey>,
+}
+
+impl SegmentClipWriter {
+    pub fn new<P: AsRef<Path>>(channel_id: usize, output_dir: P, sample_rate: u32) -> Result<Self> {
+        let output_dir = output_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&output_dir)
+            .with_context(|| format!("クリップ出力ディレクトリの作成に失敗: {:?}", output_dir))?;
+
+        Ok(Self {
+            channel_id,
+            output_dir,
+            spec: hound::WavSpec {
+                channels: 1,
+                sample_rate,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            },
+            current: None,
+            segments: VecDeque::new(),
+            encryption_key: None,
+        })
+    }
+
+    /// クリップを暗号化して `*.wav.enc` として保存する（次に開始するクリップから適用）
+    pub fn set_encryption_key(&mut self, key: RecordingKey) {
+        self.encryption_key = Some(key);
+    }
+
+    /// チャンクを処理し、音声区間の間だけクリップに書き込む
+    ///
+    /// `timestamp_ns` はチャンク先頭のタイムスタンプ (UNIX_EPOCHからのナノ秒)。
+    pub fn process(&mut self, is_voice: bool, samples: &[SampleI16], timestamp_ns: u128) -> Result<()> {
+        if !is_voice {
+            return self.finish(timestamp_ns);
+        }
+        if self.current.is_none() {
+            self.start_segment(timestamp_ns)?;
+        }
+
+        if let Some(writer) = &mut self.current {
+            writer
+                .write_samples(samples)
+                .with_context(|| "クリップへのサンプル書き込みに失敗")?;
+        }
+        Ok(())
+    }
+
+    /// 書き込み中の区間を `end_ns` で終了する（書き込み中でなければ何もしない）
+    pub fn finish(&mut self, end_ns: u128) -> Result<()> {
+        let Some(writer) = self.current.take() else {
+            return Ok(());
+        };
+        if let Some(segment) = self.segments.back_mut() {
+            segment.end_ns = Some(end_ns);
+        }
+        writer.finalize().with_context(|| "クリップのファイナライズに失敗")?;
+        Ok(())
+    }
+
+    /// 音声が `start_ns`～`end_ns` (UNIX_EPOCHからのナノ秒) の確定結果に対応するクリップのパス
+    ///
+    /// 結果の音声と重なる区間がちょうど1つの場合のみ返す。
+    /// 重なる区間がないか複数ある（曖昧な）場合は `None`。
+    pub fn clip_for(&self, start_ns: u128, end_ns: u128) -> Option<&Path> {
+        let mut candidates = self.segments.iter().filter(|segment| {
+            segment.start_ns <= end_ns && segment.end_ns.is_none_or(|segment_end_ns| start_ns <= segment_end_ns)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(segment), None) => Some(&segment.path),
+            _ => None,
+        }
+    }
+
+    fn start_segment(&mut self, start_ns: u128) -> Result<()> {
+        let started_at: DateTime<Local> = DateTime::from_timestamp_nanos(start_ns as i64).into();
+        let filename = format!(
+            "channel_{}_{}.{}",
+            self.channel_id,
+            started_at.format("%Y%m%d_%H%M%S_%3f"),
+            wav_extension(self.encryption_key.is_some())
+        );
+        let path = self.output_dir.join(filename);
+
+        let writer = WavFile::create(&path, self.spec, self.encryption_key.as_ref())
+            .with_context(|| format!("クリップの作成に失敗: {:?}", path))?;
+
+        self.current = Some(writer);
+        self.segments.push_back(Segment {
+            start_ns,
+            end_ns: None,
+            path,
+        });
+        while self.segments.len() > MAX_SEGMENTS {
+            self.segments.pop_front();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SegmentClipWriter {
+    fn drop(&mut self) {
+        if let Some(writer) = self.current.take() {
+            if let Err(e) = writer.finalize() {
+                log::error!("SegmentClipWriter のドロップ時にエラー: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: u128 = 1_000_000_000;
+    /// 2025-01-01T00:00:00Z
+    const BASE: u128 = 1_735_689_600 * SEC;
+
+    #[test]
+    fn test_writes_one_clip_per_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = SegmentClipWriter::new(0, dir.path(), 16000).unwrap();
+
+        writer.process(false, &[0; 160], BASE).unwrap();
+        writer.process(true, &[100; 160], BASE + SEC).unwrap();
+        writer.process(true, &[200; 160], BASE + 2 * SEC).unwrap();
+        writer.process(false, &[0; 160], BASE + 3 * SEC).unwrap();
+        writer.process(true, &[300; 160], BASE + 10 * SEC).unwrap();
+        writer.finish(BASE + 11 * SEC).unwrap();
+
+        let mut clips: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().path()).collect();
+        clips.sort();
+        assert_eq!(clips.len(), 2);
+        // 無音のチャンクは書き込まない
+        assert_eq!(hound::WavReader::open(&clips[0]).unwrap().len(), 320);
+        assert_eq!(hound::WavReader::open(&clips[1]).unwrap().len(), 160);
+    }
+
+    #[test]
+    fn test_clip_for_matches_by_audio_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = SegmentClipWriter::new(1, dir.path(), 16000).unwrap();
+
+        // 区間A: 1～3秒
+        writer.process(true, &[100; 16], BASE + SEC).unwrap();
+        writer.process(false, &[0; 16], BASE + 3 * SEC).unwrap();
+        let clip_a = writer.clip_for(BASE + SEC, BASE + 2 * SEC).unwrap().to_path_buf();
+        assert!(clip_a.starts_with(dir.path()));
+
+        // 区間と重ならない音声の結果は対応付けない
+        assert_eq!(writer.clip_for(BASE, BASE + SEC / 2), None);
+        assert_eq!(writer.clip_for(BASE + 4 * SEC, BASE + 5 * SEC), None);
+
+        // 区間B: 6秒～（発話中）
+        writer.process(true, &[100; 16], BASE + 6 * SEC).unwrap();
+        // 区間Aの結果は、区間Bの発話中に届いてもAに対応付ける
+        assert_eq!(writer.clip_for(BASE + 2 * SEC, BASE + 3 * SEC), Some(clip_a.as_path()));
+        let clip_b = writer.clip_for(BASE + 6 * SEC, BASE + 7 * SEC).unwrap().to_path_buf();
+        assert_ne!(clip_a, clip_b);
+        // 両方の区間にまたがる結果は曖昧
+        assert_eq!(writer.clip_for(BASE + 2 * SEC, BASE + 7 * SEC), None);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::config::{OutputConfig, SessionReportFormat};
+use crate::storage::format_bytes;
+use crate::timezone::OutputTimeZone;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// finalize した録音ファイル1つの実績
+///
+/// 時間はWAVヘッダに書いたサンプル数から、バイト数は finalize 直後の実ファイルのサイズから求める。
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RecordedFileStat {
+    pub path: String,
+    pub duration_secs: f64,
+    pub bytes: u64,
+}
+
+/// 1チャンネルのセッション中の録音実績
+///
+/// ファイルの分割（一時停止・入力の復帰・音声区間ごとの録音など）をまたいで、finalize したファイルをすべて含む。
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ChannelSessionStats {
+    pub channel: usize,
+    pub channel_name: String,
+    /// VADが音声区間を検出した回数
+    pub utterances: u64,
+    /// 概算コスト（`[cost]` が無効なら `None`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<f64>,
+    pub files: Vec<RecordedFileStat>,
+}
+
+impl ChannelSessionStats {
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        self.files.iter().map(|file| file.duration_secs).sum()
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.files.iter().map(|file| file.bytes).sum()
+    }
+}
+
+/// JSON出力の1チャンネル分（集計値とファイル一覧）
+#[derive(Debug, Serialize)]
+struct ChannelSummary<'a> {
+    channel: usize,
+    channel_name: &'a str,
+    file_count: usize,
+    duration_secs: f64,
+    bytes: u64,
+    utterances: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_cost: Option<f64>,
+    files: &'a [RecordedFileStat],
+}
+
+/// JSON出力の合計
+#[derive(Debug, Serialize)]
+struct Totals {
+    file_count: usize,
+    duration_secs: f64,
+    bytes: u64,
+    utterances: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_cost: Option<f64>,
+}
+
+/// 録音セッションの集計レポート（`[output] session_report`）
+#[derive(Debug, Clone)]
+pub struct SessionReport {
+    pub started_at: String,
+    pub ended_at: String,
+    /// チャンネルID順
+    pub channels: Vec<ChannelSessionStats>,
+}
+
+impl SessionReport {
+    pub fn new(started_at: String, ended_at: String, mut channels: Vec<ChannelSessionStats>) -> Self {
+        channels.sort_by_key(|stats| stats.channel);
+        Self {
+            started_at,
+            ended_at,
+            channels,
+        }
+    }
+
+    fn totals(&self) -> Totals {
+        let costs: Vec<f64> = self.channels.iter().filter_map(|stats| stats.estimated_cost).collect();
+        Totals {
+            file_count: self.channels.iter().map(ChannelSessionStats::file_count).sum(),
+            duration_secs: self.channels.iter().map(ChannelSessionStats::duration_secs).sum(),
+            bytes: self.channels.iter().map(ChannelSessionStats::bytes).sum(),
+            utterances: self.channels.iter().map(|stats| stats.utterances).sum(),
+            estimated_cost: (!costs.is_empty()).then(|| costs.iter().sum()),
+        }
+    }
+
+    /// JSON形式（集計値と、その元になったファイルごとの実績）
+    pub fn to_json(&self) -> Result<String> {
+        let channels: Vec<ChannelSummary> = self
+            .channels
+            .iter()
+            .map(|stats| ChannelSummary {
+                channel: stats.channel,
+                channel_name: &stats.channel_name,
+                file_count: stats.file_count(),
+                duration_secs: stats.duration_secs(),
+                bytes: stats.bytes(),
+                utterances: stats.utterances,
+                estimated_cost: stats.estimated_cost,
+                files: &stats.files,
+            })
+            .collect();
+        let report = serde_json::json!({
+            "started_at": self.started_at,
+            "ended_at": self.ended_at,
+            "channels": channels,
+            "total": self.totals(),
+        });
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
+    /// Markdown形式（チャンネルごとの集計表とファイル一覧）
+    ///
+    /// `format_cost` は概算コストの表示（通貨単位付き）。
+    pub fn to_markdown(&self, format_cost: impl Fn(f64) -> String) -> String {
+        let cost = |cost: Option<f64>| cost.map_or_else(|| "-".to_string(), &format_cost);
+        let mut md = String::from("# 録音セッションレポート\n\n");
+        let _ = writeln!(md, "- 開始: {}", self.started_at);
+        let _ = writeln!(md, "- 終了: {}", self.ended_at);
+        md.push_str("\n| チャンネル | ファイル数 | 総時間 | 総バイト | 発話回数 | 推定コスト |\n");
+        md.push_str("|---|---:|---:|---:|---:|---:|\n");
+        for stats in &self.channels {
+            let _ = writeln!(
+                md,
+                "| {} ({}) | {} | {} | {} | {} | {} |",
+                stats.channel,
+                stats.channel_name,
+                stats.file_count(),
+                format_duration(stats.duration_secs()),
+                format_bytes(stats.bytes()),
+                stats.utterances,
+                cost(stats.estimated_cost)
+            );
+        }
+        let totals = self.totals();
+        let _ = writeln!(
+            md,
+            "| 合計 | {} | {} | {} | {} | {} |",
+            totals.file_count,
+            format_duration(totals.duration_secs),
+            format_bytes(totals.bytes),
+            totals.utterances,
+            cost(totals.estimated_cost)
+        );
+
+        md.push_str("\n## 録音ファイル\n");
+        for stats in &self.channels {
+            let _ = writeln!(md, "\n### チャンネル {} ({})\n", stats.channel, stats.channel_name);
+            if stats.files.is_empty() {
+                md.push_str("- （なし）\n");
+            }
+            for file in &stats.files {
+                let _ = writeln!(
+                    md,
+                    "- `{}` {} ({} バイト)",
+                    file.path,
+                    format_duration(file.duration_secs),
+                    file.bytes
+                );
+            }
+        }
+        md
+    }
+}
+
+/// 秒数を `H:MM:SS` で表示
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::session_report::format_duration;
+/// assert_eq!(format_duration(3723.4), "1:02:03");
+/// assert_eq!(format_duration(59.6), "0:00:59");
+/// ```
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// 終了時にセッションの集計レポートを保存する（`[output] session_report`）
+#[derive(Debug)]
+pub struct SessionReporter {
+    dir: PathBuf,
+    format: SessionReportFormat,
+    timezone: OutputTimeZone,
+    started_at: SystemTime,
+}
+
+impl SessionReporter {
+    pub fn new(config: &OutputConfig, timezone: OutputTimeZone, started_at: SystemTime) -> Self {
+        Self {
+            dir: PathBuf::from(config.session_report_dir.as_deref().unwrap_or(&config.wav_output_dir)),
+            format: config.session_report_format,
+            timezone,
+            started_at,
+        }
+    }
+
+    /// レポートを作成して保存し、保存したファイルのパスを返す
+    ///
+    /// ファイル名はセッション開始時刻（ローカル時刻）から `session_report_YYYYMMDD_HHMMSS.{md,json}`。
+    pub fn write(
+        &self,
+        channels: Vec<ChannelSessionStats>,
+        ended_at: SystemTime,
+        format_cost: impl Fn(f64) -> String,
+    ) -> Result<Vec<PathBuf>> {
+        let report = SessionReport::new(
+            self.timezone.format_system_time(self.started_at),
+            self.timezone.format_system_time(ended_at),
+            channels,
+        );
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("セッションレポートの保存先の作成に失敗: {:?}", self.dir))?;
+        let stem = format!(
+            "session_report_{}",
+            DateTime::<Local>::from(self.started_at).format("%Y%m%d_%H%M%S")
+        );
+
+        let mut written = Vec::new();
+        if matches!(self.format, SessionReportFormat::Markdown | SessionReportFormat::Both) {
+            written.push(write_file(&self.dir, &stem, "md", &report.to_markdown(&format_cost))?);
+        }
+        if matches!(self.format, SessionReportFormat::Json | SessionReportFormat::Both) {
+            written.push(write_file(&self.dir, &stem, "json", &report.to_json()?)?);
+        }
+        Ok(written)
+    }
+}
+
+fn write_file(dir: &Path, stem: &str, extension: &str, content: &str) -> Result<PathBuf> {
+    let path = dir.join(format!("{}.{}", stem, extension));
+    fs::write(&path, content).with_context(|| format!("セッションレポートの書き込みに失敗: {:?}", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> Vec<ChannelSessionStats> {
+        vec![
+            ChannelSessionStats {
+                channel: 1,
+                channel_name: "警備".to_string(),
+                utterances: 3,
+                estimated_cost: Some(0.5),
+                files: vec![RecordedFileStat {
+                    path: "rec/channel_1.wav".to_string(),
+                    duration_secs: 60.0,
+                    bytes: 1_920_044,
+                }],
+            },
+            ChannelSessionStats {
+                channel: 0,
+                channel_name: "本部".to_string(),
+                utterances: 12,
+                estimated_cost: Some(1.25),
+                files: vec![
+                    RecordedFileStat {
+                        path: "rec/channel_0_a.wav".to_string(),
+                        duration_secs: 3600.0,
+                        bytes: 115_200_044,
+                    },
+                    RecordedFileStat {
+                        path: "rec/channel_0_b.wav".to_string(),
+                        duration_secs: 123.0,
+                        bytes: 3_936_044,
+                    },
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_report_aggregates_across_files() {
+        let report = SessionReport::new("start".to_string(), "end".to_string(), stats());
+        let md = report.to_markdown(|cost| format!("{:.2} USD", cost));
+        assert!(md.contains("| 0 (本部) | 2 | 1:02:03 | 119.14 MB | 12 | 1.25 USD |"));
+        assert!(md.contains("| 1 (警備) | 1 | 0:01:00 | 1.92 MB | 3 | 0.50 USD |"));
+        assert!(md.contains("| 合計 | 3 | 1:03:03 | 121.06 MB | 15 | 1.75 USD |"));
+        assert!(md.contains("- `rec/channel_0_b.wav` 0:02:03 (3936044 バイト)"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["channels"][0]["channel"], 0);
+        assert_eq!(json["channels"][0]["file_count"], 2);
+        assert_eq!(json["channels"][0]["bytes"], 119_136_088u64);
+        assert_eq!(json["total"]["duration_secs"], 3783.0);
+        assert_eq!(json["total"]["utterances"], 15);
+    }
+
+    #[test]
+    fn test_reporter_writes_selected_formats() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let config = OutputConfig {
+            wav_output_dir: temp_dir.path().to_string_lossy().to_string(),
+            session_report: true,
+            session_report_format: SessionReportFormat::Both,
+            ..OutputConfig::default()
+        };
+        let reporter = SessionReporter::new(&config, OutputTimeZone::Utc, SystemTime::now());
+        let mut no_cost = stats();
+        no_cost.iter_mut().for_each(|stats| stats.estimated_cost = None);
+        let written = reporter.write(no_cost, SystemTime::now(), |cost| cost.to_string())?;
+
+        assert_eq!(written.len(), 2);
+        assert!(written[0].extension().is_some_and(|ext| ext == "md"));
+        assert!(fs::read_to_string(&written[0])?.contains("| 合計 | 3 | 1:03:03 | 121.06 MB | 15 | - |"));
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&written[1])?)?;
+        assert!(json["total"].get("estimated_cost").is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// サイドカーYAMLの先頭に付けるコメント
+const HEADER: &str = "\
+# dcr-transcribe 録音メタデータ
+# summary / tags / notes は自由に編集できます（録音情報の欄は自動生成です）
+";
+
+/// 録音ファイル1つ分の発話統計（VADの音声区間）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeechStats {
+    /// 音声区間の数
+    pub segments: u32,
+    /// 音声区間の合計時間（秒）
+    pub voiced_secs: f64,
+    /// VADで判定した時間のうち音声区間の割合 (0.0-1.0)
+    pub voice_ratio: f64,
+}
+
+/// 録音ファイルに書き込んだチャンクのVAD判定の集計
+#[derive(Debug, Clone, Default)]
+pub struct SpeechCounter {
+    segments: u32,
+    voiced_samples: u64,
+    total_samples: u64,
+    in_voice: bool,
+}
+
+impl SpeechCounter {
+    /// チャンク1つ分の判定を加える
+    pub fn process(&mut self, is_voice: bool, samples: usize) {
+        if is_voice {
+            if !self.in_voice {
+                self.segments += 1;
+            }
+            self.voiced_samples += samples as u64;
+        }
+        self.in_voice = is_voice;
+        self.total_samples += samples as u64;
+    }
+
+    /// 集計結果を返してリセットする（次の録音ファイル用）
+    pub fn take(&mut self, sample_rate: u32) -> SpeechStats {
+        let counter = std::mem::take(self);
+        SpeechStats {
+            segments: counter.segments,
+            voiced_secs: counter.voiced_samples as f64 / sample_rate.max(1) as f64,
+            voice_ratio: if counter.total_samples == 0 {
+                0.0
+            } else {
+                counter.voiced_samples as f64 / counter.total_samples as f64
+            },
+        }
+    }
+}
+
+/// 録音WAVに付けるサイドカーメタデータ（`<録音ファイル名>.yaml`）
+///
+/// 録音情報に加えて人手で注釈を書き足すための空欄を持つ。
+/// 検証用の `stats.json`（`--verify-recordings`）とは別物で、こちらは録音処理から参照しない。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingMetadata {
+    /// 録音ファイル名（同じディレクトリのWAV）
+    pub recording: String,
+    pub channel_id: usize,
+    pub channel_name: String,
+    /// 録音開始時刻（RFC3339、`[output] timezone`）
+    pub started_at: String,
+    pub sample_rate: u32,
+    /// 録音の長さ（秒）
+    pub duration_secs: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speech: Option<SpeechStats>,
+    /// `[session]` のメタデータ
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// 内容の要約（人手で記入）
+    #[serde(default)]
+    pub summary: String,
+    /// タグ（人手で記入）
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 自由記述のメモ（人手で記入）
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl RecordingMetadata {
+    /// 録音ファイルに対応するサイドカーのパス
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dcr_transcribe::sidecar::RecordingMetadata;
+    /// # use std::path::Path;
+    /// assert_eq!(
+    ///     RecordingMetadata::sidecar_path(Path::new("rec/channel_0_20250101_000000.wav")),
+    ///     Path::new("rec/channel_0_20250101_000000.yaml")
+    /// );
+    /// ```
+    pub fn sidecar_path(wav_path: &Path) -> PathBuf {
+        wav_path.with_extension("yaml")
+    }
+
+    /// `wav_path` のサイドカーを書き出す
+    ///
+    /// 既にファイルがある場合は人手の注釈を消さないよう上書きせず `None` を返す。
+    pub fn write(&self, wav_path: &Path) -> Result<Option<PathBuf>> {
+        let path = Self::sidecar_path(wav_path);
+        if path.exists() {
+            return Ok(None);
+        }
+        let yaml = serde_yaml::to_string(self).with_context(|| "録音メタデータのシリアライズに失敗")?;
+        fs::write(&path, format!("{}{}", HEADER, yaml))
+            .with_context(|| format!("録音メタデータの書き込みに失敗: {:?}", path))?;
+        Ok(Some(path))
+    }
+
+    /// サイドカーを読み込む
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("録音メタデータの読み込みに失敗: {:?}", path))?;
+        serde_yaml::from_str(&content).with_context(|| format!("録音メタデータのパースに失敗: {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> RecordingMetadata {
+        RecordingMetadata {
+            recording: "channel_0_20250101_000000.wav".to_string(),
+            channel_id: 0,
+            channel_name: "無線機1: 本部".to_string(),
+            started_at: "2025-01-01T09:00:00+09:00".to_string(),
+            sample_rate: 16000,
+            duration_secs: 12.5,
+            speech: Some(SpeechStats {
+                segments: 2,
+                voiced_secs: 3.0,
+                voice_ratio: 0.24,
+            }),
+            metadata: BTreeMap::from([("site_id".to_string(), "tokyo-01".to_string())]),
+            summary: String::new(),
+            tags: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_speech_counter() {
+        let mut counter = SpeechCounter::default();
+        for is_voice in [false, true, true, false, true, false] {
+            counter.process(is_voice, 1600);
+        }
+
+        let stats = counter.take(16000);
+        assert_eq!(stats.segments, 2);
+        assert_eq!(stats.voiced_secs, 0.3);
+        assert_eq!(stats.voice_ratio, 0.5);
+
+        // take で次のファイル用にリセットされる
+        assert_eq!(counter.take(16000), SpeechStats::default());
+    }
+
+    #[test]
+    fn test_write_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let wav_path = dir.path().join("channel_0_20250101_000000.wav");
+
+        let path = metadata().write(&wav_path).unwrap().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# dcr-transcribe"));
+        // 人手で記入する欄は空で用意する
+        assert!(content.contains("notes: ''"), "{}", content);
+        assert!(content.contains("tags: []"), "{}", content);
+
+        assert_eq!(RecordingMetadata::load(&path).unwrap(), metadata());
+    }
+
+    #[test]
+    fn test_write_keeps_existing_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let wav_path = dir.path().join("channel_0_20250101_000000.wav");
+        let path = RecordingMetadata::sidecar_path(&wav_path);
+        fs::write(&path, "notes: 記入済み\n").unwrap();
+
+        assert_eq!(metadata().write(&wav_path).unwrap(), None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "notes: 記入済み\n");
+    }
+}
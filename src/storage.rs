@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// WAV録音に必要なディスク容量の見積もり
+///
+/// WAVヘッダ（44バイト程度）はデータ量に対して無視できるため、
+/// PCMデータ部分のみで計算する。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageEstimate {
+    /// 1時間あたりの必要バイト数（全チャンネル合計）
+    pub bytes_per_hour: u64,
+    /// 想定録音時間分の必要バイト数（全チャンネル合計）
+    pub total_bytes: u64,
+}
+
+/// 録音に必要な容量を見積もる
+///
+/// # Arguments
+///
+/// * `enabled_channels` - 有効なチャンネル数
+/// * `sample_rate` - サンプリングレート (Hz)
+/// * `bits_per_sample` - ビット深度
+/// * `hours` - 想定録音時間（時間）
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::storage::estimate_recording_size;
+/// // 16kHz/16bit/1ch は 1時間あたり 115.2MB
+/// let estimate = estimate_recording_size(1, 16000, 16, 1.0);
+/// assert_eq!(estimate.bytes_per_hour, 115_200_000);
+/// ```
+pub fn estimate_recording_size(
+    enabled_channels: usize,
+    sample_rate: u32,
+    bits_per_sample: u16,
+    hours: f64,
+) -> StorageEstimate {
+    let bytes_per_second =
+        enabled_channels as u64 * sample_rate as u64 * (bits_per_sample as u64 / 8);
+    let bytes_per_hour = bytes_per_second * 3600;
+    let total_bytes = (bytes_per_hour as f64 * hours.max(0.0)) as u64;
+
+    StorageEstimate {
+        bytes_per_hour,
+        total_bytes,
+    }
+}
+
+/// 指定パスのあるボリュームの空き容量（バイト）を取得
+///
+/// 一般ユーザーが利用可能な容量を返す（Unix の予約ブロックは含まない）。
+pub fn available_space<P: AsRef<Path>>(path: P) -> Result<u64> {
+    fs2::available_space(path.as_ref())
+        .with_context(|| format!("空き容量の取得に失敗: {:?}", path.as_ref()))
+}
+
+/// バイト数を人間が読みやすい形式に変換
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::storage::format_bytes;
+/// assert_eq!(format_bytes(512), "512 B");
+/// assert_eq!(format_bytes(1_500_000), "1.50 MB");
+/// ```
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for u in UNITS {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = u;
+    }
+    format!("{:.2} {}", value, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_estimate_recording_size() {
+        // 4ch, 48kHz, 16bit, 2時間
+        let estimate = estimate_recording_size(4, 48000, 16, 2.0);
+        assert_eq!(estimate.bytes_per_hour, 4 * 48000 * 2 * 3600);
+        assert_eq!(estimate.total_bytes, estimate.bytes_per_hour * 2);
+    }
+
+    #[test]
+    fn test_estimate_no_channels() {
+        let estimate = estimate_recording_size(0, 16000, 16, 8.0);
+        assert_eq!(estimate.bytes_per_hour, 0);
+        assert_eq!(estimate.total_bytes, 0);
+    }
+
+    #[test]
+    fn test_available_space() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(available_space(temp_dir.path()).is_ok());
+        assert!(available_space(temp_dir.path().join("nonexistent")).is_err());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1000), "1.00 KB");
+        assert_eq!(format_bytes(115_200_000), "115.20 MB");
+        assert_eq!(format_bytes(2_000_000_000_000), "2.00 TB");
+    }
+}
@@ -0,0 +1,473 @@
+use crate::broadcast::TranscriptSubscription;
+use crate::config::{Config, SummaryConfig, SummaryScope};
+use crate::tui_state::TuiState;
+use crate::types::TranscriptResult;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// 要約の期限を確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 要約を依頼する際の指示
+const SYSTEM_PROMPT: &str = "あなたは無線交信の記録係です。\
+以下は「[時刻] 話者: 発言」形式の交信記録です。\
+要点（誰が・何を・どうした、指示や依頼とその結果）を日本語で3行以内の箇条書きに要約してください。";
+
+/// 要約を生成する LLM API
+#[async_trait]
+pub trait SummaryModel: Send + Sync {
+    /// 交信記録（1行1発話）を要約する
+    async fn summarize(&self, transcript: &str) -> Result<String>;
+}
+
+/// OpenAI Chat Completions API による要約
+pub struct OpenAiSummaryModel {
+    client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiSummaryModel {
+    pub fn new(api_key: &str, model: &str) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("要約API HTTPクライアント作成失敗")?;
+        Ok(Self {
+            client,
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+#[async_trait]
+impl SummaryModel for OpenAiSummaryModel {
+    async fn summarize(&self, transcript: &str) -> Result<String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": transcript },
+            ],
+        });
+
+        let response = self
+            .client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+            .send()
+            .await
+            .context("要約API リクエスト失敗")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("要約API エラー: {} - {}", status, error_text);
+        }
+
+        let response: ChatResponse = response.json().await.context("要約API レスポンスパース失敗")?;
+        let summary = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content.trim().to_string())
+            .unwrap_or_default();
+        if summary.is_empty() {
+            anyhow::bail!("要約API の応答が空です");
+        }
+        Ok(summary)
+    }
+}
+
+/// 要約の単位（チャンネル1つ、またはグループ1つ）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryTarget {
+    /// 表示名（チャンネル名またはグループ名）
+    pub name: String,
+    /// 対象のチャンネルID
+    pub channels: Vec<usize>,
+}
+
+impl SummaryTarget {
+    /// `[summary] scope` に従って要約の単位を作る
+    pub fn from_config(config: &Config) -> Vec<Self> {
+        match config.summary.scope {
+            SummaryScope::Channel => config
+                .channels
+                .iter()
+                .filter(|c| c.enabled)
+                .map(|c| Self {
+                    name: c.name.clone(),
+                    channels: vec![c.id],
+                })
+                .collect(),
+            SummaryScope::Group => config
+                .groups
+                .iter()
+                .map(|g| Self {
+                    name: g.name.clone(),
+                    channels: g.channels.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 要約1件分の確定結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryBatch {
+    pub target: String,
+    /// 「[時刻] 話者: 発言」形式の行
+    pub lines: Vec<String>,
+    /// 最初・最後の発話の時刻（ISO 8601）
+    pub from: String,
+    pub to: String,
+}
+
+/// 要約結果（JSONL出力に `"event":"summary"` として記録する）
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryRecord {
+    /// レコードの種別（常に "summary"）
+    pub event: &'static str,
+    pub target: String,
+    pub from: String,
+    pub to: String,
+    /// 要約した確定結果の件数
+    pub utterances: usize,
+    pub summary: String,
+}
+
+/// 要約の単位ごとに蓄積中の確定結果
+#[derive(Debug)]
+struct Pending {
+    lines: Vec<String>,
+    from: Option<String>,
+    to: String,
+    /// 前回の要約（または開始）時刻
+    since: Instant,
+}
+
+/// 確定結果を要約の単位ごとに蓄積し、一定時間または一定発話数ごとに要約する
+///
+/// 要約は録音・文字起こしとは独立したタスクで行い、API の失敗は警告を出して
+/// その区間の要約をスキップするだけにする。
+pub struct Summarizer {
+    targets: Vec<SummaryTarget>,
+    pending: Vec<Pending>,
+    interval: Option<Duration>,
+    max_utterances: usize,
+    model: Arc<dyn SummaryModel>,
+}
+
+impl Summarizer {
+    pub fn new(config: &SummaryConfig, targets: Vec<SummaryTarget>, model: Arc<dyn SummaryModel>) -> Self {
+        Self::new_at(config, targets, model, Instant::now())
+    }
+
+    fn new_at(config: &SummaryConfig, targets: Vec<SummaryTarget>, model: Arc<dyn SummaryModel>, now: Instant) -> Self {
+        let pending = targets
+            .iter()
+            .map(|_| Pending {
+                lines: Vec::new(),
+                from: None,
+                to: String::new(),
+                since: now,
+            })
+            .collect();
+        Self {
+            targets,
+            pending,
+            interval: (config.interval_secs > 0).then(|| Duration::from_secs(config.interval_secs)),
+            max_utterances: config.max_utterances,
+            model,
+        }
+    }
+
+    /// 確定結果を蓄積する（部分結果・どの単位にも属さないチャンネルは無視）
+    pub fn push(&mut self, result: &TranscriptResult) {
+        if result.is_partial || result.text.trim().is_empty() {
+            return;
+        }
+        let Some(index) = self.targets.iter().position(|t| t.channels.contains(&result.channel)) else {
+            return;
+        };
+
+        let speaker = result
+            .channel_name
+            .clone()
+            .unwrap_or_else(|| format!("チャンネル{}", result.channel + 1));
+        let pending = &mut self.pending[index];
+        pending.lines.push(format!("[{}] {}: {}", result.timestamp, speaker, result.text));
+        pending.from.get_or_insert_with(|| result.timestamp.clone());
+        pending.to = result.timestamp.clone();
+    }
+
+    /// 要約の時期になった単位の蓄積分を取り出す
+    ///
+    /// `max_utterances` 件に達したか、前回から `interval_secs` 経った単位が対象。
+    /// 時間が経っても蓄積がなければ要約せず、次の区間の起点だけ進める。
+    pub fn take_due(&mut self, now: Instant) -> Vec<SummaryBatch> {
+        let mut batches = Vec::new();
+        for index in 0..self.targets.len() {
+            let pending = &self.pending[index];
+            let full = self.max_utterances > 0 && pending.lines.len() >= self.max_utterances;
+            let elapsed = self.interval.is_some_and(|interval| now.duration_since(pending.since) >= interval);
+            if full || elapsed {
+                batches.extend(self.take(index, now));
+            }
+        }
+        batches
+    }
+
+    /// 全単位の蓄積分を取り出す（停止時）
+    pub fn take_all(&mut self) -> Vec<SummaryBatch> {
+        let now = Instant::now();
+        (0..self.targets.len()).filter_map(|index| self.take(index, now)).collect()
+    }
+
+    fn take(&mut self, index: usize, now: Instant) -> Option<SummaryBatch> {
+        let pending = &mut self.pending[index];
+        pending.since = now;
+        let from = pending.from.take()?;
+        Some(SummaryBatch {
+            target: self.targets[index].name.clone(),
+            lines: std::mem::take(&mut pending.lines),
+            from,
+            to: std::mem::take(&mut pending.to),
+        })
+    }
+
+    /// 結果を購読して要約するタスクを起動
+    ///
+    /// 要約は JSONL 出力（`"event":"summary"`）と、`tui_state` があれば TUI の要約ペインに出す。
+    /// `running` が false になると、蓄積中の分を要約し終えてから終了する。
+    pub fn spawn(
+        mut self,
+        mut subscription: TranscriptSubscription,
+        tui_state: Option<TuiState>,
+        running: Arc<AtomicBool>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut requests = JoinSet::new();
+            let model = self.model.clone();
+            let request = |batch: SummaryBatch| {
+                let model = model.clone();
+                let tui_state = tui_state.clone();
+                async move {
+                    if let Some(record) = summarize(model.as_ref(), batch).await {
+                        emit(&record, tui_state.as_ref());
+                    }
+                }
+            };
+
+            while running.load(Ordering::SeqCst) {
+                tokio::select! {
+                    result = subscription.recv() => match result {
+                        Some(result) => self.push(&result),
+                        None => break,
+                    },
+                    _ = tokio::time::sleep(CHECK_INTERVAL) => {}
+                }
+
+                // API の応答を待つ間も結果の受信を止めないよう、要約は別タスクで行う
+                for batch in self.take_due(Instant::now()) {
+                    requests.spawn(request(batch));
+                }
+                while requests.try_join_next().is_some() {}
+            }
+
+            for batch in self.take_all() {
+                requests.spawn(request(batch));
+            }
+            while requests.join_next().await.is_some() {}
+        })
+    }
+}
+
+/// 1件分を要約する（失敗時は警告を出して `None`）
+pub async fn summarize(model: &dyn SummaryModel, batch: SummaryBatch) -> Option<SummaryRecord> {
+    match model.summarize(&batch.lines.join("\n")).await {
+        Ok(summary) => Some(SummaryRecord {
+            event: "summary",
+            target: batch.target,
+            from: batch.from,
+            to: batch.to,
+            utterances: batch.lines.len(),
+            summary,
+        }),
+        Err(e) => {
+            log::warn!(
+                "{} の要約に失敗したためスキップします（{}件、{} - {}）: {:#}",
+                batch.target,
+                batch.lines.len(),
+                batch.from,
+                batch.to,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// 要約を JSONL 出力と TUI に出す
+fn emit(record: &SummaryRecord, tui_state: Option<&TuiState>) {
+    if let Ok(json) = serde_json::to_string(record) {
+        log::info!("{}", json);
+    }
+    if let Some(tui_state) = tui_state {
+        tui_state.set_summary(&record.target, &record.to, &record.summary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 受け取った交信記録の行数を返す、または常に失敗する要約モデル
+    struct MockModel {
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl SummaryModel for MockModel {
+        async fn summarize(&self, transcript: &str) -> Result<String> {
+            if self.fail {
+                anyhow::bail!("rate limited");
+            }
+            Ok(format!("{}件の交信", transcript.lines().count()))
+        }
+    }
+
+    fn result(channel: usize, text: &str, timestamp: &str) -> TranscriptResult {
+        let mut result = TranscriptResult::new_with_audio_time(channel, text.to_string(), false, None, 0.0, None);
+        result.channel_name = Some(format!("無線機{}", channel + 1));
+        result.timestamp = timestamp.to_string();
+        result
+    }
+
+    fn summarizer(interval_secs: u64, max_utterances: usize, now: Instant) -> Summarizer {
+        let config = SummaryConfig {
+            enabled: true,
+            interval_secs,
+            max_utterances,
+            ..SummaryConfig::default()
+        };
+        let targets = vec![
+            SummaryTarget {
+                name: "本部".to_string(),
+                channels: vec![0],
+            },
+            SummaryTarget {
+                name: "現場".to_string(),
+                channels: vec![1, 2],
+            },
+        ];
+        Summarizer::new_at(&config, targets, Arc::new(MockModel { fail: false }), now)
+    }
+
+    #[test]
+    fn test_targets_from_config() {
+        let mut config = Config::default();
+        assert_eq!(SummaryTarget::from_config(&config).len(), 2);
+
+        config.channels[1].enabled = false;
+        assert_eq!(
+            SummaryTarget::from_config(&config),
+            vec![SummaryTarget {
+                name: "無線機1".to_string(),
+                channels: vec![0],
+            }]
+        );
+
+        config.summary.scope = SummaryScope::Group;
+        assert!(SummaryTarget::from_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_take_due_by_utterances() {
+        let now = Instant::now();
+        let mut summarizer = summarizer(300, 2, now);
+
+        summarizer.push(&result(0, "本部です", "t1"));
+        // 部分結果・対象外のチャンネルは蓄積しない
+        let mut partial = result(0, "途中", "t2");
+        partial.is_partial = true;
+        summarizer.push(&partial);
+        summarizer.push(&result(5, "対象外", "t2"));
+        assert!(summarizer.take_due(now).is_empty());
+
+        summarizer.push(&result(0, "了解", "t3"));
+        let batches = summarizer.take_due(now);
+        assert_eq!(
+            batches,
+            vec![SummaryBatch {
+                target: "本部".to_string(),
+                lines: vec!["[t1] 無線機1: 本部です".to_string(), "[t3] 無線機1: 了解".to_string()],
+                from: "t1".to_string(),
+                to: "t3".to_string(),
+            }]
+        );
+        assert!(summarizer.take_due(now).is_empty());
+    }
+
+    #[test]
+    fn test_take_due_by_interval() {
+        let start = Instant::now();
+        let mut summarizer = summarizer(60, 0, start);
+        summarizer.push(&result(1, "到着しました", "t1"));
+        summarizer.push(&result(2, "確認します", "t2"));
+
+        assert!(summarizer.take_due(start + Duration::from_secs(59)).is_empty());
+        let batches = summarizer.take_due(start + Duration::from_secs(60));
+        // 蓄積のない単位は要約しない
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].target, "現場");
+        assert_eq!(batches[0].lines.len(), 2);
+
+        // 次の区間は要約した時点から数える
+        summarizer.push(&result(1, "撤収します", "t3"));
+        assert!(summarizer.take_due(start + Duration::from_secs(90)).is_empty());
+        assert_eq!(summarizer.take_all().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_skips_failure() {
+        let batch = SummaryBatch {
+            target: "本部".to_string(),
+            lines: vec!["[t1] 無線機1: 本部です".to_string(), "[t2] 無線機1: 了解".to_string()],
+            from: "t1".to_string(),
+            to: "t2".to_string(),
+        };
+
+        let record = summarize(&MockModel { fail: false }, batch.clone()).await.unwrap();
+        assert_eq!(record.summary, "2件の交信");
+        assert_eq!(record.utterances, 2);
+        let json: serde_json::Value = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["event"], "summary");
+        assert_eq!(json["target"], "本部");
+
+        assert!(summarize(&MockModel { fail: true }, batch).await.is_none());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::audit::{AuditEvent, AuditEventKind};
+use crate::timezone::OutputTimeZone;
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// JSONL出力に書くシステムイベントの種類（`[output] timeline_events`）
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    /// 録音セッションを開始した（起動時）
+    SessionStart,
+    /// 録音セッションを終了した（停止時）
+    SessionEnd,
+    /// 録音ファイルを開いた
+    RecordingStart,
+    /// 録音ファイルを閉じた（一時停止・入力の復帰などでファイルを切り替える場合は、この後に次の `RecordingStart` が続く）
+    RecordingStop,
+    /// 文字起こしバックエンドに接続した
+    Connect,
+    /// 文字起こしバックエンドへの接続に失敗した
+    ConnectFailed,
+    /// 文字起こしバックエンドから切断した
+    Disconnect,
+}
+
+/// JSONL出力の1行としてのシステムイベント（`"type":"event"`）
+///
+/// 時刻は文字起こし結果（`TranscriptResult`）と同じ `timestamp` / `timestamp_seconds` で持ち、
+/// 後段で1つのタイムラインに並べられるようにする。
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TimelineEvent {
+    pub event: TimelineEventKind,
+    /// チャンネルID（セッション全体のイベントでは `None`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<usize>,
+    /// ISO 8601形式のタイムスタンプ
+    pub timestamp: String,
+    /// 開始時刻からの経過秒数（`TranscriptResult::timestamp_seconds` と同じ基準）
+    pub timestamp_seconds: f64,
+    /// 録音ファイルのパス
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 閉じた録音ファイルの長さ（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// エラーの内容など
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl TimelineEvent {
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_string_lossy().into_owned());
+        self
+    }
+
+    pub fn with_duration_secs(mut self, duration_secs: f64) -> Self {
+        self.duration_secs = Some(duration_secs);
+        self
+    }
+}
+
+/// JSONL出力の1行（`"type"` に種別を付け、元のレコードのフィールドをそのまま並べる）
+#[derive(Serialize)]
+struct TypedRecord<'a, T> {
+    #[serde(rename = "type")]
+    record_type: &'static str,
+    #[serde(flatten)]
+    record: &'a T,
+}
+
+/// `"type"` を付けたJSONL出力の1行に整形する（文字起こし結果は "transcript"、システムイベントは "event"）
+pub fn typed_json_line<T: Serialize>(record_type: &'static str, record: &T) -> serde_json::Result<String> {
+    serde_json::to_string(&TypedRecord { record_type, record })
+}
+
+/// システムイベントを文字起こし結果と同じJSONL出力（ログ）に書き込む（`[output] timeline_events`）
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    start_time: SystemTime,
+    timezone: OutputTimeZone,
+}
+
+impl Timeline {
+    pub fn new(start_time: SystemTime, timezone: OutputTimeZone) -> Self {
+        Self { start_time, timezone }
+    }
+
+    /// `time` に起きたイベントを作る
+    pub fn event(&self, kind: TimelineEventKind, channel: Option<usize>, time: SystemTime) -> TimelineEvent {
+        TimelineEvent {
+            event: kind,
+            channel,
+            timestamp: self
+                .timezone
+                .format_timestamp_ns(time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos()),
+            timestamp_seconds: time.duration_since(self.start_time).unwrap_or_default().as_secs_f64(),
+            path: None,
+            duration_secs: None,
+            detail: None,
+        }
+    }
+
+    /// イベントをJSONL出力に書き込む
+    pub fn emit(&self, event: &TimelineEvent) {
+        if let Ok(json) = typed_json_line("event", event) {
+            log::info!("{}", json);
+        }
+    }
+
+    /// 監査ログのイベントのうち、接続・切断をタイムラインに書き込む
+    pub fn record_audit(&self, event: &AuditEvent) {
+        let kind = match event.kind {
+            AuditEventKind::Connect => TimelineEventKind::Connect,
+            AuditEventKind::ConnectFailed => TimelineEventKind::ConnectFailed,
+            AuditEventKind::Disconnect | AuditEventKind::StreamClosed => TimelineEventKind::Disconnect,
+            _ => return,
+        };
+        let time = UNIX_EPOCH + Duration::from_nanos(event.timestamp_ns.min(u64::MAX as u128) as u64);
+        let mut timeline_event = self.event(kind, Some(event.channel_id), time);
+        timeline_event.detail = event.detail.clone();
+        self.emit(&timeline_event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TranscriptResult;
+
+    #[test]
+    fn test_typed_lines_share_timeline_fields() {
+        let start_time = UNIX_EPOCH + Duration::from_secs(1_735_961_696);
+        let timeline = Timeline::new(start_time, OutputTimeZone::Utc);
+        let event = timeline
+            .event(TimelineEventKind::RecordingStop, Some(1), start_time + Duration::from_millis(2500))
+            .with_path(Path::new("rec/channel_1.wav"))
+            .with_duration_secs(2.5);
+        let json: serde_json::Value = serde_json::from_str(&typed_json_line("event", &event).unwrap()).unwrap();
+        assert_eq!(json["type"], "event");
+        assert_eq!(json["event"], "recording_stop");
+        assert_eq!(json["channel"], 1);
+        assert_eq!(json["timestamp"], "2025-01-04T03:34:58.500+00:00");
+        assert_eq!(json["timestamp_seconds"], 2.5);
+        assert_eq!(json["path"], "rec/channel_1.wav");
+        assert!(json.get("detail").is_none());
+
+        let session = timeline.event(TimelineEventKind::SessionStart, None, start_time);
+        let json: serde_json::Value = serde_json::from_str(&typed_json_line("event", &session).unwrap()).unwrap();
+        assert_eq!(json["event"], "session_start");
+        assert!(json.get("channel").is_none());
+
+        // 文字起こし結果は元のフィールドに "type" を足すだけ
+        let result = TranscriptResult::new(0, "こちら本部".to_string(), false, None, start_time);
+        let json: serde_json::Value =
+            serde_json::from_str(&typed_json_line("transcript", &result).unwrap()).unwrap();
+        assert_eq!(json["type"], "transcript");
+        assert_eq!(json["channel"], 0);
+        assert_eq!(json["text"], "こちら本部");
+        assert!(json.get("timestamp_seconds").is_some());
+    }
+}
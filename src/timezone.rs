@@ -0,0 +1,147 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use std::time::SystemTime;
+
+/// 出力タイムスタンプのタイムゾーン
+///
+/// `[output] timezone` の値から作成する。
+/// JSONL出力・TUI表示など、すべての出力先がこの設定を基準に時刻を表す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputTimeZone {
+    /// 実行環境のローカルタイムゾーン
+    #[default]
+    Local,
+    /// 協定世界時
+    Utc,
+    /// IANAタイムゾーン名で指定（例: "Asia/Tokyo"）
+    Named(Tz),
+}
+
+impl OutputTimeZone {
+    /// 設定値からタイムゾーンを作成
+    ///
+    /// "local" と "UTC" は大文字小文字を区別しない。それ以外は IANA タイムゾーン名として解釈する。
+    ///
+    /// # Errors
+    ///
+    /// 不明なタイムゾーン名の場合にエラーを返す。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dcr_transcribe::timezone::OutputTimeZone;
+    /// assert_eq!(OutputTimeZone::parse("local").unwrap(), OutputTimeZone::Local);
+    /// assert_eq!(OutputTimeZone::parse("UTC").unwrap(), OutputTimeZone::Utc);
+    /// assert!(OutputTimeZone::parse("Asia/Tokyo").is_ok());
+    /// assert!(OutputTimeZone::parse("Mars/Olympus").is_err());
+    /// ```
+    pub fn parse(name: &str) -> Result<Self> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("local") {
+            Ok(Self::Local)
+        } else if name.eq_ignore_ascii_case("utc") {
+            Ok(Self::Utc)
+        } else {
+            name.parse::<Tz>()
+                .map(Self::Named)
+                .map_err(|_| anyhow!("不明なタイムゾーン: {:?}", name))
+        }
+    }
+
+    /// UTC時刻をこのタイムゾーンの RFC3339 文字列に変換（秒精度）
+    pub fn format_rfc3339(&self, dt: DateTime<Utc>) -> String {
+        match self {
+            Self::Local => dt.with_timezone(&Local).to_rfc3339(),
+            Self::Utc => dt.to_rfc3339(),
+            Self::Named(tz) => dt.with_timezone(tz).to_rfc3339(),
+        }
+    }
+
+    /// `SystemTime` をこのタイムゾーンの RFC3339 文字列に変換（秒精度）
+    pub fn format_system_time(&self, time: SystemTime) -> String {
+        let secs = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        DateTime::from_timestamp(secs, 0)
+            .map(|dt| self.format_rfc3339(dt))
+            .unwrap_or_default()
+    }
+
+    /// UNIX_EPOCH からのナノ秒をこのタイムゾーンの RFC3339 文字列に変換（ミリ秒精度）
+    pub fn format_timestamp_ns(&self, timestamp_ns: u128) -> String {
+        let dt = DateTime::from_timestamp_nanos(timestamp_ns.min(i64::MAX as u128) as i64);
+        match self {
+            Self::Local => dt.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::Millis, false),
+            Self::Utc => dt.to_rfc3339_opts(SecondsFormat::Millis, false),
+            Self::Named(tz) => dt.with_timezone(tz).to_rfc3339_opts(SecondsFormat::Millis, false),
+        }
+    }
+
+    /// RFC3339 文字列をこのタイムゾーンに変換
+    ///
+    /// パースに失敗した場合は `None` を返す。
+    pub fn convert_rfc3339(&self, timestamp: &str) -> Option<String> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .map(|dt| self.format_rfc3339(dt.with_timezone(&Utc)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(OutputTimeZone::parse("Local").unwrap(), OutputTimeZone::Local);
+        assert_eq!(OutputTimeZone::parse("utc").unwrap(), OutputTimeZone::Utc);
+        assert_eq!(
+            OutputTimeZone::parse("Asia/Tokyo").unwrap(),
+            OutputTimeZone::Named(chrono_tz::Asia::Tokyo)
+        );
+        assert!(OutputTimeZone::parse("").is_err());
+        assert!(OutputTimeZone::parse("JST+9").is_err());
+    }
+
+    #[test]
+    fn test_convert_rfc3339() {
+        let utc = "2025-01-04T03:34:56+00:00";
+
+        let tokyo = OutputTimeZone::parse("Asia/Tokyo").unwrap();
+        assert_eq!(
+            tokyo.convert_rfc3339(utc).unwrap(),
+            "2025-01-04T12:34:56+09:00"
+        );
+
+        let utc_tz = OutputTimeZone::Utc;
+        assert_eq!(
+            utc_tz.convert_rfc3339("2025-01-04T12:34:56+09:00").unwrap(),
+            utc
+        );
+
+        assert!(tokyo.convert_rfc3339("invalid").is_none());
+    }
+
+    #[test]
+    fn test_format_system_time() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_735_961_696);
+        assert_eq!(
+            OutputTimeZone::Utc.format_system_time(time),
+            "2025-01-04T03:34:56+00:00"
+        );
+    }
+    #[test]
+    fn test_format_timestamp_ns() {
+        let timestamp_ns = 1_735_961_696_123_456_789u128;
+        assert_eq!(
+            OutputTimeZone::Utc.format_timestamp_ns(timestamp_ns),
+            "2025-01-04T03:34:56.123+00:00"
+        );
+        assert_eq!(
+            OutputTimeZone::parse("Asia/Tokyo").unwrap().format_timestamp_ns(timestamp_ns),
+            "2025-01-04T12:34:56.123+09:00"
+        );
+    }
+}
@@ -1,7 +1,6 @@
-use crate::config::TranscribeConfig;
+use crate::config::{FlacConfig, TranscribeConfig};
 use crate::types::TranscriptResult;
 use anyhow::Result;
-use aws_config;
 use aws_sdk_transcribestreaming::Client as AwsTranscribeClient;
 use aws_sdk_transcribestreaming::types::{AudioEvent, AudioStream, LanguageCode, MediaEncoding};
 use aws_smithy_types::Blob;
@@ -19,6 +18,8 @@ pub struct TranscribeClient {
     channel_id: usize,
     start_time: SystemTime,
     retry_count: u32,
+    /// FLACの圧縮レベル（`[flac] compression_level`）
+    flac: FlacConfig,
 }
 
 impl TranscribeClient {
@@ -28,9 +29,16 @@ impl TranscribeClient {
             channel_id,
             start_time: SystemTime::now(),
             retry_count: 0,
+            flac: FlacConfig::default(),
         })
     }
 
+    /// FLACの設定（`[flac]`）を設定
+    pub fn with_flac(mut self, flac: FlacConfig) -> Self {
+        self.flac = flac;
+        self
+    }
+
     /// ストリーミング文字起こしセッションを開始
     ///
     /// # Returns
@@ -43,14 +51,14 @@ impl TranscribeClient {
         // バッファサイズを大幅拡張
         use std::sync::Arc;
         use tokio::sync::Mutex;
-        use crate::flac_encoder::FlacEncoder;
+        use crate::flac_encoder::{FlacEncodeStats, FlacEncoder};
 
         let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(4096);
         let audio_rx = Arc::new(Mutex::new(audio_rx));
         let (result_tx, result_rx) = mpsc::channel::<TranscriptResult>(32);
 
         // AWS SDKクライアント初期化
-        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let config = crate::aws_transcribe::load_sdk_config(&self.config).await;
         let client = AwsTranscribeClient::new(&config);
 
         let language_code = match self.config.language_code.as_str() {
@@ -61,21 +69,19 @@ impl TranscribeClient {
         let sample_rate = self.config.sample_rate;
         let channel_id = self.channel_id;
         let start_time = self.start_time;
+        let compression_level = self.flac.compression_level;
         tokio::spawn({
             let language_code = language_code.clone();
-            let sample_rate = sample_rate;
-            let channel_id = channel_id;
-            let start_time = start_time;
             let audio_rx = Arc::clone(&audio_rx);
             let client = client.clone();
             let result_tx = result_tx.clone();
             async move {
                 use tokio::time::{Duration, timeout};
-                'outer: loop {
+                {
                     let audio_rx_for_stream = Arc::clone(&audio_rx);
 
-                    // FLACエンコーダーを作成（圧縮レベル5）
-                    let mut flac_encoder = FlacEncoder::new(sample_rate, 5);
+                    let mut flac_encoder = FlacEncoder::new(sample_rate, compression_level);
+                    let flac_stats = FlacEncodeStats::default();
 
                     let input_stream = stream! {
                         let mut pcm_buffer: Vec<i16> = Vec::new();
@@ -105,14 +111,9 @@ impl TranscribeClient {
                                         let to_encode: Vec<i16> = pcm_buffer.drain(..min_samples.min(pcm_buffer.len())).collect();
                                         chunk_count += 1;
 
-                                        match flac_encoder.encode(&to_encode) {
-                                            Ok(flac_data) => {
-                                                let blob = Blob::new(flac_data);
-                                                yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
-                                            }
-                                            Err(e) => {
-                                                log::error!("FLACエンコードエラー: {:?}", e);
-                                            }
+                                        if let Some(flac_data) = flac_encoder.encode_or_skip(channel_id, &to_encode, &flac_stats) {
+                                            let blob = Blob::new(flac_data);
+                                            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
                                         }
                                     }
                                 }
@@ -120,15 +121,10 @@ impl TranscribeClient {
                                     log::debug!("TranscribeClient: チャンネルクローズ");
                                     // チャンネルがクローズされた場合、残りのバッファを送信
                                     if !pcm_buffer.is_empty() {
-                                        match flac_encoder.encode(&pcm_buffer) {
-                                            Ok(flac_data) => {
-                                                let blob = Blob::new(flac_data);
-                                                log::debug!("Amazon Transcribe 最終送信: {} サンプル → {} バイト", pcm_buffer.len(), blob.as_ref().len());
-                                                yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
-                                            }
-                                            Err(e) => {
-                                                log::error!("FLACエンコードエラー: {:?}", e);
-                                            }
+                                        if let Some(flac_data) = flac_encoder.encode_or_skip(channel_id, &pcm_buffer, &flac_stats) {
+                                            let blob = Blob::new(flac_data);
+                                            log::debug!("Amazon Transcribe 最終送信: {} サンプル → {} バイト", pcm_buffer.len(), blob.as_ref().len());
+                                            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
                                         }
                                     }
                                     break;
@@ -138,15 +134,10 @@ impl TranscribeClient {
                                     // タイムアウトした場合、バッファに残っているデータを送信
                                     if !pcm_buffer.is_empty() {
                                         let to_encode = pcm_buffer.split_off(0);
-                                        match flac_encoder.encode(&to_encode) {
-                                            Ok(flac_data) => {
-                                                let blob = Blob::new(flac_data);
-                                                log::debug!("Amazon Transcribe タイムアウト送信: {} サンプル → {} バイト", to_encode.len(), blob.as_ref().len());
-                                                yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
-                                            }
-                                            Err(e) => {
-                                                log::error!("FLACエンコードエラー: {:?}", e);
-                                            }
+                                        if let Some(flac_data) = flac_encoder.encode_or_skip(channel_id, &to_encode, &flac_stats) {
+                                            let blob = Blob::new(flac_data);
+                                            log::debug!("Amazon Transcribe タイムアウト送信: {} サンプル → {} バイト", to_encode.len(), blob.as_ref().len());
+                                            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(blob).build()));
                                         }
                                     }
                                 }
@@ -220,7 +211,6 @@ impl TranscribeClient {
                             }
                         }
                     }
-                    break 'outer;
                 }
             }
         });
@@ -242,7 +232,7 @@ impl TranscribeClient {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::TranscribeBackendType;
+    use crate::config::{DisconnectBufferOverflow, TranscribeBackendType};
 
     #[tokio::test]
     async fn test_transcribe_client_creation() {
@@ -255,6 +245,18 @@ mod tests {
             timeout_seconds: 10,
             connect_on_startup: false,
             send_buffered_on_reconnect: true,
+            proactive_reconnect_secs: 0,
+            partial_finalize_timeout_ms: 0,
+            initial_chunk_ms: 150,
+            chunk_ms: 200,
+            fast_start_chunks: 5,
+            predictive_connect: false,
+            endpoint_url: None,
+            allowed_languages: Vec::new(),
+            max_concurrent_streams: None,
+            stream_quota: None,
+            max_disconnect_buffer_secs: 30,
+            disconnect_buffer_overflow: DisconnectBufferOverflow::DropOldest,
         };
 
         let result = TranscribeClient::new(config, 0).await;
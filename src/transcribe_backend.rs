@@ -1,7 +1,121 @@
 use crate::types::TranscriptResult;
 use anyhow::Result;
 use async_trait::async_trait;
-use tokio::sync::mpsc;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+
+/// 文字起こしバックエンドへ送る音声
+///
+/// バックエンドはモノラル1系列を前提とするため、受け取った側は `MonoNormalizer` を通してから使う。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscribeAudio {
+    /// PCMサンプル（多チャンネルの場合はインターリーブ）
+    pub samples: Vec<i16>,
+    /// チャンネル数
+    pub channels: u16,
+}
+
+impl TranscribeAudio {
+    pub fn new(samples: Vec<i16>, channels: u16) -> Self {
+        Self { samples, channels }
+    }
+
+    /// モノラルの音声
+    pub fn mono(samples: Vec<i16>) -> Self {
+        Self::new(samples, 1)
+    }
+}
+
+/// インターリーブされた多チャンネルサンプルをモノラルにミックスダウン
+///
+/// 各フレームのサンプルを平均して1系列にする。
+/// `channels` が1以下の場合はそのまま返す。
+/// 端数のサンプル（不完全なフレーム）は破棄する。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::transcribe_backend::downmix_to_mono;
+/// assert_eq!(downmix_to_mono(&[1000, 3000, -1000, -3000], 2), vec![2000, -2000]);
+/// ```
+pub fn downmix_to_mono(samples: &[i16], channels: u16) -> Vec<i16> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+
+    samples
+        .chunks_exact(channels as usize)
+        .map(|frame| {
+            let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+            (sum / channels as i32) as i16
+        })
+        .collect()
+}
+
+/// バックエンドの送信段で、受け取った音声を必ずモノラル1系列にする
+///
+/// `ChannelProcessor` は受信したチャンネル数のまま送るため、モノラル化は各バックエンドのここでのみ行う。
+/// モノラル以外の音声は平均ミックスして送る（チャンネル数が変わるたびに警告する）。
+#[derive(Debug)]
+pub struct MonoNormalizer {
+    channel_id: usize,
+    last_channels: u16,
+}
+
+impl MonoNormalizer {
+    pub fn new(channel_id: usize) -> Self {
+        Self {
+            channel_id,
+            last_channels: 1,
+        }
+    }
+
+    /// モノラルのサンプルを返す
+    pub fn normalize(&mut self, audio: TranscribeAudio) -> Vec<i16> {
+        if audio.channels != self.last_channels {
+            if audio.channels > 1 {
+                log::warn!(
+                    "チャンネル {}: 文字起こしの送信段にモノラル以外の音声 ({}ch) が届きました - 平均ミックスして送信します",
+                    self.channel_id,
+                    audio.channels
+                );
+            }
+            self.last_channels = audio.channels;
+        }
+        if audio.channels <= 1 {
+            return audio.samples;
+        }
+        downmix_to_mono(&audio.samples, audio.channels)
+    }
+}
+
+/// 全チャンネルで共有する同時ストリーム数の上限（`[transcribe] max_concurrent_streams`）
+///
+/// 接続中のチャンネルが許可を1つずつ持ち、切断時に許可を返す。
+#[derive(Clone, Debug)]
+pub struct StreamLimiter {
+    semaphore: Arc<Semaphore>,
+    limit: usize,
+}
+
+impl StreamLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit,
+        }
+    }
+
+    /// 上限のストリーム数
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// 空きがあれば許可を取得する（許可をドロップするとストリーム1本分の枠が空く）
+    pub fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.semaphore.clone().try_acquire_owned().ok()
+    }
+}
 
 /// 文字起こしバックエンドの共通トレイト
 #[async_trait]
@@ -10,12 +124,87 @@ pub trait TranscribeBackend: Send {
     ///
     /// # Returns
     /// (送信チャンネル, 受信チャンネル) のタプル
-    /// - 送信チャンネル: PCM音声データ（i16サンプル）を送信
+    /// - 送信チャンネル: PCM音声データ（i16サンプルとチャンネル数）を送信
     /// - 受信チャンネル: 文字起こし結果を受信
     async fn start_stream(
         &mut self,
-    ) -> Result<(mpsc::Sender<Vec<i16>>, mpsc::Receiver<TranscriptResult>)>;
+    ) -> Result<(mpsc::Sender<TranscribeAudio>, mpsc::Receiver<TranscriptResult>)>;
+
+    /// 次に開始するストリームで使う言語を設定（自動言語切り替え用）
+    ///
+    /// 接続中のストリームには影響しない。言語を切り替えられないバックエンドは何もしない。
+    fn set_language(&mut self, _language: &str) {}
+
+    /// タイムスタンプの基準時刻を現在時刻にリセットし、再接続回数を数える
+    ///
+    /// 以降に開始するストリームの `timestamp_seconds` は0付近から数え直す。
+    /// `ChannelProcessor` の再接続は無音のたびに起こり、チャンネル間・再接続間で時刻をそろえるため呼ばない
+    /// （時刻の基準を取り直したいライブラリ利用者向け）。
+    /// 既定の実装は何もしない（基準時刻を持たないバックエンド用）。
+    fn reset_start_time(&mut self) {}
+
+    /// 1ストリームを1発話として扱うか（VADの発話区間ごとの送信）
+    ///
+    /// true の場合、`ChannelProcessor` は発話終了（ハングオーバー後）ですぐにストリームを閉じ、
+    /// バックエンドはストリームが閉じられた時点でまとめて文字起こしする。
+    fn segments_by_vad(&self) -> bool {
+        false
+    }
 
     /// チャンネルIDを取得
     fn channel_id(&self) -> usize;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downmix_mono_passthrough() {
+        let samples = vec![100i16, -200, 300, -400];
+        assert_eq!(downmix_to_mono(&samples, 1), samples);
+    }
+
+    #[test]
+    fn test_downmix_stereo_to_mono() {
+        // L/R インターリーブ: (1000, 3000), (-1000, -3000), (i16::MAX, i16::MAX)
+        let samples = vec![1000i16, 3000, -1000, -3000, i16::MAX, i16::MAX];
+        let mono = downmix_to_mono(&samples, 2);
+        assert_eq!(mono, vec![2000, -2000, i16::MAX]);
+    }
+
+    #[test]
+    fn test_downmix_drops_incomplete_frame() {
+        // 4ch で 9 サンプル → 完全なフレームは2つのみ
+        let samples = vec![4i16; 9];
+        let mono = downmix_to_mono(&samples, 4);
+        assert_eq!(mono, vec![4, 4]);
+    }
+
+    #[test]
+    fn test_normalizer_stereo_to_mono() {
+        let mut normalizer = MonoNormalizer::new(0);
+        assert_eq!(normalizer.normalize(TranscribeAudio::mono(vec![1, 2, 3])), vec![1, 2, 3]);
+        // ステレオが届いたら平均ミックスし、モノラルに戻ればそのまま通す
+        assert_eq!(
+            normalizer.normalize(TranscribeAudio::new(vec![100, 300, -100, -300], 2)),
+            vec![200, -200]
+        );
+        assert_eq!(normalizer.last_channels, 2);
+        assert_eq!(normalizer.normalize(TranscribeAudio::mono(vec![5])), vec![5]);
+        assert_eq!(normalizer.last_channels, 1);
+    }
+
+    #[test]
+    fn test_stream_limiter() {
+        let limiter = StreamLimiter::new(2);
+        let first = limiter.try_acquire().unwrap();
+        let _second = limiter.clone().try_acquire().unwrap();
+        assert!(limiter.try_acquire().is_none());
+
+        // 切断したチャンネルの枠を別のチャンネルが使える
+        drop(first);
+        assert!(limiter.try_acquire().is_some());
+        assert_eq!(limiter.limit(), 2);
+    }
+}
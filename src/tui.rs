@@ -1,9 +1,15 @@
-use crate::tui_state::{ChannelState, TranscribeStatus, TuiState};
+use crate::cost::CostSummary;
+use crate::feedback::{FeedbackRecord, FeedbackWriter};
+use crate::storage::format_bytes;
+use crate::tui_state::{
+    BackendSwitchStatus, ChannelState, GlobalStatus, GroupState, SummaryEntry, TranscribeStatus, TuiState,
+    WAVEFORM_POINTS,
+};
 use crate::types::VadState;
 use anyhow::Result;
 use chrono::Timelike;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -15,27 +21,303 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Gauge, Paragraph, Wrap},
     Frame, Terminal,
 };
+use futures_util::StreamExt;
+use std::collections::VecDeque;
 use std::io;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// チャンネル名の最大文字数
+const MAX_CHANNEL_NAME_CHARS: usize = 32;
+
+/// 端末上での1文字の表示幅（ASCIIは1、結合文字・異体字セレクタ・ゼロ幅文字は0、それ以外は全角として2）
+fn char_width(ch: char) -> usize {
+    match ch {
+        '\u{0300}'..='\u{036F}' | '\u{200B}'..='\u{200D}' | '\u{FE00}'..='\u{FE0F}' => 0,
+        ch if ch.is_ascii() => 1,
+        _ => 2,
+    }
+}
+
+/// 端末上での文字列の表示幅（TUIの折り返しと同じ数え方）
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::tui::display_width;
+/// assert_eq!(display_width("AB"), 2);
+/// assert_eq!(display_width("本部"), 4);
+/// assert_eq!(display_width("☎\u{FE0F}"), 2);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// 要約ペインの高さ（枠を含む行数）
+const SUMMARY_PANE_HEIGHT: u16 = 8;
+
+/// `+`/`-` キー1回あたりの出力音量の変化量
+const OUTPUT_VOLUME_STEP: f32 = 0.1;
+
+/// 状態の変更をまとめて描画する最短間隔（最大 5fps、音量メーターは入力のたびに変わるため）
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 数字キーに対応する画面上の位置（左から0始まり、`display_order` 適用後）
+///
+/// `1`～`9` が1～9番目、`0` が10番目。11番目以降のチャンネルは数字キーでは選択できない
+/// （選択するには別のキー操作を追加する必要がある）。
+fn channel_key_position(key: char) -> Option<usize> {
+    match key.to_digit(10)? {
+        0 => Some(9),
+        digit => Some(digit as usize - 1),
+    }
+}
+
+/// 状態が変わらなくても再描画する間隔（無音の経過秒数など時刻に依存する表示の更新用）
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 再描画のタイミングの判定
+///
+/// キー入力と端末サイズの変更はすぐに描画し、状態の変更は前回の描画から
+/// `MIN_FRAME_INTERVAL` 経つまでまとめる。何も起きなければ `IDLE_REDRAW_INTERVAL` ごとに描画する。
+#[derive(Debug, Default)]
+struct RedrawScheduler {
+    last_draw: Option<Instant>,
+    /// 前回の描画以降に状態が変更されたか
+    dirty: bool,
+    /// 次のループですぐに描画するか
+    immediate: bool,
+}
+
+impl RedrawScheduler {
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn request_immediate(&mut self) {
+        self.immediate = true;
+    }
+
+    fn should_draw(&self, now: Instant) -> bool {
+        self.immediate || self.last_draw.is_none() || now >= self.next_deadline()
+    }
+
+    fn drawn(&mut self, now: Instant) {
+        self.last_draw = Some(now);
+        self.dirty = false;
+        self.immediate = false;
+    }
+
+    /// 次に描画する時刻（それまでにキー入力・状態の変更がなければ眠ってよい）
+    fn next_deadline(&self) -> Instant {
+        let Some(last_draw) = self.last_draw else {
+            return Instant::now();
+        };
+        last_draw + if self.dirty { MIN_FRAME_INTERVAL } else { IDLE_REDRAW_INTERVAL }
+    }
+}
+
+/// 描画回数とキー入力から描画完了までの時間の計測（終了時にログに出す）
+#[derive(Debug)]
+struct FrameStats {
+    started: Instant,
+    draws: u64,
+    /// 描画に反映されていないキー入力のうち最初のものを受け取った時刻
+    pending_key: Option<Instant>,
+    keys: u32,
+    key_latency_total: Duration,
+    key_latency_max: Duration,
+}
+
+impl FrameStats {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            draws: 0,
+            pending_key: None,
+            keys: 0,
+            key_latency_total: Duration::ZERO,
+            key_latency_max: Duration::ZERO,
+        }
+    }
+
+    fn key_received(&mut self, now: Instant) {
+        self.pending_key.get_or_insert(now);
+    }
+
+    fn drawn(&mut self, now: Instant) {
+        self.draws += 1;
+        if let Some(received) = self.pending_key.take() {
+            let latency = now.saturating_duration_since(received);
+            self.keys += 1;
+            self.key_latency_total += latency;
+            self.key_latency_max = self.key_latency_max.max(latency);
+        }
+    }
+
+    fn log(&self) {
+        let secs = self.started.elapsed().as_secs_f64().max(f64::EPSILON);
+        let average_ms = if self.keys > 0 {
+            self.key_latency_total.as_secs_f64() * 1000.0 / self.keys as f64
+        } else {
+            0.0
+        };
+        log::info!(
+            "TUI: 描画 {}回 (平均 {:.1}回/秒), キー入力から描画完了まで 平均 {:.1}ms / 最大 {:.1}ms ({}回)",
+            self.draws,
+            self.draws as f64 / secs,
+            average_ms,
+            self.key_latency_max.as_secs_f64() * 1000.0,
+            self.keys
+        );
+    }
+}
+
+/// キー入力モード
+///
+/// モードごとにキーの解釈を切り替え、名前の編集中に数字キーや `q` が
+/// ショートカットとして処理されないようにする。
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum InputMode {
+    /// 通常（ショートカット有効）
+    Normal,
+    /// 終了確認ダイアログを表示中
+    ExitConfirm,
+    /// チャンネル名を編集中
+    Rename { channel_id: usize, buffer: String },
+}
+
+/// 名前編集モードでのキー入力の結果
+#[derive(Debug, PartialEq, Eq)]
+enum RenameAction {
+    /// 編集を継続
+    Continue,
+    /// 編集をキャンセル
+    Cancel,
+    /// 入力した名前で確定
+    Commit(String),
+}
+
+/// チャンネルの音声出力の状態（タイトルと枠の色に反映）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputMark {
+    /// TUIで選択して出力中
+    Selected,
+    /// `auto_follow_active` による自動切替で出力中
+    Auto,
+}
+
+impl InputMode {
+    /// 名前編集モードのキー入力を処理（編集モード以外では何もしない）
+    fn handle_rename_key(&mut self, key: KeyEvent) -> RenameAction {
+        let InputMode::Rename { buffer, .. } = self else {
+            return RenameAction::Continue;
+        };
+
+        match key.code {
+            KeyCode::Esc => RenameAction::Cancel,
+            KeyCode::Enter => {
+                let name = buffer.trim();
+                if name.is_empty() {
+                    RenameAction::Cancel
+                } else {
+                    RenameAction::Commit(name.to_string())
+                }
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+                RenameAction::Continue
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if buffer.chars().count() < MAX_CHANNEL_NAME_CHARS {
+                    buffer.push(c);
+                }
+                RenameAction::Continue
+            }
+            _ => RenameAction::Continue,
+        }
+    }
+}
 
 /// TUIアプリケーション
 pub struct TuiApp {
     tui_state: TuiState,
     running: Arc<AtomicBool>,
-    /// 終了確認ダイアログを表示中かどうか
-    exit_confirm_shown: bool,
+    /// 現在のキー入力モード
+    mode: InputMode,
+    /// グループ統合ビューを表示中か
+    group_view: bool,
+    /// 確信度がこの値未満の確定結果を暗い色で表示する（`None` なら色分けしない）
+    low_confidence_threshold: Option<f32>,
+    /// 各チャンネルに波形プレビューを表示するか
+    show_waveform: bool,
+    /// 誤認識のフラグを立てた確定結果の記録先（`None` なら記録しない）
+    feedback: Option<FeedbackWriter>,
 }
 
+/// 波形プレビューの振幅を表すブロック文字（小さい順）
+const WAVEFORM_GLYPHS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
 impl TuiApp {
     pub fn new(tui_state: TuiState, running: Arc<AtomicBool>) -> Self {
         Self {
             tui_state,
             running,
-            exit_confirm_shown: false,
+            mode: InputMode::Normal,
+            group_view: false,
+            low_confidence_threshold: None,
+            show_waveform: false,
+            feedback: None,
+        }
+    }
+
+    /// 低確信度の確定結果を色分けする閾値を設定
+    pub fn with_low_confidence_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.low_confidence_threshold = threshold;
+        self
+    }
+
+    /// 波形プレビューの表示を設定
+    pub fn with_waveform(mut self, show_waveform: bool) -> Self {
+        self.show_waveform = show_waveform;
+        self
+    }
+
+    /// 誤認識のフラグ（`x` キー）を立てた確定結果の記録先を設定
+    pub fn with_feedback(mut self, feedback: FeedbackWriter) -> Self {
+        self.feedback = Some(feedback);
+        self
+    }
+
+    /// 選択中のチャンネルで選択中の確定結果に誤認識のフラグを立て、フィードバックファイルに記録する
+    fn flag_selected_transcript(&self) {
+        let Some(channel_id) = self.tui_state.get_selected_channel_for_output() else {
+            return;
+        };
+        let mut flagged = None;
+        self.tui_state.update_channel(channel_id, |channel| {
+            flagged = channel
+                .flag_selected_transcript()
+                .map(|entry| FeedbackRecord::new(channel_id, channel.channel_name.clone(), &entry));
+        });
+        let (Some(record), Some(feedback)) = (flagged, &self.feedback) else {
+            return;
+        };
+        match feedback.append(&record) {
+            Ok(()) => log::info!(
+                "チャンネル {}: 誤認識として記録しました: {} ({:?})",
+                channel_id,
+                record.text,
+                feedback.path()
+            ),
+            Err(e) => log::warn!("チャンネル {}: {:#}", channel_id, e),
         }
     }
 
@@ -49,87 +331,45 @@ impl TuiApp {
         let mut terminal = Terminal::new(backend)?;
 
         // メインループ
+        //
+        // キー入力・状態の変更通知・次の描画時刻のいずれかを待ち、何も起きなければ眠る。
+        // キー入力はすぐに描画し、状態の変更は MIN_FRAME_INTERVAL ごとにまとめて描画する。
+        let mut events = EventStream::new();
+        let tui_state = self.tui_state.clone();
+        let mut scheduler = RedrawScheduler::default();
+        let mut stats = FrameStats::new();
         loop {
-            // 画面を描画
-            terminal.draw(|f| self.draw(f))?;
-
-            // イベントをポーリング（200msごと）
-            if event::poll(Duration::from_millis(200))? {
-                if let Event::Key(key) = event::read()? {
-                    // 終了確認ダイアログが表示されている場合
-                    if self.exit_confirm_shown {
-                        match key.code {
-                            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                                // 終了を確定
-                                self.running.store(false, Ordering::SeqCst);
-                                break;
-                            }
-                            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                                // キャンセル
-                                self.exit_confirm_shown = false;
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        // 通常のキー入力処理
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => {
-                                // 終了確認ダイアログを表示
-                                self.exit_confirm_shown = true;
-                            }
-                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                // Ctrl+C で即座に終了（確認なし）
-                                self.running.store(false, Ordering::SeqCst);
-                                break;
-                            }
-                            KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                // Ctrl+Z でプロセスを一時停止
-                                // まずターミナルをリストア
-                                disable_raw_mode()?;
-                                execute!(io::stdout(), LeaveAlternateScreen)?;
-
-                                // プロセスを一時停止
-                                #[cfg(unix)]
-                                {
-                                    use nix::sys::signal::{self, Signal};
-                                    let _ = signal::raise(Signal::SIGTSTP);
-                                }
-
-                                // 再開後にターミナルを再初期化
-                                enable_raw_mode()?;
-                                execute!(io::stdout(), EnterAlternateScreen)?;
-                            }
-                            KeyCode::Char(c) if c.is_ascii_digit() => {
-                                // 数字キーでチャンネルを選択（1キー→Ch0, 2キー→Ch1, 3キー→Ch2, 4キー→Ch3）
-                                if let Some(digit) = c.to_digit(10) {
-                                    if digit >= 1 && digit <= 9 {
-                                        let channel_id = (digit - 1) as usize;  // 1→0, 2→1, 3→2, 4→3
-                                        let channels = self.tui_state.get_all_channels();
-
-                                        // 該当するチャンネルが存在するか確認
-                                        if channels.iter().any(|ch| ch.channel_id == channel_id) {
-                                            // 現在の選択と同じなら選択解除、異なるなら選択
-                                            let current_selection = self.tui_state.get_selected_channel_for_output();
-                                            if current_selection == Some(channel_id) {
-                                                self.tui_state.set_selected_channel_for_output(None);
-                                            } else {
-                                                self.tui_state.set_selected_channel_for_output(Some(channel_id));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {}
+            let now = Instant::now();
+            if scheduler.should_draw(now) {
+                terminal.draw(|f| self.draw(f))?;
+                stats.drawn(Instant::now());
+                scheduler.drawn(now);
+            }
+
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(Ok(Event::Key(key))) => {
+                        stats.key_received(Instant::now());
+                        if self.handle_key(key)? {
+                            break;
                         }
+                        scheduler.request_immediate();
                     }
-                }
+                    Some(Ok(Event::Resize(..))) => scheduler.request_immediate(),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => break,
+                },
+                _ = tui_state.changed(), if !scheduler.is_dirty() => scheduler.mark_dirty(),
+                _ = tokio::time::sleep_until(scheduler.next_deadline().into()) => {}
             }
 
-            // running フラグをチェック
+            // running フラグをチェック（外部からの停止は次の通知・描画時刻に反映される）
             if !self.running.load(Ordering::SeqCst) {
                 break;
             }
         }
+        stats.log();
 
         // ターミナルをリストア
         disable_raw_mode()?;
@@ -139,6 +379,123 @@ impl TuiApp {
         Ok(())
     }
 
+    /// キー入力を処理する（終了する場合は `true`）
+    fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        if let InputMode::Rename { channel_id, .. } = self.mode {
+            // チャンネル名の編集中は全てのキーを入力として扱う
+            match self.mode.handle_rename_key(key) {
+                RenameAction::Continue => {}
+                RenameAction::Cancel => self.mode = InputMode::Normal,
+                RenameAction::Commit(name) => {
+                    self.tui_state.rename_channel(channel_id, name);
+                    self.mode = InputMode::Normal;
+                }
+            }
+        } else if self.mode == InputMode::ExitConfirm {
+            // 終了確認ダイアログが表示されている場合
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    // 終了を確定
+                    self.running.store(false, Ordering::SeqCst);
+                    return Ok(true);
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    // キャンセル
+                    self.mode = InputMode::Normal;
+                }
+                _ => {}
+            }
+        } else {
+            // 通常のキー入力処理
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    // 終了確認ダイアログを表示
+                    self.mode = InputMode::ExitConfirm;
+                }
+                KeyCode::F(2) | KeyCode::Char('e') => {
+                    // 選択中のチャンネルの名前を編集
+                    if let Some(channel_id) = self.tui_state.get_selected_channel_for_output() {
+                        let buffer = self.tui_state.get_channel_name(channel_id).unwrap_or_default();
+                        self.mode = InputMode::Rename { channel_id, buffer };
+                    }
+                }
+                KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-')
+                    if self.tui_state.get_output_channel().is_some() =>
+                {
+                    // 出力選択中のみモニタ音量を上下（`=` は Shift なしの `+` キー）
+                    let step = if key.code == KeyCode::Char('-') {
+                        -OUTPUT_VOLUME_STEP
+                    } else {
+                        OUTPUT_VOLUME_STEP
+                    };
+                    self.tui_state.set_output_volume(Self::step_volume(
+                        self.tui_state.get_output_volume(),
+                        step,
+                    ));
+                }
+                KeyCode::Up | KeyCode::Down => {
+                    // 選択中のチャンネルの確定結果を選ぶ（上が古い方）
+                    if let Some(channel_id) = self.tui_state.get_selected_channel_for_output() {
+                        let older = key.code == KeyCode::Up;
+                        self.tui_state
+                            .update_channel(channel_id, |channel| channel.move_transcript_selection(older));
+                    }
+                }
+                KeyCode::Char('x') => {
+                    // 選んだ確定結果を誤認識として記録
+                    self.flag_selected_transcript();
+                }
+                KeyCode::Char('p') => {
+                    // 全チャンネルの録音・文字起こしを一時停止/再開（反映は main 側のタスク）
+                    self.tui_state.toggle_paused();
+                }
+                KeyCode::Char('g') if !self.tui_state.get_all_groups().is_empty() => {
+                    // グループ統合ビューの切り替え（グループ定義がある場合のみ）
+                    self.group_view = !self.group_view;
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    // Ctrl+C で即座に終了（確認なし）
+                    self.running.store(false, Ordering::SeqCst);
+                    return Ok(true);
+                }
+                KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    // Ctrl+Z でプロセスを一時停止
+                    // まずターミナルをリストア
+                    disable_raw_mode()?;
+                    execute!(io::stdout(), LeaveAlternateScreen)?;
+
+                    // プロセスを一時停止
+                    #[cfg(unix)]
+                    {
+                        use nix::sys::signal::{self, Signal};
+                        let _ = signal::raise(Signal::SIGTSTP);
+                    }
+
+                    // 再開後にターミナルを再初期化
+                    enable_raw_mode()?;
+                    execute!(io::stdout(), EnterAlternateScreen)?;
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    // 数字キーで画面上の位置のチャンネルを選択（1キー→左端、2キー→左から2番目、…）
+                    // チャンネルIDや表示順（display_order）に関わらず、押した数字と画面の位置が対応する
+                    let channel_id = channel_key_position(c)
+                        .and_then(|position| self.tui_state.channel_id_at(position));
+                    if let Some(channel_id) = channel_id {
+                        // 現在の選択と同じなら選択解除、異なるなら選択
+                        let current_selection = self.tui_state.get_selected_channel_for_output();
+                        if current_selection == Some(channel_id) {
+                            self.tui_state.set_selected_channel_for_output(None);
+                        } else {
+                            self.tui_state.set_selected_channel_for_output(Some(channel_id));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+
     /// 画面を描画
     fn draw(&self, f: &mut Frame) {
         let channels = self.tui_state.get_all_channels();
@@ -152,6 +509,168 @@ impl TuiApp {
             return;
         }
 
+        // 一時停止中は画面上部に表示
+        let area = if self.tui_state.is_paused() {
+            let sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(f.area());
+            self.draw_pause_banner(f, sections[0]);
+            sections[1]
+        } else {
+            f.area()
+        };
+
+        // 全体のステータスバーを画面最下部に表示（時刻に依存するため IDLE_REDRAW_INTERVAL ごとに更新される）
+        let area = {
+            let sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(area);
+            let items = Self::status_bar_items(&self.tui_state.global_status(), chrono::Local::now());
+            let line = Paragraph::new(Self::fit_status_items(&items, sections[1].width as usize))
+                .style(Style::default().fg(Color::Black).bg(Color::Gray));
+            f.render_widget(line, sections[1]);
+            sections[0]
+        };
+
+        // 概算コストが有効ならステータスバーの上に表示
+        let area = match self.tui_state.get_cost_currency() {
+            Some(currency) => {
+                let sections = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(1)])
+                    .split(area);
+                let line = Paragraph::new(Self::cost_line(&channels, &currency))
+                    .style(Style::default().fg(Color::Cyan));
+                f.render_widget(line, sections[1]);
+                sections[0]
+            }
+            None => area,
+        };
+
+        // 要約があれば画面下部に要約ペインを表示
+        let summaries = self.tui_state.get_summaries();
+        let area = if summaries.is_empty() {
+            area
+        } else {
+            let sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(SUMMARY_PANE_HEIGHT)])
+                .split(area);
+            self.draw_summaries(f, sections[1], &summaries);
+            sections[0]
+        };
+
+        let groups = self.tui_state.get_all_groups();
+        if self.group_view && !groups.is_empty() {
+            self.draw_groups(f, area, &groups, &channels);
+        } else {
+            self.draw_channels(f, area, &channels);
+        }
+
+        // モードに応じたダイアログを描画
+        match &self.mode {
+            InputMode::Normal => {}
+            InputMode::ExitConfirm => self.draw_exit_confirm_dialog(f),
+            InputMode::Rename { channel_id, buffer } => {
+                self.draw_rename_dialog(f, *channel_id, buffer)
+            }
+        }
+    }
+
+    /// 一時停止中の表示
+    fn draw_pause_banner(&self, f: &mut Frame, area: Rect) {
+        let banner = Paragraph::new("一時停止中 - 録音・文字起こしを停止しています (p で再開)")
+            .style(Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(banner, area);
+    }
+
+    /// 送信音声時間と概算コストの表示（全体合計とチャンネルごと）
+    fn cost_line(channels: &[ChannelState], currency: &str) -> String {
+        let total = CostSummary::total(channels.iter().filter_map(|channel| channel.cost.as_ref()));
+        let mut line = format!(
+            "概算コスト: 合計 {:.4} {}（送信 {:.1}分）",
+            total.cost,
+            currency,
+            total.sent_secs / 60.0
+        );
+        for channel in channels {
+            let cost = channel.cost.unwrap_or_default();
+            line.push_str(&format!(
+                " | {} {:.4}（{:.1}分）",
+                channel.channel_name,
+                cost.cost,
+                cost.sent_secs / 60.0
+            ));
+        }
+        line
+    }
+
+    /// ステータスバーの項目（優先度の高い順）
+    fn status_bar_items(status: &GlobalStatus, now: chrono::DateTime<chrono::Local>) -> Vec<String> {
+        let recorded = status.recorded_secs as u64;
+        let mut items = vec![
+            format!("録音 {:02}:{:02}:{:02}", recorded / 3600, recorded / 60 % 60, recorded % 60),
+            format!("接続 {}/{}", status.connected, status.channels),
+            format!("有効 {}ch", status.channels),
+        ];
+        if let Some(free) = status.disk_free_bytes {
+            items.push(format!("空き {}", format_bytes(free)));
+        }
+        match status.memory {
+            Some(memory) if memory.disconnect_buffers > 0 => items.push(format!(
+                "メモリ {}（切断中 {}）",
+                format_bytes(memory.total()),
+                format_bytes(memory.disconnect_buffers)
+            )),
+            Some(memory) => items.push(format!("メモリ {}", format_bytes(memory.total()))),
+            None => {}
+        }
+        items.push(now.format("%H:%M:%S").to_string());
+        items
+    }
+
+    /// 幅に収まるだけの項目を区切り文字でつなぐ（収まらない項目は優先度の低い末尾から省略する）
+    fn fit_status_items(items: &[String], width: usize) -> String {
+        let mut line = String::new();
+        for item in items {
+            let next = if line.is_empty() {
+                format!(" {}", item)
+            } else {
+                format!("{} | {}", line, item)
+            };
+            if display_width(&next) > width {
+                break;
+            }
+            line = next;
+        }
+        line
+    }
+
+    /// 要約ペインを描画（要約の単位ごとに最新の要約を表示）
+    fn draw_summaries(&self, f: &mut Frame, area: Rect, summaries: &[SummaryEntry]) {
+        let mut lines = Vec::new();
+        for summary in summaries {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("[{}] ", Self::extract_time_hhmmss(&summary.time)),
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(summary.target.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ]));
+            lines.extend(summary.text.lines().map(|line| Line::from(line.to_string())));
+        }
+
+        let paragraph = Paragraph::new(Text::from(lines))
+            .block(Block::default().title("要約").borders(Borders::ALL))
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
+    }
+
+    /// チャンネルごとのビューを描画
+    fn draw_channels(&self, f: &mut Frame, area: Rect, channels: &[ChannelState]) {
         // チャンネル数に応じて横方向に分割
         let constraints: Vec<Constraint> = channels
             .iter()
@@ -161,44 +680,107 @@ impl TuiApp {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(constraints)
-            .split(f.area());
+            .split(area);
 
-        // 選択されているチャンネルIDを取得
+        // 選択されているチャンネルIDと、選択がない場合の自動切替のチャンネルIDを取得
         let selected_channel_id = self.tui_state.get_selected_channel_for_output();
+        let auto_channel_id = self.tui_state.get_auto_output_channel();
 
         // 各チャンネルを描画
         for (i, channel) in channels.iter().enumerate() {
             if i < chunks.len() {
-                let is_selected = selected_channel_id == Some(channel.channel_id);
-                self.draw_channel(f, chunks[i], channel, is_selected);
+                let output = if selected_channel_id == Some(channel.channel_id) {
+                    Some(OutputMark::Selected)
+                } else if selected_channel_id.is_none() && auto_channel_id == Some(channel.channel_id) {
+                    Some(OutputMark::Auto)
+                } else {
+                    None
+                };
+                self.draw_channel(f, chunks[i], channel, output);
             }
         }
+    }
 
-        // 終了確認ダイアログを描画
-        if self.exit_confirm_shown {
-            self.draw_exit_confirm_dialog(f);
+    /// グループ統合ビューを描画
+    ///
+    /// グループごとにメンバーの確定結果を時刻順に並べ、話者としてチャンネル名（と記号）を表示する。
+    fn draw_groups(&self, f: &mut Frame, area: Rect, groups: &[GroupState], channels: &[ChannelState]) {
+        let constraints: Vec<Constraint> = groups
+            .iter()
+            .map(|_| Constraint::Percentage((100 / groups.len()) as u16))
+            .collect();
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(area);
+
+        for (group, area) in groups.iter().zip(chunks.iter()) {
+            let members: Vec<String> = group.members.iter().map(|id| (id + 1).to_string()).collect();
+            let block = Block::default()
+                .title(format!("グループ: {} (ch {})", group.name, members.join(",")))
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White));
+
+            let inner_area = block.inner(*area);
+            f.render_widget(block, *area);
+
+            let available_width = inner_area.width as usize;
+            let first_line_text_width = available_width.saturating_sub(11); // "[12:34:56] ".len()
+
+            let mut all_lines: Vec<Line> = Vec::new();
+            for item in &group.transcripts {
+                let time_str = Self::extract_time_hhmmss(&item.entry.time);
+                let symbol = channels
+                    .iter()
+                    .find(|c| c.channel_id == item.channel_id)
+                    .and_then(|c| c.symbol.as_deref());
+                all_lines.extend(Self::wrap_text_with_timestamp(
+                    &time_str,
+                    symbol,
+                    &format!("{}: {}", item.speaker, item.entry.text),
+                    first_line_text_width,
+                    available_width,
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                    Style::default().fg(Self::final_text_color(
+                        item.entry.confidence,
+                        self.low_confidence_threshold,
+                    )),
+                ));
+            }
+
+            // 最新の行が見えるように古い行をスキップ
+            let available_height = inner_area.height as usize;
+            if all_lines.len() > available_height {
+                all_lines = all_lines.split_off(all_lines.len() - available_height);
+            }
+
+            f.render_widget(Paragraph::new(Text::from(all_lines)), inner_area);
         }
     }
 
     /// 1つのチャンネルを描画
-    fn draw_channel(&self, f: &mut Frame, area: Rect, channel: &ChannelState, is_selected: bool) {
-        // 選択されている場合はタイトルに [出力中] を追加し、色を変更
-        let title = if is_selected {
-            format!(
+    fn draw_channel(&self, f: &mut Frame, area: Rect, channel: &ChannelState, output: Option<OutputMark>) {
+        // 音声出力中の場合はタイトルに [出力中] / [自動出力中] を追加し、色を変更
+        let title = match output {
+            Some(OutputMark::Selected) => format!(
                 "{}: {} [出力中]",
                 channel.channel_id + 1, channel.channel_name
-            )
-        } else {
-            format!(
+            ),
+            Some(OutputMark::Auto) => format!(
+                "{}: {} [自動出力中]",
+                channel.channel_id + 1, channel.channel_name
+            ),
+            None => format!(
                 "{}: {}",
                 channel.channel_id + 1, channel.channel_name
-            )
+            ),
         };
 
-        let border_color = if is_selected {
-            Color::Yellow
-        } else {
-            Color::White
+        let border_color = match output {
+            Some(OutputMark::Selected) => Color::Yellow,
+            Some(OutputMark::Auto) => Color::Cyan,
+            None => Color::White,
         };
 
         // チャンネル全体のブロック
@@ -210,18 +792,21 @@ impl TuiApp {
         let inner_area = block.inner(area);
         f.render_widget(block, area);
 
-        // 内部を4つの領域に分割
+        // 内部を5つの領域に分割
         // 1. Transcribe結果表示（上部、ほとんどのスペース）
         // 2. 空白行（1行）
-        // 3. ボリューム表示（下部、1行）
-        // 4. ステータス表示（下部、1行）
+        // 3. 波形プレビュー（下部、1行。無効時は0行）
+        // 4. ボリューム表示（下部、1行）
+        // 5. ステータス表示（下部、1行）
+        let waveform_height = if self.show_waveform { 1 } else { 0 };
         let sections = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Min(0),    // Transcribe結果
-                Constraint::Length(1), // 空白行
-                Constraint::Length(1), // ボリュームバー
-                Constraint::Length(1), // ステータス
+                Constraint::Min(0),                  // Transcribe結果
+                Constraint::Length(1),               // 空白行
+                Constraint::Length(waveform_height), // 波形プレビュー
+                Constraint::Length(1),               // ボリュームバー
+                Constraint::Length(1),               // ステータス
             ])
             .split(inner_area);
 
@@ -230,11 +815,17 @@ impl TuiApp {
 
         // 2. 空白行（何も描画しない）
 
-        // 3. ボリューム表示
-        self.draw_volume_bar(f, sections[2], channel);
+        // 3. 波形プレビュー
+        if self.show_waveform {
+            self.draw_waveform(f, sections[2], channel);
+        }
+
+        // 4. ボリューム表示
+        self.draw_volume_bar(f, sections[3], channel);
 
-        // 4. ステータス表示
-        self.draw_status(f, sections[3], channel);
+        // 5. ステータス表示
+        let output_volume = output.map(|_| self.tui_state.get_output_volume());
+        self.draw_status(f, sections[4], channel, output_volume);
     }
 
     /// ボリュームバーを描画
@@ -288,8 +879,61 @@ impl TuiApp {
         }
     }
 
+    /// 波形プレビューを描画
+    ///
+    /// 直近 `WAVEFORM_POINTS` 点を表示幅に合わせて間引き、各列の振幅をブロック文字で表す。
+    /// フルスケールに達した（クリップした）列は赤で表示する。
+    fn draw_waveform(&self, f: &mut Frame, area: Rect, channel: &ChannelState) {
+        let base_color = match channel.vad_state {
+            VadState::Silence => Color::Gray,
+            VadState::Voice { .. } => Color::Cyan,
+        };
+
+        let spans: Vec<Span> = Self::resample_waveform(&channel.waveform, area.width as usize)
+            .into_iter()
+            .map(|column| match column {
+                Some((min, max)) => {
+                    let clipped = min == i16::MIN || max == i16::MAX;
+                    let color = if clipped { Color::Red } else { base_color };
+                    Span::styled(Self::waveform_glyph(min, max).to_string(), Style::default().fg(color))
+                }
+                None => Span::raw(" "),
+            })
+            .collect();
+
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    /// 波形を `width` 列に間引く（各列は対応する区間の最小値・最大値）
+    ///
+    /// 表示は常に `WAVEFORM_POINTS` 点分の時間幅で、新しい点ほど右に寄せる。
+    /// まだ点が揃っていない列は `None`。
+    fn resample_waveform(points: &VecDeque<(i16, i16)>, width: usize) -> Vec<Option<(i16, i16)>> {
+        let missing = WAVEFORM_POINTS.saturating_sub(points.len());
+        (0..width)
+            .map(|column| {
+                let start = column * WAVEFORM_POINTS / width;
+                let end = ((column + 1) * WAVEFORM_POINTS / width).max(start + 1);
+                let range = start.saturating_sub(missing).min(points.len())..end.saturating_sub(missing).min(points.len());
+                points.range(range).fold(None, |acc, &(min, max)| match acc {
+                    Some((acc_min, acc_max)) => Some((min.min(acc_min), max.max(acc_max))),
+                    None => Some((min, max)),
+                })
+            })
+            .collect()
+    }
+
+    /// 区間の振幅（最小値・最大値の絶対値の大きい方）に応じたブロック文字
+    fn waveform_glyph(min: i16, max: i16) -> char {
+        let peak = min.unsigned_abs().max(max.unsigned_abs()) as usize;
+        let index = (peak * WAVEFORM_GLYPHS.len() / (i16::MAX as usize + 1)).min(WAVEFORM_GLYPHS.len() - 1);
+        WAVEFORM_GLYPHS[index]
+    }
+
     /// ステータス表示を描画
-    fn draw_status(&self, f: &mut Frame, area: Rect, channel: &ChannelState) {
+    ///
+    /// `output_volume` は音声出力中のチャンネルのみ指定し、モニタ音量を表示する。
+    fn draw_status(&self, f: &mut Frame, area: Rect, channel: &ChannelState, output_volume: Option<f32>) {
         // VAD状態
         let (vad_color, vad_text) = match channel.vad_state {
             VadState::Silence => (Color::Gray, "無音".to_string()),
@@ -321,6 +965,87 @@ impl TuiApp {
             ),
         ]);
 
+        let status_line = match output_volume {
+            Some(volume) => {
+                let mut spans = status_line.spans;
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(
+                    format!("出力音量: {:.0}%", volume * 100.0),
+                    Style::default().fg(Color::Yellow),
+                ));
+                Line::from(spans)
+            }
+            None => status_line,
+        };
+
+        let status_line = if channel.recording_error {
+            let mut spans = status_line.spans;
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                "録音停止（I/Oエラー）",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+            Line::from(spans)
+        } else {
+            status_line
+        };
+
+        // クリップ警告中はゲインの下げ幅を提案
+        let status_line = match channel.clip_reduction_db {
+            Some(db) => {
+                let mut spans = status_line.spans;
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(
+                    format!("クリップ: ゲインを下げてください（推奨-{:.0} dB）", db),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ));
+                Line::from(spans)
+            }
+            None => status_line,
+        };
+
+        // 長時間の切断で膨らむ送信待ちの音声
+        let status_line = match channel.disconnect_buffer {
+            (bytes, secs) if bytes > 0 => {
+                let mut spans = status_line.spans;
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(
+                    format!("切断中バッファ: {}（{:.0}秒）", format_bytes(bytes), secs),
+                    Style::default().fg(Color::Yellow),
+                ));
+                Line::from(spans)
+            }
+            _ => status_line,
+        };
+
+        // 接続から最初の文字起こし結果までの時間（チューニングの目安）
+        let status_line = match &channel.first_result_latency {
+            Some(stats) => {
+                let mut spans = status_line.spans;
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(stats.describe(), Style::default().fg(Color::Gray)));
+                Line::from(spans)
+            }
+            None => status_line,
+        };
+
+        // 設定リロードによるバックエンドの切り替え状況
+        let status_line = match &channel.backend_switch {
+            Some(status) => {
+                let (color, text) = match status {
+                    BackendSwitchStatus::Pending(name) => (Color::Yellow, format!("{} へ切替待ち", name)),
+                    BackendSwitchStatus::Switched(name) => (Color::Green, format!("{} に切替済み", name)),
+                    BackendSwitchStatus::Failed(name) => (Color::Red, format!("切替失敗（{} を継続）", name)),
+                };
+                let mut spans = status_line.spans;
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled("バックエンド: ", Style::default().fg(Color::White)));
+                spans.push(Span::styled(text, Style::default().fg(color)));
+                Line::from(spans)
+            }
+            None => status_line,
+        };
+
         let paragraph = Paragraph::new(status_line);
         f.render_widget(paragraph, area);
     }
@@ -338,18 +1063,34 @@ impl TuiApp {
         let mut entries_with_lines: Vec<Vec<Line>> = Vec::new();
 
         // 確定結果を古い順に処理
-        for entry in channel.transcripts.iter() {
+        // 選択中のエントリは反転表示、誤認識のフラグを立てたエントリは赤の取り消し線で表示する
+        let mut selected_end = None;
+        for (index, entry) in channel.transcripts.iter().enumerate() {
             let time_str = Self::extract_time_hhmmss(&entry.time);
+            let mut timestamp_style = Style::default().fg(Color::Green).add_modifier(Modifier::BOLD);
+            let mut text_style =
+                Style::default().fg(Self::final_text_color(entry.confidence, self.low_confidence_threshold));
+            if entry.flagged {
+                text_style = text_style.fg(Color::Red).add_modifier(Modifier::CROSSED_OUT);
+            }
+            if channel.selected_transcript == Some(index) {
+                timestamp_style = timestamp_style.add_modifier(Modifier::REVERSED);
+                text_style = text_style.add_modifier(Modifier::REVERSED);
+            }
             let wrapped_lines = Self::wrap_text_with_timestamp(
                 &time_str,
+                channel.symbol.as_deref(),
                 &entry.text,
                 first_line_text_width,
                 available_width,
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-                Style::default().fg(Color::White),
+                timestamp_style,
+                text_style,
             );
 
             entries_with_lines.push(wrapped_lines);
+            if channel.selected_transcript == Some(index) {
+                selected_end = Some(entries_with_lines.iter().map(Vec::len).sum());
+            }
         }
 
         // 部分結果を最後に追加（あれば）
@@ -365,6 +1106,7 @@ impl TuiApp {
 
             let wrapped_lines = Self::wrap_text_with_timestamp(
                 &time_str,
+                channel.symbol.as_deref(),
                 &partial.text,
                 first_line_text_width,
                 available_width,
@@ -380,13 +1122,10 @@ impl TuiApp {
             all_lines.extend(lines);
         }
 
-        // 表示可能な行数を超えている場合、最新の行が見えるように古い行をスキップ
-        let lines_to_display = if all_lines.len() > available_height {
-            // 最新のavailable_height行のみを表示（最後の部分が常に表示される）
-            all_lines.split_off(all_lines.len() - available_height)
-        } else {
-            all_lines
-        };
+        // 表示可能な行数を超えている場合、最新の行（選択中なら選択した結果）が見えるように前後の行をスキップ
+        let end = Self::visible_end(all_lines.len(), selected_end, available_height);
+        all_lines.truncate(end);
+        let lines_to_display = all_lines.split_off(end.saturating_sub(available_height));
 
         let text = Text::from(lines_to_display);
         let paragraph = Paragraph::new(text)
@@ -395,14 +1134,21 @@ impl TuiApp {
     }
 
     /// テキストを折り返してタイムスタンプ付きの行に変換
+    ///
+    /// `symbol` を指定すると1行目のタイムスタンプの後ろに付け、その幅（と区切りの空白）だけ1行目を短くする。
     fn wrap_text_with_timestamp(
         timestamp: &str,
+        symbol: Option<&str>,
         text: &str,
         first_line_text_width: usize,
         available_width: usize,
         timestamp_style: Style,
         text_style: Style,
     ) -> Vec<Line<'static>> {
+        let first_line_text_width = match symbol {
+            Some(symbol) => first_line_text_width.saturating_sub(display_width(symbol) + 1),
+            None => first_line_text_width,
+        };
         if first_line_text_width == 0 {
             return vec![];
         }
@@ -425,7 +1171,7 @@ impl TuiApp {
             let mut current_width = 0;
 
             for ch in remaining.chars() {
-                let char_width = if ch.is_ascii() { 1 } else { 2 }; // 全角文字は幅2
+                let char_width = char_width(ch);
 
                 if current_width + char_width > line_width {
                     break;
@@ -446,11 +1192,13 @@ impl TuiApp {
             remaining = &remaining[byte_count..];
 
             if is_first_line {
-                // 最初の行：タイムスタンプを含める
-                lines.push(Line::from(vec![
-                    Span::styled(format!("[{}] ", timestamp), timestamp_style),
-                    Span::styled(line_text.to_string(), text_style),
-                ]));
+                // 最初の行：タイムスタンプ（と記号）を含める
+                let mut spans = vec![Span::styled(format!("[{}] ", timestamp), timestamp_style)];
+                if let Some(symbol) = symbol {
+                    spans.push(Span::styled(format!("{} ", symbol), text_style));
+                }
+                spans.push(Span::styled(line_text.to_string(), text_style));
+                lines.push(Line::from(spans));
                 is_first_line = false;
             } else {
                 // 2行目以降：インデントなし、全幅を使う
@@ -463,6 +1211,34 @@ impl TuiApp {
         lines
     }
 
+    /// 結果表示の最後に表示する行（この行の手前まで、`available_height` 行を表示する）
+    ///
+    /// 通常は最新の行まで表示し、確定結果を選択中で最新の行までに収まらない場合は、
+    /// 選択した結果の最後の行（`selected_end` 行目の手前）が最下行になるようにさかのぼる。
+    fn visible_end(total_lines: usize, selected_end: Option<usize>, available_height: usize) -> usize {
+        match selected_end {
+            Some(selected_end) if selected_end + available_height <= total_lines => {
+                selected_end.max(available_height).min(total_lines)
+            }
+            _ => total_lines,
+        }
+    }
+
+    /// 確定結果のテキスト色（確信度が閾値未満なら暗灰色）
+    ///
+    /// 確信度を持たない結果や閾値が未設定の場合は通常の色にする。
+    fn final_text_color(confidence: Option<f32>, threshold: Option<f32>) -> Color {
+        match (confidence, threshold) {
+            (Some(confidence), Some(threshold)) if confidence < threshold => Color::DarkGray,
+            _ => Color::White,
+        }
+    }
+
+    /// 出力音量を `step` だけ変化させる（0.1刻みに丸めてクランプ）
+    fn step_volume(volume: f32, step: f32) -> f32 {
+        crate::audio_output::clamp_volume(((volume + step) * 10.0).round() / 10.0)
+    }
+
     /// dBを0.0～1.0の比率に変換
     /// -60dB～0dB を 0.0～1.0 にマッピング
     fn db_to_ratio(db: f32) -> f64 {
@@ -473,17 +1249,13 @@ impl TuiApp {
     }
 
     /// ISO 8601形式のタイムスタンプからHH:MM:SSフォーマットを抽出
+    ///
+    /// タイムスタンプは `[output] timezone` で変換済みのため、
+    /// JSONL出力と同じ基準になるよう記載されたオフセットのまま時刻を取り出す。
     fn extract_time_hhmmss(timestamp: &str) -> String {
         // ISO 8601形式（例: "2025-01-04T12:34:56+09:00"）から時:分:秒を抽出
         if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(timestamp) {
-            // ローカルタイムゾーンに変換
-            let local_dt = dt.with_timezone(&chrono::Local);
-            format!(
-                "{:02}:{:02}:{:02}",
-                local_dt.hour(),
-                local_dt.minute(),
-                local_dt.second()
-            )
+            format!("{:02}:{:02}:{:02}", dt.hour(), dt.minute(), dt.second())
         } else {
             // パース失敗時はタイムスタンプの一部を抽出する簡易版
             // "2025-01-04T12:34:56" の形式から "12:34:56" を抽出
@@ -496,6 +1268,53 @@ impl TuiApp {
         }
     }
 
+    /// チャンネル名編集ダイアログを描画
+    fn draw_rename_dialog(&self, f: &mut Frame, channel_id: usize, buffer: &str) {
+        let area = f.area();
+
+        let dialog_width = area.width.saturating_mul(50) / 100;
+        let dialog_height = 7;
+
+        let dialog_area = Rect {
+            x: (area.width.saturating_sub(dialog_width)) / 2,
+            y: (area.height.saturating_sub(dialog_height)) / 2,
+            width: dialog_width,
+            height: dialog_height,
+        };
+
+        f.render_widget(Clear, dialog_area);
+
+        let block = Block::default()
+            .title(format!("チャンネル {} の名前を変更", channel_id + 1))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .style(Style::default().bg(Color::Black).fg(Color::White));
+
+        let inner_area = block.inner(dialog_area);
+        f.render_widget(block, dialog_area);
+
+        let message = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{}_", buffer),
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Enter", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(": 確定  "),
+                Span::styled("Esc", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(": キャンセル"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(message)
+            .style(Style::default().bg(Color::Black))
+            .alignment(ratatui::layout::Alignment::Center);
+
+        f.render_widget(paragraph, inner_area);
+    }
+
     /// 終了確認ダイアログを描画
     fn draw_exit_confirm_dialog(&self, f: &mut Frame) {
         // 画面中央にダイアログを配置
@@ -553,3 +1372,273 @@ impl TuiApp {
         f.render_widget(paragraph, inner_area);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryUsage;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_rename_mode_takes_shortcut_keys_as_input() {
+        let mut mode = InputMode::Rename {
+            channel_id: 1,
+            buffer: String::new(),
+        };
+
+        // 数字や q は編集中は文字として入力される
+        for c in ['現', '場', '2', 'q'] {
+            assert_eq!(mode.handle_rename_key(key(KeyCode::Char(c))), RenameAction::Continue);
+        }
+        assert_eq!(mode.handle_rename_key(key(KeyCode::Backspace)), RenameAction::Continue);
+        assert_eq!(
+            mode.handle_rename_key(key(KeyCode::Enter)),
+            RenameAction::Commit("現場2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_redraw_scheduler() {
+        let start = Instant::now();
+        let mut scheduler = RedrawScheduler::default();
+        assert!(scheduler.should_draw(start));
+        scheduler.drawn(start);
+
+        // 変化がなければ IDLE_REDRAW_INTERVAL まで描画しない
+        assert!(!scheduler.should_draw(start + MIN_FRAME_INTERVAL));
+        assert_eq!(scheduler.next_deadline(), start + IDLE_REDRAW_INTERVAL);
+        assert!(scheduler.should_draw(start + IDLE_REDRAW_INTERVAL));
+
+        // 状態の変更は MIN_FRAME_INTERVAL ごとにまとめる
+        scheduler.mark_dirty();
+        assert!(!scheduler.should_draw(start + MIN_FRAME_INTERVAL / 2));
+        assert_eq!(scheduler.next_deadline(), start + MIN_FRAME_INTERVAL);
+        assert!(scheduler.should_draw(start + MIN_FRAME_INTERVAL));
+
+        // キー入力はすぐに描画する
+        scheduler.drawn(start + MIN_FRAME_INTERVAL);
+        scheduler.request_immediate();
+        assert!(scheduler.should_draw(start + MIN_FRAME_INTERVAL));
+        scheduler.drawn(start + MIN_FRAME_INTERVAL);
+        assert!(!scheduler.is_dirty());
+        assert!(!scheduler.should_draw(start + MIN_FRAME_INTERVAL * 2));
+    }
+
+    #[test]
+    fn test_cost_line() {
+        let mut first = ChannelState::new(0, "無線機1".to_string());
+        first.cost = Some(CostSummary { sent_secs: 90.0, cost: 0.036 });
+        let second = ChannelState::new(1, "無線機2".to_string());
+        assert_eq!(
+            TuiApp::cost_line(&[first, second], "USD"),
+            "概算コスト: 合計 0.0360 USD（送信 1.5分） | 無線機1 0.0360（1.5分） | 無線機2 0.0000（0.0分）"
+        );
+    }
+
+    #[test]
+    fn test_status_bar_items() {
+        use chrono::TimeZone;
+        let now = chrono::Local.with_ymd_and_hms(2025, 1, 4, 12, 34, 56).unwrap();
+        let status = GlobalStatus {
+            recorded_secs: 3723.9,
+            channels: 4,
+            connected: 2,
+            disk_free_bytes: Some(120_000_000_000),
+            memory: None,
+        };
+        let items = TuiApp::status_bar_items(&status, now);
+        assert_eq!(items, vec!["録音 01:02:03", "接続 2/4", "有効 4ch", "空き 120.00 GB", "12:34:56"]);
+
+        // 空き容量を取得していなければ表示しない
+        let status = GlobalStatus { disk_free_bytes: None, ..status };
+        assert_eq!(TuiApp::status_bar_items(&status, now).len(), 4);
+
+        // 切断中バッファがあれば内訳を添える
+        let memory = MemoryUsage {
+            audio_buffers: 9_600_000,
+            ..MemoryUsage::default()
+        };
+        let status = GlobalStatus { memory: Some(memory), ..status };
+        assert_eq!(TuiApp::status_bar_items(&status, now)[3], "メモリ 9.60 MB");
+        let status = GlobalStatus {
+            memory: Some(MemoryUsage {
+                disconnect_buffers: 2_000_000,
+                ..memory
+            }),
+            ..status
+        };
+        assert_eq!(TuiApp::status_bar_items(&status, now)[3], "メモリ 11.60 MB（切断中 2.00 MB）");
+    }
+
+    #[test]
+    fn test_fit_status_items() {
+        let items: Vec<String> = ["録音 01:02:03", "接続 2/4", "12:34:56"].iter().map(|s| s.to_string()).collect();
+        let full = " 録音 01:02:03 | 接続 2/4 | 12:34:56";
+        assert_eq!(TuiApp::fit_status_items(&items, 80), full);
+        assert_eq!(TuiApp::fit_status_items(&items, display_width(full)), full);
+
+        // 狭い場合は末尾の項目から省略する
+        assert_eq!(TuiApp::fit_status_items(&items, display_width(full) - 1), " 録音 01:02:03 | 接続 2/4");
+        assert_eq!(TuiApp::fit_status_items(&items, 16), " 録音 01:02:03");
+        assert_eq!(TuiApp::fit_status_items(&items, 5), "");
+    }
+
+    #[test]
+    fn test_frame_stats_key_latency() {
+        let start = Instant::now();
+        let mut stats = FrameStats::new();
+        stats.drawn(start);
+        // 描画前に複数のキーが来たら最初のキーから計る
+        stats.key_received(start);
+        stats.key_received(start + Duration::from_millis(3));
+        stats.drawn(start + Duration::from_millis(5));
+        stats.key_received(start + Duration::from_millis(10));
+        stats.drawn(start + Duration::from_millis(11));
+
+        assert_eq!(stats.draws, 3);
+        assert_eq!(stats.keys, 2);
+        assert_eq!(stats.key_latency_max, Duration::from_millis(5));
+        assert_eq!(stats.key_latency_total, Duration::from_millis(6));
+    }
+
+    #[test]
+    fn test_channel_key_position() {
+        assert_eq!(channel_key_position('1'), Some(0));
+        assert_eq!(channel_key_position('9'), Some(8));
+        assert_eq!(channel_key_position('0'), Some(9));
+        assert_eq!(channel_key_position('a'), None);
+    }
+
+    #[test]
+    fn test_step_volume() {
+        assert_eq!(TuiApp::step_volume(1.0, OUTPUT_VOLUME_STEP), 1.1);
+        assert_eq!(TuiApp::step_volume(0.1, -OUTPUT_VOLUME_STEP), 0.0);
+        // 上下限でクランプ
+        assert_eq!(TuiApp::step_volume(0.0, -OUTPUT_VOLUME_STEP), 0.0);
+        assert_eq!(TuiApp::step_volume(2.0, OUTPUT_VOLUME_STEP), crate::audio_output::MAX_OUTPUT_VOLUME);
+        // 繰り返しても誤差が蓄積しない
+        let volume = (0..7).fold(1.0, |v, _| TuiApp::step_volume(v, -OUTPUT_VOLUME_STEP));
+        assert_eq!(volume, 0.3);
+    }
+
+    #[test]
+    fn test_resample_waveform() {
+        // 点が揃うまでは左側を空ける
+        let points: VecDeque<_> = (0..WAVEFORM_POINTS / 2).map(|i| (-(i as i16), i as i16)).collect();
+        let columns = TuiApp::resample_waveform(&points, 4);
+        assert_eq!(columns[0], None);
+        assert_eq!(columns[1], None);
+        assert_eq!(columns[2], Some((-49, 49)));
+        assert_eq!(columns[3], Some((-99, 99)));
+
+        // 表示幅が点数より広くても各列に値が入る
+        let points: VecDeque<_> = vec![(0, 0); WAVEFORM_POINTS].into();
+        let columns = TuiApp::resample_waveform(&points, WAVEFORM_POINTS * 2);
+        assert!(columns.iter().all(|column| *column == Some((0, 0))));
+    }
+
+    #[test]
+    fn test_waveform_glyph() {
+        assert_eq!(TuiApp::waveform_glyph(0, 0), '▁');
+        assert_eq!(TuiApp::waveform_glyph(-16384, 100), '▅');
+        assert_eq!(TuiApp::waveform_glyph(i16::MIN, 0), '█');
+        assert_eq!(TuiApp::waveform_glyph(0, i16::MAX), '█');
+    }
+
+    /// 行の表示幅の合計
+    fn line_width(line: &Line) -> usize {
+        line.spans.iter().map(|span| display_width(&span.content)).sum()
+    }
+
+    #[test]
+    fn test_wrap_text_with_symbol() {
+        let text = "了解しました、現在地から北へ向かいます";
+        let wrap = |symbol| {
+            TuiApp::wrap_text_with_timestamp(
+                "12:34:56",
+                symbol,
+                text,
+                20 - 11,
+                20,
+                Style::default(),
+                Style::default(),
+            )
+        };
+
+        let plain = wrap(None);
+        let with_symbol = wrap(Some("📻"));
+        // 記号の分だけ1行目が短くなり、どの行も幅を超えない
+        assert!(with_symbol.iter().all(|line| line_width(line) <= 20));
+        assert!(plain.iter().all(|line| line_width(line) <= 20));
+        assert_eq!(with_symbol[0].spans[1].content, "📻 ");
+        assert_eq!(line_width(&with_symbol[0]), 11 + 3 + 6);
+        assert_eq!(plain[0].spans.len(), 2);
+
+        // 折り返してもテキストは欠けない
+        let joined: String = with_symbol
+            .iter()
+            .map(|line| line.spans.last().unwrap().content.to_string())
+            .collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn test_visible_end() {
+        // 未選択なら最新の行まで
+        assert_eq!(TuiApp::visible_end(30, None, 10), 30);
+        // 選択した結果が最新の10行に入っていればそのまま
+        assert_eq!(TuiApp::visible_end(30, Some(25), 10), 30);
+        assert_eq!(TuiApp::visible_end(30, Some(21), 10), 30);
+        // 入っていなければ選択した結果の最後の行が最下行
+        assert_eq!(TuiApp::visible_end(30, Some(15), 10), 15);
+        // 先頭付近なら先頭から表示
+        assert_eq!(TuiApp::visible_end(30, Some(3), 10), 10);
+        // 全体が収まる
+        assert_eq!(TuiApp::visible_end(5, Some(2), 10), 5);
+    }
+
+    #[test]
+    fn test_final_text_color() {
+        // 閾値未設定なら色分けしない
+        assert_eq!(TuiApp::final_text_color(Some(0.2), None), Color::White);
+        // 確信度を持たない結果は通常の色
+        assert_eq!(TuiApp::final_text_color(None, Some(0.7)), Color::White);
+
+        assert_eq!(TuiApp::final_text_color(Some(0.5), Some(0.7)), Color::DarkGray);
+        assert_eq!(TuiApp::final_text_color(Some(0.7), Some(0.7)), Color::White);
+        assert_eq!(TuiApp::final_text_color(Some(0.5), Some(0.4)), Color::White);
+    }
+
+    #[test]
+    fn test_rename_mode_cancel() {
+        let mut mode = InputMode::Rename {
+            channel_id: 0,
+            buffer: "無線機1".to_string(),
+        };
+        assert_eq!(mode.handle_rename_key(key(KeyCode::Esc)), RenameAction::Cancel);
+
+        // 空の名前は確定しない
+        let mut mode = InputMode::Rename {
+            channel_id: 0,
+            buffer: "  ".to_string(),
+        };
+        assert_eq!(mode.handle_rename_key(key(KeyCode::Enter)), RenameAction::Cancel);
+    }
+
+    #[test]
+    fn test_extract_time_uses_timestamp_offset() {
+        // 出力タイムゾーンで変換済みのオフセットをそのまま使う
+        assert_eq!(
+            TuiApp::extract_time_hhmmss("2025-01-04T12:34:56+09:00"),
+            "12:34:56"
+        );
+        assert_eq!(
+            TuiApp::extract_time_hhmmss("2025-01-04T03:34:56+00:00"),
+            "03:34:56"
+        );
+        assert_eq!(TuiApp::extract_time_hhmmss("bad"), "--:--:--");
+    }
+}
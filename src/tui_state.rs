@@ -1,7 +1,17 @@
+use crate::cost::CostSummary;
+use crate::latency::FirstResultStats;
+use crate::memory::MemoryUsage;
 use crate::types::{Stability, VadState};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::Instant;
+use tokio::sync::Notify;
+
+/// チャンネル・グループごとに保持する確定結果の件数（`[ui] max_transcript_history` のデフォルト）
+pub const DEFAULT_TRANSCRIPT_HISTORY: usize = 100;
+
+/// TUIの波形プレビューで保持する区間数（1区間 = ダウンサンプル後の1点）
+pub const WAVEFORM_POINTS: usize = 200;
 
 /// Transcribe接続状態
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -14,6 +24,17 @@ pub enum TranscribeStatus {
     Disconnected,
 }
 
+/// 設定リロードによる文字起こしバックエンドの切り替え状況（TUI表示用）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackendSwitchStatus {
+    /// 接続中の発話が終わるのを待っている（切り替え先の名前）
+    Pending(String),
+    /// 切り替えた（切り替え先の名前）
+    Switched(String),
+    /// 切り替えに失敗し、元のバックエンドを使い続けている（元のバックエンドの名前）
+    Failed(String),
+}
+
 /// 文字起こし結果（TUI表示用）
 #[derive(Clone, Debug)]
 pub struct TranscriptEntry {
@@ -27,6 +48,33 @@ pub struct TranscriptEntry {
     pub is_partial: bool,
     /// 部分結果の安定性
     pub stability: Option<Stability>,
+    /// 確定結果の確信度（バックエンドが返さない場合は `None`）
+    pub confidence: Option<f32>,
+    /// 発話区間のクリップのパス（`[output] save_clips` が無効なら `None`）
+    pub clip_path: Option<String>,
+    /// 誤認識のフラグ（TUIの `x` キー）
+    pub flagged: bool,
+}
+
+impl TranscriptEntry {
+    /// 概算メモリ使用量（バイト）
+    pub fn estimated_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.text.len()
+            + self.time.len()
+            + self.clip_path.as_ref().map_or(0, String::len)
+    }
+}
+
+/// 要約ペインに表示する要約（`[summary]` 有効時）
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummaryEntry {
+    /// 要約の単位（チャンネル名またはグループ名）
+    pub target: String,
+    /// 要約した最後の発話の時刻（ISO 8601形式）
+    pub time: String,
+    /// 要約テキスト
+    pub text: String,
 }
 
 /// チャンネル状態（TUI表示用）
@@ -48,8 +96,32 @@ pub struct ChannelState {
     pub transcribe_status: TranscribeStatus,
     /// 最新の文字起こし結果（確定結果のみ、表示可能な分だけTUIで表示）
     pub transcripts: VecDeque<TranscriptEntry>,
+    /// 上下キーで選択中の確定結果（`transcripts` の位置、未選択なら `None`）
+    pub selected_transcript: Option<usize>,
     /// 現在表示中の部分結果（partial）
     pub partial_transcript: Option<TranscriptEntry>,
+    /// クリップ警告中の場合、推奨するゲインの下げ幅 (dB)
+    pub clip_reduction_db: Option<f32>,
+    /// 設定リロードによるバックエンドの切り替え状況（切り替えていなければ `None`）
+    pub backend_switch: Option<BackendSwitchStatus>,
+    /// TUIでの表示順（`None` ならID順で末尾）
+    pub display_order: Option<u32>,
+    /// 各発話の先頭に付ける記号（`None` なら付けない）
+    pub symbol: Option<String>,
+    /// 直近の波形（区間ごとの最小値・最大値、古い順。波形プレビュー無効時は空）
+    pub waveform: VecDeque<(i16, i16)>,
+    /// 文字起こしバックエンドへの送信音声時間と概算コスト（`[cost]` が無効なら None）
+    pub cost: Option<CostSummary>,
+    /// 録音ファイルへの書き込み失敗が続き、録音を停止しているか
+    pub recording_error: bool,
+    /// 録音ファイルに書き込んだ音声の累計時間（秒、ファイルの切り替えをまたいで積算）
+    pub recorded_secs: f64,
+    /// 切断中にためている送信待ちの音声の量（バイト）と長さ（秒）（`[memory]` の集計時に更新）
+    pub disconnect_buffer: (u64, f64),
+    /// 接続から最初の文字起こし結果までの時間の集計（まだ計測していなければ None）
+    pub first_result_latency: Option<FirstResultStats>,
+    /// 確定結果の保持件数（超えた分は古いものから捨てる）
+    max_transcripts: usize,
 }
 
 impl ChannelState {
@@ -63,7 +135,19 @@ impl ChannelState {
             silence_start: Some(Instant::now()),
             transcribe_status: TranscribeStatus::Disconnected,
             transcripts: VecDeque::new(),
+            selected_transcript: None,
             partial_transcript: None,
+            clip_reduction_db: None,
+            backend_switch: None,
+            display_order: None,
+            symbol: None,
+            waveform: VecDeque::with_capacity(WAVEFORM_POINTS),
+            cost: None,
+            recording_error: false,
+            recorded_secs: 0.0,
+            disconnect_buffer: (0, 0.0),
+            first_result_latency: None,
+            max_transcripts: DEFAULT_TRANSCRIPT_HISTORY,
         }
     }
 
@@ -77,14 +161,27 @@ impl ChannelState {
         self.current_volume_db = volume_db;
     }
 
+    /// 波形プレビューにサンプルを追加
+    ///
+    /// `bucket_len` サンプルごとに最小値・最大値の1点へ間引き、直近 `WAVEFORM_POINTS` 点を保持する。
+    pub fn push_waveform(&mut self, samples: &[i16], bucket_len: usize) {
+        for bucket in samples.chunks(bucket_len.max(1)) {
+            let min = bucket.iter().copied().min().unwrap_or(0);
+            let max = bucket.iter().copied().max().unwrap_or(0);
+            self.waveform.push_back((min, max));
+        }
+        while self.waveform.len() > WAVEFORM_POINTS {
+            self.waveform.pop_front();
+        }
+    }
+
     /// VAD状態を更新
     pub fn update_vad_state(&mut self, state: VadState) {
         // 状態が変わった場合のみ処理
-        let state_changed = match (&self.vad_state, &state) {
-            (VadState::Silence, VadState::Voice { .. }) => true,
-            (VadState::Voice { .. }, VadState::Silence) => true,
-            _ => false,
-        };
+        let state_changed = matches!(
+            (&self.vad_state, &state),
+            (VadState::Silence, VadState::Voice { .. }) | (VadState::Voice { .. }, VadState::Silence)
+        );
 
         if state_changed {
             match state {
@@ -118,23 +215,8 @@ impl ChannelState {
     }
 
     /// 文字起こし結果を追加
-    pub fn add_transcript(
-        &mut self,
-        text: String,
-        time: String,
-        seconds: f64,
-        is_partial: bool,
-        stability: Option<Stability>,
-    ) {
-        let entry = TranscriptEntry {
-            text,
-            time,
-            seconds,
-            is_partial,
-            stability,
-        };
-
-        if is_partial {
+    pub fn add_transcript(&mut self, entry: TranscriptEntry) {
+        if entry.is_partial {
             // 部分結果は上書き
             self.partial_transcript = Some(entry);
         } else {
@@ -142,35 +224,190 @@ impl ChannelState {
             self.partial_transcript = None; // 部分結果をクリア
             self.transcripts.push_back(entry);
 
-            // 最大100件まで保持（メモリ節約のため）
+            // 保持件数を超えたら古いものから捨てる（メモリ節約のため）
             // 実際の表示件数は画面サイズによって動的に決定される
-            while self.transcripts.len() > 100 {
+            while self.transcripts.len() > self.max_transcripts {
                 self.transcripts.pop_front();
+                // 選択中のエントリを指し続ける（捨てたエントリを選択していたら解除）
+                self.selected_transcript = self.selected_transcript.and_then(|index| index.checked_sub(1));
             }
         }
     }
+
+    /// 確定結果の選択を1件古い方（`older`）または新しい方へ動かす
+    ///
+    /// 未選択で古い方へ動かすと最新の確定結果を選び、最新より新しい方へ動かすと選択を解除する。
+    pub fn move_transcript_selection(&mut self, older: bool) {
+        let Some(last) = self.transcripts.len().checked_sub(1) else {
+            self.selected_transcript = None;
+            return;
+        };
+        self.selected_transcript = match (self.selected_transcript, older) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => (index < last).then_some(index + 1),
+        };
+    }
+
+    /// 選択中の確定結果に誤認識のフラグを立て、そのエントリを返す
+    ///
+    /// 未選択または既にフラグを立てたエントリなら何もせず `None` を返す（同じエントリを二重に記録しない）。
+    pub fn flag_selected_transcript(&mut self) -> Option<TranscriptEntry> {
+        let entry = self.transcripts.get_mut(self.selected_transcript?)?;
+        if entry.flagged {
+            return None;
+        }
+        entry.flagged = true;
+        Some(entry.clone())
+    }
+}
+
+/// グループ統合トランスクリプトの1件（TUI表示用）
+#[derive(Clone, Debug)]
+pub struct GroupTranscriptEntry {
+    /// 発話したチャンネルID
+    pub channel_id: usize,
+    /// 話者ラベル（チャンネル名）
+    pub speaker: String,
+    /// 文字起こし結果
+    pub entry: TranscriptEntry,
+}
+
+/// チャンネルグループの状態（TUI表示用）
+#[derive(Clone, Debug)]
+pub struct GroupState {
+    /// グループ名
+    pub name: String,
+    /// メンバーのチャンネルID
+    pub members: Vec<usize>,
+    /// メンバーの確定結果を時刻順にマージしたもの
+    pub transcripts: VecDeque<GroupTranscriptEntry>,
+    /// 確定結果の保持件数（超えた分は古いものから捨てる）
+    max_transcripts: usize,
+}
+
+impl GroupState {
+    pub fn new(name: String, members: Vec<usize>) -> Self {
+        Self {
+            name,
+            members,
+            transcripts: VecDeque::new(),
+            max_transcripts: DEFAULT_TRANSCRIPT_HISTORY,
+        }
+    }
+
+    /// 確定結果を時刻（音声上の秒）順の位置に挿入
+    ///
+    /// チャンネルごとに結果の到着タイミングがずれるため、
+    /// 到着順ではなく発話時刻でマージする。
+    pub fn add_transcript(&mut self, channel_id: usize, speaker: String, entry: TranscriptEntry) {
+        let position = self
+            .transcripts
+            .iter()
+            .rposition(|e| e.entry.seconds <= entry.seconds)
+            .map_or(0, |i| i + 1);
+        self.transcripts.insert(
+            position,
+            GroupTranscriptEntry {
+                channel_id,
+                speaker,
+                entry,
+            },
+        );
+
+        // チャンネル表示と同じ件数まで保持
+        while self.transcripts.len() > self.max_transcripts {
+            self.transcripts.pop_front();
+        }
+    }
+}
+
+/// 画面下部のステータスバーに表示する全体の集計
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GlobalStatus {
+    /// 録音時間（秒、チャンネルのうち最も長いもの）
+    pub recorded_secs: f64,
+    /// 有効なチャンネル数
+    pub channels: usize,
+    /// 文字起こしバックエンドに接続中のチャンネル数
+    pub connected: usize,
+    /// 録音出力先の空き容量（バイト、まだ取得していなければ None）
+    pub disk_free_bytes: Option<u64>,
+    /// 概算メモリ使用量（まだ集計していなければ None）
+    pub memory: Option<MemoryUsage>,
 }
 
 /// 全チャンネルの状態を管理
 #[derive(Clone)]
 pub struct TuiState {
     channels: Arc<Mutex<Vec<ChannelState>>>,
+    /// チャンネルグループ（`[[groups]]` 未定義なら空）
+    groups: Arc<Mutex<Vec<GroupState>>>,
     /// 音声出力用に選択されているチャンネルID (None = 選択なし)
     selected_channel_for_output: Arc<Mutex<Option<usize>>>,
+    /// `auto_follow_active` で自動選択されたチャンネルID（手動選択中は None）
+    auto_output_channel: Arc<Mutex<Option<usize>>>,
+    /// モニタ出力の音量係数（1.0 = 原音）
+    output_volume: Arc<Mutex<f32>>,
+    /// 全チャンネルの録音・文字起こしを一時停止中か
+    paused: Arc<Mutex<bool>>,
+    /// 要約の単位ごとの最新の要約（追加順）
+    summaries: Arc<Mutex<Vec<SummaryEntry>>>,
+    /// 概算コストの通貨単位（`[cost]` が無効なら None で、コストを表示しない）
+    cost_currency: Arc<Mutex<Option<String>>>,
+    /// 録音出力先の空き容量（バイト、まだ取得していなければ None）
+    disk_free_bytes: Arc<Mutex<Option<u64>>>,
+    /// 概算メモリ使用量（まだ集計していなければ None）
+    memory: Arc<Mutex<Option<MemoryUsage>>>,
+    /// 以降に追加するチャンネル・グループの確定結果の保持件数
+    max_transcript_history: usize,
+    /// 状態の変更通知（TUIが再描画のきっかけとして待つ）
+    changed: Arc<Notify>,
 }
 
 impl TuiState {
     pub fn new() -> Self {
         Self {
             channels: Arc::new(Mutex::new(Vec::new())),
+            groups: Arc::new(Mutex::new(Vec::new())),
             selected_channel_for_output: Arc::new(Mutex::new(None)),
+            auto_output_channel: Arc::new(Mutex::new(None)),
+            output_volume: Arc::new(Mutex::new(1.0)),
+            paused: Arc::new(Mutex::new(false)),
+            summaries: Arc::new(Mutex::new(Vec::new())),
+            cost_currency: Arc::new(Mutex::new(None)),
+            disk_free_bytes: Arc::new(Mutex::new(None)),
+            memory: Arc::new(Mutex::new(None)),
+            max_transcript_history: DEFAULT_TRANSCRIPT_HISTORY,
+            changed: Arc::new(Notify::new()),
         }
     }
 
+    /// 状態の変更を通知する（待っている側がいなければ次の `changed` がすぐに返る）
+    fn notify_changed(&self) {
+        self.changed.notify_one();
+    }
+
+    /// 前回の呼び出し以降に状態が変更されるまで待つ
+    ///
+    /// 複数回の変更は1回の通知にまとめられる。
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+
+    /// 確定結果の保持件数を設定（チャンネル・グループを追加する前に呼ぶ）
+    pub fn with_max_transcript_history(mut self, max_transcript_history: usize) -> Self {
+        self.max_transcript_history = max_transcript_history.max(1);
+        self
+    }
+
     /// チャンネルを追加
     pub fn add_channel(&self, channel_id: usize, channel_name: String) {
-        let mut channels = self.channels.lock().unwrap();
-        channels.push(ChannelState::new(channel_id, channel_name));
+        let mut channel = ChannelState::new(channel_id, channel_name);
+        channel.max_transcripts = self.max_transcript_history;
+        self.channels.lock().unwrap().push(channel);
+        self.notify_changed();
     }
 
     /// チャンネル状態を取得
@@ -179,10 +416,31 @@ impl TuiState {
         channels.iter().find(|c| c.channel_id == channel_id).cloned()
     }
 
-    /// 全チャンネル状態を取得
+    /// チャンネルのTUIでの表示順を設定
+    pub fn set_display_order(&self, channel_id: usize, display_order: Option<u32>) {
+        self.update_channel(channel_id, |channel| channel.display_order = display_order);
+    }
+
+    /// チャンネルの発話の先頭に付ける記号を設定
+    pub fn set_symbol(&self, channel_id: usize, symbol: Option<String>) {
+        self.update_channel(channel_id, |channel| channel.symbol = symbol);
+    }
+
+    /// 全チャンネル状態を表示順で取得
+    ///
+    /// `display_order` の昇順（同値は追加順）で、未指定のチャンネルはその後ろにID順で並べる。
     pub fn get_all_channels(&self) -> Vec<ChannelState> {
-        let channels = self.channels.lock().unwrap();
-        channels.clone()
+        let mut channels = self.channels.lock().unwrap().clone();
+        channels.sort_by_key(|channel| match channel.display_order {
+            Some(order) => (0, order as usize),
+            None => (1, channel.channel_id),
+        });
+        channels
+    }
+
+    /// 画面上の位置（表示順で0始まり）のチャンネルIDを取得
+    pub fn channel_id_at(&self, position: usize) -> Option<usize> {
+        self.get_all_channels().get(position).map(|channel| channel.channel_id)
     }
 
     /// チャンネル状態を更新
@@ -193,6 +451,56 @@ impl TuiState {
         let mut channels = self.channels.lock().unwrap();
         if let Some(channel) = channels.iter_mut().find(|c| c.channel_id == channel_id) {
             f(channel);
+            self.notify_changed();
+        }
+    }
+
+    /// チャンネル名を変更
+    ///
+    /// 該当するチャンネルが存在した場合は true を返す。
+    pub fn rename_channel(&self, channel_id: usize, channel_name: String) -> bool {
+        let mut channels = self.channels.lock().unwrap();
+        match channels.iter_mut().find(|c| c.channel_id == channel_id) {
+            Some(channel) => {
+                channel.channel_name = channel_name;
+                self.notify_changed();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// チャンネル名を取得
+    pub fn get_channel_name(&self, channel_id: usize) -> Option<String> {
+        let channels = self.channels.lock().unwrap();
+        channels
+            .iter()
+            .find(|c| c.channel_id == channel_id)
+            .map(|c| c.channel_name.clone())
+    }
+
+    /// チャンネルグループを追加
+    pub fn add_group(&self, name: String, members: Vec<usize>) {
+        let mut group = GroupState::new(name, members);
+        group.max_transcripts = self.max_transcript_history;
+        self.groups.lock().unwrap().push(group);
+        self.notify_changed();
+    }
+
+    /// 全グループ状態を取得
+    pub fn get_all_groups(&self) -> Vec<GroupState> {
+        let groups = self.groups.lock().unwrap();
+        groups.clone()
+    }
+
+    /// チャンネルの確定結果を所属グループの統合トランスクリプトに追加
+    ///
+    /// どのグループにも所属しないチャンネルの場合は何もしない。
+    pub fn add_group_transcript(&self, channel_id: usize, speaker: String, entry: TranscriptEntry) {
+        let mut groups = self.groups.lock().unwrap();
+        if let Some(group) = groups.iter_mut().find(|g| g.members.contains(&channel_id)) {
+            group.add_transcript(channel_id, speaker, entry);
+            self.notify_changed();
         }
     }
 
@@ -200,6 +508,7 @@ impl TuiState {
     pub fn set_selected_channel_for_output(&self, channel_id: Option<usize>) {
         let mut selected = self.selected_channel_for_output.lock().unwrap();
         *selected = channel_id;
+        self.notify_changed();
     }
 
     /// 音声出力用に選択されているチャンネルIDを取得
@@ -207,6 +516,146 @@ impl TuiState {
         let selected = self.selected_channel_for_output.lock().unwrap();
         *selected
     }
+
+    /// 自動切替で音声出力するチャンネルを設定
+    pub fn set_auto_output_channel(&self, channel_id: Option<usize>) {
+        let mut auto = self.auto_output_channel.lock().unwrap();
+        if *auto != channel_id {
+            *auto = channel_id;
+            self.notify_changed();
+        }
+    }
+
+    /// 自動切替で音声出力しているチャンネルIDを取得
+    pub fn get_auto_output_channel(&self) -> Option<usize> {
+        let auto = self.auto_output_channel.lock().unwrap();
+        *auto
+    }
+
+    /// 実際に音声出力するチャンネルID（手動選択を優先し、なければ自動切替のチャンネル）
+    pub fn get_output_channel(&self) -> Option<usize> {
+        self.get_selected_channel_for_output()
+            .or_else(|| self.get_auto_output_channel())
+    }
+
+    /// チャンネルごとの発話状態（チャンネルID, 発話中か, 現在の音量 dB）
+    pub fn voice_activity(&self) -> Vec<(usize, bool, f32)> {
+        let channels = self.channels.lock().unwrap();
+        channels
+            .iter()
+            .map(|c| (c.channel_id, matches!(c.vad_state, VadState::Voice { .. }), c.current_volume_db))
+            .collect()
+    }
+
+    /// モニタ出力の音量係数を設定
+    pub fn set_output_volume(&self, gain: f32) {
+        let mut volume = self.output_volume.lock().unwrap();
+        *volume = gain;
+        self.notify_changed();
+    }
+
+    /// モニタ出力の音量係数を取得
+    pub fn get_output_volume(&self) -> f32 {
+        let volume = self.output_volume.lock().unwrap();
+        *volume
+    }
+
+    /// 概算コストの表示を有効にする（`currency` は表示する通貨単位）
+    pub fn set_cost_currency(&self, currency: &str) {
+        let mut cost_currency = self.cost_currency.lock().unwrap();
+        *cost_currency = Some(currency.to_string());
+        self.notify_changed();
+    }
+
+    /// 概算コストの通貨単位（表示しない場合は None）
+    pub fn get_cost_currency(&self) -> Option<String> {
+        let cost_currency = self.cost_currency.lock().unwrap();
+        cost_currency.clone()
+    }
+
+    /// 一時停止と再開を切り替え、切り替え後の状態（一時停止中なら `true`）を返す
+    pub fn toggle_paused(&self) -> bool {
+        let mut paused = self.paused.lock().unwrap();
+        *paused = !*paused;
+        self.notify_changed();
+        *paused
+    }
+
+    /// 一時停止中か
+    pub fn is_paused(&self) -> bool {
+        let paused = self.paused.lock().unwrap();
+        *paused
+    }
+
+    /// 要約の単位 `target` の最新の要約を設定（前回の要約は置き換える）
+    pub fn set_summary(&self, target: &str, time: &str, text: &str) {
+        let entry = SummaryEntry {
+            target: target.to_string(),
+            time: time.to_string(),
+            text: text.to_string(),
+        };
+        let mut summaries = self.summaries.lock().unwrap();
+        match summaries.iter_mut().find(|s| s.target == target) {
+            Some(existing) => *existing = entry,
+            None => summaries.push(entry),
+        }
+        self.notify_changed();
+    }
+
+    /// 全要約を取得（まだ要約がなければ空）
+    pub fn get_summaries(&self) -> Vec<SummaryEntry> {
+        self.summaries.lock().unwrap().clone()
+    }
+
+    /// 録音出力先の空き容量を更新（取得に失敗した場合は None）
+    ///
+    /// 表示用のため、値が変わっても再描画の通知はしない（次の定期的な再描画で反映される）。
+    pub fn set_disk_free_bytes(&self, bytes: Option<u64>) {
+        *self.disk_free_bytes.lock().unwrap() = bytes;
+    }
+
+    /// 概算メモリ使用量を更新
+    ///
+    /// 表示用のため、再描画の通知はしない（次の定期的な再描画で反映される）。
+    pub fn set_memory_usage(&self, usage: MemoryUsage) {
+        *self.memory.lock().unwrap() = Some(usage);
+    }
+
+    /// チャンネル・グループの確定結果と部分結果の履歴の概算メモリ使用量（バイト）
+    pub fn history_bytes(&self) -> usize {
+        let channels: usize = self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|c| c.transcripts.iter().chain(&c.partial_transcript))
+            .map(TranscriptEntry::estimated_bytes)
+            .sum();
+        let groups: usize = self
+            .groups
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|g| &g.transcripts)
+            .map(|e| std::mem::size_of::<usize>() + e.speaker.len() + e.entry.estimated_bytes())
+            .sum();
+        channels + groups
+    }
+
+    /// ステータスバー用に全チャンネルの状態を集計
+    pub fn global_status(&self) -> GlobalStatus {
+        let channels = self.channels.lock().unwrap();
+        GlobalStatus {
+            recorded_secs: channels.iter().map(|c| c.recorded_secs).fold(0.0, f64::max),
+            channels: channels.len(),
+            connected: channels
+                .iter()
+                .filter(|c| c.transcribe_status == TranscribeStatus::Connected)
+                .count(),
+            disk_free_bytes: *self.disk_free_bytes.lock().unwrap(),
+            memory: *self.memory.lock().unwrap(),
+        }
+    }
 }
 
 impl Default for TuiState {
@@ -214,3 +663,265 @@ impl Default for TuiState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_channel() {
+        let state = TuiState::new();
+        state.add_channel(0, "無線機1".to_string());
+        state.add_channel(1, "無線機2".to_string());
+
+        assert!(state.rename_channel(1, "現場A".to_string()));
+        assert_eq!(state.get_channel_name(1).as_deref(), Some("現場A"));
+        assert_eq!(state.get_channel_name(0).as_deref(), Some("無線機1"));
+
+        // 存在しないチャンネル
+        assert!(!state.rename_channel(5, "不明".to_string()));
+        assert!(state.get_channel_name(5).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_changed_coalesces_notifications() {
+        let state = TuiState::new();
+        state.add_channel(0, "無線機1".to_string());
+        state.update_channel(0, |c| c.update_volume(-20.0));
+        state.update_channel(0, |c| c.update_volume(-30.0));
+
+        // 複数の変更は1回にまとめて通知する
+        state.changed().await;
+        let pending = tokio::time::timeout(std::time::Duration::from_millis(20), state.changed()).await;
+        assert!(pending.is_err());
+
+        // 存在しないチャンネルの更新は通知しない
+        state.update_channel(5, |c| c.update_volume(-10.0));
+        let pending = tokio::time::timeout(std::time::Duration::from_millis(20), state.changed()).await;
+        assert!(pending.is_err());
+
+        state.set_output_volume(0.5);
+        state.changed().await;
+    }
+
+    #[test]
+    fn test_output_channel_prefers_manual_selection() {
+        let state = TuiState::new();
+        state.add_channel(0, "無線機1".to_string());
+        state.add_channel(1, "無線機2".to_string());
+        state.update_channel(1, |c| {
+            c.update_volume(-20.0);
+            c.update_vad_state(VadState::Voice { hangover_remaining_ms: 500 });
+        });
+
+        assert_eq!(state.voice_activity(), vec![(0, false, -100.0), (1, true, -20.0)]);
+
+        state.set_auto_output_channel(Some(1));
+        assert_eq!(state.get_output_channel(), Some(1));
+        state.set_selected_channel_for_output(Some(0));
+        assert_eq!(state.get_output_channel(), Some(0));
+    }
+
+    #[test]
+    fn test_push_waveform_downsamples_and_trims() {
+        let mut channel = ChannelState::new(0, "A".to_string());
+
+        channel.push_waveform(&[1, -5, 3, 7, 0, -2], 4);
+        // 端数の区間も1点として扱う
+        assert_eq!(channel.waveform, VecDeque::from(vec![(-5, 7), (-2, 0)]));
+
+        channel.push_waveform(&vec![100; 4 * WAVEFORM_POINTS], 4);
+        assert_eq!(channel.waveform.len(), WAVEFORM_POINTS);
+        assert!(channel.waveform.iter().all(|&point| point == (100, 100)));
+    }
+
+    #[test]
+    fn test_channels_sorted_by_display_order() {
+        let state = TuiState::new();
+        for id in 0..5 {
+            state.add_channel(id, format!("ch{}", id));
+        }
+        state.set_display_order(3, Some(1));
+        state.set_display_order(1, Some(2));
+        state.set_display_order(4, Some(1));
+
+        // 指定ありを昇順（同値は追加順）、未指定はその後ろにID順
+        let ids: Vec<usize> = state.get_all_channels().iter().map(|c| c.channel_id).collect();
+        assert_eq!(ids, vec![3, 4, 1, 0, 2]);
+
+        // 画面上の位置からチャンネルIDを引く（数字キーの選択用）
+        assert_eq!(state.channel_id_at(0), Some(3));
+        assert_eq!(state.channel_id_at(3), Some(0));
+        assert_eq!(state.channel_id_at(5), None);
+    }
+
+    #[test]
+    fn test_channel_id_at_with_sparse_ids() {
+        // チャンネルIDが連番でなくても、画面上の位置で選べる
+        let state = TuiState::new();
+        for id in [2, 5, 7] {
+            state.add_channel(id, format!("ch{}", id));
+        }
+        assert_eq!(state.channel_id_at(0), Some(2));
+        assert_eq!(state.channel_id_at(2), Some(7));
+        assert_eq!(state.channel_id_at(3), None);
+    }
+
+    fn entry(text: &str, seconds: f64) -> TranscriptEntry {
+        TranscriptEntry {
+            text: text.to_string(),
+            time: String::new(),
+            seconds,
+            is_partial: false,
+            stability: None,
+            confidence: None,
+            clip_path: None,
+            flagged: false,
+        }
+    }
+
+    #[test]
+    fn test_transcript_selection_and_flag() {
+        let mut channel = ChannelState::new(0, "無線機1".to_string());
+        channel.max_transcripts = 3;
+        channel.move_transcript_selection(true);
+        assert_eq!(channel.selected_transcript, None);
+        assert!(channel.flag_selected_transcript().is_none());
+
+        for i in 0..3 {
+            channel.add_transcript(TranscriptEntry {
+                clip_path: Some(format!("clips/{}.wav", i)),
+                ..entry(&format!("発話{}", i), i as f64)
+            });
+        }
+
+        // 上キーで最新から古い方へ、先頭で止まる
+        channel.move_transcript_selection(true);
+        assert_eq!(channel.selected_transcript, Some(2));
+        channel.move_transcript_selection(true);
+        channel.move_transcript_selection(true);
+        channel.move_transcript_selection(true);
+        assert_eq!(channel.selected_transcript, Some(0));
+        channel.move_transcript_selection(false);
+        assert_eq!(channel.selected_transcript, Some(1));
+
+        let flagged = channel.flag_selected_transcript().unwrap();
+        assert_eq!(flagged.text, "発話1");
+        assert_eq!(flagged.clip_path.as_deref(), Some("clips/1.wav"));
+        assert!(channel.transcripts[1].flagged);
+        // 二重にフラグを立てない
+        assert!(channel.flag_selected_transcript().is_none());
+
+        // 古い結果が捨てられても同じエントリを選択し続ける
+        channel.add_transcript(entry("発話3", 3.0));
+        assert_eq!(channel.selected_transcript, Some(0));
+        assert_eq!(channel.transcripts[0].text, "発話1");
+        channel.add_transcript(entry("発話4", 4.0));
+        assert_eq!(channel.selected_transcript, None);
+
+        // 最新より新しい方へ動かすと選択解除
+        channel.move_transcript_selection(true);
+        channel.move_transcript_selection(false);
+        assert_eq!(channel.selected_transcript, None);
+    }
+
+    #[test]
+    fn test_group_transcripts_merged_by_time() {
+        let state = TuiState::new();
+        state.add_group("第1小隊".to_string(), vec![0, 1]);
+
+        // 到着順と発話時刻の順序が異なる
+        state.add_group_transcript(0, "無線機1".to_string(), entry("了解", 5.0));
+        state.add_group_transcript(1, "無線機2".to_string(), entry("こちら2号", 3.0));
+        state.add_group_transcript(1, "無線機2".to_string(), entry("どうぞ", 7.0));
+        // グループ外のチャンネルは無視
+        state.add_group_transcript(2, "無線機3".to_string(), entry("無関係", 4.0));
+
+        let groups = state.get_all_groups();
+        let merged: Vec<(&str, &str)> = groups[0]
+            .transcripts
+            .iter()
+            .map(|e| (e.speaker.as_str(), e.entry.text.as_str()))
+            .collect();
+        assert_eq!(
+            merged,
+            vec![("無線機2", "こちら2号"), ("無線機1", "了解"), ("無線機2", "どうぞ")]
+        );
+    }
+
+    #[test]
+    fn test_transcript_history_limit() {
+        let state = TuiState::new().with_max_transcript_history(3);
+        state.add_channel(0, "無線機1".to_string());
+        state.add_group("第1小隊".to_string(), vec![0]);
+
+        for i in 0..5 {
+            state.update_channel(0, |channel| {
+                channel.add_transcript(entry(&format!("発話{}", i), i as f64));
+            });
+            state.add_group_transcript(0, "無線機1".to_string(), entry(&format!("発話{}", i), i as f64));
+        }
+
+        // 最古のものから捨てる
+        let texts: Vec<String> = state.get_channel(0).unwrap().transcripts.iter().map(|e| e.text.clone()).collect();
+        assert_eq!(texts, vec!["発話2", "発話3", "発話4"]);
+        assert_eq!(state.get_all_groups()[0].transcripts.len(), 3);
+        assert_eq!(state.get_all_groups()[0].transcripts[0].entry.text, "発話2");
+
+        // 保持している分だけをメモリ使用量として数える（"発話N" は7バイト）
+        let entry_bytes = std::mem::size_of::<TranscriptEntry>() + 7;
+        let group_entry_bytes = std::mem::size_of::<usize>() + "無線機1".len() + entry_bytes;
+        assert_eq!(state.history_bytes(), entry_bytes * 3 + group_entry_bytes * 3);
+    }
+
+    #[test]
+    fn test_global_status() {
+        let state = TuiState::new();
+        assert_eq!(state.global_status(), GlobalStatus::default());
+
+        state.add_channel(0, "A".to_string());
+        state.add_channel(1, "B".to_string());
+        state.add_channel(2, "C".to_string());
+        state.update_channel(0, |c| {
+            c.recorded_secs = 120.5;
+            c.update_transcribe_status(TranscribeStatus::Connected);
+        });
+        state.update_channel(1, |c| {
+            c.recorded_secs = 60.0;
+            c.update_transcribe_status(TranscribeStatus::Error);
+        });
+        state.set_disk_free_bytes(Some(5_000_000_000));
+        let memory = MemoryUsage {
+            audio_buffers: 9_600_000,
+            ..MemoryUsage::default()
+        };
+        state.set_memory_usage(memory);
+
+        assert_eq!(
+            state.global_status(),
+            GlobalStatus {
+                recorded_secs: 120.5,
+                channels: 3,
+                connected: 1,
+                disk_free_bytes: Some(5_000_000_000),
+                memory: Some(memory),
+            }
+        );
+    }
+
+    #[test]
+    fn test_summary_replaced_per_target() {
+        let state = TuiState::new();
+        assert!(state.get_summaries().is_empty());
+
+        state.set_summary("本部", "2025-01-01T09:05:00+09:00", "- 出動指示");
+        state.set_summary("現場", "2025-01-01T09:06:00+09:00", "- 到着報告");
+        state.set_summary("本部", "2025-01-01T09:10:00+09:00", "- 撤収指示");
+
+        let summaries = state.get_summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].target, "本部");
+        assert_eq!(summaries[0].text, "- 撤収指示");
+        assert_eq!(summaries[1].target, "現場");
+    }
+}
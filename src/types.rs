@@ -1,4 +1,6 @@
+use crate::timezone::OutputTimeZone;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::SystemTime;
 
 /// 16ビット整数型のオーディオサンプル
@@ -7,6 +9,96 @@ use std::time::SystemTime;
 /// -32768 から 32767 の範囲の値を取る。
 pub type SampleI16 = i16;
 
+/// 32ビット浮動小数点型のオーディオサンプル
+///
+/// `[audio] internal_format = "f32"` で内部処理に使う。-1.0 から 1.0 の範囲を取る。
+pub type SampleF32 = f32;
+
+/// 浮動小数点サンプルを16ビット整数に量子化する
+///
+/// 範囲外の値は -1.0 ～ 1.0 にクランプし、最も近い整数に丸める。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::types::quantize_to_i16;
+/// assert_eq!(quantize_to_i16(1.0), i16::MAX);
+/// assert_eq!(quantize_to_i16(-2.0), -i16::MAX);
+/// assert_eq!(quantize_to_i16(0.0), 0);
+/// assert_eq!(quantize_to_i16(0.9999 / i16::MAX as f32), 1);
+/// ```
+pub fn quantize_to_i16(sample: SampleF32) -> SampleI16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as SampleI16
+}
+
+/// 16ビット整数サンプルを -1.0 ～ 1.0 の浮動小数点に戻す（[`quantize_to_i16`] の逆変換）
+///
+/// `i16::MIN` は -1.0 に丸める。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::types::{dequantize_i16, quantize_to_i16};
+/// assert_eq!(dequantize_i16(i16::MAX), 1.0);
+/// assert_eq!(dequantize_i16(i16::MIN), -1.0);
+/// assert_eq!(quantize_to_i16(dequantize_i16(1234)), 1234);
+/// ```
+pub fn dequantize_i16(sample: SampleI16) -> SampleF32 {
+    (sample as f32 / i16::MAX as f32).max(-1.0)
+}
+
+/// 音声デバイスのサンプルフォーマットと内部形式の相互変換
+///
+/// 入力（`AudioInput`）と出力（`AudioOutput`）で共通に使う。
+/// 浮動小数点は -1.0 ～ 1.0、符号なし整数は中央値（`u16` なら 32768）を無音とする。
+pub trait DeviceSample: Copy {
+    /// -1.0 ～ 1.0 の浮動小数点に変換（入力）
+    fn to_f32(self) -> SampleF32;
+
+    /// 16ビット整数サンプルから変換（出力）
+    fn from_i16(sample: SampleI16) -> Self;
+}
+
+impl DeviceSample for f32 {
+    fn to_f32(self) -> SampleF32 {
+        self
+    }
+
+    fn from_i16(sample: SampleI16) -> Self {
+        dequantize_i16(sample)
+    }
+}
+
+impl DeviceSample for i16 {
+    fn to_f32(self) -> SampleF32 {
+        dequantize_i16(self)
+    }
+
+    fn from_i16(sample: SampleI16) -> Self {
+        sample
+    }
+}
+
+impl DeviceSample for u16 {
+    fn to_f32(self) -> SampleF32 {
+        dequantize_i16((self as i32 - 0x8000) as i16)
+    }
+
+    fn from_i16(sample: SampleI16) -> Self {
+        (sample as i32 + 0x8000) as u16
+    }
+}
+
+impl DeviceSample for i32 {
+    fn to_f32(self) -> SampleF32 {
+        (self as f64 / i32::MAX as f64).max(-1.0) as f32
+    }
+
+    fn from_i16(sample: SampleI16) -> Self {
+        (sample as i32) << 16
+    }
+}
+
 /// オーディオフォーマット情報
 ///
 /// 音声データのサンプリングレートとチャンネル数を保持する。
@@ -46,6 +138,7 @@ pub struct AudioFormat {
 ///     samples: vec![0i16; 1600], // 100ms分 @ 16kHz
 ///     format: AudioFormat { sample_rate: 16000, channels: 1 },
 ///     timestamp_ns: 1_000_000_000, // 1秒
+///     samples_f32: None,
 /// };
 /// ```
 #[derive(Clone, Debug)]
@@ -60,6 +153,11 @@ pub struct AudioChunk {
     ///
     /// UNIX_EPOCHからの経過時間
     pub timestamp_ns: u128,
+
+    /// 浮動小数点のサンプル（`[audio] internal_format = "f32"` の場合のみ）
+    ///
+    /// 入力から届いた時点では `samples` は空で、チャンネル処理の先頭でWAV・Transcribe用に量子化する。
+    pub samples_f32: Option<Vec<SampleF32>>,
 }
 
 /// バッファリングされたチャンク
@@ -73,6 +171,9 @@ pub struct BufferedChunk {
 
     /// このチャンクの開始タイムスタンプ (ナノ秒)
     pub timestamp_ns: u128,
+
+    /// VADで音声と判定されたか（`DropPolicy::KeepVoice` で無音から破棄するために使う）
+    pub is_voice: bool,
 }
 
 /// バッファオーバーフロー時のドロップポリシー
@@ -98,11 +199,18 @@ pub enum DropPolicy {
     /// 過去のデータを優先する場合に使用
     DropNewest,
 
-    /// ブロッキング（未実装）
+    /// ブロッキング
     ///
-    /// バッファが空くまで待機する。
-    /// 現在の実装では DropOldest として扱われる。
+    /// 容量を超える追加を `AudioBuffer::try_push` が `BufferFullError` で拒否する。
+    /// バックプレッシャーを掛けられるのは `try_push` を使う呼び出し側のみ。
+    /// 音声パイプライン（`ChannelProcessor`）は待てない `AudioBuffer::push` を使うため、DropOldest として動作する。
     Block,
+
+    /// 無音のデータを古いものから優先して破棄
+    ///
+    /// ネットワーク断が長引いた場合でも音声区間をなるべく残す。
+    /// 無音のチャンクがなければ DropOldest と同じ。
+    KeepVoice,
 }
 
 /// VAD（Voice Activity Detection）の状態
@@ -137,6 +245,49 @@ pub enum VadState {
     },
 }
 
+/// VADイベントの種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VadEventKind {
+    /// 音声区間の開始
+    VoiceStart,
+    /// 音声区間の終了（ハングオーバー経過後）
+    VoiceEnd,
+}
+
+/// VADの状態変化イベント
+///
+/// `ChannelProcessor::add_vad_hook` で登録したフックに渡される。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VadEvent {
+    /// チャンネルID
+    pub channel_id: usize,
+    /// イベントの種類
+    pub kind: VadEventKind,
+    /// 状態が変化したチャンクの開始タイムスタンプ (UNIX_EPOCHからのナノ秒)
+    pub timestamp_ns: u128,
+    /// 状態が変化したチャンクの音量 (dB)
+    pub volume_db: f32,
+}
+
+/// 確定結果の後処理の段（`[postprocess]`）
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PostprocessStage {
+    /// 全角英数字の正規化（`normalize_width`）
+    NormalizeWidth,
+    /// フィラーワード削除（`remove_fillers`）
+    RemoveFillers,
+    /// 置換ルール（`replacements`）
+    Replacements,
+}
+
+/// 後処理の1段を適用した後のテキスト
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct PostprocessStep {
+    pub stage: PostprocessStage,
+    pub text: String,
+}
+
 /// PartialResultsの安定性レベル
 #[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
@@ -163,7 +314,8 @@ pub enum Stability {
 ///   "timestamp_seconds": 15.234,
 ///   "text": "こちら本部、応答願います",
 ///   "is_partial": false,
-///   "stability": null
+///   "stability": null,
+///   "sequence": 42
 /// }
 /// ```
 #[derive(Clone, Debug, Serialize)]
@@ -171,15 +323,40 @@ pub struct TranscriptResult {
     /// チャンネルID
     pub channel: usize,
 
+    /// チャンネル名（出力時点の名前。TUIでの改名が反映される）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_name: Option<String>,
+
+    /// 所属するチャンネルグループ名（`[[groups]]` 未所属の場合は None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
     /// ISO 8601形式のタイムスタンプ
     pub timestamp: String,
 
     /// 開始時刻からの経過秒数
     pub timestamp_seconds: f64,
 
-    /// 文字起こしテキスト
+    /// 結果に対応する発話の長さ（秒）
+    ///
+    /// Amazon Transcribe は最初の単語の開始から最後の単語の終了まで、Whisper はセグメントの長さ。
+    /// 取得できない場合は `None`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_duration_seconds: Option<f64>,
+
+    /// 文字起こしテキスト（確定結果は後処理済みの表示用テキスト）
     pub text: String,
 
+    /// 後処理前の原文テキスト（後処理を適用した確定結果のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
+
+    /// 後処理の各段を適用した後のテキスト（適用順、テキストが変わった段のみ）
+    ///
+    /// `raw_text` から `text` までの変化を段ごとに追える。結合した結果（`merged`）では空。
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub postprocess: Vec<PostprocessStep>,
+
     /// 部分結果かどうか
     ///
     /// true: 部分結果, false: 確定結果
@@ -188,6 +365,42 @@ pub struct TranscriptResult {
     /// 部分結果の安定性（部分結果の場合のみ有効）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stability: Option<Stability>,
+
+    /// チャンネル内での結果の通し番号
+    ///
+    /// Amazon Transcribe バックエンドが結果を出力した順に増加する（他のバックエンドでは常に0）。
+    /// ストリームの予防的な切り替えをまたいでも連続する。
+    pub sequence: u64,
+
+    /// 確定結果が届かなかった部分結果を確定扱いに昇格したものか
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub promoted_from_partial: bool,
+
+    /// 分断された複数の確定結果を1つの発話に結合したものか（`[merge]` 有効時）
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub merged: bool,
+
+    /// 確信度（0.0～1.0、単語ごとの確信度の平均）
+    ///
+    /// 確信度を返さないバックエンドでは `None`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+
+    /// 検出した発話の言語（`[transcribe] allowed_languages` による自動言語切り替え時のみ）
+    ///
+    /// バックエンドの言語検出、なければ文字種による推定の結果を候補の言語コードで表す。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+
+    /// この発話の音声クリップのパス（`[output] save_clips` 有効時の確定結果のみ）
+    ///
+    /// 発話区間との対応が曖昧な場合は `None`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clip_path: Option<String>,
+
+    /// セッションのメタデータ（`[session]` の `site_id`・`event_id` など、未設定なら `None`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
 }
 
 impl TranscriptResult {
@@ -229,23 +442,28 @@ impl TranscriptResult {
         let duration = now.duration_since(start_time).unwrap_or_default();
         let timestamp_seconds = duration.as_secs_f64();
 
-        // ISO 8601形式のタイムスタンプを生成
-        let timestamp = chrono::DateTime::from_timestamp(
-            now.duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs() as i64,
-            0,
-        )
-        .map(|dt| dt.to_rfc3339())
-        .unwrap_or_default();
+        // ISO 8601形式のタイムスタンプを生成（出力タイムゾーンへは ChannelProcessor で変換する）
+        let timestamp = OutputTimeZone::Utc.format_system_time(now);
 
         Self {
             channel,
+            channel_name: None,
+            group: None,
             timestamp,
             timestamp_seconds,
+            audio_duration_seconds: None,
             text,
+            raw_text: None,
+            postprocess: Vec::new(),
             is_partial,
             stability,
+            sequence: 0,
+            promoted_from_partial: false,
+            merged: false,
+            confidence: None,
+            detected_language: None,
+            clip_path: None,
+            metadata: None,
         }
     }
 
@@ -260,32 +478,106 @@ impl TranscriptResult {
     /// * `is_partial` - 部分結果かどうか
     /// * `stability` - 部分結果の安定性（部分結果の場合のみ）
     /// * `audio_start_seconds` - 音声の実際の開始時刻（秒）
+    /// * `audio_end_seconds` - 音声の実際の終了時刻（秒）。分かれば開始時刻との差を発話時間にする
     pub fn new_with_audio_time(
         channel: usize,
         text: String,
         is_partial: bool,
         stability: Option<Stability>,
         audio_start_seconds: f64,
+        audio_end_seconds: Option<f64>,
     ) -> Self {
         let now = SystemTime::now();
 
-        // ISO 8601形式のタイムスタンプを生成
-        let timestamp = chrono::DateTime::from_timestamp(
-            now.duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs() as i64,
-            0,
-        )
-        .map(|dt| dt.to_rfc3339())
-        .unwrap_or_default();
+        // ISO 8601形式のタイムスタンプを生成（出力タイムゾーンへは ChannelProcessor で変換する）
+        let timestamp = OutputTimeZone::Utc.format_system_time(now);
 
         Self {
             channel,
+            channel_name: None,
+            group: None,
             timestamp,
             timestamp_seconds: audio_start_seconds,
+            audio_duration_seconds: audio_end_seconds.map(|end| (end - audio_start_seconds).max(0.0)),
             text,
+            raw_text: None,
+            postprocess: Vec::new(),
             is_partial,
             stability,
+            sequence: 0,
+            promoted_from_partial: false,
+            merged: false,
+            confidence: None,
+            detected_language: None,
+            clip_path: None,
+            metadata: None,
+        }
+    }
+
+    /// 通し番号を設定
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// 確信度を設定
+    pub fn with_confidence(mut self, confidence: Option<f32>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// 検出した言語を設定
+    pub fn with_detected_language(mut self, detected_language: Option<String>) -> Self {
+        self.detected_language = detected_language;
+        self
+    }
+
+    /// 確信度が `min_confidence` 以上か（JSONL出力の除外判定）
+    ///
+    /// 閾値が未設定、または確信度を持たない結果は常に `true`。
+    pub fn meets_min_confidence(&self, min_confidence: Option<f32>) -> bool {
+        match (self.confidence, min_confidence) {
+            (Some(confidence), Some(min)) => confidence >= min,
+            _ => true,
+        }
+    }
+}
+
+/// 一時停止による録音・文字起こしの空白区間（JSONL出力用）
+///
+/// 再開時に1件出力し、WAVファイルの切れ目と文字起こし結果の時刻の空白を記録する。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PauseGap {
+    /// レコードの種別（常に "pause"）
+    pub event: &'static str,
+    /// 一時停止した時刻（ISO 8601形式）
+    pub paused_at: String,
+    /// 再開した時刻（ISO 8601形式）
+    pub resumed_at: String,
+    /// 一時停止した時点の開始時刻からの経過秒数（`TranscriptResult::timestamp_seconds` と同じ基準）
+    pub paused_at_seconds: f64,
+    /// 再開した時点の開始時刻からの経過秒数
+    pub resumed_at_seconds: f64,
+    /// 一時停止していた秒数
+    pub duration_secs: f64,
+}
+
+impl PauseGap {
+    pub fn new(
+        start_time: SystemTime,
+        paused_at: SystemTime,
+        resumed_at: SystemTime,
+        timezone: &OutputTimeZone,
+    ) -> Self {
+        let seconds_since_start =
+            |time: SystemTime| time.duration_since(start_time).unwrap_or_default().as_secs_f64();
+        Self {
+            event: "pause",
+            paused_at: timezone.format_system_time(paused_at),
+            resumed_at: timezone.format_system_time(resumed_at),
+            paused_at_seconds: seconds_since_start(paused_at),
+            resumed_at_seconds: seconds_since_start(resumed_at),
+            duration_secs: resumed_at.duration_since(paused_at).unwrap_or_default().as_secs_f64(),
         }
     }
 }
@@ -294,6 +586,30 @@ impl TranscriptResult {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_device_sample_representative_values() {
+        // -1.0 / 0 / +1.0 に相当する値
+        let samples = [-i16::MAX, 0, i16::MAX];
+        assert_eq!(samples.map(f32::from_i16), [-1.0, 0.0, 1.0]);
+        assert_eq!(samples.map(i16::from_i16), samples);
+        assert_eq!(samples.map(u16::from_i16), [1, 32768, u16::MAX]);
+        assert_eq!(u16::from_i16(i16::MIN), 0);
+
+        assert_eq!([1u16, 32768, u16::MAX].map(u16::to_f32), [-1.0, 0.0, 1.0]);
+        assert_eq!(0u16.to_f32(), -1.0);
+        assert_eq!([i32::MIN, 0, i32::MAX].map(i32::to_f32), [-1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_device_sample_round_trip_is_lossless() {
+        // 整数フォーマットは内部の i16 との往復で値が変わらない（量子化ノイズが乗らない）
+        for sample in (-i16::MAX..=i16::MAX).step_by(7) {
+            assert_eq!(quantize_to_i16(f32::from_i16(sample)), sample);
+            assert_eq!(quantize_to_i16(i16::from_i16(sample).to_f32()), sample);
+            assert_eq!(quantize_to_i16(u16::from_i16(sample).to_f32()), sample);
+        }
+    }
+
     #[test]
     fn test_audio_format_creation() {
         let format = AudioFormat {
@@ -313,6 +629,7 @@ mod tests {
                 channels: 1,
             },
             timestamp_ns: 1_000_000_000,
+            samples_f32: None,
         };
         assert_eq!(chunk.samples.len(), 1600);
         assert_eq!(chunk.format.sample_rate, 16000);
@@ -327,6 +644,9 @@ mod tests {
 
         let deserialized: DropPolicy = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, DropPolicy::DropOldest);
+
+        let keep_voice: DropPolicy = serde_json::from_str("\"keep_voice\"").unwrap();
+        assert_eq!(keep_voice, DropPolicy::KeepVoice);
     }
 
     #[test]
@@ -372,4 +692,76 @@ mod tests {
         assert_eq!(parsed["text"], "こんにちは");
         assert_eq!(parsed["is_partial"], true);
     }
+
+    #[test]
+    fn test_meets_min_confidence() {
+        let start_time = SystemTime::now();
+        let result = TranscriptResult::new(0, "了解".to_string(), false, None, start_time);
+
+        // 確信度を持たない結果は閾値に関わらず除外しない
+        assert!(result.meets_min_confidence(None));
+        assert!(result.meets_min_confidence(Some(0.9)));
+
+        let result = result.with_confidence(Some(0.6));
+        assert!(result.meets_min_confidence(None));
+        assert!(result.meets_min_confidence(Some(0.5)));
+        assert!(result.meets_min_confidence(Some(0.6)));
+        assert!(!result.meets_min_confidence(Some(0.7)));
+
+        // 確信度はある場合のみJSONに出力
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"confidence\":0.6"));
+        let json = serde_json::to_string(&result.with_confidence(None)).unwrap();
+        assert!(!json.contains("confidence"));
+    }
+
+    #[test]
+    fn test_metadata_serialization() {
+        let start_time = SystemTime::now();
+        let mut result = TranscriptResult::new(0, "了解".to_string(), false, None, start_time);
+
+        // メタデータがなければ従来通り省略
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(!json.contains("metadata"));
+
+        result.metadata = Some(HashMap::from([("site_id".to_string(), "tokyo-01".to_string())]));
+        let parsed: serde_json::Value = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(parsed["metadata"]["site_id"], "tokyo-01");
+    }
+
+    #[test]
+    fn test_audio_duration() {
+        let result = TranscriptResult::new_with_audio_time(0, "了解".to_string(), false, None, 12.5, Some(14.0));
+        assert_eq!(result.timestamp_seconds, 12.5);
+        assert_eq!(result.audio_duration_seconds, Some(1.5));
+        let parsed: serde_json::Value = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(parsed["audio_duration_seconds"], 1.5);
+
+        // 終了が開始より前でも負にはしない
+        let result = TranscriptResult::new_with_audio_time(0, "了解".to_string(), false, None, 12.5, Some(12.0));
+        assert_eq!(result.audio_duration_seconds, Some(0.0));
+
+        // 取得できなければ省略
+        let result = TranscriptResult::new_with_audio_time(0, "了解".to_string(), false, None, 12.5, None);
+        assert!(!serde_json::to_string(&result).unwrap().contains("audio_duration_seconds"));
+    }
+
+    #[test]
+    fn test_pause_gap() {
+        use std::time::Duration;
+
+        let start_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_689_600);
+        let paused_at = start_time + Duration::from_secs(600);
+        let resumed_at = paused_at + Duration::from_secs(90);
+        let gap = PauseGap::new(start_time, paused_at, resumed_at, &OutputTimeZone::Utc);
+
+        assert_eq!(gap.paused_at, "2025-01-01T00:10:00+00:00");
+        assert_eq!(gap.resumed_at, "2025-01-01T00:11:30+00:00");
+        assert_eq!(gap.paused_at_seconds, 600.0);
+        assert_eq!(gap.resumed_at_seconds, 690.0);
+        assert_eq!(gap.duration_secs, 90.0);
+
+        let parsed: serde_json::Value = serde_json::from_str(&serde_json::to_string(&gap).unwrap()).unwrap();
+        assert_eq!(parsed["event"], "pause");
+    }
 }
@@ -0,0 +1,255 @@
+use crate::config::{UploadBackendType, UploadConfig};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// アップロード先のオブジェクトストレージ
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// ローカルファイルを指定キーでアップロード
+    async fn put(&self, key: &str, path: &Path) -> Result<()>;
+}
+
+/// Amazon S3 へのアップロード
+pub struct S3ObjectStore {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+}
+
+impl S3ObjectStore {
+    pub async fn new(config: &UploadConfig) -> Result<Self> {
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if let Some(region) = &config.region {
+            loader = loader.region(aws_config::Region::new(region.clone()));
+        }
+        let sdk_config = loader.load().await;
+
+        Ok(Self {
+            client: aws_sdk_s3::Client::new(&sdk_config),
+            bucket: config.bucket.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3ObjectStore {
+    async fn put(&self, key: &str, path: &Path) -> Result<()> {
+        let body = aws_sdk_s3::primitives::ByteStream::from_path(path)
+            .await
+            .with_context(|| format!("アップロード対象の読み込みに失敗: {:?}", path))?;
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("S3 へのアップロードに失敗: s3://{}/{}", self.bucket, key))?;
+
+        Ok(())
+    }
+}
+
+/// 設定に応じたオブジェクトストレージを作成
+pub async fn create_object_store(config: &UploadConfig) -> Result<Arc<dyn ObjectStore>> {
+    match config.backend {
+        UploadBackendType::S3 => Ok(Arc::new(S3ObjectStore::new(config).await?)),
+    }
+}
+
+/// finalize 済みの録音ファイルをバックグラウンドでアップロードするキュー
+///
+/// `enqueue` したファイルは1件ずつ順番にアップロードされる。
+/// 失敗時は待ち時間を倍増させながらリトライし、最後まで失敗した場合は
+/// ローカルファイルを残したままにする。
+/// すべての `UploadSink` がドロップされると、キューに残ったファイルを
+/// 処理し終えてからワーカーが終了する。
+#[derive(Clone)]
+pub struct UploadSink {
+    tx: mpsc::UnboundedSender<PathBuf>,
+}
+
+impl UploadSink {
+    /// アップロードワーカーを起動
+    ///
+    /// 戻り値の `JoinHandle` を待つとキューが空になるまで待機できる。
+    pub fn spawn(
+        config: &UploadConfig,
+        store: Arc<dyn ObjectStore>,
+    ) -> (Self, tokio::task::JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+        let prefix = config.prefix.clone();
+        let delete_after_upload = config.delete_after_upload;
+        let max_retries = config.max_retries;
+        let retry_interval = Duration::from_secs(config.retry_interval_secs);
+
+        let handle = tokio::spawn(async move {
+            while let Some(path) = rx.recv().await {
+                let key = object_key(&prefix, &path);
+                match upload_with_retry(store.as_ref(), &key, &path, max_retries, retry_interval)
+                    .await
+                {
+                    Ok(()) => {
+                        log::info!("アップロード完了: {:?} -> {}", path, key);
+                        if delete_after_upload {
+                            if let Err(e) = std::fs::remove_file(&path) {
+                                log::warn!("アップロード済みファイルの削除に失敗: {:?}: {}", path, e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("アップロードを断念しました（ローカルに保持）: {:?}: {:#}", path, e);
+                    }
+                }
+            }
+        });
+
+        (Self { tx }, handle)
+    }
+
+    /// ファイルをアップロードキューに追加
+    pub fn enqueue(&self, path: PathBuf) {
+        log::debug!("アップロードキューに追加: {:?}", path);
+        if self.tx.send(path).is_err() {
+            log::warn!("アップロードワーカーが停止しているためキューに追加できません");
+        }
+    }
+}
+
+/// 接頭辞とファイル名からオブジェクトキーを作成
+fn object_key(prefix: &str, path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    if prefix.is_empty() || prefix.ends_with('/') {
+        format!("{}{}", prefix, file_name)
+    } else {
+        format!("{}/{}", prefix, file_name)
+    }
+}
+
+/// リトライ付きでアップロード（待ち時間は失敗ごとに倍増、最大5分）
+async fn upload_with_retry(
+    store: &dyn ObjectStore,
+    key: &str,
+    path: &Path,
+    max_retries: u32,
+    retry_interval: Duration,
+) -> Result<()> {
+    const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+    let mut wait = retry_interval;
+    let mut attempt = 0;
+    loop {
+        match store.put(key, path).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < max_retries => {
+                attempt += 1;
+                log::warn!(
+                    "アップロード失敗（{}/{}回目のリトライを{}秒後に実行）: {:?}: {:#}",
+                    attempt,
+                    max_retries,
+                    wait.as_secs(),
+                    path,
+                    e
+                );
+                tokio::time::sleep(wait).await;
+                wait = (wait * 2).min(MAX_RETRY_INTERVAL);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tempfile::TempDir;
+
+    /// 指定回数だけ失敗してから成功するテスト用ストア
+    struct FlakyStore {
+        failures_left: AtomicU32,
+        uploaded: Mutex<Vec<String>>,
+    }
+
+    impl FlakyStore {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures_left: AtomicU32::new(failures),
+                uploaded: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn put(&self, key: &str, _path: &Path) -> Result<()> {
+            if self.failures_left.load(Ordering::SeqCst) > 0 {
+                self.failures_left.fetch_sub(1, Ordering::SeqCst);
+                anyhow::bail!("network unreachable");
+            }
+            self.uploaded.lock().unwrap().push(key.to_string());
+            Ok(())
+        }
+    }
+
+    fn test_config(delete_after_upload: bool, max_retries: u32) -> UploadConfig {
+        UploadConfig {
+            backend: UploadBackendType::S3,
+            bucket: "test".to_string(),
+            prefix: "rec".to_string(),
+            region: None,
+            delete_after_upload,
+            max_retries,
+            retry_interval_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_object_key() {
+        let path = Path::new("/tmp/recordings/channel_0_20250101_000000.wav");
+        assert_eq!(object_key("", path), "channel_0_20250101_000000.wav");
+        assert_eq!(object_key("rec", path), "rec/channel_0_20250101_000000.wav");
+        assert_eq!(object_key("rec/", path), "rec/channel_0_20250101_000000.wav");
+    }
+
+    #[tokio::test]
+    async fn test_upload_retries_and_deletes() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("channel_0.wav");
+        std::fs::write(&file, b"RIFF").unwrap();
+
+        let store = Arc::new(FlakyStore::new(2));
+        let (sink, handle) = UploadSink::spawn(&test_config(true, 3), store.clone());
+        sink.enqueue(file.clone());
+        drop(sink);
+        handle.await.unwrap();
+
+        assert_eq!(*store.uploaded.lock().unwrap(), vec!["rec/channel_0.wav"]);
+        assert!(!file.exists());
+    }
+
+    #[tokio::test]
+    async fn test_upload_failure_keeps_local_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("channel_1.wav");
+        std::fs::write(&file, b"RIFF").unwrap();
+
+        let store = Arc::new(FlakyStore::new(10));
+        let (sink, handle) = UploadSink::spawn(&test_config(true, 2), store.clone());
+        sink.enqueue(file.clone());
+        drop(sink);
+        handle.await.unwrap();
+
+        assert!(store.uploaded.lock().unwrap().is_empty());
+        assert!(file.exists());
+    }
+}
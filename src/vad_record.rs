@@ -0,0 +1,234 @@
+use crate::types::SampleI16;
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `record_mode = "vad_triggered"` の1チャンク分の処理
+#[derive(Debug, Clone, PartialEq)]
+pub enum VadRecordStep {
+    /// 無音区間のため書き込まない
+    Skip,
+    /// 音声区間の開始。`preroll` を先に書き込んでからチャンクを書き込む
+    Start {
+        /// 音声区間の直前の音声（`vad_preroll_ms` まで）
+        preroll: Vec<SampleI16>,
+        /// 録音セグメントの先頭（プリロールの先頭）の時刻 (UNIX_EPOCHからのナノ秒)
+        start_ns: u128,
+    },
+    /// 音声区間の続き（ハングオーバー中を含む）
+    Write,
+    /// 音声区間の終了（このチャンクは書き込まない）
+    End,
+}
+
+/// VADの判定から録音する区間を切り出す（`record_mode = "vad_triggered"`）
+///
+/// 無音区間の直近 `vad_preroll_ms` 分を保持し、音声区間の開始時に先頭に付けて語頭の欠けを防ぐ。
+/// 終了はVADのハングオーバーが明けた最初の無音チャンクで判定するため、語尾はハングオーバー分含まれる。
+/// 書き込まなかった音声だけをプリロールとして保持するため、区間の間隔が短くても重複して書き込まない。
+#[derive(Debug)]
+pub struct VadRecordGate {
+    preroll: VecDeque<SampleI16>,
+    preroll_samples: usize,
+    sample_rate: u32,
+    recording: bool,
+}
+
+impl VadRecordGate {
+    pub fn new(preroll_ms: u32, sample_rate: u32) -> Self {
+        let preroll_samples = (sample_rate as u64 * preroll_ms as u64 / 1000) as usize;
+        Self {
+            preroll: VecDeque::with_capacity(preroll_samples),
+            preroll_samples,
+            sample_rate: sample_rate.max(1),
+            recording: false,
+        }
+    }
+
+    /// 音声区間を録音中か
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// チャンクのVAD判定から書き込み方を決める
+    ///
+    /// `timestamp_ns` はチャンク先頭のタイムスタンプ (UNIX_EPOCHからのナノ秒)。
+    pub fn process(&mut self, is_voice: bool, samples: &[SampleI16], timestamp_ns: u128) -> VadRecordStep {
+        match (is_voice, self.recording) {
+            (true, false) => {
+                self.recording = true;
+                let preroll: Vec<SampleI16> = self.preroll.drain(..).collect();
+                let preroll_ns = preroll.len() as u128 * 1_000_000_000 / self.sample_rate as u128;
+                VadRecordStep::Start {
+                    start_ns: timestamp_ns.saturating_sub(preroll_ns),
+                    preroll,
+                }
+            }
+            (true, true) => VadRecordStep::Write,
+            (false, recording) => {
+                self.recording = false;
+                self.push_preroll(samples);
+                if recording {
+                    VadRecordStep::End
+                } else {
+                    VadRecordStep::Skip
+                }
+            }
+        }
+    }
+
+    /// 区間の途中で録音を区切った場合（一時停止など）に状態を戻す
+    pub fn reset(&mut self) {
+        self.recording = false;
+        self.preroll.clear();
+    }
+
+    fn push_preroll(&mut self, samples: &[SampleI16]) {
+        if self.preroll_samples == 0 {
+            return;
+        }
+        let samples = &samples[samples.len().saturating_sub(self.preroll_samples)..];
+        let overflow = (self.preroll.len() + samples.len()).saturating_sub(self.preroll_samples);
+        self.preroll.drain(..overflow);
+        self.preroll.extend(samples);
+    }
+}
+
+/// 1つのファイルに詰めて書いた録音セグメント（`vad_record_files = "single"`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordedSegment {
+    /// ファイル内の開始位置（サンプル数）
+    pub offset_samples: usize,
+    /// 長さ（サンプル数）
+    pub samples: usize,
+    /// 先頭のサンプルの実時刻 (UNIX_EPOCHからのナノ秒)
+    pub start_ns: u128,
+}
+
+impl RecordedSegment {
+    /// 先頭のサンプルの実時刻
+    pub fn started_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_nanos(self.start_ns as u64)
+    }
+}
+
+/// 録音ファイルに対応するセグメント一覧のパス
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::vad_record::segments_path;
+/// # use std::path::Path;
+/// assert_eq!(
+///     segments_path(Path::new("rec/channel_0_20250101_000000.wav")),
+///     Path::new("rec/channel_0_20250101_000000.segments.csv")
+/// );
+/// ```
+pub fn segments_path(wav_path: &Path) -> PathBuf {
+    wav_path.with_extension("segments.csv")
+}
+
+/// セグメント一覧を `<録音ファイル名>.segments.csv` に書き出す
+///
+/// 各行はファイル内の開始位置・長さ（秒）と、その区間を実際に録音した時刻（`format_time` で整形）。
+/// 行の間が、録音しなかった無音区間（ギャップ）になる。
+pub fn write_segments(
+    wav_path: &Path,
+    segments: &[RecordedSegment],
+    sample_rate: u32,
+    format_time: impl Fn(SystemTime) -> String,
+) -> Result<PathBuf> {
+    let sample_rate = sample_rate.max(1) as f64;
+    let mut csv = String::from("offset_secs,duration_secs,started_at\n");
+    for segment in segments {
+        let _ = writeln!(
+            csv,
+            "{:.3},{:.3},{}",
+            segment.offset_samples as f64 / sample_rate,
+            segment.samples as f64 / sample_rate,
+            format_time(segment.started_at())
+        );
+    }
+
+    let path = segments_path(wav_path);
+    fs::write(&path, csv).with_context(|| format!("録音セグメント一覧の書き込みに失敗: {:?}", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gate_with_preroll() {
+        // 16kHz でプリロール 100ms = 1600サンプル
+        let mut gate = VadRecordGate::new(100, 16000);
+        assert_eq!(gate.process(false, &[1; 1000], 0), VadRecordStep::Skip);
+        assert_eq!(gate.process(false, &[2; 1000], 62_500_000), VadRecordStep::Skip);
+
+        // 直近1600サンプル（1つ目の末尾600 + 2つ目の1000）をプリロールとして先頭に付ける
+        let VadRecordStep::Start { preroll, start_ns } = gate.process(true, &[3; 1000], 125_000_000) else {
+            panic!("音声区間の開始");
+        };
+        assert_eq!(preroll.len(), 1600);
+        assert_eq!(&preroll[..600], &[1; 600]);
+        assert_eq!(&preroll[600..], &[2; 1000]);
+        assert_eq!(start_ns, 25_000_000);
+        assert!(gate.is_recording());
+
+        assert_eq!(gate.process(true, &[3; 1000], 0), VadRecordStep::Write);
+        assert_eq!(gate.process(false, &[4; 100], 0), VadRecordStep::End);
+
+        // 書き込まなかった終了チャンクだけが次のプリロールになる（書き込み済みの音声は重複させない）
+        let VadRecordStep::Start { preroll, .. } = gate.process(true, &[5; 100], 0) else {
+            panic!("音声区間の開始");
+        };
+        assert_eq!(preroll, vec![4; 100]);
+    }
+
+    #[test]
+    fn test_gate_without_preroll() {
+        let mut gate = VadRecordGate::new(0, 16000);
+        gate.process(false, &[1; 1600], 0);
+        assert_eq!(
+            gate.process(true, &[2; 1600], 1_000),
+            VadRecordStep::Start {
+                preroll: Vec::new(),
+                start_ns: 1_000
+            }
+        );
+        gate.reset();
+        assert!(!gate.is_recording());
+    }
+
+    #[test]
+    fn test_write_segments() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let wav_path = temp_dir.path().join("channel_0.wav");
+        let segments = [
+            RecordedSegment {
+                offset_samples: 0,
+                samples: 16000,
+                start_ns: 1_000_000_000,
+            },
+            RecordedSegment {
+                offset_samples: 16000,
+                samples: 8000,
+                start_ns: 11_000_000_000,
+            },
+        ];
+
+        let path = write_segments(&wav_path, &segments, 16000, |time| {
+            time.duration_since(UNIX_EPOCH).unwrap().as_secs().to_string()
+        })?;
+        assert_eq!(path, temp_dir.path().join("channel_0.segments.csv"));
+        assert_eq!(
+            fs::read_to_string(path)?,
+            "offset_secs,duration_secs,started_at\n0.000,1.000,1\n1.000,0.500,11\n"
+        );
+        Ok(())
+    }
+}
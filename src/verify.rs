@@ -0,0 +1,556 @@
+use crate::vad::calculate_rms;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 飽和とみなすサンプルの絶対値
+const CLIP_LEVEL: u16 = i16::MAX as u16 - 1;
+
+/// 無音率を計算するウィンドウ長（ミリ秒）
+const SILENCE_WINDOW_MS: u32 = 100;
+
+/// この音量未満のウィンドウを無音とみなす (dBFS)
+const SILENCE_THRESHOLD_DB: f32 = -50.0;
+
+/// WAVファイルの検査で見つかった問題
+#[derive(Debug, Clone, PartialEq)]
+pub enum WavIssue {
+    /// RIFF/WAVE ヘッダではない
+    NotWav,
+    /// fmt チャンクがない
+    MissingFmt,
+    /// data チャンクがない
+    MissingData,
+    /// 16bit PCM 以外のフォーマット
+    UnsupportedFormat {
+        format_tag: u16,
+        bits_per_sample: u16,
+    },
+    /// RIFF ヘッダのサイズがファイルサイズと一致しない
+    RiffSizeMismatch { declared: u64, actual: u64 },
+    /// data チャンクのサイズが実データ長と一致しない（書き込み途中で終了した可能性）
+    DataSizeMismatch { declared: u64, actual: u64 },
+    /// データ長がフレーム境界で終わっていない
+    PartialFrame { trailing_bytes: u64 },
+    /// サイドカー stats.json の値と実データが矛盾する
+    StatsMismatch {
+        field: &'static str,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl WavIssue {
+    /// ヘッダの書き直しで修復できる問題か（`--repair-wav` の対象）
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            WavIssue::RiffSizeMismatch { .. }
+                | WavIssue::DataSizeMismatch { .. }
+                | WavIssue::PartialFrame { .. }
+        )
+    }
+
+    /// ファイルが破損しているとみなす問題か（サイドカーとの矛盾は警告のみ）
+    pub fn is_corruption(&self) -> bool {
+        !matches!(self, WavIssue::StatsMismatch { .. })
+    }
+}
+
+impl fmt::Display for WavIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WavIssue::NotWav => write!(f, "RIFF/WAVE ヘッダではありません"),
+            WavIssue::MissingFmt => write!(f, "fmt チャンクがありません"),
+            WavIssue::MissingData => write!(f, "data チャンクがありません"),
+            WavIssue::UnsupportedFormat {
+                format_tag,
+                bits_per_sample,
+            } => write!(
+                f,
+                "未対応のフォーマット (format_tag={}, {}bit)",
+                format_tag, bits_per_sample
+            ),
+            WavIssue::RiffSizeMismatch { declared, actual } => {
+                write!(
+                    f,
+                    "RIFFサイズ不一致 (ヘッダ {} / 実際 {})",
+                    declared, actual
+                )
+            }
+            WavIssue::DataSizeMismatch { declared, actual } => {
+                write!(
+                    f,
+                    "dataサイズ不一致 (ヘッダ {} / 実際 {})",
+                    declared, actual
+                )
+            }
+            WavIssue::PartialFrame { trailing_bytes } => {
+                write!(f, "末尾に不完全なフレーム ({}バイト)", trailing_bytes)
+            }
+            WavIssue::StatsMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "stats.json と不一致: {} (stats {} / 実際 {})",
+                field, expected, actual
+            ),
+        }
+    }
+}
+
+/// サイドカー stats.json の内容（存在する項目のみ照合する）
+#[derive(Debug, Default, Deserialize)]
+pub struct WavStatsSidecar {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    pub samples: Option<u64>,
+}
+
+/// 1ファイルの検査結果
+#[derive(Debug, Clone)]
+pub struct WavReport {
+    pub path: PathBuf,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// 実データから数えたサンプル数（全チャンネル合計）
+    pub samples: u64,
+    /// クリップしたサンプルの割合（0.0～1.0）
+    pub clip_ratio: f64,
+    /// 無音ウィンドウの割合（0.0～1.0）
+    pub silence_ratio: f64,
+    pub issues: Vec<WavIssue>,
+    /// サイドカー stats.json を照合したか
+    pub stats_checked: bool,
+}
+
+impl WavReport {
+    fn empty(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            sample_rate: 0,
+            channels: 0,
+            samples: 0,
+            clip_ratio: 0.0,
+            silence_ratio: 0.0,
+            issues: Vec::new(),
+            stats_checked: false,
+        }
+    }
+
+    /// 破損しているか
+    pub fn is_corrupt(&self) -> bool {
+        self.issues.iter().any(WavIssue::is_corruption)
+    }
+
+    /// ヘッダの書き直しで修復できるか
+    pub fn is_repairable(&self) -> bool {
+        self.is_corrupt()
+            && self
+                .issues
+                .iter()
+                .filter(|i| i.is_corruption())
+                .all(WavIssue::is_repairable)
+    }
+
+    /// 長さ（秒）
+    pub fn duration_seconds(&self) -> f64 {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return 0.0;
+        }
+        self.samples as f64 / self.channels as f64 / self.sample_rate as f64
+    }
+}
+
+/// ディレクトリ全体の検査結果
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub files: Vec<WavReport>,
+}
+
+impl VerifyReport {
+    /// 破損ファイルの一覧
+    pub fn corrupt_files(&self) -> impl Iterator<Item = &WavReport> {
+        self.files.iter().filter(|f| f.is_corrupt())
+    }
+
+    /// 人間可読なレポートを出力
+    pub fn print(&self) {
+        for file in &self.files {
+            let status = if file.is_corrupt() {
+                "NG"
+            } else if file.issues.is_empty() {
+                "OK"
+            } else {
+                "WARN"
+            };
+            println!(
+                "[{}] {} ({:.1}秒, {}Hz/{}ch, クリップ {:.3}%, 無音 {:.1}%{})",
+                status,
+                file.path.display(),
+                file.duration_seconds(),
+                file.sample_rate,
+                file.channels,
+                file.clip_ratio * 100.0,
+                file.silence_ratio * 100.0,
+                if file.stats_checked {
+                    ", stats.json 照合済み"
+                } else {
+                    ""
+                }
+            );
+            for issue in &file.issues {
+                println!("    - {}", issue);
+            }
+        }
+
+        let corrupt: Vec<&WavReport> = self.corrupt_files().collect();
+        println!();
+        println!(
+            "検査 {} 件: 正常 {} 件, 破損 {} 件",
+            self.files.len(),
+            self.files.len() - corrupt.len(),
+            corrupt.len()
+        );
+
+        if !corrupt.is_empty() {
+            println!();
+            println!("破損ファイル:");
+            for file in &corrupt {
+                let note = if file.is_repairable() {
+                    "--repair-wav で修復可能"
+                } else {
+                    "修復不可"
+                };
+                println!("  {} ({})", file.path.display(), note);
+            }
+        }
+    }
+}
+
+/// ディレクトリ内の全WAVファイルを並列に検査
+pub fn verify_directory<P: AsRef<Path>>(dir: P) -> Result<VerifyReport> {
+    let dir = dir.as_ref();
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("ディレクトリの読み込みに失敗: {:?}", dir))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+        })
+        .collect();
+    paths.sort();
+
+    let files = paths
+        .par_iter()
+        .map(verify_wav)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(VerifyReport { files })
+}
+
+/// 1つのWAVファイルを検査
+///
+/// ヘッダとデータ長の整合性、クリップ率、無音率を調べ、
+/// サイドカー `<名前>.stats.json` があれば照合する。
+pub fn verify_wav<P: AsRef<Path>>(path: P) -> Result<WavReport> {
+    let path = path.as_ref();
+    let bytes =
+        fs::read(path).with_context(|| format!("WAVファイルの読み込みに失敗: {:?}", path))?;
+    let mut report = WavReport::empty(path);
+
+    let Some(layout) = parse_layout(&bytes, &mut report.issues) else {
+        return Ok(report);
+    };
+    report.sample_rate = layout.sample_rate;
+    report.channels = layout.channels;
+
+    let data = &bytes[layout.data_offset..layout.data_offset + layout.data_len];
+    let samples: Vec<i16> = data
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+    report.samples = samples.len() as u64;
+    report.clip_ratio = clip_ratio(&samples);
+    report.silence_ratio = silence_ratio(&samples, layout.sample_rate, layout.channels);
+
+    if let Some(stats) = read_sidecar(path)? {
+        report.stats_checked = true;
+        check_sidecar(&stats, &mut report);
+    }
+
+    Ok(report)
+}
+
+/// ヘッダから読み取ったデータ配置
+struct WavLayout {
+    sample_rate: u32,
+    channels: u16,
+    data_offset: usize,
+    /// フレーム境界に切り詰めた実データ長
+    data_len: usize,
+}
+
+/// チャンクを走査してヘッダとデータ長の整合性を検査
+fn parse_layout(bytes: &[u8], issues: &mut Vec<WavIssue>) -> Option<WavLayout> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        issues.push(WavIssue::NotWav);
+        return None;
+    }
+
+    let read_u16 = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+    let read_u32 =
+        |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+
+    let riff_declared = read_u32(4) as u64;
+    let riff_actual = bytes.len() as u64 - 8;
+    if riff_declared != riff_actual {
+        issues.push(WavIssue::RiffSizeMismatch {
+            declared: riff_declared,
+            actual: riff_actual,
+        });
+    }
+
+    let mut fmt = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let declared = read_u32(offset + 4) as u64;
+        let body = offset + 8;
+
+        if id == b"fmt " && body + 16 <= bytes.len() {
+            fmt = Some((
+                read_u16(body),
+                read_u16(body + 2),
+                read_u32(body + 4),
+                read_u16(body + 14),
+            ));
+        } else if id == b"data" {
+            let Some((format_tag, channels, sample_rate, bits_per_sample)) = fmt else {
+                issues.push(WavIssue::MissingFmt);
+                return None;
+            };
+            // WAVE_FORMAT_PCM または WAVE_FORMAT_EXTENSIBLE の 16bit のみ対応
+            if !(format_tag == 1 || format_tag == 0xFFFE) || bits_per_sample != 16 {
+                issues.push(WavIssue::UnsupportedFormat {
+                    format_tag,
+                    bits_per_sample,
+                });
+                return None;
+            }
+
+            let available = (bytes.len() - body) as u64;
+            // 書き込み途中で終了したファイルはヘッダのサイズが 0 や古い値のまま残る
+            if declared != available {
+                issues.push(WavIssue::DataSizeMismatch {
+                    declared,
+                    actual: available,
+                });
+            }
+
+            let block_align = channels.max(1) as u64 * 2;
+            let usable = declared.min(available);
+            let trailing = usable % block_align;
+            if trailing != 0 {
+                issues.push(WavIssue::PartialFrame {
+                    trailing_bytes: trailing,
+                });
+            }
+
+            return Some(WavLayout {
+                sample_rate,
+                channels,
+                data_offset: body,
+                data_len: (usable - trailing) as usize,
+            });
+        }
+
+        // チャンクは2バイト境界にパディングされる
+        offset = body.saturating_add(declared as usize + (declared as usize & 1));
+    }
+
+    issues.push(if fmt.is_none() {
+        WavIssue::MissingFmt
+    } else {
+        WavIssue::MissingData
+    });
+    None
+}
+
+fn clip_ratio(samples: &[i16]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let clipped = samples
+        .iter()
+        .filter(|s| s.unsigned_abs() >= CLIP_LEVEL)
+        .count();
+    clipped as f64 / samples.len() as f64
+}
+
+fn silence_ratio(samples: &[i16], sample_rate: u32, channels: u16) -> f64 {
+    let window =
+        (sample_rate as usize * SILENCE_WINDOW_MS as usize / 1000) * channels.max(1) as usize;
+    if window == 0 || samples.is_empty() {
+        return 0.0;
+    }
+
+    let (silent, total) = samples
+        .chunks(window)
+        .fold((0usize, 0usize), |(silent, total), w| {
+            let rms = calculate_rms(w);
+            let db = if rms > 0.0 {
+                20.0 * rms.log10()
+            } else {
+                -100.0
+            };
+            (silent + (db < SILENCE_THRESHOLD_DB) as usize, total + 1)
+        });
+    silent as f64 / total as f64
+}
+
+/// `<名前>.stats.json` を読み込む（なければ None）
+fn read_sidecar(wav_path: &Path) -> Result<Option<WavStatsSidecar>> {
+    let sidecar = wav_path.with_extension("stats.json");
+    if !sidecar.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&sidecar)
+        .with_context(|| format!("stats.json の読み込みに失敗: {:?}", sidecar))?;
+    let stats = serde_json::from_str(&content)
+        .with_context(|| format!("stats.json のパースに失敗: {:?}", sidecar))?;
+    Ok(Some(stats))
+}
+
+fn check_sidecar(stats: &WavStatsSidecar, report: &mut WavReport) {
+    if let Some(sample_rate) = stats.sample_rate.filter(|&r| r != report.sample_rate) {
+        report.issues.push(WavIssue::StatsMismatch {
+            field: "sample_rate",
+            expected: sample_rate.to_string(),
+            actual: report.sample_rate.to_string(),
+        });
+    }
+    if let Some(channels) = stats.channels.filter(|&c| c != report.channels) {
+        report.issues.push(WavIssue::StatsMismatch {
+            field: "channels",
+            expected: channels.to_string(),
+            actual: report.channels.to_string(),
+        });
+    }
+    if let Some(samples) = stats.samples.filter(|&s| s != report.samples) {
+        report.issues.push(WavIssue::StatsMismatch {
+            field: "samples",
+            expected: samples.to_string(),
+            actual: report.samples.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wav_writer::WavWriter;
+    use tempfile::TempDir;
+
+    /// WavWriter で正常なファイルを作成
+    fn write_wav(dir: &Path, samples: &[i16]) -> PathBuf {
+        let mut writer = WavWriter::new(0, dir, 16000).unwrap();
+        writer.write_samples(samples).unwrap();
+        writer.finalize().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_valid_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut samples = vec![0i16; 16000];
+        samples[8000..16000].fill(10000);
+        samples[100..104].fill(i16::MAX);
+        let path = write_wav(temp_dir.path(), &samples);
+
+        let report = verify_wav(&path).unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!(report.samples, 16000);
+        assert_eq!(report.duration_seconds(), 1.0);
+        assert_eq!(report.clip_ratio, 4.0 / 16000.0);
+        assert!((report.silence_ratio - 0.5).abs() < 0.11);
+    }
+
+    #[test]
+    fn test_truncated_file_detected() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_wav(temp_dir.path(), &vec![100i16; 1600]);
+
+        // 書き込み途中で落ちた状態を再現（末尾を切り詰め、奇数バイトにする）
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 101]).unwrap();
+
+        let report = verify_wav(&path).unwrap();
+        assert!(report.is_corrupt());
+        assert!(report.is_repairable());
+        assert!(report.issues.iter().any(|i| matches!(
+            i,
+            WavIssue::DataSizeMismatch {
+                declared: 3200,
+                actual: 3099
+            }
+        )));
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| matches!(i, WavIssue::PartialFrame { trailing_bytes: 1 })));
+        assert_eq!(report.samples, 1549);
+    }
+
+    #[test]
+    fn test_not_wav() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("broken.wav");
+        fs::write(&path, b"garbage").unwrap();
+
+        let report = verify_wav(&path).unwrap();
+        assert_eq!(report.issues, vec![WavIssue::NotWav]);
+        assert!(!report.is_repairable());
+    }
+
+    #[test]
+    fn test_sidecar_mismatch_is_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_wav(temp_dir.path(), &vec![0i16; 1600]);
+        fs::write(
+            path.with_extension("stats.json"),
+            r#"{"sample_rate": 16000, "samples": 3200}"#,
+        )
+        .unwrap();
+
+        let report = verify_wav(&path).unwrap();
+        assert!(report.stats_checked);
+        assert!(!report.is_corrupt());
+        assert_eq!(report.issues.len(), 1);
+        assert!(matches!(
+            report.issues[0],
+            WavIssue::StatsMismatch {
+                field: "samples",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_verify_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        write_wav(temp_dir.path(), &vec![0i16; 1600]);
+        fs::write(temp_dir.path().join("z_broken.wav"), b"RIFF").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), b"not audio").unwrap();
+
+        let report = verify_directory(temp_dir.path()).unwrap();
+        assert_eq!(report.files.len(), 2);
+        let corrupt: Vec<_> = report.corrupt_files().collect();
+        assert_eq!(corrupt.len(), 1);
+        assert!(corrupt[0].path.ends_with("z_broken.wav"));
+    }
+}
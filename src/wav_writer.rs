@@ -1,18 +1,306 @@
+use crate::encryption::{DEFAULT_CHUNK_SIZE, ENCRYPTED_EXTENSION, EncryptedWriter, RecordingKey};
+use crate::timeline::{Timeline, TimelineEventKind};
 use crate::types::SampleI16;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
 use std::fs;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// 録音ファイル名テンプレートのデフォルト（`channel_<ID>_<開始時刻>.wav`）
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "channel_{channel}_{timestamp}.wav";
+
+/// テンプレートで常に使えるプレースホルダ
+const BUILTIN_PLACEHOLDERS: [&str; 5] = ["channel", "name", "date", "time", "timestamp"];
+
+/// ファイル名に使えない文字（展開した値ではこれらを `_` に置き換える）
+const INVALID_FILENAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// 録音ファイル名テンプレート（`[output] filename_template`）
+///
+/// `wav_output_dir` からの相対パスを表し、`/` 区切りでサブディレクトリを作れる。
+/// 使えるプレースホルダは `{channel}`（チャンネルID）、`{name}`（チャンネル名）、
+/// `{date}`（録音開始日 YYYYMMDD）、`{time}`（録音開始時刻 HHMMSS）、`{timestamp}`（`{date}_{time}`）と、
+/// `[session]` のキー（`{site_id}` など）。拡張子 `.wav` は省略でき、なければ付ける。
+///
+/// 未知のプレースホルダ、絶対パスや `..` を含むテンプレートは `parse` でエラーにする。
+/// 展開した値に含まれるパス区切りなどファイル名に使えない文字は `_` に置き換える。
+///
+/// # Examples
+///
+/// ```
+/// # use dcr_transcribe::wav_writer::FilenameTemplate;
+/// # use std::collections::HashMap;
+/// use chrono::TimeZone;
+///
+/// let session = HashMap::from([("site_id".to_string(), "tokyo-01".to_string())]);
+/// let template = FilenameTemplate::parse("{date}/{site_id}/ch{channel}_{name}_{time}.wav", &session).unwrap();
+/// let start = chrono::Local.with_ymd_and_hms(2025, 1, 2, 9, 30, 0).unwrap();
+/// assert_eq!(
+///     template.expand(3, "本部/無線", &start),
+///     "20250102/tokyo-01/ch3_本部_無線_093000"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameTemplate {
+    parts: Vec<TemplatePart>,
+    /// `[session]` のメタデータ（起動時に固定）
+    session: HashMap<String, String>,
+}
+
+impl FilenameTemplate {
+    /// テンプレートを解釈する
+    pub fn parse(template: &str, session: &HashMap<String, String>) -> Result<Self> {
+        let stem = template.strip_suffix(".wav").unwrap_or(template);
+        if stem.is_empty() {
+            bail!("ファイル名テンプレートが空です");
+        }
+        if stem.starts_with('/') || stem.starts_with('\\') || Path::new(stem).is_absolute() {
+            bail!("ファイル名テンプレートに絶対パスは指定できません: {}", template);
+        }
+        if stem
+            .split('/')
+            .any(|component| component.is_empty() || component == "." || component == "..")
+        {
+            bail!(
+                "ファイル名テンプレートに空のディレクトリ名や \".\" \"..\" は使えません: {}",
+                template
+            );
+        }
+
+        let mut parts = Vec::new();
+        let mut rest = stem;
+        while !rest.is_empty() {
+            match rest.find(['{', '}']) {
+                Some(i) if rest[i..].starts_with('}') => {
+                    bail!("ファイル名テンプレートの \"}}\" に対応する \"{{\" がありません: {}", template)
+                }
+                Some(i) => {
+                    if i > 0 {
+                        parts.push(TemplatePart::Literal(rest[..i].to_string()));
+                    }
+                    let Some(end) = rest[i..].find('}') else {
+                        bail!("ファイル名テンプレートの \"{{\" が閉じられていません: {}", template);
+                    };
+                    let name = &rest[i + 1..i + end];
+                    if !BUILTIN_PLACEHOLDERS.contains(&name) && !session.contains_key(name) {
+                        bail!(
+                            "ファイル名テンプレートのプレースホルダ {{{}}} は不明です（{} と [session] のキーが使えます）",
+                            name,
+                            BUILTIN_PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(", ")
+                        );
+                    }
+                    parts.push(TemplatePart::Placeholder(name.to_string()));
+                    rest = &rest[i + end + 1..];
+                }
+                None => {
+                    parts.push(TemplatePart::Literal(rest.to_string()));
+                    rest = "";
+                }
+            }
+        }
+
+        for part in &parts {
+            if let TemplatePart::Literal(literal) = part {
+                if let Some(c) = literal
+                    .chars()
+                    .find(|&c| c != '/' && (INVALID_FILENAME_CHARS.contains(&c) || c.is_control()))
+                {
+                    bail!("ファイル名テンプレートに使えない文字 {:?} が含まれています: {}", c, template);
+                }
+            }
+        }
+
+        Ok(Self {
+            parts,
+            session: session.clone(),
+        })
+    }
+
+    /// `wav_output_dir` からの相対パス（拡張子なし）に展開する
+    pub fn expand(&self, channel_id: usize, channel_name: &str, started_at: &DateTime<Local>) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(literal) => literal.clone(),
+                TemplatePart::Placeholder(name) => {
+                    let value = match name.as_str() {
+                        "channel" => channel_id.to_string(),
+                        "name" => channel_name.to_string(),
+                        "date" => started_at.format("%Y%m%d").to_string(),
+                        "time" => started_at.format("%H%M%S").to_string(),
+                        "timestamp" => started_at.format("%Y%m%d_%H%M%S").to_string(),
+                        key => self.session.get(key).cloned().unwrap_or_default(),
+                    };
+                    sanitize(&value)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for FilenameTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_FILENAME_TEMPLATE, &HashMap::new())
+            .expect("デフォルトのファイル名テンプレートは常に解釈できる")
+    }
+}
+
+/// 展開した値をファイル名の一部として使える形にする
+///
+/// 使えない文字は `_` に置き換え、空・`.`・`..` は `_` にする（ディレクトリを作らせない）。
+fn sanitize(value: &str) -> String {
+    let value: String = value
+        .trim()
+        .chars()
+        .map(|c| {
+            if INVALID_FILENAME_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    match value.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => value,
+    }
+}
+
+/// 16bit PCM のWAVヘッダの長さ
+const WAV_HEADER_LEN: usize = 44;
+
+/// 録音ファイルの拡張子（暗号化時は `wav.enc`）
+pub(crate) fn wav_extension(encrypted: bool) -> String {
+    if encrypted {
+        format!("wav.{}", ENCRYPTED_EXTENSION)
+    } else {
+        "wav".to_string()
+    }
+}
+
+/// 16bit PCM のWAVヘッダ（`data_len` は音声データのバイト数）
+fn wav_header(spec: &hound::WavSpec, data_len: u32) -> [u8; WAV_HEADER_LEN] {
+    let block_align = spec.channels * 2;
+    let mut header = [0u8; WAV_HEADER_LEN];
+    header[0..4].copy_from_slice(b"RIFF");
+    header[4..8].copy_from_slice(&(data_len.saturating_add(WAV_HEADER_LEN as u32 - 8)).to_le_bytes());
+    header[8..16].copy_from_slice(b"WAVEfmt ");
+    header[16..20].copy_from_slice(&16u32.to_le_bytes());
+    header[20..22].copy_from_slice(&1u16.to_le_bytes());
+    header[22..24].copy_from_slice(&spec.channels.to_le_bytes());
+    header[24..28].copy_from_slice(&spec.sample_rate.to_le_bytes());
+    header[28..32].copy_from_slice(&(spec.sample_rate * block_align as u32).to_le_bytes());
+    header[32..34].copy_from_slice(&block_align.to_le_bytes());
+    header[34..36].copy_from_slice(&16u16.to_le_bytes());
+    header[36..40].copy_from_slice(b"data");
+    header[40..44].copy_from_slice(&data_len.to_le_bytes());
+    header
+}
+
+/// 書き込み中の録音ファイル（平文のWAV、または暗号化したWAV）
+///
+/// hound の `finalize` は書き込み先を返さず `EncryptedWriter::finish` を呼べないため、
+/// 暗号化時は16bit PCM のWAVヘッダを自前で書き、終了時に先頭チャンクのサイズ欄を書き換える。
+pub(crate) enum WavFile {
+    Plain(hound::WavWriter<BufWriter<fs::File>>),
+    Encrypted {
+        writer: Box<EncryptedWriter<BufWriter<fs::File>>>,
+        spec: hound::WavSpec,
+        /// 書き込んだ音声データのバイト数
+        data_len: u32,
+    },
+}
+
+impl WavFile {
+    /// `path` にファイルを作成する（`key` があれば暗号化する）
+    pub(crate) fn create(path: &Path, spec: hound::WavSpec, key: Option<&RecordingKey>) -> Result<Self> {
+        let Some(key) = key else {
+            return Ok(Self::Plain(hound::WavWriter::create(path, spec)?));
+        };
+        let file = BufWriter::new(fs::File::create(path)?);
+        let mut writer = Box::new(EncryptedWriter::new(file, key, DEFAULT_CHUNK_SIZE)?);
+        writer.write_all(&wav_header(&spec, 0))?;
+        Ok(Self::Encrypted {
+            writer,
+            spec,
+            data_len: 0,
+        })
+    }
+
+    pub(crate) fn write_samples(&mut self, samples: &[SampleI16]) -> Result<()> {
+        match self {
+            Self::Plain(writer) => {
+                for &sample in samples {
+                    writer.write_sample(sample)?;
+                }
+            }
+            Self::Encrypted { writer, data_len, .. } => {
+                let bytes: Vec<u8> = samples.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+                writer.write_all(&bytes)?;
+                *data_len = data_len.saturating_add(bytes.len() as u32);
+            }
+        }
+        Ok(())
+    }
+
+    /// ヘッダのサイズ欄を確定してファイルを閉じる
+    pub(crate) fn finalize(self) -> Result<()> {
+        match self {
+            Self::Plain(writer) => writer.finalize()?,
+            Self::Encrypted {
+                mut writer,
+                spec,
+                data_len,
+            } => {
+                writer.rewrite_head(0, &wav_header(&spec, data_len))?;
+                writer.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 時間ベースのローテーションで閉じた録音ファイル（`[output] wav_rotation_secs`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotatedFile {
+    pub path: PathBuf,
+    /// ファイルの先頭のサンプルの録音時刻
+    pub started_at: SystemTime,
+    pub duration_secs: f64,
+}
 
 /// チャンネル毎のWAVファイル書き出し
 ///
 /// 無音区間を含む全音声データをWAVファイルとして保存
 pub struct WavWriter {
     channel_id: usize,
+    /// ファイル名テンプレートの `{name}` に使うチャンネル名
+    channel_name: String,
     output_dir: PathBuf,
-    current_file: Option<hound::WavWriter<BufWriter<fs::File>>>,
+    filename_template: FilenameTemplate,
+    current_file: Option<WavFile>,
+    current_path: Option<PathBuf>,
+    /// 録音ファイルの暗号鍵（`[output.encryption]` が無効なら None）
+    encryption_key: Option<RecordingKey>,
+    /// 書き込み中のファイルを開始した時刻
+    started_at: Option<SystemTime>,
     spec: hound::WavSpec,
     samples_written: usize,
+    /// ファイルの開始・終了を書き込むタイムライン（`[output] timeline_events` が無効なら None）
+    timeline: Option<Timeline>,
+    /// 1ファイルの最大の長さ（秒）。超えたら閉じて次のファイルを開く（None ならローテーションしない）
+    rotation_interval_secs: Option<u32>,
+    /// ローテーションで閉じ、まだ `take_rotated` で受け取られていないファイル
+    rotated: Vec<RotatedFile>,
 }
 
 impl WavWriter {
@@ -38,50 +326,164 @@ impl WavWriter {
 
         Ok(Self {
             channel_id,
+            channel_name: String::new(),
             output_dir,
+            filename_template: FilenameTemplate::default(),
             current_file: None,
+            current_path: None,
+            encryption_key: None,
+            started_at: None,
             spec,
             samples_written: 0,
+            timeline: None,
+            rotation_interval_secs: None,
+            rotated: Vec::new(),
         })
     }
 
+    /// 録音ファイルの開始・終了をJSONL出力のタイムラインに書き込む
+    pub fn set_timeline(&mut self, timeline: Timeline) {
+        self.timeline = Some(timeline);
+    }
+
+    /// 1ファイルの長さが `secs` 秒に達したら閉じて次のファイルを開く（`None` で無効）
+    pub fn set_rotation_interval_secs(&mut self, secs: Option<u32>) {
+        self.rotation_interval_secs = secs.filter(|&secs| secs > 0);
+    }
+
+    /// ローテーションで閉じたファイルを受け取る（受け取ったものは一覧から消える）
+    pub fn take_rotated(&mut self) -> Vec<RotatedFile> {
+        std::mem::take(&mut self.rotated)
+    }
+
+    /// ファイル名テンプレートを設定（次に開始するファイルから適用）
+    pub fn set_filename_template(&mut self, template: FilenameTemplate, channel_name: &str) {
+        self.filename_template = template;
+        self.channel_name = channel_name.to_string();
+    }
+
+    /// 録音ファイルを暗号化して `*.wav.enc` として保存する（次に開始するファイルから適用）
+    pub fn set_encryption_key(&mut self, key: RecordingKey) {
+        self.encryption_key = Some(key);
+    }
+
+    /// 録音ファイルの暗号鍵（暗号化しない場合は `None`）
+    pub fn encryption_key(&self) -> Option<&RecordingKey> {
+        self.encryption_key.as_ref()
+    }
+
     /// WAVファイルを開始（新しいファイルを作成）
+    ///
+    /// テンプレートがサブディレクトリを含む場合は作成する。
     pub fn start(&mut self) -> Result<()> {
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("channel_{}_{}.wav", self.channel_id, timestamp);
-        let filepath = self.output_dir.join(&filename);
+        self.start_at(SystemTime::now())
+    }
+
+    /// 先頭のサンプルの録音時刻を `started_at` としてWAVファイルを開始
+    ///
+    /// 録音開始をずらしている間に保持した音声を書き込む場合など、
+    /// ファイルを開くのが先頭のサンプルより遅れる場合に使う（ファイル名の日時も `started_at` になる）。
+    pub fn start_at(&mut self, started_at: SystemTime) -> Result<()> {
+        let stem = self
+            .filename_template
+            .expand(self.channel_id, &self.channel_name, &DateTime::<Local>::from(started_at));
+        let filepath = self.unused_path(&stem);
+        if let Some(parent) = filepath.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("出力ディレクトリの作成に失敗: {:?}", parent))?;
+        }
 
         log::info!("WAVファイル作成: {:?}", filepath);
 
-        let writer = hound::WavWriter::create(&filepath, self.spec)
+        let writer = WavFile::create(&filepath, self.spec, self.encryption_key.as_ref())
             .with_context(|| format!("WAVファイルの作成に失敗: {:?}", filepath))?;
+        if let Some(timeline) = &self.timeline {
+            timeline.emit(
+                &timeline
+                    .event(TimelineEventKind::RecordingStart, Some(self.channel_id), started_at)
+                    .with_path(&filepath),
+            );
+        }
 
         self.current_file = Some(writer);
+        self.current_path = Some(filepath);
+        self.started_at = Some(started_at);
         self.samples_written = 0;
 
         Ok(())
     }
 
+    /// `stem` に対応する既存ファイルと重ならないパス
+    ///
+    /// 一時停止・再開などで同じ秒に2つ目のファイルを開く場合は `_2`, `_3`, ... を付ける。
+    fn unused_path(&self, stem: &str) -> PathBuf {
+        let extension = wav_extension(self.encryption_key.is_some());
+        let mut filepath = self.output_dir.join(format!("{}.{}", stem, extension));
+        let mut suffix = 2;
+        while filepath.exists() {
+            filepath = self.output_dir.join(format!("{}_{}.{}", stem, suffix, extension));
+            suffix += 1;
+        }
+        filepath
+    }
+
     /// サンプルを書き込み
-    pub fn write_samples(&mut self, samples: &[SampleI16]) -> Result<()> {
+    ///
+    /// ローテーションが有効な場合、ファイルの長さが上限に達した位置でサンプルを分けて次のファイルに書き込む。
+    /// 次のファイルの開始時刻は、閉じたファイルの開始時刻に書き込んだ時間を足したものにする。
+    pub fn write_samples(&mut self, mut samples: &[SampleI16]) -> Result<()> {
         if self.current_file.is_none() {
             self.start()?;
         }
 
-        if let Some(writer) = &mut self.current_file {
-            for &sample in samples {
-                writer
-                    .write_sample(sample)
-                    .with_context(|| "WAVファイルへのサンプル書き込みに失敗")?;
+        let rotation_samples = self.rotation_samples();
+        while let Some(writer) = &mut self.current_file {
+            let capacity =
+                rotation_samples.map_or(samples.len(), |limit| limit.saturating_sub(self.samples_written));
+            let (head, rest) = samples.split_at(capacity.min(samples.len()));
+            writer
+                .write_samples(head)
+                .with_context(|| "WAVファイルへのサンプル書き込みに失敗")?;
+            self.samples_written += head.len();
+            samples = rest;
+            if samples.is_empty() {
+                break;
             }
-            self.samples_written += samples.len();
+            self.rotate()?;
         }
 
         Ok(())
     }
 
+    /// ローテーションする1ファイルのサンプル数
+    fn rotation_samples(&self) -> Option<usize> {
+        self.rotation_interval_secs
+            .map(|secs| (secs as usize * self.spec.sample_rate as usize).max(1))
+    }
+
+    /// 現在のファイルを閉じ、続きの時刻から次のファイルを開く
+    fn rotate(&mut self) -> Result<()> {
+        let started_at = self.started_at.unwrap_or_else(SystemTime::now);
+        let duration_secs = self.duration_seconds();
+        if let Some(path) = self.finalize_file(Some("rotation"))? {
+            self.rotated.push(RotatedFile {
+                path,
+                started_at,
+                duration_secs,
+            });
+        }
+        self.start_at(started_at + Duration::from_secs_f64(duration_secs))
+    }
+
     /// 現在のファイルを終了
-    pub fn finalize(&mut self) -> Result<()> {
+    ///
+    /// 書き込み中のファイルがあった場合は、そのパスを返す。
+    pub fn finalize(&mut self) -> Result<Option<PathBuf>> {
+        self.finalize_file(None)
+    }
+
+    /// 現在のファイルを終了（`detail` はタイムラインの `RecordingStop` に付ける理由）
+    fn finalize_file(&mut self, detail: Option<&str>) -> Result<Option<PathBuf>> {
         if let Some(writer) = self.current_file.take() {
             writer
                 .finalize()
@@ -92,9 +494,18 @@ impl WavWriter {
                 self.samples_written,
                 self.samples_written as f64 / self.spec.sample_rate as f64
             );
+            if let (Some(timeline), Some(path)) = (&self.timeline, &self.current_path) {
+                let mut event = timeline
+                    .event(TimelineEventKind::RecordingStop, Some(self.channel_id), SystemTime::now())
+                    .with_path(path)
+                    .with_duration_secs(self.duration_seconds());
+                event.detail = detail.map(str::to_string);
+                timeline.emit(&event);
+            }
             self.samples_written = 0;
         }
-        Ok(())
+        self.started_at = None;
+        Ok(self.current_path.take())
     }
 
     /// 書き込んだサンプル数
@@ -102,6 +513,11 @@ impl WavWriter {
         self.samples_written
     }
 
+    /// 書き込み中のファイルを開始した時刻（書き込み中でなければ `None`）
+    pub fn started_at(&self) -> Option<SystemTime> {
+        self.started_at
+    }
+
     /// 書き込んだ時間（秒）
     pub fn duration_seconds(&self) -> f64 {
         self.samples_written as f64 / self.spec.sample_rate as f64
@@ -147,4 +563,180 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_finalize_returns_path() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut writer = WavWriter::new(3, temp_dir.path(), 16000)?;
+
+        // 未開始なら何も返さない
+        assert!(writer.finalize()?.is_none());
+
+        writer.write_samples(&[0i16; 160])?;
+        let path = writer.finalize()?.expect("finalize したファイルのパス");
+        assert!(path.exists());
+        assert!(path.starts_with(temp_dir.path()));
+
+        // 2回目は何も返さない
+        assert!(writer.finalize()?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotation_splits_without_losing_samples() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut writer = WavWriter::new(0, temp_dir.path(), 100)?;
+        writer.set_rotation_interval_secs(Some(1));
+        let started_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_961_696);
+        writer.start_at(started_at)?;
+
+        // 1ファイル100サンプルの境界をまたぐ長さで書き込む
+        let samples: Vec<i16> = (0..350).collect();
+        for chunk in samples.chunks(70) {
+            writer.write_samples(chunk)?;
+        }
+        let rotated = writer.take_rotated();
+        assert!(writer.take_rotated().is_empty());
+        let last = writer.finalize()?.unwrap();
+
+        assert_eq!(rotated.len(), 3);
+        for (i, file) in rotated.iter().enumerate() {
+            assert_eq!(file.duration_secs, 1.0);
+            assert_eq!(file.started_at, started_at + Duration::from_secs(i as u64));
+        }
+        // 境界で欠けたり重複したりしない
+        let mut restored = Vec::new();
+        for path in rotated.iter().map(|file| &file.path).chain([&last]) {
+            let mut reader = hound::WavReader::open(path)?;
+            restored.extend(reader.samples::<i16>().collect::<std::result::Result<Vec<_>, _>>()?);
+        }
+        assert_eq!(restored, samples);
+        assert_eq!(fs::read_dir(temp_dir.path())?.count(), 4);
+
+        Ok(())
+    }
+    #[test]
+    fn test_encrypted_recording_decrypts_to_same_wav() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let key = RecordingKey::from_hex(&"42".repeat(32))?;
+        // 先頭チャンク（64KiB）を超える長さ
+        let samples: Vec<i16> = (0..40000).map(|i| (i % 2000) as i16 - 1000).collect();
+
+        let mut plain = WavWriter::new(0, temp_dir.path().join("plain"), 16000)?;
+        plain.write_samples(&samples)?;
+        let plain_path = plain.finalize()?.unwrap();
+
+        let mut encrypted = WavWriter::new(0, temp_dir.path().join("encrypted"), 16000)?;
+        encrypted.set_encryption_key(key.clone());
+        encrypted.write_samples(&samples[..100])?;
+        encrypted.write_samples(&samples[100..])?;
+        let encrypted_path = encrypted.finalize()?.unwrap();
+        assert!(encrypted_path.to_string_lossy().ends_with(".wav.enc"));
+        assert!(hound::WavReader::open(&encrypted_path).is_err());
+
+        let (decrypted_path, summary) = crate::encryption::decrypt_file(&encrypted_path, &key)?;
+        assert!(summary.complete);
+        assert_eq!(fs::read(decrypted_path)?, fs::read(plain_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restart_does_not_overwrite() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut writer = WavWriter::new(0, temp_dir.path(), 16000)?;
+
+        // 同じ秒のうちに finalize → start しても別ファイルになる
+        writer.write_samples(&[1i16; 160])?;
+        let first = writer.finalize()?.unwrap();
+        writer.write_samples(&[2i16; 320])?;
+        let second = writer.finalize()?.unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(hound::WavReader::open(&first)?.len(), 160);
+        assert_eq!(hound::WavReader::open(&second)?.len(), 320);
+
+        Ok(())
+    }
+
+    fn start_time() -> DateTime<Local> {
+        use chrono::TimeZone;
+        Local.with_ymd_and_hms(2025, 1, 2, 9, 30, 5).unwrap()
+    }
+
+    #[test]
+    fn test_default_template_matches_legacy_name() {
+        let template = FilenameTemplate::default();
+        assert_eq!(template.expand(2, "本部", &start_time()), "channel_2_20250102_093005");
+        // 拡張子の有無は同じ扱い
+        assert_eq!(
+            FilenameTemplate::parse("channel_{channel}_{timestamp}", &HashMap::new()).unwrap(),
+            template
+        );
+    }
+
+    #[test]
+    fn test_template_expansion() {
+        let session = HashMap::from([("site_id".to_string(), "tokyo-01".to_string())]);
+        let template =
+            FilenameTemplate::parse("{date}/{site_id}/ch{channel}_{name}_{time}.wav", &session).unwrap();
+        assert_eq!(
+            template.expand(0, "無線 1", &start_time()),
+            "20250102/tokyo-01/ch0_無線 1_093005"
+        );
+
+        // 値に含まれるパス区切りや使えない文字は置き換える
+        assert_eq!(template.expand(1, "../a:b", &start_time()), "20250102/tokyo-01/ch1_.._a_b_093005");
+        let template = FilenameTemplate::parse("{name}/{channel}", &HashMap::new()).unwrap();
+        assert_eq!(template.expand(1, "..", &start_time()), "_/1");
+        assert_eq!(template.expand(1, "", &start_time()), "_/1");
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let session = HashMap::from([("site_id".to_string(), "tokyo-01".to_string())]);
+        for template in [
+            "",
+            ".wav",
+            "{site}/{channel}",
+            "{channel",
+            "channel}",
+            "/abs/{channel}",
+            "../{channel}",
+            "a//{channel}",
+            "{date}/",
+            "ch:{channel}",
+        ] {
+            assert!(
+                FilenameTemplate::parse(template, &session).is_err(),
+                "{:?} はエラーになるべき",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn test_template_creates_subdirectories() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let session = HashMap::from([("site_id".to_string(), "tokyo-01".to_string())]);
+        let mut writer = WavWriter::new(4, temp_dir.path(), 16000)?;
+        writer.set_filename_template(
+            FilenameTemplate::parse("{site_id}/{date}/ch{channel}_{name}", &session)?,
+            "本部",
+        );
+
+        writer.write_samples(&[0i16; 160])?;
+        let first = writer.finalize()?.unwrap();
+        writer.write_samples(&[0i16; 160])?;
+        let second = writer.finalize()?.unwrap();
+
+        let dir = temp_dir.path().join("tokyo-01");
+        assert!(first.starts_with(&dir));
+        assert!(first.file_name().unwrap().to_string_lossy().starts_with("ch4_本部"));
+        assert_eq!(first.extension().unwrap(), "wav");
+        // 同じ名前になる場合は番号を付ける
+        assert_eq!(second.file_name().unwrap().to_string_lossy(), "ch4_本部_2.wav");
+
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
-use crate::transcribe_backend::TranscribeBackend;
+use crate::language::{match_allowed, primary_subtag};
+use crate::transcribe_backend::{MonoNormalizer, TranscribeAudio, TranscribeBackend};
 use crate::types::TranscriptResult;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -18,12 +19,78 @@ pub struct WhisperConfig {
     pub language: Option<String>, // "ja", "en", など
     pub sample_rate: u32,
     pub chunk_duration_secs: u64, // 音声チャンクをためる時間（秒）
+    /// 発話区間（1ストリーム）ごとに送るか（`chunk_duration_secs` ごとには区切らない）
+    pub vad_segmentation: bool,
+    /// `vad_segmentation` 時に1リクエストに含める最大の長さ（秒）
+    pub max_segment_secs: u64,
+    /// 自動言語切り替えの候補（`[transcribe] allowed_languages`、空なら `language` に固定）
+    pub allowed_languages: Vec<String>,
 }
 
-/// OpenAI Whisper API レスポンス
+/// OpenAI Whisper API レスポンス（`verbose_json`）
 #[derive(Debug, Deserialize)]
 struct WhisperResponse {
     text: String,
+    /// 検出した言語名（"japanese" など）
+    #[serde(default)]
+    language: Option<String>,
+    /// 発話のセグメント（返さないモデルでは空）
+    #[serde(default)]
+    segments: Vec<WhisperSegment>,
+}
+
+/// `verbose_json` のセグメント（時刻は送信したチャンクの先頭からの秒数）
+#[derive(Debug, Deserialize)]
+struct WhisperSegment {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+/// 1回の API 呼び出し結果をセグメントごとの確定結果に分割する
+///
+/// 各結果の時刻はチャンクの開始時刻 `chunk_offset_secs` にセグメントの `start` を加えたもの。
+/// セグメントの時刻はチャンクの長さ `chunk_secs` の範囲に収め、前のセグメントより戻らないようにする
+/// （次のチャンクの結果と順序が入れ替わらない）。発話時間は範囲に収めた `start`〜`end` の長さ。
+/// セグメントがなければ全文を1件にする（発話時間は不明）。
+fn segment_results(
+    channel_id: usize,
+    response: &WhisperResponse,
+    chunk_offset_secs: f64,
+    chunk_secs: f64,
+    detected_language: Option<String>,
+) -> Vec<TranscriptResult> {
+    let text = response.text.trim();
+    if response.segments.is_empty() {
+        if text.is_empty() {
+            return Vec::new();
+        }
+        return vec![
+            TranscriptResult::new_with_audio_time(channel_id, text.to_string(), false, None, chunk_offset_secs, None)
+                .with_detected_language(detected_language),
+        ];
+    }
+
+    let mut last_start = 0.0f64;
+    response
+        .segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| {
+            let start = segment.start.min(segment.end).clamp(last_start, chunk_secs.max(last_start));
+            let end = segment.end.clamp(start, chunk_secs.max(start));
+            last_start = start;
+            TranscriptResult::new_with_audio_time(
+                channel_id,
+                segment.text.trim().to_string(),
+                false, // Whisper APIは常に最終結果
+                None,  // Whisperはstabilityなし
+                chunk_offset_secs + start,
+                Some(chunk_offset_secs + end),
+            )
+            .with_detected_language(detected_language.clone())
+        })
+        .collect()
 }
 
 /// OpenAI Whisper API バックエンド
@@ -38,8 +105,29 @@ pub struct WhisperBackend {
     task_handle: Option<tokio::task::JoinHandle<()>>,
 }
 
+impl WhisperConfig {
+    /// 1リクエストにまとめる最大サンプル数
+    ///
+    /// 発話区間ごとの送信では、ストリームが閉じられる（発話が終わる）までためるが、
+    /// `max_segment_secs` を超えた分は強制的に分割する。
+    fn samples_per_request(&self) -> usize {
+        let secs = if self.vad_segmentation {
+            self.max_segment_secs
+        } else {
+            self.chunk_duration_secs
+        };
+        (self.sample_rate as u64 * secs) as usize
+    }
+}
+
 impl WhisperBackend {
-    pub async fn new(config: WhisperConfig, channel_id: usize, start_time: SystemTime) -> Result<Self> {
+    /// 現在時刻をタイムスタンプの基準時刻として作成
+    pub async fn new(config: WhisperConfig, channel_id: usize) -> Result<Self> {
+        Self::new_with_start_time(config, channel_id, SystemTime::now()).await
+    }
+
+    /// タイムスタンプの基準時刻を指定して作成（複数チャンネルで時刻をそろえる場合）
+    pub async fn new_with_start_time(config: WhisperConfig, channel_id: usize, start_time: SystemTime) -> Result<Self> {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
@@ -55,6 +143,14 @@ impl WhisperBackend {
         })
     }
 
+    /// 基準時刻からストリーム開始時点までの経過秒数
+    fn stream_offset_secs(&self) -> f64 {
+        SystemTime::now()
+            .duration_since(self.start_time)
+            .unwrap_or_default()
+            .as_secs_f64()
+    }
+
     /// PCMデータをWAVフォーマットに変換
     fn pcm_to_wav(&self, pcm_data: &[i16]) -> Result<Vec<u8>> {
         let spec = hound::WavSpec {
@@ -79,18 +175,66 @@ impl WhisperBackend {
         Ok(cursor.into_inner())
     }
 
+    /// チャンクを文字起こしし、セグメントごとの確定結果を返す
+    ///
+    /// `chunk_offset_secs` はチャンク先頭の開始時刻からの経過秒数。
+    async fn transcribe_chunk(&self, pcm_data: &[i16], chunk_offset_secs: f64) -> Result<Vec<TranscriptResult>> {
+        let wav_data = self.pcm_to_wav(pcm_data)?;
+        log::debug!("Whisper API: WAVデータサイズ {} バイト", wav_data.len());
+
+        let (response, detected_language) = self.transcribe_audio(wav_data).await?;
+        let chunk_secs = pcm_data.len() as f64 / self.config.sample_rate as f64;
+        Ok(segment_results(
+            self.channel_id,
+            &response,
+            chunk_offset_secs,
+            chunk_secs,
+            detected_language,
+        ))
+    }
+
     /// Whisper APIを呼び出して文字起こし
-    async fn transcribe_audio(&self, wav_data: Vec<u8>) -> Result<String> {
+    ///
+    /// 戻り値はレスポンスと、自動言語切り替え時に検出した言語（`allowed_languages` の言語コード）。
+    /// 自動言語切り替え時は言語を指定せずに検出させ、候補外の言語と判定された場合は
+    /// 直前の発話の言語（`language`）を指定して文字起こしし直す。
+    async fn transcribe_audio(&self, wav_data: Vec<u8>) -> Result<(WhisperResponse, Option<String>)> {
+        let allowed = &self.config.allowed_languages;
+        if allowed.is_empty() {
+            let response = self.request(wav_data, self.config.language.as_deref()).await?;
+            return Ok((response, None));
+        }
+
+        let response = self.request(wav_data.clone(), None).await?;
+        let detected = response.language.as_deref().and_then(|language| match_allowed(language, allowed));
+        if detected.is_some() {
+            return Ok((response, detected));
+        }
+
+        log::debug!(
+            "チャンネル {}: Whisper API の検出言語 {:?} が候補外のため {:?} で再試行",
+            self.channel_id,
+            response.language,
+            self.config.language
+        );
+        let response = self.request(wav_data, self.config.language.as_deref()).await?;
+        let language = self.config.language.as_deref().and_then(|language| match_allowed(language, allowed));
+        Ok((response, language))
+    }
+
+    /// Whisper API に1回リクエストする（セグメントと検出言語付きの `verbose_json` で受け取る）
+    async fn request(&self, wav_data: Vec<u8>, language: Option<&str>) -> Result<WhisperResponse> {
         let part = multipart::Part::bytes(wav_data)
             .file_name("audio.wav")
             .mime_str("audio/wav")?;
 
         let mut form = multipart::Form::new()
             .part("file", part)
-            .text("model", self.config.model.clone());
+            .text("model", self.config.model.clone())
+            .text("response_format", "verbose_json");
 
-        if let Some(ref language) = self.config.language {
-            form = form.text("language", language.clone());
+        if let Some(language) = language {
+            form = form.text("language", language.to_string());
         }
 
         let response = self
@@ -108,12 +252,10 @@ impl WhisperBackend {
             anyhow::bail!("Whisper API エラー: {} - {}", status, error_text);
         }
 
-        let whisper_response: WhisperResponse = response
+        response
             .json::<WhisperResponse>()
             .await
-            .context("Whisper API レスポンスパース失敗")?;
-
-        Ok(whisper_response.text)
+            .context("Whisper API レスポンスパース失敗")
     }
 }
 
@@ -121,13 +263,13 @@ impl WhisperBackend {
 impl TranscribeBackend for WhisperBackend {
     async fn start_stream(
         &mut self,
-    ) -> Result<(mpsc::Sender<Vec<i16>>, mpsc::Receiver<TranscriptResult>)> {
-        let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(4096);
+    ) -> Result<(mpsc::Sender<TranscribeAudio>, mpsc::Receiver<TranscriptResult>)> {
+        let (audio_tx, audio_rx) = mpsc::channel::<TranscribeAudio>(4096);
         let audio_rx = Arc::new(Mutex::new(audio_rx));
         let (result_tx, result_rx) = mpsc::channel::<TranscriptResult>(32);
 
         let sample_rate = self.config.sample_rate;
-        let chunk_duration_secs = self.config.chunk_duration_secs;
+        let samples_per_chunk = self.config.samples_per_request();
         let channel_id = self.channel_id;
         let start_time = self.start_time;
         let config = self.config.clone();
@@ -140,66 +282,55 @@ impl TranscribeBackend for WhisperBackend {
             drop(old_handle);
         }
 
+        // チャンクの時刻はストリーム開始時点の経過時間に、送信済みのサンプル数を加えて求める
+        let stream_offset_secs = self.stream_offset_secs();
+
         let handle = tokio::spawn(async move {
             use tokio::time::{Duration, timeout};
 
             let mut pcm_buffer: Vec<i16> = Vec::new();
-            let samples_per_chunk = (sample_rate as u64 * chunk_duration_secs) as usize;
+            let mut normalizer = MonoNormalizer::new(channel_id);
+            let mut transcribed_samples: u64 = 0;
+            let backend = WhisperBackend {
+                config,
+                channel_id,
+                start_time,
+                client,
+                reconnection_count: 0,
+                task_handle: None,
+            };
 
             loop {
                 let mut rx = audio_rx.lock().await;
 
                 // データを待機（最大2秒）
                 match timeout(Duration::from_secs(2), rx.recv()).await {
-                    Ok(Some(samples)) => {
+                    Ok(Some(audio)) => {
                         drop(rx); // ロックを解放
 
-                        pcm_buffer.extend_from_slice(&samples);
+                        pcm_buffer.extend_from_slice(&normalizer.normalize(audio));
 
-                        // バッファが一定サイズに達したら文字起こし
+                        // バッファが一定サイズ（発話区間ごとの送信では最大長）に達したら文字起こし
                         if pcm_buffer.len() >= samples_per_chunk {
-                            let to_transcribe: Vec<i16> = pcm_buffer.drain(..).collect();
+                            let to_transcribe: Vec<i16> = std::mem::take(&mut pcm_buffer);
+                            let chunk_offset_secs =
+                                stream_offset_secs + transcribed_samples as f64 / sample_rate as f64;
+                            transcribed_samples += to_transcribe.len() as u64;
 
                             log::debug!("Whisper API: {} サンプルを文字起こし中", to_transcribe.len());
 
-                            // WAVに変換
-                            let backend = WhisperBackend {
-                                config: config.clone(),
-                                channel_id,
-                                start_time,
-                                client: client.clone(),
-                                reconnection_count: 0,
-                                task_handle: None,
-                            };
-
-                            match backend.pcm_to_wav(&to_transcribe) {
-                                Ok(wav_data) => {
-                                    log::debug!("Whisper API: WAVデータサイズ {} バイト", wav_data.len());
-
-                                    // Whisper APIを呼び出し
-                                    match backend.transcribe_audio(wav_data).await {
-                                        Ok(text) => {
-                                            if !text.is_empty() {
-                                                log::debug!("Whisper API: 文字起こし結果 - {}", text);
-                                                let transcript = TranscriptResult::new(
-                                                    channel_id,
-                                                    text,
-                                                    false, // Whisper APIは常に最終結果
-                                                    None,  // Whisperはstabilityなし
-                                                    start_time,
-                                                );
-                                                if let Err(e) = result_tx.try_send(transcript) {
-                                                    log::warn!("Whisper API 結果送信失敗: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            log::error!("Whisper API 文字起こし失敗: {}", e);
+                            // セグメントごとの結果を順に送る
+                            match backend.transcribe_chunk(&to_transcribe, chunk_offset_secs).await {
+                                Ok(transcripts) => {
+                                    for transcript in transcripts {
+                                        log::debug!("Whisper API: 文字起こし結果 - {}", transcript.text);
+                                        if let Err(e) = result_tx.try_send(transcript) {
+                                            log::warn!("Whisper API 結果送信失敗: {}", e);
                                         }
                                     }
                                 }
                                 Err(e) => {
-                                    log::error!("WAV変換失敗: {}", e);
+                                    log::error!("Whisper API 文字起こし失敗: {}", e);
                                 }
                             }
                         }
@@ -210,38 +341,17 @@ impl TranscribeBackend for WhisperBackend {
                         // 残りのバッファを処理
                         if !pcm_buffer.is_empty() {
                             log::debug!("Whisper API: 残りの {} サンプルを文字起こし中", pcm_buffer.len());
+                            let chunk_offset_secs =
+                                stream_offset_secs + transcribed_samples as f64 / sample_rate as f64;
 
-                            let backend = WhisperBackend {
-                                config: config.clone(),
-                                channel_id,
-                                start_time,
-                                client: client.clone(),
-                                reconnection_count: 0,
-                                task_handle: None,
-                            };
-
-                            match backend.pcm_to_wav(&pcm_buffer) {
-                                Ok(wav_data) => {
-                                    match backend.transcribe_audio(wav_data).await {
-                                        Ok(text) => {
-                                            if !text.is_empty() {
-                                                let transcript = TranscriptResult::new(
-                                                    channel_id,
-                                                    text,
-                                                    false,
-                                                    None,
-                                                    start_time,
-                                                );
-                                                let _ = result_tx.try_send(transcript);
-                                            }
-                                        }
-                                        Err(e) => {
-                                            log::error!("Whisper API 最終文字起こし失敗: {}", e);
-                                        }
+                            match backend.transcribe_chunk(&pcm_buffer, chunk_offset_secs).await {
+                                Ok(transcripts) => {
+                                    for transcript in transcripts {
+                                        let _ = result_tx.try_send(transcript);
                                     }
                                 }
                                 Err(e) => {
-                                    log::error!("WAV変換失敗: {}", e);
+                                    log::error!("Whisper API 最終文字起こし失敗: {}", e);
                                 }
                             }
                         }
@@ -261,7 +371,153 @@ impl TranscribeBackend for WhisperBackend {
         Ok((audio_tx, result_rx))
     }
 
+    fn set_language(&mut self, language: &str) {
+        self.config.language = Some(primary_subtag(language).to_string());
+    }
+
+    fn segments_by_vad(&self) -> bool {
+        self.config.vad_segmentation
+    }
+
+    fn reset_start_time(&mut self) {
+        self.start_time = SystemTime::now();
+        self.reconnection_count += 1;
+        log::info!(
+            "チャンネル {}: タイムスタンプの基準時刻をリセット (再接続 {}回目)",
+            self.channel_id,
+            self.reconnection_count
+        );
+    }
+
     fn channel_id(&self) -> usize {
         self.channel_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(json: &str) -> WhisperResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_segment_results_split_with_audio_time() {
+        let response = response(
+            r#"{
+                "task": "transcribe",
+                "language": "japanese",
+                "duration": 5.0,
+                "text": "こちら本部。 どうぞ。",
+                "segments": [
+                    {"id": 0, "start": 0.4, "end": 2.1, "text": " こちら本部。"},
+                    {"id": 1, "start": 2.1, "end": 4.8, "text": " どうぞ。"},
+                    {"id": 2, "start": 4.8, "end": 5.0, "text": " "}
+                ]
+            }"#,
+        );
+
+        let results = segment_results(1, &response, 10.0, 5.0, Some("ja-JP".to_string()));
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].text, "こちら本部。");
+        assert!((results[0].timestamp_seconds - 10.4).abs() < 1e-9);
+        assert_eq!(results[1].text, "どうぞ。");
+        assert!((results[1].timestamp_seconds - 12.1).abs() < 1e-9);
+        assert!((results[0].audio_duration_seconds.unwrap() - 1.7).abs() < 1e-9);
+        assert!((results[1].audio_duration_seconds.unwrap() - 2.7).abs() < 1e-9);
+        assert!(results.iter().all(|r| r.channel == 1 && !r.is_partial));
+        assert!(results.iter().all(|r| r.detected_language.as_deref() == Some("ja-JP")));
+    }
+
+    #[test]
+    fn test_segment_results_continuous_across_chunks() {
+        // 5秒チャンクを連続して処理した場合、結果の時刻は単調に増加し、チャンクの範囲に収まる
+        let chunks = [
+            r#"{"text": "a b", "segments": [
+                {"start": 0.0, "end": 2.5, "text": "a"},
+                {"start": 2.5, "end": 5.0, "text": "b"}]}"#,
+            // 範囲外・逆順のセグメント（ハルシネーションなど）
+            r#"{"text": "c d e", "segments": [
+                {"start": 1.0, "end": 3.0, "text": "c"},
+                {"start": 0.5, "end": 1.5, "text": "d"},
+                {"start": 7.0, "end": 9.0, "text": "e"}]}"#,
+            r#"{"text": "f", "segments": [{"start": 0.2, "end": 1.0, "text": "f"}]}"#,
+        ];
+
+        let stream_offset_secs = 3.0;
+        let mut timestamps = Vec::new();
+        let mut durations = Vec::new();
+        for (i, json) in chunks.iter().enumerate() {
+            let chunk_offset_secs = stream_offset_secs + i as f64 * 5.0;
+            for result in segment_results(0, &response(json), chunk_offset_secs, 5.0, None) {
+                assert!(result.timestamp_seconds >= chunk_offset_secs);
+                assert!(result.timestamp_seconds <= chunk_offset_secs + 5.0);
+                timestamps.push(result.timestamp_seconds);
+                durations.push(result.audio_duration_seconds.unwrap());
+            }
+        }
+
+        assert_eq!(timestamps, vec![3.0, 5.5, 9.0, 9.0, 13.0, 13.2]);
+        // 発話時間もチャンクの範囲に収める（範囲外の "e" は長さ0）
+        let expected = [2.5, 2.5, 2.0, 0.5, 0.0, 0.8];
+        assert_eq!(durations.len(), expected.len());
+        assert!(durations.iter().zip(expected).all(|(d, e)| (d - e).abs() < 1e-9), "{:?}", durations);
+        assert!(timestamps.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_samples_per_request() {
+        let mut config = WhisperConfig {
+            api_key: String::new(),
+            model: "whisper-1".to_string(),
+            language: None,
+            sample_rate: 16000,
+            chunk_duration_secs: 5,
+            vad_segmentation: false,
+            max_segment_secs: 30,
+            allowed_languages: Vec::new(),
+        };
+        assert_eq!(config.samples_per_request(), 16000 * 5);
+
+        // 発話区間ごとの送信では最大長まで1リクエストにまとめる
+        config.vad_segmentation = true;
+        assert_eq!(config.samples_per_request(), 16000 * 30);
+    }
+
+    #[tokio::test]
+    async fn test_reset_start_time() {
+        let config = WhisperConfig {
+            api_key: String::new(),
+            model: "whisper-1".to_string(),
+            language: None,
+            sample_rate: 16000,
+            chunk_duration_secs: 5,
+            vad_segmentation: false,
+            max_segment_secs: 30,
+            allowed_languages: Vec::new(),
+        };
+        let started = SystemTime::now() - std::time::Duration::from_secs(60);
+        let mut backend = WhisperBackend::new_with_start_time(config, 0, started).await.unwrap();
+        assert!(backend.stream_offset_secs() >= 60.0);
+
+        // 再接続後のストリームは0付近から数え直す
+        backend.reset_start_time();
+        assert!(backend.stream_offset_secs() < 1.0);
+        assert_eq!(backend.reconnection_count, 1);
+        let results = segment_results(0, &response(r#"{"text": "了解"}"#), backend.stream_offset_secs(), 5.0, None);
+        assert!(results[0].timestamp_seconds < 1.0);
+    }
+
+    #[test]
+    fn test_segment_results_without_segments() {
+        // セグメントを返さない場合は全文を1件にする
+        let results = segment_results(0, &response(r#"{"text": " 了解 "}"#), 7.5, 5.0, None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "了解");
+        assert_eq!(results[0].timestamp_seconds, 7.5);
+        assert_eq!(results[0].audio_duration_seconds, None);
+
+        assert!(segment_results(0, &response(r#"{"text": ""}"#), 7.5, 5.0, None).is_empty());
+    }
+}